@@ -107,6 +107,7 @@ pub fn run() {
         camera.set_viewport(frame_input.viewport);
 
         control.handle_events(&mut camera, &mut frame_input.events);
+        control.update(&mut camera, frame_input.elapsed_time);
         let elapsed_time = (frame_input.elapsed_time * 0.001) as f32;
 
         // Update the time in the particlesystem; this automatically integrates the velocity and
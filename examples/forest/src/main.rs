@@ -107,6 +107,7 @@ pub async fn run() {
         redraw |= camera.set_viewport(frame_input.viewport);
 
         redraw |= control.handle_events(&mut camera, &mut frame_input.events);
+        redraw |= control.update(&mut camera, frame_input.elapsed_time);
 
         if redraw {
             frame_input
@@ -115,6 +115,7 @@ pub async fn run() {
         camera.set_viewport(viewport);
 
         control.handle_events(&mut camera, &mut frame_input.events);
+        control.update(&mut camera, frame_input.elapsed_time);
 
         frame_input
             .screen()
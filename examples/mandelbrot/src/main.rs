@@ -80,6 +80,8 @@ pub fn main() {
         redraw |= camera.set_viewport(frame_input.viewport);
 
         redraw |= control.handle_events(&mut camera, &mut frame_input.events);
+        let panning = control.update(&mut camera, frame_input.elapsed_time);
+        redraw |= panning;
 
         if redraw {
             frame_input
@@ -90,7 +92,7 @@ pub fn main() {
 
         FrameOutput {
             swap_buffers: redraw,
-            wait_next_event: true,
+            wait_next_event: !panning,
             ..Default::default()
         }
     });
@@ -138,11 +138,14 @@ pub async fn run() {
                         None
                     },
                     render_states: model.material.render_states,
+                    lod_bias: model.material.lod_bias,
                     is_transparent: model.material.is_transparent,
                     lighting_model: LightingModel::Cook(
                         NormalDistributionFunction::TrowbridgeReitzGGX,
                         GeometryFunction::SmithSchlickGGX,
                     ),
+                    detail: model.material.detail.clone(),
+                    stochastic_sampling: model.material.stochastic_sampling,
                 };
                 model.render_with_material(&material, &camera, &[&light]);
                 gui.render();
@@ -33,13 +33,12 @@ pub async fn run() {
         .unwrap();
     let cpu_point_cloud: PointCloud = loaded.deserialize("hand.pcd").unwrap();
 
-    let mut point_mesh = CpuMesh::sphere(4);
-    point_mesh.transform(&Mat4::from_scale(0.001)).unwrap();
-
     let mut point_cloud = Gm {
-        geometry: InstancedMesh::new(&context, &cpu_point_cloud.into(), &point_mesh),
+        geometry: Points::new(&context, &cpu_point_cloud),
         material: ColorMaterial::default(),
     };
+    point_cloud.point_size = 0.002;
+    point_cloud.size_attenuation = true;
     let c = -point_cloud.aabb().center();
     point_cloud.set_transformation(Mat4::from_translation(c));
 
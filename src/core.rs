@@ -32,6 +32,30 @@ mod scissor_box;
 #[doc(inline)]
 pub use scissor_box::*;
 
+mod capabilities;
+#[doc(inline)]
+pub use capabilities::*;
+
+mod occlusion_query;
+#[doc(inline)]
+pub use occlusion_query::*;
+
+mod depth_pyramid;
+#[doc(inline)]
+pub use depth_pyramid::*;
+
+mod render_layers;
+#[doc(inline)]
+pub use render_layers::*;
+
+mod image_statistics;
+#[doc(inline)]
+pub use image_statistics::*;
+
+mod render_projection;
+#[doc(inline)]
+pub use render_projection::*;
+
 pub mod prelude {
 
     //!
@@ -55,6 +79,16 @@ pub enum CoreError {
     ContextCreation(String),
     #[error("failed rendering with error: {0}")]
     ContextError(String),
+    #[error("the context {0} is not current, make it current using Context::set_current or WindowedContext::make_current")]
+    ContextNotCurrent(String),
+    #[error("{0} was created on the context {1} which does not share resources with the current context {2}, use Context::from_gl_context_shared to share resources between contexts")]
+    ContextMismatch(String, String, String),
+    #[error("failed parsing {0} compressed texture: {1}")]
+    CompressedTextureParsing(String, String),
+    #[error("the compressed texture format {0} is not supported")]
+    UnsupportedCompressedFormat(String),
+    #[error("the texture size {0}x{1} is larger than the maximum texture size {2}")]
+    TextureTooLarge(u32, u32, u32),
     #[error("failed compiling {0} shader: {1}\n{2}")]
     ShaderCompilation(String, String, String),
     #[error("failed to link shader program: {0}")]
@@ -166,15 +200,35 @@ fn from_byte_slice<T: DataType>(data: &[u8]) -> &[T] {
 }
 
 fn format_from_data_type<T: DataType>() -> u32 {
-    match T::size() {
-        1 => crate::context::RED,
-        2 => crate::context::RG,
-        3 => crate::context::RGB,
-        4 => crate::context::RGBA,
-        _ => unreachable!(),
+    if is_integer_data_type::<T>() {
+        match T::size() {
+            1 => crate::context::RED_INTEGER,
+            2 => crate::context::RG_INTEGER,
+            3 => crate::context::RGB_INTEGER,
+            4 => crate::context::RGBA_INTEGER,
+            _ => unreachable!(),
+        }
+    } else {
+        match T::size() {
+            1 => crate::context::RED,
+            2 => crate::context::RG,
+            3 => crate::context::RGB,
+            4 => crate::context::RGBA,
+            _ => unreachable!(),
+        }
     }
 }
 
+fn is_integer_data_type<T: DataType>() -> bool {
+    matches!(
+        T::data_type(),
+        crate::context::UNSIGNED_SHORT
+            | crate::context::UNSIGNED_INT
+            | crate::context::SHORT
+            | crate::context::INT
+    )
+}
+
 fn flip_y<T: TextureDataType>(pixels: &mut [T], width: usize, height: usize) {
     for row in 0..height / 2 {
         for col in 0..width {
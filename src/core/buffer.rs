@@ -17,6 +17,10 @@ mod uniform_buffer;
 #[doc(inline)]
 pub use uniform_buffer::*;
 
+mod draw_indirect_buffer;
+#[doc(inline)]
+pub use draw_indirect_buffer::*;
+
 use crate::core::*;
 use data_type::*;
 
@@ -82,11 +86,41 @@ impl Buffer {
         self.normalized = T::normalized();
     }
 
+    pub fn fill_subset<T: BufferDataType>(&mut self, offset: u32, data: &[T]) {
+        if T::data_type() != self.data_type
+            || T::size() != self.data_size
+            || T::normalized() != self.normalized
+        {
+            panic!("the data type does not match the data type of the buffer");
+        }
+        if offset as usize + data.len() > self.attribute_count as usize {
+            panic!(
+                "cannot fill {} values starting at index {} in a buffer with {} values",
+                data.len(),
+                offset,
+                self.attribute_count
+            );
+        }
+        if data.is_empty() {
+            return;
+        }
+        self.bind();
+        unsafe {
+            self.context.buffer_sub_data_u8_slice(
+                crate::context::ARRAY_BUFFER,
+                (offset as usize * std::mem::size_of::<T>()) as i32,
+                to_byte_slice(data),
+            );
+        }
+        self.unbind();
+    }
+
     pub fn attribute_count(&self) -> u32 {
         self.attribute_count
     }
 
     pub fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the buffer");
         unsafe {
             self.context
                 .bind_buffer(crate::context::ARRAY_BUFFER, Some(self.inner));
@@ -0,0 +1,123 @@
+use crate::core::*;
+
+///
+/// The parameters of one draw call in a [DrawIndirectBuffer], which draws `count` indices starting at the index with number `first_index` in an [ElementBuffer].
+/// The layout matches the `DrawElementsIndirectCommand` structure in OpenGL.
+///
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DrawElementsIndirectCommand {
+    /// The number of indices to draw.
+    pub count: u32,
+    /// The number of instances to draw.
+    pub instance_count: u32,
+    /// The number of the first index in the element buffer.
+    pub first_index: u32,
+    /// The value added to each index before the vertex data is fetched.
+    pub base_vertex: i32,
+    /// The instance number of the first instance, which offsets the instance attributes.
+    /// Must be zero on OpenGL ES and on desktop OpenGL before version 4.2.
+    pub base_instance: u32,
+}
+
+///
+/// A buffer containing the parameters of a list of draw calls, see [Program::multi_draw_elements_indirect] to draw all of them in one call.
+///
+pub struct DrawIndirectBuffer {
+    context: Context,
+    inner: crate::context::Buffer,
+    commands: Vec<DrawElementsIndirectCommand>,
+}
+
+impl DrawIndirectBuffer {
+    ///
+    /// Creates a new empty draw indirect buffer.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            inner: unsafe { context.create_buffer().expect("Failed to create buffer") },
+            commands: Vec::new(),
+        }
+    }
+
+    ///
+    /// Creates a new draw indirect buffer and fills it with the given commands.
+    ///
+    pub fn new_with_data(context: &Context, commands: &[DrawElementsIndirectCommand]) -> Self {
+        let mut buffer = Self::new(context);
+        if !commands.is_empty() {
+            buffer.fill(commands);
+        }
+        buffer
+    }
+
+    ///
+    /// Fills the buffer with the given commands.
+    ///
+    pub fn fill(&mut self, commands: &[DrawElementsIndirectCommand]) {
+        self.commands = commands.to_vec();
+        // Only upload when the commands can be read from the buffer, otherwise they are drawn one by one from the copy in memory
+        if self.context.capabilities().draw_indirect {
+            let data = commands
+                .iter()
+                .flat_map(|c| {
+                    [
+                        c.count,
+                        c.instance_count,
+                        c.first_index,
+                        c.base_vertex as u32,
+                        c.base_instance,
+                    ]
+                })
+                .collect::<Vec<_>>();
+            self.bind();
+            unsafe {
+                self.context.buffer_data_u8_slice(
+                    crate::context::DRAW_INDIRECT_BUFFER,
+                    to_byte_slice(&data),
+                    crate::context::DYNAMIC_DRAW,
+                );
+            }
+            self.unbind();
+        }
+    }
+
+    ///
+    /// The number of commands in the buffer.
+    ///
+    pub fn count(&self) -> usize {
+        self.commands.len()
+    }
+
+    ///
+    /// The commands in the buffer.
+    ///
+    pub fn commands(&self) -> &[DrawElementsIndirectCommand] {
+        &self.commands
+    }
+
+    pub(crate) fn bind(&self) {
+        self.context
+            .debug_assert_shared_with_current("the draw indirect buffer");
+        unsafe {
+            self.context
+                .bind_buffer(crate::context::DRAW_INDIRECT_BUFFER, Some(self.inner));
+        }
+    }
+
+    pub(crate) fn unbind(&self) {
+        unsafe {
+            self.context
+                .bind_buffer(crate::context::DRAW_INDIRECT_BUFFER, None);
+        }
+    }
+}
+
+impl Drop for DrawIndirectBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.context.delete_buffer(self.inner);
+        }
+    }
+}
@@ -33,6 +33,7 @@ pub struct ElementBuffer {
     inner: crate::context::Buffer,
     count: usize,
     data_type: u32,
+    data_size: u32,
 }
 
 impl ElementBuffer {
@@ -46,6 +47,7 @@ impl ElementBuffer {
             inner: buffer,
             count: 0,
             data_type: 0,
+            data_size: 0,
         }
     }
 
@@ -69,6 +71,7 @@ impl ElementBuffer {
         self.unbind();
         self.count = data.len();
         self.data_type = T::data_type();
+        self.data_size = std::mem::size_of::<T>() as u32;
     }
 
     ///
@@ -86,6 +89,8 @@ impl ElementBuffer {
     }
 
     pub(crate) fn bind(&self) {
+        self.context
+            .debug_assert_shared_with_current("the element buffer");
         unsafe {
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, Some(self.inner));
@@ -112,6 +117,10 @@ impl ElementBuffer {
     pub(crate) fn data_type(&self) -> u32 {
         self.data_type
     }
+
+    pub(crate) fn data_size(&self) -> u32 {
+        self.data_size
+    }
 }
 
 impl Drop for ElementBuffer {
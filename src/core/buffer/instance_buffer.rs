@@ -37,6 +37,17 @@ impl InstanceBuffer {
         self.buffer.fill(data)
     }
 
+    ///
+    /// Replaces the instance attributes starting at the given index with the given data without reallocating the buffer, which is faster than [InstanceBuffer::fill] when only a small part of the data changes.
+    /// The data must have the same type as the data the buffer was filled with.
+    ///
+    /// # Panic
+    /// Will panic if the data type does not match the data in the buffer or if the data does not fit in the buffer.
+    ///
+    pub fn fill_subset<T: BufferDataType>(&mut self, offset: u32, data: &[T]) {
+        self.buffer.fill_subset(offset, data);
+    }
+
     ///
     /// The number of values in the buffer.
     ///
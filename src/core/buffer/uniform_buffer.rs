@@ -38,6 +38,8 @@ impl UniformBuffer {
     }
 
     pub(crate) fn bind(&self, id: u32) {
+        self.context
+            .debug_assert_shared_with_current("the uniform buffer");
         unsafe {
             self.context
                 .bind_buffer_base(crate::context::UNIFORM_BUFFER, id, Some(self.inner))
@@ -37,6 +37,17 @@ impl VertexBuffer {
         self.buffer.fill(data);
     }
 
+    ///
+    /// Replaces the vertex attributes starting at the given index with the given data without reallocating the buffer, which is faster than [VertexBuffer::fill] when only a small part of the data changes.
+    /// The data must have the same type as the data the buffer was filled with.
+    ///
+    /// # Panic
+    /// Will panic if the data type does not match the data in the buffer or if the data does not fit in the buffer.
+    ///
+    pub fn fill_subset<T: BufferDataType>(&mut self, offset: u32, data: &[T]) {
+        self.buffer.fill_subset(offset, data);
+    }
+
     ///
     /// The number of values in the buffer.
     ///
@@ -71,3 +82,74 @@ impl VertexBuffer {
         self.buffer.normalized
     }
 }
+
+///
+/// The type of the components of an attribute stored in a [VertexBuffer] containing several interleaved attributes, see [VertexAttributeLayout].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttributeComponentType {
+    /// 32 bit floating point values.
+    F32,
+    /// 16 bit floating point values.
+    F16,
+    /// 8 bit unsigned integers used as integers in the shader, for example `uvec4`.
+    U8,
+    /// 8 bit unsigned integers converted to floating point values in the range `[0..1]` in the shader, for example a color stored as `[u8; 4]`.
+    U8Normalized,
+    /// 16 bit unsigned integers used as integers in the shader.
+    U16,
+    /// 16 bit unsigned integers converted to floating point values in the range `[0..1]` in the shader.
+    U16Normalized,
+    /// 32 bit unsigned integers used as integers in the shader.
+    U32,
+    /// 32 bit signed integers used as integers in the shader.
+    I32,
+}
+
+impl AttributeComponentType {
+    ///
+    /// Returns the size in bytes of one component.
+    ///
+    pub fn size(&self) -> u32 {
+        match self {
+            Self::U8 | Self::U8Normalized => 1,
+            Self::F16 | Self::U16 | Self::U16Normalized => 2,
+            Self::F32 | Self::U32 | Self::I32 => 4,
+        }
+    }
+
+    pub(in crate::core) fn data_type(&self) -> u32 {
+        match self {
+            Self::F32 => crate::context::FLOAT,
+            Self::F16 => crate::context::HALF_FLOAT,
+            Self::U8 | Self::U8Normalized => crate::context::UNSIGNED_BYTE,
+            Self::U16 | Self::U16Normalized => crate::context::UNSIGNED_SHORT,
+            Self::U32 => crate::context::UNSIGNED_INT,
+            Self::I32 => crate::context::INT,
+        }
+    }
+
+    pub(in crate::core) fn is_integer(&self) -> bool {
+        matches!(self, Self::U8 | Self::U16 | Self::U32 | Self::I32)
+    }
+
+    pub(in crate::core) fn is_normalized(&self) -> bool {
+        matches!(self, Self::U8Normalized | Self::U16Normalized)
+    }
+}
+
+///
+/// Describes where one attribute is stored in a [VertexBuffer] containing the data of several attributes interleaved, ie. all attributes of the first vertex followed by all attributes of the second vertex and so on.
+/// To send the attribute to a shader, use the [Program::use_interleaved_vertex_attribute] method.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VertexAttributeLayout {
+    /// The type of the components of the attribute.
+    pub component_type: AttributeComponentType,
+    /// The number of components of the attribute, between 1 and 4, for example 3 for a `vec3`.
+    pub components: u32,
+    /// The offset in bytes of the attribute from the start of the data of each vertex.
+    pub offset: u32,
+    /// The size in bytes of the data of each vertex, ie. the distance between the attribute of one vertex and the attribute of the next vertex.
+    pub stride: u32,
+}
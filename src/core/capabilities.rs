@@ -0,0 +1,214 @@
+use crate::context::HasContext;
+use crate::core::CompressedFormat;
+
+///
+/// The precision of the color channels of a texture which is rendered into, used to select a fallback when the preferred precision is not supported, see [Capabilities::best_render_precision].
+/// The precisions are ordered from the lowest to the highest precision.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorPrecision {
+    /// 8 bit unsigned normalized values in the range `[0..1]`, for example `[u8; 4]`.
+    U8,
+    /// 16 bit floating point values, for example `[f16; 4]`.
+    F16,
+    /// 32 bit floating point values, for example `[f32; 4]`.
+    F32,
+}
+
+///
+/// The capabilities and limits of the graphics device, see [Context::capabilities](crate::core::Context::capabilities).
+/// Use this to select features which are supported on the device, for example on low-end devices running WebGL.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether or not the context is an OpenGL ES or WebGL context, which has fewer features than desktop OpenGL.
+    pub is_embedded: bool,
+    /// The maximum width and height of a 2D texture.
+    pub max_texture_size: u32,
+    /// The maximum width and height of a side of a cube map texture.
+    pub max_cube_map_texture_size: u32,
+    /// The maximum width, height and depth of a 3D texture.
+    pub max_3d_texture_size: u32,
+    /// The maximum number of layers in a texture array.
+    pub max_array_texture_layers: u32,
+    /// The maximum number of color textures attached to a render target, ie. the maximum number of render targets when rendering to multiple render targets.
+    pub max_color_attachments: u32,
+    /// The maximum number of color outputs written by a fragment shader.
+    pub max_draw_buffers: u32,
+    /// The maximum number of samples in a multisample render target.
+    pub max_samples: u32,
+    /// Whether or not instanced rendering is supported.
+    pub instancing: bool,
+    /// Whether or not it is possible to render into textures with 32 bit floating point values.
+    pub float_render_targets: bool,
+    /// Whether or not it is possible to render into textures with 16 bit floating point values.
+    pub half_float_render_targets: bool,
+    /// Whether or not blending is supported when rendering into textures with 32 bit floating point values.
+    pub float_blending: bool,
+    /// Whether or not textures with 32 bit floating point values can be sampled with linear interpolation.
+    pub float_linear_filtering: bool,
+    /// Whether or not the S3TC compressed formats [CompressedFormat::Bc1], [CompressedFormat::Bc2] and [CompressedFormat::Bc3] are supported.
+    pub s3tc_compression: bool,
+    /// Whether or not the BPTC compressed format [CompressedFormat::Bc7] is supported.
+    pub bptc_compression: bool,
+    /// Whether or not the ETC2 compressed formats [CompressedFormat::Etc2Rgb] and [CompressedFormat::Etc2Rgba] are supported.
+    pub etc2_compression: bool,
+    /// Whether or not the ASTC compressed formats [CompressedFormat::Astc] are supported.
+    pub astc_compression: bool,
+    /// Whether or not blending with the second color output of the fragment shader, ie. the `Src1` multipliers in [BlendMultiplierType](crate::core::BlendMultiplierType), is supported.
+    pub dual_source_blending: bool,
+    /// Whether or not clamping the depth instead of clipping at the near and far plane, see [RenderStates::depth_clamp](crate::core::RenderStates::depth_clamp), is supported.
+    pub depth_clamp: bool,
+    /// Whether or not the parameters of a draw call can be read from a [DrawIndirectBuffer](crate::core::DrawIndirectBuffer), which is not supported on WebGL.
+    pub draw_indirect: bool,
+    /// Whether or not all of the draw calls in a [DrawIndirectBuffer](crate::core::DrawIndirectBuffer) can be drawn in one call, see [Program::multi_draw_elements_indirect](crate::core::Program::multi_draw_elements_indirect).
+    pub multi_draw_indirect: bool,
+}
+
+impl Capabilities {
+    pub(in crate::core) fn new(context: &crate::context::Context) -> Self {
+        let has_extension = |name: &str| {
+            let extensions = context.supported_extensions();
+            extensions.contains(name) || extensions.contains(&format!("GL_{}", name))
+        };
+        let parameter =
+            |parameter: u32| unsafe { context.get_parameter_i32(parameter).max(0) as u32 };
+        let version = context.version();
+        let is_embedded = version.is_embedded;
+        let float_render_targets = !is_embedded || has_extension("EXT_color_buffer_float");
+        let version_at_least = |major: u32, minor: u32| {
+            version.major > major || (version.major == major && version.minor >= minor)
+        };
+        // ETC2 is part of OpenGL ES 3.0 and OpenGL 4.3, but is only available as an extension on WebGL
+        let etc2_compression = if cfg!(target_arch = "wasm32") {
+            has_extension("WEBGL_compressed_texture_etc")
+        } else {
+            is_embedded || version_at_least(4, 3) || has_extension("ARB_ES3_compatibility")
+        };
+        // Indirect drawing is part of OpenGL 4.0 and OpenGL ES 3.1, multi draw indirect is part of OpenGL 4.3, and neither is available on WebGL
+        let draw_indirect = !cfg!(target_arch = "wasm32")
+            && ((is_embedded && version_at_least(3, 1))
+                || (!is_embedded && version_at_least(4, 0))
+                || has_extension("ARB_draw_indirect"));
+        let multi_draw_indirect = draw_indirect
+            && ((!is_embedded && version_at_least(4, 3))
+                || has_extension("ARB_multi_draw_indirect")
+                || has_extension("EXT_multi_draw_indirect"));
+        Self {
+            is_embedded,
+            max_texture_size: parameter(crate::context::MAX_TEXTURE_SIZE),
+            max_cube_map_texture_size: parameter(crate::context::MAX_CUBE_MAP_TEXTURE_SIZE),
+            max_3d_texture_size: parameter(crate::context::MAX_3D_TEXTURE_SIZE),
+            max_array_texture_layers: parameter(crate::context::MAX_ARRAY_TEXTURE_LAYERS),
+            max_color_attachments: parameter(crate::context::MAX_COLOR_ATTACHMENTS),
+            max_draw_buffers: parameter(crate::context::MAX_DRAW_BUFFERS),
+            max_samples: parameter(crate::context::MAX_SAMPLES),
+            instancing: version.major >= 3 || has_extension("ANGLE_instanced_arrays"),
+            float_render_targets,
+            half_float_render_targets: float_render_targets
+                || has_extension("EXT_color_buffer_half_float"),
+            float_blending: float_render_targets
+                && (!is_embedded || has_extension("EXT_float_blend")),
+            float_linear_filtering: !is_embedded || has_extension("OES_texture_float_linear"),
+            s3tc_compression: has_extension("EXT_texture_compression_s3tc")
+                || has_extension("WEBGL_compressed_texture_s3tc"),
+            bptc_compression: (!is_embedded && version_at_least(4, 2))
+                || has_extension("ARB_texture_compression_bptc")
+                || has_extension("EXT_texture_compression_bptc"),
+            etc2_compression,
+            astc_compression: has_extension("KHR_texture_compression_astc_ldr")
+                || has_extension("WEBGL_compressed_texture_astc"),
+            dual_source_blending: (!is_embedded && version_at_least(3, 3))
+                || has_extension("ARB_blend_func_extended")
+                || has_extension("EXT_blend_func_extended")
+                || has_extension("WEBGL_blend_func_extended"),
+            depth_clamp: (!is_embedded && version_at_least(3, 2))
+                || has_extension("ARB_depth_clamp")
+                || has_extension("EXT_depth_clamp"),
+            draw_indirect,
+            multi_draw_indirect,
+        }
+    }
+
+    ///
+    /// Returns whether or not it is possible to render into a texture with the given precision and, if `linear_filtering` is true, sample it with linear interpolation.
+    ///
+    pub fn supports_render_precision(
+        &self,
+        precision: ColorPrecision,
+        linear_filtering: bool,
+    ) -> bool {
+        match precision {
+            ColorPrecision::U8 => true,
+            ColorPrecision::F16 => self.half_float_render_targets,
+            ColorPrecision::F32 => {
+                self.float_render_targets && (!linear_filtering || self.float_linear_filtering)
+            }
+        }
+    }
+
+    ///
+    /// Returns the highest supported precision which is not higher than the preferred precision, see [Capabilities::supports_render_precision].
+    ///
+    pub fn best_render_precision(
+        &self,
+        preferred: ColorPrecision,
+        linear_filtering: bool,
+    ) -> ColorPrecision {
+        [ColorPrecision::F32, ColorPrecision::F16, ColorPrecision::U8]
+            .into_iter()
+            .filter(|p| *p <= preferred)
+            .find(|p| self.supports_render_precision(*p, linear_filtering))
+            .unwrap_or(ColorPrecision::U8)
+    }
+
+    ///
+    /// Returns whether or not textures with the given compressed format can be created on the graphics device, see [Texture2D::new_compressed](crate::core::Texture2D::new_compressed).
+    ///
+    pub fn supports_compressed_format(&self, format: CompressedFormat) -> bool {
+        match format {
+            CompressedFormat::Bc1 | CompressedFormat::Bc2 | CompressedFormat::Bc3 => {
+                self.s3tc_compression
+            }
+            CompressedFormat::Bc7 => self.bptc_compression,
+            CompressedFormat::Etc2Rgb | CompressedFormat::Etc2Rgba => self.etc2_compression,
+            CompressedFormat::Astc { .. } => self.astc_compression,
+        }
+    }
+
+    ///
+    /// Returns the highest supported number of samples in a multisample render target which is not higher than the given number of samples.
+    /// The returned number is a power of two or zero if multisampling is not supported.
+    ///
+    pub fn supported_samples(&self, number_of_samples: u32) -> u32 {
+        let samples = number_of_samples.min(self.max_samples);
+        if samples == 0 {
+            0
+        } else {
+            1 << (31 - samples.leading_zeros())
+        }
+    }
+}
+
+///
+/// A fallback which was chosen by a material or effect because a feature is not supported on the graphics device, see [Context::fallbacks](crate::core::Context::fallbacks).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fallback {
+    /// The feature which uses the fallback, for example `"ambient occlusion"`.
+    pub feature: String,
+    /// A description of what was requested.
+    pub requested: String,
+    /// A description of what was chosen instead.
+    pub chosen: String,
+}
+
+impl std::fmt::Display for Fallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} uses {} since {} is not supported",
+            self.feature, self.chosen, self.requested
+        )
+    }
+}
@@ -6,6 +6,20 @@ use std::sync::RwLock;
 #[doc(hidden)]
 pub use crate::context::HasContext;
 
+// The signature of glClipControl(origin, depth)
+type ClipControlFunction = unsafe extern "system" fn(u32, u32);
+
+thread_local! {
+    // The id and share group of the context which is current on this thread, see Context::set_current
+    static CURRENT_CONTEXT: std::cell::Cell<Option<(ContextId, usize)>> = std::cell::Cell::new(None);
+}
+
+///
+/// A unique identifier of a [Context], see [Context::id].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ContextId(usize);
+
 ///
 /// Contains the low-level OpenGL/WebGL graphics context as well as other "global" variables.
 /// Implements Deref with the low-level graphics context as target, so you can call low-level functionality
@@ -16,6 +30,19 @@ pub struct Context {
     context: Arc<crate::context::Context>,
     pub(super) vao: crate::context::VertexArray,
     programs: Arc<RwLock<HashMap<(String, String), Program>>>,
+    active_vao: Arc<RwLock<crate::context::VertexArray>>,
+    reverse_z: Arc<std::sync::atomic::AtomicBool>,
+    clip_control: Arc<RwLock<Option<ClipControlFunction>>>,
+    linear_output: Arc<std::sync::atomic::AtomicBool>,
+    texture_lod_bias: Arc<RwLock<f32>>,
+    stencil_override: Arc<RwLock<Option<Stencil>>>,
+    depth_test_override: Arc<RwLock<Option<DepthTest>>>,
+    logarithmic_depth: Arc<RwLock<Option<f32>>>,
+    capabilities: Arc<Capabilities>,
+    fallbacks: Arc<RwLock<Vec<Fallback>>>,
+    object_id_output: Arc<std::sync::atomic::AtomicBool>,
+    // The texture array, layer, precision and viewport of the object IDs written by the latest render call, see RenderTarget::read_id_at
+    pub(crate) object_ids: Arc<RwLock<Option<(Texture2DArray, u32, ColorPrecision, Viewport)>>>,
 }
 
 impl Context {
@@ -28,8 +55,9 @@ impl Context {
     pub fn from_gl_context(context: Arc<crate::context::Context>) -> Result<Self, CoreError> {
         unsafe {
             if !context.version().is_embedded {
-                // Enable seamless cube map textures - not available on OpenGL ES and WebGL
+                // Enable seamless cube map textures and setting the size of points in the vertex shader - not available on OpenGL ES and WebGL, where it is always enabled
                 context.enable(crate::context::TEXTURE_CUBE_MAP_SEAMLESS);
+                context.enable(crate::context::PROGRAM_POINT_SIZE);
             }
             context.pixel_store_i32(crate::context::UNPACK_ALIGNMENT, 1);
             context.pixel_store_i32(crate::context::PACK_ALIGNMENT, 1);
@@ -39,15 +67,139 @@ impl Context {
             let vao = context
                 .create_vertex_array()
                 .map_err(CoreError::ContextCreation)?;
+            let capabilities = Arc::new(Capabilities::new(&context));
             Self {
                 context,
                 vao,
                 programs: Arc::new(RwLock::new(HashMap::new())),
+                active_vao: Arc::new(RwLock::new(vao)),
+                reverse_z: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                clip_control: Arc::new(RwLock::new(None)),
+                linear_output: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                texture_lod_bias: Arc::new(RwLock::new(0.0)),
+                stencil_override: Arc::new(RwLock::new(None)),
+                depth_test_override: Arc::new(RwLock::new(None)),
+                logarithmic_depth: Arc::new(RwLock::new(None)),
+                capabilities,
+                fallbacks: Arc::new(RwLock::new(Vec::new())),
+                object_id_output: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                object_ids: Arc::new(RwLock::new(None)),
             }
         };
+        // The context has just been used, so it must be current
+        c.set_current();
         Ok(c)
     }
 
+    ///
+    /// Creates a new mid-level context from a low-level OpenGL context which shares objects with the low-level context of the given context,
+    /// for example a context created using glutin with the `with_sharing` option.
+    /// The programs compiled using [Context::program] are shared between the two contexts and textures and buffers created on one of them can be used on the other.
+    ///
+    /// Note that sharing is not possible on web, since WebGL contexts cannot share objects.
+    ///
+    pub fn from_gl_context_shared(
+        context: Arc<crate::context::Context>,
+        shared_with: &Context,
+    ) -> Result<Self, CoreError> {
+        let mut c = Self::from_gl_context(context)?;
+        c.programs = shared_with.programs.clone();
+        c.active_vao = shared_with.active_vao.clone();
+        c.set_current();
+        Ok(c)
+    }
+
+    ///
+    /// Returns a unique identifier of this context, which is the same for all clones of this context.
+    ///
+    pub fn id(&self) -> ContextId {
+        ContextId(Arc::as_ptr(&self.context) as usize)
+    }
+
+    ///
+    /// Returns whether or not this context is the same as the given context or shares resources with it, see [Context::from_gl_context_shared].
+    ///
+    pub fn shares_resources_with(&self, other: &Context) -> bool {
+        self.share_group() == other.share_group()
+    }
+
+    ///
+    /// Marks this context as the current context on this thread, which is needed when switching between multiple contexts, for example when rendering to multiple windows.
+    /// The low-level context must also be made current, which is done by [WindowedContext::make_current](crate::WindowedContext::make_current) when using the [window](crate::window) module.
+    /// The mark is removed when the [WindowedContext](crate::WindowedContext) or [HeadlessContext](crate::HeadlessContext) owning the context is dropped.
+    ///
+    pub fn set_current(&self) {
+        CURRENT_CONTEXT.with(|current| current.set(Some((self.id(), self.share_group()))));
+        *self.active_vao.write().unwrap() = self.vao;
+    }
+
+    ///
+    /// Returns whether or not this context is marked as the current context on this thread, see [Context::set_current].
+    ///
+    pub fn is_current(&self) -> bool {
+        CURRENT_CONTEXT
+            .with(|current| current.get())
+            .map(|(id, _)| id)
+            == Some(self.id())
+    }
+
+    ///
+    /// Returns an error if this context is not marked as the current context on this thread, see [Context::set_current].
+    ///
+    pub fn verify_current(&self) -> Result<(), CoreError> {
+        if self.is_current() {
+            Ok(())
+        } else {
+            Err(CoreError::ContextNotCurrent(format!("{:?}", self.id())))
+        }
+    }
+
+    ///
+    /// Panics with a descriptive message if this context is not marked as the current context on this thread, see [Context::verify_current].
+    ///
+    pub fn assert_current(&self) {
+        if let Err(e) = self.verify_current() {
+            panic!("{}", e);
+        }
+    }
+
+    ///
+    /// Removes the mark of this context as the current context on this thread, if it is marked, see [Context::set_current].
+    /// This is called when the window or headless context owning this context is dropped.
+    ///
+    pub(crate) fn clear_current(&self) {
+        if self.is_current() {
+            CURRENT_CONTEXT.with(|current| current.set(None));
+        }
+    }
+
+    ///
+    /// In debug builds, panics with a descriptive message if a resource created on this context is used while a context, which does not share resources with this context, is current.
+    /// The check relies on the contexts being marked current using [Context::set_current], which is not required when only the low-level contexts are made current,
+    /// so it is not performed in release builds.
+    ///
+    pub(in crate::core) fn debug_assert_shared_with_current(&self, resource: &str) {
+        if let Some((id, share_group)) = CURRENT_CONTEXT.with(|current| current.get()) {
+            debug_assert!(
+                share_group == self.share_group(),
+                "{}",
+                CoreError::ContextMismatch(
+                    resource.to_owned(),
+                    format!("{:?}", self.id()),
+                    format!("{:?}", id)
+                )
+            );
+        }
+    }
+
+    fn share_group(&self) -> usize {
+        Arc::as_ptr(&self.programs) as usize
+    }
+
+    pub(in crate::core) fn active_vao(&self) -> crate::context::VertexArray {
+        *self.active_vao.read().unwrap()
+    }
+
     ///
     /// Compiles a [Program] with the given vertex and fragment shader source and stores it for later use.
     /// If it has already been created, then it is just returned.
@@ -58,7 +210,33 @@ impl Context {
         fragment_shader_source: String,
         callback: impl FnOnce(&Program),
     ) -> Result<(), CoreError> {
-        let key = (vertex_shader_source, fragment_shader_source);
+        let mut defines = String::new();
+        if self.linear_output() {
+            defines.push_str("#define LINEAR_OUTPUT\n");
+        }
+        if self.reverse_z() {
+            defines.push_str("#define REVERSE_Z\n");
+            if self.clip_depth_zero_to_one() {
+                defines.push_str("#define CLIP_DEPTH_ZERO_TO_ONE\n");
+            }
+        }
+        if let Some(far) = self.logarithmic_depth() {
+            defines.push_str(&format!(
+                "#define LOGARITHMIC_DEPTH\nconst float logDepthFar = {:?};\n{}",
+                far,
+                include_str!("shaders/logarithmic_depth.glsl")
+            ));
+        }
+        let key = if defines.is_empty() {
+            (vertex_shader_source, fragment_shader_source)
+        } else {
+            (
+                format!("{}{}", defines, vertex_shader_source),
+                format!("{}{}", defines, fragment_shader_source),
+            )
+        };
+        // The programs can be shared with other contexts, so make sure the program uses the vertex array object of this context
+        *self.active_vao.write().unwrap() = self.vao;
         let mut programs = self.programs.write().unwrap();
         if let Some(program) = programs.get(&key) {
             callback(program);
@@ -70,6 +248,275 @@ impl Context {
         Ok(())
     }
 
+    ///
+    /// Returns the number of [Program]s compiled and stored using [Context::program].
+    ///
+    pub fn program_count(&self) -> usize {
+        self.programs.read().unwrap().len()
+    }
+
+    ///
+    /// Returns the capabilities and limits of the graphics device, for example the maximum texture size and whether or not it is possible to render into floating point textures.
+    ///
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    ///
+    /// Returns the highest supported precision for a texture which is rendered into, which is not higher than the preferred precision, see [Capabilities::best_render_precision].
+    /// If the preferred precision is not supported, the chosen fallback is reported with the given feature name, see [Context::fallbacks].
+    ///
+    pub fn select_render_precision(
+        &self,
+        feature: &str,
+        preferred: ColorPrecision,
+        linear_filtering: bool,
+    ) -> ColorPrecision {
+        let precision = self
+            .capabilities
+            .best_render_precision(preferred, linear_filtering);
+        if precision != preferred {
+            self.report_fallback(
+                feature,
+                &format!("{:?} precision", preferred),
+                &format!("{:?} precision", precision),
+            );
+        }
+        precision
+    }
+
+    ///
+    /// Reports that a fallback was chosen for the given feature because the requested functionality is not supported on the graphics device.
+    /// Each fallback is only reported once, see [Context::fallbacks].
+    ///
+    pub fn report_fallback(&self, feature: &str, requested: &str, chosen: &str) {
+        let fallback = Fallback {
+            feature: feature.to_owned(),
+            requested: requested.to_owned(),
+            chosen: chosen.to_owned(),
+        };
+        let mut fallbacks = self.fallbacks.write().unwrap();
+        if !fallbacks.contains(&fallback) {
+            fallbacks.push(fallback);
+        }
+    }
+
+    ///
+    /// Enables or disables writing the ID of each object in the render calls of a render target, for example [RenderTarget::render](crate::core::RenderTarget::render),
+    /// into an extra layer of the G-buffer used by the deferred render path. The forward objects are included and the render path is used even if there are no deferred objects.
+    /// When enabled, the object at a given pixel can be found using [RenderTarget::read_id_at](crate::core::RenderTarget::read_id_at) after rendering.
+    /// Disabling the object ID output releases the object IDs of the latest render call.
+    ///
+    pub fn set_object_id_output(&self, enabled: bool) {
+        self.object_id_output
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+        if !enabled {
+            *self.object_ids.write().unwrap() = None;
+        }
+    }
+
+    ///
+    /// Returns whether or not object IDs are written in the render calls, see [Context::set_object_id_output].
+    ///
+    pub fn object_id_output(&self) -> bool {
+        self.object_id_output
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    ///
+    /// Returns the fallbacks which the built-in materials and effects have chosen because a feature is not supported on the graphics device, see [Context::capabilities].
+    ///
+    pub fn fallbacks(&self) -> Vec<Fallback> {
+        self.fallbacks.read().unwrap().clone()
+    }
+
+    ///
+    /// Enables or disables reversed depth, where the near plane of the camera is mapped to a depth of 1 in the depth buffer and the far plane to a depth of 0.
+    /// Combined with a 32 bit floating point depth buffer, for example a [DepthTexture2D] with the `f32` data type,
+    /// the precision of the depth buffer is distributed almost evenly between the near and the far plane, which reduces z-fighting in scenes with a large ratio between the far and near plane.
+    ///
+    /// The precision is only improved when the clip space depth is in the range `[0..1]`, which requires `glClipControl` (OpenGL 4.5 or the `ARB_clip_control` extension), see [Context::load_clip_control].
+    /// In that case, the built-in geometries use a reversed projection, see [RenderProjection].
+    /// Otherwise, the depth is reversed using the depth range, which gives the same depth values but no gain in precision, for example on OpenGL ES and WebGL.
+    ///
+    /// The depth tests in the render states are reversed automatically, for example [DepthTest::Less] becomes [DepthTest::Greater],
+    /// and the depth values given to [ClearState] and returned by `read_depth` use the usual convention where 1 is the far plane.
+    /// Programs compiled after this call are compiled with `REVERSE_Z` defined and, if the clip space depth is in the range `[0..1]`, with `CLIP_DEPTH_ZERO_TO_ONE` defined.
+    /// The built-in materials and effects which read from a depth texture, for example the deferred lighting pass, fog, ambient occlusion, decals and shadows, use `sample_depth` which returns the usual depth
+    /// and the built-in shaders which write to `gl_FragDepth` use `depth_from_clip_position`.
+    /// Custom shaders should compute the clip space position using [RenderProjection::render_view_projection] and use `1.0 - depth` when reading a depth texture directly.
+    ///
+    pub fn set_reverse_z(&self, enabled: bool) {
+        self.reverse_z
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+        unsafe {
+            if let Some(clip_control) = *self.clip_control.read().unwrap() {
+                clip_control(
+                    crate::context::LOWER_LEFT,
+                    if enabled {
+                        crate::context::ZERO_TO_ONE
+                    } else {
+                        crate::context::NEGATIVE_ONE_TO_ONE
+                    },
+                );
+                self.depth_range_f32(0.0, 1.0);
+            } else if enabled {
+                self.depth_range_f32(1.0, 0.0);
+            } else {
+                self.depth_range_f32(0.0, 1.0);
+            }
+        }
+    }
+
+    ///
+    /// Returns whether or not reversed depth is enabled, see [Context::set_reverse_z].
+    ///
+    pub fn reverse_z(&self) -> bool {
+        self.reverse_z.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    ///
+    /// Returns whether or not the clip space depth is in the range `[0..1]`, which is the case when reversed depth is enabled and `glClipControl` is loaded, see [Context::set_reverse_z].
+    /// The projection returned from [RenderProjection::render_projection] depends on this.
+    ///
+    pub fn clip_depth_zero_to_one(&self) -> bool {
+        self.reverse_z() && self.clip_control.read().unwrap().is_some()
+    }
+
+    ///
+    /// Loads the `glClipControl` function, which is not available through the low-level graphics context, so reversed depth can use a clip space depth in the range `[0..1]`, see [Context::set_reverse_z].
+    /// Does nothing if the function is not supported, ie. on OpenGL ES, WebGL and OpenGL versions below 4.5 without the `ARB_clip_control` extension.
+    /// This is done automatically when the context is created by the [window](crate::window) module.
+    ///
+    /// # Safety
+    /// The loader function must return the address of the OpenGL function with the given name for the low-level graphics context of this context or a null pointer,
+    /// for example the same loader function as the one used to create the low-level graphics context.
+    ///
+    pub unsafe fn load_clip_control(
+        &self,
+        mut loader_function: impl FnMut(&str) -> *const std::os::raw::c_void,
+    ) {
+        let version = self.version();
+        let supported = !version.is_embedded
+            && (version.major > 4
+                || (version.major == 4 && version.minor >= 5)
+                || self.supported_extensions().contains("GL_ARB_clip_control"));
+        if !supported {
+            return;
+        }
+        let address = loader_function("glClipControl");
+        if !address.is_null() {
+            *self.clip_control.write().unwrap() = Some(std::mem::transmute::<
+                *const std::os::raw::c_void,
+                ClipControlFunction,
+            >(address));
+            // Apply the current setting, in case reversed depth was enabled before the function was loaded
+            self.set_reverse_z(self.reverse_z());
+        }
+    }
+
+    ///
+    /// Enables or disables linear output, where the built-in materials write linear colors instead of converting them to the sRGB color space.
+    /// All lighting is computed with linear colors, so the colors have to be converted to the sRGB color space once before they are displayed.
+    /// By default, each material does this as the last step in its shader. With linear output enabled, the conversion is left to either
+    /// - the graphics hardware when rendering to a texture in the sRGB color space, see [Texture2D::new_empty_srgb], or to an sRGB capable screen.
+    ///   On desktop OpenGL, the hardware conversion is enabled together with linear output, on OpenGL ES and WebGL it is always enabled.
+    /// - or a final pass, see [SrgbEncodingEffect](crate::renderer::SrgbEncodingEffect), which is needed when rendering to a texture with a higher precision,
+    ///   for example to apply post-processing effects to the linear colors.
+    ///
+    /// Blending and anti-aliasing is more correct with linear output, since the colors are blended before they are converted.
+    /// Programs compiled after this call are compiled with `LINEAR_OUTPUT` defined, which custom shaders can use to skip the conversion to the sRGB color space.
+    ///
+    pub fn set_linear_output(&self, enabled: bool) {
+        self.linear_output
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+        if !self.version().is_embedded {
+            unsafe {
+                if enabled {
+                    self.enable(crate::context::FRAMEBUFFER_SRGB);
+                } else {
+                    self.disable(crate::context::FRAMEBUFFER_SRGB);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns whether or not linear output is enabled, see [Context::set_linear_output].
+    ///
+    pub fn linear_output(&self) -> bool {
+        self.linear_output
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    ///
+    /// Sets the global bias added to the level of detail used when sampling textures in the materials that support it, for example the physical material.
+    /// The global bias is added to the bias of each material.
+    /// A negative bias gives sharper textures, for example when using a temporal upscaler, while a positive bias reduces shimmering on noisy textures.
+    ///
+    pub fn set_texture_lod_bias(&self, bias: f32) {
+        *self.texture_lod_bias.write().unwrap() = bias;
+    }
+
+    ///
+    /// Returns the global texture level of detail bias, see [Context::set_texture_lod_bias].
+    ///
+    pub fn texture_lod_bias(&self) -> f32 {
+        *self.texture_lod_bias.read().unwrap()
+    }
+
+    ///
+    /// Sets a stencil state which is used instead of the stencil state in the render states of all render calls until it is set to `None` again,
+    /// for example to restrict the rendering of objects with any material to a masked region, see [Stencil] and `MaskedRendering`.
+    ///
+    pub fn set_stencil_override(&self, stencil: Option<Stencil>) {
+        *self.stencil_override.write().unwrap() = stencil;
+    }
+
+    ///
+    /// Returns the stencil state which overrides the stencil state in the render states, see [Context::set_stencil_override].
+    ///
+    pub fn stencil_override(&self) -> Option<Stencil> {
+        *self.stencil_override.read().unwrap()
+    }
+
+    ///
+    /// Sets a depth test which is used instead of the [DepthTest::Less] and [DepthTest::LessOrEqual] depth tests in the render states of all render calls until it is set to `None` again,
+    /// for example to only shade the visible surfaces after a depth pre-pass, see `RenderTarget::render_with_depth_prepass`.
+    /// Render calls with any other depth test, for example [DepthTest::Always], are not affected.
+    ///
+    pub fn set_depth_test_override(&self, depth_test: Option<DepthTest>) {
+        *self.depth_test_override.write().unwrap() = depth_test;
+    }
+
+    ///
+    /// Returns the depth test which overrides the less than depth tests in the render states, see [Context::set_depth_test_override].
+    ///
+    pub fn depth_test_override(&self) -> Option<DepthTest> {
+        *self.depth_test_override.read().unwrap()
+    }
+
+    ///
+    /// Enables logarithmic depth with the given maximum distance from the camera, or disables it if `None`.
+    /// With logarithmic depth, the depth written to the depth buffer is proportional to the logarithm of the distance from the camera instead of the inverse of the distance,
+    /// which distributes the precision of the depth buffer evenly across orders of magnitude,
+    /// so objects millimeters and megameters from the camera can be rendered in one pass without z-fighting, for example in astronomical or geospatial scenes.
+    ///
+    /// Logarithmic depth is applied in the vertex shaders of the built-in geometries and handled by the built-in effects which read from a depth texture, for example the deferred lighting pass and fog.
+    /// Custom shaders should apply `logarithmic_depth` to `gl_Position` and use `world_pos_from_depth` when `LOGARITHMIC_DEPTH` is defined.
+    /// Only perspective cameras are supported and the depth is only correct at the vertices, so large triangles close to the camera should be subdivided.
+    ///
+    pub fn set_logarithmic_depth(&self, max_distance: Option<f32>) {
+        *self.logarithmic_depth.write().unwrap() = max_distance;
+    }
+
+    ///
+    /// Returns the maximum distance from the camera if logarithmic depth is enabled, see [Context::set_logarithmic_depth].
+    ///
+    pub fn logarithmic_depth(&self) -> Option<f32> {
+        *self.logarithmic_depth.read().unwrap()
+    }
+
     ///
     /// Set the scissor test for this context (see [ScissorBox]).
     ///
@@ -147,6 +594,11 @@ impl Context {
     /// Set the depth test for this context (see [DepthTest]).
     ///
     pub fn set_depth_test(&self, depth_test: DepthTest) {
+        let depth_test = if self.reverse_z() {
+            depth_test.reversed()
+        } else {
+            depth_test
+        };
         unsafe {
             self.enable(crate::context::DEPTH_TEST);
             match depth_test {
@@ -209,6 +661,104 @@ impl Context {
         }
     }
 
+    ///
+    /// Set the constant color used when blending with one of the constant multipliers, for example [BlendMultiplierType::ConstantColor].
+    ///
+    pub fn set_blend_color(&self, color: Color) {
+        unsafe {
+            self.blend_color(
+                color.r as f32 / 255.0,
+                color.g as f32 / 255.0,
+                color.b as f32 / 255.0,
+                color.a as f32 / 255.0,
+            );
+        }
+    }
+
+    ///
+    /// Set the polygon offset for this context (see [PolygonOffset]) or disable it if `None`.
+    ///
+    pub fn set_polygon_offset(&self, polygon_offset: Option<PolygonOffset>) {
+        unsafe {
+            if let Some(polygon_offset) = polygon_offset {
+                self.enable(crate::context::POLYGON_OFFSET_FILL);
+                self.polygon_offset(polygon_offset.factor, polygon_offset.units);
+            } else {
+                self.disable(crate::context::POLYGON_OFFSET_FILL);
+            }
+        }
+    }
+
+    ///
+    /// Enables or disables depth clamping for this context (see [RenderStates::depth_clamp]). Does nothing if depth clamping is not supported.
+    ///
+    pub fn set_depth_clamp(&self, depth_clamp: bool) {
+        if self.capabilities.depth_clamp {
+            unsafe {
+                if depth_clamp {
+                    self.enable(crate::context::DEPTH_CLAMP);
+                } else {
+                    self.disable(crate::context::DEPTH_CLAMP);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Set the stencil test for this context (see [Stencil]).
+    ///
+    pub fn set_stencil(&self, stencil: Stencil) {
+        unsafe {
+            if let Stencil::Enabled {
+                test,
+                reference,
+                read_mask,
+                write_mask,
+                stencil_fail,
+                depth_fail,
+                pass,
+            } = stencil
+            {
+                self.enable(crate::context::STENCIL_TEST);
+                self.stencil_func(
+                    match test {
+                        StencilTest::Never => crate::context::NEVER,
+                        StencilTest::Less => crate::context::LESS,
+                        StencilTest::Equal => crate::context::EQUAL,
+                        StencilTest::LessOrEqual => crate::context::LEQUAL,
+                        StencilTest::Greater => crate::context::GREATER,
+                        StencilTest::NotEqual => crate::context::NOTEQUAL,
+                        StencilTest::GreaterOrEqual => crate::context::GEQUAL,
+                        StencilTest::Always => crate::context::ALWAYS,
+                    },
+                    reference as i32,
+                    read_mask as u32,
+                );
+                self.stencil_op(
+                    Self::stencil_const_from_operation(stencil_fail),
+                    Self::stencil_const_from_operation(depth_fail),
+                    Self::stencil_const_from_operation(pass),
+                );
+                self.stencil_mask(write_mask as u32);
+            } else {
+                self.disable(crate::context::STENCIL_TEST);
+            }
+        }
+    }
+
+    fn stencil_const_from_operation(operation: StencilOperation) -> u32 {
+        match operation {
+            StencilOperation::Keep => crate::context::KEEP,
+            StencilOperation::Zero => crate::context::ZERO,
+            StencilOperation::Replace => crate::context::REPLACE,
+            StencilOperation::Increment => crate::context::INCR,
+            StencilOperation::IncrementWrap => crate::context::INCR_WRAP,
+            StencilOperation::Decrement => crate::context::DECR,
+            StencilOperation::DecrementWrap => crate::context::DECR_WRAP,
+            StencilOperation::Invert => crate::context::INVERT,
+        }
+    }
+
     fn blend_const_from_multiplier(multiplier: BlendMultiplierType) -> u32 {
         match multiplier {
             BlendMultiplierType::Zero => crate::context::ZERO,
@@ -222,6 +772,14 @@ impl Context {
             BlendMultiplierType::DstAlpha => crate::context::DST_ALPHA,
             BlendMultiplierType::OneMinusDstAlpha => crate::context::ONE_MINUS_DST_ALPHA,
             BlendMultiplierType::SrcAlphaSaturate => crate::context::SRC_ALPHA_SATURATE,
+            BlendMultiplierType::ConstantColor => crate::context::CONSTANT_COLOR,
+            BlendMultiplierType::OneMinusConstantColor => crate::context::ONE_MINUS_CONSTANT_COLOR,
+            BlendMultiplierType::ConstantAlpha => crate::context::CONSTANT_ALPHA,
+            BlendMultiplierType::OneMinusConstantAlpha => crate::context::ONE_MINUS_CONSTANT_ALPHA,
+            BlendMultiplierType::Src1Color => crate::context::SRC1_COLOR,
+            BlendMultiplierType::OneMinusSrc1Color => crate::context::ONE_MINUS_SRC1_COLOR,
+            BlendMultiplierType::Src1Alpha => crate::context::SRC1_ALPHA,
+            BlendMultiplierType::OneMinusSrc1Alpha => crate::context::ONE_MINUS_SRC1_ALPHA,
         }
     }
 
@@ -241,12 +799,29 @@ impl Context {
     pub fn set_render_states(&self, render_states: RenderStates) {
         self.set_cull(render_states.cull);
         self.set_write_mask(render_states.write_mask);
-        if !render_states.write_mask.depth && render_states.depth_test == DepthTest::Always {
+        let depth_test = match self.depth_test_override() {
+            Some(depth_test)
+                if matches!(
+                    render_states.depth_test,
+                    DepthTest::Less | DepthTest::LessOrEqual
+                ) =>
+            {
+                depth_test
+            }
+            _ => render_states.depth_test,
+        };
+        if !render_states.write_mask.depth && depth_test == DepthTest::Always {
             unsafe { self.disable(crate::context::DEPTH_TEST) }
         } else {
-            self.set_depth_test(render_states.depth_test);
+            self.set_depth_test(depth_test);
         }
         self.set_blend(render_states.blend);
+        if render_states.blend != Blend::Disabled {
+            self.set_blend_color(render_states.blend_color);
+        }
+        self.set_stencil(self.stencil_override().unwrap_or(render_states.stencil));
+        self.set_polygon_offset(render_states.polygon_offset);
+        self.set_depth_clamp(render_states.depth_clamp);
     }
 
     ///
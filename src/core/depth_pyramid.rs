@@ -0,0 +1,141 @@
+use crate::core::*;
+
+///
+/// A hierarchical depth buffer (Hi-Z buffer), ie. a texture where the first mip level contains the depth of a [DepthTexture2D]
+/// and each of the following mip levels contains the maximum depth of the corresponding 2x2 texels in the previous mip level.
+/// A texel in a mip level therefore contains the depth of the farthest surface in the region of the screen it covers,
+/// which makes it possible to conservatively test whether something is hidden behind the rendered scene or to skip empty space when marching rays in screen space
+/// by reading a few texels at a coarse mip level instead of many texels at the first mip level.
+///
+/// The depth is stored in the red channel of the [DepthPyramid::texture] as a 32 bit float and should be read using `texelFetch` with an explicit mip level.
+/// It is stored with 1 at the far plane, also when reversed depth is enabled, see [Context::set_reverse_z].
+///
+pub struct DepthPyramid {
+    context: Context,
+    texture: Texture2D,
+    mip_level_count: u32,
+}
+
+impl DepthPyramid {
+    ///
+    /// Constructs a new depth pyramid with the given size of the first mip level, which should be the size of the depth textures given to [DepthPyramid::update].
+    ///
+    pub fn new(context: &Context, width: u32, height: u32) -> Self {
+        let mip_level_count = 32 - width.max(height).max(1).leading_zeros();
+        Self {
+            context: context.clone(),
+            texture: Texture2D::new_empty_with_mip_maps::<f32>(
+                context,
+                width,
+                height,
+                mip_level_count,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                Some(Interpolation::Nearest),
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            mip_level_count,
+        }
+    }
+
+    ///
+    /// Builds the depth pyramid from the given depth texture, for example the depth of the previous frame.
+    /// If the size of the depth texture is different from the size of this depth pyramid, the depth pyramid is resized.
+    ///
+    pub fn update(&mut self, depth_texture: &DepthTexture2D) {
+        if depth_texture.width() != self.width() || depth_texture.height() != self.height() {
+            *self = Self::new(&self.context, depth_texture.width(), depth_texture.height());
+        }
+        let render_states = RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        };
+
+        self.texture.set_mip_level_range(0, 0);
+        let target = ColorTarget::new_texture2d(&self.context, &self.texture, Some(0));
+        target.write(|| {
+            apply_effect(
+                &self.context,
+                "
+                uniform sampler2D depthMap;
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {
+                    float depth = texelFetch(depthMap, ivec2(gl_FragCoord.xy), 0).r;
+                #ifdef REVERSE_Z
+                    depth = 1.0 - depth;
+                #endif
+                    outColor = vec4(depth);
+                }
+                ",
+                render_states,
+                target.viewport(),
+                |program| program.use_depth_texture("depthMap", depth_texture),
+            );
+        });
+
+        for level in 1..self.mip_level_count {
+            // Only the previous mip level is sampled while writing to this mip level
+            self.texture.set_mip_level_range(level - 1, level - 1);
+            let previous_size = Vector2::new(
+                self.level_width(level - 1) as i32,
+                self.level_height(level - 1) as i32,
+            );
+            let target = ColorTarget::new_texture2d(&self.context, &self.texture, Some(level));
+            target.write(|| {
+                apply_effect(
+                    &self.context,
+                    include_str!("shaders/depth_pyramid.frag"),
+                    render_states,
+                    target.viewport(),
+                    |program| {
+                        program.use_texture("previousLevel", &self.texture);
+                        program.use_uniform("previousSize", previous_size);
+                    },
+                );
+            });
+        }
+        self.texture
+            .set_mip_level_range(0, self.mip_level_count - 1);
+    }
+
+    ///
+    /// Returns the texture containing the depth pyramid where each mip level contains the maximum depth of the previous mip level.
+    ///
+    pub fn texture(&self) -> &Texture2D {
+        &self.texture
+    }
+
+    ///
+    /// Returns the number of mip levels, the last of which is 1x1 texels and contains the maximum depth of the entire depth texture.
+    ///
+    pub fn mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
+    /// The width of the first mip level.
+    pub fn width(&self) -> u32 {
+        self.texture.width()
+    }
+
+    /// The height of the first mip level.
+    pub fn height(&self) -> u32 {
+        self.texture.height()
+    }
+
+    ///
+    /// Returns the width of the given mip level.
+    ///
+    pub fn level_width(&self, level: u32) -> u32 {
+        (self.width() >> level).max(1)
+    }
+
+    ///
+    /// Returns the height of the given mip level.
+    ///
+    pub fn level_height(&self, level: u32) -> u32 {
+        (self.height() >> level).max(1)
+    }
+}
@@ -0,0 +1,502 @@
+use crate::core::*;
+
+// The weights of the red, green and blue channels when computing the luminance of a linear color
+const LUMINANCE_WEIGHTS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+///
+/// Statistics of the colors in a [ColorTexture], computed on the GPU by repeatedly reducing 2x2 texels to one texel, see [ImageStatistics::new].
+/// Useful for example for auto exposure, where the exposure is adjusted towards the average luminance of the rendered image.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageStatistics {
+    /// The minimum value of each channel.
+    pub min: Vec4,
+    /// The maximum value of each channel.
+    pub max: Vec4,
+    /// The average value of each channel.
+    pub average: Vec4,
+    /// The average luminance, assuming the colors are linear.
+    pub average_luminance: f32,
+    /// The geometric mean of the luminance, ie. the exponential of the average logarithm of the luminance, which is less sensitive to a few very bright texels than the average luminance.
+    /// Luminances below 0.0001 are clamped to 0.0001 to avoid the logarithm of zero.
+    pub log_average_luminance: f32,
+}
+
+impl ImageStatistics {
+    ///
+    /// Computes the statistics of the colors in the given color texture, which is the first layer if the color texture is an array and the first side if it is a cube map.
+    /// The statistics are computed on the GPU and only the result is read back, which stalls until the GPU is done, so avoid calling this more than once per frame.
+    /// Falls back to reading the texture and computing the statistics on the CPU if rendering into textures with 32 bit floating point values is not supported.
+    ///
+    pub fn new(context: &Context, color_texture: ColorTexture) -> Self {
+        if !context.capabilities().float_render_targets {
+            context.report_fallback("Image statistics", "GPU", "CPU");
+            return Self::from_texels(&read_texels(context, color_texture));
+        }
+        let render_states = RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        let layers = [0, 1, 2, 3];
+        let mut source_size = (color_texture.width(), color_texture.height());
+        let mut previous: Option<Texture2DArray> = None;
+        loop {
+            let width = (source_size.0 + 1) / 2;
+            let height = (source_size.1 + 1) / 2;
+            let mut level = new_level(context, width, height);
+            let target = level.as_color_target(&layers, None);
+            target.write(|| {
+                let size = Vector2::new(source_size.0 as i32, source_size.1 as i32);
+                if let Some(previous) = &previous {
+                    apply_effect(
+                        context,
+                        include_str!("shaders/image_statistics.frag"),
+                        render_states,
+                        target.viewport(),
+                        |program| {
+                            program.use_texture_array("previousLevel", previous);
+                            program.use_uniform("sourceSize", size);
+                        },
+                    );
+                } else {
+                    apply_effect(
+                        context,
+                        &format!(
+                            "#define FIRST_PASS\n{}{}",
+                            fetch_color_source(color_texture),
+                            include_str!("shaders/image_statistics.frag")
+                        ),
+                        render_states,
+                        target.viewport(),
+                        |program| {
+                            use_fetch_color_uniforms(program, color_texture);
+                            program.use_uniform("sourceSize", size);
+                        },
+                    );
+                }
+            });
+            source_size = (width, height);
+            previous = Some(level);
+            if width == 1 && height == 1 {
+                break;
+            }
+        }
+
+        let mut result = previous.unwrap();
+        let mut read_layer = |layer: u32| {
+            let value = result.as_color_target(&[layer], None).read::<[f32; 4]>()[0];
+            Vec4::new(value[0], value[1], value[2], value[3])
+        };
+        let min = read_layer(0);
+        let max = read_layer(1);
+        let average = read_layer(2);
+        let luminance = read_layer(3);
+        Self {
+            min,
+            max,
+            average,
+            average_luminance: luminance.x,
+            log_average_luminance: luminance.y.exp(),
+        }
+    }
+
+    fn from_texels(texels: &[Vec4]) -> Self {
+        let mut min = Vec4::from_value(f32::MAX);
+        let mut max = Vec4::from_value(f32::MIN);
+        let mut sum = Vec4::zero();
+        let mut sum_luminance = 0.0;
+        let mut sum_log_luminance = 0.0;
+        for texel in texels {
+            min = vec4(
+                min.x.min(texel.x),
+                min.y.min(texel.y),
+                min.z.min(texel.z),
+                min.w.min(texel.w),
+            );
+            max = vec4(
+                max.x.max(texel.x),
+                max.y.max(texel.y),
+                max.z.max(texel.z),
+                max.w.max(texel.w),
+            );
+            sum += *texel;
+            let l = luminance(*texel);
+            sum_luminance += l;
+            sum_log_luminance += l.max(0.0001).ln();
+        }
+        let count = texels.len().max(1) as f32;
+        Self {
+            min,
+            max,
+            average: sum / count,
+            average_luminance: sum_luminance / count,
+            log_average_luminance: (sum_log_luminance / count).exp(),
+        }
+    }
+}
+
+///
+/// A channel of a [Histogram].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HistogramChannel {
+    /// The red channel.
+    Red,
+    /// The green channel.
+    Green,
+    /// The blue channel.
+    Blue,
+    /// The luminance, assuming the colors are linear.
+    Luminance,
+}
+
+///
+/// The histograms of the red, green, blue and luminance channels of a [ColorTexture], computed on the GPU, see [Histogram::new].
+/// Each histogram divides the range from [Histogram::min] to [Histogram::max] into a number of bins of equal size and counts the number of texels with a value in each bin.
+/// Useful for example for a levels tool, for analysing scientific images or for auto exposure based on a percentile of the luminance, see [Histogram::percentile].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    min: f32,
+    max: f32,
+    bins: [Vec<u32>; 4],
+}
+
+impl Histogram {
+    ///
+    /// Computes the histograms of the colors in the given color texture, which is the first layer if the color texture is an array and the first side if it is a cube map, with the given number of bins between the given minimum and maximum value.
+    /// Values below the minimum are counted in the first bin and values above the maximum in the last bin.
+    /// The histograms are computed on the GPU by drawing a tiny triangle for each channel of each texel into the bin it belongs to using additive blending,
+    /// and only the result is read back, which stalls until the GPU is done.
+    /// Falls back to reading the texture and computing the histograms on the CPU if blending into textures with 32 bit floating point values is not supported.
+    ///
+    /// **Note:** The counts are exact up to 16777216 texels in one bin.
+    ///
+    pub fn new(
+        context: &Context,
+        color_texture: ColorTexture,
+        bin_count: u32,
+        min: f32,
+        max: f32,
+    ) -> Self {
+        let bin_count = bin_count.max(1);
+        if !context.capabilities().float_blending {
+            context.report_fallback("Histogram", "GPU", "CPU");
+            return Self::from_texels(&read_texels(context, color_texture), bin_count, min, max);
+        }
+        let mut texture = Texture2D::new_empty::<f32>(
+            context,
+            bin_count,
+            4,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let width = color_texture.width();
+        let height = color_texture.height();
+        let target = texture.as_color_target(None);
+        target.clear(ClearState::color(0.0, 0.0, 0.0, 0.0));
+        target.write(|| {
+            context
+                .program(
+                    format!(
+                        "{}{}",
+                        fetch_color_source(color_texture),
+                        include_str!("shaders/histogram.vert")
+                    ),
+                    "
+                    layout (location = 0) out vec4 outColor;
+                    void main()
+                    {
+                        outColor = vec4(1.0);
+                    }
+                    "
+                    .to_owned(),
+                    |program| {
+                        use_fetch_color_uniforms(program, color_texture);
+                        program
+                            .use_uniform("sourceSize", Vector2::new(width as i32, height as i32));
+                        program.use_uniform("binCount", bin_count as i32);
+                        program.use_uniform("minValue", min);
+                        program.use_uniform("maxValue", max);
+                        program.draw_arrays(
+                            RenderStates {
+                                depth_test: DepthTest::Always,
+                                write_mask: WriteMask::COLOR,
+                                blend: Blend::ADD,
+                                cull: Cull::None,
+                                ..Default::default()
+                            },
+                            target.viewport(),
+                            width * height * 4 * 3,
+                        );
+                    },
+                )
+                .expect("Failed compiling shader");
+        });
+        let counts = target.read::<f32>();
+        let channel = |index: usize| {
+            counts[index * bin_count as usize..(index + 1) * bin_count as usize]
+                .iter()
+                .map(|c| c.round() as u32)
+                .collect::<Vec<_>>()
+        };
+        Self {
+            min,
+            max,
+            bins: [channel(0), channel(1), channel(2), channel(3)],
+        }
+    }
+
+    fn from_texels(texels: &[Vec4], bin_count: u32, min: f32, max: f32) -> Self {
+        let mut bins = [
+            vec![0; bin_count as usize],
+            vec![0; bin_count as usize],
+            vec![0; bin_count as usize],
+            vec![0; bin_count as usize],
+        ];
+        let bin = |value: f32| {
+            (((value - min) / (max - min) * bin_count as f32)
+                .floor()
+                .max(0.0) as u32)
+                .min(bin_count - 1) as usize
+        };
+        for texel in texels {
+            bins[0][bin(texel.x)] += 1;
+            bins[1][bin(texel.y)] += 1;
+            bins[2][bin(texel.z)] += 1;
+            bins[3][bin(luminance(*texel))] += 1;
+        }
+        Self { min, max, bins }
+    }
+
+    ///
+    /// Returns the minimum value of the first bin.
+    ///
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    ///
+    /// Returns the maximum value of the last bin.
+    ///
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    ///
+    /// Returns the number of bins in each histogram.
+    ///
+    pub fn bin_count(&self) -> u32 {
+        self.bins[0].len() as u32
+    }
+
+    ///
+    /// Returns the range of values counted in the bin with the given index.
+    ///
+    pub fn bin_range(&self, bin: u32) -> std::ops::Range<f32> {
+        let size = (self.max - self.min) / self.bin_count() as f32;
+        self.min + bin as f32 * size..self.min + (bin + 1) as f32 * size
+    }
+
+    ///
+    /// Returns the number of texels in each bin of the histogram of the given channel.
+    ///
+    pub fn bins(&self, channel: HistogramChannel) -> &[u32] {
+        &self.bins[channel as usize]
+    }
+
+    ///
+    /// Returns the value below which the given fraction of the texels are in the given channel, for example 0.5 for the median.
+    /// The value is interpolated linearly within the bin containing it.
+    ///
+    pub fn percentile(&self, channel: HistogramChannel, fraction: f32) -> f32 {
+        let bins = self.bins(channel);
+        let total = bins.iter().map(|c| *c as u64).sum::<u64>();
+        if total == 0 {
+            return self.min;
+        }
+        let limit = fraction.clamp(0.0, 1.0) as f64 * total as f64;
+        let mut count = 0u64;
+        for (index, bin_count) in bins.iter().enumerate() {
+            let next = count + *bin_count as u64;
+            if next as f64 >= limit && *bin_count > 0 {
+                let range = self.bin_range(index as u32);
+                let t = ((limit - count as f64) / *bin_count as f64) as f32;
+                return range.start + t * (range.end - range.start);
+            }
+            count = next;
+        }
+        self.max
+    }
+}
+
+fn luminance(color: Vec4) -> f32 {
+    color.x * LUMINANCE_WEIGHTS[0] + color.y * LUMINANCE_WEIGHTS[1] + color.z * LUMINANCE_WEIGHTS[2]
+}
+
+fn new_level(context: &Context, width: u32, height: u32) -> Texture2DArray {
+    Texture2DArray::new_empty::<[f32; 4]>(
+        context,
+        width,
+        height,
+        4,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
+
+// Returns the shader source defining `vec4 fetch_color(ivec2 texel)`, which reads a texel without filtering in both vertex and fragment shaders
+fn fetch_color_source(color_texture: ColorTexture) -> &'static str {
+    match color_texture {
+        ColorTexture::Single(_) => {
+            "
+            uniform sampler2D colorMap;
+            vec4 fetch_color(ivec2 texel)
+            {
+                return texelFetch(colorMap, texel, 0);
+            }
+            "
+        }
+        ColorTexture::Array { .. } => {
+            "
+            uniform sampler2DArray colorMap;
+            uniform int colorLayer;
+            vec4 fetch_color(ivec2 texel)
+            {
+                return texelFetch(colorMap, ivec3(texel, colorLayer), 0);
+            }
+            "
+        }
+        ColorTexture::CubeMap { .. } => {
+            // Samples the center of the texel, so the result is not affected by the filtering of the cube map
+            "
+            uniform samplerCube colorMap;
+            uniform int colorSide;
+            uniform ivec2 colorSize;
+            vec4 fetch_color(ivec2 texel)
+            {
+                vec2 uv = 2.0 * (vec2(texel) + 0.5) / vec2(colorSize) - 1.0;
+                vec3 direction;
+                if (colorSide == 0) {
+                    direction = vec3(1.0, -uv.y, -uv.x);
+                } else if (colorSide == 1) {
+                    direction = vec3(-1.0, -uv.y, uv.x);
+                } else if (colorSide == 2) {
+                    direction = vec3(uv.x, 1.0, uv.y);
+                } else if (colorSide == 3) {
+                    direction = vec3(uv.x, -1.0, -uv.y);
+                } else if (colorSide == 4) {
+                    direction = vec3(uv.x, -uv.y, 1.0);
+                } else {
+                    direction = vec3(-uv.x, -uv.y, -1.0);
+                }
+                return textureLod(colorMap, direction, 0.0);
+            }
+            "
+        }
+    }
+}
+
+fn use_fetch_color_uniforms(program: &Program, color_texture: ColorTexture) {
+    match color_texture {
+        ColorTexture::Single(texture) => program.use_texture("colorMap", texture),
+        ColorTexture::Array { texture, layers } => {
+            program.use_texture_array("colorMap", texture);
+            program.use_uniform("colorLayer", layers[0] as i32);
+        }
+        ColorTexture::CubeMap { texture, sides } => {
+            program.use_texture_cube("colorMap", texture);
+            program.use_uniform(
+                "colorSide",
+                (sides[0].to_const() - crate::context::TEXTURE_CUBE_MAP_POSITIVE_X) as i32,
+            );
+            program.use_uniform(
+                "colorSize",
+                Vector2::new(texture.width() as i32, texture.height() as i32),
+            );
+        }
+    }
+}
+
+// Copies the color texture into a texture which can be read and returns the texels
+fn read_texels(context: &Context, color_texture: ColorTexture) -> Vec<Vec4> {
+    let width = color_texture.width();
+    let height = color_texture.height();
+    let copy = |program: &Program| use_fetch_color_uniforms(program, color_texture);
+    let fragment_shader_source = format!(
+        "{}
+        layout (location = 0) out vec4 outColor;
+        void main()
+        {{
+            outColor = fetch_color(ivec2(gl_FragCoord.xy));
+        }}",
+        fetch_color_source(color_texture)
+    );
+    let render_states = RenderStates {
+        depth_test: DepthTest::Always,
+        write_mask: WriteMask::COLOR,
+        cull: Cull::None,
+        ..Default::default()
+    };
+    // Only 8 bit colors can be read if rendering into floating point textures is not supported
+    if context.capabilities().float_render_targets {
+        let mut texture = new_readable_texture::<[f32; 4]>(context, width, height);
+        let target = texture.as_color_target(None);
+        target.write(|| {
+            apply_effect(
+                context,
+                &fragment_shader_source,
+                render_states,
+                target.viewport(),
+                copy,
+            )
+        });
+        target
+            .read::<[f32; 4]>()
+            .into_iter()
+            .map(|c| vec4(c[0], c[1], c[2], c[3]))
+            .collect()
+    } else {
+        let mut texture = new_readable_texture::<[u8; 4]>(context, width, height);
+        let target = texture.as_color_target(None);
+        target.write(|| {
+            apply_effect(
+                context,
+                &fragment_shader_source,
+                render_states,
+                target.viewport(),
+                copy,
+            )
+        });
+        target
+            .read::<[u8; 4]>()
+            .into_iter()
+            .map(|c| vec4(c[0] as f32, c[1] as f32, c[2] as f32, c[3] as f32) / 255.0)
+            .collect()
+    }
+}
+
+fn new_readable_texture<T: TextureDataType>(
+    context: &Context,
+    width: u32,
+    height: u32,
+) -> Texture2D {
+    Texture2D::new_empty::<T>(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
@@ -0,0 +1,113 @@
+use crate::core::*;
+
+///
+/// A query which tests whether any fragment of the render calls between [OcclusionQuery::begin] and [OcclusionQuery::end] passes the depth test,
+/// ie. whether anything rendered in between is visible, for example to test if a bounding box is hidden behind other objects.
+///
+/// The result is computed on the GPU, so to avoid stalling the pipeline, poll [OcclusionQuery::result_available] and use the result when available, usually in one of the next frames.
+///
+pub struct OcclusionQuery {
+    context: Context,
+    id: crate::context::Query,
+    active: bool,
+    has_result: bool,
+}
+
+impl OcclusionQuery {
+    ///
+    /// Creates a new occlusion query.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            id: unsafe { context.create_query().expect("Failed creating query") },
+            active: false,
+            has_result: false,
+        }
+    }
+
+    ///
+    /// Starts the query. All render calls until [OcclusionQuery::end] is called are tested and any previous result is discarded.
+    ///
+    /// # Panic
+    /// Will panic if the query is already started.
+    ///
+    pub fn begin(&mut self) {
+        if self.active {
+            panic!("the occlusion query is already started");
+        }
+        unsafe {
+            self.context
+                .begin_query(crate::context::ANY_SAMPLES_PASSED, self.id);
+        }
+        self.active = true;
+        self.has_result = false;
+    }
+
+    ///
+    /// Ends the query started with [OcclusionQuery::begin].
+    ///
+    /// # Panic
+    /// Will panic if the query is not started.
+    ///
+    pub fn end(&mut self) {
+        if !self.active {
+            panic!("the occlusion query is not started");
+        }
+        unsafe {
+            self.context.end_query(crate::context::ANY_SAMPLES_PASSED);
+        }
+        self.active = false;
+        self.has_result = true;
+    }
+
+    ///
+    /// Returns whether or not the query has ended and the result is available, so [OcclusionQuery::result] returns without waiting for the GPU.
+    ///
+    pub fn result_available(&self) -> bool {
+        self.has_result
+            && unsafe {
+                self.context
+                    .get_query_parameter_u32(self.id, crate::context::QUERY_RESULT_AVAILABLE)
+                    != 0
+            }
+    }
+
+    ///
+    /// Returns whether or not any fragment passed the depth test between [OcclusionQuery::begin] and [OcclusionQuery::end] if the result is available and otherwise `None`.
+    ///
+    pub fn result(&self) -> Option<bool> {
+        if self.result_available() {
+            Some(unsafe {
+                self.context
+                    .get_query_parameter_u32(self.id, crate::context::QUERY_RESULT)
+                    != 0
+            })
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Returns whether or not any fragment passed the depth test, waiting for the GPU to finish the query if the result is not available yet.
+    /// Returns `None` if the query has not been started and ended.
+    ///
+    pub fn wait_for_result(&self) -> Option<bool> {
+        if !self.has_result {
+            return None;
+        }
+        Some(unsafe {
+            self.context
+                .get_query_parameter_u32(self.id, crate::context::QUERY_RESULT)
+                != 0
+        })
+    }
+}
+
+impl Drop for OcclusionQuery {
+    fn drop(&mut self) {
+        unsafe {
+            self.context.delete_query(self.id);
+        }
+    }
+}
@@ -340,7 +340,8 @@ impl Program {
             buffer.bind();
             let loc = self.location(name);
             unsafe {
-                self.context.bind_vertex_array(Some(self.context.vao));
+                self.context
+                    .bind_vertex_array(Some(self.context.active_vao()));
                 self.context.enable_vertex_attrib_array(loc);
                 if !buffer.normalized()
                     && (buffer.data_type() == crate::context::UNSIGNED_BYTE
@@ -374,6 +375,54 @@ impl Program {
         }
     }
 
+    ///
+    /// Uses one of the attributes in the given [VertexBuffer] containing several interleaved attributes in this shader program and associates it with the given named variable.
+    /// The layout describes where the attribute is stored in the data of each vertex, while the buffer can be filled with data of any type, for example bytes.
+    /// Storing the attributes interleaved in one buffer improves the memory locality compared to one buffer per attribute, for example when rendering millions of points.
+    ///
+    /// # Panic
+    /// Will panic if the attribute is not defined in the shader code or not used.
+    /// In the latter case the variable is removed by the shader compiler.
+    ///
+    pub fn use_interleaved_vertex_attribute(
+        &self,
+        name: &str,
+        buffer: &VertexBuffer,
+        layout: VertexAttributeLayout,
+    ) {
+        if buffer.count() > 0 {
+            buffer.bind();
+            let loc = self.location(name);
+            let component_type = layout.component_type;
+            unsafe {
+                self.context
+                    .bind_vertex_array(Some(self.context.active_vao()));
+                self.context.enable_vertex_attrib_array(loc);
+                if component_type.is_integer() {
+                    self.context.vertex_attrib_pointer_i32(
+                        loc,
+                        layout.components as i32,
+                        component_type.data_type(),
+                        layout.stride as i32,
+                        layout.offset as i32,
+                    );
+                } else {
+                    self.context.vertex_attrib_pointer_f32(
+                        loc,
+                        layout.components as i32,
+                        component_type.data_type(),
+                        component_type.is_normalized(),
+                        layout.stride as i32,
+                        layout.offset as i32,
+                    );
+                }
+                self.context.vertex_attrib_divisor(loc, 0);
+            }
+            buffer.unbind();
+            self.unuse_program();
+        }
+    }
+
     ///
     /// Uses the given [InstanceBuffer] data in this shader program and associates it with the given named variable.
     /// Each value in the buffer is used when rendering one instance using the [Program::draw_arrays_instanced] or [Program::draw_elements_instanced] methods.
@@ -388,7 +437,8 @@ impl Program {
             buffer.bind();
             let loc = self.location(name);
             unsafe {
-                self.context.bind_vertex_array(Some(self.context.vao));
+                self.context
+                    .bind_vertex_array(Some(self.context.active_vao()));
                 self.context.enable_vertex_attrib_array(loc);
                 if !buffer.normalized()
                     && (buffer.data_type() == crate::context::UNSIGNED_BYTE
@@ -429,12 +479,28 @@ impl Program {
     /// If you want to use an [ElementBuffer], see [Program::draw_elements].
     ///
     pub fn draw_arrays(&self, render_states: RenderStates, viewport: Viewport, count: u32) {
+        self.draw_subset_of_arrays(render_states, viewport, 0, count)
+    }
+
+    ///
+    /// Same as [Program::draw_arrays] except that it starts at the vertex with number `first` in the vertex buffers and uses `count` vertices.
+    ///
+    pub fn draw_subset_of_arrays(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        first: u32,
+        count: u32,
+    ) {
         self.context.set_viewport(viewport);
         self.context.set_render_states(render_states);
         self.use_program();
         unsafe {
-            self.context
-                .draw_arrays(render_states.draw_primitive.into(), 0, count as i32);
+            self.context.draw_arrays(
+                render_states.draw_primitive.into(),
+                first as i32,
+                count as i32,
+            );
             for location in self.attributes.values() {
                 self.context.disable_vertex_attrib_array(*location);
             }
@@ -505,7 +571,8 @@ impl Program {
     }
 
     ///
-    /// Draws a subset of the triangles defined by the given [ElementBuffer] with the given render states and viewport using this shader program.
+    /// Draws a subset of the triangles defined by the given [ElementBuffer] with the given render states and viewport using this shader program,
+    /// starting at the index with number `first` in the element buffer and using `count` indices.
     /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
     /// If you do not want to use an [ElementBuffer], see [Program::draw_arrays].
     ///
@@ -526,7 +593,7 @@ impl Program {
                 render_states.draw_primitive.into(),
                 count as i32,
                 element_buffer.data_type(),
-                first as i32,
+                (first * element_buffer.data_size()) as i32,
             );
             element_buffer.unbind();
 
@@ -586,7 +653,7 @@ impl Program {
                 render_states.draw_primitive.into(),
                 count as i32,
                 element_buffer.data_type(),
-                first as i32,
+                (first * element_buffer.data_size()) as i32,
                 instance_count as i32,
             );
             element_buffer.unbind();
@@ -603,6 +670,88 @@ impl Program {
             .expect("Unexpected rendering error occured")
     }
 
+    ///
+    /// Draws all of the draw calls in the given [DrawIndirectBuffer], each of which draws a subset of the triangles defined by the given [ElementBuffer],
+    /// with the given render states and viewport using this shader program.
+    /// The draw calls are submitted in one call if [Capabilities::multi_draw_indirect] is supported,
+    /// otherwise one call per draw call is used, reading the parameters from the buffer if [Capabilities::draw_indirect] is supported.
+    ///
+    /// # Panic
+    /// Will panic if [Capabilities::draw_indirect] is not supported and a draw call has a non-zero `base_vertex` or `base_instance`.
+    ///
+    pub fn multi_draw_elements_indirect(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        indirect_buffer: &DrawIndirectBuffer,
+    ) {
+        if indirect_buffer.count() == 0 {
+            return;
+        }
+        self.context.set_viewport(viewport);
+        self.context.set_render_states(render_states);
+        self.use_program();
+        element_buffer.bind();
+        let mode = render_states.draw_primitive.into();
+        let capabilities = self.context.capabilities();
+        unsafe {
+            if capabilities.draw_indirect {
+                indirect_buffer.bind();
+                if capabilities.multi_draw_indirect {
+                    self.context.multi_draw_elements_indirect_offset(
+                        mode,
+                        element_buffer.data_type(),
+                        0,
+                        indirect_buffer.count() as i32,
+                        0,
+                    );
+                } else {
+                    let stride = std::mem::size_of::<DrawElementsIndirectCommand>();
+                    for i in 0..indirect_buffer.count() {
+                        self.context.draw_elements_indirect_offset(
+                            mode,
+                            element_buffer.data_type(),
+                            (i * stride) as i32,
+                        );
+                    }
+                }
+                indirect_buffer.unbind();
+            } else {
+                for command in indirect_buffer.commands() {
+                    if command.base_vertex != 0 || command.base_instance != 0 {
+                        panic!("drawing with a base vertex or base instance requires indirect drawing which is not supported");
+                    }
+                    self.context.draw_elements_instanced(
+                        mode,
+                        command.count as i32,
+                        element_buffer.data_type(),
+                        (command.first_index * element_buffer.data_size()) as i32,
+                        command.instance_count as i32,
+                    );
+                }
+            }
+            element_buffer.unbind();
+            for location in self.attributes.values() {
+                self.context.disable_vertex_attrib_array(*location);
+            }
+            self.context.bind_vertex_array(None);
+        }
+        self.unuse_program();
+
+        #[cfg(debug_assertions)]
+        self.context
+            .error_check()
+            .expect("Unexpected rendering error occured")
+    }
+
+    ///
+    /// Returns the context this program is created with.
+    ///
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
     ///
     /// Returns true if this program uses the uniform with the given name.
     ///
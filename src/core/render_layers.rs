@@ -0,0 +1,80 @@
+///
+/// A set of up to 32 render layers, used to select which objects are visible in a view, see [RenderTarget::with_visible_layers](crate::core::RenderTarget::with_visible_layers).
+/// An object is visible if at least one of its layers, see [Geometry::layers](crate::renderer::Geometry::layers), is also one of the visible layers of the render target.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RenderLayers(pub u32);
+
+impl RenderLayers {
+    ///
+    /// All layers.
+    ///
+    pub const ALL: Self = Self(u32::MAX);
+
+    ///
+    /// No layers.
+    ///
+    pub const NONE: Self = Self(0);
+
+    ///
+    /// Returns the mask containing only the layer with the given index, which must be less than 32.
+    ///
+    pub const fn layer(index: u32) -> Self {
+        Self(1 << index)
+    }
+
+    ///
+    /// Returns whether or not this mask contains the layer with the given index.
+    ///
+    pub fn contains(&self, index: u32) -> bool {
+        self.intersects(Self::layer(index))
+    }
+
+    ///
+    /// Returns whether or not this mask and the other mask have at least one layer in common.
+    ///
+    pub fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    ///
+    /// Returns this mask with the layer with the given index added.
+    ///
+    pub fn with(self, index: u32) -> Self {
+        self | Self::layer(index)
+    }
+
+    ///
+    /// Returns this mask with the layer with the given index removed.
+    ///
+    pub fn without(self, index: u32) -> Self {
+        Self(self.0 & !Self::layer(index).0)
+    }
+}
+
+impl Default for RenderLayers {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl std::ops::BitOr for RenderLayers {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitAnd for RenderLayers {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl std::ops::Not for RenderLayers {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
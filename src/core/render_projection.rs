@@ -0,0 +1,51 @@
+use crate::core::*;
+
+///
+/// Extends the [Camera] with the projection which is used when rendering with the camera,
+/// which is different from [Camera::projection] when reversed depth is enabled, see [Context::set_reverse_z].
+///
+/// The projection returned from [Camera::projection] maps the near plane to a clip space depth of -1 and the far plane to 1
+/// and is still the one to use when computing positions on the CPU or when unprojecting depth values in the usual convention, for example those returned by `sample_depth` in a shader.
+/// The projection returned from [RenderProjection::render_projection] is the one to use when computing `gl_Position` in a vertex shader.
+///
+pub trait RenderProjection {
+    ///
+    /// Returns the projection matrix which maps the near plane to a clip space depth of 1 and the far plane to 0.
+    ///
+    fn reversed_z_projection(&self) -> Mat4;
+
+    ///
+    /// Returns the projection matrix to use when rendering with the given context,
+    /// which is the reversed projection if the clip space depth is in the range `[0..1]`, see [Context::clip_depth_zero_to_one], and otherwise the usual projection.
+    ///
+    fn render_projection(&self, context: &Context) -> Mat4;
+
+    ///
+    /// Returns the render projection matrix multiplied by the view matrix, see [RenderProjection::render_projection].
+    ///
+    fn render_view_projection(&self, context: &Context) -> Mat4;
+}
+
+impl RenderProjection for Camera {
+    fn reversed_z_projection(&self) -> Mat4 {
+        // The clip space depth is (w - z) / 2 where z is the clip space depth from the usual projection,
+        // which maps the near plane to 1 and the far plane to 0
+        let mut projection = self.projection();
+        for column in 0..4 {
+            projection[column][2] = 0.5 * (projection[column][3] - projection[column][2]);
+        }
+        projection
+    }
+
+    fn render_projection(&self, context: &Context) -> Mat4 {
+        if context.clip_depth_zero_to_one() {
+            self.reversed_z_projection()
+        } else {
+            self.projection()
+        }
+    }
+
+    fn render_view_projection(&self, context: &Context) -> Mat4 {
+        self.render_projection(context) * self.view()
+    }
+}
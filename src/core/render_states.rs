@@ -2,6 +2,8 @@
 //! Definitions of the input state needed for any draw call.
 //!
 
+use crate::core::*;
+
 ///
 /// A set of render specific states that has to be specified at each render call.
 ///
@@ -27,6 +29,12 @@ pub struct RenderStates {
     ///
     pub blend: Blend,
 
+    ///
+    /// The constant color used by the [BlendMultiplierType::ConstantColor] and [BlendMultiplierType::ConstantAlpha] multipliers (and their inverse) when blending,
+    /// for example to fade an object in and out without changing its material.
+    ///
+    pub blend_color: Color,
+
     ///
     /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
     ///
@@ -36,6 +44,27 @@ pub struct RenderStates {
     /// Defines which primitive to use in a draw call
     ///
     pub draw_primitive: DrawPrimitive,
+
+    ///
+    /// Defines the stencil test and how the stencil buffer is updated in a render call.
+    /// The stencil test determines whether or not a fragment from the current render call should be discarded
+    /// when comparing a reference value with the value in the stencil buffer, which is used for masking, see [Stencil].
+    ///
+    pub stencil: Stencil,
+
+    ///
+    /// Defines an offset added to the depth of the triangles in a render call before the depth test, if any.
+    /// This is used to avoid z-fighting between coplanar triangles, for example decals and overlays on a surface or the geometry rendered into a shadow map.
+    ///
+    pub polygon_offset: Option<PolygonOffset>,
+
+    ///
+    /// Defines whether the depth of the triangles is clamped to the depth range instead of clipping the triangles at the near and far plane,
+    /// for example to avoid holes in shadow casters in front of the near plane of a shadow map or in geometry close to the camera.
+    ///
+    /// **Note:** Depth clamping is ignored if not supported, see [Capabilities::depth_clamp].
+    ///
+    pub depth_clamp: bool,
 }
 
 ///
@@ -78,12 +107,183 @@ pub enum DepthTest {
     Always,
 }
 
+impl DepthTest {
+    ///
+    /// Returns the depth test which gives the same result when the depth values are reversed, for example [DepthTest::Greater] instead of [DepthTest::Less],
+    /// see [Context::set_reverse_z](crate::core::Context::set_reverse_z).
+    ///
+    pub fn reversed(&self) -> Self {
+        match self {
+            Self::Less => Self::Greater,
+            Self::LessOrEqual => Self::GreaterOrEqual,
+            Self::Greater => Self::Less,
+            Self::GreaterOrEqual => Self::LessOrEqual,
+            _ => *self,
+        }
+    }
+}
+
 impl Default for DepthTest {
     fn default() -> Self {
         Self::Less
     }
 }
 
+///
+/// Defines the stencil test and how the stencil buffer is updated in a render call.
+/// When enabled, the reference value is compared with the value in the stencil buffer using the test, where both values are masked by the read mask,
+/// and the fragment is discarded if the test fails. Then the stencil buffer is updated with the operation which corresponds to the result of the stencil and depth test,
+/// where only the bits in the write mask are written.
+///
+/// **Note:** The stencil test is only performed if the render target has a stencil buffer,
+/// for example a [DepthTexture2D] with the [f24s8] data type or a window with a stencil buffer (see `SurfaceSettings::stencil_buffer`).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Stencil {
+    /// The stencil test is enabled.
+    Enabled {
+        /// The test which compares the reference value with the value in the stencil buffer.
+        test: StencilTest,
+        /// The reference value used in the test and written by [StencilOperation::Replace].
+        reference: u8,
+        /// The bits of the reference value and the value in the stencil buffer which are compared in the test.
+        read_mask: u8,
+        /// The bits of the stencil buffer which are written.
+        write_mask: u8,
+        /// The operation applied to the stencil buffer if the stencil test fails.
+        stencil_fail: StencilOperation,
+        /// The operation applied to the stencil buffer if the stencil test passes and the depth test fails.
+        depth_fail: StencilOperation,
+        /// The operation applied to the stencil buffer if both the stencil and depth test passes.
+        pass: StencilOperation,
+    },
+    /// The stencil test is disabled and the stencil buffer is not changed.
+    Disabled,
+}
+
+impl Stencil {
+    ///
+    /// Writes the given reference value to the stencil buffer for all fragments which passes the depth test, for example to mark a region as the mask.
+    ///
+    pub const fn write(reference: u8) -> Self {
+        Self::Enabled {
+            test: StencilTest::Always,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0xFF,
+            stencil_fail: StencilOperation::Keep,
+            depth_fail: StencilOperation::Keep,
+            pass: StencilOperation::Replace,
+        }
+    }
+
+    ///
+    /// Only renders the fragments where the value in the stencil buffer is equal to the given reference value, for example inside a mask, without changing the stencil buffer.
+    ///
+    pub const fn equal(reference: u8) -> Self {
+        Self::Enabled {
+            test: StencilTest::Equal,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: StencilOperation::Keep,
+            depth_fail: StencilOperation::Keep,
+            pass: StencilOperation::Keep,
+        }
+    }
+
+    ///
+    /// Only renders the fragments where the value in the stencil buffer is not equal to the given reference value, for example outside a mask, without changing the stencil buffer.
+    ///
+    pub const fn not_equal(reference: u8) -> Self {
+        Self::Enabled {
+            test: StencilTest::NotEqual,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: StencilOperation::Keep,
+            depth_fail: StencilOperation::Keep,
+            pass: StencilOperation::Keep,
+        }
+    }
+}
+
+impl Default for Stencil {
+    fn default() -> Self {
+        Self::Disabled
+    }
+}
+
+///
+/// The comparison between the reference value and the value in the stencil buffer in a [Stencil] test, for example [StencilTest::Less] passes if the reference value is less than the value in the stencil buffer.
+///
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilTest {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+///
+/// The operation applied to the value in the stencil buffer after a [Stencil] test.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilOperation {
+    /// Keeps the current value.
+    Keep,
+    /// Sets the value to zero.
+    Zero,
+    /// Sets the value to the reference value.
+    Replace,
+    /// Increments the value, clamped to the maximum value.
+    Increment,
+    /// Increments the value and wraps to zero after the maximum value.
+    IncrementWrap,
+    /// Decrements the value, clamped to zero.
+    Decrement,
+    /// Decrements the value and wraps to the maximum value after zero.
+    DecrementWrap,
+    /// Inverts the bits of the value.
+    Invert,
+}
+
+///
+/// An offset added to the depth of each fragment before the depth test, see [RenderStates::polygon_offset].
+/// The offset is `factor * slope + units * r`, where `slope` is the depth slope of the triangle and `r` is the smallest resolvable difference in depth,
+/// so positive values move the triangles away from the camera and negative values towards the camera.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PolygonOffset {
+    /// The scale of the variable offset which depends on the depth slope of the triangle.
+    pub factor: f32,
+    /// The scale of the constant offset in units of the smallest resolvable difference in depth.
+    pub units: f32,
+}
+
+impl PolygonOffset {
+    ///
+    /// Moves the triangles slightly towards the camera, for example to render decals and overlays on top of a coplanar surface.
+    ///
+    pub const TOWARDS_CAMERA: Self = Self {
+        factor: -1.0,
+        units: -1.0,
+    };
+
+    ///
+    /// Moves the triangles away from the camera, for example when rendering shadow casters into a shadow map to avoid shadow acne.
+    ///
+    pub const AWAY_FROM_CAMERA: Self = Self {
+        factor: 2.0,
+        units: 4.0,
+    };
+}
+
 ///
 /// Defines which channels (red, green, blue, alpha and depth) to write to in a render call.
 ///
@@ -206,6 +406,80 @@ impl Blend {
         rgb_equation: BlendEquationType::Add,
         alpha_equation: BlendEquationType::Add,
     };
+
+    ///
+    /// Transparency blending where the output color of the render call is already multiplied by its alpha value,
+    /// which gives correct results when blending colors that have been filtered or blended before, for example in textures with premultiplied alpha.
+    ///
+    pub const PREMULTIPLIED_ALPHA: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        destination_alpha_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Multiplies the color of the render target with the output color of the render call, which darkens the render target, for example for tinted glass or shadows.
+    ///
+    pub const MULTIPLY: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::DstColor,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::Zero,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Adds the output color of the render call, weighted by its alpha value, to the color of the render target without changing the alpha value of the render target,
+    /// which brightens the render target, for example for glow, fire or light beams.
+    ///
+    pub const ADDITIVE_GLOW: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::SrcAlpha,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Blends the output color of the render call with the color of the render target using the constant [RenderStates::blend_color],
+    /// where the alpha value of the constant color is the weight of the output color, for example to cross-fade between two renderings.
+    ///
+    pub const CONSTANT_ALPHA: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::ConstantAlpha,
+        source_alpha_multiplier: BlendMultiplierType::ConstantAlpha,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusConstantAlpha,
+        destination_alpha_multiplier: BlendMultiplierType::OneMinusConstantAlpha,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Returns whether or not the blend state uses the second color output of the fragment shader, see [BlendMultiplierType::Src1Color].
+    ///
+    pub fn is_dual_source(&self) -> bool {
+        match self {
+            Self::Enabled {
+                source_rgb_multiplier,
+                source_alpha_multiplier,
+                destination_rgb_multiplier,
+                destination_alpha_multiplier,
+                ..
+            } => [
+                source_rgb_multiplier,
+                source_alpha_multiplier,
+                destination_rgb_multiplier,
+                destination_alpha_multiplier,
+            ]
+            .iter()
+            .any(|m| m.is_dual_source()),
+            Self::Disabled => false,
+        }
+    }
 }
 
 impl Default for Blend {
@@ -217,6 +491,11 @@ impl Default for Blend {
 ///
 /// Value multiplied with the source or target color or alpha value in [Blend].
 ///
+/// The constant multipliers use the [RenderStates::blend_color].
+/// The `Src1` multipliers use the second color output of the fragment shader, which is declared as `layout (location = 0, index = 1) out vec4 outColor1;`,
+/// to blend each color channel with a different factor, for example for subpixel text rendering or colored transparency.
+/// This is called dual-source blending and is only supported if [Capabilities::dual_source_blending] is true.
+///
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum BlendMultiplierType {
@@ -231,6 +510,26 @@ pub enum BlendMultiplierType {
     DstAlpha,
     OneMinusDstAlpha,
     SrcAlphaSaturate,
+    ConstantColor,
+    OneMinusConstantColor,
+    ConstantAlpha,
+    OneMinusConstantAlpha,
+    Src1Color,
+    OneMinusSrc1Color,
+    Src1Alpha,
+    OneMinusSrc1Alpha,
+}
+
+impl BlendMultiplierType {
+    ///
+    /// Returns whether or not this multiplier uses the second color output of the fragment shader, which requires dual-source blending.
+    ///
+    pub fn is_dual_source(&self) -> bool {
+        matches!(
+            self,
+            Self::Src1Color | Self::OneMinusSrc1Color | Self::Src1Alpha | Self::OneMinusSrc1Alpha
+        )
+    }
 }
 
 ///
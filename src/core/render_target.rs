@@ -26,6 +26,10 @@ mod depth_target_multisample;
 #[doc(inline)]
 pub use depth_target_multisample::*;
 
+mod pixel_readback;
+#[doc(inline)]
+pub use pixel_readback::*;
+
 use crate::core::*;
 
 use crate::context::Framebuffer;
@@ -37,9 +41,10 @@ use crate::context::Framebuffer;
 ///
 pub struct RenderTarget<'a> {
     id: Option<Framebuffer>,
-    color: Option<ColorTarget<'a>>,
+    colors: Vec<ColorTarget<'a>>,
     depth: Option<DepthTarget<'a>>,
     pub(crate) context: Context,
+    pub(crate) visible_layers: RenderLayers,
     width: u32,
     height: u32,
 }
@@ -52,8 +57,9 @@ impl<'a> RenderTarget<'a> {
     pub fn screen(context: &Context, width: u32, height: u32) -> Self {
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             id: None,
-            color: None,
+            colors: Vec::new(),
             depth: None,
             width,
             height,
@@ -68,8 +74,41 @@ impl<'a> RenderTarget<'a> {
         let height = color.height();
         Self {
             context: color.context.clone(),
+            visible_layers: RenderLayers::ALL,
             id: Some(new_framebuffer(&color.context)),
-            color: Some(color),
+            colors: vec![color],
+            depth: Some(depth),
+            width,
+            height,
+        }
+    }
+
+    ///
+    /// Constructs a new render target that enables rendering into all of the given [ColorTarget]s and the [DepthTarget] at the same time (multiple render targets).
+    /// The color attachments are numbered in the order of the color targets, where a color target with several layers or sides uses one attachment for each layer or side,
+    /// and the output of a fragment shader with `layout (location = n)` is written to the n'th attachment, see [Material::color_outputs](crate::renderer::Material::color_outputs).
+    /// All color targets must have the same size. Use [RenderTarget::clear_attachments] to clear each attachment to a different value.
+    ///
+    /// # Panic
+    /// Will panic if no color targets are given or if the color targets do not have the same size.
+    ///
+    pub fn new_mrt(colors: &[ColorTarget<'a>], depth: DepthTarget<'a>) -> Self {
+        let first = colors
+            .first()
+            .expect("a render target with multiple render targets needs at least one color target");
+        let width = first.width();
+        let height = first.height();
+        if colors
+            .iter()
+            .any(|color| color.width() != width || color.height() != height)
+        {
+            panic!("all color targets of a render target must have the same size");
+        }
+        Self {
+            context: first.context.clone(),
+            visible_layers: RenderLayers::ALL,
+            id: Some(new_framebuffer(&first.context)),
+            colors: colors.to_vec(),
             depth: Some(depth),
             width,
             height,
@@ -103,6 +142,54 @@ impl<'a> RenderTarget<'a> {
         self
     }
 
+    ///
+    /// Clears the color of this render target to the given value.
+    /// Use this instead of [RenderTarget::clear] when the color texture has an unsigned integer format, for example a [Texture2D] with `u32` data type.
+    ///
+    pub fn clear_color_u32(&self, value: [u32; 4]) -> &Self {
+        self.context.set_scissor(self.scissor_box());
+        self.bind(crate::context::DRAW_FRAMEBUFFER);
+        self.context.set_write_mask(WriteMask::COLOR);
+        unsafe {
+            self.context
+                .clear_buffer_u32_slice(crate::context::COLOR, 0, &value);
+        }
+        self
+    }
+
+    ///
+    /// Clears each color attachment of this render target to the corresponding value, where `None` means that the attachment is not cleared,
+    /// and the depth to the given value if specified. Use this to clear the attachments of a render target constructed with [RenderTarget::new_mrt] to different values.
+    ///
+    pub fn clear_attachments(&self, colors: &[Option<Vec4>], depth: Option<f32>) -> &Self {
+        self.context.set_scissor(self.scissor_box());
+        self.bind(crate::context::DRAW_FRAMEBUFFER);
+        self.context.set_write_mask(WriteMask::default());
+        for (attachment, color) in colors.iter().enumerate() {
+            if let Some(color) = color {
+                unsafe {
+                    self.context.clear_buffer_f32_slice(
+                        crate::context::COLOR,
+                        attachment as u32,
+                        &[color.x, color.y, color.z, color.w],
+                    );
+                }
+            }
+        }
+        if let Some(depth) = depth {
+            unsafe {
+                let depth = if self.context.reverse_z() {
+                    1.0 - depth
+                } else {
+                    depth
+                };
+                self.context
+                    .clear_buffer_f32_slice(crate::context::DEPTH, 0, &[depth]);
+            }
+        }
+        self
+    }
+
     ///
     /// Writes whatever rendered in the `render` closure into this render target.
     ///
@@ -117,7 +204,7 @@ impl<'a> RenderTarget<'a> {
         self.context.set_scissor(scissor_box);
         self.bind(crate::context::DRAW_FRAMEBUFFER);
         render();
-        if let Some(ref color) = self.color {
+        for color in self.colors.iter() {
             color.generate_mip_maps();
         }
         self
@@ -140,7 +227,7 @@ impl<'a> RenderTarget<'a> {
     /// **Note:** On web, the data format needs to match the data format of the color texture.
     ///
     pub fn read_color_partially<T: TextureDataType>(&self, scissor_box: ScissorBox) -> Vec<T> {
-        if self.id.is_some() && self.color.is_none() {
+        if self.id.is_some() && self.colors.is_empty() {
             panic!("cannot read color from a render target without a color target");
         }
         self.bind(crate::context::DRAW_FRAMEBUFFER);
@@ -172,6 +259,100 @@ impl<'a> RenderTarget<'a> {
         pixels
     }
 
+    ///
+    /// Starts reading the colors of the pixels in this render target without waiting for the rendering to finish and returns a handle to the result,
+    /// which can be polled each frame with [PixelReadback::is_ready] and read when ready, usually in one of the next frames, to avoid stalling the pipeline.
+    /// The number of channels per pixel and the data format for each channel is specified by the generic parameter.
+    ///
+    /// **Note:** On web, the data format needs to match the data format of the color texture.
+    ///
+    pub fn read_color_async<T: TextureDataType>(&self) -> PixelReadback<T> {
+        self.read_color_partially_async(self.scissor_box())
+    }
+
+    ///
+    /// Same as [RenderTarget::read_color_async] except that only the pixels inside the given scissor box are read.
+    ///
+    pub fn read_color_partially_async<T: TextureDataType>(
+        &self,
+        scissor_box: ScissorBox,
+    ) -> PixelReadback<T> {
+        self.read_color_async_with_buffer(scissor_box, None)
+    }
+
+    ///
+    /// Same as [RenderTarget::read_color_partially_async] except that the given pixel buffer is used, if any, instead of creating a new one.
+    ///
+    pub(crate) fn read_color_async_with_buffer<T: TextureDataType>(
+        &self,
+        scissor_box: ScissorBox,
+        buffer: Option<crate::context::Buffer>,
+    ) -> PixelReadback<T> {
+        if self.id.is_some() && self.colors.is_empty() {
+            panic!("cannot read color from a render target without a color target");
+        }
+        self.bind(crate::context::DRAW_FRAMEBUFFER);
+        self.bind(crate::context::READ_FRAMEBUFFER);
+        let mut data_size = std::mem::size_of::<T>();
+        // On web, the format needs to be RGBA if the data type is byte.
+        if data_size / T::size() as usize == 1 {
+            data_size *= 4 / T::size() as usize
+        }
+        PixelReadback::new(
+            &self.context,
+            buffer,
+            scissor_box,
+            data_size,
+            |context| unsafe {
+                context.read_pixels(
+                    scissor_box.x,
+                    scissor_box.y,
+                    scissor_box.width as i32,
+                    scissor_box.height as i32,
+                    format_from_data_type::<T>(),
+                    T::data_type(),
+                    crate::context::PixelPackData::BufferOffset(0),
+                );
+            },
+        )
+    }
+
+    ///
+    /// Starts reading the depth values in this render target without waiting for the rendering to finish, see [RenderTarget::read_color_async].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_depth_async(&self) -> PixelReadback<f32> {
+        self.read_depth_partially_async(self.scissor_box())
+    }
+
+    ///
+    /// Same as [RenderTarget::read_depth_async] except that only the depth values inside the given scissor box are read.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_depth_partially_async(&self, scissor_box: ScissorBox) -> PixelReadback<f32> {
+        if self.id.is_some() && self.depth.is_none() {
+            panic!("cannot read depth from a render target without a depth target");
+        }
+        self.bind(crate::context::DRAW_FRAMEBUFFER);
+        self.bind(crate::context::READ_FRAMEBUFFER);
+        let mut readback =
+            PixelReadback::new(&self.context, None, scissor_box, 4, |context| unsafe {
+                context.read_pixels(
+                    scissor_box.x,
+                    scissor_box.y,
+                    scissor_box.width as i32,
+                    scissor_box.height as i32,
+                    crate::context::DEPTH_COMPONENT,
+                    crate::context::FLOAT,
+                    crate::context::PixelPackData::BufferOffset(0),
+                );
+            });
+        if self.context.reverse_z() {
+            readback.map_pixel = Some(|depth: &mut f32| *depth = 1.0 - *depth);
+        }
+        readback
+    }
+
     ///
     /// Returns the depth values in this render target.
     ///
@@ -202,7 +383,11 @@ impl<'a> RenderTarget<'a> {
                 crate::context::PixelPackData::Slice(&mut pixels),
             );
         }
-        from_byte_slice(&pixels).to_vec()
+        let mut depths: Vec<f32> = from_byte_slice(&pixels).to_vec();
+        if self.context.reverse_z() {
+            depths.iter_mut().for_each(|depth| *depth = 1.0 - *depth);
+        }
+        depths
     }
 
     ///
@@ -245,7 +430,11 @@ impl<'a> RenderTarget<'a> {
                 void main()
                 {{
                     color = sample_color(uvs);
+                #ifdef REVERSE_Z
+                    gl_FragDepth = 1.0 - sample_depth(uvs);
+                #else
                     gl_FragDepth = sample_depth(uvs);
+                #endif
                 }}",
                 color_texture.fragment_shader_source(),
                 depth_texture.fragment_shader_source()
@@ -341,7 +530,11 @@ impl<'a> RenderTarget<'a> {
                     in vec2 uvs;
                     void main()
                     {{
+                    #ifdef REVERSE_Z
+                        gl_FragDepth = 1.0 - sample_depth(uvs);
+                    #else
                         gl_FragDepth = sample_depth(uvs);
+                    #endif
                     }}",
                 depth_texture.fragment_shader_source(),
             );
@@ -373,9 +566,10 @@ impl<'a> RenderTarget<'a> {
     ) -> Self {
         Self {
             id: Some(framebuffer),
-            color: None,
+            colors: Vec::new(),
             depth: None,
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             width,
             height,
         }
@@ -392,8 +586,8 @@ impl<'a> RenderTarget<'a> {
     pub(in crate::core) fn blit_to(&self, target: &RenderTarget) {
         self.bind(crate::context::DRAW_FRAMEBUFFER);
         target.bind(crate::context::DRAW_FRAMEBUFFER);
-        let target_is_screen = target.color.is_none() && target.depth.is_none();
-        let mask = if self.color.is_some() && (target.color.is_some() || target_is_screen) {
+        let target_is_screen = target.colors.is_empty() && target.depth.is_none();
+        let mask = if !self.colors.is_empty() && (!target.colors.is_empty() || target_is_screen) {
             let mut mask = crate::context::COLOR_BUFFER_BIT;
             if self.depth.is_some() && (target.depth.is_some() || target_is_screen) {
                 mask |= crate::context::DEPTH_BUFFER_BIT;
@@ -428,8 +622,9 @@ impl<'a> RenderTarget<'a> {
         let height = color.height();
         Self {
             context: color.context.clone(),
+            visible_layers: color.visible_layers,
             id: Some(new_framebuffer(&color.context)),
-            color: Some(color),
+            colors: vec![color],
             depth: None,
             width,
             height,
@@ -441,9 +636,10 @@ impl<'a> RenderTarget<'a> {
         let height = depth.height();
         Self {
             context: depth.context.clone(),
+            visible_layers: depth.visible_layers,
             id: Some(new_framebuffer(&depth.context)),
             depth: Some(depth),
-            color: None,
+            colors: Vec::new(),
             width,
             height,
         }
@@ -453,8 +649,18 @@ impl<'a> RenderTarget<'a> {
         unsafe {
             self.context.bind_framebuffer(target, self.id);
         }
-        if let Some(ref color) = self.color {
-            color.bind(&self.context);
+        if !self.colors.is_empty() {
+            let mut attachment_count = 0;
+            for color in self.colors.iter() {
+                attachment_count += color.bind(attachment_count);
+            }
+            unsafe {
+                self.context.draw_buffers(
+                    &(0..attachment_count)
+                        .map(|i| crate::context::COLOR_ATTACHMENT0 + i)
+                        .collect::<Vec<u32>>(),
+                );
+            }
         }
         if let Some(ref depth) = self.depth {
             depth.bind();
@@ -11,6 +11,7 @@ use super::*;
 #[derive(Clone)]
 pub struct ColorTarget<'a> {
     pub(crate) context: Context,
+    pub(crate) visible_layers: RenderLayers,
     mip_level: Option<u32>,
     target: Option<ColorTexture<'a>>,
     multisample_target: Option<&'a Texture2DMultisample>,
@@ -24,6 +25,7 @@ impl<'a> ColorTarget<'a> {
     ) -> Self {
         ColorTarget {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             mip_level,
             target: Some(ColorTexture::Single(texture)),
             multisample_target: None,
@@ -38,6 +40,7 @@ impl<'a> ColorTarget<'a> {
     ) -> Self {
         ColorTarget {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             mip_level,
             target: Some(ColorTexture::CubeMap { texture, sides }),
             multisample_target: None,
@@ -52,6 +55,7 @@ impl<'a> ColorTarget<'a> {
     ) -> Self {
         ColorTarget {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             mip_level,
             target: Some(ColorTexture::Array { texture, layers }),
             multisample_target: None,
@@ -64,6 +68,7 @@ impl<'a> ColorTarget<'a> {
     ) -> Self {
         ColorTarget {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             mip_level: None,
             target: None,
             multisample_target: Some(texture),
@@ -85,6 +90,7 @@ impl<'a> ColorTarget<'a> {
             scissor_box,
             ClearState {
                 depth: None,
+                stencil: None,
                 ..clear_state
             },
         );
@@ -225,50 +231,41 @@ impl<'a> ColorTarget<'a> {
         }
     }
 
-    pub(super) fn bind(&self, context: &Context) {
+    // Binds the textures of this color target to the color attachments starting at the given attachment and returns the number of attachments used
+    pub(super) fn bind(&self, first_attachment: u32) -> u32 {
         if let Some(target) = self.target {
             match target {
-                ColorTexture::Single(texture) => unsafe {
-                    context.draw_buffers(&[crate::context::COLOR_ATTACHMENT0]);
-                    texture.bind_as_color_target(0, self.mip_level.unwrap_or(0));
-                },
-                ColorTexture::Array { texture, layers } => unsafe {
-                    context.draw_buffers(
-                        &(0..layers.len())
-                            .map(|i| crate::context::COLOR_ATTACHMENT0 + i as u32)
-                            .collect::<Vec<u32>>(),
-                    );
+                ColorTexture::Single(texture) => {
+                    texture.bind_as_color_target(first_attachment, self.mip_level.unwrap_or(0));
+                    1
+                }
+                ColorTexture::Array { texture, layers } => {
                     (0..layers.len()).for_each(|channel| {
                         texture.bind_as_color_target(
                             layers[channel],
-                            channel as u32,
+                            first_attachment + channel as u32,
                             self.mip_level.unwrap_or(0),
                         );
                     });
-                },
-                ColorTexture::CubeMap { texture, sides } => unsafe {
-                    context.draw_buffers(
-                        &(0..sides.len())
-                            .map(|i| crate::context::COLOR_ATTACHMENT0 + i as u32)
-                            .collect::<Vec<u32>>(),
-                    );
+                    layers.len() as u32
+                }
+                ColorTexture::CubeMap { texture, sides } => {
                     (0..sides.len()).for_each(|channel| {
                         texture.bind_as_color_target(
                             sides[channel],
-                            channel as u32,
+                            first_attachment + channel as u32,
                             self.mip_level.unwrap_or(0),
                         );
                     });
-                },
+                    sides.len() as u32
+                }
             }
         } else {
-            unsafe {
-                context.draw_buffers(&[crate::context::COLOR_ATTACHMENT0]);
-                self.multisample_target
-                    .as_ref()
-                    .unwrap()
-                    .bind_as_color_target(0);
-            }
+            self.multisample_target
+                .as_ref()
+                .unwrap()
+                .bind_as_color_target(first_attachment);
+            1
         }
     }
 }
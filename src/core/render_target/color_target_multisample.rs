@@ -10,6 +10,7 @@ use crate::core::*;
 ///
 pub struct ColorTargetMultisample<C: TextureDataType> {
     pub(crate) context: Context,
+    pub(crate) visible_layers: RenderLayers,
     color: Texture2DMultisample,
     _c: std::marker::PhantomData<C>,
 }
@@ -24,6 +25,7 @@ impl<C: TextureDataType> ColorTargetMultisample<C> {
         super::multisample_sanity_check(context, number_of_samples);
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             color: Texture2DMultisample::new::<C>(context, width, height, number_of_samples),
             _c: std::marker::PhantomData,
         }
@@ -47,6 +49,7 @@ impl<C: TextureDataType> ColorTargetMultisample<C> {
             scissor_box,
             ClearState {
                 depth: None,
+                stencil: None,
                 ..clear_state
             },
         );
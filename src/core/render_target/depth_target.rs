@@ -9,6 +9,7 @@ use super::*;
 #[derive(Clone)]
 pub struct DepthTarget<'a> {
     pub(crate) context: Context,
+    pub(crate) visible_layers: RenderLayers,
     target: Option<DepthTexture<'a>>,
     multisample_target: Option<&'a DepthTexture2DMultisample>,
 }
@@ -17,6 +18,7 @@ impl<'a> DepthTarget<'a> {
     pub(in crate::core) fn new_texture2d(context: &Context, texture: &'a DepthTexture2D) -> Self {
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             target: Some(DepthTexture::Single(texture)),
             multisample_target: None,
         }
@@ -29,6 +31,7 @@ impl<'a> DepthTarget<'a> {
     ) -> Self {
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             target: Some(DepthTexture::CubeMap { texture, side }),
             multisample_target: None,
         }
@@ -41,6 +44,7 @@ impl<'a> DepthTarget<'a> {
     ) -> Self {
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             target: Some(DepthTexture::Array { texture, layer }),
             multisample_target: None,
         }
@@ -52,6 +56,7 @@ impl<'a> DepthTarget<'a> {
     ) -> Self {
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             target: None,
             multisample_target: Some(texture),
         }
@@ -72,6 +77,7 @@ impl<'a> DepthTarget<'a> {
             scissor_box,
             ClearState {
                 depth: clear_state.depth,
+                stencil: clear_state.stencil,
                 ..ClearState::none()
             },
         );
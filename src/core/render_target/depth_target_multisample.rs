@@ -10,6 +10,7 @@ use crate::core::*;
 ///
 pub struct DepthTargetMultisample<D: DepthTextureDataType> {
     pub(crate) context: Context,
+    pub(crate) visible_layers: RenderLayers,
     depth: DepthTexture2DMultisample,
     _d: std::marker::PhantomData<D>,
 }
@@ -24,6 +25,7 @@ impl<D: DepthTextureDataType> DepthTargetMultisample<D> {
         super::multisample_sanity_check(context, number_of_samples);
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             depth: DepthTexture2DMultisample::new::<D>(context, width, height, number_of_samples),
             _d: std::marker::PhantomData,
         }
@@ -44,6 +46,7 @@ impl<D: DepthTextureDataType> DepthTargetMultisample<D> {
             scissor_box,
             ClearState {
                 depth: clear_state.depth,
+                stencil: clear_state.stencil,
                 ..ClearState::none()
             },
         );
@@ -11,6 +11,7 @@ use crate::core::*;
 ///
 pub struct RenderTargetMultisample<C: TextureDataType, D: DepthTextureDataType> {
     pub(crate) context: Context,
+    pub(crate) visible_layers: RenderLayers,
     color: Texture2DMultisample,
     depth: DepthTexture2DMultisample,
     _c: std::marker::PhantomData<C>,
@@ -27,6 +28,7 @@ impl<C: TextureDataType, D: DepthTextureDataType> RenderTargetMultisample<C, D>
         super::multisample_sanity_check(context, number_of_samples);
         Self {
             context: context.clone(),
+            visible_layers: RenderLayers::ALL,
             color: Texture2DMultisample::new::<C>(context, width, height, number_of_samples),
             depth: DepthTexture2DMultisample::new::<D>(context, width, height, number_of_samples),
             _c: std::marker::PhantomData,
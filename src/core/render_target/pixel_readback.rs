@@ -0,0 +1,140 @@
+use crate::core::*;
+use std::marker::PhantomData;
+
+///
+/// A handle to pixels which are being read from a render target without stalling the pipeline, see for example [RenderTarget::read_color_async].
+/// The pixels are copied into a pixel buffer on the GPU and a fence is used to detect when the copy is finished, which is also supported on web.
+/// Poll [PixelReadback::is_ready] each frame and call [PixelReadback::read] when it returns true, or use [PixelReadback::try_read].
+///
+pub struct PixelReadback<T: TextureDataType> {
+    context: Context,
+    // Taken when the buffer is reused for another readback, see [PixelReadback::read_and_take_buffer]
+    buffer: Option<crate::context::Buffer>,
+    fence: Option<crate::context::Fence>,
+    width: u32,
+    height: u32,
+    data_size: usize,
+    // Applied to each pixel when it is read, for example to convert reversed depth values
+    pub(in crate::core) map_pixel: Option<fn(&mut T)>,
+    _data_type: PhantomData<T>,
+}
+
+impl<T: TextureDataType> PixelReadback<T> {
+    pub(in crate::core) fn new(
+        context: &Context,
+        buffer: Option<crate::context::Buffer>,
+        scissor_box: ScissorBox,
+        data_size: usize,
+        read_pixels: impl FnOnce(&Context),
+    ) -> Self {
+        let buffer = buffer.unwrap_or_else(|| unsafe {
+            context.create_buffer().expect("Failed to create buffer")
+        });
+        let fence = unsafe {
+            context.bind_buffer(crate::context::PIXEL_PACK_BUFFER, Some(buffer));
+            context.buffer_data_size(
+                crate::context::PIXEL_PACK_BUFFER,
+                (scissor_box.width as usize * scissor_box.height as usize * data_size) as i32,
+                crate::context::STREAM_READ,
+            );
+            read_pixels(context);
+            context.bind_buffer(crate::context::PIXEL_PACK_BUFFER, None);
+            let fence = context
+                .fence_sync(crate::context::SYNC_GPU_COMMANDS_COMPLETE, 0)
+                .ok();
+            context.flush();
+            fence
+        };
+        Self {
+            context: context.clone(),
+            buffer: Some(buffer),
+            fence,
+            width: scissor_box.width,
+            height: scissor_box.height,
+            data_size,
+            map_pixel: None,
+            _data_type: PhantomData,
+        }
+    }
+
+    /// The width of the read area in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the read area in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    ///
+    /// Returns whether or not the pixels have been copied, so [PixelReadback::read] returns without waiting.
+    ///
+    pub fn is_ready(&self) -> bool {
+        match self.fence {
+            Some(fence) => {
+                let status = unsafe { self.context.client_wait_sync(fence, 0, 0) };
+                status == crate::context::ALREADY_SIGNALED
+                    || status == crate::context::CONDITION_SATISFIED
+            }
+            None => true,
+        }
+    }
+
+    ///
+    /// Returns the pixels from the top row to the bottom row, waiting for the copy to finish if it is not ready.
+    ///
+    pub fn read(self) -> Vec<T> {
+        self.read_pixels()
+    }
+
+    ///
+    /// Returns the pixels, see [PixelReadback::read], and the pixel buffer, so it can be reused for another readback instead of being deleted.
+    ///
+    pub(crate) fn read_and_take_buffer(mut self) -> (Vec<T>, crate::context::Buffer) {
+        let pixels = self.read_pixels();
+        (pixels, self.buffer.take().unwrap())
+    }
+
+    fn read_pixels(&self) -> Vec<T> {
+        let mut bytes = vec![0u8; self.width as usize * self.height as usize * self.data_size];
+        unsafe {
+            self.context
+                .bind_buffer(crate::context::PIXEL_PACK_BUFFER, self.buffer);
+            self.context
+                .get_buffer_sub_data(crate::context::PIXEL_PACK_BUFFER, 0, &mut bytes);
+            self.context
+                .bind_buffer(crate::context::PIXEL_PACK_BUFFER, None);
+        }
+        let mut pixels: Vec<T> = from_byte_slice(&bytes).to_vec();
+        if let Some(map_pixel) = self.map_pixel {
+            pixels.iter_mut().for_each(map_pixel);
+        }
+        flip_y(&mut pixels, self.width as usize, self.height as usize);
+        pixels
+    }
+
+    ///
+    /// Returns the pixels, see [PixelReadback::read], if they are ready, otherwise this handle is returned so it can be polled again later.
+    ///
+    pub fn try_read(self) -> Result<Vec<T>, Self> {
+        if self.is_ready() {
+            Ok(self.read())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: TextureDataType> Drop for PixelReadback<T> {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(fence) = self.fence.take() {
+                self.context.delete_sync(fence);
+            }
+            if let Some(buffer) = self.buffer.take() {
+                self.context.delete_buffer(buffer);
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+uniform sampler2D previousLevel;
+uniform ivec2 previousSize;
+
+layout (location = 0) out vec4 outColor;
+
+float fetch_depth(ivec2 texel)
+{
+    return texelFetch(previousLevel, min(texel, previousSize - 1), 0).r;
+}
+
+void main()
+{
+    ivec2 texel = 2 * ivec2(gl_FragCoord.xy);
+    float depth = max(
+        max(fetch_depth(texel), fetch_depth(texel + ivec2(1, 0))),
+        max(fetch_depth(texel + ivec2(0, 1)), fetch_depth(texel + ivec2(1, 1)))
+    );
+
+    // When the size of the previous level is odd, the last column and row also cover the extra texel
+    bool extraColumn = (previousSize.x & 1) == 1 && texel.x + 3 == previousSize.x;
+    bool extraRow = (previousSize.y & 1) == 1 && texel.y + 3 == previousSize.y;
+    if (extraColumn) {
+        depth = max(depth, max(fetch_depth(texel + ivec2(2, 0)), fetch_depth(texel + ivec2(2, 1))));
+    }
+    if (extraRow) {
+        depth = max(depth, max(fetch_depth(texel + ivec2(0, 2)), fetch_depth(texel + ivec2(1, 2))));
+    }
+    if (extraColumn && extraRow) {
+        depth = max(depth, fetch_depth(texel + ivec2(2, 2)));
+    }
+    outColor = vec4(depth);
+}
@@ -0,0 +1,30 @@
+
+uniform ivec2 sourceSize;
+uniform int binCount;
+uniform float minValue;
+uniform float maxValue;
+
+void main()
+{
+    // Each texel is drawn as four tiny triangles, one for each channel, which covers the center of exactly one pixel in the row of the channel
+    int triangle = gl_VertexID / 3;
+    int corner = gl_VertexID - 3 * triangle;
+    int channel = triangle - 4 * (triangle / 4);
+    int texelIndex = triangle / 4;
+    ivec2 texel = ivec2(texelIndex - sourceSize.x * (texelIndex / sourceSize.x), texelIndex / sourceSize.x);
+    vec4 color = fetch_color(texel);
+
+    float value = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+    if (channel == 0) {
+        value = color.r;
+    } else if (channel == 1) {
+        value = color.g;
+    } else if (channel == 2) {
+        value = color.b;
+    }
+    int bin = clamp(int(floor((value - minValue) / (maxValue - minValue) * float(binCount))), 0, binCount - 1);
+
+    vec2 offset = corner == 0 ? vec2(-0.5, -0.5) : (corner == 1 ? vec2(1.0, -0.5) : vec2(-0.5, 1.0));
+    vec2 pixel = vec2(float(bin) + 0.5, float(channel) + 0.5) + offset;
+    gl_Position = vec4(2.0 * pixel / vec2(float(binCount), 4.0) - 1.0, 0.0, 1.0);
+}
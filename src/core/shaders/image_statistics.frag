@@ -0,0 +1,61 @@
+
+uniform ivec2 sourceSize;
+
+layout (location = 0) out vec4 outMin;
+layout (location = 1) out vec4 outMax;
+layout (location = 2) out vec4 outMean;
+layout (location = 3) out vec4 outLuminance;
+
+#ifdef FIRST_PASS
+// The first pass reads the colors of the source texture
+void fetch(ivec2 texel, out vec4 minColor, out vec4 maxColor, out vec4 meanColor, out vec3 luminance)
+{
+    vec4 color = fetch_color(texel);
+    float l = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+    minColor = color;
+    maxColor = color;
+    meanColor = color;
+    luminance = vec3(l, log(max(l, 0.0001)), 1.0);
+}
+#else
+// The following passes read the statistics of the previous pass, where the count of the reduced texels is stored in the blue channel of the luminance layer
+uniform sampler2DArray previousLevel;
+
+void fetch(ivec2 texel, out vec4 minColor, out vec4 maxColor, out vec4 meanColor, out vec3 luminance)
+{
+    minColor = texelFetch(previousLevel, ivec3(texel, 0), 0);
+    maxColor = texelFetch(previousLevel, ivec3(texel, 1), 0);
+    meanColor = texelFetch(previousLevel, ivec3(texel, 2), 0);
+    luminance = texelFetch(previousLevel, ivec3(texel, 3), 0).rgb;
+}
+#endif
+
+void main()
+{
+    ivec2 base = 2 * ivec2(gl_FragCoord.xy);
+    vec4 minColor = vec4(3.4e38);
+    vec4 maxColor = vec4(-3.4e38);
+    vec4 sumColor = vec4(0.0);
+    vec2 sumLuminance = vec2(0.0);
+    float count = 0.0;
+    for (int y = 0; y < 2; y++) {
+        for (int x = 0; x < 2; x++) {
+            ivec2 texel = base + ivec2(x, y);
+            if (texel.x < sourceSize.x && texel.y < sourceSize.y) {
+                vec4 mn, mx, mean;
+                vec3 luminance;
+                fetch(texel, mn, mx, mean, luminance);
+                minColor = min(minColor, mn);
+                maxColor = max(maxColor, mx);
+                // The means are weighted by the number of texels they cover, which differs at the edges of textures with a size which is not a power of two
+                sumColor += mean * luminance.z;
+                sumLuminance += luminance.xy * luminance.z;
+                count += luminance.z;
+            }
+        }
+    }
+    outMin = minColor;
+    outMax = maxColor;
+    outMean = sumColor / max(count, 1.0);
+    outLuminance = vec4(sumLuminance / max(count, 1.0), count, 0.0);
+}
@@ -0,0 +1,12 @@
+vec4 logarithmic_depth(vec4 clipPosition)
+{
+    // The depth is log2(1 + w) / log2(1 + far) in the range [0..1], where w is the distance from the camera along the view direction
+    float depth = log2(max(1.0 + clipPosition.w, 0.000001)) / log2(logDepthFar + 1.0);
+#ifdef CLIP_DEPTH_ZERO_TO_ONE
+    // The clip space depth is reversed and in the range [0..1], see Context::set_reverse_z
+    clipPosition.z = (1.0 - depth) * clipPosition.w;
+#else
+    clipPosition.z = (2.0 * depth - 1.0) * clipPosition.w;
+#endif
+    return clipPosition;
+}
@@ -8,6 +8,9 @@ float saturate(in float value)
 }
 
 vec3 srgb_from_rgb(vec3 rgb) {
+#ifdef LINEAR_OUTPUT
+	return rgb;
+#else
 	vec3 a = vec3(0.055, 0.055, 0.055);
 	vec3 ap1 = vec3(1.0, 1.0, 1.0) + a;
 	vec3 g = vec3(2.4, 2.4, 2.4);
@@ -16,6 +19,7 @@ vec3 srgb_from_rgb(vec3 rgb) {
 	vec3 lo = rgb * 12.92;
 	vec3 hi = ap1 * pow(rgb, ginv) - a;
 	return mix(lo, hi, select);
+#endif
 }
 
 vec3 rgb_from_srgb(vec3 srgb) {
@@ -29,9 +33,29 @@ vec3 rgb_from_srgb(vec3 srgb) {
 }
 
 vec3 world_pos_from_depth(mat4 viewProjectionInverse, float depth, vec2 uv) {
+#ifdef LOGARITHMIC_DEPTH
+    // The view depth is found from the logarithmic depth and the position is interpolated between the near and far plane,
+    // where the w component of the unprojected positions is one divided by the view depth
+    vec4 near = viewProjectionInverse * vec4(uv * 2.0 - 1.0, -1.0, 1.0);
+    vec4 far = viewProjectionInverse * vec4(uv * 2.0 - 1.0, 1.0, 1.0);
+    float viewDepth = pow(logDepthFar + 1.0, depth) - 1.0;
+    float nearDepth = 1.0 / near.w;
+    float farDepth = 1.0 / far.w;
+    return mix(near.xyz / near.w, far.xyz / far.w, (viewDepth - nearDepth) / (farDepth - nearDepth));
+#else
     vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
     vec4 position = viewProjectionInverse * clipSpacePosition;
     return position.xyz / position.w;
+#endif
+}
+
+// Returns the value to write to gl_FragDepth for the given clip space position computed using the camera projection, taking reversed depth into account
+float depth_from_clip_position(vec4 clipPosition) {
+    float depth = 0.5 * clipPosition.z / clipPosition.w + 0.5;
+#ifdef REVERSE_Z
+    depth = 1.0 - depth;
+#endif
+    return depth;
 }
 
 vec3 reinhard_tone_mapping(vec3 color) {
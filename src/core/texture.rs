@@ -37,6 +37,12 @@ mod depth_texture2d_multisample;
 #[doc(inline)]
 pub(in crate::core) use depth_texture2d_multisample::*;
 
+mod compressed;
+#[doc(inline)]
+pub use compressed::*;
+
+mod decompression;
+
 use data_type::*;
 pub use three_d_asset::texture::{
     Interpolation, Texture2D as CpuTexture, Texture3D as CpuTexture3D, TextureData, Wrapping,
@@ -47,6 +53,7 @@ pub trait TextureDataType: DataType {}
 impl TextureDataType for u8 {}
 impl TextureDataType for f16 {}
 impl TextureDataType for f32 {}
+impl TextureDataType for u32 {}
 
 impl<T: TextureDataType + PrimitiveDataType> TextureDataType for Vector2<T> {}
 impl<T: TextureDataType + PrimitiveDataType> TextureDataType for Vector3<T> {}
@@ -68,9 +75,15 @@ pub trait DepthTextureDataType: DepthDataType {}
 #[derive(Clone, Copy, Default, Debug)]
 pub struct f24 {}
 
+/// 24 bit float depth together with an 8 bit stencil value which can be used as [DepthTextureDataType] when a stencil buffer is needed, see [Stencil].
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct f24s8 {}
+
 impl DepthTextureDataType for f16 {}
 impl DepthTextureDataType for f24 {}
 impl DepthTextureDataType for f32 {}
+impl DepthTextureDataType for f24s8 {}
 
 ///
 /// A reference to some type of texture containing colors.
@@ -0,0 +1,520 @@
+use super::decompression::*;
+use crate::core::*;
+
+///
+/// A block compressed texture format, see [CpuCompressedTexture].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompressedFormat {
+    /// S3TC DXT1 (BC1) with RGB colors and optionally a 1 bit alpha, 8 bytes per 4x4 block.
+    Bc1,
+    /// S3TC DXT3 (BC2) with RGB colors and a 4 bit alpha, 16 bytes per 4x4 block.
+    Bc2,
+    /// S3TC DXT5 (BC3) with RGB colors and an interpolated alpha, 16 bytes per 4x4 block.
+    Bc3,
+    /// BPTC (BC7) with high quality RGBA colors, 16 bytes per 4x4 block.
+    Bc7,
+    /// ETC2 with RGB colors, 8 bytes per 4x4 block. ETC1 data is also valid ETC2 data.
+    Etc2Rgb,
+    /// ETC2 with RGBA colors, 16 bytes per 4x4 block.
+    Etc2Rgba,
+    /// ASTC with RGBA colors and the given block size in texels, 16 bytes per block.
+    /// The block size must be one of the sizes supported by ASTC, from 4x4 to 12x12.
+    Astc {
+        /// The width of a block in texels.
+        block_width: u32,
+        /// The height of a block in texels.
+        block_height: u32,
+    },
+}
+
+// The largest width and height of a compressed texture, which is larger than the maximum texture size of any graphics device,
+// the maximum texture size of the current device is checked when the texture is transferred to the GPU, see Capabilities::max_texture_size
+const MAX_TEXTURE_SIZE: u32 = 1 << 16;
+
+// The block sizes supported by ASTC in the order of the OpenGL and Vulkan formats
+const ASTC_BLOCK_SIZES: [(u32, u32); 14] = [
+    (4, 4),
+    (5, 4),
+    (5, 5),
+    (6, 5),
+    (6, 6),
+    (8, 5),
+    (8, 6),
+    (8, 8),
+    (10, 5),
+    (10, 6),
+    (10, 8),
+    (10, 10),
+    (12, 10),
+    (12, 12),
+];
+
+impl CompressedFormat {
+    ///
+    /// Returns the width and height of a block in texels.
+    ///
+    pub fn block_size(&self) -> (u32, u32) {
+        match self {
+            Self::Astc {
+                block_width,
+                block_height,
+            } => (*block_width, *block_height),
+            _ => (4, 4),
+        }
+    }
+
+    ///
+    /// Returns the number of bytes in a block.
+    ///
+    pub fn block_bytes(&self) -> usize {
+        match self {
+            Self::Bc1 | Self::Etc2Rgb => 8,
+            _ => 16,
+        }
+    }
+
+    ///
+    /// Returns the number of bytes of a mip level with the given size or `None` if the number of bytes overflows.
+    ///
+    pub fn level_bytes(&self, width: u32, height: u32) -> Option<usize> {
+        let (block_width, block_height) = self.block_size();
+        (width.div_ceil(block_width) as usize)
+            .checked_mul(height.div_ceil(block_height) as usize)?
+            .checked_mul(self.block_bytes())
+    }
+
+    pub(in crate::core) fn internal_format(&self) -> u32 {
+        match self {
+            Self::Bc1 => 0x83F1,
+            Self::Bc2 => 0x83F2,
+            Self::Bc3 => 0x83F3,
+            Self::Bc7 => 0x8E8C,
+            Self::Etc2Rgb => 0x9274,
+            Self::Etc2Rgba => 0x9278,
+            Self::Astc { .. } => {
+                0x93B0
+                    + ASTC_BLOCK_SIZES
+                        .iter()
+                        .position(|s| *s == self.block_size())
+                        .unwrap_or(0) as u32
+            }
+        }
+    }
+}
+
+///
+/// A texture which is compressed using a block compressed format, for example loaded from a KTX2 or DDS file, see [Texture2D::new_compressed].
+/// The compressed data stays compressed in GPU memory, which reduces the memory usage and bandwidth compared to an uncompressed [CpuTexture].
+///
+#[derive(Clone, Debug)]
+pub struct CpuCompressedTexture {
+    /// The compressed format of the data.
+    pub format: CompressedFormat,
+    /// The width of the first mip level in texels.
+    pub width: u32,
+    /// The height of the first mip level in texels.
+    pub height: u32,
+    /// The compressed data of each mip level, starting with the largest level, where the blocks are stored row by row from the top.
+    pub mip_levels: Vec<Vec<u8>>,
+    /// The way the pixel data is interpolated when the texture is far away
+    pub min_filter: Interpolation,
+    /// The way the pixel data is interpolated when the texture is close
+    pub mag_filter: Interpolation,
+    /// Specifies whether mipmaps should be used when sampling and the interpolation between them.
+    /// If `None`, only the first mip level is used.
+    pub mip_map_filter: Option<Interpolation>,
+    /// Determines how the texture is sampled outside the [0..1] s coordinate range (the first value of the uv coordinates).
+    pub wrap_s: Wrapping,
+    /// Determines how the texture is sampled outside the [0..1] t coordinate range (the second value of the uv coordinates).
+    pub wrap_t: Wrapping,
+}
+
+impl CpuCompressedTexture {
+    ///
+    /// Creates a new compressed texture with the given format, size and mip levels, starting with the largest level, and default sampling parameters.
+    ///
+    pub fn new(
+        format: CompressedFormat,
+        width: u32,
+        height: u32,
+        mip_levels: Vec<Vec<u8>>,
+    ) -> Result<Self, CoreError> {
+        let texture = Self {
+            format,
+            width,
+            height,
+            mip_levels,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mip_map_filter: Some(Interpolation::Linear),
+            wrap_s: Wrapping::Repeat,
+            wrap_t: Wrapping::Repeat,
+        };
+        texture.validate()?;
+        Ok(texture)
+    }
+
+    ///
+    /// Parses a DDS file with BC1, BC2, BC3 or BC7 compressed data, for example loaded using [three_d_asset::io::load].
+    /// Only the first image is used if the file contains a texture array or a cube map.
+    ///
+    pub fn from_dds(bytes: &[u8]) -> Result<Self, CoreError> {
+        let error = |message: &str| {
+            CoreError::CompressedTextureParsing("DDS".to_owned(), message.to_owned())
+        };
+        if bytes.len() < 128 || &bytes[0..4] != b"DDS " {
+            Err(error("not a DDS file"))?;
+        }
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let flags = read_u32(8);
+        let height = read_u32(12);
+        let width = read_u32(16);
+        check_size(width, height).map_err(|message| error(&message))?;
+        // The mip map count is only valid if the DDSD_MIPMAPCOUNT flag is set
+        let level_count = if flags & 0x20000 != 0 {
+            read_u32(28).clamp(1, 32)
+        } else {
+            1
+        };
+        let (format, mut offset) = match &bytes[84..88] {
+            b"DXT1" => (CompressedFormat::Bc1, 128),
+            b"DXT3" => (CompressedFormat::Bc2, 128),
+            b"DXT5" => (CompressedFormat::Bc3, 128),
+            b"DX10" => {
+                if bytes.len() < 148 {
+                    Err(error("the DX10 header is missing"))?;
+                }
+                let format = match read_u32(128) {
+                    70..=72 => CompressedFormat::Bc1,
+                    73..=75 => CompressedFormat::Bc2,
+                    76..=78 => CompressedFormat::Bc3,
+                    97..=99 => CompressedFormat::Bc7,
+                    f => Err(error(&format!("the DXGI format {} is not supported", f)))?,
+                };
+                (format, 148)
+            }
+            _ => Err(error("the pixel format is not supported"))?,
+        };
+        let mut mip_levels = Vec::new();
+        for level in 0..level_count {
+            let end = format
+                .level_bytes((width >> level).max(1), (height >> level).max(1))
+                .and_then(|size| offset.checked_add(size))
+                .filter(|end| *end <= bytes.len())
+                .ok_or_else(|| error("the file is truncated"))?;
+            mip_levels.push(bytes[offset..end].to_vec());
+            offset = end;
+        }
+        Self::new(format, width, height, mip_levels)
+    }
+
+    ///
+    /// Parses a KTX2 file with BC1, BC2, BC3, BC7, ETC2 or ASTC compressed data, for example loaded using [three_d_asset::io::load].
+    /// Supercompressed files, for example Basis Universal files, are not supported.
+    /// Only the first image is used if the file contains a texture array or a cube map.
+    ///
+    pub fn from_ktx2(bytes: &[u8]) -> Result<Self, CoreError> {
+        const IDENTIFIER: [u8; 12] = [
+            0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+        ];
+        let error = |message: &str| {
+            CoreError::CompressedTextureParsing("KTX2".to_owned(), message.to_owned())
+        };
+        if bytes.len() < 80 || bytes[0..12] != IDENTIFIER {
+            Err(error("not a KTX2 file"))?;
+        }
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let read_u64 =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let format = match read_u32(12) {
+            131..=134 => CompressedFormat::Bc1,
+            135 | 136 => CompressedFormat::Bc2,
+            137 | 138 => CompressedFormat::Bc3,
+            145 | 146 => CompressedFormat::Bc7,
+            147 | 148 => CompressedFormat::Etc2Rgb,
+            151 | 152 => CompressedFormat::Etc2Rgba,
+            f @ 157..=184 => {
+                let (block_width, block_height) = ASTC_BLOCK_SIZES[(f as usize - 157) / 2];
+                CompressedFormat::Astc {
+                    block_width,
+                    block_height,
+                }
+            }
+            0 => Err(error(
+                "the format is not specified, which is only the case for supercompressed files",
+            ))?,
+            f => Err(error(&format!("the Vulkan format {} is not supported", f)))?,
+        };
+        let width = read_u32(20);
+        let height = read_u32(24);
+        check_size(width, height).map_err(|message| error(&message))?;
+        if read_u32(44) != 0 {
+            Err(error("supercompressed files are not supported"))?;
+        }
+        let level_count = read_u32(40).clamp(1, 32) as usize;
+        if bytes.len() < 80 + 24 * level_count {
+            Err(error("the file is truncated"))?;
+        }
+        let mut mip_levels = Vec::new();
+        for level in 0..level_count {
+            let offset = usize::try_from(read_u64(80 + 24 * level))
+                .map_err(|_| error("the file is truncated"))?;
+            let end = format
+                .level_bytes((width >> level).max(1), (height >> level).max(1))
+                .and_then(|size| offset.checked_add(size))
+                .filter(|end| *end <= bytes.len())
+                .ok_or_else(|| error("the file is truncated"))?;
+            mip_levels.push(bytes[offset..end].to_vec());
+        }
+        Self::new(format, width, height, mip_levels)
+    }
+
+    ///
+    /// Decompresses the first mip level into an uncompressed texture with RGBA colors and the same sampling parameters.
+    /// Returns an error for the [CompressedFormat::Astc] formats, which cannot be decompressed.
+    ///
+    pub fn decompress(&self) -> Result<CpuTexture, CoreError> {
+        let decode = match self.format {
+            CompressedFormat::Bc1 => decode_bc1,
+            CompressedFormat::Bc2 => decode_bc2,
+            CompressedFormat::Bc3 => decode_bc3,
+            CompressedFormat::Bc7 => decode_bc7,
+            CompressedFormat::Etc2Rgb => decode_etc2_rgb,
+            CompressedFormat::Etc2Rgba => decode_etc2_rgba,
+            CompressedFormat::Astc { .. } => Err(CoreError::UnsupportedCompressedFormat(format!(
+                "{:?}",
+                self.format
+            )))?,
+        };
+        Ok(CpuTexture {
+            data: TextureData::RgbaU8(decompress_blocks(
+                self.width,
+                self.height,
+                &self.mip_levels[0],
+                self.format.block_bytes(),
+                decode,
+            )),
+            width: self.width,
+            height: self.height,
+            min_filter: self.min_filter,
+            mag_filter: self.mag_filter,
+            mip_map_filter: self.mip_map_filter,
+            wrap_s: self.wrap_s,
+            wrap_t: self.wrap_t,
+            ..Default::default()
+        })
+    }
+
+    fn validate(&self) -> Result<(), CoreError> {
+        if let CompressedFormat::Astc { .. } = self.format {
+            if !ASTC_BLOCK_SIZES.contains(&self.format.block_size()) {
+                Err(CoreError::UnsupportedCompressedFormat(format!(
+                    "{:?}",
+                    self.format
+                )))?;
+            }
+        }
+        check_size(self.width, self.height).map_err(|message| {
+            CoreError::CompressedTextureParsing(format!("{:?}", self.format), message)
+        })?;
+        if self.mip_levels.is_empty() {
+            Err(CoreError::CompressedTextureParsing(
+                format!("{:?}", self.format),
+                "the texture has no mip levels".to_owned(),
+            ))?;
+        }
+        for (level, data) in self.mip_levels.iter().enumerate() {
+            let expected = self
+                .format
+                .level_bytes((self.width >> level).max(1), (self.height >> level).max(1));
+            if Some(data.len()) != expected {
+                Err(CoreError::CompressedTextureParsing(
+                    format!("{:?}", self.format),
+                    format!(
+                        "mip level {} contains {} bytes but {} bytes was expected",
+                        level,
+                        data.len(),
+                        expected.unwrap_or(usize::MAX)
+                    ),
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_size(width: u32, height: u32) -> Result<(), String> {
+    if width == 0 || height == 0 || width > MAX_TEXTURE_SIZE || height > MAX_TEXTURE_SIZE {
+        Err(format!("the size {}x{} is not supported", width, height))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dds(width: u32, height: u32, four_cc: &[u8; 4], level_count: u32, data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 128];
+        bytes[0..4].copy_from_slice(b"DDS ");
+        bytes[4..8].copy_from_slice(&124u32.to_le_bytes());
+        bytes[8..12].copy_from_slice(&0x20000u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&height.to_le_bytes());
+        bytes[16..20].copy_from_slice(&width.to_le_bytes());
+        bytes[28..32].copy_from_slice(&level_count.to_le_bytes());
+        bytes[84..88].copy_from_slice(four_cc);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn ktx2(
+        vk_format: u32,
+        width: u32,
+        height: u32,
+        levels: &[(u64, u64)],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = vec![
+            0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+        ];
+        bytes.resize(80, 0);
+        bytes[12..16].copy_from_slice(&vk_format.to_le_bytes());
+        bytes[16..20].copy_from_slice(&1u32.to_le_bytes());
+        bytes[20..24].copy_from_slice(&width.to_le_bytes());
+        bytes[24..28].copy_from_slice(&height.to_le_bytes());
+        bytes[40..44].copy_from_slice(&(levels.len() as u32).to_le_bytes());
+        for (offset, size) in levels {
+            bytes.extend_from_slice(&offset.to_le_bytes());
+            bytes.extend_from_slice(&size.to_le_bytes());
+            bytes.extend_from_slice(&size.to_le_bytes());
+        }
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_level_bytes() {
+        assert_eq!(CompressedFormat::Bc1.level_bytes(5, 3), Some(16));
+        assert_eq!(CompressedFormat::Bc7.level_bytes(8, 8), Some(64));
+        let astc = CompressedFormat::Astc {
+            block_width: 12,
+            block_height: 12,
+        };
+        assert_eq!(astc.level_bytes(10, 25), Some(48));
+        assert_eq!(CompressedFormat::Bc7.level_bytes(u32::MAX, u32::MAX), None);
+    }
+
+    #[test]
+    fn test_from_dds() {
+        let texture = CpuCompressedTexture::from_dds(&dds(8, 4, b"DXT1", 2, &[0; 24])).unwrap();
+        assert_eq!(texture.format, CompressedFormat::Bc1);
+        assert_eq!((texture.width, texture.height), (8, 4));
+        assert_eq!(texture.mip_levels.len(), 2);
+        assert_eq!(texture.mip_levels[0].len(), 16);
+        assert_eq!(texture.mip_levels[1].len(), 8);
+    }
+
+    #[test]
+    fn test_from_dds_truncated() {
+        assert!(CpuCompressedTexture::from_dds(&dds(8, 4, b"DXT1", 1, &[])[..100]).is_err());
+        assert!(CpuCompressedTexture::from_dds(&dds(8, 4, b"DXT1", 2, &[0; 20])).is_err());
+        assert!(CpuCompressedTexture::from_dds(&dds(8, 4, b"DX10", 1, &[])).is_err());
+        assert!(CpuCompressedTexture::from_dds(&dds(8, 4, b"ABCD", 1, &[0; 16])).is_err());
+    }
+
+    #[test]
+    fn test_from_dds_overflow() {
+        let bytes = dds(u32::MAX, u32::MAX, b"DXT5", 32, &[0; 16]);
+        assert!(CpuCompressedTexture::from_dds(&bytes).is_err());
+        let bytes = dds(0, 4, b"DXT5", 1, &[0; 16]);
+        assert!(CpuCompressedTexture::from_dds(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_from_ktx2() {
+        let bytes = ktx2(145, 4, 4, &[(104, 16)], &[0; 16]);
+        let texture = CpuCompressedTexture::from_ktx2(&bytes).unwrap();
+        assert_eq!(texture.format, CompressedFormat::Bc7);
+        assert_eq!((texture.width, texture.height), (4, 4));
+        assert_eq!(texture.mip_levels, vec![vec![0; 16]]);
+    }
+
+    #[test]
+    fn test_from_ktx2_truncated() {
+        let bytes = ktx2(145, 4, 4, &[(104, 16)], &[0; 16]);
+        assert!(CpuCompressedTexture::from_ktx2(&bytes[..60]).is_err());
+        assert!(CpuCompressedTexture::from_ktx2(&bytes[..110]).is_err());
+        // The header specifies two levels, but the level index only contains one
+        let mut bytes = ktx2(145, 8, 8, &[(104, 64)], &[0; 8]);
+        bytes[40..44].copy_from_slice(&2u32.to_le_bytes());
+        bytes.truncate(104);
+        assert!(CpuCompressedTexture::from_ktx2(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_from_ktx2_overflow() {
+        let bytes = ktx2(145, 4, 4, &[(u64::MAX, 16)], &[0; 16]);
+        assert!(CpuCompressedTexture::from_ktx2(&bytes).is_err());
+        let bytes = ktx2(145, 4, 4, &[(u64::MAX - 8, 16)], &[0; 16]);
+        assert!(CpuCompressedTexture::from_ktx2(&bytes).is_err());
+        let bytes = ktx2(145, u32::MAX, u32::MAX, &[(104, 16)], &[0; 16]);
+        assert!(CpuCompressedTexture::from_ktx2(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_from_ktx2_unsupported() {
+        let mut bytes = ktx2(145, 4, 4, &[(104, 16)], &[0; 16]);
+        bytes[44..48].copy_from_slice(&1u32.to_le_bytes());
+        assert!(CpuCompressedTexture::from_ktx2(&bytes).is_err());
+        let bytes = ktx2(37, 4, 4, &[(104, 64)], &[0; 64]);
+        assert!(CpuCompressedTexture::from_ktx2(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decompress() {
+        // A red and a blue BC1 block next to each other
+        let data = vec![
+            0x00, 0xF8, 0x00, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+        let texture = CpuCompressedTexture::new(CompressedFormat::Bc1, 8, 4, vec![data])
+            .unwrap()
+            .decompress()
+            .unwrap();
+        assert_eq!((texture.width, texture.height), (8, 4));
+        match texture.data {
+            TextureData::RgbaU8(texels) => {
+                assert_eq!(texels[3], [255, 0, 0, 255]);
+                assert_eq!(texels[4], [0, 0, 255, 255]);
+            }
+            _ => panic!("expected RGBA data"),
+        }
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert!(CpuCompressedTexture::new(CompressedFormat::Bc1, 8, 4, vec![vec![0; 8]]).is_err());
+        assert!(CpuCompressedTexture::new(CompressedFormat::Bc1, 8, 4, vec![]).is_err());
+        let astc = CompressedFormat::Astc {
+            block_width: 3,
+            block_height: 3,
+        };
+        assert!(CpuCompressedTexture::new(astc, 3, 3, vec![vec![0; 16]]).is_err());
+        assert!(CpuCompressedTexture::new(
+            CompressedFormat::Astc {
+                block_width: 4,
+                block_height: 4
+            },
+            4,
+            4,
+            vec![vec![0; 16]]
+        )
+        .unwrap()
+        .decompress()
+        .is_err());
+    }
+}
@@ -0,0 +1,765 @@
+//
+// Software decoders for block compressed texture formats, used when a compressed format is not supported by the graphics device.
+// Each decoder takes one block and returns the 4x4 texels of the block in row major order.
+//
+
+pub(super) type Block = [[u8; 4]; 16];
+
+///
+/// Decodes blocks of 4x4 texels stored row by row from the top into RGBA texels stored row by row from the top.
+///
+pub(super) fn decompress_blocks(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    block_bytes: usize,
+    decode: impl Fn(&[u8]) -> Block,
+) -> Vec<[u8; 4]> {
+    let (width, height) = (width as usize, height as usize);
+    let blocks_x = width.div_ceil(4);
+    let mut texels = vec![[0u8; 4]; width * height];
+    for (i, block) in data.chunks_exact(block_bytes).enumerate() {
+        let (bx, by) = (i % blocks_x * 4, i / blocks_x * 4);
+        if by >= height {
+            break;
+        }
+        let decoded = decode(block);
+        for y in 0..4.min(height - by) {
+            for x in 0..4.min(width - bx) {
+                texels[(by + y) * width + bx + x] = decoded[y * 4 + x];
+            }
+        }
+    }
+    texels
+}
+
+fn rgb565(color: u16) -> [u8; 3] {
+    let r = (color >> 11) as u8 & 31;
+    let g = (color >> 5) as u8 & 63;
+    let b = color as u8 & 31;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    ]
+}
+
+fn bc1_colors(block: &[u8], allow_transparency: bool) -> Block {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (rgb565(c0), rgb565(c1));
+    let mix = |wa: u32, wb: u32, d: u32| {
+        let mut c = [0u8, 0, 0, 255];
+        for i in 0..3 {
+            c[i] = ((wa * a[i] as u32 + wb * b[i] as u32) / d) as u8;
+        }
+        c
+    };
+    let palette = if c0 > c1 || !allow_transparency {
+        [
+            [a[0], a[1], a[2], 255],
+            [b[0], b[1], b[2], 255],
+            mix(2, 1, 3),
+            mix(1, 2, 3),
+        ]
+    } else {
+        [
+            [a[0], a[1], a[2], 255],
+            [b[0], b[1], b[2], 255],
+            mix(1, 1, 2),
+            [0, 0, 0, 0],
+        ]
+    };
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    let mut texels = [[0u8; 4]; 16];
+    for (i, texel) in texels.iter_mut().enumerate() {
+        *texel = palette[(indices >> (2 * i)) as usize & 3];
+    }
+    texels
+}
+
+fn bc3_alpha(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let mut palette = [a0, a1, 0, 0, 0, 0, 0, 0];
+    if a0 > a1 {
+        for i in 1..7 {
+            palette[i + 1] = ((7 - i as u32) * a0 + i as u32 * a1) / 7;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = ((5 - i as u32) * a0 + i as u32 * a1) / 5;
+        }
+        palette[7] = 255;
+    }
+    let mut bits = 0u64;
+    for (i, byte) in block[2..8].iter().enumerate() {
+        bits |= (*byte as u64) << (8 * i);
+    }
+    let mut alpha = [0u8; 16];
+    for (i, a) in alpha.iter_mut().enumerate() {
+        *a = palette[(bits >> (3 * i)) as usize & 7] as u8;
+    }
+    alpha
+}
+
+/// Decodes a BC1 (DXT1) block of 8 bytes.
+pub(super) fn decode_bc1(block: &[u8]) -> Block {
+    bc1_colors(block, true)
+}
+
+/// Decodes a BC2 (DXT3) block of 16 bytes.
+pub(super) fn decode_bc2(block: &[u8]) -> Block {
+    let mut texels = bc1_colors(&block[8..16], false);
+    let alpha = u64::from_le_bytes(block[0..8].try_into().unwrap());
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[3] = ((alpha >> (4 * i)) & 15) as u8 * 17;
+    }
+    texels
+}
+
+/// Decodes a BC3 (DXT5) block of 16 bytes.
+pub(super) fn decode_bc3(block: &[u8]) -> Block {
+    let mut texels = bc1_colors(&block[8..16], false);
+    for (texel, alpha) in texels.iter_mut().zip(bc3_alpha(&block[0..8])) {
+        texel[3] = alpha;
+    }
+    texels
+}
+
+const ETC_MODIFIERS: [[i32; 2]; 8] = [
+    [2, 8],
+    [5, 17],
+    [9, 29],
+    [13, 42],
+    [18, 60],
+    [24, 80],
+    [33, 106],
+    [47, 183],
+];
+
+const ETC_DISTANCES: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+const EAC_MODIFIERS: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+fn clamp_u8(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+fn extend4(value: u8) -> i32 {
+    (value as i32 & 15) * 17
+}
+
+fn extend5(value: i32) -> i32 {
+    (value << 3) | (value >> 2)
+}
+
+/// Decodes an ETC2 RGB block of 8 bytes, which includes ETC1 blocks.
+pub(super) fn decode_etc2_rgb(block: &[u8]) -> Block {
+    let word = u64::from_be_bytes(block[0..8].try_into().unwrap());
+    // The pixel indices are stored column by column
+    let msb = (word >> 16) as u32 & 0xFFFF;
+    let lsb = word as u32 & 0xFFFF;
+    let index = |x: usize, y: usize| {
+        let p = x * 4 + y;
+        (((msb >> p) & 1) << 1 | ((lsb >> p) & 1)) as usize
+    };
+    let mut texels = [[0u8, 0, 0, 255]; 16];
+    let (b0, b1, b2, b3) = (block[0], block[1], block[2], block[3]);
+    let differential = b3 & 2 != 0;
+    let flip = b3 & 1 != 0;
+
+    let (base0, base1) = if differential {
+        let delta = |b: u8| ((b as i32 & 7) << 29) >> 29;
+        let r = (b0 >> 3) as i32;
+        let g = (b1 >> 3) as i32;
+        let b = (b2 >> 3) as i32;
+        let (r2, g2, b2d) = (r + delta(b0), g + delta(b1), b + delta(b2));
+        if !(0..32).contains(&r2) {
+            // T mode
+            let c0 = [
+                extend4(((b0 >> 1) & 12) | (b0 & 3)),
+                extend4(b1 >> 4),
+                extend4(b1),
+            ];
+            let c1 = [extend4(b2 >> 4), extend4(b2), extend4(b3 >> 4)];
+            let d = ETC_DISTANCES[(((b3 >> 1) & 6) | (b3 & 1)) as usize];
+            let paint = [
+                c0,
+                [c1[0] + d, c1[1] + d, c1[2] + d],
+                c1,
+                [c1[0] - d, c1[1] - d, c1[2] - d],
+            ];
+            return paint_texels(&paint, index);
+        } else if !(0..32).contains(&g2) {
+            // H mode
+            let r0 = (b0 >> 3) & 15;
+            let g0 = ((b0 & 7) << 1) | ((b1 >> 4) & 1);
+            let bl0 = (b1 & 8) | ((b1 & 3) << 1) | (b2 >> 7);
+            let r1 = (b2 >> 3) & 15;
+            let g1 = ((b2 & 7) << 1) | (b3 >> 7);
+            let bl1 = (b3 >> 3) & 15;
+            let value0 = ((r0 as u32) << 8) | ((g0 as u32) << 4) | bl0 as u32;
+            let value1 = ((r1 as u32) << 8) | ((g1 as u32) << 4) | bl1 as u32;
+            let d = ETC_DISTANCES[((b3 & 4) | ((b3 & 1) << 1) | (value0 >= value1) as u8) as usize];
+            let c0 = [extend4(r0), extend4(g0), extend4(bl0)];
+            let c1 = [extend4(r1), extend4(g1), extend4(bl1)];
+            let paint = [
+                [c0[0] + d, c0[1] + d, c0[2] + d],
+                [c0[0] - d, c0[1] - d, c0[2] - d],
+                [c1[0] + d, c1[1] + d, c1[2] + d],
+                [c1[0] - d, c1[1] - d, c1[2] - d],
+            ];
+            return paint_texels(&paint, index);
+        } else if !(0..32).contains(&b2d) {
+            // Planar mode
+            let bits = |shift: u32, count: u32| ((word >> shift) & ((1 << count) - 1)) as i32;
+            let extend6 = |v: i32| (v << 2) | (v >> 4);
+            let extend7 = |v: i32| (v << 1) | (v >> 6);
+            let o = [
+                extend6(bits(57, 6)),
+                extend7((bits(56, 1) << 6) | bits(49, 6)),
+                extend6((bits(48, 1) << 5) | (bits(43, 2) << 3) | bits(39, 3)),
+            ];
+            let h = [
+                extend6((bits(34, 5) << 1) | bits(32, 1)),
+                extend7(bits(25, 7)),
+                extend6(bits(19, 6)),
+            ];
+            let v = [
+                extend6(bits(13, 6)),
+                extend7(bits(6, 7)),
+                extend6(bits(0, 6)),
+            ];
+            for y in 0..4 {
+                for x in 0..4 {
+                    let texel = &mut texels[y * 4 + x];
+                    for c in 0..3 {
+                        texel[c] = clamp_u8(
+                            (x as i32 * (h[c] - o[c]) + y as i32 * (v[c] - o[c]) + 4 * o[c] + 2)
+                                >> 2,
+                        );
+                    }
+                }
+            }
+            return texels;
+        }
+        (
+            [extend5(r), extend5(g), extend5(b)],
+            [extend5(r2), extend5(g2), extend5(b2d)],
+        )
+    } else {
+        (
+            [extend4(b0 >> 4), extend4(b1 >> 4), extend4(b2 >> 4)],
+            [extend4(b0), extend4(b1), extend4(b2)],
+        )
+    };
+
+    let tables = [(b3 >> 5) as usize, ((b3 >> 2) & 7) as usize];
+    for y in 0..4 {
+        for x in 0..4 {
+            let subblock = if flip {
+                (y >= 2) as usize
+            } else {
+                (x >= 2) as usize
+            };
+            let base = if subblock == 0 { base0 } else { base1 };
+            let table = ETC_MODIFIERS[tables[subblock]];
+            let modifier = match index(x, y) {
+                0 => table[0],
+                1 => table[1],
+                2 => -table[0],
+                _ => -table[1],
+            };
+            let texel = &mut texels[y * 4 + x];
+            for c in 0..3 {
+                texel[c] = clamp_u8(base[c] + modifier);
+            }
+        }
+    }
+    texels
+}
+
+fn paint_texels(paint: &[[i32; 3]; 4], index: impl Fn(usize, usize) -> usize) -> Block {
+    let mut texels = [[0u8, 0, 0, 255]; 16];
+    for y in 0..4 {
+        for x in 0..4 {
+            let color = paint[index(x, y)];
+            texels[y * 4 + x] = [
+                clamp_u8(color[0]),
+                clamp_u8(color[1]),
+                clamp_u8(color[2]),
+                255,
+            ];
+        }
+    }
+    texels
+}
+
+/// Decodes an ETC2 RGBA block of 16 bytes, ie. an EAC alpha block followed by an ETC2 RGB block.
+pub(super) fn decode_etc2_rgba(block: &[u8]) -> Block {
+    let mut texels = decode_etc2_rgb(&block[8..16]);
+    let base = block[0] as i32;
+    let multiplier = (block[1] >> 4) as i32;
+    let table = EAC_MODIFIERS[(block[1] & 15) as usize];
+    let mut bits = 0u64;
+    for byte in block[2..8].iter() {
+        bits = (bits << 8) | *byte as u64;
+    }
+    for y in 0..4 {
+        for x in 0..4 {
+            let p = x * 4 + y;
+            let index = (bits >> (45 - 3 * p)) as usize & 7;
+            texels[y * 4 + x][3] = clamp_u8(base + table[index] * multiplier);
+        }
+    }
+    texels
+}
+
+// The subset of each texel for each of the 64 partitions with two subsets, one bit per texel
+const BC7_PARTITIONS2: [u16; 64] = [
+    0xCCCC, 0x8888, 0xEEEE, 0xECC8, 0xC880, 0xFEEC, 0xFEC8, 0xEC80, 0xC800, 0xFFEC, 0xFE80, 0xE800,
+    0xFFE8, 0xFF00, 0xFFF0, 0xF000, 0xF710, 0x008E, 0x7100, 0x08CE, 0x008C, 0x7310, 0x3100, 0x8CCE,
+    0x088C, 0x3110, 0x6666, 0x366C, 0x17E8, 0x0FF0, 0x718E, 0x399C, 0xAAAA, 0xF0F0, 0x5A5A, 0x33CC,
+    0x3C3C, 0x55AA, 0x9696, 0xA55A, 0x73CE, 0x13C8, 0x324C, 0x3BDC, 0x6996, 0xC33C, 0x9966, 0x0660,
+    0x0272, 0x04E4, 0x4E40, 0x2720, 0xC936, 0x936C, 0x39C6, 0x639C, 0x9336, 0x9CC6, 0x817E, 0xE718,
+    0xCCF0, 0x0FCC, 0x7744, 0xEE22,
+];
+
+// The subset of each texel for each of the 64 partitions with three subsets
+const BC7_PARTITIONS3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2],
+    [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0],
+    [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0],
+    [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2],
+    [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0],
+    [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0],
+    [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1],
+    [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1],
+    [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2],
+    [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2],
+    [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1],
+    [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+// The anchor texel of the second subset for each partition with two subsets
+const BC7_ANCHORS2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 2, 8, 2, 2, 8, 8, 15, 2, 8,
+    2, 2, 8, 8, 2, 2, 15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6, 6, 2, 6, 8, 15, 15, 2,
+    2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+// The anchor texels of the second and third subset for each partition with three subsets
+const BC7_ANCHORS3: [[u8; 64]; 2] = [
+    [
+        3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3, 3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6,
+        8, 5, 15, 15, 8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15, 3, 15, 5, 5, 5, 8,
+        5, 10, 5, 10, 8, 13, 15, 12, 3, 3,
+    ],
+    [
+        15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8, 15, 8, 15, 3, 15, 8, 15, 8, 3,
+        15, 6, 10, 15, 15, 10, 8, 15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8, 15, 3, 15,
+        15, 15, 15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+    ],
+];
+
+const BC7_WEIGHTS2: [u32; 4] = [0, 21, 43, 64];
+const BC7_WEIGHTS3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+const BC7_WEIGHTS4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+struct Bc7Mode {
+    subsets: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    index_selection_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: u32,
+    secondary_index_bits: u32,
+}
+
+const fn bc7_mode(
+    subsets: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    index_selection_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: u32,
+    secondary_index_bits: u32,
+) -> Bc7Mode {
+    Bc7Mode {
+        subsets,
+        partition_bits,
+        rotation_bits,
+        index_selection_bits,
+        color_bits,
+        alpha_bits,
+        endpoint_pbits,
+        shared_pbits,
+        index_bits,
+        secondary_index_bits,
+    }
+}
+
+const BC7_MODES: [Bc7Mode; 8] = [
+    bc7_mode(3, 4, 0, 0, 4, 0, true, false, 3, 0),
+    bc7_mode(2, 6, 0, 0, 6, 0, false, true, 3, 0),
+    bc7_mode(3, 6, 0, 0, 5, 0, false, false, 2, 0),
+    bc7_mode(2, 6, 0, 0, 7, 0, true, false, 2, 0),
+    bc7_mode(1, 0, 2, 1, 5, 6, false, false, 2, 3),
+    bc7_mode(1, 0, 2, 0, 7, 8, false, false, 2, 2),
+    bc7_mode(1, 0, 0, 0, 7, 7, true, false, 4, 0),
+    bc7_mode(2, 6, 0, 0, 5, 5, true, false, 2, 0),
+];
+
+struct BitReader {
+    bits: u128,
+    position: u32,
+}
+
+impl BitReader {
+    fn read(&mut self, count: u32) -> u32 {
+        if count == 0 {
+            return 0;
+        }
+        let value = (self.bits >> self.position) as u32 & ((1u64 << count) - 1) as u32;
+        self.position += count;
+        value
+    }
+}
+
+fn bc7_interpolate(e0: u32, e1: u32, index: usize, bits: u32) -> u8 {
+    let weight = match bits {
+        2 => BC7_WEIGHTS2[index],
+        3 => BC7_WEIGHTS3[index],
+        _ => BC7_WEIGHTS4[index],
+    };
+    (((64 - weight) * e0 + weight * e1 + 32) >> 6) as u8
+}
+
+/// Decodes a BC7 block of 16 bytes.
+pub(super) fn decode_bc7(block: &[u8]) -> Block {
+    let mut reader = BitReader {
+        bits: u128::from_le_bytes(block[0..16].try_into().unwrap()),
+        position: 0,
+    };
+    let mode_index = match (0..8).find(|_| reader.read(1) == 1) {
+        Some(i) => i,
+        // Reserved mode
+        None => return [[0; 4]; 16],
+    };
+    let mode = &BC7_MODES[mode_index];
+    let partition = reader.read(mode.partition_bits) as usize;
+    let rotation = reader.read(mode.rotation_bits);
+    let index_selection = reader.read(mode.index_selection_bits);
+
+    let endpoint_count = mode.subsets * 2;
+    let mut endpoints = [[0u32; 4]; 6];
+    for c in 0..3 {
+        for endpoint in endpoints.iter_mut().take(endpoint_count) {
+            endpoint[c] = reader.read(mode.color_bits);
+        }
+    }
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        endpoint[3] = reader.read(mode.alpha_bits);
+    }
+    let mut color_bits = mode.color_bits;
+    let mut alpha_bits = mode.alpha_bits;
+    if mode.endpoint_pbits || mode.shared_pbits {
+        let mut pbits = [0u32; 6];
+        if mode.endpoint_pbits {
+            for pbit in pbits.iter_mut().take(endpoint_count) {
+                *pbit = reader.read(1);
+            }
+        } else {
+            for s in 0..mode.subsets {
+                let pbit = reader.read(1);
+                pbits[2 * s] = pbit;
+                pbits[2 * s + 1] = pbit;
+            }
+        }
+        for (endpoint, pbit) in endpoints.iter_mut().zip(pbits).take(endpoint_count) {
+            for (c, value) in endpoint.iter_mut().enumerate() {
+                if c < 3 || mode.alpha_bits > 0 {
+                    *value = (*value << 1) | pbit;
+                }
+            }
+        }
+        color_bits += 1;
+        if alpha_bits > 0 {
+            alpha_bits += 1;
+        }
+    }
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        for (c, value) in endpoint.iter_mut().enumerate() {
+            let bits = if c < 3 { color_bits } else { alpha_bits };
+            *value = if bits == 0 {
+                255
+            } else {
+                let v = *value << (8 - bits);
+                v | (v >> bits)
+            };
+        }
+    }
+
+    let subset = |texel: usize| match mode.subsets {
+        1 => 0,
+        2 => ((BC7_PARTITIONS2[partition] >> texel) & 1) as usize,
+        _ => BC7_PARTITIONS3[partition][texel] as usize,
+    };
+    let is_anchor = |texel: usize| {
+        texel == 0
+            || match mode.subsets {
+                1 => false,
+                2 => BC7_ANCHORS2[partition] as usize == texel,
+                _ => {
+                    BC7_ANCHORS3[0][partition] as usize == texel
+                        || BC7_ANCHORS3[1][partition] as usize == texel
+                }
+            }
+    };
+    let mut indices = [0usize; 16];
+    for (texel, index) in indices.iter_mut().enumerate() {
+        let bits = if is_anchor(texel) {
+            mode.index_bits - 1
+        } else {
+            mode.index_bits
+        };
+        *index = reader.read(bits) as usize;
+    }
+    let mut secondary_indices = [0usize; 16];
+    if mode.secondary_index_bits > 0 {
+        for (texel, index) in secondary_indices.iter_mut().enumerate() {
+            let bits = if texel == 0 {
+                mode.secondary_index_bits - 1
+            } else {
+                mode.secondary_index_bits
+            };
+            *index = reader.read(bits) as usize;
+        }
+    }
+
+    let mut texels = [[0u8; 4]; 16];
+    for (texel, color) in texels.iter_mut().enumerate() {
+        let s = subset(texel);
+        let (e0, e1) = (endpoints[2 * s], endpoints[2 * s + 1]);
+        let (color_index, color_index_bits, alpha_index, alpha_index_bits) =
+            if mode.secondary_index_bits == 0 {
+                (
+                    indices[texel],
+                    mode.index_bits,
+                    indices[texel],
+                    mode.index_bits,
+                )
+            } else if index_selection == 0 {
+                (
+                    indices[texel],
+                    mode.index_bits,
+                    secondary_indices[texel],
+                    mode.secondary_index_bits,
+                )
+            } else {
+                (
+                    secondary_indices[texel],
+                    mode.secondary_index_bits,
+                    indices[texel],
+                    mode.index_bits,
+                )
+            };
+        for c in 0..3 {
+            color[c] = bc7_interpolate(e0[c], e1[c], color_index, color_index_bits);
+        }
+        color[3] = bc7_interpolate(e0[3], e1[3], alpha_index, alpha_index_bits);
+        match rotation {
+            1 => color.swap(0, 3),
+            2 => color.swap(1, 3),
+            3 => color.swap(2, 3),
+            _ => {}
+        }
+    }
+    texels
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_bc1_opaque() {
+        // Red and blue endpoints and the indices 0, 1, 2 and 3 for the first four texels
+        let block = [0x00, 0xF8, 0x1F, 0x00, 0xE4, 0x00, 0x00, 0x00];
+        let texels = decode_bc1(&block);
+        assert_eq!(texels[0], [255, 0, 0, 255]);
+        assert_eq!(texels[1], [0, 0, 255, 255]);
+        assert_eq!(texels[2], [170, 0, 85, 255]);
+        assert_eq!(texels[3], [85, 0, 170, 255]);
+        assert!(texels[4..].iter().all(|texel| *texel == [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_decode_bc1_transparent() {
+        // The first endpoint is smaller than the second, so index 3 is transparent
+        let block = [0x1F, 0x00, 0x00, 0xF8, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(decode_bc1(&block), [[0, 0, 0, 0]; 16]);
+    }
+
+    #[test]
+    fn test_decode_bc2() {
+        let mut block = [0u8; 16];
+        block[0] = 0x0F;
+        block[8..10].copy_from_slice(&[0xFF, 0xFF]);
+        let texels = decode_bc2(&block);
+        assert_eq!(texels[0], [255, 255, 255, 255]);
+        assert!(texels[1..].iter().all(|texel| *texel == [255, 255, 255, 0]));
+    }
+
+    #[test]
+    fn test_decode_bc3() {
+        // The alpha indices 0, 1 and 2 for the first three texels
+        let mut block = [0u8; 16];
+        block[0] = 255;
+        block[2] = 0x88;
+        block[8..10].copy_from_slice(&[0xFF, 0xFF]);
+        let texels = decode_bc3(&block);
+        assert_eq!(texels[0], [255, 255, 255, 255]);
+        assert_eq!(texels[1], [255, 255, 255, 0]);
+        assert_eq!(texels[2], [255, 255, 255, 218]);
+        assert!(texels[3..]
+            .iter()
+            .all(|texel| *texel == [255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_decode_etc2_rgb_individual() {
+        // A red left half and a black right half with the smallest modifier table, and the index 1 for the top left texel
+        let block = [0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+        let texels = decode_etc2_rgb(&block);
+        assert_eq!(texels[0], [255, 8, 8, 255]);
+        assert_eq!(texels[1], [255, 2, 2, 255]);
+        assert_eq!(texels[4], [255, 2, 2, 255]);
+        assert_eq!(texels[2], [2, 2, 2, 255]);
+        assert_eq!(texels[15], [2, 2, 2, 255]);
+    }
+
+    #[test]
+    fn test_decode_etc2_rgb_differential() {
+        // Both halves have the red base color 16 since the differences are zero
+        let block = [0x80, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(decode_etc2_rgb(&block), [[134, 2, 2, 255]; 16]);
+    }
+
+    #[test]
+    fn test_decode_etc2_rgba() {
+        // Base alpha 128 with multiplier 1 and modifier table 13, and the index 7 for the top left texel
+        let mut block = [0u8; 16];
+        block[0] = 128;
+        block[1] = 0x1D;
+        block[2] = 0xE0;
+        let texels = decode_etc2_rgba(&block);
+        assert_eq!(texels[0], [2, 2, 2, 137]);
+        assert!(texels[1..].iter().all(|texel| *texel == [2, 2, 2, 127]));
+    }
+
+    #[test]
+    fn test_decode_bc7_mode6() {
+        // Mode 6 with a red opaque first endpoint, a black almost opaque second endpoint and the index 15 for the second texel
+        let bits: u128 = (1 << 6) | (127 << 7) | (127 << 49) | (127 << 56) | (1 << 63) | (15 << 68);
+        let texels = decode_bc7(&bits.to_le_bytes());
+        assert_eq!(texels[0], [255, 1, 1, 255]);
+        assert_eq!(texels[1], [0, 0, 0, 254]);
+        assert!(texels[2..].iter().all(|texel| *texel == [255, 1, 1, 255]));
+    }
+
+    #[test]
+    fn test_decode_bc7_reserved_mode() {
+        assert_eq!(decode_bc7(&[0u8; 16]), [[0, 0, 0, 0]; 16]);
+    }
+
+    #[test]
+    fn test_decompress_partial_blocks() {
+        // Two blocks where only the first column of the second block is inside the texture
+        let red = [0x00, 0xF8, 0x00, 0xF8, 0x00, 0x00, 0x00, 0x00];
+        let blue = [0x1F, 0x00, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let texels = decompress_blocks(5, 3, &[red, blue].concat(), 8, decode_bc1);
+        assert_eq!(texels.len(), 15);
+        for y in 0..3 {
+            assert!(texels[y * 5..y * 5 + 4]
+                .iter()
+                .all(|texel| *texel == [255, 0, 0, 255]));
+            assert_eq!(texels[y * 5 + 4], [0, 0, 255, 255]);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use crate::core::texture::*;
 pub struct DepthTexture2D {
     context: Context,
     id: crate::context::Texture,
+    attachment: u32,
     width: u32,
     height: u32,
 }
@@ -25,6 +26,7 @@ impl DepthTexture2D {
         let texture = Self {
             context: context.clone(),
             id,
+            attachment: T::attachment(),
             width,
             height,
         };
@@ -73,7 +75,7 @@ impl DepthTexture2D {
         unsafe {
             self.context.framebuffer_texture_2d(
                 crate::context::FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                self.attachment,
                 crate::context::TEXTURE_2D,
                 Some(self.id),
                 0,
@@ -82,6 +84,7 @@ impl DepthTexture2D {
     }
 
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_texture(crate::context::TEXTURE_2D, Some(self.id));
@@ -6,6 +6,7 @@ use crate::core::texture::*;
 pub struct DepthTexture2DArray {
     context: Context,
     id: crate::context::Texture,
+    attachment: u32,
     width: u32,
     height: u32,
     depth: u32,
@@ -27,6 +28,7 @@ impl DepthTexture2DArray {
         let texture = Self {
             context: context.clone(),
             id,
+            attachment: T::attachment(),
             width,
             height,
             depth,
@@ -82,7 +84,7 @@ impl DepthTexture2DArray {
         unsafe {
             self.context.framebuffer_texture_layer(
                 crate::context::DRAW_FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                self.attachment,
                 Some(self.id),
                 0,
                 layer as i32,
@@ -91,6 +93,7 @@ impl DepthTexture2DArray {
     }
 
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_texture(crate::context::TEXTURE_2D_ARRAY, Some(self.id));
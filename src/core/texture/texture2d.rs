@@ -10,6 +10,8 @@ pub struct Texture2D {
     height: u32,
     number_of_mip_maps: u32,
     data_byte_size: usize,
+    compressed_format: Option<CompressedFormat>,
+    is_srgb: bool,
 }
 
 impl Texture2D {
@@ -67,8 +69,115 @@ impl Texture2D {
         wrap_s: Wrapping,
         wrap_t: Wrapping,
     ) -> Self {
-        let id = generate(context);
         let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height, None);
+        Self::new_empty_with_mip_maps::<T>(
+            context,
+            width,
+            height,
+            number_of_mip_maps,
+            min_filter,
+            mag_filter,
+            mip_map_filter,
+            wrap_s,
+            wrap_t,
+        )
+    }
+
+    ///
+    /// Constructs a new texture with the given 8 bit RGB or RGBA data, which is assumed to be in the sRGB color space, for example the albedo of a material.
+    /// The colors are converted to linear colors by the graphics hardware when sampled, which is faster and, in combination with linear filtering, more correct
+    /// than converting after sampling, see [Texture2D::is_srgb].
+    /// Other data is not in the sRGB color space, so a texture with other data is constructed as in [Texture2D::new].
+    ///
+    pub fn new_srgb(context: &Context, cpu_texture: &CpuTexture) -> Self {
+        let data = match cpu_texture.data {
+            TextureData::RgbU8(ref data) => data.iter().map(|c| [c[0], c[1], c[2], 255]).collect(),
+            TextureData::RgbaU8(ref data) => data.clone(),
+            _ => return Self::new(context, cpu_texture),
+        };
+        let mut texture = Self::new_empty_srgb(
+            context,
+            cpu_texture.width,
+            cpu_texture.height,
+            cpu_texture.min_filter,
+            cpu_texture.mag_filter,
+            cpu_texture.mip_map_filter,
+            cpu_texture.wrap_s,
+            cpu_texture.wrap_t,
+        );
+        texture.fill(&data);
+        texture
+    }
+
+    ///
+    /// Constructs a new empty texture with 8 bit RGBA data in the sRGB color space, see [Texture2D::is_srgb].
+    /// Linear colors written to this texture, for example when rendering with linear output enabled, see [Context::set_linear_output],
+    /// are converted to the sRGB color space by the graphics hardware, which preserves more precision in the dark colors than storing the linear colors in 8 bits.
+    ///
+    pub fn new_empty_srgb(
+        context: &Context,
+        width: u32,
+        height: u32,
+        min_filter: Interpolation,
+        mag_filter: Interpolation,
+        mip_map_filter: Option<Interpolation>,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+    ) -> Self {
+        let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height, None);
+        Self::new_empty_with_internal_format::<[u8; 4]>(
+            context,
+            width,
+            height,
+            number_of_mip_maps,
+            crate::context::SRGB8_ALPHA8,
+            min_filter,
+            mag_filter,
+            mip_map_filter,
+            wrap_s,
+            wrap_t,
+        )
+    }
+
+    // Constructs a new empty 2D texture with the given number of mip levels, which is not restricted to square power of two sizes
+    pub(in crate::core) fn new_empty_with_mip_maps<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+        number_of_mip_maps: u32,
+        min_filter: Interpolation,
+        mag_filter: Interpolation,
+        mip_map_filter: Option<Interpolation>,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+    ) -> Self {
+        Self::new_empty_with_internal_format::<T>(
+            context,
+            width,
+            height,
+            number_of_mip_maps,
+            T::internal_format(),
+            min_filter,
+            mag_filter,
+            mip_map_filter,
+            wrap_s,
+            wrap_t,
+        )
+    }
+
+    fn new_empty_with_internal_format<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+        number_of_mip_maps: u32,
+        internal_format: u32,
+        min_filter: Interpolation,
+        mag_filter: Interpolation,
+        mip_map_filter: Option<Interpolation>,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+    ) -> Self {
+        let id = generate(context);
         let texture = Self {
             context: context.clone(),
             id,
@@ -76,6 +185,8 @@ impl Texture2D {
             height,
             number_of_mip_maps,
             data_byte_size: std::mem::size_of::<T>(),
+            compressed_format: None,
+            is_srgb: internal_format == crate::context::SRGB8_ALPHA8,
         };
         texture.bind();
         set_parameters(
@@ -96,7 +207,7 @@ impl Texture2D {
             context.tex_storage_2d(
                 crate::context::TEXTURE_2D,
                 number_of_mip_maps as i32,
-                T::internal_format(),
+                internal_format,
                 width as i32,
                 height as i32,
             );
@@ -105,14 +216,138 @@ impl Texture2D {
         texture
     }
 
+    ///
+    /// Constructs a new texture with the given compressed data, which stays compressed in GPU memory.
+    /// If the compressed format is not supported on the graphics device, see [Capabilities::supports_compressed_format],
+    /// the first mip level is decompressed instead and the fallback is reported, see [Context::fallbacks].
+    /// Returns an error if the format is not supported and cannot be decompressed, which is the case for the [CompressedFormat::Astc] formats,
+    /// or if the size is larger than the maximum texture size, see [Capabilities::max_texture_size].
+    ///
+    /// **Note:** Compressed data cannot be flipped vertically when it is transferred to the GPU, as it is the case for uncompressed data,
+    /// so the texture is upside down compared to a texture constructed from a [CpuTexture] with the same image.
+    /// Flip the v texture coordinate when sampling the texture, for example using the transformation of a [Texture2DRef](crate::renderer::Texture2DRef).
+    /// A decompressed fallback texture has the same orientation as a compressed texture.
+    ///
+    pub fn new_compressed(
+        context: &Context,
+        cpu_texture: &CpuCompressedTexture,
+    ) -> Result<Self, CoreError> {
+        let format = cpu_texture.format;
+        let max_texture_size = context.capabilities().max_texture_size;
+        if cpu_texture.width > max_texture_size || cpu_texture.height > max_texture_size {
+            Err(CoreError::TextureTooLarge(
+                cpu_texture.width,
+                cpu_texture.height,
+                max_texture_size,
+            ))?;
+        }
+        if !context.capabilities().supports_compressed_format(format) {
+            let mut decompressed = cpu_texture.decompress()?;
+            context.report_fallback(
+                "compressed texture",
+                &format!("{:?} compression", format),
+                "decompressed RGBA data",
+            );
+            // Flip the rows, since they are flipped again when the data is transferred to the GPU
+            if let TextureData::RgbaU8(ref mut data) = decompressed.data {
+                flip_y(
+                    data,
+                    decompressed.width as usize,
+                    decompressed.height as usize,
+                );
+            }
+            return Ok(Self::new(context, &decompressed));
+        }
+
+        let number_of_mip_maps = if cpu_texture.mip_map_filter.is_some() {
+            cpu_texture.mip_levels.len() as u32
+        } else {
+            1
+        };
+        let texture = Self {
+            context: context.clone(),
+            id: generate(context),
+            width: cpu_texture.width,
+            height: cpu_texture.height,
+            number_of_mip_maps,
+            data_byte_size: 0,
+            compressed_format: Some(format),
+            is_srgb: false,
+        };
+        texture.bind();
+        set_parameters(
+            context,
+            crate::context::TEXTURE_2D,
+            cpu_texture.min_filter,
+            cpu_texture.mag_filter,
+            if number_of_mip_maps == 1 {
+                None
+            } else {
+                cpu_texture.mip_map_filter
+            },
+            cpu_texture.wrap_s,
+            cpu_texture.wrap_t,
+            None,
+        );
+        unsafe {
+            context.tex_storage_2d(
+                crate::context::TEXTURE_2D,
+                number_of_mip_maps as i32,
+                format.internal_format(),
+                texture.width as i32,
+                texture.height as i32,
+            );
+            for (level, data) in cpu_texture
+                .mip_levels
+                .iter()
+                .take(number_of_mip_maps as usize)
+                .enumerate()
+            {
+                context.compressed_tex_sub_image_2d(
+                    crate::context::TEXTURE_2D,
+                    level as i32,
+                    0,
+                    0,
+                    (texture.width >> level).max(1) as i32,
+                    (texture.height >> level).max(1) as i32,
+                    format.internal_format(),
+                    crate::context::CompressedPixelUnpackData::Slice(data),
+                );
+            }
+        }
+        Ok(texture)
+    }
+
+    ///
+    /// Returns whether or not the colors in this texture are stored in the sRGB color space and converted to linear colors by the graphics hardware when sampled,
+    /// see [Texture2D::new_srgb]. The built-in materials skip their own conversion from sRGB for such textures.
+    ///
+    pub fn is_srgb(&self) -> bool {
+        self.is_srgb
+    }
+
+    ///
+    /// Returns the compressed format of this texture or `None` if it is not compressed, see [Texture2D::new_compressed].
+    ///
+    pub fn compressed_format(&self) -> Option<CompressedFormat> {
+        self.compressed_format
+    }
+
     ///
     /// Fills this texture with the given data.
     ///
     /// # Panic
-    /// Will panic if the length of the data does not correspond to the width, height and format specified at construction.
+    /// Will panic if the length of the data does not correspond to the width, height and format specified at construction
+    /// or if the texture is compressed, see [Texture2D::new_compressed].
     /// It is therefore necessary to create a new texture if the texture size or format has changed.
     ///
     pub fn fill<T: TextureDataType>(&mut self, data: &[T]) {
+        if let Some(format) = self.compressed_format {
+            panic!(
+                "cannot fill a texture with the compressed format {:?}",
+                format
+            );
+        }
         check_data_length::<T>(self.width, self.height, 1, self.data_byte_size, data.len());
         self.bind();
         let mut data = data.to_owned();
@@ -155,8 +390,25 @@ impl Texture2D {
         self.height
     }
 
+    ///
+    /// Returns the number of bytes of GPU memory used by this texture, including all mip levels.
+    ///
+    pub fn memory_size(&self) -> usize {
+        (0..self.number_of_mip_maps)
+            .map(|level| {
+                let (width, height) = ((self.width >> level).max(1), (self.height >> level).max(1));
+                match self.compressed_format {
+                    // The size was validated when the texture was constructed
+                    Some(format) => format.level_bytes(width, height).unwrap_or(usize::MAX),
+                    None => width as usize * height as usize * self.data_byte_size,
+                }
+            })
+            .sum()
+    }
+
     pub(crate) fn generate_mip_maps(&self) {
-        if self.number_of_mip_maps > 1 {
+        // The mip levels of a compressed texture are given when it is constructed
+        if self.number_of_mip_maps > 1 && self.compressed_format.is_none() {
             self.bind();
             unsafe {
                 self.context.generate_mipmap(crate::context::TEXTURE_2D);
@@ -164,6 +416,23 @@ impl Texture2D {
         }
     }
 
+    // Restricts sampling to the given range of mip levels, for example to read from one mip level while writing to another
+    pub(in crate::core) fn set_mip_level_range(&self, base_level: u32, max_level: u32) {
+        self.bind();
+        unsafe {
+            self.context.tex_parameter_i32(
+                crate::context::TEXTURE_2D,
+                crate::context::TEXTURE_BASE_LEVEL,
+                base_level as i32,
+            );
+            self.context.tex_parameter_i32(
+                crate::context::TEXTURE_2D,
+                crate::context::TEXTURE_MAX_LEVEL,
+                max_level as i32,
+            );
+        }
+    }
+
     pub(in crate::core) fn bind_as_color_target(&self, channel: u32, mip_level: u32) {
         unsafe {
             self.context.framebuffer_texture_2d(
@@ -176,6 +445,7 @@ impl Texture2D {
         }
     }
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_texture(crate::context::TEXTURE_2D, Some(self.id));
@@ -299,6 +299,7 @@ impl Texture2DArray {
     }
 
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_texture(crate::context::TEXTURE_2D_ARRAY, Some(self.id));
@@ -66,6 +66,7 @@ impl Texture2DMultisample {
         }
     }
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_renderbuffer(crate::context::RENDERBUFFER, Some(self.id));
@@ -144,6 +144,40 @@ impl Texture3D {
         self.generate_mip_maps();
     }
 
+    ///
+    /// Fills the slice with the given depth index, ie. the 2D layer of texels with z coordinate `z`, with the given data.
+    /// Use this to upload large volumes one slice at a time, for example when loading a stack of medical images.
+    ///
+    /// # Panic
+    /// Will panic if the length of the data does not correspond to the width, height and format specified at construction or if the depth index is outside the texture.
+    ///
+    pub fn fill_slice<T: TextureDataType>(&mut self, z: u32, data: &[T]) {
+        if z >= self.depth {
+            panic!(
+                "cannot fill slice {} of a 3D texture with depth {}",
+                z, self.depth
+            );
+        }
+        check_data_length::<T>(self.width, self.height, 1, self.data_byte_size, data.len());
+        self.bind();
+        unsafe {
+            self.context.tex_sub_image_3d(
+                crate::context::TEXTURE_3D,
+                0,
+                0,
+                0,
+                z as i32,
+                self.width as i32,
+                self.height as i32,
+                1,
+                format_from_data_type::<T>(),
+                T::data_type(),
+                crate::context::PixelUnpackData::Slice(to_byte_slice(data)),
+            );
+        }
+        self.generate_mip_maps();
+    }
+
     /// The width of this texture.
     pub fn width(&self) -> u32 {
         self.width
@@ -168,6 +202,7 @@ impl Texture3D {
         }
     }
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_texture(crate::context::TEXTURE_3D, Some(self.id));
@@ -443,7 +443,17 @@ impl TextureCubeMap {
         context: &Context,
         cpu_texture: &CpuTexture,
     ) -> Self {
-        let texture_size = cpu_texture.width / 4;
+        Self::new_from_equirectangular_texture::<T>(context, &Texture2D::new(context, cpu_texture))
+    }
+
+    ///
+    /// Creates a new cube texture generated from the equirectangular texture on the GPU given as input, for example a panorama rendered with [TextureCubeMap::to_equirectangular].
+    ///
+    pub fn new_from_equirectangular_texture<T: PrimitiveDataType + TextureDataType>(
+        context: &Context,
+        map: &Texture2D,
+    ) -> Self {
+        let texture_size = map.width() / 4;
         let mut texture = Self::new_empty::<[T; 4]>(
             context,
             texture_size,
@@ -456,9 +466,7 @@ impl TextureCubeMap {
             Wrapping::ClampToEdge,
         );
 
-        {
-            let map = Texture2D::new(context, cpu_texture);
-            let fragment_shader_source = "
+        let fragment_shader_source = "
             uniform sampler2D equirectangularMap;
             in vec3 pos;
             layout (location = 0) out vec4 outColor;
@@ -470,28 +478,74 @@ impl TextureCubeMap {
                 outColor = texture(equirectangularMap, uv);
             }";
 
-            for side in CubeMapSide::iter() {
-                let viewport = Viewport::new_at_origin(texture_size, texture_size);
-                texture
-                    .as_color_target(&[side], None)
-                    .clear(ClearState::default())
-                    .write(|| {
-                        apply_cube_effect(
-                            context,
-                            side,
-                            fragment_shader_source,
-                            RenderStates::default(),
-                            viewport,
-                            |program| {
-                                program.use_texture("equirectangularMap", &map);
-                            },
-                        );
-                    });
-            }
+        for side in CubeMapSide::iter() {
+            let viewport = Viewport::new_at_origin(texture_size, texture_size);
+            texture
+                .as_color_target(&[side], None)
+                .clear(ClearState::default())
+                .write(|| {
+                    apply_cube_effect(
+                        context,
+                        side,
+                        fragment_shader_source,
+                        RenderStates::default(),
+                        viewport,
+                        |program| {
+                            program.use_texture("equirectangularMap", map);
+                        },
+                    );
+                });
         }
         texture
     }
 
+    ///
+    /// Creates a new equirectangular texture, ie. a panorama where the horizontal axis is the longitude and the vertical axis is the latitude,
+    /// with the given width and half the width as height from this cube texture.
+    /// This is the inverse of [TextureCubeMap::new_from_equirectangular_texture] and can for example be used to save a baked environment map or a rendered panorama,
+    /// see [ColorTarget::read].
+    ///
+    pub fn to_equirectangular<T: TextureDataType>(&self, width: u32) -> Texture2D {
+        let height = (width / 2).max(1);
+        let mut texture = Texture2D::new_empty::<T>(
+            &self.context,
+            width,
+            height,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            None,
+            Wrapping::Repeat,
+            Wrapping::ClampToEdge,
+        );
+        let fragment_shader_source = "
+            uniform samplerCube cubeMap;
+            in vec2 uvs;
+            layout (location = 0) out vec4 outColor;
+
+            void main()
+            {
+                float longitude = (uvs.x - 0.5) * 6.2831853;
+                float latitude = (uvs.y - 0.5) * 3.1415927;
+                vec3 v = vec3(cos(latitude) * cos(longitude), sin(latitude), cos(latitude) * sin(longitude));
+                outColor = texture(cubeMap, v);
+            }";
+        texture
+            .as_color_target(None)
+            .clear(ClearState::default())
+            .write(|| {
+                apply_effect(
+                    &self.context,
+                    fragment_shader_source,
+                    RenderStates::default(),
+                    Viewport::new_at_origin(width, height),
+                    |program| {
+                        program.use_texture_cube("cubeMap", self);
+                    },
+                );
+            });
+        texture
+    }
+
     ///
     /// Returns a [ColorTarget] which can be used to clear, write to and read from the given side and mip level of this texture.
     /// Combine this together with a [DepthTarget] with [RenderTarget::new] to be able to write to both a depth and color target at the same time.
@@ -551,6 +605,7 @@ impl TextureCubeMap {
     }
 
     pub(in crate::core) fn bind(&self) {
+        self.context.debug_assert_shared_with_current("the texture");
         unsafe {
             self.context
                 .bind_texture(crate::context::TEXTURE_CUBE_MAP, Some(self.id));
@@ -0,0 +1,695 @@
+//!
+//! Golden image tests, which render the built-in materials, effects and geometries into small images on a headless context
+//! and compare them to reference images, to detect when a change silently breaks the rendering of another feature.
+//!
+//! The reference images of the built-in cases are committed in the `tests/golden` directory of the repository, see [GOLDEN_IMAGES_DIRECTORY],
+//! and are created or updated by running the tests with the `golden-images` feature and the `THREE_D_UPDATE_GOLDEN_IMAGES` environment variable set.
+//!
+
+use crate::context::HasContext;
+use crate::*;
+use std::path::{Path, PathBuf};
+
+///
+/// The directory in the repository containing the committed golden images of the built-in cases, see [GoldenImageHarness::add_builtin_cases].
+///
+pub const GOLDEN_IMAGES_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+///
+/// Error when running the golden image tests, see [GoldenImageHarness::run].
+///
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum GoldenImageError {
+    #[error("error when rendering the golden images")]
+    HeadlessError(#[from] HeadlessError),
+    #[error("failed reading or writing the image {0}")]
+    Image(String, #[source] image::ImageError),
+    #[error("failed creating the directory {0}")]
+    Io(String, #[source] std::io::Error),
+}
+
+///
+/// A test case rendered by a [GoldenImageHarness] into an image which is compared to the golden image with the same name.
+///
+pub struct GoldenCase {
+    /// The name of the case, which is also the file name of the golden image without the `.png` extension.
+    pub name: String,
+    render: Box<dyn Fn(&Context, &RenderTarget, &Camera)>,
+}
+
+impl GoldenCase {
+    ///
+    /// Creates a new case with the given name.
+    /// The `render` closure is called with a cleared render target and a camera looking at the origin from the front, slightly from above and to the right,
+    /// and should render the case into the render target.
+    ///
+    pub fn new(
+        name: impl Into<String>,
+        render: impl Fn(&Context, &RenderTarget, &Camera) + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            render: Box::new(render),
+        }
+    }
+}
+
+///
+/// The allowed difference between a rendered image and a golden image, see [GoldenImageHarness::set_tolerance].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GoldenTolerance {
+    /// The largest difference of a color channel which is not counted as a difference.
+    pub max_channel_difference: u8,
+    /// The fraction of pixels, between 0 and 1, which are allowed to differ more than [GoldenTolerance::max_channel_difference] before the case fails.
+    pub max_differing_pixels: f32,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            max_channel_difference: 8,
+            max_differing_pixels: 0.01,
+        }
+    }
+}
+
+///
+/// The result of comparing the rendered image of a [GoldenCase] to the golden image.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoldenStatus {
+    /// The rendered image matches the golden image within the tolerance.
+    Passed {
+        /// The number of pixels which differ more than the tolerance.
+        differing_pixels: usize,
+        /// The largest difference of a color channel of a pixel.
+        max_difference: u8,
+    },
+    /// The rendered image does not match the golden image.
+    Failed {
+        /// The number of pixels which differ more than the tolerance.
+        differing_pixels: usize,
+        /// The largest difference of a color channel of a pixel.
+        max_difference: u8,
+    },
+    /// The golden image did not exist or [GoldenImageHarness::update] is enabled, so the rendered image was saved as the golden image.
+    Created,
+    /// The golden image does not exist.
+    Missing,
+}
+
+///
+/// The result of a single [GoldenCase].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoldenResult {
+    /// The name of the case.
+    pub name: String,
+    /// The result of the comparison.
+    pub status: GoldenStatus,
+}
+
+///
+/// The results of running all cases of a [GoldenImageHarness] on a graphics context.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenReport {
+    /// A description of the graphics backend the cases were rendered with, for example `OpenGL 4.6 Mesa 23.0`.
+    pub backend: String,
+    /// The tolerance used for the backend, see [GoldenImageHarness::tolerance].
+    pub tolerance: GoldenTolerance,
+    /// The result of each case in the order the cases were added.
+    pub results: Vec<GoldenResult>,
+}
+
+impl GoldenReport {
+    ///
+    /// Returns whether all cases passed or created a golden image.
+    ///
+    pub fn is_passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    ///
+    /// Returns the results of the cases which failed or are missing a golden image.
+    ///
+    pub fn failures(&self) -> impl Iterator<Item = &GoldenResult> {
+        self.results.iter().filter(|result| {
+            matches!(
+                result.status,
+                GoldenStatus::Failed { .. } | GoldenStatus::Missing
+            )
+        })
+    }
+
+    ///
+    /// Panics with a description of the failed cases if any case failed, for example to fail a test.
+    ///
+    pub fn assert_passed(&self) {
+        if !self.is_passed() {
+            panic!("{}", self);
+        }
+    }
+}
+
+impl std::fmt::Display for GoldenReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "golden images rendered with {} (tolerance {}, {}% of the pixels):",
+            self.backend,
+            self.tolerance.max_channel_difference,
+            self.tolerance.max_differing_pixels * 100.0
+        )?;
+        for result in self.results.iter() {
+            match result.status {
+                GoldenStatus::Passed { .. } => writeln!(f, "  {}: passed", result.name)?,
+                GoldenStatus::Failed {
+                    differing_pixels,
+                    max_difference,
+                } => writeln!(
+                    f,
+                    "  {}: FAILED, {} pixels differ by up to {}",
+                    result.name, differing_pixels, max_difference
+                )?,
+                GoldenStatus::Created => writeln!(f, "  {}: created", result.name)?,
+                GoldenStatus::Missing => writeln!(f, "  {}: MISSING golden image", result.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Renders a set of [GoldenCase]s into small images and compares them to the golden images with the same name in a directory,
+/// for example to check in continuous integration that a change does not break the rendering of the built-in materials and effects, see [GoldenImageHarness::add_builtin_cases].
+///
+/// The same golden images are used for all graphics backends, so the comparison allows small differences which depend on the backend, see [GoldenImageHarness::tolerance].
+/// When a case fails, the rendered image is saved as `<name>.actual.png` and an image highlighting the differing pixels as `<name>.diff.png` next to the golden image.
+/// Missing golden images are created when [GoldenImageHarness::update] is enabled, which is the default if the `THREE_D_UPDATE_GOLDEN_IMAGES` environment variable is set.
+///
+pub struct GoldenImageHarness {
+    directory: PathBuf,
+    /// The width of the rendered images in pixels.
+    pub width: u32,
+    /// The height of the rendered images in pixels.
+    pub height: u32,
+    /// Whether to save the rendered images as the golden images instead of comparing them, which is used to create or update the golden images.
+    pub update: bool,
+    default_tolerance: GoldenTolerance,
+    tolerances: Vec<(String, GoldenTolerance)>,
+    cases: Vec<GoldenCase>,
+}
+
+impl GoldenImageHarness {
+    ///
+    /// Creates a new harness without any cases which reads and writes the golden images in the given directory.
+    /// The golden images are expected to be rendered with desktop OpenGL, so OpenGL ES and WebGL, which often render with a lower precision, are allowed larger differences by default.
+    ///
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            width: 64,
+            height: 64,
+            update: std::env::var_os("THREE_D_UPDATE_GOLDEN_IMAGES").is_some(),
+            default_tolerance: GoldenTolerance::default(),
+            tolerances: vec![(
+                "OpenGL ES".to_owned(),
+                GoldenTolerance {
+                    max_channel_difference: 16,
+                    max_differing_pixels: 0.03,
+                },
+            )],
+            cases: Vec::new(),
+        }
+    }
+
+    ///
+    /// Sets the tolerance used when the description of the graphics backend, see [GoldenReport::backend], starts with the given prefix,
+    /// for example `OpenGL ES` for all OpenGL ES versions or `OpenGL 3.3` for a specific OpenGL version.
+    /// If several prefixes match, the longest is used.
+    ///
+    pub fn set_tolerance(
+        &mut self,
+        backend: impl Into<String>,
+        tolerance: GoldenTolerance,
+    ) -> &mut Self {
+        let backend = backend.into();
+        self.tolerances.retain(|(prefix, _)| *prefix != backend);
+        self.tolerances.push((backend, tolerance));
+        self
+    }
+
+    ///
+    /// Sets the tolerance used for the graphics backends without a specific tolerance, see [GoldenImageHarness::set_tolerance].
+    ///
+    pub fn set_default_tolerance(&mut self, tolerance: GoldenTolerance) -> &mut Self {
+        self.default_tolerance = tolerance;
+        self
+    }
+
+    ///
+    /// Returns the tolerance used for the graphics backend with the given description, see [GoldenImageHarness::set_tolerance].
+    ///
+    pub fn tolerance(&self, backend: &str) -> GoldenTolerance {
+        self.tolerances
+            .iter()
+            .filter(|(prefix, _)| backend.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, tolerance)| *tolerance)
+            .unwrap_or(self.default_tolerance)
+    }
+
+    ///
+    /// Returns the directory containing the golden images.
+    ///
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    ///
+    /// Adds a case.
+    ///
+    pub fn add_case(&mut self, case: GoldenCase) -> &mut Self {
+        self.cases.push(case);
+        self
+    }
+
+    ///
+    /// Adds a case for each combination of the built-in materials and a sphere and a cube, an instanced mesh and the built-in effects, see [GoldenImageHarness::builtin_cases].
+    ///
+    pub fn add_builtin_cases(&mut self) -> &mut Self {
+        self.cases.extend(Self::builtin_cases());
+        self
+    }
+
+    ///
+    /// Returns the built-in cases.
+    ///
+    pub fn builtin_cases() -> Vec<GoldenCase> {
+        let mut cases = Vec::new();
+        for (geometry, cpu_mesh) in [("sphere", CpuMesh::sphere(16)), ("cube", CpuMesh::cube())] {
+            cases.push(mesh_case(
+                format!("color_{}", geometry),
+                cpu_mesh.clone(),
+                ColorMaterial {
+                    color: Color::new_opaque(200, 80, 40),
+                    ..Default::default()
+                },
+            ));
+            cases.push(mesh_case(
+                format!("normal_{}", geometry),
+                cpu_mesh.clone(),
+                NormalMaterial::default(),
+            ));
+            cases.push(mesh_case(
+                format!("depth_{}", geometry),
+                cpu_mesh.clone(),
+                DepthMaterial::default(),
+            ));
+            cases.push(mesh_case(
+                format!("position_{}", geometry),
+                cpu_mesh.clone(),
+                PositionMaterial::default(),
+            ));
+            cases.push(mesh_case(
+                format!("uv_{}", geometry),
+                cpu_mesh.clone(),
+                UVMaterial::default(),
+            ));
+            cases.push(mesh_case(
+                format!("physical_{}", geometry),
+                cpu_mesh.clone(),
+                PhysicalMaterial {
+                    albedo: Color::new_opaque(200, 80, 40),
+                    metallic: 0.2,
+                    roughness: 0.5,
+                    ..Default::default()
+                },
+            ));
+            cases.push(mesh_case(
+                format!("orm_{}", geometry),
+                cpu_mesh,
+                ORMMaterial::default(),
+            ));
+        }
+
+        cases.push(GoldenCase::new(
+            "instanced_cube",
+            |context, target, camera| {
+                let instances = Instances {
+                    transformations: vec![
+                        Mat4::from_translation(vec3(-0.6, 0.0, 0.0)) * Mat4::from_scale(0.4),
+                        Mat4::from_translation(vec3(0.6, 0.0, 0.0)) * Mat4::from_scale(0.4),
+                    ],
+                    colors: Some(vec![Color::RED, Color::GREEN]),
+                    ..Default::default()
+                };
+                let model = Gm::new(
+                    InstancedMesh::new(context, &instances, &CpuMesh::cube()),
+                    PhysicalMaterial::default(),
+                );
+                let (ambient, directional) = lights(context);
+                target.render(camera, &model, &[&ambient, &directional]);
+            },
+        ));
+
+        cases.push(GoldenCase::new("fxaa_effect", |context, target, camera| {
+            let mut color_texture = Texture2D::new_empty::<[u8; 4]>(
+                context,
+                target.width(),
+                target.height(),
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            let mut depth_texture = DepthTexture2D::new::<f32>(
+                context,
+                target.width(),
+                target.height(),
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            let model = Gm::new(
+                Mesh::new(context, &CpuMesh::cube()),
+                ColorMaterial::default(),
+            );
+            RenderTarget::new(
+                color_texture.as_color_target(None),
+                depth_texture.as_depth_target(),
+            )
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .render(camera, &model, &[]);
+            target.write(|| {
+                FxaaEffect::default().apply(context, ColorTexture::Single(&color_texture))
+            });
+        }));
+
+        cases.push(GoldenCase::new("fog_effect", |context, target, camera| {
+            let mut depth_texture = DepthTexture2D::new::<f32>(
+                context,
+                target.width(),
+                target.height(),
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            let model = Gm::new(
+                Mesh::new(context, &CpuMesh::sphere(16)),
+                PhysicalMaterial::default(),
+            );
+            depth_texture
+                .as_depth_target()
+                .clear(ClearState::depth(1.0))
+                .render(camera, &model, &[]);
+            let (ambient, directional) = lights(context);
+            target.render(camera, &model, &[&ambient, &directional]);
+            target.write(|| {
+                FogEffect::default().apply(
+                    context,
+                    0.0,
+                    camera,
+                    DepthTexture::Single(&depth_texture),
+                )
+            });
+        }));
+        cases
+    }
+
+    ///
+    /// Renders all cases on the given context and compares them to the golden images.
+    /// Returns an error if rendering fails or if an image cannot be read or written, while differences to the golden images are reported in the returned [GoldenReport].
+    ///
+    pub fn run(&self, context: &Context) -> Result<GoldenReport, GoldenImageError> {
+        std::fs::create_dir_all(&self.directory)
+            .map_err(|e| GoldenImageError::Io(self.directory.display().to_string(), e))?;
+        let version = context.version();
+        let backend = format!(
+            "OpenGL{} {}.{} {}",
+            if version.is_embedded { " ES" } else { "" },
+            version.major,
+            version.minor,
+            version.vendor_info
+        );
+        let tolerance = self.tolerance(&backend);
+
+        let camera = Camera::new_perspective(
+            Viewport::new_at_origin(self.width, self.height),
+            vec3(1.2, 1.0, 2.4),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            10.0,
+        );
+        let mut color_texture = Texture2D::new_empty::<[u8; 4]>(
+            context,
+            self.width,
+            self.height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth_texture = DepthTexture2D::new::<f32>(
+            context,
+            self.width,
+            self.height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+
+        let mut results = Vec::new();
+        for case in self.cases.iter() {
+            let target = RenderTarget::new(
+                color_texture.as_color_target(None),
+                depth_texture.as_depth_target(),
+            );
+            target.clear(ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0));
+            (case.render)(context, &target, &camera);
+            let image = image::RgbaImage::from_raw(
+                self.width,
+                self.height,
+                target
+                    .read_color::<[u8; 4]>()
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+            )
+            .unwrap();
+            results.push(GoldenResult {
+                name: case.name.clone(),
+                status: self.compare(&case.name, image, tolerance)?,
+            });
+        }
+        Ok(GoldenReport {
+            backend,
+            tolerance,
+            results,
+        })
+    }
+
+    fn compare(
+        &self,
+        name: &str,
+        actual: image::RgbaImage,
+        tolerance: GoldenTolerance,
+    ) -> Result<GoldenStatus, GoldenImageError> {
+        let save = |image: &image::RgbaImage, path: PathBuf| {
+            image
+                .save(&path)
+                .map_err(|e| GoldenImageError::Image(path.display().to_string(), e))
+        };
+        let path = self.directory.join(format!("{}.png", name));
+        if self.update {
+            save(&actual, path)?;
+            return Ok(GoldenStatus::Created);
+        }
+        if !path.exists() {
+            return Ok(GoldenStatus::Missing);
+        }
+        let golden = image::open(&path)
+            .map_err(|e| GoldenImageError::Image(path.display().to_string(), e))?
+            .to_rgba8();
+
+        let (status, diff) = compare_images(&golden, &actual, tolerance);
+        if let GoldenStatus::Failed { .. } = status {
+            save(&actual, self.directory.join(format!("{}.actual.png", name)))?;
+            save(&diff, self.directory.join(format!("{}.diff.png", name)))?;
+        }
+        Ok(status)
+    }
+}
+
+///
+/// Compares the actual image to the golden image and returns the status and an image highlighting the pixels which differ more than the tolerance.
+///
+fn compare_images(
+    golden: &image::RgbaImage,
+    actual: &image::RgbaImage,
+    tolerance: GoldenTolerance,
+) -> (GoldenStatus, image::RgbaImage) {
+    let mut diff =
+        image::RgbaImage::from_pixel(actual.width(), actual.height(), image::Rgba([0, 0, 0, 255]));
+    if golden.dimensions() != actual.dimensions() {
+        return (
+            GoldenStatus::Failed {
+                differing_pixels: (actual.width() * actual.height()) as usize,
+                max_difference: u8::MAX,
+            },
+            diff,
+        );
+    }
+    let mut differing_pixels = 0;
+    let mut max_difference = 0;
+    for (x, y, pixel) in actual.enumerate_pixels() {
+        let difference = pixel
+            .0
+            .iter()
+            .zip(golden.get_pixel(x, y).0.iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        if difference > tolerance.max_channel_difference {
+            differing_pixels += 1;
+            diff.put_pixel(x, y, image::Rgba([255, 0, 0, 255]));
+        }
+    }
+
+    let allowed =
+        (tolerance.max_differing_pixels * (actual.width() * actual.height()) as f32) as usize;
+    let status = if differing_pixels > allowed {
+        GoldenStatus::Failed {
+            differing_pixels,
+            max_difference,
+        }
+    } else {
+        GoldenStatus::Passed {
+            differing_pixels,
+            max_difference,
+        }
+    };
+    (status, diff)
+}
+
+fn mesh_case(
+    name: String,
+    cpu_mesh: CpuMesh,
+    material: impl Material + Clone + 'static,
+) -> GoldenCase {
+    GoldenCase::new(name, move |context, target, camera| {
+        let model = Gm::new(Mesh::new(context, &cpu_mesh), material.clone());
+        let (ambient, directional) = lights(context);
+        target.render(camera, &model, &[&ambient, &directional]);
+    })
+}
+
+fn lights(context: &Context) -> (AmbientLight, DirectionalLight) {
+    (
+        AmbientLight::new(context, 0.4, Color::WHITE),
+        DirectionalLight::new(context, 2.0, Color::WHITE, &vec3(-0.5, -1.0, -0.8)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builtin_cases_match_the_committed_golden_images() {
+        // Skipped when no graphics context can be created, for example on a machine without a GPU
+        let Ok(context) = HeadlessContext::new() else {
+            return;
+        };
+        let mut harness = GoldenImageHarness::new(GOLDEN_IMAGES_DIRECTORY);
+        harness.add_builtin_cases();
+        let report = harness.run(&context).unwrap();
+        assert_eq!(
+            report.results.len(),
+            GoldenImageHarness::builtin_cases().len()
+        );
+        report.assert_passed();
+    }
+
+    #[test]
+    fn tolerance_is_chosen_by_the_longest_matching_backend_prefix() {
+        let mut harness = GoldenImageHarness::new(GOLDEN_IMAGES_DIRECTORY);
+        let gl33 = GoldenTolerance {
+            max_channel_difference: 12,
+            max_differing_pixels: 0.02,
+        };
+        let gles30 = GoldenTolerance {
+            max_channel_difference: 24,
+            max_differing_pixels: 0.05,
+        };
+        harness
+            .set_tolerance("OpenGL 3.3", gl33)
+            .set_tolerance("OpenGL ES 3.0", gles30);
+
+        assert_eq!(harness.tolerance("OpenGL 3.3 Mesa 23.0"), gl33);
+        assert_eq!(
+            harness.tolerance("OpenGL 4.6 NVIDIA"),
+            GoldenTolerance::default()
+        );
+        assert_eq!(harness.tolerance("OpenGL ES 3.0 WebGL 2.0"), gles30);
+        assert_eq!(
+            harness.tolerance("OpenGL ES 3.2 Mali"),
+            GoldenTolerance {
+                max_channel_difference: 16,
+                max_differing_pixels: 0.03,
+            }
+        );
+    }
+
+    #[test]
+    fn images_differing_within_the_tolerance_pass() {
+        let golden = image::RgbaImage::from_pixel(10, 10, image::Rgba([100, 100, 100, 255]));
+        let mut actual = golden.clone();
+        actual.put_pixel(0, 0, image::Rgba([108, 100, 100, 255]));
+        actual.put_pixel(1, 0, image::Rgba([100, 50, 100, 255]));
+
+        let tolerance = GoldenTolerance {
+            max_channel_difference: 8,
+            max_differing_pixels: 0.01,
+        };
+        let (status, diff) = compare_images(&golden, &actual, tolerance);
+        assert_eq!(
+            status,
+            GoldenStatus::Passed {
+                differing_pixels: 1,
+                max_difference: 50,
+            }
+        );
+        assert_eq!(*diff.get_pixel(0, 0), image::Rgba([0, 0, 0, 255]));
+        assert_eq!(*diff.get_pixel(1, 0), image::Rgba([255, 0, 0, 255]));
+
+        actual.put_pixel(2, 0, image::Rgba([100, 100, 90, 255]));
+        let (status, _) = compare_images(&golden, &actual, tolerance);
+        assert_eq!(
+            status,
+            GoldenStatus::Failed {
+                differing_pixels: 2,
+                max_difference: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn images_with_different_sizes_fail() {
+        let golden = image::RgbaImage::new(10, 10);
+        let actual = image::RgbaImage::new(10, 8);
+        let (status, _) = compare_images(&golden, &actual, GoldenTolerance::default());
+        assert_eq!(
+            status,
+            GoldenStatus::Failed {
+                differing_pixels: 80,
+                max_difference: u8::MAX,
+            }
+        );
+    }
+}
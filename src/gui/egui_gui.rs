@@ -15,6 +15,7 @@ pub struct GUI {
     output: RefCell<Option<egui::FullOutput>>,
     viewport: Viewport,
     modifiers: Modifiers,
+    copied_text: String,
 }
 
 impl GUI {
@@ -36,6 +37,7 @@ impl GUI {
             output: RefCell::new(None),
             viewport: Viewport::new_at_origin(1, 1),
             modifiers: Modifiers::default(),
+            copied_text: String::new(),
         }
     }
 
@@ -157,6 +159,9 @@ impl GUI {
                         }
                     }
                     Event::Text(text) => Some(egui::Event::Text(text.clone())),
+                    Event::Copy => Some(egui::Event::Copy),
+                    Event::Cut => Some(egui::Event::Cut),
+                    Event::Paste(text) => Some(egui::Event::Paste(text.clone())),
                     Event::MouseLeave => Some(egui::Event::PointerGone),
                     Event::MouseWheel { delta, handled, .. } => {
                         if !handled {
@@ -176,7 +181,9 @@ impl GUI {
 
         self.egui_context.begin_frame(egui_input);
         callback(&self.egui_context);
-        *self.output.borrow_mut() = Some(self.egui_context.end_frame());
+        let output = self.egui_context.end_frame();
+        self.copied_text = output.platform_output.copied_text.clone();
+        *self.output.borrow_mut() = Some(output);
 
         for event in events.iter_mut() {
             if let Event::ModifiersChange { modifiers } = event {
@@ -204,6 +211,11 @@ impl GUI {
                     } => {
                         *handled = true;
                     }
+                    Event::Touch {
+                        ref mut handled, ..
+                    } => {
+                        *handled = true;
+                    }
                     _ => {}
                 }
             }
@@ -227,6 +239,18 @@ impl GUI {
         self.egui_context.wants_pointer_input() || self.egui_context.wants_keyboard_input()
     }
 
+    ///
+    /// Returns the text copied or cut in the GUI in the last [update](Self::update), if any, which should be written to the clipboard,
+    /// for example by setting it as the `copied_text` of the `FrameOutput` when using the default window.
+    ///
+    pub fn copied_text(&self) -> Option<&str> {
+        if self.copied_text.is_empty() {
+            None
+        } else {
+            Some(&self.copied_text)
+        }
+    }
+
     ///
     /// Render the GUI defined in the [update](Self::update) function.
     /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
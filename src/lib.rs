@@ -25,5 +25,9 @@ pub use renderer::*;
 pub mod window;
 pub use window::*;
 
+#[cfg(all(feature = "golden-images", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(feature = "golden-images"))]
+pub mod golden;
+
 mod gui;
 pub use gui::*;
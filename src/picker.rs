@@ -1,8 +1,17 @@
 //! This module contains functionality for picking objects in a scene.
 
-use three_d_asset::{Camera, PixelPoint, Vec3};
+use three_d_asset::{
+    Camera, Indices, Mat4, PixelPoint, ProjectionType, TriMesh as CpuMesh, Vec2, Vec3,
+};
 
-use crate::{ColorMaterial, Context, DepthMaterial, Geometry};
+use crate::{
+    Context, DepthMaterial, FragmentAttributes, FragmentShader, Geometry, Light, Material,
+    MaterialType, NormalMaterial, PositionMaterial, Program, RenderStates, UVMaterial,
+};
+
+mod interaction;
+#[doc(inline)]
+pub use interaction::*;
 
 ///
 /// A trait that allows for objects to be picked in a collection of gemetries
@@ -22,12 +31,143 @@ pub trait Pick {
         pixel: impl Into<PixelPoint> + Copy,
         geometries: &[&dyn Geometry],
     ) -> Option<Self::PickResult>;
+
+    ///
+    /// Same as [Pick::pick] except that the given filter decides which of the geometries can be picked and in which order they are picked.
+    /// The geometries with the highest priority are picked first and the geometries with a lower priority are only picked if none of those are hit,
+    /// so for example gizmo handles can be picked even when they are behind a scene object.
+    /// The indices in the result refer to the given slice of geometries, also for the geometries which are skipped by the filter.
+    ///
+    fn pick_filtered(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+        filter: &PickFilter,
+    ) -> Option<Self::PickResult> {
+        filter
+            .groups(geometries.len())
+            .into_iter()
+            .find_map(|indices| {
+                let group: Vec<&dyn Geometry> = indices.iter().map(|i| geometries[*i]).collect();
+                self.pick(camera, pixel, &group)
+                    .map(|result| Self::remap_indices(result, &indices))
+            })
+    }
+
+    ///
+    /// Replaces each index into the slice of geometries in the given result, for example [ObjectPick::index], with the index at that position in the given indices.
+    /// This is used by [Pick::pick_filtered] to map the indices in the result of a subset of the geometries to the indices of all the geometries,
+    /// so it must be implemented if the result contains indices of the geometries.
+    ///
+    fn remap_indices(result: Self::PickResult, _indices: &[usize]) -> Self::PickResult {
+        result
+    }
+}
+
+///
+/// Decides which geometries can be picked and in which order when using [Pick::pick_filtered], for example to skip invisible or locked objects in an editor
+/// or to pick gizmo handles before the scene objects.
+/// The callbacks are given the index of the geometry in the slice of geometries passed to the pick method.
+///
+#[derive(Default)]
+pub struct PickFilter<'a> {
+    filter: Option<Box<dyn Fn(usize) -> bool + 'a>>,
+    priority: Option<Box<dyn Fn(usize) -> i32 + 'a>>,
+}
+
+impl<'a> PickFilter<'a> {
+    ///
+    /// Creates a new filter where all geometries can be picked and have the same priority.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Only the geometries for which the given callback returns true can be picked.
+    ///
+    pub fn with_filter(mut self, filter: impl Fn(usize) -> bool + 'a) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    ///
+    /// The geometries are picked in the order of the priority returned by the given callback, starting with the highest priority. The default priority is 0.
+    ///
+    pub fn with_priority(mut self, priority: impl Fn(usize) -> i32 + 'a) -> Self {
+        self.priority = Some(Box::new(priority));
+        self
+    }
+
+    ///
+    /// Returns whether or not the geometry with the given index can be picked.
+    ///
+    pub fn is_pickable(&self, index: usize) -> bool {
+        self.filter.as_ref().map(|f| f(index)).unwrap_or(true)
+    }
+
+    ///
+    /// Returns the priority of the geometry with the given index.
+    ///
+    pub fn priority(&self, index: usize) -> i32 {
+        self.priority.as_ref().map(|p| p(index)).unwrap_or(0)
+    }
+
+    // Returns the indices of the pickable geometries grouped by priority, starting with the highest priority
+    fn groups(&self, count: usize) -> Vec<Vec<usize>> {
+        let mut indices: Vec<(i32, usize)> = (0..count)
+            .filter(|i| self.is_pickable(*i))
+            .map(|i| (-self.priority(i), i))
+            .collect();
+        indices.sort_unstable();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut previous = None;
+        for (priority, index) in indices {
+            if previous != Some(priority) {
+                groups.push(Vec::new());
+                previous = Some(priority);
+            }
+            groups.last_mut().unwrap().push(index);
+        }
+        groups
+    }
+}
+
+///
+/// One of the clipping planes of a camera, see [LocationPick::clipped_by].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipPlane {
+    /// The near plane at `z_near` from the camera.
+    Near,
+    /// The far plane at `z_far` from the camera.
+    Far,
+}
+
+///
+/// The result of a [LocationPicker::pick_location] operation.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocationPick {
+    /// The picked position in world space.
+    pub position: Vec3,
+    /// The distance from the camera position to the picked position.
+    pub distance: f32,
+    /// The distance from the camera position to the picked position along the view direction of the camera, which is compared to `z_near` and `z_far` when clipping.
+    pub depth: f32,
+    /// The clipping plane which hides the picked position or `None` if the picked position is visible at the pixel.
+    pub clipped_by: Option<ClipPlane>,
 }
 
 ///
 /// A picker which returns the location in the 3D scene shown at a pixel on the screen.
 /// This picker can be used to get a point on the surface of a 3D model for example.
 ///
+/// The geometries are rendered with the same vertex shaders and the same camera as when they are rendered to the screen, only restricted to the picked pixel,
+/// so animations, morph targets, instancing, displacement and view dependent geometry like sprites, heightfield levels of detail and lines with a width in pixels
+/// are picked exactly where they appear on the screen.
+///
 pub struct LocationPicker {
     context: Context,
 }
@@ -43,75 +183,66 @@ impl LocationPicker {
     }
 
     ///
-    /// Finds the closest intersection between a ray starting at the given position in the given direction and the given geometries.
+    /// Finds the closest intersection between a ray starting at the given origin in the given direction and the given geometries,
+    /// for example to place an object dropped on the scene or to test the line of sight between two points.
+    /// The direction does not need to be normalized.
     /// Returns ```None``` if no geometry was hit before the given maximum depth.
     ///
-    fn ray_intersect(
+    pub fn intersect_ray(
         &self,
-        position: Vec3,
+        origin: Vec3,
         direction: Vec3,
         max_depth: f32,
         geometries: impl IntoIterator<Item = impl Geometry>,
     ) -> Option<Vec3> {
-        use crate::core::*;
-        let viewport = Viewport::new_at_origin(1, 1);
-        let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
-            direction.cross(vec3(0.0, 1.0, 0.0))
-        } else {
-            direction.cross(vec3(1.0, 0.0, 0.0))
-        };
-        let camera = Camera::new_orthographic(
-            viewport,
-            position,
-            position + direction * max_depth,
-            up,
-            0.01,
-            0.0,
-            max_depth,
-        );
-        let mut texture = Texture2D::new_empty::<f32>(
-            &self.context,
-            viewport.width,
-            viewport.height,
-            Interpolation::Nearest,
-            Interpolation::Nearest,
-            None,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
-        let mut depth_texture = DepthTexture2D::new::<f32>(
+        render_position(
             &self.context,
-            viewport.width,
-            viewport.height,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
-        let depth_material = DepthMaterial {
-            render_states: RenderStates {
-                write_mask: WriteMask {
-                    red: true,
-                    ..WriteMask::DEPTH
-                },
-                ..Default::default()
-            },
-            ..Default::default()
-        };
-        let depth = RenderTarget::new(
-            texture.as_color_target(None),
-            depth_texture.as_depth_target(),
+            &ray_camera(origin, direction, max_depth),
+            geometries,
         )
-        .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
-        .write(|| {
-            for geometry in geometries {
-                geometry.render_with_material(&depth_material, &camera, &[]);
+    }
+
+    ///
+    /// Same as [Pick::pick] except that the distance and depth of the picked position is returned as well.
+    /// If no geometry is visible at the pixel, the geometries in front of the near plane and behind the far plane of the camera are picked instead,
+    /// where [LocationPick::clipped_by] tells which plane clipped the picked position, so a geometry which is too far away can be distinguished from no geometry.
+    /// Returns ```None``` if no geometry is hit along the ray through the pixel.
+    ///
+    pub fn pick_location(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<LocationPick> {
+        let mut pixel_camera = pixel_camera(camera, pixel);
+        if let Some(position) = render_position(&self.context, &pixel_camera, geometries) {
+            return Some(location_pick(camera, position));
+        }
+
+        // Render again with the near and far planes moved to find the clipped geometries
+        let (z_near, z_far) = (camera.z_near(), camera.z_far());
+        match camera.projection_type() {
+            ProjectionType::Perspective { field_of_view_y } => pixel_camera
+                .set_perspective_projection(
+                    *field_of_view_y,
+                    z_near / CLIP_EXTENSION,
+                    z_far * CLIP_EXTENSION,
+                ),
+            ProjectionType::Orthographic { height } => {
+                let range = (z_far - z_near).max(1.0) * CLIP_EXTENSION;
+                pixel_camera.set_orthographic_projection(*height, z_near - range, z_far + range)
             }
-        })
-        .read_color()[0];
-        if depth < 1.0 {
-            Some(position + direction * depth * max_depth)
-        } else {
-            None
+            #[allow(unreachable_patterns)]
+            _ => return None,
         }
+        let position = render_position(&self.context, &pixel_camera, geometries)?;
+        let mut pick = location_pick(camera, position);
+        pick.clipped_by = Some(if pick.depth < z_near {
+            ClipPlane::Near
+        } else {
+            ClipPlane::Far
+        });
+        Some(pick)
     }
 }
 
@@ -129,27 +260,241 @@ impl Pick for LocationPicker {
         pixel: impl Into<PixelPoint> + Copy,
         geometries: &[&dyn Geometry],
     ) -> Option<Vec3> {
-        let pos = camera.position_at_pixel(pixel);
-        let dir = camera.view_direction_at_pixel(pixel);
-        self.ray_intersect(
-            pos + dir * camera.z_near(),
-            dir,
-            camera.z_far() - camera.z_near(),
-            geometries,
+        render_position(&self.context, &pixel_camera(camera, pixel), geometries)
+    }
+}
+
+// How much further the near and far planes are moved when looking for clipped geometries
+const CLIP_EXTENSION: f32 = 100.0;
+
+fn location_pick(camera: &Camera, position: Vec3) -> LocationPick {
+    use crate::core::*;
+    LocationPick {
+        position,
+        distance: position.distance(*camera.position()),
+        depth: (position - *camera.position()).dot(camera.view_direction()),
+        clipped_by: None,
+    }
+}
+
+///
+/// Returns a copy of the camera where the viewport is moved so that the given pixel is at the origin,
+/// which renders exactly what is shown in that pixel into a single pixel render target.
+///
+fn pixel_camera(camera: &Camera, pixel: impl Into<PixelPoint>) -> Camera {
+    region_camera(camera, pixel, 0)
+}
+
+///
+/// Returns a copy of the camera where the viewport is moved so that the square of pixels within the given radius of the given pixel starts at the origin,
+/// which renders exactly what is shown in those pixels into a render target with a width and height of `2 * radius + 1`.
+///
+fn region_camera(camera: &Camera, pixel: impl Into<PixelPoint>, radius: u32) -> Camera {
+    let pixel = pixel.into();
+    let viewport = camera.viewport();
+    let mut pixel_camera = camera.clone();
+    pixel_camera.set_viewport(crate::core::Viewport {
+        x: viewport.x - pixel.x.floor() as i32 + radius as i32,
+        y: viewport.y - pixel.y.floor() as i32 + radius as i32,
+        width: viewport.width,
+        height: viewport.height,
+    });
+    pixel_camera
+}
+
+///
+/// A constraint on how a dragged point moves, used by [DragPlane] or directly via [DragConstraint::solve] in custom manipulation tools.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DragConstraint {
+    /// The point moves freely on the plane facing the camera.
+    ScreenPlane,
+    /// The point moves along the line through the start point with the given direction in world space.
+    Axis(Vec3),
+    /// The point moves on the plane through the start point with the given normal in world space.
+    Plane(Vec3),
+}
+
+impl DragConstraint {
+    ///
+    /// Returns the constrained translation in world space from the given start point to where the given ray hits the constraint,
+    /// where the view direction is the direction the camera is looking, used for the screen plane and for choosing the plane through an axis.
+    /// Returns ```None``` if the ray is parallel to the constraint or if the constraint is hit behind the ray origin.
+    ///
+    pub fn solve(
+        &self,
+        start: Vec3,
+        ray_origin: Vec3,
+        ray_direction: Vec3,
+        view_direction: Vec3,
+    ) -> Option<Vec3> {
+        use crate::core::*;
+        let normal = match *self {
+            DragConstraint::ScreenPlane => view_direction,
+            DragConstraint::Plane(normal) => normal,
+            DragConstraint::Axis(axis) => {
+                // The plane containing the axis which faces the camera as much as possible
+                axis.cross(view_direction.cross(axis))
+            }
+        };
+        if normal.magnitude2() < 0.000001 {
+            return None;
+        }
+        let denominator = ray_direction.dot(normal);
+        if denominator.abs() < 0.000001 {
+            return None;
+        }
+        let t = (start - ray_origin).dot(normal) / denominator;
+        if t < 0.0 {
+            return None;
+        }
+        Some(self.constrain(ray_origin + ray_direction * t - start, view_direction))
+    }
+
+    ///
+    /// Same as [DragConstraint::solve] except that the translation is passed through the given snapping function, for example [snap_to_increment],
+    /// and the snapped translation is then constrained again so that snapping cannot move the point off the constraint.
+    ///
+    pub fn solve_snapped(
+        &self,
+        start: Vec3,
+        ray_origin: Vec3,
+        ray_direction: Vec3,
+        view_direction: Vec3,
+        snap: impl Fn(Vec3) -> Vec3,
+    ) -> Option<Vec3> {
+        self.solve(start, ray_origin, ray_direction, view_direction)
+            .map(|translation| self.constrain(snap(translation), view_direction))
+    }
+
+    ///
+    /// Removes the part of the given translation that is not allowed by this constraint.
+    ///
+    pub fn constrain(&self, translation: Vec3, view_direction: Vec3) -> Vec3 {
+        use crate::core::*;
+        let remove_along = |normal: Vec3| {
+            let normal = normal.normalize();
+            translation - normal * translation.dot(normal)
+        };
+        match *self {
+            DragConstraint::ScreenPlane => remove_along(view_direction),
+            DragConstraint::Plane(normal) => remove_along(normal),
+            DragConstraint::Axis(axis) => {
+                let axis = axis.normalize();
+                axis * translation.dot(axis)
+            }
+        }
+    }
+}
+
+///
+/// Snaps each component of the given translation to the nearest multiple of the given increment.
+/// Can be used as the snapping function in [DragConstraint::solve_snapped] and [DragPlane::translation_snapped].
+///
+pub fn snap_to_increment(translation: Vec3, increment: f32) -> Vec3 {
+    if increment <= 0.0 {
+        return translation;
+    }
+    Vec3::new(
+        (translation.x / increment).round() * increment,
+        (translation.y / increment).round() * increment,
+        (translation.z / increment).round() * increment,
+    )
+}
+
+///
+/// Converts the motion of the mouse into a translation in world space, which is the core interaction for moving objects with the mouse.
+/// When the drag starts, the point under the cursor is found using a [LocationPicker].
+/// Subsequent cursor positions are converted into a translation of that point,
+/// either on the plane through it facing the camera or constrained to an axis or a plane (see [DragConstraint]).
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DragPlane {
+    start: Vec3,
+    /// Defines how the dragged point moves.
+    pub constraint: DragConstraint,
+}
+
+impl DragPlane {
+    ///
+    /// Starts a drag at the point in the scene shown at the given pixel.
+    /// Returns ```None``` if no geometry is shown at the pixel.
+    ///
+    pub fn start(
+        picker: &LocationPicker,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+        constraint: DragConstraint,
+    ) -> Option<Self> {
+        picker
+            .pick(camera, pixel, geometries)
+            .map(|start| Self::new(start, constraint))
+    }
+
+    ///
+    /// Starts a drag at the given point in world space, for example the center of the dragged object.
+    ///
+    pub fn new(start: Vec3, constraint: DragConstraint) -> Self {
+        Self { start, constraint }
+    }
+
+    ///
+    /// Returns the point in world space where the drag started.
+    ///
+    pub fn start_point(&self) -> Vec3 {
+        self.start
+    }
+
+    ///
+    /// Returns the translation in world space from the start point to the point on the constraint shown at the given pixel.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+    /// Returns ```None``` if the ray through the pixel is parallel to the constraint or if the constraint is behind the camera.
+    ///
+    pub fn translation(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+    ) -> Option<Vec3> {
+        self.constraint.solve(
+            self.start,
+            camera.position_at_pixel(pixel),
+            camera.view_direction_at_pixel(pixel),
+            camera.view_direction(),
+        )
+    }
+
+    ///
+    /// Same as [DragPlane::translation] except that the translation is snapped using the given snapping function, for example [snap_to_increment].
+    ///
+    pub fn translation_snapped(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        snap: impl Fn(Vec3) -> Vec3,
+    ) -> Option<Vec3> {
+        self.constraint.solve_snapped(
+            self.start,
+            camera.position_at_pixel(pixel),
+            camera.view_direction_at_pixel(pixel),
+            camera.view_direction(),
+            snap,
         )
     }
 }
 
 ///
-/// A picker that returns the index of the picked object from the slice of geomerties passed to the pick method
+/// A picker which returns the surface normal, in world space and facing the camera, of the 3D scene shown at a pixel on the screen.
+/// This picker can be used to orient objects or decals placed on the surface of a 3D model for example.
 ///
-pub struct ObjectPicker {
+pub struct NormalPicker {
     context: Context,
 }
 
-impl ObjectPicker {
+impl NormalPicker {
     ///
-    /// Creates a new instance of the ObjectPicker
+    /// Create a new instance of the [NormalPicker].
     ///
     pub fn new(context: &Context) -> Self {
         Self {
@@ -158,93 +503,1298 @@ impl ObjectPicker {
     }
 
     ///
-    /// Finds the closest intersection between a ray starting at the given position in the given direction and the given geometries.
-    /// Returns ```None``` if no geometry was hit before the given maximum depth.
+    /// Renders the normals of the given geometries into a single pixel render target, where the pixel at the origin of the viewport of the camera is rendered,
+    /// and returns the normal if any geometry is visible in that pixel.
     ///
-    fn ray_intersect(
-        &self,
-        position: Vec3,
-        direction: Vec3,
-        max_depth: f32,
-        geometries: &[&dyn Geometry],
-    ) -> Option<usize> {
+    fn render_normal(&self, camera: &Camera, geometries: &[&dyn Geometry]) -> Option<Vec3> {
         use crate::core::*;
-        let viewport = Viewport::new_at_origin(1, 1);
-        let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
-            direction.cross(vec3(0.0, 1.0, 0.0))
-        } else {
-            direction.cross(vec3(1.0, 0.0, 0.0))
-        };
-        let camera = Camera::new_orthographic(
-            viewport,
-            position,
-            position + direction * max_depth,
-            up,
-            0.01,
-            0.0,
-            max_depth,
-        );
-        let mut texture = Texture2D::new_empty::<Vec4>(
-            &self.context,
-            viewport.width,
-            viewport.height,
-            Interpolation::Nearest,
-            Interpolation::Nearest,
-            None,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
-        let mut depth_texture = DepthTexture2D::new::<f32>(
+        let normal_material = NormalMaterial::default();
+        let color = render_pixel::<[f32; 4]>(
             &self.context,
-            viewport.width,
-            viewport.height,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
+            |target| {
+                target.clear(ClearState::color(0.0, 0.0, 0.0, 0.0));
+            },
+            || {
+                for geometry in geometries {
+                    geometry.render_with_material(&normal_material, camera, &[]);
+                }
+            },
         );
-        let color = RenderTarget::new(
-            texture.as_color_target(None),
-            depth_texture.as_depth_target(),
-        )
-        .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
-        .write(|| {
-            for (i, geometry) in geometries.iter().enumerate() {
-                // TODO:Fix color precision issues which occur because color is normalized
-                // when sent to shaders which may not return the original color. This could
-                // lead to wrong object being picked.
-                let color = i.try_into().expect("Too many objects");
-                let color_material = ColorMaterial {
-                    color,
-                    ..Default::default()
-                };
-                geometry.render_with_material(&color_material, &camera, &[]);
-            }
+        (color[3] > 0.0).then(|| {
+            vec3(
+                2.0 * color[0] - 1.0,
+                2.0 * color[1] - 1.0,
+                2.0 * color[2] - 1.0,
+            )
+            .normalize()
         })
-        .read_color::<Vec4>()[0];
-        let picked_color = Color::from_rgba_slice(&[color.x, color.y, color.z, color.w]);
-        if picked_color == Color::WHITE {
-            return None;
-        } else {
-            return Some(picked_color.into());
-        }
     }
 }
 
-impl Pick for ObjectPicker {
-    type PickResult = usize;
-
+impl Pick for NormalPicker {
+    type PickResult = Vec3;
+    ///
+    /// Finds the surface normal at the closest intersection between a ray from the given camera in the given pixel coordinate and the given geometries.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+    /// Returns ```None``` if no geometry was hit between the near (`z_near`) and far (`z_far`) plane for this camera.
+    ///
     fn pick(
         &self,
         camera: &Camera,
         pixel: impl Into<PixelPoint> + Copy,
         geometries: &[&dyn Geometry],
-    ) -> Option<Self::PickResult> {
-        let pos = camera.position_at_pixel(pixel);
-        let dir = camera.view_direction_at_pixel(pixel);
-        self.ray_intersect(
-            pos + dir * camera.z_near(),
-            dir,
-            camera.z_far() - camera.z_near(),
-            geometries,
-        )
+    ) -> Option<Vec3> {
+        self.render_normal(&pixel_camera(camera, pixel), geometries)
+    }
+}
+
+///
+/// The result of a [UvPicker] pick operation.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UvPick {
+    /// The index of the picked object in the slice of geometries passed to the pick method.
+    pub index: usize,
+    /// The interpolated uv coordinates of the picked object at the picked point, in the same convention as the uv coordinates of a [CpuMesh].
+    pub uv: Vec2,
+}
+
+///
+/// A picker that returns the uv coordinates and the index of the geometry shown at a pixel on the screen,
+/// for example to map a brush stroke on the screen into the texture space of a model when painting on it.
+/// The picked geometries must have uv coordinates.
+///
+pub struct UvPicker {
+    context: Context,
+}
+
+impl UvPicker {
+    ///
+    /// Create a new instance of the [UvPicker].
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+        }
+    }
+
+    ///
+    /// Renders the uv coordinates of the given geometries into a single pixel render target, where the pixel at the origin of the viewport of the camera is rendered,
+    /// and returns the uv coordinates and the index of the geometry if any geometry is visible in that pixel.
+    ///
+    fn render_uv(&self, camera: &Camera, geometries: &[&dyn Geometry]) -> Option<UvPick> {
+        use crate::core::*;
+        let id = render_object_id(&self.context, camera, geometries);
+        if id == 0 {
+            return None;
+        }
+        let index = id as usize - 1;
+        let color = render_pixel::<[f32; 4]>(
+            &self.context,
+            |target| {
+                target.clear(ClearState::color(0.0, 0.0, 0.0, 0.0));
+            },
+            || {
+                geometries[index].render_with_material(&UVMaterial::default(), camera, &[]);
+            },
+        );
+        // The uv coordinates used in the shaders are flipped in v compared to standard uv coordinates
+        (color[3] > 0.0).then(|| UvPick {
+            index,
+            uv: vec2(color[0], 1.0 - color[1]),
+        })
+    }
+}
+
+impl Pick for UvPicker {
+    type PickResult = UvPick;
+
+    ///
+    /// Finds the uv coordinates and the index of the geometry at the closest intersection between a ray from the given camera in the given pixel coordinate and the given geometries.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+    /// Returns ```None``` if no geometry was hit between the near (`z_near`) and far (`z_far`) plane for this camera.
+    ///
+    fn pick(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<UvPick> {
+        self.render_uv(&pixel_camera(camera, pixel), geometries)
+    }
+}
+
+///
+/// The result of an [ObjectPicker] pick operation.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectPick {
+    /// The index of the picked object in the slice of geometries passed to the pick method.
+    pub index: usize,
+    /// The distance from the camera to the picked point on the object along the view direction at the picked pixel,
+    /// or the distance from the origin of the ray when using [ObjectPicker::intersect_ray].
+    pub depth: f32,
+}
+
+///
+/// A picker that returns the index of the picked object from the slice of geomerties passed to the pick method together with the depth of the hit.
+/// The index is written to an unsigned integer texture, so the picked index is exact for any number of objects.
+///
+pub struct ObjectPicker {
+    context: Context,
+    /// The distance in physical pixels from the picked pixel within which objects are picked when no object is visible at the picked pixel.
+    /// The object closest to the picked pixel is picked, so thin objects like a [Line2D](crate::Line2D) or an [Outline](crate::Outline) can be picked by clicking near them.
+    /// The default is 0, which only picks the object at the picked pixel.
+    pub tolerance: u32,
+}
+
+impl ObjectPicker {
+    ///
+    /// Creates a new instance of the ObjectPicker
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            tolerance: 0,
+        }
+    }
+
+    ///
+    /// Creates a new instance of the ObjectPicker with the given pick tolerance in physical pixels, see [ObjectPicker::tolerance].
+    ///
+    pub fn new_with_tolerance(context: &Context, tolerance: u32) -> Self {
+        Self {
+            tolerance,
+            ..Self::new(context)
+        }
+    }
+
+    ///
+    /// Finds the closest intersection between a ray starting at the given origin in the given direction and the given geometries,
+    /// for example to find the object hit by a projectile or to test the line of sight between two points.
+    /// The direction does not need to be normalized.
+    /// Returns the index of the intersected geometry and the distance from the given origin to the intersection point
+    /// or ```None``` if no geometry was hit before the given maximum depth.
+    ///
+    pub fn intersect_ray(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_depth: f32,
+        geometries: &[&dyn Geometry],
+    ) -> Option<ObjectPick> {
+        let direction = direction.normalize();
+        self.render_pick(
+            &ray_camera(origin, direction, max_depth),
+            origin,
+            direction,
+            geometries,
+        )
+    }
+
+    ///
+    /// Renders the given geometries into a single pixel render target, where the pixel at the origin of the viewport of the camera is rendered,
+    /// and returns the index of the geometry visible in that pixel and the distance from the given origin along the given normalized direction to the visible point.
+    ///
+    fn render_pick(
+        &self,
+        camera: &Camera,
+        origin: Vec3,
+        direction: Vec3,
+        geometries: &[&dyn Geometry],
+    ) -> Option<ObjectPick> {
+        use crate::core::*;
+        let id = render_object_id(&self.context, camera, geometries);
+        if id == 0 {
+            return None;
+        }
+        let index = id as usize - 1;
+        // The depth test makes sure the closest surface of the geometry is found, also when it is rendered in several draw calls or has overlapping triangles
+        let position = render_position(&self.context, camera, &geometries[index..=index])?;
+        Some(ObjectPick {
+            index,
+            depth: (position - origin).dot(direction),
+        })
+    }
+}
+
+impl Pick for ObjectPicker {
+    type PickResult = ObjectPick;
+
+    fn remap_indices(result: ObjectPick, indices: &[usize]) -> ObjectPick {
+        ObjectPick {
+            index: indices[result.index],
+            ..result
+        }
+    }
+
+    fn pick(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<Self::PickResult> {
+        let pick_at_pixel = |pixel: PixelPoint, geometries: &[&dyn Geometry]| {
+            self.render_pick(
+                &pixel_camera(camera, pixel),
+                camera.position_at_pixel(pixel),
+                camera.view_direction_at_pixel(pixel),
+                geometries,
+            )
+        };
+        let pixel = pixel.into();
+        if let Some(pick) = pick_at_pixel(pixel, geometries) {
+            return Some(pick);
+        }
+        if self.tolerance == 0 {
+            return None;
+        }
+
+        // Find the objects visible in the pixels around the picked pixel and pick the closest of them
+        let radius = self.tolerance;
+        let size = 2 * radius + 1;
+        let ids = render_object_ids_with_size(
+            &self.context,
+            &region_camera(camera, pixel, radius),
+            size,
+            size,
+            geometries,
+        );
+        // The ids are read from the top row to the bottom row
+        let mut hits: Vec<(u32, PixelPoint, usize)> = ids
+            .into_iter()
+            .enumerate()
+            .filter(|(_, id)| *id > 0)
+            .filter_map(|(i, id)| {
+                let dx = (i as u32 % size) as i32 - radius as i32;
+                let dy = (size - 1 - i as u32 / size) as i32 - radius as i32;
+                let distance2 = (dx * dx + dy * dy) as u32;
+                (distance2 <= radius * radius).then(|| {
+                    let hit_pixel = PixelPoint {
+                        x: pixel.x.floor() + dx as f32 + 0.5,
+                        y: pixel.y.floor() + dy as f32 + 0.5,
+                    };
+                    (distance2, hit_pixel, id as usize - 1)
+                })
+            })
+            .collect();
+        hits.sort_by_key(|(distance2, _, _)| *distance2);
+        hits.into_iter().find_map(|(_, hit_pixel, index)| {
+            pick_at_pixel(hit_pixel, &[geometries[index]]).map(|pick| ObjectPick { index, ..pick })
+        })
+    }
+}
+
+///
+/// The result of an [InstancePicker] pick operation.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InstancePick {
+    /// The index of the picked geometry in the slice of geometries passed to the pick method.
+    pub geometry_index: usize,
+    /// The index of the picked instance of the geometry, for example the index into the [Instances](crate::Instances) of an [InstancedMesh](crate::InstancedMesh).
+    /// This is always 0 for geometries that are not instanced.
+    pub instance_index: usize,
+}
+
+///
+/// A picker that returns the index of the picked geometry from the slice of geometries passed to the pick method together with the index of the picked instance of that geometry.
+/// The picked geometry must support the instance ID fragment attribute (see [FragmentAttributes::instance_id]).
+///
+pub struct InstancePicker {
+    context: Context,
+}
+
+impl InstancePicker {
+    ///
+    /// Creates a new instance of the [InstancePicker].
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+        }
+    }
+
+    ///
+    /// Renders the instance ids of the given geometries into a single pixel render target, where the pixel at the origin of the viewport of the camera is rendered,
+    /// and returns the geometry and instance if any geometry is visible in that pixel.
+    ///
+    fn render_instance(
+        &self,
+        camera: &Camera,
+        geometries: &[&dyn Geometry],
+    ) -> Option<InstancePick> {
+        let id = render_object_id(&self.context, camera, geometries);
+        if id == 0 {
+            return None;
+        }
+        let geometry_index = id as usize - 1;
+        let instance = render_pixel::<[u32; 4]>(
+            &self.context,
+            |target| {
+                target.clear_color_u32([0, 0, 0, 0]);
+            },
+            || {
+                geometries[geometry_index].render_with_material(&InstanceIdMaterial, camera, &[]);
+            },
+        );
+        // The instance index is offset by one so that zero means that nothing was hit
+        (instance[0] > 0).then(|| InstancePick {
+            geometry_index,
+            instance_index: instance[0] as usize - 1,
+        })
+    }
+}
+
+impl Pick for InstancePicker {
+    type PickResult = InstancePick;
+
+    fn remap_indices(result: InstancePick, indices: &[usize]) -> InstancePick {
+        InstancePick {
+            geometry_index: indices[result.geometry_index],
+            ..result
+        }
+    }
+
+    ///
+    /// Finds the geometry and instance at the closest intersection between a ray from the given camera in the given pixel coordinate and the given geometries.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+    /// Returns ```None``` if no geometry was hit between the near (`z_near`) and far (`z_far`) plane for this camera.
+    ///
+    fn pick(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<InstancePick> {
+        self.render_instance(&pixel_camera(camera, pixel), geometries)
+    }
+}
+
+///
+/// The region on the screen used by a [RegionPicker].
+/// All points are in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectionRegion {
+    /// An axis aligned rectangle spanned by the two given corners.
+    Rectangle(PixelPoint, PixelPoint),
+    /// A polygon, for example drawn with a lasso tool, defined by its corners.
+    /// The polygon is closed automatically and may be concave.
+    Polygon(Vec<PixelPoint>),
+}
+
+impl SelectionRegion {
+    ///
+    /// Returns whether or not the given point is inside the region.
+    ///
+    pub fn contains(&self, point: PixelPoint) -> bool {
+        match self {
+            Self::Rectangle(a, b) => {
+                point.x >= a.x.min(b.x)
+                    && point.x <= a.x.max(b.x)
+                    && point.y >= a.y.min(b.y)
+                    && point.y <= a.y.max(b.y)
+            }
+            Self::Polygon(corners) => {
+                let mut inside = false;
+                for i in 0..corners.len() {
+                    let a = corners[i];
+                    let b = corners[(i + 1) % corners.len()];
+                    if (a.y > point.y) != (b.y > point.y)
+                        && point.x < a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y)
+                    {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+}
+
+///
+/// Defines which objects are selected by a [RegionPicker].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Selects the objects that are visible in at least one pixel inside the region.
+    Intersecting,
+    /// Selects the objects that are visible inside the region and where all of the pixels covered by the object, ignoring occlusion, are inside the region.
+    FullyContained,
+}
+
+///
+/// A picker that returns the indices of the objects, from the slice of geometries passed to the pick method, that are inside a region on the screen.
+/// This is for example used for drag-select in editors.
+/// When using the [Pick] trait, the pixel given to the pick method closes the [RegionPicker::region]:
+/// it is the second corner of a [SelectionRegion::Rectangle] and the last corner of a [SelectionRegion::Polygon].
+///
+pub struct RegionPicker {
+    context: Context,
+    /// The region, which is closed by the pixel given to the pick method.
+    pub region: SelectionRegion,
+    /// Defines which objects are selected.
+    pub mode: SelectionMode,
+}
+
+impl RegionPicker {
+    ///
+    /// Creates a new instance of the [RegionPicker] where the selection starts at the given pixel.
+    /// Use [SelectionRegion::Polygon] as the region to do lasso selection instead of rectangle selection.
+    ///
+    pub fn new(context: &Context, start: impl Into<PixelPoint>) -> Self {
+        let start = start.into();
+        Self {
+            context: context.clone(),
+            region: SelectionRegion::Rectangle(start, start),
+            mode: SelectionMode::Intersecting,
+        }
+    }
+
+    ///
+    /// Returns the indices, in increasing order, of the geometries which are inside the given region as seen from the given camera.
+    ///
+    pub fn pick_region(
+        &self,
+        camera: &Camera,
+        region: &SelectionRegion,
+        geometries: &[&dyn Geometry],
+    ) -> Vec<usize> {
+        use crate::core::*;
+        let viewport = camera.viewport();
+        let mut id_camera = camera.clone();
+        id_camera.set_viewport(Viewport::new_at_origin(viewport.width, viewport.height));
+        let (width, height) = (viewport.width as usize, viewport.height as usize);
+        // The pixels are read from the top row to the bottom row
+        let is_inside = |index: usize| {
+            region.contains(PixelPoint {
+                x: viewport.x as f32 + (index % width) as f32 + 0.5,
+                y: viewport.y as f32 + (height - 1 - index / width) as f32 + 0.5,
+            })
+        };
+
+        let mut selected = vec![false; geometries.len()];
+        for (index, id) in render_object_ids(&self.context, &id_camera, geometries)
+            .into_iter()
+            .enumerate()
+        {
+            if id > 0 && is_inside(index) {
+                selected[id as usize - 1] = true;
+            }
+        }
+
+        if self.mode == SelectionMode::FullyContained {
+            for (i, geometry) in geometries.iter().enumerate() {
+                if selected[i] {
+                    selected[i] = render_object_ids(&self.context, &id_camera, &[*geometry])
+                        .into_iter()
+                        .enumerate()
+                        .all(|(index, id)| id == 0 || is_inside(index));
+                }
+            }
+        }
+        (0..geometries.len()).filter(|i| selected[*i]).collect()
+    }
+}
+
+impl Pick for RegionPicker {
+    type PickResult = Vec<usize>;
+
+    fn remap_indices(result: Vec<usize>, indices: &[usize]) -> Vec<usize> {
+        result.into_iter().map(|i| indices[i]).collect()
+    }
+
+    fn pick(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<Self::PickResult> {
+        let region = match &self.region {
+            SelectionRegion::Rectangle(start, _) => {
+                SelectionRegion::Rectangle(*start, pixel.into())
+            }
+            SelectionRegion::Polygon(corners) => {
+                let mut corners = corners.clone();
+                corners.push(pixel.into());
+                SelectionRegion::Polygon(corners)
+            }
+        };
+        let indices = self.pick_region(camera, &region, geometries);
+        if indices.is_empty() {
+            None
+        } else {
+            Some(indices)
+        }
+    }
+}
+
+///
+/// The type of geometry feature snapped to by a [SnapPicker].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapKind {
+    /// A vertex of a mesh.
+    Vertex,
+    /// The midpoint of an edge of a mesh.
+    EdgeMidpoint,
+}
+
+///
+/// The result of a [SnapPicker] pick operation.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Snap {
+    /// The position of the snapped feature in world space.
+    pub position: Vec3,
+    /// The type of the snapped feature.
+    pub kind: SnapKind,
+    /// The index of the mesh containing the snapped feature, see [SnapPicker::add_mesh].
+    pub index: usize,
+    /// The distance in physical pixels from the picked pixel to the snapped feature on the screen.
+    pub pixel_distance: f32,
+}
+
+struct SnapMesh {
+    vertices: Vec<Vec3>,
+    edges: Vec<(usize, usize)>,
+}
+
+///
+/// A picker that snaps a pixel to the nearest visible mesh vertex or edge midpoint within a radius, for example to snap the cursor to geometry features in CAD-style tools.
+/// The vertices and edges are found on the CPU from the meshes added with [SnapPicker::add_mesh],
+/// while the geometries given to the pick method are used to discard the features that are hidden behind other geometries.
+///
+pub struct SnapPicker {
+    context: Context,
+    meshes: Vec<SnapMesh>,
+    /// The snap radius in physical pixels.
+    pub radius: f32,
+    /// Whether or not to snap to edge midpoints in addition to vertices.
+    pub snap_to_edges: bool,
+}
+
+impl SnapPicker {
+    ///
+    /// Creates a new instance of the [SnapPicker] with the given snap radius in physical pixels.
+    ///
+    pub fn new(context: &Context, radius: f32) -> Self {
+        Self {
+            context: context.clone(),
+            meshes: Vec::new(),
+            radius,
+            snap_to_edges: true,
+        }
+    }
+
+    ///
+    /// Adds a mesh, with the given transformation from local to world space, that can be snapped to and returns the index of the mesh.
+    ///
+    pub fn add_mesh(&mut self, cpu_mesh: &CpuMesh, transformation: Mat4) -> usize {
+        let vertices: Vec<Vec3> = cpu_mesh
+            .positions
+            .to_f32()
+            .into_iter()
+            .map(|p| (transformation * p.extend(1.0)).truncate())
+            .collect();
+        let indices: Vec<usize> = match &cpu_mesh.indices {
+            Indices::U8(ind) => ind.iter().map(|i| *i as usize).collect(),
+            Indices::U16(ind) => ind.iter().map(|i| *i as usize).collect(),
+            Indices::U32(ind) => ind.iter().map(|i| *i as usize).collect(),
+            Indices::None => (0..vertices.len()).collect(),
+        };
+        let mut edges = std::collections::HashSet::new();
+        for triangle in indices.chunks(3) {
+            for (a, b) in [
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                edges.insert((a.min(b), a.max(b)));
+            }
+        }
+        self.meshes.push(SnapMesh {
+            vertices,
+            edges: edges.into_iter().collect(),
+        });
+        self.meshes.len() - 1
+    }
+
+    ///
+    /// Removes all meshes.
+    ///
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+    }
+}
+
+impl Pick for SnapPicker {
+    type PickResult = Snap;
+
+    ///
+    /// Finds the vertex or edge midpoint closest to the given pixel on the screen within the snap radius, which is not hidden behind any of the given geometries.
+    /// Use an empty slice of geometries to ignore occlusion.
+    ///
+    fn pick(
+        &self,
+        camera: &Camera,
+        pixel: impl Into<PixelPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<Snap> {
+        use crate::core::*;
+        let pixel = pixel.into();
+        let viewport = camera.viewport();
+        let view_projection = camera.projection() * camera.view();
+        let to_pixel = |p: Vec3| {
+            let clip = view_projection * p.extend(1.0);
+            if clip.w <= 0.0 {
+                return None;
+            }
+            Some(vec2(
+                viewport.x as f32 + (0.5 * clip.x / clip.w + 0.5) * viewport.width as f32,
+                viewport.y as f32 + (0.5 * clip.y / clip.w + 0.5) * viewport.height as f32,
+            ))
+        };
+
+        let mut candidates = Vec::new();
+        for (index, mesh) in self.meshes.iter().enumerate() {
+            let vertices = mesh.vertices.iter().map(|p| (*p, SnapKind::Vertex));
+            let midpoints = mesh
+                .edges
+                .iter()
+                .filter(|_| self.snap_to_edges)
+                .map(|(a, b)| {
+                    (
+                        0.5 * (mesh.vertices[*a] + mesh.vertices[*b]),
+                        SnapKind::EdgeMidpoint,
+                    )
+                });
+            for (position, kind) in vertices.chain(midpoints) {
+                if let Some(p) = to_pixel(position) {
+                    let pixel_distance = vec2(p.x - pixel.x, p.y - pixel.y).magnitude();
+                    if pixel_distance <= self.radius {
+                        candidates.push((
+                            p,
+                            Snap {
+                                position,
+                                kind,
+                                index,
+                                pixel_distance,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        candidates.sort_by(|a, b| {
+            a.1.pixel_distance
+                .partial_cmp(&b.1.pixel_distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        if candidates.is_empty() || geometries.is_empty() {
+            return candidates.first().map(|c| c.1);
+        }
+
+        // Render the distance to the closest surface in the pixels around the picked pixel into a render target covering only those pixels
+        let radius = self.radius.ceil() as u32 + 1;
+        let size = 2 * radius + 1;
+        let distance_camera = region_camera(camera, pixel, radius);
+        let mut texture = Texture2D::new_empty::<f32>(
+            &self.context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth_texture = DepthTexture2D::new::<f32>(
+            &self.context,
+            size,
+            size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let depth_material = DepthMaterial {
+            min_distance: Some(0.0),
+            max_distance: Some(camera.z_far()),
+            render_states: RenderStates {
+                write_mask: WriteMask {
+                    red: true,
+                    ..WriteMask::DEPTH
+                },
+                ..Default::default()
+            },
+        };
+        let distances = RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
+        .write(|| {
+            for geometry in geometries {
+                geometry.render_with_material(&depth_material, &distance_camera, &[]);
+            }
+        })
+        .read_color::<f32>();
+
+        // The pixels are read from the top row to the bottom row
+        let surface_distance = |p: Vec2| {
+            let x = p.x.floor() as i32 - pixel.x.floor() as i32 + radius as i32;
+            let y = p.y.floor() as i32 - pixel.y.floor() as i32 + radius as i32;
+            if x < 0 || y < 0 || x >= size as i32 || y >= size as i32 {
+                return f32::MAX;
+            }
+            let row = size as i32 - 1 - y;
+            distances[(row * size as i32 + x) as usize] * camera.z_far()
+        };
+        candidates
+            .into_iter()
+            .find(|(p, snap)| {
+                let distance = snap.position.distance(*camera.position());
+                distance <= surface_distance(*p) * 1.01 + 0.001 * camera.z_far()
+            })
+            .map(|c| c.1)
+    }
+}
+
+///
+/// Returns an orthographic camera which renders the geometries hit by a ray starting at the given origin in the given direction into a single pixel render target,
+/// which is used to intersect a ray with the geometries.
+/// The direction does not need to be normalized.
+///
+fn ray_camera(origin: Vec3, direction: Vec3, max_depth: f32) -> Camera {
+    use crate::core::*;
+    let direction = direction.normalize();
+    let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
+        direction.cross(vec3(0.0, 1.0, 0.0))
+    } else {
+        direction.cross(vec3(1.0, 0.0, 0.0))
+    };
+    Camera::new_orthographic(
+        Viewport::new_at_origin(1, 1),
+        origin,
+        origin + direction * max_depth,
+        up,
+        0.01,
+        0.0,
+        max_depth,
+    )
+}
+
+///
+/// Renders into a single pixel render target with a depth buffer, where the color is cleared by the given `clear` closure, and returns the color of the pixel.
+/// Use a [pixel_camera] or a [ray_camera] to render the pixel of interest.
+///
+fn render_pixel<T: crate::core::TextureDataType>(
+    context: &Context,
+    clear: impl FnOnce(&crate::core::RenderTarget),
+    render: impl FnOnce(),
+) -> T {
+    use crate::core::*;
+    let mut texture = Texture2D::new_empty::<T>(
+        context,
+        1,
+        1,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture =
+        DepthTexture2D::new::<f32>(context, 1, 1, Wrapping::ClampToEdge, Wrapping::ClampToEdge);
+    let target = RenderTarget::new(
+        texture.as_color_target(None),
+        depth_texture.as_depth_target(),
+    );
+    target.clear(ClearState::depth(1.0));
+    clear(&target);
+    target.write(render).read_color::<T>()[0]
+}
+
+///
+/// Renders the world position of the given geometries into a single pixel render target, where the pixel at the origin of the viewport of the camera is rendered,
+/// and returns the position if any geometry is visible in that pixel.
+///
+fn render_position(
+    context: &Context,
+    camera: &Camera,
+    geometries: impl IntoIterator<Item = impl Geometry>,
+) -> Option<Vec3> {
+    use crate::core::*;
+    let position_material = PositionMaterial::default();
+    let color = render_pixel::<[f32; 4]>(
+        context,
+        |target| {
+            target.clear(ClearState::color(0.0, 0.0, 0.0, 0.0));
+        },
+        || {
+            for geometry in geometries {
+                geometry.render_with_material(&position_material, camera, &[]);
+            }
+        },
+    );
+    (color[3] > 0.0).then(|| vec3(color[0], color[1], color[2]))
+}
+
+///
+/// Renders the index + 1 of the geometry visible in the pixel at the origin of the viewport of the camera, where zero means that no geometry is visible.
+///
+fn render_object_id(context: &Context, camera: &Camera, geometries: &[&dyn Geometry]) -> u32 {
+    render_object_ids_with_size(context, camera, 1, 1, geometries)[0]
+}
+
+///
+/// Renders the index + 1 of each of the given geometries into an unsigned integer texture with the size of the viewport of the camera,
+/// where zero means that no geometry is visible, and returns the pixels from the top row to the bottom row.
+///
+fn render_object_ids(context: &Context, camera: &Camera, geometries: &[&dyn Geometry]) -> Vec<u32> {
+    let viewport = camera.viewport();
+    render_object_ids_with_size(context, camera, viewport.width, viewport.height, geometries)
+}
+
+///
+/// Renders the object ids into a render target with the given size, which is smaller than the viewport when rendering with a pixel or region camera.
+///
+fn render_object_ids_with_size(
+    context: &Context,
+    camera: &Camera,
+    width: u32,
+    height: u32,
+    geometries: &[&dyn Geometry],
+) -> Vec<u32> {
+    use crate::core::*;
+    let mut texture = Texture2D::new_empty::<[u32; 4]>(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        context,
+        width,
+        height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    RenderTarget::new(
+        texture.as_color_target(None),
+        depth_texture.as_depth_target(),
+    )
+    .clear(ClearState::depth(1.0))
+    .clear_color_u32([0, 0, 0, 0])
+    .write(|| {
+        for (i, geometry) in geometries.iter().enumerate() {
+            let material = ObjectIdMaterial {
+                id: u32::try_from(i + 1).expect("Too many objects"),
+            };
+            geometry.render_with_material(&material, camera, &[]);
+        }
+    })
+    .read_color::<[u32; 4]>()
+    .into_iter()
+    .map(|id| id[0])
+    .collect()
+}
+
+///
+/// Writes an unsigned integer ID to an unsigned integer color texture.
+///
+struct ObjectIdMaterial {
+    id: u32,
+}
+
+impl Material for ObjectIdMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: "
+uniform uint id;
+
+layout (location = 0) out uvec4 outId;
+
+void main()
+{
+    outId = uvec4(id, 0u, 0u, 0u);
+}"
+            .to_string(),
+            attributes: FragmentAttributes::NONE,
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform("id", self.id);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+///
+/// Writes the index + 1 of the rendered instance to an unsigned integer color texture.
+///
+struct InstanceIdMaterial;
+
+impl Material for InstanceIdMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: "
+flat in int instanceId;
+
+layout (location = 0) out uvec4 outId;
+
+void main()
+{
+    outId = uvec4(uint(instanceId) + 1u, 0u, 0u, 0u);
+}"
+            .to_string(),
+            attributes: FragmentAttributes {
+                instance_id: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, _program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {}
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use crate::core::*;
+    use crate::*;
+
+    // Runs the test with a headless context, or skips it when no graphics context can be created, for example on a machine without a GPU
+    fn with_context(test: impl FnOnce(&Context)) {
+        if let Ok(context) = HeadlessContext::new() {
+            test(&context);
+        }
+    }
+
+    fn camera() -> Camera {
+        Camera::new_perspective(
+            Viewport::new_at_origin(64, 64),
+            vec3(0.0, 0.0, 5.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            100.0,
+        )
+    }
+
+    fn pixel_at(camera: &Camera, position: Vec3) -> PixelPoint {
+        let clip = camera.projection() * camera.view() * position.extend(1.0);
+        let viewport = camera.viewport();
+        PixelPoint {
+            x: (0.5 * clip.x / clip.w + 0.5) * viewport.width as f32,
+            y: (0.5 * clip.y / clip.w + 0.5) * viewport.height as f32,
+        }
+    }
+
+    fn assert_picked(picked: Option<Vec3>, expected: Vec3) {
+        let picked = picked.expect("nothing was picked");
+        assert!(
+            picked.distance(expected) < 0.05,
+            "picked {:?} but expected {:?}",
+            picked,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_pick_morphed_mesh() {
+        with_context(|context| {
+            let cpu_mesh = CpuMesh::square();
+            let mut mesh = Mesh::new(context, &cpu_mesh);
+            mesh.set_morph_targets(&[CpuMesh {
+                positions: Positions::F32(vec![vec3(0.0, 0.0, 1.0); cpu_mesh.positions.len()]),
+                ..Default::default()
+            }]);
+            mesh.set_morph_weights(&[1.0]);
+            let camera = camera();
+            let expected = vec3(0.0, 0.0, 1.0);
+            let picked =
+                LocationPicker::new(context).pick(&camera, pixel_at(&camera, expected), &[&mesh]);
+            assert_picked(picked, expected);
+        });
+    }
+
+    #[test]
+    fn test_pick_location_clipped_by_far_plane() {
+        with_context(|context| {
+            let mut mesh = Mesh::new(context, &CpuMesh::square());
+            mesh.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, -2.0)));
+            let mut camera = camera();
+            let picker = LocationPicker::new(context);
+            let pixel = pixel_at(&camera, vec3(0.0, 0.0, -2.0));
+            let pick = picker
+                .pick_location(&camera, pixel, &[&mesh])
+                .expect("nothing was picked");
+            assert_eq!(pick.clipped_by, None);
+            assert!((pick.depth - 7.0).abs() < 0.05);
+            assert!((pick.distance - 7.0).abs() < 0.05);
+
+            camera.set_perspective_projection(degrees(45.0), 0.1, 5.0);
+            assert!(picker.pick(&camera, pixel, &[&mesh]).is_none());
+            let pick = picker
+                .pick_location(&camera, pixel, &[&mesh])
+                .expect("the clipped mesh was not picked");
+            assert_eq!(pick.clipped_by, Some(ClipPlane::Far));
+            assert!((pick.depth - 7.0).abs() < 0.05);
+            assert!(picker
+                .pick_location(&camera, PixelPoint { x: 1.0, y: 1.0 }, &[&mesh])
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_pick_filtered() {
+        with_context(|context| {
+            let front = Mesh::new(context, &CpuMesh::square());
+            let mut back = Mesh::new(context, &CpuMesh::square());
+            back.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, -1.0)));
+            let mut hidden = Mesh::new(context, &CpuMesh::square());
+            hidden.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, 1.0)));
+            let geometries: [&dyn Geometry; 3] = [&hidden, &front, &back];
+            let camera = camera();
+            let pixel = pixel_at(&camera, vec3(0.0, 0.0, 0.0));
+            let picker = ObjectPicker::new(context);
+            let visible = PickFilter::new().with_filter(|i| i != 0);
+            let pick = |filter: &PickFilter| {
+                picker
+                    .pick_filtered(&camera, pixel, &geometries, filter)
+                    .map(|pick| pick.index)
+            };
+
+            assert_eq!(pick(&PickFilter::new()), Some(0));
+            assert_eq!(pick(&visible), Some(1));
+            assert_eq!(
+                pick(&visible.with_priority(|i| if i == 2 { 1 } else { 0 })),
+                Some(2)
+            );
+            assert_eq!(pick(&PickFilter::new().with_filter(|_| false)), None);
+        });
+    }
+
+    #[test]
+    fn test_pick_thin_line_with_tolerance() {
+        with_context(|context| {
+            let line = Line2D::new(context, vec2(8.0, 32.0), vec2(56.0, 32.0), 1);
+            let camera = camera2d(Viewport::new_at_origin(64, 64));
+            let pixel = PixelPoint { x: 32.5, y: 35.5 };
+            assert!(ObjectPicker::new(context)
+                .pick(&camera, pixel, &[&line])
+                .is_none());
+            let pick = ObjectPicker::new_with_tolerance(context, 4)
+                .pick(&camera, pixel, &[&line])
+                .expect("the line was not picked");
+            assert_eq!(pick.index, 0);
+            assert!(ObjectPicker::new_with_tolerance(context, 4)
+                .pick(&camera, PixelPoint { x: 32.5, y: 45.5 }, &[&line])
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_pick_animated_mesh() {
+        with_context(|context| {
+            let mut mesh = Mesh::new(context, &CpuMesh::square());
+            mesh.set_animation(|time| Mat4::from_translation(vec3(0.0, 0.0, time)));
+            mesh.animate(1.5);
+            let camera = camera();
+            let expected = vec3(0.0, 0.0, 1.5);
+            let picked =
+                LocationPicker::new(context).pick(&camera, pixel_at(&camera, expected), &[&mesh]);
+            assert_picked(picked, expected);
+        });
+    }
+
+    #[test]
+    fn test_pick_instanced_mesh() {
+        with_context(|context| {
+            let mesh = InstancedMesh::new(
+                context,
+                &Instances {
+                    transformations: vec![
+                        Mat4::from_translation(vec3(-1.5, 0.0, 0.0)),
+                        Mat4::from_translation(vec3(1.5, 0.0, 2.0)) * Mat4::from_scale(0.5),
+                    ],
+                    ..Default::default()
+                },
+                &CpuMesh::square(),
+            );
+            let camera = camera();
+            let picker = LocationPicker::new(context);
+            for expected in [vec3(-1.5, 0.0, 0.0), vec3(1.5, 0.0, 2.0)] {
+                let picked = picker.pick(&camera, pixel_at(&camera, expected), &[&mesh]);
+                assert_picked(picked, expected);
+            }
+            assert!(picker
+                .pick(&camera, pixel_at(&camera, vec3(0.0, 0.0, 0.0)), &[&mesh])
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_intersect_ray() {
+        with_context(|context| {
+            let mut near = Mesh::new(context, &CpuMesh::square());
+            near.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, -2.0)));
+            let mut far = Mesh::new(context, &CpuMesh::square());
+            far.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, -6.0)));
+            let geometries: [&dyn Geometry; 2] = [&far, &near];
+            let origin = vec3(0.5, 0.5, 0.0);
+            let direction = vec3(0.0, 0.0, -3.0);
+
+            let location =
+                LocationPicker::new(context).intersect_ray(origin, direction, 10.0, &geometries);
+            assert_picked(location, vec3(0.5, 0.5, -2.0));
+            let object = ObjectPicker::new(context)
+                .intersect_ray(origin, direction, 10.0, &geometries)
+                .expect("nothing was hit");
+            assert_eq!(object.index, 1);
+            assert!((object.depth - 2.0).abs() < 0.05);
+            assert!(LocationPicker::new(context)
+                .intersect_ray(origin, direction, 1.0, &geometries)
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_intersect_ray_closed_mesh() {
+        with_context(|context| {
+            // The ray enters the cube through the front face at z = 1 and leaves through the back face at z = -1
+            let cube = Mesh::new(context, &CpuMesh::cube());
+            let object = ObjectPicker::new(context)
+                .intersect_ray(vec3(0.2, 0.3, 5.0), vec3(0.0, 0.0, -1.0), 10.0, &[&cube])
+                .expect("nothing was hit");
+            assert_eq!(object.index, 0);
+            assert!(
+                (object.depth - 4.0).abs() < 0.05,
+                "hit at distance {} but expected 4",
+                object.depth
+            );
+        });
+    }
+
+    #[test]
+    fn test_pick_sprites_facing_the_camera() {
+        with_context(|context| {
+            let center = vec3(2.0, 0.0, 0.0);
+            let sprites = Sprites::new(context, &[center], None);
+            let camera = camera();
+            let picked = LocationPicker::new(context)
+                .pick(&camera, pixel_at(&camera, center), &[&sprites])
+                .expect("nothing was picked");
+            let normal = (*camera.position() - center).normalize();
+            assert!((picked - center).dot(normal).abs() < 0.01);
+            assert!(picked.distance(center) < 0.1);
+        });
+    }
+
+    #[test]
+    fn test_pick_with_all_pickers() {
+        with_context(|context| {
+            let mut square = Mesh::new(context, &CpuMesh::square());
+            square.set_transformation(Mat4::from_translation(vec3(1.5, 0.0, 1.0)));
+            let mesh = InstancedMesh::new(
+                context,
+                &Instances {
+                    transformations: vec![
+                        Mat4::from_translation(vec3(-1.5, 0.0, 0.0)),
+                        Mat4::from_translation(vec3(-1.5, 1.5, 0.0)) * Mat4::from_scale(0.5),
+                    ],
+                    ..Default::default()
+                },
+                &CpuMesh::square(),
+            );
+            let geometries: [&dyn Geometry; 2] = [&square, &mesh];
+            let camera = camera();
+            let position = vec3(1.5, 0.0, 1.0);
+            let pixel = pixel_at(&camera, position);
+
+            let object = ObjectPicker::new(context)
+                .pick(&camera, pixel, &geometries)
+                .expect("the square was not picked");
+            assert_eq!(object.index, 0);
+            let distance = position.distance(*camera.position());
+            assert!(
+                (object.depth - distance).abs() < 0.05,
+                "picked at depth {} but expected {}",
+                object.depth,
+                distance
+            );
+
+            let normal = NormalPicker::new(context)
+                .pick(&camera, pixel, &geometries)
+                .expect("the normal was not picked");
+            assert!(normal.dot(vec3(0.0, 0.0, 1.0)) > 0.99);
+
+            let instance = InstancePicker::new(context)
+                .pick(
+                    &camera,
+                    pixel_at(&camera, vec3(-1.5, 1.5, 0.0)),
+                    &geometries,
+                )
+                .expect("the instance was not picked");
+            assert_eq!(
+                instance,
+                InstancePick {
+                    geometry_index: 1,
+                    instance_index: 1
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_snap_to_visible_vertex() {
+        with_context(|context| {
+            let square = Mesh::new(context, &CpuMesh::square());
+            let mut occluder = Mesh::new(context, &CpuMesh::square());
+            occluder.set_transformation(
+                Mat4::from_translation(vec3(0.0, 0.0, 1.0)) * Mat4::from_scale(2.0),
+            );
+            let mut picker = SnapPicker::new(context, 5.0);
+            picker.add_mesh(&CpuMesh::square(), Mat4::identity());
+            let camera = camera();
+            let corner = vec3(1.0, 1.0, 0.0);
+            let pixel = pixel_at(&camera, corner);
+            let pixel = PixelPoint {
+                x: pixel.x - 2.0,
+                y: pixel.y - 1.0,
+            };
+
+            let snap = picker
+                .pick(&camera, pixel, &[&square])
+                .expect("nothing was snapped to");
+            assert_eq!(snap.kind, SnapKind::Vertex);
+            assert_eq!(snap.index, 0);
+            assert!(snap.position.distance(corner) < 0.001);
+            assert!(picker.pick(&camera, pixel, &[&square, &occluder]).is_none());
+        });
     }
 }
@@ -0,0 +1,305 @@
+use crate::picker::*;
+use crate::renderer::*;
+
+///
+/// An event concerning one of the objects given to [Interaction::handle_events], where `index` is the index of the object in the given slice of geometries.
+/// The positions are in physical pixels, see [PhysicalPoint].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObjectEvent {
+    /// The pointer moved onto the object.
+    Enter {
+        /// The index of the object.
+        index: usize,
+    },
+    /// The pointer moved off the object.
+    Leave {
+        /// The index of the object.
+        index: usize,
+    },
+    /// A mouse button was pressed while the pointer was on the object.
+    Down {
+        /// The index of the object.
+        index: usize,
+        /// The pressed button.
+        button: MouseButton,
+        /// The position of the pointer.
+        position: PhysicalPoint,
+    },
+    /// The mouse button which was pressed on the object was released, which can be anywhere if the object was dragged.
+    Up {
+        /// The index of the object.
+        index: usize,
+        /// The released button.
+        button: MouseButton,
+        /// The position of the pointer.
+        position: PhysicalPoint,
+    },
+    /// The mouse button was pressed and released on the object without moving the pointer further than the drag threshold, see [Interaction::drag_threshold].
+    /// Sent after the [ObjectEvent::Up] event.
+    Click {
+        /// The index of the object.
+        index: usize,
+        /// The clicked button.
+        button: MouseButton,
+        /// The position of the pointer.
+        position: PhysicalPoint,
+    },
+    /// The pointer moved while a mouse button was pressed on the object and it has moved further than the drag threshold since the button was pressed, see [Interaction::drag_threshold].
+    /// The drag ends with an [ObjectEvent::Up] event.
+    Drag {
+        /// The index of the object.
+        index: usize,
+        /// The pressed button.
+        button: MouseButton,
+        /// The position of the pointer when the button was pressed.
+        start: PhysicalPoint,
+        /// The position of the pointer.
+        position: PhysicalPoint,
+        /// The movement of the pointer since the last [ObjectEvent::Drag] event, or since the button was pressed for the first drag event.
+        delta: Vec2,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Press {
+    index: usize,
+    button: MouseButton,
+    start: PhysicalPoint,
+    last: PhysicalPoint,
+    dragging: bool,
+}
+
+///
+/// Keeps track of which object is under the pointer and which object is pressed or dragged and turns the input events of each frame into [ObjectEvent]s,
+/// so applications like editors do not have to implement the hit testing and the state machine for hovering, clicking and dragging objects themselves.
+/// The objects are picked using an [ObjectPicker].
+///
+/// The mouse events which start, continue or end an interaction with an object are marked as handled,
+/// so call [Interaction::handle_events] before handing the events to a camera control to avoid rotating the camera while dragging an object.
+///
+pub struct Interaction {
+    picker: ObjectPicker,
+    /// The distance in physical pixels the pointer has to move while a mouse button is pressed on an object before the object is dragged instead of clicked.
+    /// The default is 4 physical pixels.
+    pub drag_threshold: f32,
+    hovered: Option<usize>,
+    press: Option<Press>,
+}
+
+impl Interaction {
+    ///
+    /// Creates a new interaction state where no object is hovered or pressed.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self::new_with_picker(ObjectPicker::new(context))
+    }
+
+    ///
+    /// Creates a new interaction state which uses the given picker, for example a picker with a pick tolerance to make it easier to hover thin lines, see [ObjectPicker::tolerance].
+    ///
+    pub fn new_with_picker(picker: ObjectPicker) -> Self {
+        Self {
+            picker,
+            drag_threshold: 4.0,
+            hovered: None,
+            press: None,
+        }
+    }
+
+    ///
+    /// Returns the index of the object under the pointer, if any.
+    ///
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    ///
+    /// Returns the index of the object on which a mouse button is pressed, if any.
+    ///
+    pub fn pressed(&self) -> Option<usize> {
+        self.press.map(|press| press.index)
+    }
+
+    ///
+    /// Returns the index of the object which is being dragged, if any.
+    ///
+    pub fn dragged(&self) -> Option<usize> {
+        self.press
+            .filter(|press| press.dragging)
+            .map(|press| press.index)
+    }
+
+    ///
+    /// Forgets the hovered and pressed object without sending any events, for example when the objects have changed so the indices are no longer valid.
+    ///
+    pub fn reset(&mut self) {
+        self.hovered = None;
+        self.press = None;
+    }
+
+    ///
+    /// Processes the events of a frame and returns the resulting events for the given objects in the order they happened.
+    /// The given objects should be the same in each frame, otherwise call [Interaction::reset] when they change.
+    ///
+    /// The objects are only picked when a mouse button is pressed or released and once for the last pointer position in the events,
+    /// so the cost does not depend on the number of motion events.
+    ///
+    pub fn handle_events(
+        &mut self,
+        camera: &Camera,
+        events: &mut [Event],
+        geometries: &[&dyn Geometry],
+    ) -> Vec<ObjectEvent> {
+        self.handle_events_filtered(camera, events, geometries, &PickFilter::new())
+    }
+
+    ///
+    /// Same as [Interaction::handle_events] except that the given filter decides which of the objects can be hovered and pressed and in which order they are picked, see [Pick::pick_filtered].
+    ///
+    pub fn handle_events_filtered(
+        &mut self,
+        camera: &Camera,
+        events: &mut [Event],
+        geometries: &[&dyn Geometry],
+        filter: &PickFilter,
+    ) -> Vec<ObjectEvent> {
+        let mut object_events = Vec::new();
+        // The position of the last motion which has not yet been used to update the hovered object
+        let mut pending_hover: Option<PhysicalPoint> = None;
+        for event in events.iter_mut() {
+            match event {
+                Event::MousePress {
+                    button,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    let position = PhysicalPoint::from(*position);
+                    pending_hover = None;
+                    self.update_hover(camera, position, geometries, filter, &mut object_events);
+                    if *handled || self.press.is_some() {
+                        continue;
+                    }
+                    if let Some(index) = self.hovered {
+                        object_events.push(ObjectEvent::Down {
+                            index,
+                            button: *button,
+                            position,
+                        });
+                        self.press = Some(Press {
+                            index,
+                            button: *button,
+                            start: position,
+                            last: position,
+                            dragging: false,
+                        });
+                        *handled = true;
+                    }
+                }
+                Event::MouseRelease {
+                    button,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    let position = PhysicalPoint::from(*position);
+                    pending_hover = None;
+                    let press = match self.press {
+                        Some(press) if press.button == *button => press,
+                        _ => {
+                            self.update_hover(
+                                camera,
+                                position,
+                                geometries,
+                                filter,
+                                &mut object_events,
+                            );
+                            continue;
+                        }
+                    };
+                    self.press = None;
+                    object_events.push(ObjectEvent::Up {
+                        index: press.index,
+                        button: press.button,
+                        position,
+                    });
+                    self.update_hover(camera, position, geometries, filter, &mut object_events);
+                    if !press.dragging && self.hovered == Some(press.index) {
+                        object_events.push(ObjectEvent::Click {
+                            index: press.index,
+                            button: press.button,
+                            position,
+                        });
+                    }
+                    *handled = true;
+                }
+                Event::MouseMotion {
+                    position, handled, ..
+                } => {
+                    let position = PhysicalPoint::from(*position);
+                    if let Some(press) = &mut self.press {
+                        if !press.dragging && distance(press.start, position) > self.drag_threshold
+                        {
+                            press.dragging = true;
+                        }
+                        if press.dragging {
+                            object_events.push(ObjectEvent::Drag {
+                                index: press.index,
+                                button: press.button,
+                                start: press.start,
+                                position,
+                                delta: vec2(position.x - press.last.x, position.y - press.last.y),
+                            });
+                            press.last = position;
+                        }
+                        *handled = true;
+                    } else {
+                        pending_hover = Some(position);
+                    }
+                }
+                Event::MouseLeave => {
+                    pending_hover = None;
+                    if self.press.is_none() {
+                        if let Some(index) = self.hovered.take() {
+                            object_events.push(ObjectEvent::Leave { index });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(position) = pending_hover {
+            self.update_hover(camera, position, geometries, filter, &mut object_events);
+        }
+        object_events
+    }
+
+    // Picks the object at the given position and sends enter and leave events if it is not the hovered object
+    fn update_hover(
+        &mut self,
+        camera: &Camera,
+        position: PhysicalPoint,
+        geometries: &[&dyn Geometry],
+        filter: &PickFilter,
+        object_events: &mut Vec<ObjectEvent>,
+    ) {
+        let hovered = self
+            .picker
+            .pick_filtered(camera, position, geometries, filter)
+            .map(|pick| pick.index);
+        if hovered != self.hovered {
+            if let Some(index) = self.hovered {
+                object_events.push(ObjectEvent::Leave { index });
+            }
+            if let Some(index) = hovered {
+                object_events.push(ObjectEvent::Enter { index });
+            }
+            self.hovered = hovered;
+        }
+    }
+}
+
+fn distance(a: PhysicalPoint, b: PhysicalPoint) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
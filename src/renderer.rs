@@ -49,12 +49,84 @@ pub use object::*;
 pub mod control;
 pub use control::*;
 
+pub mod statistics;
+pub use statistics::*;
+
+pub mod portal;
+pub use portal::*;
+
+pub mod animation;
+pub use animation::*;
+
+pub mod debug;
+pub use debug::*;
+
+pub mod aov;
+pub use aov::*;
+
+pub mod progressive;
+pub use progressive::*;
+
+pub mod stereo;
+pub use stereo::*;
+
+pub mod recording;
+pub use recording::*;
+
+pub mod cube_map_target;
+pub use cube_map_target::*;
+
+pub mod masked_rendering;
+pub use masked_rendering::*;
+
+pub mod occlusion_culling;
+pub use occlusion_culling::*;
+
+pub mod gbuffer;
+pub use gbuffer::*;
+
+pub mod multi_viewport;
+pub use multi_viewport::*;
+
+pub mod canvas;
+pub use canvas::*;
+
 macro_rules! impl_render_target_extensions_body {
     () => {
+        ///
+        /// Returns this render target with the given visible layers.
+        /// Only the objects and geometries in at least one of the visible layers, see [Geometry::layers], are rendered in the render calls of this render target,
+        /// so the same list of objects can be rendered in several views, for example a main view, a minimap and an overlay, where each view shows different layers.
+        /// All layers are visible by default.
+        ///
+        pub fn with_visible_layers(mut self, visible_layers: RenderLayers) -> Self {
+            self.visible_layers = visible_layers;
+            self
+        }
+
+        ///
+        /// Sets the visible layers of this render target, see [RenderTarget::with_visible_layers].
+        ///
+        pub fn set_visible_layers(&mut self, visible_layers: RenderLayers) {
+            self.visible_layers = visible_layers;
+        }
+
+        ///
+        /// Returns the visible layers of this render target, see [RenderTarget::with_visible_layers].
+        ///
+        pub fn visible_layers(&self) -> RenderLayers {
+            self.visible_layers
+        }
+
+        fn is_visible(&self, camera: &Camera, geometry: &impl Geometry) -> bool {
+            self.visible_layers.intersects(geometry.layers()) && camera.in_frustum(&geometry.aabb())
+        }
+
         ///
         /// Render the objects using the given camera and lights into this render target.
         /// Use an empty array for the `lights` argument, if the objects does not require lights to be rendered.
-        /// Also, objects outside the camera frustum are not rendered and the objects are rendered in the order given by [cmp_render_order].
+        /// Also, objects outside the camera frustum or the visible layers, see [RenderTarget::with_visible_layers], are not rendered
+        /// and the objects are rendered in the order given by [cmp_render_order].
         ///
         pub fn render(
             &self,
@@ -68,7 +140,8 @@ macro_rules! impl_render_target_extensions_body {
         ///
         /// Render the objects using the given camera and lights into the part of this render target defined by the scissor box.
         /// Use an empty array for the `lights` argument, if the objects does not require lights to be rendered.
-        /// Also, objects outside the camera frustum are not rendered and the objects are rendered in the order given by [cmp_render_order].
+        /// Also, objects outside the camera frustum or the visible layers, see [RenderTarget::with_visible_layers], are not rendered
+        /// and the objects are rendered in the order given by [cmp_render_order].
         ///
         pub fn render_partially(
             &self,
@@ -76,78 +149,180 @@ macro_rules! impl_render_target_extensions_body {
             camera: &Camera,
             objects: impl IntoIterator<Item = impl Object>,
             lights: &[&dyn Light],
+        ) -> &Self {
+            self.render_partially_with_optional_gbuffer(scissor_box, None, camera, objects, lights)
+        }
+
+        ///
+        /// Render the objects like [RenderTarget::render], except that the geometry information of the objects with a deferred material, for example [DeferredPhysicalMaterial],
+        /// is written to the given [GBuffer], which uses a custom [GBufferLayout] and is reused between frames.
+        /// If the layout stores object IDs, the IDs of both the deferred and the forward objects are written to the G-buffer and can be read using [GBuffer::read_id_at].
+        /// Use [Context::set_object_id_output] together with [RenderTarget::render] and [RenderTarget::read_id_at] instead, if the G-buffer is not needed for anything else.
+        ///
+        pub fn render_with_gbuffer(
+            &self,
+            gbuffer: &mut GBuffer,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+        ) -> &Self {
+            self.render_partially_with_gbuffer(self.scissor_box(), gbuffer, camera, objects, lights)
+        }
+
+        ///
+        /// Render the objects like [RenderTarget::render_partially], except that the geometry information of the objects with a deferred material
+        /// is written to the given [GBuffer], see [RenderTarget::render_with_gbuffer].
+        ///
+        pub fn render_partially_with_gbuffer(
+            &self,
+            scissor_box: ScissorBox,
+            gbuffer: &mut GBuffer,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+        ) -> &Self {
+            self.render_partially_with_optional_gbuffer(
+                scissor_box,
+                Some(gbuffer),
+                camera,
+                objects,
+                lights,
+            )
+        }
+
+        fn render_partially_with_optional_gbuffer(
+            &self,
+            scissor_box: ScissorBox,
+            gbuffer: Option<&mut GBuffer>,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
         ) -> &Self {
             let (mut deferred_objects, mut forward_objects): (Vec<_>, Vec<_>) = objects
                 .into_iter()
-                .filter(|o| camera.in_frustum(&o.aabb()))
-                .partition(|o| o.material_type() == MaterialType::Deferred);
+                .enumerate()
+                .filter(|(_, o)| self.is_visible(camera, o))
+                .partition(|(_, o)| o.material_type() == MaterialType::Deferred);
 
             // Deferred
-            if deferred_objects.len() > 0 {
+            // The object IDs are stored in the context for RenderTarget::read_id_at if no G-buffer is given and object ID output is enabled
+            let object_id_output = gbuffer.is_none() && self.context.object_id_output();
+            // A G-buffer which stores object IDs is also used when there are no deferred objects, since it stores the IDs of the forward objects as well
+            let mut gbuffer =
+                gbuffer.filter(|gbuffer| gbuffer.layout().object_ids || deferred_objects.len() > 0);
+            let mut default_gbuffer = None;
+            if gbuffer.is_some() || object_id_output || deferred_objects.len() > 0 {
                 // Geometry pass
-                let mut geometry_pass_camera = camera.clone();
-                let viewport =
-                    Viewport::new_at_origin(camera.viewport().width, camera.viewport().height);
-                geometry_pass_camera.set_viewport(viewport);
-                deferred_objects.sort_by(|a, b| cmp_render_order(&geometry_pass_camera, a, b));
-                let mut geometry_pass_texture = Texture2DArray::new_empty::<[u8; 4]>(
-                    &self.context,
-                    viewport.width,
-                    viewport.height,
-                    3,
-                    Interpolation::Nearest,
-                    Interpolation::Nearest,
-                    None,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-                let mut geometry_pass_depth_texture = DepthTexture2D::new::<f32>(
-                    &self.context,
-                    viewport.width,
-                    viewport.height,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-                let gbuffer_layers = [0, 1, 2];
-                RenderTarget::new(
-                    geometry_pass_texture.as_color_target(&gbuffer_layers, None),
-                    geometry_pass_depth_texture.as_depth_target(),
-                )
-                .clear(ClearState::default())
-                .write(|| {
-                    for object in deferred_objects {
-                        object.render(&geometry_pass_camera, lights);
-                    }
-                });
-
-                // Lighting pass
-                self.write_partially(scissor_box, || {
-                    DeferredPhysicalMaterial::lighting_pass(
+                let gbuffer = match gbuffer.as_deref_mut() {
+                    Some(gbuffer) => gbuffer,
+                    None => default_gbuffer.insert(GBuffer::new(
                         &self.context,
-                        camera,
-                        ColorTexture::Array {
-                            texture: &geometry_pass_texture,
-                            layers: &gbuffer_layers,
+                        camera.viewport().width,
+                        camera.viewport().height,
+                        GBufferLayout {
+                            object_ids: object_id_output,
+                            ..Default::default()
                         },
-                        DepthTexture::Single(&geometry_pass_depth_texture),
-                        lights,
-                    )
-                });
+                    )),
+                };
+                gbuffer.render_geometry(camera, &mut deferred_objects, lights);
+
+                // Lighting pass
+                if deferred_objects.len() > 0 {
+                    self.write_partially(scissor_box, || gbuffer.lighting_pass(camera, lights));
+                }
             }
 
             // Forward
-            forward_objects.sort_by(|a, b| cmp_render_order(camera, a, b));
+            forward_objects.sort_by(|a, b| cmp_render_order(camera, &a.1, &b.1));
             self.write_partially(scissor_box, || {
-                for object in forward_objects {
+                for (_, object) in forward_objects.iter() {
                     object.render(camera, lights);
                 }
             });
+            if let Some(gbuffer) = gbuffer.or(default_gbuffer.as_mut()) {
+                gbuffer.render_object_ids(camera, &forward_objects);
+            }
+            if object_id_output {
+                *self.context.object_ids.write().unwrap() =
+                    default_gbuffer.and_then(|gbuffer| gbuffer.into_object_ids());
+            }
+            self
+        }
+
+        ///
+        /// Render the objects like [RenderTarget::render], except that the depth of the opaque objects is rendered first in a depth pre-pass
+        /// and then the opaque objects are rendered with [DepthTest::Equal], so the possibly expensive material is only evaluated for the visible surface in each pixel.
+        /// Only the objects with a [DepthTest::Less] or [DepthTest::LessOrEqual] depth test are rendered with [DepthTest::Equal], the others keep their depth test.
+        /// The built-in vertex shaders declare `gl_Position` as invariant so both passes compute the exact same depth, custom vertex shaders should do the same.
+        /// This reduces the cost of rendering scenes with a lot of overdraw, for example a forest or a city, at the cost of rendering the geometry twice.
+        /// The depth pre-pass does not take alpha cutout into account, so objects with an alpha cutout material, for example foliage, should not be opaque.
+        ///
+        pub fn render_with_depth_prepass(
+            &self,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+        ) -> &Self {
+            let (mut opaque_objects, other_objects): (Vec<_>, Vec<_>) = objects
+                .into_iter()
+                .filter(|o| self.is_visible(camera, o))
+                .partition(|o| o.material_type() == MaterialType::Opaque);
+            opaque_objects.sort_by(|a, b| cmp_render_order(camera, a, b));
+
+            // Depth pre-pass
+            let depth_material = DepthMaterial {
+                render_states: RenderStates {
+                    write_mask: WriteMask::DEPTH,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            self.write(|| {
+                for object in opaque_objects.iter() {
+                    object.render_with_material(&depth_material, camera, &[]);
+                }
+            });
+
+            let previous = self.context.depth_test_override();
+            self.context.set_depth_test_override(Some(DepthTest::Equal));
+            self.render(camera, &opaque_objects, lights);
+            self.context.set_depth_test_override(previous);
+            self.render(camera, other_objects, lights)
+        }
+
+        ///
+        /// Render the objects like [RenderTarget::render], except that the objects which were hidden behind other objects in the latest occlusion test are not rendered.
+        /// After rendering, the bounding boxes of the objects are tested against the depth of the rendered objects, and the result is used in one of the next frames, see [OcclusionCulling].
+        /// The same objects must be given in the same order each frame.
+        ///
+        pub fn render_with_occlusion_culling(
+            &self,
+            occlusion_culling: &mut OcclusionCulling,
+            camera: &Camera,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+        ) -> &Self {
+            let objects = objects.into_iter().collect::<Vec<_>>();
+            let aabbs = objects.iter().map(|o| o.aabb()).collect::<Vec<_>>();
+            let visible = occlusion_culling.update(camera, &aabbs);
+            self.render(
+                camera,
+                objects
+                    .iter()
+                    .zip(visible)
+                    .filter(|(_, visible)| *visible)
+                    .map(|(object, _)| object),
+                lights,
+            );
+            self.write(|| occlusion_culling.test(camera, &aabbs));
             self
         }
 
         ///
         /// Render the geometries with the given [Material] using the given camera and lights into this render target.
         /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
+        /// Also, geometries outside the camera frustum or the visible layers, see [RenderTarget::with_visible_layers], are not rendered.
         ///
         pub fn render_with_material(
             &self,
@@ -180,7 +355,7 @@ macro_rules! impl_render_target_extensions_body {
             self.write_partially(scissor_box, || {
                 for object in geometries
                     .into_iter()
-                    .filter(|o| camera.in_frustum(&o.aabb()))
+                    .filter(|o| self.is_visible(camera, o))
                 {
                     object.render_with_material(material, camera, lights);
                 }
@@ -191,6 +366,7 @@ macro_rules! impl_render_target_extensions_body {
         ///
         /// Render the geometries with the given [PostMaterial] using the given camera and lights into this render target.
         /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
+        /// Also, geometries outside the camera frustum or the visible layers, see [RenderTarget::with_visible_layers], are not rendered.
         ///
         pub fn render_with_post_material(
             &self,
@@ -229,7 +405,7 @@ macro_rules! impl_render_target_extensions_body {
             self.write_partially(scissor_box, || {
                 for object in geometries
                     .into_iter()
-                    .filter(|o| camera.in_frustum(&o.aabb()))
+                    .filter(|o| self.is_visible(camera, o))
                 {
                     object.render_with_post_material(
                         material,
@@ -279,6 +455,23 @@ impl_render_target_extensions!(RenderTargetMultisample<C: TextureDataType, D: De
 impl_render_target_extensions!(ColorTargetMultisample<C: TextureDataType>);
 impl_render_target_extensions!(DepthTargetMultisample<D: DepthTextureDataType>);
 
+impl RenderTarget<'_> {
+    ///
+    /// Returns the ID of the object visible at the given pixel in the latest render call, ie. the index of the object
+    /// in the list of objects given to for example [RenderTarget::render], or `None` if no object is visible at the pixel.
+    /// Both the deferred and the forward objects are included.
+    /// Requires that object ID output is enabled using [Context::set_object_id_output] before rendering,
+    /// except when rendering with a custom G-buffer, where the IDs are read using [GBuffer::read_id_at] instead.
+    /// The pixel is given in the same coordinates as the camera viewport.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn read_id_at(&self, pixel: impl Into<PhysicalPoint>) -> Option<u32> {
+        let mut object_ids = self.context.object_ids.write().unwrap();
+        let (texture, layer, precision, viewport) = object_ids.as_mut()?;
+        gbuffer::read_id_at(texture, *layer, *precision, *viewport, pixel)
+    }
+}
+
 ///
 /// Returns an orthographic camera for viewing 2D content.
 /// The camera is placed at the center of the given viewport.
@@ -306,8 +499,9 @@ pub fn camera2d(viewport: Viewport) -> Camera {
 }
 
 ///
-/// Compare function for sorting objects based on distance from the camera.
-/// The order is opaque objects from nearest to farthest away from the camera,
+/// Compare function for sorting objects based on their render queue and distance from the camera.
+/// The objects are first ordered by their [RenderQueue] and within each render queue the order is
+/// opaque objects from nearest to farthest away from the camera,
 /// then transparent objects from farthest away to closest to the camera.
 ///
 pub fn cmp_render_order(
@@ -315,7 +509,9 @@ pub fn cmp_render_order(
     obj0: impl Object,
     obj1: impl Object,
 ) -> std::cmp::Ordering {
-    if obj0.material_type() == MaterialType::Transparent
+    if obj0.render_queue() != obj1.render_queue() {
+        obj0.render_queue().cmp(&obj1.render_queue())
+    } else if obj0.material_type() == MaterialType::Transparent
         && obj1.material_type() != MaterialType::Transparent
     {
         std::cmp::Ordering::Greater
@@ -0,0 +1,412 @@
+//!
+//! Playback of animation clips consisting of key framed transformation and morph weight tracks, with looping and cross-fading between clips.
+//!
+
+use crate::renderer::*;
+
+///
+/// The values of an [AnimationTrack] at each key frame.
+///
+#[derive(Clone, Debug)]
+pub enum TrackValues {
+    /// The translation of the target.
+    Translation(Vec<Vec3>),
+    /// The rotation of the target.
+    Rotation(Vec<Quat>),
+    /// The scale of the target.
+    Scale(Vec<Vec3>),
+    /// The morph target weights of the target, one list of weights for each key frame.
+    MorphWeights(Vec<Vec<f32>>),
+}
+
+///
+/// A key framed track in an [AnimationClip] animating one property of a target.
+/// The values are interpolated linearly between the key frames, using spherical linear interpolation for rotations.
+///
+#[derive(Clone, Debug)]
+pub struct AnimationTrack {
+    /// The name of the animated target, for example the name of a [SceneNode].
+    pub target: String,
+    /// The time of each key frame in seconds in increasing order.
+    pub times: Vec<f32>,
+    /// The value at each key frame.
+    pub values: TrackValues,
+}
+
+impl AnimationTrack {
+    fn sample(&self, time: f32, pose: &mut AnimationPose) {
+        if self.times.is_empty() {
+            return;
+        }
+        let next = self.times.partition_point(|t| *t <= time);
+        let (i0, i1, f) = if next == 0 {
+            (0, 0, 0.0)
+        } else if next >= self.times.len() {
+            (self.times.len() - 1, self.times.len() - 1, 0.0)
+        } else {
+            let (t0, t1) = (self.times[next - 1], self.times[next]);
+            (next - 1, next, (time - t0) / (t1 - t0).max(0.000001))
+        };
+        match &self.values {
+            TrackValues::Translation(values) => {
+                pose.translation = Some(values[i0] + (values[i1] - values[i0]) * f);
+            }
+            TrackValues::Rotation(values) => {
+                pose.rotation = Some(values[i0].slerp(values[i1], f));
+            }
+            TrackValues::Scale(values) => {
+                pose.scale = Some(values[i0] + (values[i1] - values[i0]) * f);
+            }
+            TrackValues::MorphWeights(values) => {
+                pose.morph_weights = Some(mix_weights(&values[i0], &values[i1], f));
+            }
+        }
+    }
+}
+
+///
+/// A named set of [AnimationTrack]s that are played together by an [AnimationPlayer].
+///
+#[derive(Clone, Debug)]
+pub struct AnimationClip {
+    /// The name of the clip used for choosing the clip to play.
+    pub name: String,
+    /// The tracks of the clip.
+    pub tracks: Vec<AnimationTrack>,
+}
+
+impl AnimationClip {
+    ///
+    /// Returns the duration of the clip in seconds, ie. the time of the last key frame in any of the tracks.
+    ///
+    pub fn duration(&self) -> f32 {
+        self.tracks
+            .iter()
+            .filter_map(|track| track.times.last())
+            .fold(0.0, |max, t| t.max(max))
+    }
+
+    fn pose(&self, target: &str, time: f32) -> AnimationPose {
+        let mut pose = AnimationPose::default();
+        for track in self.tracks.iter().filter(|track| track.target == target) {
+            track.sample(time, &mut pose);
+        }
+        pose
+    }
+}
+
+///
+/// The animated properties of a target at a specific time, see [AnimationPlayer::pose].
+/// A property is `None` if it is not animated.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationPose {
+    /// The translation of the target.
+    pub translation: Option<Vec3>,
+    /// The rotation of the target.
+    pub rotation: Option<Quat>,
+    /// The scale of the target.
+    pub scale: Option<Vec3>,
+    /// The morph target weights of the target.
+    pub morph_weights: Option<Vec<f32>>,
+}
+
+impl AnimationPose {
+    ///
+    /// Returns the transformation composed of the translation, rotation and scale, where the properties that are not animated are taken from the given default values.
+    ///
+    pub fn transformation(&self, translation: Vec3, rotation: Quat, scale: Vec3) -> Mat4 {
+        let s = self.scale.unwrap_or(scale);
+        Mat4::from_translation(self.translation.unwrap_or(translation))
+            * Mat4::from(self.rotation.unwrap_or(rotation))
+            * Mat4::from_nonuniform_scale(s.x, s.y, s.z)
+    }
+
+    fn blend(self, other: Self, f: f32) -> Self {
+        fn mix<T>(a: Option<T>, b: Option<T>, f: impl Fn(T, T) -> T) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(f(a, b)),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            translation: mix(self.translation, other.translation, |a, b| a + (b - a) * f),
+            rotation: mix(self.rotation, other.rotation, |a, b| a.slerp(b, f)),
+            scale: mix(self.scale, other.scale, |a, b| a + (b - a) * f),
+            morph_weights: mix(self.morph_weights, other.morph_weights, |a, b| {
+                mix_weights(&a, &b, f)
+            }),
+        }
+    }
+}
+
+fn mix_weights(a: &[f32], b: &[f32], f: f32) -> Vec<f32> {
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            let (a, b) = (
+                a.get(i).copied().unwrap_or(0.0),
+                b.get(i).copied().unwrap_or(0.0),
+            );
+            a + (b - a) * f
+        })
+        .collect()
+}
+
+///
+/// Defines what happens when the playback reaches the end of an [AnimationClip].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Stops at the end of the clip.
+    Once,
+    /// Starts over from the beginning of the clip.
+    #[default]
+    Repeat,
+    /// Plays the clip backwards when reaching the end and forwards again when reaching the beginning.
+    PingPong,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ClipState {
+    clip: usize,
+    time: f32,
+}
+
+///
+/// Plays [AnimationClip]s with support for pausing, seeking, looping, changing the playback speed and cross-fading between clips.
+/// Call [AnimationPlayer::update] each frame with the elapsed time and then apply the animation to the animated objects,
+/// for example using [AnimationPlayer::apply_to_scene] or [AnimationPlayer::pose].
+///
+#[derive(Clone, Debug)]
+pub struct AnimationPlayer {
+    clips: Vec<AnimationClip>,
+    current: Option<ClipState>,
+    previous: Option<ClipState>,
+    fade: (f32, f32),
+    paused: bool,
+    /// The playback speed, where 1 is normal speed and a negative speed plays the clips backwards.
+    pub speed: f32,
+    /// Defines what happens when the playback reaches the end of a clip.
+    pub loop_mode: LoopMode,
+}
+
+impl AnimationPlayer {
+    ///
+    /// Creates a new animation player with the given clips. No clip is playing until [AnimationPlayer::play] is called.
+    ///
+    pub fn new(clips: Vec<AnimationClip>) -> Self {
+        Self {
+            clips,
+            current: None,
+            previous: None,
+            fade: (0.0, 0.0),
+            paused: false,
+            speed: 1.0,
+            loop_mode: LoopMode::default(),
+        }
+    }
+
+    ///
+    /// Returns the clips of this player.
+    ///
+    pub fn clips(&self) -> &[AnimationClip] {
+        &self.clips
+    }
+
+    ///
+    /// Adds a clip to this player.
+    ///
+    pub fn add_clip(&mut self, clip: AnimationClip) {
+        self.clips.push(clip);
+    }
+
+    ///
+    /// Starts playing the clip with the given name from the beginning. Returns false if there is no clip with the given name.
+    ///
+    pub fn play(&mut self, name: &str) -> bool {
+        self.cross_fade(name, 0.0)
+    }
+
+    ///
+    /// Starts playing the clip with the given name from the beginning, while blending from the currently playing clip to the new clip over the given duration in seconds.
+    /// Returns false if there is no clip with the given name.
+    ///
+    pub fn cross_fade(&mut self, name: &str, duration: f32) -> bool {
+        if let Some(clip) = self.clips.iter().position(|c| c.name == name) {
+            self.previous = if duration > 0.0 { self.current } else { None };
+            self.fade = (0.0, duration);
+            self.current = Some(ClipState { clip, time: 0.0 });
+            self.paused = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Stops the playback.
+    ///
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.previous = None;
+    }
+
+    ///
+    /// Pauses the playback, which is continued by [AnimationPlayer::resume].
+    ///
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    ///
+    /// Continues the playback after [AnimationPlayer::pause].
+    ///
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    ///
+    /// Returns whether or not a clip is playing and the playback is not paused.
+    ///
+    pub fn is_playing(&self) -> bool {
+        !self.paused && self.current.is_some()
+    }
+
+    ///
+    /// Returns the name of the currently playing clip.
+    ///
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current
+            .map(|state| self.clips[state.clip].name.as_str())
+    }
+
+    ///
+    /// Returns the time in seconds in the currently playing clip.
+    ///
+    pub fn time(&self) -> f32 {
+        self.current.map(|state| state.time).unwrap_or(0.0)
+    }
+
+    ///
+    /// Moves the playback of the currently playing clip to the given time in seconds and ends any cross-fade.
+    ///
+    pub fn seek(&mut self, time: f32) {
+        self.previous = None;
+        if let Some(state) = &mut self.current {
+            state.time = time;
+        }
+    }
+
+    ///
+    /// Advances the playback by the given elapsed time in seconds multiplied by the playback speed.
+    ///
+    pub fn update(&mut self, elapsed_time: f32) {
+        if self.paused {
+            return;
+        }
+        let delta = elapsed_time * self.speed;
+        for state in [&mut self.current, &mut self.previous]
+            .into_iter()
+            .flatten()
+        {
+            state.time += delta;
+        }
+        if self.previous.is_some() {
+            self.fade.0 += elapsed_time;
+            if self.fade.0 >= self.fade.1 {
+                self.previous = None;
+            }
+        }
+    }
+
+    ///
+    /// Returns the blended pose of the target with the given name at the current time or `None` if the target is not animated by the playing clips.
+    ///
+    pub fn pose(&self, target: &str) -> Option<AnimationPose> {
+        let current = self.clip_pose(self.current?, target);
+        let pose = if let Some(previous) = self.previous {
+            let f = (self.fade.0 / self.fade.1.max(0.000001)).clamp(0.0, 1.0);
+            self.clip_pose(previous, target).blend(current, f)
+        } else {
+            current
+        };
+        if pose == AnimationPose::default() {
+            None
+        } else {
+            Some(pose)
+        }
+    }
+
+    ///
+    /// Applies the current poses to the nodes in the given scene with the same name as the targets of the tracks.
+    /// The transformation of an animated node is replaced by the animated translation, rotation and scale, where the properties that are not animated are taken from the current transformation of the node,
+    /// and the animated morph weights are applied to the meshes attached to the node.
+    ///
+    pub fn apply_to_scene<M: Material>(&self, scene: &mut Scene<M>) {
+        let targets = self.targets();
+        for target in targets {
+            if let (Some(pose), Some(node)) = (self.pose(&target), scene.find_mut(&target)) {
+                let (translation, rotation, scale) = decompose(node.transformation());
+                node.set_transformation(pose.transformation(translation, rotation, scale));
+                if let Some(weights) = &pose.morph_weights {
+                    for object in node.objects.iter_mut() {
+                        object.set_morph_weights(weights);
+                    }
+                }
+            }
+        }
+        scene.update_transformations();
+    }
+
+    fn targets(&self) -> Vec<String> {
+        let mut targets = Vec::new();
+        for state in [self.current, self.previous].into_iter().flatten() {
+            for track in self.clips[state.clip].tracks.iter() {
+                if !targets.contains(&track.target) {
+                    targets.push(track.target.clone());
+                }
+            }
+        }
+        targets
+    }
+
+    fn clip_pose(&self, state: ClipState, target: &str) -> AnimationPose {
+        let clip = &self.clips[state.clip];
+        let duration = clip.duration();
+        let time = if duration <= 0.0 {
+            0.0
+        } else {
+            match self.loop_mode {
+                LoopMode::Once => state.time.clamp(0.0, duration),
+                LoopMode::Repeat => state.time.rem_euclid(duration),
+                LoopMode::PingPong => {
+                    let t = state.time.rem_euclid(2.0 * duration);
+                    if t > duration {
+                        2.0 * duration - t
+                    } else {
+                        t
+                    }
+                }
+            }
+        };
+        clip.pose(target, time)
+    }
+}
+
+///
+/// Decomposes a transformation without shear into translation, rotation and scale.
+///
+fn decompose(transformation: Mat4) -> (Vec3, Quat, Vec3) {
+    let translation = transformation.w.truncate();
+    let scale = vec3(
+        transformation.x.truncate().magnitude(),
+        transformation.y.truncate().magnitude(),
+        transformation.z.truncate().magnitude(),
+    );
+    let rotation = Mat3::from_cols(
+        transformation.x.truncate() / scale.x.max(0.000001),
+        transformation.y.truncate() / scale.y.max(0.000001),
+        transformation.z.truncate() / scale.z.max(0.000001),
+    );
+    (translation, Quat::from(rotation), scale)
+}
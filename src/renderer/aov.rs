@@ -0,0 +1,256 @@
+//!
+//! Rendering of arbitrary output variables (AOVs), ie. auxiliary buffers like object IDs, normals, depth, motion vectors and albedo,
+//! which can be used for post processing or compositing outside of this crate, for example by a denoiser or a machine learning pipeline.
+//!
+
+use crate::renderer::*;
+
+///
+/// An auxiliary buffer which can be rendered by [AovTargets].
+/// All buffers contain 32 bit floating point values.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Aov {
+    /// The index of the visible object in the list of objects given to [AovTargets::render] plus one in the red channel, zero where no object is visible.
+    ObjectId,
+    /// The world space normal of the visible surface in the red, green and blue channels.
+    Normal,
+    /// The distance from the camera to the visible surface along the view direction in the red channel and the value in the depth buffer in the green channel.
+    Depth,
+    /// The screen space motion of the visible surface since the previous call to [AovTargets::render] in pixels in the red and green channels,
+    /// ie. the current pixel position minus the pixel position in the previous frame.
+    MotionVector,
+    /// The linear base color of the material of the visible object, see [Material::albedo].
+    Albedo,
+}
+
+impl Aov {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ObjectId => "OBJECT_ID",
+            Self::Normal => "NORMAL",
+            Self::Depth => "DEPTH",
+            Self::MotionVector => "MOTION_VECTOR",
+            Self::Albedo => "ALBEDO",
+        }
+    }
+}
+
+///
+/// A set of textures which auxiliary buffers, see [Aov], are rendered into.
+/// All of the buffers are rendered in one pass, each into a layer of the same texture array.
+///
+pub struct AovTargets {
+    aovs: Vec<Aov>,
+    layers: Vec<u32>,
+    texture: Texture2DArray,
+    depth_texture: DepthTexture2D,
+    previous_view_projection: Option<Mat4>,
+}
+
+impl AovTargets {
+    ///
+    /// Creates new targets with the given size for the given auxiliary buffers.
+    ///
+    pub fn new(context: &Context, width: u32, height: u32, aovs: &[Aov]) -> Self {
+        let mut unique_aovs = Vec::new();
+        for aov in aovs {
+            if !unique_aovs.contains(aov) {
+                unique_aovs.push(*aov);
+            }
+        }
+        Self {
+            texture: Texture2DArray::new_empty::<[f32; 4]>(
+                context,
+                width,
+                height,
+                unique_aovs.len().max(1) as u32,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            depth_texture: DepthTexture2D::new::<f32>(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            layers: (0..unique_aovs.len() as u32).collect(),
+            aovs: unique_aovs,
+            previous_view_projection: None,
+        }
+    }
+
+    ///
+    /// Returns the auxiliary buffers rendered by these targets.
+    ///
+    pub fn aovs(&self) -> &[Aov] {
+        &self.aovs
+    }
+
+    ///
+    /// Renders the auxiliary buffers for the given objects, which are assumed to be static.
+    /// The motion vectors are therefore only caused by the movement of the camera since the previous call.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(&mut self, camera: &Camera, objects: impl IntoIterator<Item = impl Object>) {
+        self.render_with_motion(
+            camera,
+            objects.into_iter().map(|object| (object, Mat4::identity())),
+        )
+    }
+
+    ///
+    /// Renders the auxiliary buffers for the given objects, each given together with its motion since the previous call,
+    /// ie. the transformation from the current world space position to the previous world space position.
+    /// For an object with a transformation, the motion is `previous_transformation * current_transformation.invert()`.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render_with_motion(
+        &mut self,
+        camera: &Camera,
+        objects: impl IntoIterator<Item = (impl Object, Mat4)>,
+    ) {
+        if self.aovs.is_empty() {
+            return;
+        }
+        let viewport = Viewport::new_at_origin(self.texture.width(), self.texture.height());
+        let mut aov_camera = camera.clone();
+        aov_camera.set_viewport(viewport);
+        let view_projection = camera.projection() * camera.view();
+        let previous_view_projection = self.previous_view_projection.unwrap_or(view_projection);
+        let aovs = &self.aovs;
+        RenderTarget::new(
+            self.texture.as_color_target(&self.layers, None),
+            self.depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
+        .write(|| {
+            for (index, (object, motion)) in objects.into_iter().enumerate() {
+                if !aov_camera.in_frustum(&object.aabb()) {
+                    continue;
+                }
+                object.render_with_material(
+                    &AovMaterial {
+                        aovs,
+                        object_id: index as u32 + 1,
+                        albedo: object.albedo(),
+                        motion,
+                        previous_view_projection,
+                    },
+                    &aov_camera,
+                    &[],
+                );
+            }
+        });
+        self.previous_view_projection = Some(view_projection);
+    }
+
+    ///
+    /// Returns the texture array containing the auxiliary buffers, where each buffer is in the layer with the same index as in [AovTargets::aovs].
+    ///
+    pub fn texture(&self) -> &Texture2DArray {
+        &self.texture
+    }
+
+    ///
+    /// Returns the given auxiliary buffer as a [ColorTexture] which can be used as input to an effect or a [PostMaterial],
+    /// or `None` if the buffer is not rendered by these targets.
+    ///
+    pub fn color_texture(&self, aov: Aov) -> Option<ColorTexture> {
+        let layer = self.layer(aov)? as usize;
+        Some(ColorTexture::Array {
+            texture: &self.texture,
+            layers: &self.layers[layer..layer + 1],
+        })
+    }
+
+    ///
+    /// Returns the depth texture used when rendering the auxiliary buffers.
+    ///
+    pub fn depth_texture(&self) -> DepthTexture {
+        DepthTexture::Single(&self.depth_texture)
+    }
+
+    ///
+    /// Reads the given auxiliary buffer from the GPU, or returns `None` if the buffer is not rendered by these targets.
+    /// The pixels are ordered in rows from the top to the bottom.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn read(&mut self, aov: Aov) -> Option<Vec<[f32; 4]>> {
+        let layer = [self.layer(aov)?];
+        Some(
+            self.texture
+                .as_color_target(&layer, None)
+                .read::<[f32; 4]>(),
+        )
+    }
+
+    ///
+    /// Discards the camera from the previous call to [AovTargets::render], so the next motion vectors are zero for static objects, for example after a cut.
+    ///
+    pub fn reset_motion(&mut self) {
+        self.previous_view_projection = None;
+    }
+
+    fn layer(&self, aov: Aov) -> Option<u32> {
+        self.aovs.iter().position(|a| *a == aov).map(|i| i as u32)
+    }
+}
+
+struct AovMaterial<'a> {
+    aovs: &'a [Aov],
+    object_id: u32,
+    albedo: Color,
+    motion: Mat4,
+    previous_view_projection: Mat4,
+}
+
+impl Material for AovMaterial<'_> {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        let mut source = String::new();
+        let mut attributes = FragmentAttributes::NONE;
+        for (location, aov) in self.aovs.iter().enumerate() {
+            source.push_str(&format!(
+                "#define USE_{0}\n#define {0}_LOCATION {1}\n",
+                aov.name(),
+                location
+            ));
+            match aov {
+                Aov::Normal => attributes.normal = true,
+                Aov::Depth | Aov::MotionVector => attributes.position = true,
+                _ => {}
+            }
+        }
+        if attributes.position {
+            source.push_str("#define USE_POSITION\n");
+        }
+        source.push_str(include_str!("shaders/aov.frag"));
+        FragmentShader { source, attributes }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform_if_required("objectId", self.object_id as f32);
+        program.use_uniform_if_required("albedo", self.albedo);
+        program.use_uniform_if_required("view", camera.view());
+        program.use_uniform_if_required("viewProjection", camera.projection() * camera.view());
+        program.use_uniform_if_required("previousViewProjection", self.previous_view_projection);
+        program.use_uniform_if_required("motion", self.motion);
+        let viewport = camera.viewport();
+        program.use_uniform_if_required(
+            "resolution",
+            vec2(viewport.width as f32, viewport.height as f32),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
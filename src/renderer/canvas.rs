@@ -0,0 +1,554 @@
+//!
+//! An immediate mode 2D drawing layer for simple overlays, HUDs and editors, see [Canvas2D].
+//!
+
+use crate::renderer::*;
+use std::ops::Range;
+use std::sync::Arc;
+
+///
+/// A monospace bitmap font used for drawing text on a [Canvas2D].
+/// The font is a texture containing a grid of glyphs of equal size, where the glyphs are ordered row by row starting at the top left corner,
+/// for example the printable ASCII characters from `' '` to `'~'` in 16 columns and 6 rows.
+/// The glyphs should be white on a transparent background, so they can be tinted with the color of the text.
+///
+pub struct CanvasFont {
+    texture: Arc<Texture2D>,
+    columns: u32,
+    rows: u32,
+    first_char: u32,
+}
+
+impl CanvasFont {
+    ///
+    /// Creates a new font from the given texture containing the given number of columns and rows of glyphs, where the first glyph is the given character.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_texture: &CpuTexture,
+        columns: u32,
+        rows: u32,
+        first_char: char,
+    ) -> Self {
+        Self {
+            texture: Arc::new(Texture2D::new(context, cpu_texture)),
+            columns: columns.max(1),
+            rows: rows.max(1),
+            first_char: first_char as u32,
+        }
+    }
+
+    ///
+    /// Returns the texture containing the glyphs.
+    ///
+    pub fn texture(&self) -> &Arc<Texture2D> {
+        &self.texture
+    }
+
+    ///
+    /// Returns the width of one glyph when the text is drawn with the given height.
+    ///
+    pub fn glyph_width(&self, height: f32) -> f32 {
+        let glyph_width = self.texture.width() as f32 / self.columns as f32;
+        let glyph_height = self.texture.height() as f32 / self.rows as f32;
+        height * glyph_width / glyph_height
+    }
+
+    ///
+    /// Returns the width and height of the given text when drawn with the given height, taking line breaks into account.
+    ///
+    pub fn text_size(&self, text: &str, height: f32) -> Vec2 {
+        let lines = text.lines().collect::<Vec<_>>();
+        let longest = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        vec2(
+            longest as f32 * self.glyph_width(height),
+            lines.len() as f32 * height,
+        )
+    }
+
+    // Returns the uv coordinates of the bottom left and top right corner of the glyph of the given character in the flipped texture
+    fn glyph_uvs(&self, c: char) -> Option<(Vec2, Vec2)> {
+        let index = (c as u32).checked_sub(self.first_char)?;
+        if index >= self.columns * self.rows {
+            return None;
+        }
+        let column = (index % self.columns) as f32;
+        let row = (index / self.columns) as f32;
+        let size = vec2(1.0 / self.columns as f32, 1.0 / self.rows as f32);
+        Some((
+            vec2(column * size.x, 1.0 - (row + 1.0) * size.y),
+            vec2((column + 1.0) * size.x, 1.0 - row * size.y),
+        ))
+    }
+}
+
+///
+/// An immediate mode 2D drawing layer, where lines, rectangles, circles, paths and text are drawn by calling a method each frame
+/// instead of creating and updating a geometry and an object for each of them.
+/// The shapes are accumulated until [Canvas2D::render] is called, which renders all of them with the [camera2d] camera in as few draw calls as possible
+/// and then clears the canvas, ready for the next frame.
+/// The positions and sizes are in pixels, where `(0, 0)` is the bottom left corner of the render target.
+///
+/// The shapes are drawn in the order they were added, so later shapes are drawn on top of earlier shapes.
+/// The edges of the shapes are anti-aliased by fading out the alpha over a small band, see [Canvas2D::set_anti_aliasing_width],
+/// so multisampling is not needed.
+///
+pub struct Canvas2D {
+    context: Context,
+    anti_aliasing_width: f32,
+    positions: Vec<Vec2>,
+    colors: Vec<Color>,
+    uvs: Vec<Vec2>,
+    indices: Vec<u32>,
+    batches: Vec<(Option<Arc<Texture2D>>, Range<u32>)>,
+    position_buffer: VertexBuffer,
+    color_buffer: VertexBuffer,
+    uv_buffer: VertexBuffer,
+    element_buffer: ElementBuffer,
+}
+
+impl Canvas2D {
+    ///
+    /// Creates a new empty canvas.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            anti_aliasing_width: 1.0,
+            positions: Vec::new(),
+            colors: Vec::new(),
+            uvs: Vec::new(),
+            indices: Vec::new(),
+            batches: Vec::new(),
+            position_buffer: VertexBuffer::new(context),
+            color_buffer: VertexBuffer::new(context),
+            uv_buffer: VertexBuffer::new(context),
+            element_buffer: ElementBuffer::new(context),
+        }
+    }
+
+    ///
+    /// Returns the width in pixels of the band along the edges of the shapes where the alpha fades out, see [Canvas2D::set_anti_aliasing_width].
+    ///
+    pub fn anti_aliasing_width(&self) -> f32 {
+        self.anti_aliasing_width
+    }
+
+    ///
+    /// Sets the width in pixels of the band along the edges of the shapes where the alpha fades out, which is 1 pixel by default.
+    /// A width of zero disables the anti-aliasing, for example when rendering to a multisample render target.
+    ///
+    pub fn set_anti_aliasing_width(&mut self, width: f32) {
+        self.anti_aliasing_width = width.max(0.0);
+    }
+
+    ///
+    /// Returns whether or not anything has been drawn since the last call to [Canvas2D::render] or [Canvas2D::clear].
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    ///
+    /// Draws a line between the two given points with the given thickness in pixels.
+    ///
+    pub fn line(
+        &mut self,
+        start: impl Into<PhysicalPoint>,
+        end: impl Into<PhysicalPoint>,
+        thickness: f32,
+        color: Color,
+    ) {
+        self.path([start.into(), end.into()], false, thickness, color);
+    }
+
+    ///
+    /// Draws a filled rectangle with the given bottom left corner and size.
+    ///
+    pub fn rect(&mut self, position: impl Into<PhysicalPoint>, size: Vec2, color: Color) {
+        let points = rect_points(position.into(), size);
+        self.polygon(points, color);
+    }
+
+    ///
+    /// Draws the outline of a rectangle with the given bottom left corner and size, where the outline is centered on the edges of the rectangle.
+    ///
+    pub fn rect_outline(
+        &mut self,
+        position: impl Into<PhysicalPoint>,
+        size: Vec2,
+        thickness: f32,
+        color: Color,
+    ) {
+        let points = rect_points(position.into(), size);
+        self.path(points, true, thickness, color);
+    }
+
+    ///
+    /// Draws a filled circle with the given center and radius.
+    ///
+    pub fn circle(&mut self, center: impl Into<PhysicalPoint>, radius: f32, color: Color) {
+        let points = circle_points(center.into(), radius);
+        self.polygon(points, color);
+    }
+
+    ///
+    /// Draws the outline of a circle with the given center and radius, where the outline is centered on the edge of the circle.
+    ///
+    pub fn circle_outline(
+        &mut self,
+        center: impl Into<PhysicalPoint>,
+        radius: f32,
+        thickness: f32,
+        color: Color,
+    ) {
+        let points = circle_points(center.into(), radius);
+        self.path(points, true, thickness, color);
+    }
+
+    ///
+    /// Draws a line through the given points with the given thickness in pixels, which is closed by a line from the last to the first point if `closed` is true.
+    /// The lines are joined with miter joins.
+    ///
+    pub fn path(
+        &mut self,
+        points: impl IntoIterator<Item = impl Into<PhysicalPoint>>,
+        closed: bool,
+        thickness: f32,
+        color: Color,
+    ) {
+        let points = clean_points(points);
+        let count = points.len();
+        if count < 2 || thickness <= 0.0 {
+            return;
+        }
+        let closed = closed && count > 2;
+        let aa = self.anti_aliasing_width;
+        // Lines thinner than the anti-aliasing band fade out instead of getting thinner
+        let (thickness, color) = if aa > 0.0 && thickness < aa {
+            (aa, with_alpha(color, thickness / aa))
+        } else {
+            (thickness, color)
+        };
+        let half_inner = 0.5 * (thickness - aa);
+        let half_outer = half_inner + aa;
+
+        let segment_count = if closed { count } else { count - 1 };
+        let normals = (0..segment_count)
+            .map(|i| {
+                let d = (points[(i + 1) % count] - points[i]).normalize();
+                vec2(-d.y, d.x)
+            })
+            .collect::<Vec<_>>();
+        let transparent = with_alpha(color, 0.0);
+        let vertices_per_point = if aa > 0.0 { 4 } else { 2 };
+        let base = self.begin(None);
+        for (i, point) in points.iter().enumerate() {
+            let normal = if !closed && i == 0 {
+                normals[0]
+            } else if !closed && i == count - 1 {
+                normals[count - 2]
+            } else {
+                miter(
+                    normals[(i + segment_count - 1) % segment_count],
+                    normals[i % segment_count],
+                )
+            };
+            if aa > 0.0 {
+                self.vertex(*point + normal * half_outer, transparent);
+                self.vertex(*point + normal * half_inner, color);
+                self.vertex(*point - normal * half_inner, color);
+                self.vertex(*point - normal * half_outer, transparent);
+            } else {
+                self.vertex(*point + normal * 0.5 * thickness, color);
+                self.vertex(*point - normal * 0.5 * thickness, color);
+            }
+        }
+        for i in 0..segment_count as u32 {
+            let a = base + i * vertices_per_point;
+            let b = base + ((i + 1) % count as u32) * vertices_per_point;
+            for k in 0..vertices_per_point - 1 {
+                self.quad(a + k, a + k + 1, b + k + 1, b + k);
+            }
+        }
+    }
+
+    ///
+    /// Draws a filled convex polygon with the given corners.
+    /// The corners can be given in clockwise or counterclockwise order, but the polygon is not drawn correctly if it is not convex.
+    ///
+    pub fn polygon(
+        &mut self,
+        points: impl IntoIterator<Item = impl Into<PhysicalPoint>>,
+        color: Color,
+    ) {
+        let mut points = clean_points(points);
+        let count = points.len();
+        if count < 3 {
+            return;
+        }
+        let signed_area = (0..count)
+            .map(|i| {
+                let a = points[i];
+                let b = points[(i + 1) % count];
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f32>();
+        if signed_area < 0.0 {
+            points.reverse();
+        }
+
+        let aa = self.anti_aliasing_width;
+        let base = self.begin(None);
+        if aa > 0.0 {
+            // The inner vertices are inset and the outer vertices outset by half the anti-aliasing band, so the band is centered on the edges
+            let transparent = with_alpha(color, 0.0);
+            let normals = (0..count)
+                .map(|i| {
+                    let d = (points[(i + 1) % count] - points[i]).normalize();
+                    vec2(d.y, -d.x)
+                })
+                .collect::<Vec<_>>();
+            for (i, point) in points.iter().enumerate() {
+                let normal = miter(normals[(i + count - 1) % count], normals[i]);
+                self.vertex(*point - normal * 0.5 * aa, color);
+                self.vertex(*point + normal * 0.5 * aa, transparent);
+            }
+            for i in 2..count as u32 {
+                self.triangle(base, base + 2 * (i - 1), base + 2 * i);
+            }
+            for i in 0..count as u32 {
+                let j = (i + 1) % count as u32;
+                self.quad(
+                    base + 2 * i,
+                    base + 2 * j,
+                    base + 2 * j + 1,
+                    base + 2 * i + 1,
+                );
+            }
+        } else {
+            for point in points.iter() {
+                self.vertex(*point, color);
+            }
+            for i in 2..count as u32 {
+                self.triangle(base, base + i - 1, base + i);
+            }
+        }
+    }
+
+    ///
+    /// Draws the given text with the given font, where the given position is the bottom left corner of the first line
+    /// and the given height is the height of a line in pixels. Each line break moves the following text one line down.
+    /// Characters which are not in the font are skipped, but still take up space.
+    ///
+    pub fn text(
+        &mut self,
+        font: &CanvasFont,
+        position: impl Into<PhysicalPoint>,
+        height: f32,
+        text: &str,
+        color: Color,
+    ) {
+        let position = position.into();
+        let glyph_width = font.glyph_width(height);
+        for (line_index, line) in text.lines().enumerate() {
+            let y = position.y - line_index as f32 * height;
+            for (index, c) in line.chars().enumerate() {
+                if let Some((uv_min, uv_max)) = font.glyph_uvs(c) {
+                    let x = position.x + index as f32 * glyph_width;
+                    let base = self.begin(Some(&font.texture));
+                    self.textured_vertex(vec2(x, y), color, uv_min);
+                    self.textured_vertex(vec2(x + glyph_width, y), color, vec2(uv_max.x, uv_min.y));
+                    self.textured_vertex(vec2(x + glyph_width, y + height), color, uv_max);
+                    self.textured_vertex(vec2(x, y + height), color, vec2(uv_min.x, uv_max.y));
+                    self.quad(base, base + 1, base + 2, base + 3);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Renders everything drawn since the last call to [Canvas2D::render] or [Canvas2D::clear] on top of the content of the given render target
+    /// and then clears the canvas.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(&mut self, target: &RenderTarget) {
+        if !self.is_empty() {
+            self.position_buffer.fill(&self.positions);
+            self.color_buffer.fill(&self.colors);
+            self.uv_buffer.fill(&self.uvs);
+            self.element_buffer.fill(&self.indices);
+            let camera = camera2d(target.viewport());
+            target.write(|| {
+                for (texture, range) in self.batches.iter() {
+                    self.draw(&camera, texture.as_deref(), range.clone());
+                }
+            });
+        }
+        self.clear();
+    }
+
+    ///
+    /// Discards everything drawn since the last call to [Canvas2D::render] or [Canvas2D::clear].
+    ///
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.colors.clear();
+        self.uvs.clear();
+        self.indices.clear();
+        self.batches.clear();
+    }
+
+    fn draw(&self, camera: &Camera, texture: Option<&Texture2D>, range: Range<u32>) {
+        let defines = match texture {
+            Some(texture) if texture.is_srgb() => "#define USE_TEXTURE\n#define TEXTURE_SRGB\n",
+            Some(_) => "#define USE_TEXTURE\n",
+            None => "",
+        };
+        self.context
+            .program(
+                format!("{}{}", defines, include_str!("shaders/canvas.vert")),
+                format!(
+                    "{}{}{}",
+                    defines,
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/canvas.frag")
+                ),
+                |program| {
+                    program.use_uniform(
+                        "viewProjection",
+                        camera.render_view_projection(program.context()),
+                    );
+                    program.use_vertex_attribute("position", &self.position_buffer);
+                    program.use_vertex_attribute("color", &self.color_buffer);
+                    if let Some(texture) = texture {
+                        program.use_texture("tex", texture);
+                        program.use_vertex_attribute("uv", &self.uv_buffer);
+                    }
+                    program.draw_subset_of_elements(
+                        RenderStates {
+                            depth_test: DepthTest::Always,
+                            write_mask: WriteMask::COLOR,
+                            blend: Blend::TRANSPARENCY,
+                            cull: Cull::None,
+                            ..Default::default()
+                        },
+                        camera.viewport(),
+                        &self.element_buffer,
+                        range.start,
+                        range.end - range.start,
+                    );
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    // Starts a new shape drawn with the given texture, which is added to the last batch if it uses the same texture, and returns the index of the next vertex
+    fn begin(&mut self, texture: Option<&Arc<Texture2D>>) -> u32 {
+        let index = self.indices.len() as u32;
+        let same_texture = self.batches.last().map(|(t, _)| match (t, texture) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        });
+        if same_texture != Some(true) {
+            self.batches.push((texture.cloned(), index..index));
+        }
+        self.positions.len() as u32
+    }
+
+    fn vertex(&mut self, position: Vec2, color: Color) {
+        self.textured_vertex(position, color, vec2(0.0, 0.0));
+    }
+
+    fn textured_vertex(&mut self, position: Vec2, color: Color, uv: Vec2) {
+        self.positions.push(position);
+        self.colors.push(color);
+        self.uvs.push(uv);
+    }
+
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+        self.batches.last_mut().unwrap().1.end = self.indices.len() as u32;
+    }
+
+    fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+}
+
+fn with_alpha(color: Color, factor: f32) -> Color {
+    Color {
+        a: (color.a as f32 * factor).round() as u8,
+        ..color
+    }
+}
+
+// Returns the normal at a corner between two edges with the given normals, scaled so the offset edges meet
+fn miter(n0: Vec2, n1: Vec2) -> Vec2 {
+    let normal = 0.5 * (n0 + n1);
+    let length2 = normal.magnitude2();
+    // Limits the length of the miter at sharp corners
+    if length2 > 0.000001 {
+        normal / length2.max(0.1)
+    } else {
+        n1
+    }
+}
+
+// Converts the points and removes consecutive duplicates, which do not have a direction
+fn clean_points(points: impl IntoIterator<Item = impl Into<PhysicalPoint>>) -> Vec<Vec2> {
+    let mut result: Vec<Vec2> = Vec::new();
+    for point in points {
+        let point: PhysicalPoint = point.into();
+        let point = vec2(point.x, point.y);
+        if result
+            .last()
+            .map(|p| p.distance2(point) > 0.000001)
+            .unwrap_or(true)
+        {
+            result.push(point);
+        }
+    }
+    if result.len() > 1 && result[0].distance2(result[result.len() - 1]) <= 0.000001 {
+        result.pop();
+    }
+    result
+}
+
+fn rect_points(position: PhysicalPoint, size: Vec2) -> [PhysicalPoint; 4] {
+    [
+        position,
+        PhysicalPoint {
+            x: position.x + size.x,
+            y: position.y,
+        },
+        PhysicalPoint {
+            x: position.x + size.x,
+            y: position.y + size.y,
+        },
+        PhysicalPoint {
+            x: position.x,
+            y: position.y + size.y,
+        },
+    ]
+}
+
+fn circle_points(center: PhysicalPoint, radius: f32) -> Vec<PhysicalPoint> {
+    // Roughly one segment for every three pixels of the circumference
+    let segments = ((std::f32::consts::TAU * radius / 3.0).ceil() as u32).clamp(12, 256);
+    (0..segments)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * i as f32 / segments as f32;
+            PhysicalPoint {
+                x: center.x + radius * angle.cos(),
+                y: center.y + radius * angle.sin(),
+            }
+        })
+        .collect()
+}
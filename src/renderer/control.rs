@@ -22,6 +22,26 @@ mod two_d_control;
 #[doc(inline)]
 pub use two_d_control::*;
 
+mod touch_gestures;
+#[doc(inline)]
+pub use touch_gestures::*;
+
+mod floating_origin;
+#[doc(inline)]
+pub use floating_origin::*;
+
+mod camera_animator;
+#[doc(inline)]
+pub use camera_animator::*;
+
+mod camera_constraints;
+#[doc(inline)]
+pub use camera_constraints::*;
+
+mod input_map;
+#[doc(inline)]
+pub use input_map::*;
+
 pub use three_d_asset::PixelPoint as PhysicalPoint;
 
 ///
@@ -91,6 +111,19 @@ pub enum MouseButton {
     Middle,
 }
 
+/// The phase of a touch, see [Event::Touch].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TouchPhase {
+    /// A finger started touching the screen.
+    Started,
+    /// A finger moved on the screen.
+    Moved,
+    /// A finger stopped touching the screen.
+    Ended,
+    /// The touch was cancelled by the system, for example when the window lost focus.
+    Cancelled,
+}
+
 /// An input event (from mouse, keyboard or similar).
 #[derive(Clone, Debug)]
 pub enum Event {
@@ -140,6 +173,21 @@ pub enum Event {
         /// Whether or not this event already have been handled.
         handled: bool,
     },
+    /// Fired when a finger starts touching, moves on or stops touching the screen.
+    /// Touches are also reported as mouse events, where one finger acts as the left mouse button and the change in distance between two fingers acts as the mouse wheel,
+    /// so use these events, for example with [TouchGestures], when more control over multi-touch gestures is needed.
+    Touch {
+        /// An identifier of the finger which is unique while it touches the screen.
+        id: u64,
+        /// The phase of the touch.
+        phase: TouchPhase,
+        /// The screen position in logical pixels.
+        position: LogicalPoint,
+        /// The state of modifiers.
+        modifiers: Modifiers,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
     /// Fired when the mouse enters the window.
     MouseEnter,
     /// Fired when the mouse leaves the window.
@@ -167,8 +215,34 @@ pub enum Event {
         /// The state of modifiers after the change.
         modifiers: Modifiers,
     },
+    /// Fired when the number of physical pixels for each logical pixel changes,
+    /// for example when the window is moved to a monitor with a different resolution or when the display scaling is changed.
+    DevicePixelRatioChange {
+        /// The number of physical pixels for each logical pixel after the change.
+        device_pixel_ratio: f32,
+    },
     /// Fires when some text has been written.
     Text(String),
+    /// Fired when a file is dragged from outside the application and dropped onto the window.
+    /// One event is fired for each file when several files are dropped at the same time.
+    FileDropped {
+        /// The name of the file including the extension, for example `"model.glb"`.
+        name: String,
+        /// The path to the file. Only available on native, on web the file system cannot be accessed so the contents are available in `bytes` instead.
+        path: Option<std::path::PathBuf>,
+        /// The contents of the file. Only available on web, on native the file can be loaded from the `path`.
+        bytes: Option<Vec<u8>>,
+        /// The position of the pointer when the file was dropped, if known.
+        position: Option<LogicalPoint>,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
+    /// Fired when the copy shortcut is pressed, ie. ⌘ Command+C on Mac and Ctrl+C on other platforms.
+    Copy,
+    /// Fired when the cut shortcut is pressed, ie. ⌘ Command+X on Mac and Ctrl+X on other platforms.
+    Cut,
+    /// Fired when text is pasted from the clipboard, which contains the pasted text.
+    Paste(String),
 }
 
 /// Keyboard key input.
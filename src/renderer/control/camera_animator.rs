@@ -0,0 +1,395 @@
+use crate::renderer::*;
+use three_d_asset::ProjectionType;
+
+///
+/// An easing function which maps the linear progress of an animation in the range `[0..1]` to the eased progress.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slowly and accelerates.
+    EaseIn,
+    /// Starts fast and decelerates.
+    EaseOut,
+    /// Starts slowly, accelerates and decelerates at the end.
+    #[default]
+    EaseInOut,
+    /// A custom easing function which should return 0 at 0 and 1 at 1.
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    ///
+    /// Returns the eased progress for the given linear progress in the range `[0..1]`.
+    ///
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Self::Custom(f) => f(t),
+        }
+    }
+}
+
+///
+/// The projection of a [Viewpoint].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViewpointProjection {
+    /// A perspective projection with the given vertical field of view.
+    Perspective(Radians),
+    /// An orthographic projection with the given height of the view.
+    Orthographic(f32),
+}
+
+///
+/// A saved view of a camera, which can be applied to a camera immediately or by animating the camera using a [CameraAnimator].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewpoint {
+    /// The position of the camera.
+    pub position: Vec3,
+    /// The position the camera is looking at.
+    pub target: Vec3,
+    /// The up direction of the camera.
+    pub up: Vec3,
+    /// The projection of the camera.
+    pub projection: ViewpointProjection,
+}
+
+impl Viewpoint {
+    ///
+    /// Returns the current view of the given camera.
+    ///
+    pub fn from_camera(camera: &Camera) -> Self {
+        let projection = if let ProjectionType::Orthographic { height } = camera.projection_type() {
+            ViewpointProjection::Orthographic(*height)
+        } else if let ProjectionType::Perspective { field_of_view_y } = camera.projection_type() {
+            ViewpointProjection::Perspective(*field_of_view_y)
+        } else {
+            ViewpointProjection::Perspective(degrees(45.0).into())
+        };
+        Self {
+            position: *camera.position(),
+            target: *camera.target(),
+            up: *camera.up(),
+            projection,
+        }
+    }
+
+    ///
+    /// Returns a view from the same direction as the given camera, which fits the given bounding box in the view of the camera.
+    /// Useful for focusing on an object.
+    ///
+    pub fn focus_on(camera: &Camera, aabb: &AxisAlignedBoundingBox) -> Self {
+        let mut viewpoint = Self::from_camera(camera);
+        let center = 0.5 * (aabb.min() + aabb.max());
+        let radius = 0.5 * aabb.size().magnitude().max(0.0001);
+        let direction = camera.view_direction();
+        let distance = match viewpoint.projection {
+            ViewpointProjection::Perspective(field_of_view_y) => {
+                let viewport = camera.viewport();
+                let aspect = viewport.width as f32 / viewport.height.max(1) as f32;
+                let half_fov = 0.5 * field_of_view_y.0;
+                let half_fov = half_fov.min((half_fov.tan() * aspect).atan());
+                radius / half_fov.sin()
+            }
+            ViewpointProjection::Orthographic(_) => {
+                viewpoint.projection = ViewpointProjection::Orthographic(2.0 * radius);
+                2.0 * radius
+            }
+        };
+        viewpoint.target = center;
+        viewpoint.position = center - direction * distance;
+        viewpoint
+    }
+
+    ///
+    /// Sets the view and projection of the given camera to this viewpoint. The near and far planes of the camera are kept.
+    ///
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.set_view(self.position, self.target, self.up);
+        let (z_near, z_far) = (camera.z_near(), camera.z_far());
+        match self.projection {
+            ViewpointProjection::Perspective(field_of_view_y) => {
+                camera.set_perspective_projection(field_of_view_y, z_near, z_far)
+            }
+            ViewpointProjection::Orthographic(height) => {
+                camera.set_orthographic_projection(height, z_near, z_far)
+            }
+        }
+    }
+
+    ///
+    /// Interpolates between this viewpoint and the other viewpoint, where a factor of 0 returns this viewpoint and a factor of 1 returns the other viewpoint.
+    /// If the projections are of different types, the projection switches half way.
+    ///
+    pub fn interpolate(&self, other: &Self, factor: f32) -> Self {
+        let lerp = |a: Vec3, b: Vec3| a + (b - a) * factor;
+        let up = lerp(self.up, other.up);
+        Self {
+            position: lerp(self.position, other.position),
+            target: lerp(self.target, other.target),
+            up: if up.magnitude2() > 0.0001 {
+                up.normalize()
+            } else {
+                other.up
+            },
+            projection: interpolate_projection(self.projection, other.projection, factor),
+        }
+    }
+}
+
+fn interpolate_projection(
+    a: ViewpointProjection,
+    b: ViewpointProjection,
+    factor: f32,
+) -> ViewpointProjection {
+    match (a, b) {
+        (ViewpointProjection::Perspective(a), ViewpointProjection::Perspective(b)) => {
+            ViewpointProjection::Perspective(radians(a.0 + (b.0 - a.0) * factor))
+        }
+        (ViewpointProjection::Orthographic(a), ViewpointProjection::Orthographic(b)) => {
+            ViewpointProjection::Orthographic(a + (b - a) * factor)
+        }
+        _ => {
+            if factor < 0.5 {
+                a
+            } else {
+                b
+            }
+        }
+    }
+}
+
+///
+/// A key frame in a [CameraPath].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraKeyframe {
+    /// The time of the key frame in seconds.
+    pub time: f32,
+    /// The view of the camera at the time of the key frame.
+    pub viewpoint: Viewpoint,
+}
+
+///
+/// A fly-through path of the camera defined by key frames.
+/// The camera position and target follow smooth Catmull-Rom splines through the key frames, so the camera passes through all of the key frames without stopping.
+///
+#[derive(Clone, Debug, Default)]
+pub struct CameraPath {
+    keyframes: Vec<CameraKeyframe>,
+    /// Whether or not the path starts over when the end is reached.
+    pub looping: bool,
+}
+
+impl CameraPath {
+    ///
+    /// Creates a new path from the given key frames. The key frames are sorted by time.
+    ///
+    pub fn new(mut keyframes: Vec<CameraKeyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            keyframes,
+            looping: false,
+        }
+    }
+
+    ///
+    /// Adds a key frame to the path.
+    ///
+    pub fn add_keyframe(&mut self, keyframe: CameraKeyframe) {
+        let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    ///
+    /// Returns the key frames of the path sorted by time.
+    ///
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    ///
+    /// Returns the duration of the path in seconds, ie. the time of the last key frame.
+    ///
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|k| k.time).unwrap_or(0.0)
+    }
+
+    ///
+    /// Returns the viewpoint at the given time in seconds or `None` if the path has no key frames.
+    ///
+    pub fn viewpoint(&self, time: f32) -> Option<Viewpoint> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if time <= first.time {
+            return Some(first.viewpoint);
+        }
+        if time >= last.time {
+            return Some(last.viewpoint);
+        }
+        let i = self.keyframes.partition_point(|k| k.time <= time) - 1;
+        let k1 = &self.keyframes[i];
+        let k2 = &self.keyframes[i + 1];
+        let k0 = &self.keyframes[i.saturating_sub(1)];
+        let k3 = &self.keyframes[(i + 2).min(self.keyframes.len() - 1)];
+        let t = (time - k1.time) / (k2.time - k1.time).max(f32::EPSILON);
+        let mut viewpoint = k1.viewpoint.interpolate(&k2.viewpoint, t);
+        viewpoint.position = catmull_rom(
+            k0.viewpoint.position,
+            k1.viewpoint.position,
+            k2.viewpoint.position,
+            k3.viewpoint.position,
+            t,
+        );
+        viewpoint.target = catmull_rom(
+            k0.viewpoint.target,
+            k1.viewpoint.target,
+            k2.viewpoint.target,
+            k3.viewpoint.target,
+            t,
+        );
+        Some(viewpoint)
+    }
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+enum CameraAnimation {
+    Transition {
+        from: Viewpoint,
+        to: Viewpoint,
+        duration: f32,
+    },
+    Path(CameraPath),
+}
+
+///
+/// Animates a camera, either as a smooth transition to a [Viewpoint], for example to focus on an object, or along a [CameraPath] for a fly-through.
+/// Call [CameraAnimator::update] each frame with the elapsed time to move the camera.
+///
+pub struct CameraAnimator {
+    /// The easing used for transitions between viewpoints.
+    pub easing: Easing,
+    animation: Option<CameraAnimation>,
+    time: f32,
+}
+
+impl Default for CameraAnimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraAnimator {
+    ///
+    /// Creates a new camera animator which is not animating.
+    ///
+    pub fn new() -> Self {
+        Self {
+            easing: Easing::default(),
+            animation: None,
+            time: 0.0,
+        }
+    }
+
+    ///
+    /// Starts a transition from the current view of the camera to the given viewpoint over the given duration in seconds.
+    ///
+    pub fn transition_to(&mut self, camera: &Camera, viewpoint: Viewpoint, duration: f32) {
+        self.animation = Some(CameraAnimation::Transition {
+            from: Viewpoint::from_camera(camera),
+            to: viewpoint,
+            duration,
+        });
+        self.time = 0.0;
+    }
+
+    ///
+    /// Starts a transition to a view of the given bounding box from the current view direction of the camera, see [Viewpoint::focus_on].
+    ///
+    pub fn focus_on(&mut self, camera: &Camera, aabb: &AxisAlignedBoundingBox, duration: f32) {
+        self.transition_to(camera, Viewpoint::focus_on(camera, aabb), duration);
+    }
+
+    ///
+    /// Starts moving the camera along the given path from the beginning.
+    ///
+    pub fn play_path(&mut self, path: CameraPath) {
+        self.animation = Some(CameraAnimation::Path(path));
+        self.time = 0.0;
+    }
+
+    ///
+    /// Stops the current animation, leaving the camera where it is.
+    ///
+    pub fn stop(&mut self) {
+        self.animation = None;
+    }
+
+    ///
+    /// Returns whether or not the camera is currently being animated.
+    ///
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    ///
+    /// Returns the time in seconds since the current animation started.
+    ///
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    ///
+    /// Advances the current animation by the given elapsed time in seconds and updates the camera.
+    /// Returns true if the camera was changed.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, elapsed_time: f32) -> bool {
+        let Some(animation) = &self.animation else {
+            return false;
+        };
+        self.time += elapsed_time;
+        let (viewpoint, finished) = match animation {
+            CameraAnimation::Transition { from, to, duration } => {
+                let t = if *duration > 0.0 {
+                    self.time / duration
+                } else {
+                    1.0
+                };
+                (from.interpolate(to, self.easing.apply(t)), t >= 1.0)
+            }
+            CameraAnimation::Path(path) => {
+                let duration = path.duration();
+                if path.looping && duration > 0.0 {
+                    self.time %= duration;
+                }
+                match path.viewpoint(self.time) {
+                    Some(viewpoint) => (viewpoint, !path.looping && self.time >= duration),
+                    None => {
+                        self.animation = None;
+                        return false;
+                    }
+                }
+            }
+        };
+        viewpoint.apply(camera);
+        if finished {
+            self.animation = None;
+        }
+        true
+    }
+}
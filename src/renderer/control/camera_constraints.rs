@@ -0,0 +1,228 @@
+use crate::renderer::*;
+
+///
+/// Constraints on the camera position relative to the camera target, which are applied after a control has moved the camera,
+/// so they can be combined with any of the controls, for example [OrbitControl] or [FlyControl].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CameraConstraints {
+    /// The minimum distance between the camera position and target.
+    pub min_distance: Option<f32>,
+    /// The maximum distance between the camera position and target.
+    pub max_distance: Option<f32>,
+    /// The minimum pitch, ie. the angle between the view direction and the plane orthogonal to the camera up direction, which is negative when looking down.
+    pub min_pitch: Option<Radians>,
+    /// The maximum pitch, ie. the angle between the view direction and the plane orthogonal to the camera up direction, which is positive when looking up.
+    pub max_pitch: Option<Radians>,
+}
+
+impl CameraConstraints {
+    ///
+    /// Moves the camera position, while keeping the target, so that the camera satisfies the constraints.
+    /// Returns true if the camera was changed.
+    ///
+    pub fn apply(&self, camera: &mut Camera) -> bool {
+        let position = *camera.position();
+        let target = *camera.target();
+        let up = camera.up().normalize();
+        let offset = position - target;
+        let mut distance = offset.magnitude();
+        if distance < f32::EPSILON {
+            return false;
+        }
+        let direction = -offset / distance;
+        if let Some(min) = self.min_distance {
+            distance = distance.max(min);
+        }
+        if let Some(max) = self.max_distance {
+            distance = distance.min(max);
+        }
+
+        let vertical = direction.dot(up).clamp(-1.0, 1.0);
+        let pitch = vertical.asin();
+        let mut clamped_pitch = pitch;
+        if let Some(min) = self.min_pitch {
+            clamped_pitch = clamped_pitch.max(min.0);
+        }
+        if let Some(max) = self.max_pitch {
+            clamped_pitch = clamped_pitch.min(max.0);
+        }
+        let direction = if clamped_pitch != pitch {
+            let horizontal = direction - up * vertical;
+            let horizontal = if horizontal.magnitude2() > 1e-8 {
+                horizontal.normalize()
+            } else {
+                up.cross(camera.right_direction()).normalize()
+            };
+            horizontal * clamped_pitch.cos() + up * clamped_pitch.sin()
+        } else {
+            direction
+        };
+
+        let new_position = target - direction * distance;
+        if new_position == position {
+            return false;
+        }
+        camera.set_view(new_position, target, *camera.up());
+        true
+    }
+}
+
+///
+/// Makes the camera follow a moving target, for example an object controlled by the player, by moving the camera position and target by the same amount as the followed target.
+/// When combined with an [OrbitControl], also update the target of the control using [OrbitControl::set_target].
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TargetLock {
+    previous_target: Option<Vec3>,
+}
+
+impl TargetLock {
+    ///
+    /// Creates a new target lock. The first call to [TargetLock::update] moves the camera target to the followed target.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Moves the camera by the movement of the followed target since the last call and returns true if the camera was changed.
+    /// In the first call, the camera target is set to the followed target while the camera position is kept.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, target: Vec3) -> bool {
+        let change = if let Some(previous_target) = self.previous_target {
+            if previous_target == target {
+                false
+            } else {
+                camera.translate(&(target - previous_target));
+                true
+            }
+        } else {
+            let position = *camera.position();
+            let up = *camera.up();
+            camera.set_view(position, target, up);
+            true
+        };
+        self.previous_target = Some(target);
+        change
+    }
+
+    ///
+    /// Releases the lock, so the next call to [TargetLock::update] sets the camera target again.
+    ///
+    pub fn release(&mut self) {
+        self.previous_target = None;
+    }
+}
+
+///
+/// A camera shake effect, for example as feedback for an explosion or impact in a game.
+/// The shake is started with [CameraShake::start], advanced each frame with [CameraShake::update] and applied to a copy of the camera with [CameraShake::apply],
+/// so the shake does not interfere with the controls which move the camera.
+///
+#[derive(Clone, Debug)]
+pub struct CameraShake {
+    /// The maximum offset of the camera in world units at full intensity.
+    pub amplitude: f32,
+    /// The maximum rotation of the camera around the view direction at full intensity.
+    pub rotation: Radians,
+    /// The number of shakes per second.
+    pub frequency: f32,
+    /// How fast the intensity decays, measured per second.
+    pub decay: f32,
+    intensity: f32,
+    time: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            amplitude: 0.1,
+            rotation: degrees(2.0).into(),
+            frequency: 15.0,
+            decay: 2.0,
+            intensity: 0.0,
+            time: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    ///
+    /// Creates a new camera shake with the given amplitude, frequency and decay, see [CameraShake::amplitude], [CameraShake::frequency] and [CameraShake::decay].
+    ///
+    pub fn new(amplitude: f32, frequency: f32, decay: f32) -> Self {
+        Self {
+            amplitude,
+            frequency,
+            decay,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Starts shaking with the given intensity in the range `[0..1]`, which is added to the intensity of an ongoing shake.
+    ///
+    pub fn start(&mut self, intensity: f32) {
+        self.intensity = (self.intensity + intensity).clamp(0.0, 1.0);
+    }
+
+    ///
+    /// Stops the shake immediately.
+    ///
+    pub fn stop(&mut self) {
+        self.intensity = 0.0;
+    }
+
+    ///
+    /// Returns whether or not the camera is shaking.
+    ///
+    pub fn is_shaking(&self) -> bool {
+        self.intensity > 0.0
+    }
+
+    ///
+    /// Advances the shake by the given time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time).
+    ///
+    pub fn update(&mut self, elapsed_time: f64) {
+        let dt = (elapsed_time * 0.001) as f32;
+        self.time += dt;
+        self.intensity *= (-self.decay * dt).exp();
+        if self.intensity < 0.001 {
+            self.intensity = 0.0;
+        }
+    }
+
+    ///
+    /// Returns a copy of the given camera which is offset and rotated by the shake. Render with the returned camera.
+    ///
+    pub fn apply(&self, camera: &Camera) -> Camera {
+        let mut camera = camera.clone();
+        if !self.is_shaking() {
+            return camera;
+        }
+        // The square of the intensity gives a more natural falloff
+        let strength = self.intensity * self.intensity;
+        let t = self.time * self.frequency;
+        let right = camera.right_direction();
+        let up = right.cross(camera.view_direction()).normalize();
+        let offset =
+            (right * smooth_noise(t, 0.0) + up * smooth_noise(t, 17.0)) * self.amplitude * strength;
+        let roll = self.rotation.0 * strength * smooth_noise(t, 43.0);
+        let position = *camera.position() + offset;
+        let target = *camera.target() + offset;
+        let rotated_up =
+            Mat3::from_axis_angle(camera.view_direction(), radians(roll)) * *camera.up();
+        camera.set_view(position, target, rotated_up);
+        camera
+    }
+}
+
+// Smooth value noise in the range [-1..1]
+fn smooth_noise(t: f32, seed: f32) -> f32 {
+    let hash = |i: f32| ((i + seed).sin() * 43758.547).fract() * 2.0 - 1.0;
+    let i = t.floor();
+    let f = t - i;
+    let f = f * f * (3.0 - 2.0 * f);
+    hash(i) * (1.0 - f) + hash(i + 1.0) * f
+}
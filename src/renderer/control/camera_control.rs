@@ -149,7 +149,12 @@ impl CameraControl {
         change
     }
 
-    fn handle_action(&mut self, camera: &mut Camera, control_type: CameraAction, x: f32) -> bool {
+    pub(super) fn handle_action(
+        &mut self,
+        camera: &mut Camera,
+        control_type: CameraAction,
+        x: f32,
+    ) -> bool {
         match control_type {
             CameraAction::Pitch { speed } => {
                 camera.pitch(radians(speed * x));
@@ -0,0 +1,163 @@
+use crate::renderer::*;
+
+///
+/// Implemented by everything that can be moved when the origin is rebased by a [FloatingOrigin].
+///
+pub trait Rebase {
+    ///
+    /// Moves this by the negated offset, ie. the point at `offset` is moved to the origin.
+    ///
+    fn rebase(&mut self, offset: Vec3);
+}
+
+impl<T: Rebase + ?Sized> Rebase for &mut T {
+    fn rebase(&mut self, offset: Vec3) {
+        (**self).rebase(offset)
+    }
+}
+
+impl<T: Rebase + ?Sized> Rebase for Box<T> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.as_mut().rebase(offset)
+    }
+}
+
+impl<T: Rebase> Rebase for [T] {
+    fn rebase(&mut self, offset: Vec3) {
+        self.iter_mut().for_each(|t| t.rebase(offset))
+    }
+}
+
+impl<T: Rebase> Rebase for Vec<T> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.iter_mut().for_each(|t| t.rebase(offset))
+    }
+}
+
+impl Rebase for Camera {
+    fn rebase(&mut self, offset: Vec3) {
+        self.translate(&-offset);
+    }
+}
+
+impl Rebase for Mesh {
+    fn rebase(&mut self, offset: Vec3) {
+        self.set_transformation(Mat4::from_translation(-offset) * self.transformation());
+    }
+}
+
+impl Rebase for InstancedMesh {
+    fn rebase(&mut self, offset: Vec3) {
+        self.set_transformation(Mat4::from_translation(-offset) * self.transformation());
+    }
+}
+
+impl Rebase for Axes {
+    fn rebase(&mut self, offset: Vec3) {
+        self.set_transformation(Mat4::from_translation(-offset) * self.transformation());
+    }
+}
+
+impl<G: Geometry + Rebase, M: Material> Rebase for Gm<G, M> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.geometry.rebase(offset)
+    }
+}
+
+impl<M: Material> Rebase for ModelPart<M> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.geometry.rebase(offset)
+    }
+}
+
+impl<M: Material> Rebase for Model<M> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.iter_mut().for_each(|part| part.rebase(offset))
+    }
+}
+
+impl<M: Material> Rebase for InstancedModelPart<M> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.geometry.rebase(offset)
+    }
+}
+
+impl<M: Material> Rebase for InstancedModel<M> {
+    fn rebase(&mut self, offset: Vec3) {
+        self.iter_mut().for_each(|part| part.rebase(offset))
+    }
+}
+
+impl Rebase for PointLight {
+    fn rebase(&mut self, offset: Vec3) {
+        self.position -= offset;
+    }
+}
+
+impl Rebase for SpotLight {
+    fn rebase(&mut self, offset: Vec3) {
+        self.position -= offset;
+    }
+}
+
+///
+/// Keeps the camera close to the origin to avoid the loss of floating point precision in large scenes.
+/// When the camera moves further away from the origin than the threshold distance, the camera and all given objects are moved
+/// such that the camera is at the origin again. The accumulated offset is kept so it is still possible to convert between
+/// the rendered (local) positions and the actual world positions.
+///
+pub struct FloatingOrigin {
+    /// The distance from the origin the camera is allowed to move before everything is rebased.
+    pub threshold: f32,
+    offset: Vec3,
+}
+
+impl FloatingOrigin {
+    ///
+    /// Creates a new floating origin which rebases when the camera is further than `threshold` away from the origin.
+    ///
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            offset: Vec3::zero(),
+        }
+    }
+
+    ///
+    /// Checks the distance from the camera to the origin and, if it is above the threshold, moves the camera and the given objects
+    /// such that the camera is at the origin.
+    /// Should be called once every frame, before rendering.
+    /// Returns the offset the camera and objects were moved by, if they were moved.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, objects: &mut [&mut dyn Rebase]) -> Option<Vec3> {
+        let position = *camera.position();
+        if position.magnitude() <= self.threshold {
+            return None;
+        }
+        camera.rebase(position);
+        objects.rebase(position);
+        self.offset += position;
+        Some(position)
+    }
+
+    ///
+    /// Returns the total offset from the world origin to the current (local) origin.
+    ///
+    pub fn offset(&self) -> Vec3 {
+        self.offset
+    }
+
+    ///
+    /// Converts a position in the rebased (local) coordinate system, for example a picked position, into a world position.
+    ///
+    pub fn to_world(&self, local_position: Vec3) -> Vec3 {
+        local_position + self.offset
+    }
+
+    ///
+    /// Converts a world position into the rebased (local) coordinate system, for example before placing a new object.
+    ///
+    pub fn to_local(&self, world_position: Vec3) -> Vec3 {
+        world_position - self.offset
+    }
+}
@@ -1,11 +1,32 @@
 use super::*;
 use crate::core::*;
+use std::collections::HashSet;
 
 ///
 /// A control that makes the camera fly through the 3D scene.
 ///
+/// The camera moves forward, left, backward and right with the W, A, S and D keys or the arrow keys and straight up and down with the E and Q keys,
+/// faster when shift is held down, see [FlyControl::sprint_multiplier]. The movement is applied in [FlyControl::update].
+/// Looking around is done by dragging with the left mouse button or, if [FlyControl::look_button] is `None`, by moving the mouse,
+/// which is best combined with locking the pointer, on web for example by calling `request_pointer_lock` on the canvas when it is clicked.
+/// Scrolling moves the camera forward and backward and dragging with the right mouse button moves it sideways and up and down.
+/// On touch screens, dragging with one finger looks around, pinching moves the camera forward and backward, dragging with two fingers moves it sideways and up and down
+/// and rotating two fingers turns the camera.
+///
 pub struct FlyControl {
     control: CameraControl,
+    /// The speed of the movement with the keyboard in world units per second.
+    /// The default is 30 times the speed given in [FlyControl::new].
+    pub movement_speed: f32,
+    /// The factor which the movement speed is multiplied with while shift is held down.
+    pub sprint_multiplier: f32,
+    /// The rotation in radians per logical pixel that the mouse moves when looking around.
+    pub look_speed: f32,
+    /// The mouse button which must be held down to look around or `None` if the camera should look around whenever the mouse moves.
+    pub look_button: Option<MouseButton>,
+    pressed_keys: HashSet<Key>,
+    sprint: bool,
+    touch_gestures: TouchGestures,
 }
 
 impl FlyControl {
@@ -13,22 +34,152 @@ impl FlyControl {
     pub fn new(speed: f32) -> Self {
         Self {
             control: CameraControl {
-                left_drag_horizontal: CameraAction::Yaw {
-                    speed: std::f32::consts::PI / 1800.0,
-                },
-                left_drag_vertical: CameraAction::Pitch {
-                    speed: std::f32::consts::PI / 1800.0,
-                },
                 scroll_vertical: CameraAction::Forward { speed },
                 right_drag_horizontal: CameraAction::Left { speed },
                 right_drag_vertical: CameraAction::Up { speed },
                 ..Default::default()
             },
+            movement_speed: 30.0 * speed,
+            sprint_multiplier: 4.0,
+            look_speed: std::f32::consts::PI / 1800.0,
+            look_button: Some(MouseButton::Left),
+            pressed_keys: HashSet::new(),
+            sprint: false,
+            touch_gestures: TouchGestures::new(),
         }
     }
 
     /// Handles the events. Must be called each frame.
     pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event]) -> bool {
-        self.control.handle_events(camera, events)
+        let mut change = false;
+        for event in events.iter_mut() {
+            let gesture = self.touch_gestures.handle_event(event);
+            match event {
+                Event::Touch { handled, .. } => {
+                    if let Some(gesture) = gesture.filter(|_| !*handled) {
+                        self.handle_touch_gesture(camera, gesture);
+                        *handled = true;
+                        change = true;
+                    }
+                }
+                // Mouse events are emulated from the touches, which are handled above
+                Event::MousePress { handled, .. }
+                | Event::MouseRelease { handled, .. }
+                | Event::MouseMotion { handled, .. }
+                | Event::MouseWheel { handled, .. }
+                    if self.touch_gestures.is_active() =>
+                {
+                    *handled = true;
+                }
+                Event::MouseMotion {
+                    delta,
+                    button,
+                    handled,
+                    ..
+                } if !*handled && *button == self.look_button => {
+                    let yaw = CameraAction::Yaw {
+                        speed: self.look_speed,
+                    };
+                    let pitch = CameraAction::Pitch {
+                        speed: self.look_speed,
+                    };
+                    *handled = self.control.handle_action(camera, yaw, delta.0);
+                    *handled |= self.control.handle_action(camera, pitch, delta.1);
+                    change |= *handled;
+                }
+                Event::KeyPress {
+                    kind,
+                    modifiers,
+                    handled,
+                } => {
+                    self.sprint = modifiers.shift;
+                    if !*handled && Self::movement_direction(*kind).is_some() {
+                        self.pressed_keys.insert(*kind);
+                        *handled = true;
+                    }
+                }
+                Event::KeyRelease {
+                    kind,
+                    modifiers,
+                    handled,
+                } => {
+                    self.sprint = modifiers.shift;
+                    if self.pressed_keys.remove(kind) {
+                        *handled = true;
+                    }
+                }
+                Event::ModifiersChange { modifiers } => {
+                    self.sprint = modifiers.shift;
+                }
+                Event::MouseLeave => {
+                    self.pressed_keys.clear();
+                }
+                _ => {}
+            }
+        }
+        change |= self.control.handle_events(camera, events);
+        change
+    }
+
+    ///
+    /// Moves the camera according to the keys which are held down.
+    /// Must be called each frame after [FlyControl::handle_events] with the time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time).
+    /// Returns true if the camera was changed.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, elapsed_time: f64) -> bool {
+        let mut direction = vec3(0.0, 0.0, 0.0);
+        for key in self.pressed_keys.iter() {
+            if let Some(d) = Self::movement_direction(*key) {
+                direction += d;
+            }
+        }
+        if direction == vec3(0.0, 0.0, 0.0) {
+            return false;
+        }
+        let mut distance = self.movement_speed * (elapsed_time * 0.001) as f32;
+        if self.sprint {
+            distance *= self.sprint_multiplier;
+        }
+        let forward = camera.view_direction();
+        let right = camera.right_direction();
+        let up = camera.up().normalize();
+        let change =
+            (right * direction.x + up * direction.y + forward * direction.z).normalize() * distance;
+        camera.translate(&change);
+        true
+    }
+
+    fn handle_touch_gesture(&mut self, camera: &mut Camera, gesture: TouchGesture) {
+        let yaw = CameraAction::Yaw {
+            speed: self.look_speed,
+        };
+        if gesture.fingers == 1 {
+            let pitch = CameraAction::Pitch {
+                speed: self.look_speed,
+            };
+            self.control.handle_action(camera, yaw, gesture.pan.x);
+            self.control.handle_action(camera, pitch, gesture.pan.y);
+        } else {
+            self.control
+                .handle_action(camera, self.control.scroll_vertical, gesture.pinch);
+            self.control
+                .handle_action(camera, self.control.right_drag_horizontal, gesture.pan.x);
+            self.control
+                .handle_action(camera, self.control.right_drag_vertical, gesture.pan.y);
+            self.control
+                .handle_action(camera, yaw, gesture.rotation / self.look_speed.max(1e-6));
+        }
+    }
+
+    fn movement_direction(key: Key) -> Option<Vec3> {
+        match key {
+            Key::W | Key::ArrowUp => Some(vec3(0.0, 0.0, 1.0)),
+            Key::S | Key::ArrowDown => Some(vec3(0.0, 0.0, -1.0)),
+            Key::D | Key::ArrowRight => Some(vec3(1.0, 0.0, 0.0)),
+            Key::A | Key::ArrowLeft => Some(vec3(-1.0, 0.0, 0.0)),
+            Key::E => Some(vec3(0.0, 1.0, 0.0)),
+            Key::Q => Some(vec3(0.0, -1.0, 0.0)),
+            _ => None,
+        }
     }
 }
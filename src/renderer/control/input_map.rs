@@ -0,0 +1,388 @@
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+///
+/// A key or mouse button which can be bound to an action in an [InputMap].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Input {
+    /// A key on the keyboard.
+    Key(Key),
+    /// A mouse button.
+    Mouse(MouseButton),
+}
+
+///
+/// A key or mouse button combined with the modifiers which must be held at the same time, for example Ctrl+Z, see [InputMap::bind].
+///
+/// The [Modifiers::command] modifier is the platform dependent modifier used for shortcuts, ie. the ⌘ Command key on Mac and the Control key on other platforms,
+/// so a chord with the command modifier, for example parsed from `"Cmd+Z"`, is the usual undo shortcut on all platforms.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    /// The key or mouse button which triggers the chord.
+    pub input: Input,
+    /// The modifiers which must be held when the key or mouse button is pressed. Other modifiers must not be held.
+    pub modifiers: Modifiers,
+}
+
+impl Chord {
+    ///
+    /// Creates a chord of the given key without any modifiers.
+    ///
+    pub fn key(key: Key) -> Self {
+        Self {
+            input: Input::Key(key),
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    ///
+    /// Creates a chord of the given mouse button without any modifiers.
+    ///
+    pub fn mouse(button: MouseButton) -> Self {
+        Self {
+            input: Input::Mouse(button),
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    ///
+    /// Returns this chord with the platform dependent command modifier added, ie. ⌘ Command on Mac and Control on other platforms.
+    ///
+    pub fn command(mut self) -> Self {
+        self.modifiers.command = true;
+        self
+    }
+
+    ///
+    /// Returns this chord with the control modifier added.
+    ///
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    ///
+    /// Returns this chord with the shift modifier added.
+    ///
+    pub fn shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+
+    ///
+    /// Returns this chord with the alt modifier added.
+    ///
+    pub fn alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    ///
+    /// Parses a chord from modifiers and a key or mouse button separated by `+`, for example `"Ctrl+Z"`, `"Cmd+Shift+Z"` or `"Alt+MouseLeft"`.
+    /// The modifiers are `Ctrl`, `Shift`, `Alt` and `Cmd`, where `Cmd` (or `Command`) is the platform dependent command modifier, see [Chord].
+    /// The keys are named as in [Key], where the digits and arrow keys can also be written as for example `1` and `Up`,
+    /// and the mouse buttons are `MouseLeft`, `MouseRight` and `MouseMiddle`. The names are case insensitive.
+    /// Returns `None` if the text is not a valid chord.
+    ///
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = Modifiers::default();
+        let mut input = None;
+        for part in text.split('+').map(|part| part.trim().to_lowercase()) {
+            if input.is_some() {
+                return None;
+            }
+            match part.as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "shift" => modifiers.shift = true,
+                "alt" | "option" => modifiers.alt = true,
+                "cmd" | "command" => modifiers.command = true,
+                name => input = Some(parse_input(name)?),
+            }
+        }
+        Some(Self {
+            input: input?,
+            modifiers,
+        })
+    }
+
+    ///
+    /// Returns whether or not this chord is triggered by the given input when the given modifiers are held.
+    ///
+    pub fn matches(&self, input: Input, modifiers: Modifiers) -> bool {
+        if self.input != input
+            || self.modifiers.alt != modifiers.alt
+            || self.modifiers.shift != modifiers.shift
+        {
+            return false;
+        }
+        if cfg!(target_os = "macos") {
+            self.modifiers.ctrl == modifiers.ctrl && self.modifiers.command == modifiers.command
+        } else {
+            // The command modifier is set together with the control modifier, so they cannot be distinguished
+            (self.modifiers.ctrl || self.modifiers.command) == (modifiers.ctrl || modifiers.command)
+        }
+    }
+}
+
+fn parse_input(name: &str) -> Option<Input> {
+    let key = match name {
+        "mouseleft" => return Some(Input::Mouse(MouseButton::Left)),
+        "mouseright" => return Some(Input::Mouse(MouseButton::Right)),
+        "mousemiddle" => return Some(Input::Mouse(MouseButton::Middle)),
+        "arrowdown" | "down" => Key::ArrowDown,
+        "arrowleft" | "left" => Key::ArrowLeft,
+        "arrowright" | "right" => Key::ArrowRight,
+        "arrowup" | "up" => Key::ArrowUp,
+        "escape" | "esc" => Key::Escape,
+        "tab" => Key::Tab,
+        "backspace" => Key::Backspace,
+        "enter" | "return" => Key::Enter,
+        "space" => Key::Space,
+        "insert" => Key::Insert,
+        "delete" | "del" => Key::Delete,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        _ => {
+            let name = name.strip_prefix("num").unwrap_or(name);
+            let mut chars = name.chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            const DIGITS: [Key; 10] = [
+                Key::Num0,
+                Key::Num1,
+                Key::Num2,
+                Key::Num3,
+                Key::Num4,
+                Key::Num5,
+                Key::Num6,
+                Key::Num7,
+                Key::Num8,
+                Key::Num9,
+            ];
+            const LETTERS: [Key; 26] = [
+                Key::A,
+                Key::B,
+                Key::C,
+                Key::D,
+                Key::E,
+                Key::F,
+                Key::G,
+                Key::H,
+                Key::I,
+                Key::J,
+                Key::K,
+                Key::L,
+                Key::M,
+                Key::N,
+                Key::O,
+                Key::P,
+                Key::Q,
+                Key::R,
+                Key::S,
+                Key::T,
+                Key::U,
+                Key::V,
+                Key::W,
+                Key::X,
+                Key::Y,
+                Key::Z,
+            ];
+            if let Some(digit) = c.to_digit(10) {
+                DIGITS[digit as usize]
+            } else if c.is_ascii_lowercase() {
+                LETTERS[(c as u8 - b'a') as usize]
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(Input::Key(key))
+}
+
+///
+/// Maps keys and mouse buttons, optionally combined with modifiers, to named actions, so the application can check for example whether the "undo" action was triggered
+/// instead of checking for the specific keys, which also makes it possible to let the user change the bindings.
+/// Call [InputMap::handle_events] once each frame with the events of the frame and then query the state of the actions, for example with [InputMap::action_pressed].
+///
+/// Holding a key down usually makes the operating system repeat the key press, which is reported by [InputMap::action_repeated] but not by [InputMap::action_pressed].
+///
+pub struct InputMap {
+    bindings: HashMap<String, Vec<Chord>>,
+    held_inputs: HashSet<Input>,
+    held_actions: HashMap<String, Input>,
+    pressed: HashSet<String>,
+    repeated: HashSet<String>,
+    released: HashSet<String>,
+}
+
+impl InputMap {
+    ///
+    /// Creates a new input map without any bindings.
+    ///
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            held_inputs: HashSet::new(),
+            held_actions: HashMap::new(),
+            pressed: HashSet::new(),
+            repeated: HashSet::new(),
+            released: HashSet::new(),
+        }
+    }
+
+    ///
+    /// Binds the given chord to the given action, in addition to the chords already bound to the action.
+    ///
+    pub fn bind(&mut self, action: &str, chord: Chord) -> &mut Self {
+        let chords = self.bindings.entry(action.to_owned()).or_default();
+        if !chords.contains(&chord) {
+            chords.push(chord);
+        }
+        self
+    }
+
+    ///
+    /// Removes all chords bound to the given action.
+    ///
+    pub fn unbind(&mut self, action: &str) {
+        self.bindings.remove(action);
+        self.held_actions.remove(action);
+    }
+
+    ///
+    /// Returns the chords bound to the given action.
+    ///
+    pub fn bindings(&self, action: &str) -> &[Chord] {
+        self.bindings
+            .get(action)
+            .map(|chords| chords.as_slice())
+            .unwrap_or(&[])
+    }
+
+    ///
+    /// Updates the state of the actions from the given events of a frame.
+    /// Events which trigger an action are marked as handled and events which are already handled are ignored, except that releasing a key or mouse button always releases it.
+    ///
+    pub fn handle_events(&mut self, events: &mut [Event]) {
+        self.pressed.clear();
+        self.repeated.clear();
+        self.released.clear();
+        for event in events.iter_mut() {
+            match event {
+                Event::KeyPress {
+                    kind,
+                    modifiers,
+                    handled,
+                } => {
+                    let input = Input::Key(*kind);
+                    let repeat = !self.held_inputs.insert(input);
+                    if !*handled {
+                        *handled = self.press(input, *modifiers, repeat);
+                    }
+                }
+                Event::MousePress {
+                    button,
+                    modifiers,
+                    handled,
+                    ..
+                } => {
+                    let input = Input::Mouse(*button);
+                    self.held_inputs.insert(input);
+                    if !*handled {
+                        *handled = self.press(input, *modifiers, false);
+                    }
+                }
+                Event::KeyRelease { kind, handled, .. } => {
+                    *handled |= self.release(Input::Key(*kind));
+                }
+                Event::MouseRelease {
+                    button, handled, ..
+                } => {
+                    *handled |= self.release(Input::Mouse(*button));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ///
+    /// Returns whether or not the given action was triggered in the last frame, not counting repeated key presses.
+    ///
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.pressed.contains(action)
+    }
+
+    ///
+    /// Returns whether or not the given action was triggered in the last frame, including key presses repeated by the operating system while the key is held down,
+    /// which is useful for example for undo or for moving a selection with the arrow keys.
+    ///
+    pub fn action_repeated(&self, action: &str) -> bool {
+        self.pressed.contains(action) || self.repeated.contains(action)
+    }
+
+    ///
+    /// Returns whether or not the key or mouse button which triggered the given action is held down.
+    ///
+    pub fn action_down(&self, action: &str) -> bool {
+        self.held_actions.contains_key(action)
+    }
+
+    ///
+    /// Returns whether or not the key or mouse button which triggered the given action was released in the last frame.
+    ///
+    pub fn action_released(&self, action: &str) -> bool {
+        self.released.contains(action)
+    }
+
+    ///
+    /// Returns whether or not the given key or mouse button is held down, regardless of any bindings.
+    ///
+    pub fn is_down(&self, input: Input) -> bool {
+        self.held_inputs.contains(&input)
+    }
+
+    // Triggers the actions bound to the input with the given modifiers and returns whether any action was triggered
+    fn press(&mut self, input: Input, modifiers: Modifiers, repeat: bool) -> bool {
+        let mut triggered = false;
+        for (action, chords) in self.bindings.iter() {
+            if chords.iter().any(|chord| chord.matches(input, modifiers)) {
+                triggered = true;
+                if repeat {
+                    self.repeated.insert(action.clone());
+                } else {
+                    self.pressed.insert(action.clone());
+                    self.held_actions.insert(action.clone(), input);
+                }
+            }
+        }
+        triggered
+    }
+
+    // Releases the input and the actions triggered by it and returns whether any action was released
+    fn release(&mut self, input: Input) -> bool {
+        self.held_inputs.remove(&input);
+        let released = self
+            .held_actions
+            .iter()
+            .filter(|(_, held_input)| **held_input == input)
+            .map(|(action, _)| action.clone())
+            .collect::<Vec<_>>();
+        for action in released.iter() {
+            self.held_actions.remove(action);
+            self.released.insert(action.clone());
+        }
+        !released.is_empty()
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,9 +3,45 @@ use crate::core::*;
 
 ///
 /// A control that makes the camera orbit around a target.
+/// Dragging with the left mouse button orbits, dragging with the right mouse button pans the camera and the target, and scrolling zooms.
+/// On touch screens, dragging with one finger orbits, dragging with two fingers pans, pinching zooms and rotating two fingers orbits around the up direction.
+///
+/// Optionally, the camera motion can continue and slow down smoothly after the input stops, see [OrbitControl::damping].
 ///
 pub struct OrbitControl {
     control: CameraControl,
+    target: Vec3,
+    /// The rate at which the camera motion slows down after the input stops, measured per second.
+    /// A value of zero disables the inertia, so the camera stops the instant the input stops. A value around 5 gives a smooth motion.
+    /// When enabled, [OrbitControl::update] must be called each frame.
+    pub damping: f32,
+    velocity: Motion,
+    frame_motion: Motion,
+    dragging: bool,
+    touch_gestures: TouchGestures,
+}
+
+#[derive(Clone, Copy)]
+struct Motion {
+    orbit: Vec2,
+    pan: Vec2,
+    zoom: f32,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self {
+            orbit: vec2(0.0, 0.0),
+            pan: vec2(0.0, 0.0),
+            zoom: 0.0,
+        }
+    }
+}
+
+impl Motion {
+    fn is_zero(&self) -> bool {
+        self.orbit == vec2(0.0, 0.0) && self.pan == vec2(0.0, 0.0) && self.zoom == 0.0
+    }
 }
 
 impl OrbitControl {
@@ -23,23 +59,222 @@ impl OrbitControl {
                 },
                 ..Default::default()
             },
+            target,
+            damping: 0.0,
+            velocity: Motion::default(),
+            frame_motion: Motion::default(),
+            dragging: false,
+            touch_gestures: TouchGestures::new(),
         }
     }
 
+    /// Creates a new orbit control with inertia, ie. where the camera motion slows down smoothly with the given damping, see [OrbitControl::damping].
+    pub fn new_with_inertia(
+        target: Vec3,
+        min_distance: f32,
+        max_distance: f32,
+        damping: f32,
+    ) -> Self {
+        Self {
+            damping,
+            ..Self::new(target, min_distance, max_distance)
+        }
+    }
+
+    /// Returns the target which the camera orbits around.
+    pub fn target(&self) -> Vec3 {
+        self.target
+    }
+
     /// Handles the events. Must be called each frame.
     pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event]) -> bool {
-        if let CameraAction::Zoom { speed, target, .. } = &mut self.control.scroll_vertical {
-            let x = target.distance(*camera.position());
-            *speed = 0.01 * x + 0.001;
+        self.update_speeds(camera);
+        let mut change = false;
+        for event in events.iter_mut() {
+            let gesture = self.touch_gestures.handle_event(event);
+            match event {
+                Event::Touch { phase, handled, .. } => {
+                    if *phase == TouchPhase::Started && !*handled {
+                        self.velocity = Motion::default();
+                    }
+                    self.dragging = self.touch_gestures.is_active();
+                    if let Some(gesture) = gesture.filter(|_| !*handled) {
+                        self.handle_touch_gesture(camera, gesture);
+                        *handled = true;
+                        change = true;
+                    }
+                }
+                // Mouse events are emulated from the touches, which are handled above
+                Event::MousePress { handled, .. }
+                | Event::MouseRelease { handled, .. }
+                | Event::MouseMotion { handled, .. }
+                | Event::MouseWheel { handled, .. }
+                    if self.touch_gestures.is_active() =>
+                {
+                    *handled = true;
+                }
+                Event::MousePress { handled, .. } if !*handled => {
+                    self.dragging = true;
+                    self.velocity = Motion::default();
+                }
+                Event::MouseRelease { .. } => {
+                    self.dragging = false;
+                }
+                Event::MouseMotion {
+                    delta,
+                    button: Some(button),
+                    handled,
+                    ..
+                } if !*handled => match button {
+                    MouseButton::Left => {
+                        self.frame_motion.orbit += vec2(delta.0, delta.1);
+                    }
+                    MouseButton::Right => {
+                        self.pan(camera, vec2(delta.0, delta.1));
+                        self.frame_motion.pan += vec2(delta.0, delta.1);
+                        *handled = true;
+                        change = true;
+                    }
+                    MouseButton::Middle => {}
+                },
+                Event::MouseWheel { delta, handled, .. } if !*handled && self.damping > 0.0 => {
+                    // Scrolling gives an impulse which moves the camera the same total distance as without inertia
+                    self.velocity.zoom += delta.1 * self.damping;
+                    *handled = true;
+                }
+                _ => {}
+            }
+        }
+        change |= self.control.handle_events(camera, events);
+        change
+    }
+
+    ///
+    /// Continues the camera motion after the input stops when inertia is enabled, see [OrbitControl::damping].
+    /// Must be called each frame after [OrbitControl::handle_events] with the time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time).
+    /// Returns true if the camera was changed.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, elapsed_time: f64) -> bool {
+        let dt = (elapsed_time * 0.001) as f32;
+        let frame_motion = std::mem::take(&mut self.frame_motion);
+        if self.damping <= 0.0 || dt <= 0.0 {
+            self.velocity = Motion::default();
+            return false;
         }
-        if let CameraAction::OrbitLeft { speed, target } = &mut self.control.left_drag_horizontal {
-            let x = target.distance(*camera.position());
-            *speed = 0.01 * x + 0.001;
+        if self.dragging {
+            // Follow the input while dragging, smoothing the velocity over a few frames
+            let factor = (1.0 - (-20.0 * dt).exp()).min(1.0);
+            self.velocity.orbit += (frame_motion.orbit / dt - self.velocity.orbit) * factor;
+            self.velocity.pan += (frame_motion.pan / dt - self.velocity.pan) * factor;
+            if self.velocity.zoom == 0.0 {
+                return false;
+            }
         }
-        if let CameraAction::OrbitUp { speed, target } = &mut self.control.left_drag_vertical {
-            let x = target.distance(*camera.position());
-            *speed = 0.01 * x + 0.001;
+        if self.velocity.is_zero() {
+            return false;
+        }
+
+        self.update_speeds(camera);
+        let orbit = if self.dragging {
+            vec2(0.0, 0.0)
+        } else {
+            self.velocity.orbit * dt
+        };
+        let pan = if self.dragging {
+            vec2(0.0, 0.0)
+        } else {
+            self.velocity.pan * dt
+        };
+        self.control
+            .handle_action(camera, self.control.left_drag_horizontal, orbit.x);
+        self.control
+            .handle_action(camera, self.control.left_drag_vertical, orbit.y);
+        self.pan(camera, pan);
+        self.control.handle_action(
+            camera,
+            self.control.scroll_vertical,
+            self.velocity.zoom * dt,
+        );
+
+        let decay = (-self.damping * dt).exp();
+        if !self.dragging {
+            self.velocity.orbit *= decay;
+            self.velocity.pan *= decay;
+        }
+        self.velocity.zoom *= decay;
+        if self.velocity.orbit.magnitude() < 0.01 {
+            self.velocity.orbit = vec2(0.0, 0.0);
+        }
+        if self.velocity.pan.magnitude() < 0.01 {
+            self.velocity.pan = vec2(0.0, 0.0);
+        }
+        if self.velocity.zoom.abs() < 0.01 {
+            self.velocity.zoom = 0.0;
+        }
+        true
+    }
+
+    fn handle_touch_gesture(&mut self, camera: &mut Camera, gesture: TouchGesture) {
+        self.update_speeds(camera);
+        if gesture.fingers == 1 {
+            self.control
+                .handle_action(camera, self.control.left_drag_horizontal, gesture.pan.x);
+            self.control
+                .handle_action(camera, self.control.left_drag_vertical, gesture.pan.y);
+            self.frame_motion.orbit += gesture.pan;
+        } else {
+            self.pan(camera, gesture.pan);
+            self.frame_motion.pan += gesture.pan;
+            self.control
+                .handle_action(camera, self.control.scroll_vertical, gesture.pinch);
+            let distance = self.target.distance(*camera.position());
+            camera.rotate_around_with_fixed_up(&self.target, -gesture.rotation * distance, 0.0);
+        }
+    }
+
+    fn update_speeds(&mut self, camera: &Camera) {
+        let x = self.target.distance(*camera.position());
+        let speed = 0.01 * x + 0.001;
+        if let CameraAction::Zoom { speed: s, .. } = &mut self.control.scroll_vertical {
+            *s = speed;
+        }
+        if let CameraAction::OrbitLeft { speed: s, .. } = &mut self.control.left_drag_horizontal {
+            *s = speed;
+        }
+        if let CameraAction::OrbitUp { speed: s, .. } = &mut self.control.left_drag_vertical {
+            *s = speed;
+        }
+    }
+
+    fn pan(&mut self, camera: &mut Camera, delta: Vec2) {
+        if delta == vec2(0.0, 0.0) {
+            return;
+        }
+        let speed = 0.001 * self.target.distance(*camera.position());
+        let right = camera.right_direction();
+        let up = right.cross(camera.view_direction());
+        let change = (-right * delta.x + up * delta.y) * speed;
+        camera.translate(&change);
+        self.set_target(self.target + change);
+    }
+
+    ///
+    /// Sets the target which the camera orbits around without moving the camera,
+    /// for example when the camera follows a moving object, see [TargetLock].
+    ///
+    pub fn set_target(&mut self, target: Vec3) {
+        self.target = target;
+        for action in [
+            &mut self.control.left_drag_horizontal,
+            &mut self.control.left_drag_vertical,
+            &mut self.control.scroll_vertical,
+        ] {
+            match action {
+                CameraAction::OrbitLeft { target, .. }
+                | CameraAction::OrbitUp { target, .. }
+                | CameraAction::Zoom { target, .. } => *target = self.target,
+                _ => {}
+            }
         }
-        self.control.handle_events(camera, events)
     }
 }
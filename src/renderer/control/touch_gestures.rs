@@ -0,0 +1,136 @@
+use super::*;
+use crate::core::*;
+
+///
+/// The change of a touch gesture caused by one [Event::Touch] event, see [TouchGestures].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchGesture {
+    /// The number of fingers touching the screen.
+    pub fingers: usize,
+    /// The screen position of the center of the first two fingers, or of the finger if only one finger touches the screen, in logical pixels.
+    pub position: LogicalPoint,
+    /// The movement of the center of the first two fingers, or of the finger if only one finger touches the screen, in logical pixels.
+    pub pan: Vec2,
+    /// The change in the distance between the first two fingers in logical pixels, which is positive when the fingers move apart and zero if only one finger touches the screen.
+    pub pinch: f32,
+    /// The change in the angle of the line between the first two fingers in radians, which is positive when the fingers rotate clockwise on the screen and zero if only one finger touches the screen.
+    pub rotation: f32,
+}
+
+///
+/// Recognizes multi-touch gestures, ie. panning with one or more fingers, pinching and rotating with two fingers, from [Event::Touch] events.
+/// Used by the camera controls, but can also be used for custom touch handling.
+///
+#[derive(Clone, Debug, Default)]
+pub struct TouchGestures {
+    touches: Vec<(u64, LogicalPoint)>,
+}
+
+impl TouchGestures {
+    ///
+    /// Creates a new gesture recognizer with no fingers touching the screen.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns the number of fingers currently touching the screen.
+    ///
+    pub fn finger_count(&self) -> usize {
+        self.touches.len()
+    }
+
+    ///
+    /// Returns whether or not any fingers are currently touching the screen.
+    /// While this is the case, the mouse events are emulated from the touches, see [Event::Touch].
+    ///
+    pub fn is_active(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    ///
+    /// Updates the state of the fingers touching the screen from the given event, which is ignored if it is not an [Event::Touch] event,
+    /// and returns the change of the gesture if a finger moved.
+    /// Must be called with all events, including handled events, to keep track of the fingers.
+    ///
+    pub fn handle_event(&mut self, event: &Event) -> Option<TouchGesture> {
+        if let Event::Touch {
+            id,
+            phase,
+            position,
+            ..
+        } = event
+        {
+            let index = self.touches.iter().position(|(i, _)| i == id);
+            match phase {
+                TouchPhase::Started => {
+                    if let Some(index) = index {
+                        self.touches[index].1 = *position;
+                    } else {
+                        self.touches.push((*id, *position));
+                    }
+                }
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    if let Some(index) = index {
+                        self.touches.remove(index);
+                    }
+                }
+                TouchPhase::Moved => {
+                    let index = index?;
+                    let before = self.center_and_line();
+                    self.touches[index].1 = *position;
+                    let after = self.center_and_line();
+                    // Only the first two fingers contribute to the gesture
+                    if index > 1 {
+                        return None;
+                    }
+                    let (center_before, line_before) = before;
+                    let (center_after, line_after) = after;
+                    let (pinch, rotation) = match (line_before, line_after) {
+                        (Some(before), Some(after)) => {
+                            let mut rotation = after.y.atan2(after.x) - before.y.atan2(before.x);
+                            if rotation > std::f32::consts::PI {
+                                rotation -= 2.0 * std::f32::consts::PI;
+                            } else if rotation < -std::f32::consts::PI {
+                                rotation += 2.0 * std::f32::consts::PI;
+                            }
+                            (after.magnitude() - before.magnitude(), rotation)
+                        }
+                        _ => (0.0, 0.0),
+                    };
+                    return Some(TouchGesture {
+                        fingers: self.touches.len(),
+                        position: LogicalPoint {
+                            x: center_after.x,
+                            y: center_after.y,
+                            ..*position
+                        },
+                        pan: center_after - center_before,
+                        pinch,
+                        rotation,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    ///
+    /// Discards the state of the fingers, for example when the window loses focus.
+    ///
+    pub fn reset(&mut self) {
+        self.touches.clear();
+    }
+
+    fn center_and_line(&self) -> (Vec2, Option<Vec2>) {
+        let first = Vec2::from(self.touches[0].1);
+        if let Some((_, second)) = self.touches.get(1) {
+            let second = Vec2::from(second);
+            (0.5 * (first + second), Some(second - first))
+        } else {
+            (first, None)
+        }
+    }
+}
@@ -1,46 +1,225 @@
 use super::*;
 use crate::core::*;
+use std::collections::HashSet;
+use three_d_asset::ProjectionType;
 
 ///
 /// A control that makes the camera move perpendicular to the xy plane. Useful for 2D editors.
+/// Dragging with the left mouse button or one finger pans the camera, scrolling or pinching zooms and rotating two fingers rotates the camera around the view direction.
+/// The camera can also be panned with the arrow keys, see [TwoDControl::update], and by dragging with the middle mouse button.
 ///
 pub struct TwoDControl {
     frustum_height: f32,
+    /// The speed of the panning with the arrow keys in logical pixels per second.
+    pub keyboard_speed: f32,
+    /// Whether or not the space key must be held down to pan by dragging with the left mouse button, for example when the left mouse button is also used for selecting.
+    pub drag_requires_space: bool,
+    /// Optional bounds in world space, for example the document area, which the camera cannot pan or zoom outside of.
+    /// Only the x and y coordinates are used.
+    pub bounds: Option<AxisAlignedBoundingBox>,
+    touch_gestures: TouchGestures,
+    pressed_keys: HashSet<Key>,
+    space_down: bool,
 }
 
 impl TwoDControl {
     /// Creates a new 2d control with the given frustum height.
     pub fn new(frustum_height: f32) -> Self {
-        Self { frustum_height }
+        Self {
+            frustum_height,
+            keyboard_speed: 500.0,
+            drag_requires_space: false,
+            bounds: None,
+            touch_gestures: TouchGestures::new(),
+            pressed_keys: HashSet::new(),
+            space_down: false,
+        }
     }
 
     /// Handles the events. Must be called each frame.
     pub fn handle_events(&mut self, camera: &mut Camera, events: &mut [Event]) -> bool {
         let mut handled = false;
-        for event in events.iter() {
+        for event in events.iter_mut() {
+            let gesture = self.touch_gestures.handle_event(event);
+            if self.touch_gestures.is_active() {
+                // Mouse events are emulated from the touches, so only the touches are handled
+                if let Some(gesture) = gesture {
+                    self.pan(camera, gesture.pan);
+                    if gesture.fingers > 1 {
+                        self.zoom(camera, &gesture.position, gesture.pinch);
+                        camera.roll(radians(gesture.rotation));
+                    }
+                    handled = true;
+                }
+                continue;
+            }
             match event {
                 Event::MouseMotion { delta, button, .. } => {
-                    if *button == Some(MouseButton::Left) {
-                        let pan_factor = self.frustum_height / camera.viewport().height as f32;
-                        let speed = pan_factor * camera.position().z.abs();
-                        let right = camera.right_direction();
-                        let up = right.cross(camera.view_direction());
-                        let delta = -right * speed * delta.0 + up * speed * delta.1;
-                        camera.translate(&delta);
+                    let drag = match button {
+                        Some(MouseButton::Left) => !self.drag_requires_space || self.space_down,
+                        Some(MouseButton::Middle) => true,
+                        _ => false,
+                    };
+                    if drag {
+                        self.pan(camera, vec2(delta.0, delta.1));
                         handled = true;
                     }
                 }
                 Event::MouseWheel {
                     delta, position, ..
                 } => {
-                    let mut target = camera.position_at_pixel(position);
-                    target.z = 0.0;
-                    camera.zoom_towards_2d(&target, delta.1.into());
+                    self.zoom(camera, position, delta.1);
                     handled = true;
                 }
+                Event::KeyPress {
+                    kind,
+                    handled: key_handled,
+                    ..
+                } if !*key_handled => match kind {
+                    Key::ArrowLeft | Key::ArrowRight | Key::ArrowUp | Key::ArrowDown => {
+                        self.pressed_keys.insert(*kind);
+                        *key_handled = true;
+                    }
+                    Key::Space => {
+                        self.space_down = true;
+                        *key_handled = self.drag_requires_space;
+                    }
+                    _ => {}
+                },
+                Event::KeyRelease { kind, .. } => {
+                    if *kind == Key::Space {
+                        self.space_down = false;
+                    }
+                    self.pressed_keys.remove(kind);
+                }
+                Event::MouseLeave => {
+                    self.pressed_keys.clear();
+                    self.space_down = false;
+                }
                 _ => {}
             }
         }
+        if handled {
+            self.clamp_to_bounds(camera);
+        }
         handled
     }
+
+    ///
+    /// Pans the camera according to the arrow keys which are held down.
+    /// Must be called each frame after [TwoDControl::handle_events] with the time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time).
+    /// Returns true if the camera was changed.
+    ///
+    pub fn update(&mut self, camera: &mut Camera, elapsed_time: f64) -> bool {
+        let mut direction = vec2(0.0, 0.0);
+        for key in self.pressed_keys.iter() {
+            match key {
+                Key::ArrowLeft => direction.x += 1.0,
+                Key::ArrowRight => direction.x -= 1.0,
+                Key::ArrowUp => direction.y += 1.0,
+                Key::ArrowDown => direction.y -= 1.0,
+                _ => {}
+            }
+        }
+        if direction == vec2(0.0, 0.0) {
+            return false;
+        }
+        // Limits the time step, since the time since the last frame can be long when the application waits for events
+        let distance = self.keyboard_speed * (elapsed_time.min(100.0) * 0.001) as f32;
+        self.pan(camera, direction.normalize() * distance);
+        self.clamp_to_bounds(camera);
+        true
+    }
+
+    ///
+    /// Centers the camera on the given content and zooms so that the content fills the viewport in at least one direction.
+    ///
+    pub fn zoom_to_fit(&self, camera: &mut Camera, aabb: AxisAlignedBoundingBox) {
+        if aabb.is_empty() {
+            return;
+        }
+        let viewport = camera.viewport();
+        let aspect = viewport.width as f32 / viewport.height.max(1) as f32;
+        let size = aabb.size();
+        let center = aabb.center();
+        set_visible_height(camera, size.y.max(size.x / aspect).max(f32::EPSILON));
+        set_center(camera, center.x, center.y);
+        self.clamp_to_bounds(camera);
+    }
+
+    fn clamp_to_bounds(&self, camera: &mut Camera) {
+        let bounds = match self.bounds {
+            Some(bounds) if !bounds.is_empty() => bounds,
+            _ => return,
+        };
+        let viewport = camera.viewport();
+        let aspect = viewport.width as f32 / viewport.height.max(1) as f32;
+        let size = bounds.size();
+        let max_height = size.y.min(size.x / aspect);
+        let mut height = visible_height(camera);
+        if height > max_height && max_height > 0.0 {
+            set_visible_height(camera, max_height);
+            height = max_height;
+        }
+        let clamp = |value: f32, min: f32, max: f32, half_size: f32| {
+            if max - min <= 2.0 * half_size {
+                0.5 * (min + max)
+            } else {
+                value.clamp(min + half_size, max - half_size)
+            }
+        };
+        let position = camera.position();
+        let (min, max) = (bounds.min(), bounds.max());
+        let x = clamp(position.x, min.x, max.x, 0.5 * height * aspect);
+        let y = clamp(position.y, min.y, max.y, 0.5 * height);
+        set_center(camera, x, y);
+    }
+
+    fn pan(&self, camera: &mut Camera, delta: Vec2) {
+        let pan_factor = self.frustum_height / camera.viewport().height as f32;
+        let speed = pan_factor * camera.position().z.abs();
+        let right = camera.right_direction();
+        let up = right.cross(camera.view_direction());
+        let delta = -right * speed * delta.x + up * speed * delta.y;
+        camera.translate(&delta);
+    }
+
+    fn zoom(&self, camera: &mut Camera, position: &LogicalPoint, delta: f32) {
+        let mut target = camera.position_at_pixel(position);
+        target.z = 0.0;
+        camera.zoom_towards_2d(&target, delta.into());
+    }
+}
+
+fn visible_height(camera: &Camera) -> f32 {
+    match camera.projection_type() {
+        ProjectionType::Orthographic { height } => *height,
+        ProjectionType::Perspective { field_of_view_y } => {
+            2.0 * (0.5 * field_of_view_y.0).tan() * camera.position().z.abs()
+        }
+        #[allow(unreachable_patterns)]
+        _ => 0.0,
+    }
+}
+
+fn set_visible_height(camera: &mut Camera, height: f32) {
+    let (z_near, z_far) = (camera.z_near(), camera.z_far());
+    match *camera.projection_type() {
+        ProjectionType::Orthographic { .. } => {
+            camera.set_orthographic_projection(height, z_near, z_far);
+        }
+        ProjectionType::Perspective { field_of_view_y } => {
+            let position = *camera.position();
+            let distance = 0.5 * height / (0.5 * field_of_view_y.0).tan();
+            let change = vec3(0.0, 0.0, distance.copysign(position.z) - position.z);
+            camera.translate(&change);
+        }
+        #[allow(unreachable_patterns)]
+        _ => {}
+    }
+}
+
+fn set_center(camera: &mut Camera, x: f32, y: f32) {
+    let position = *camera.position();
+    camera.translate(&vec3(x - position.x, y - position.y, 0.0));
 }
@@ -0,0 +1,132 @@
+//!
+//! Rendering of the scene into the six sides of a cube map as seen from a point, for example to bake environment maps, capture panoramas or update reflection probes.
+//!
+
+use crate::renderer::*;
+
+///
+/// A cube map color texture together with a cube map depth texture which the scene can be rendered into from a given position.
+/// Each side is rendered with a camera with a 90 degree field of view looking from the position towards the side, see [CubeMapTarget::camera].
+/// The rendered cube map can be used directly, for example as the background of a [Skybox] or as an environment map,
+/// or converted to an equirectangular panorama with [TextureCubeMap::to_equirectangular].
+///
+pub struct CubeMapTarget {
+    color_texture: TextureCubeMap,
+    depth_texture: DepthTextureCubeMap,
+}
+
+impl CubeMapTarget {
+    ///
+    /// Creates a new cube map target where each side has the given size in pixels.
+    /// The data format of the color texture is specified by the generic parameter, for example `[f16; 4]` for HDR environment maps.
+    ///
+    pub fn new<T: TextureDataType>(context: &Context, size: u32) -> Self {
+        Self {
+            color_texture: TextureCubeMap::new_empty::<T>(
+                context,
+                size,
+                size,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                Some(Interpolation::Linear),
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            depth_texture: DepthTextureCubeMap::new::<f32>(
+                context,
+                size,
+                size,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+        }
+    }
+
+    ///
+    /// Returns the camera used for rendering the given side from the given position with the given near and far planes.
+    ///
+    pub fn camera(&self, position: Vec3, side: CubeMapSide, z_near: f32, z_far: f32) -> Camera {
+        Camera::new_perspective(
+            Viewport::new_at_origin(self.color_texture.width(), self.color_texture.height()),
+            position,
+            position + side.direction(),
+            side.up(),
+            degrees(90.0),
+            z_near,
+            z_far,
+        )
+    }
+
+    ///
+    /// Clears each side of the cube map with the given clear state and renders the given objects with the given lights as seen from the given position.
+    ///
+    pub fn render(
+        &mut self,
+        position: Vec3,
+        z_near: f32,
+        z_far: f32,
+        clear_state: ClearState,
+        objects: impl IntoIterator<Item = impl Object> + Clone,
+        lights: &[&dyn Light],
+    ) -> &mut Self {
+        self.render_with(position, z_near, z_far, |_, target, camera| {
+            target
+                .clear(clear_state)
+                .render(camera, objects.clone(), lights);
+        })
+    }
+
+    ///
+    /// Calls the given closure once for each side of the cube map with the side, the render target of that side and the camera to render the side with,
+    /// see [CubeMapTarget::camera]. The render target is not cleared.
+    /// Use this for rendering with other means than [RenderTarget::render], for example with a [Skybox] or a deferred pipeline.
+    ///
+    pub fn render_with(
+        &mut self,
+        position: Vec3,
+        z_near: f32,
+        z_far: f32,
+        mut render: impl FnMut(CubeMapSide, &RenderTarget, &Camera),
+    ) -> &mut Self {
+        for side in CubeMapSide::iter() {
+            let camera = self.camera(position, side, z_near, z_far);
+            let sides = [side];
+            let target = RenderTarget::new(
+                self.color_texture.as_color_target(&sides, None),
+                self.depth_texture.as_depth_target(side),
+            );
+            render(side, &target, &camera);
+        }
+        self
+    }
+
+    ///
+    /// Returns the cube map color texture.
+    ///
+    pub fn color_texture(&self) -> &TextureCubeMap {
+        &self.color_texture
+    }
+
+    ///
+    /// Returns the cube map depth texture.
+    ///
+    pub fn depth_texture(&self) -> &DepthTextureCubeMap {
+        &self.depth_texture
+    }
+
+    ///
+    /// Returns the cube map color texture and discards the depth texture, for example to keep a baked environment map.
+    ///
+    pub fn into_color_texture(self) -> TextureCubeMap {
+        self.color_texture
+    }
+
+    ///
+    /// Returns an equirectangular panorama of the rendered cube map with the given width and half the width as height, see [TextureCubeMap::to_equirectangular].
+    ///
+    pub fn to_equirectangular<T: TextureDataType>(&self, width: u32) -> Texture2D {
+        self.color_texture.to_equirectangular::<T>(width)
+    }
+}
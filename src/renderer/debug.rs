@@ -0,0 +1,480 @@
+//!
+//! Helpers for visualising the data of a geometry, for example normals, tangents, uv coordinates, vertex colors and bounding boxes.
+//! Invaluable when debugging imported assets, see [DebugObject] for an easy way to toggle the visualisations per object.
+//!
+
+use crate::renderer::*;
+
+///
+/// A geometry consisting of colored line segments, each rendered as a thin cylinder.
+/// Render it with a [ColorMaterial] to see the colors of the line segments.
+///
+pub struct DebugLines {
+    mesh: InstancedMesh,
+    aabb: AxisAlignedBoundingBox,
+}
+
+impl DebugLines {
+    ///
+    /// Creates line segments from the given start points, end points and colors. The colors are assumed to be in linear color space.
+    ///
+    pub fn new(context: &Context, segments: &[(Vec3, Vec3, Color)], thickness: f32) -> Self {
+        let mut transformations = Vec::with_capacity(segments.len());
+        let mut colors = Vec::with_capacity(segments.len());
+        let mut positions = Vec::with_capacity(2 * segments.len());
+        for (start, end, color) in segments {
+            let direction = end - start;
+            let length = direction.magnitude();
+            if length < f32::EPSILON {
+                continue;
+            }
+            let rotation = Quat::from_arc(vec3(1.0, 0.0, 0.0), direction / length, None);
+            transformations.push(
+                Mat4::from_translation(*start)
+                    * Mat4::from(rotation)
+                    * Mat4::from_nonuniform_scale(length, thickness, thickness),
+            );
+            colors.push(*color);
+            positions.push(*start);
+            positions.push(*end);
+        }
+        let mut aabb = AxisAlignedBoundingBox::new_with_positions(&positions);
+        if !positions.is_empty() {
+            let t = vec3(thickness, thickness, thickness);
+            aabb = AxisAlignedBoundingBox::new_with_positions(&[aabb.min() - t, aabb.max() + t]);
+        }
+        let mesh = InstancedMesh::new(
+            context,
+            &Instances {
+                transformations,
+                colors: Some(colors),
+                ..Default::default()
+            },
+            &CpuMesh::cylinder(8),
+        );
+        Self { mesh, aabb }
+    }
+
+    ///
+    /// Creates line segments visualising the normals of the given mesh.
+    /// Each line starts at a vertex, has the given length and is colored according to the direction of the normal, using the same colors as [DebugView::Normals].
+    /// Creates no line segments if the mesh has no normals, see [CpuMesh::compute_normals].
+    ///
+    pub fn from_normals(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        length: f32,
+        thickness: f32,
+    ) -> Self {
+        let positions = cpu_mesh.positions.to_f32();
+        let segments = cpu_mesh
+            .normals
+            .as_ref()
+            .map(|normals| {
+                positions
+                    .iter()
+                    .zip(normals.iter())
+                    .map(|(p, n)| (*p, p + n * length, direction_color(*n)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        Self::new(context, &segments, thickness)
+    }
+
+    ///
+    /// Creates line segments visualising the tangent frames of the given mesh.
+    /// For each vertex, a red line shows the tangent and a green line shows the bitangent, both with the given length.
+    /// Creates no line segments if the mesh has no normals and tangents, see [CpuMesh::compute_tangents].
+    ///
+    pub fn from_tangents(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        length: f32,
+        thickness: f32,
+    ) -> Self {
+        let positions = cpu_mesh.positions.to_f32();
+        let mut segments = Vec::new();
+        if let (Some(normals), Some(tangents)) = (&cpu_mesh.normals, &cpu_mesh.tangents) {
+            for ((p, n), t) in positions.iter().zip(normals.iter()).zip(tangents.iter()) {
+                let tangent = t.truncate();
+                let bitangent = n.cross(tangent) * t.w;
+                segments.push((*p, p + tangent * length, Color::RED));
+                segments.push((*p, p + bitangent * length, Color::GREEN));
+            }
+        }
+        Self::new(context, &segments, thickness)
+    }
+
+    ///
+    /// Creates a wireframe of the given axis aligned bounding box.
+    ///
+    pub fn from_aabb(
+        context: &Context,
+        aabb: AxisAlignedBoundingBox,
+        color: Color,
+        thickness: f32,
+    ) -> Self {
+        let center = 0.5 * (aabb.min() + aabb.max());
+        let half_size = 0.5 * aabb.size();
+        Self::from_oriented_box(
+            context,
+            Mat4::from_translation(center)
+                * Mat4::from_nonuniform_scale(half_size.x, half_size.y, half_size.z),
+            color,
+            thickness,
+        )
+    }
+
+    ///
+    /// Creates a wireframe of an oriented bounding box, given as the transformation that maps the cube from `(-1, -1, -1)` to `(1, 1, 1)`, ie. [CpuMesh::cube], to the box.
+    ///
+    pub fn from_oriented_box(
+        context: &Context,
+        transformation: Mat4,
+        color: Color,
+        thickness: f32,
+    ) -> Self {
+        let corner = |i: usize| {
+            (transformation
+                * vec4(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                    1.0,
+                ))
+            .truncate()
+        };
+        let mut segments = Vec::with_capacity(12);
+        for i in 0..8 {
+            for axis in [1, 2, 4] {
+                if i & axis == 0 {
+                    segments.push((corner(i), corner(i | axis), color));
+                }
+            }
+        }
+        Self::new(context, &segments, thickness)
+    }
+}
+
+fn direction_color(direction: Vec3) -> Color {
+    let c = 127.5 * (direction.normalize() + vec3(1.0, 1.0, 1.0));
+    Color::new_opaque(c.x as u8, c.y as u8, c.z as u8)
+}
+
+impl<'a> IntoIterator for &'a DebugLines {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for DebugLines {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+}
+
+///
+/// The vertex data visualised by a [DebugMaterial].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DebugView {
+    /// The normals mapped from `[-1..1]` to colors in `[0..1]`, so for example a normal pointing along the positive x axis is pink and along the positive y axis is light green.
+    /// Requires normals.
+    Normals,
+    /// The tangents mapped to colors in the same way as the normals. Requires tangents.
+    Tangents,
+    /// The uv coordinates, where the u coordinate is shown as red and the v coordinate as green. The coordinates are wrapped to `[0..1]`. Requires uv coordinates.
+    Uvs,
+    /// A checkerboard pattern in uv space, useful for spotting stretching and seams in the uv mapping.
+    /// The parts of the surface with uv coordinates outside `[0..1]` are tinted purple. Requires uv coordinates.
+    Checkerboard {
+        /// The number of squares along each of the uv axes.
+        squares: u32,
+    },
+    /// The vertex colors. Geometries without vertex colors are shown as white.
+    VertexColors,
+}
+
+///
+/// A material which visualises vertex data of a geometry, see [DebugView].
+/// It is not affected by lights.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct DebugMaterial {
+    /// The vertex data to visualise.
+    pub view: DebugView,
+    /// The colors of the squares when using [DebugView::Checkerboard]. Assumed to be in linear color space.
+    pub checkerboard_colors: [Color; 2],
+}
+
+impl DebugMaterial {
+    ///
+    /// Creates a new debug material showing the given view.
+    ///
+    pub fn new(view: DebugView) -> Self {
+        Self {
+            view,
+            checkerboard_colors: [
+                Color::new_opaque(50, 50, 50),
+                Color::new_opaque(200, 200, 200),
+            ],
+        }
+    }
+}
+
+impl Material for DebugMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        let (define, attributes) = match self.view {
+            DebugView::Normals => (
+                "VIEW_NORMALS",
+                FragmentAttributes {
+                    normal: true,
+                    ..FragmentAttributes::NONE
+                },
+            ),
+            DebugView::Tangents => (
+                "VIEW_TANGENTS",
+                FragmentAttributes {
+                    normal: true,
+                    tangents: true,
+                    ..FragmentAttributes::NONE
+                },
+            ),
+            DebugView::Uvs => (
+                "VIEW_UVS",
+                FragmentAttributes {
+                    uv: true,
+                    ..FragmentAttributes::NONE
+                },
+            ),
+            DebugView::Checkerboard { .. } => (
+                "VIEW_CHECKERBOARD",
+                FragmentAttributes {
+                    uv: true,
+                    ..FragmentAttributes::NONE
+                },
+            ),
+            DebugView::VertexColors => (
+                "VIEW_VERTEX_COLORS",
+                FragmentAttributes {
+                    color: true,
+                    ..FragmentAttributes::NONE
+                },
+            ),
+        };
+        FragmentShader {
+            source: format!(
+                "#define {}\n{}{}",
+                define,
+                include_str!("../core/shared.frag"),
+                include_str!("shaders/debug_material.frag")
+            ),
+            attributes,
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        if let DebugView::Checkerboard { squares } = self.view {
+            program.use_uniform("squares", squares.max(1) as f32);
+            program.use_uniform("color0", self.checkerboard_colors[0]);
+            program.use_uniform("color1", self.checkerboard_colors[1]);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+///
+/// Wraps any [Object] and adds debug visualisations which can be toggled on and off per object.
+/// When [DebugObject::enabled] is true, the object is rendered with a [DebugMaterial] if [DebugObject::view] is set, otherwise with its own material,
+/// and the [DebugObject::lines], for example normals created with [DebugLines::from_normals], and the bounding box are rendered on top.
+/// When disabled, the object is rendered exactly as without the wrapper.
+///
+pub struct DebugObject<T: Object> {
+    context: Context,
+    object: T,
+    /// Whether or not the debug visualisations are shown.
+    pub enabled: bool,
+    /// The vertex data to visualise instead of the material of the object, or `None` to render the object with its own material.
+    pub view: Option<DebugMaterial>,
+    /// Additional line geometries, for example normals, tangents or oriented bounding boxes. They are rendered with a [ColorMaterial] so the line colors are shown.
+    pub lines: Vec<DebugLines>,
+    /// The color of the axis aligned bounding box of the object, or `None` to hide the bounding box.
+    pub bounding_box_color: Option<Color>,
+}
+
+impl<T: Object> DebugObject<T> {
+    ///
+    /// Wraps the given object. The debug visualisations are enabled, but nothing is visualised until one of [DebugObject::view], [DebugObject::lines] or [DebugObject::bounding_box_color] is set.
+    ///
+    pub fn new(context: &Context, object: T) -> Self {
+        Self {
+            context: context.clone(),
+            object,
+            enabled: true,
+            view: None,
+            lines: Vec::new(),
+            bounding_box_color: None,
+        }
+    }
+
+    ///
+    /// Returns a reference to the wrapped object.
+    ///
+    pub fn object(&self) -> &T {
+        &self.object
+    }
+
+    ///
+    /// Returns a mutable reference to the wrapped object.
+    ///
+    pub fn object_mut(&mut self) -> &mut T {
+        &mut self.object
+    }
+
+    ///
+    /// Returns the wrapped object.
+    ///
+    pub fn into_inner(self) -> T {
+        self.object
+    }
+
+    fn render_debug(&self, camera: &Camera, lights: &[&dyn Light]) {
+        let material = ColorMaterial::default();
+        for lines in self.lines.iter() {
+            lines.render_with_material(&material, camera, lights);
+        }
+        if let Some(color) = self.bounding_box_color {
+            let aabb = self.object.aabb();
+            let size = aabb.size();
+            if !aabb.is_empty() && size.x.is_finite() && size.y.is_finite() && size.z.is_finite() {
+                DebugLines::from_aabb(
+                    &self.context,
+                    aabb,
+                    color,
+                    0.005 * size.x.max(size.y).max(size.z),
+                )
+                .render_with_material(&material, camera, lights);
+            }
+        }
+    }
+}
+
+impl<'a, T: Object> IntoIterator for &'a DebugObject<T> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<T: Object> Geometry for DebugObject<T> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.object.aabb();
+        if self.enabled {
+            for lines in self.lines.iter() {
+                aabb.expand_with_aabb(&lines.aabb());
+            }
+        }
+        aabb
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.object.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.object.render_with_post_material(
+            material,
+            camera,
+            lights,
+            color_texture,
+            depth_texture,
+        )
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.object.layers()
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.object.triangle_count()
+    }
+}
+
+impl<T: Object> Object for DebugObject<T> {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        if !self.enabled {
+            return self.object.render(camera, lights);
+        }
+        if let Some(material) = &self.view {
+            self.object.render_with_material(material, camera, lights);
+        } else {
+            self.object.render(camera, lights);
+        }
+        self.render_debug(camera, lights);
+    }
+
+    fn material_type(&self) -> MaterialType {
+        if self.enabled && self.view.is_some() {
+            MaterialType::Opaque
+        } else {
+            self.object.material_type()
+        }
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.object.render_queue()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.object.material()
+    }
+}
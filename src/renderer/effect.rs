@@ -2,6 +2,10 @@
 //! A collection of image based effects, ie. effects applied to each pixel of a rendered image.
 //!
 
+mod ambient_occlusion;
+#[doc(inline)]
+pub use ambient_occlusion::*;
+
 mod fog;
 #[doc(inline)]
 pub use fog::*;
@@ -9,3 +13,19 @@ pub use fog::*;
 mod fxaa;
 #[doc(inline)]
 pub use fxaa::*;
+
+mod srgb_encoding;
+#[doc(inline)]
+pub use srgb_encoding::*;
+
+mod outline;
+#[doc(inline)]
+pub use outline::*;
+
+mod highlight;
+#[doc(inline)]
+pub use highlight::*;
+
+mod eye_dome_lighting;
+#[doc(inline)]
+pub use eye_dome_lighting::*;
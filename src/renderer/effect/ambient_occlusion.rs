@@ -0,0 +1,226 @@
+use crate::renderer::*;
+
+///
+/// The method used for calculating the ambient occlusion in an [AmbientOcclusionEffect].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AmbientOcclusionMethod {
+    /// Screen space ambient occlusion which counts how many sample points in a hemisphere around each surface point are hidden behind the depth buffer.
+    /// Fast, but noisy and it tends to over-darken.
+    #[default]
+    Ssao,
+    /// Ground truth ambient occlusion which finds the horizons in a number of slices around each surface point and integrates the visible part of the hemisphere analytically.
+    /// More expensive, but closer to ray traced ambient occlusion. Combine it with temporal accumulation, see [AmbientOcclusionEffect::temporal_blend], for the best quality.
+    Gtao,
+}
+
+///
+/// An effect that darkens creases, holes and areas close to other surfaces, where ambient light is less likely to reach, based only on the depth buffer.
+/// First, calculate the ambient occlusion with [AmbientOcclusionEffect::compute] and then apply it to the rendered image with [AmbientOcclusionEffect::apply].
+///
+/// The result can be accumulated over several frames, which removes most of the noise when the camera and the objects are still or moving slowly.
+/// Parts of the image that were hidden in the previous frame are detected by comparing depths and are not accumulated.
+///
+pub struct AmbientOcclusionEffect {
+    /// The method used for calculating the ambient occlusion.
+    pub method: AmbientOcclusionMethod,
+    /// The radius in world units around each surface point where other surfaces occlude.
+    pub radius: f32,
+    /// The strength of the occlusion. A value of 1 gives the physically based result for [AmbientOcclusionMethod::Gtao].
+    pub intensity: f32,
+    /// The number of sample points for [AmbientOcclusionMethod::Ssao] or the number of slices for [AmbientOcclusionMethod::Gtao].
+    pub samples: u32,
+    /// The number of steps on each side of a slice when searching for horizons with [AmbientOcclusionMethod::Gtao].
+    pub steps: u32,
+    /// How much of the result from the previous frames is kept, in the range `[0..1]`. A value of 0 disables temporal accumulation.
+    pub temporal_blend: f32,
+    textures: Vec<Texture2D>,
+    current: usize,
+    frame: u32,
+    previous_view_projection: Option<Mat4>,
+}
+
+impl Default for AmbientOcclusionEffect {
+    fn default() -> Self {
+        Self {
+            method: AmbientOcclusionMethod::default(),
+            radius: 0.5,
+            intensity: 1.0,
+            samples: 16,
+            steps: 8,
+            temporal_blend: 0.0,
+            textures: Vec::new(),
+            current: 0,
+            frame: 0,
+            previous_view_projection: None,
+        }
+    }
+}
+
+impl AmbientOcclusionEffect {
+    ///
+    /// Creates a new ground truth ambient occlusion effect with temporal accumulation, which is the high quality alternative to the default screen space ambient occlusion.
+    ///
+    pub fn new_gtao() -> Self {
+        Self {
+            method: AmbientOcclusionMethod::Gtao,
+            samples: 4,
+            steps: 8,
+            temporal_blend: 0.9,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Calculates the ambient occlusion from the given depth texture, which must have the same size as the viewport of the camera.
+    /// The result is stored in this effect and used by [AmbientOcclusionEffect::apply].
+    /// If temporal accumulation is enabled, it is blended with the result of the previous call.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn compute(&mut self, context: &Context, camera: &Camera, depth_texture: DepthTexture) {
+        let (width, height) = depth_texture.resolution();
+        if self.textures.len() != 2
+            || self.textures[0].width() != width
+            || self.textures[0].height() != height
+        {
+            let precision =
+                context.select_render_precision("ambient occlusion", ColorPrecision::F16, true);
+            self.textures = (0..2)
+                .map(|_| match precision {
+                    ColorPrecision::U8 => new_texture::<[u8; 2]>(context, width, height),
+                    _ => new_texture::<[f16; 2]>(context, width, height),
+                })
+                .collect();
+            self.previous_view_projection = None;
+        }
+        let use_history = self.temporal_blend > 0.0 && self.previous_view_projection.is_some();
+        let view_projection = camera.projection() * camera.view();
+        let previous_view_projection = self.previous_view_projection.unwrap_or(view_projection);
+
+        let (first, second) = self.textures.split_at_mut(1);
+        let (target, history) = if self.current == 0 {
+            (&mut second[0], &first[0])
+        } else {
+            (&mut first[0], &second[0])
+        };
+        let history = ColorTexture::Single(history);
+        let viewport = Viewport::new_at_origin(width, height);
+        let fragment_shader_source = format!(
+            "{}{}\n{}\n{}\n{}",
+            if self.method == AmbientOcclusionMethod::Gtao {
+                "#define USE_GTAO\n"
+            } else {
+                ""
+            },
+            include_str!("../../core/shared.frag"),
+            depth_texture.fragment_shader_source(),
+            history.fragment_shader_source(),
+            include_str!("shaders/ambient_occlusion_effect.frag")
+        );
+        target.as_color_target(None).write(|| {
+            apply_effect(
+                context,
+                &fragment_shader_source,
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTest::Always,
+                    cull: Cull::Back,
+                    ..Default::default()
+                },
+                viewport,
+                |program| {
+                    depth_texture.use_uniforms(program);
+                    program.use_uniform("projection", camera.projection());
+                    program.use_uniform("projectionInverse", camera.projection().invert().unwrap());
+                    program.use_uniform_if_required(
+                        "viewProjectionInverse",
+                        view_projection.invert().unwrap(),
+                    );
+                    program.use_uniform_if_required(
+                        "previousViewProjection",
+                        previous_view_projection,
+                    );
+                    program.use_uniform("resolution", vec2(width as f32, height as f32));
+                    program.use_uniform("radius", self.radius);
+                    program.use_uniform("intensity", self.intensity);
+                    program.use_uniform("samples", self.samples.max(1) as i32);
+                    program.use_uniform_if_required("steps", self.steps.max(1) as i32);
+                    program.use_uniform_if_required("frame", self.frame as f32);
+                    program.use_uniform_if_required("temporalBlend", self.temporal_blend.min(1.0));
+                    program.use_uniform_if_required("useHistory", use_history as i32);
+                    if use_history {
+                        history.use_uniforms(program);
+                    }
+                },
+            );
+        });
+        self.current = 1 - self.current;
+        self.frame = self.frame.wrapping_add(1);
+        self.previous_view_projection = Some(view_projection);
+    }
+
+    ///
+    /// Returns the texture containing the ambient occlusion calculated by the last call to [AmbientOcclusionEffect::compute], if any.
+    /// The red channel contains the amount of ambient light reaching each pixel in the range `[0..1]`.
+    ///
+    pub fn texture(&self) -> Option<&Texture2D> {
+        self.textures.get(self.current)
+    }
+
+    ///
+    /// Darkens the current content of the render target by multiplying it with the ambient occlusion calculated by the last call to [AmbientOcclusionEffect::compute].
+    /// Does nothing if the ambient occlusion has not been calculated.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn apply(&self, context: &Context, camera: &Camera) {
+        if let Some(texture) = self.texture() {
+            let texture = ColorTexture::Single(texture);
+            apply_effect(
+                context,
+                &format!(
+                    "{}\n{}",
+                    texture.fragment_shader_source(),
+                    include_str!("shaders/ambient_occlusion_apply.frag")
+                ),
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTest::Always,
+                    blend: Blend::Enabled {
+                        source_rgb_multiplier: BlendMultiplierType::Zero,
+                        source_alpha_multiplier: BlendMultiplierType::Zero,
+                        destination_rgb_multiplier: BlendMultiplierType::SrcColor,
+                        destination_alpha_multiplier: BlendMultiplierType::One,
+                        rgb_equation: BlendEquationType::Add,
+                        alpha_equation: BlendEquationType::Add,
+                    },
+                    cull: Cull::Back,
+                    ..Default::default()
+                },
+                camera.viewport(),
+                |program| {
+                    texture.use_uniforms(program);
+                },
+            )
+        }
+    }
+
+    ///
+    /// Discards the accumulated result of the previous frames, for example when the camera jumps to a new position.
+    ///
+    pub fn reset_history(&mut self) {
+        self.previous_view_projection = None;
+    }
+}
+
+fn new_texture<T: TextureDataType>(context: &Context, width: u32, height: u32) -> Texture2D {
+    Texture2D::new_empty::<T>(
+        context,
+        width,
+        height,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
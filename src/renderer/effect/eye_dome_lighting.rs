@@ -0,0 +1,120 @@
+use crate::renderer::*;
+
+///
+/// Eye-dome lighting, a screen space shading based only on the depth of the rendered image, which greatly improves the depth perception of unlit geometry, for example [Points].
+/// Each pixel is darkened depending on how much closer the neighbouring pixels are to the camera, which gives dark outlines around the silhouettes and along depth discontinuities.
+///
+/// Render the scene into a color and depth texture and then apply the effect using [EyeDomeLightingEffect::apply].
+/// Since the effect is a [PostMaterial], it can also be applied to a geometry using for example [RenderTarget::render_with_post_material].
+///
+#[derive(Clone, Debug)]
+pub struct EyeDomeLightingEffect {
+    /// The strength of the shading, where a higher value gives darker outlines.
+    pub strength: f32,
+    /// The distance in pixels to the neighbouring pixels which are compared to each pixel, where a higher value gives wider outlines.
+    pub radius: f32,
+}
+
+impl Default for EyeDomeLightingEffect {
+    fn default() -> Self {
+        Self {
+            strength: 1.0,
+            radius: 1.4,
+        }
+    }
+}
+
+impl EyeDomeLightingEffect {
+    ///
+    /// Applies the eye-dome lighting to the given color texture based on the given depth texture and writes the shaded colors to the current render target.
+    /// The camera must be the camera used for rendering the color and depth textures.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn apply(
+        &self,
+        context: &Context,
+        camera: &Camera,
+        color_texture: ColorTexture,
+        depth_texture: DepthTexture,
+    ) {
+        let fragment_shader = self.fragment_shader(&[], Some(color_texture), Some(depth_texture));
+        apply_effect(
+            context,
+            &fragment_shader.source,
+            self.render_states(),
+            camera.viewport(),
+            |program| {
+                self.use_uniforms(
+                    program,
+                    camera,
+                    &[],
+                    Some(color_texture),
+                    Some(depth_texture),
+                )
+            },
+        )
+    }
+}
+
+impl PostMaterial for EyeDomeLightingEffect {
+    fn fragment_shader(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}\n{}\n{}",
+                color_texture
+                    .expect("Must supply a color texture to apply the eye-dome lighting effect")
+                    .fragment_shader_source(),
+                depth_texture
+                    .expect("Must supply a depth texture to apply the eye-dome lighting effect")
+                    .fragment_shader_source(),
+                include_str!("shaders/eye_dome_lighting_effect.frag")
+            ),
+            attributes: FragmentAttributes {
+                uv: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        color_texture
+            .expect("Must supply a color texture to apply the eye-dome lighting effect")
+            .use_uniforms(program);
+        let depth_texture = depth_texture
+            .expect("Must supply a depth texture to apply the eye-dome lighting effect");
+        depth_texture.use_uniforms(program);
+        let (width, height) = depth_texture.resolution();
+        program.use_uniform("resolution", vec2(width as f32, height as f32));
+        program.use_uniform(
+            "projectionInverse",
+            camera.projection().invert().unwrap_or(Mat4::identity()),
+        );
+        program.use_uniform("strength", self.strength);
+        program.use_uniform("radius", self.radius);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
@@ -0,0 +1,190 @@
+use crate::renderer::*;
+
+///
+/// Defines how objects are highlighted by a [Highlighter].
+///
+#[derive(Clone, Debug)]
+pub struct HighlightStyle {
+    /// The tint and hatch lines rendered on top of the highlighted objects or `None` to disable them.
+    pub material: Option<HighlightMaterial>,
+    /// The outline drawn around the highlighted objects or `None` to disable it.
+    pub outline: Option<OutlineEffect>,
+}
+
+impl HighlightStyle {
+    ///
+    /// The default style for hovered objects, which is a light tint.
+    ///
+    pub fn hover() -> Self {
+        Self {
+            material: Some(HighlightMaterial {
+                tint: Color::new(255, 255, 255, 50),
+                ..Default::default()
+            }),
+            outline: None,
+        }
+    }
+
+    ///
+    /// The default style for selected objects, which is an orange outline and tint.
+    ///
+    pub fn selection() -> Self {
+        Self {
+            material: Some(HighlightMaterial::default()),
+            outline: Some(OutlineEffect::default()),
+        }
+    }
+}
+
+///
+/// Keeps track of the hovered object and the selected objects, for example found using the [ObjectPicker](crate::ObjectPicker),
+/// and highlights them on top of the rendered scene using a [HighlightStyle] for each state.
+/// The objects are identified by their index in the slice of geometries given to [Highlighter::render], which is the same index as returned by the picker.
+///
+#[derive(Clone, Debug)]
+pub struct Highlighter {
+    /// The style of the hovered object.
+    pub hover_style: HighlightStyle,
+    /// The style of the selected objects.
+    pub selection_style: HighlightStyle,
+    hovered: Option<usize>,
+    selection: Vec<usize>,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Highlighter {
+    ///
+    /// Creates a new highlighter with the default styles and no hovered or selected objects.
+    ///
+    pub fn new() -> Self {
+        Self {
+            hover_style: HighlightStyle::hover(),
+            selection_style: HighlightStyle::selection(),
+            hovered: None,
+            selection: Vec::new(),
+        }
+    }
+
+    ///
+    /// Sets the hovered object. Returns true if the hovered object changed.
+    ///
+    pub fn set_hovered(&mut self, index: Option<usize>) -> bool {
+        let change = self.hovered != index;
+        self.hovered = index;
+        change
+    }
+
+    ///
+    /// Returns the hovered object.
+    ///
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    ///
+    /// Adds the given object to the selection.
+    ///
+    pub fn select(&mut self, index: usize) {
+        if let Err(i) = self.selection.binary_search(&index) {
+            self.selection.insert(i, index);
+        }
+    }
+
+    ///
+    /// Removes the given object from the selection.
+    ///
+    pub fn deselect(&mut self, index: usize) {
+        if let Ok(i) = self.selection.binary_search(&index) {
+            self.selection.remove(i);
+        }
+    }
+
+    ///
+    /// Removes the given object from the selection if it is selected and otherwise adds it, for example when clicking with a modifier key pressed.
+    ///
+    pub fn toggle_selection(&mut self, index: usize) {
+        match self.selection.binary_search(&index) {
+            Ok(i) => {
+                self.selection.remove(i);
+            }
+            Err(i) => self.selection.insert(i, index),
+        }
+    }
+
+    ///
+    /// Replaces the selection with the given objects, for example the result of a [RegionPicker](crate::RegionPicker).
+    ///
+    pub fn set_selection(&mut self, indices: impl IntoIterator<Item = usize>) {
+        self.selection = indices.into_iter().collect();
+        self.selection.sort_unstable();
+        self.selection.dedup();
+    }
+
+    ///
+    /// Deselects all objects.
+    ///
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+    }
+
+    ///
+    /// Returns whether or not the given object is selected.
+    ///
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selection.binary_search(&index).is_ok()
+    }
+
+    ///
+    /// Returns the selected objects in increasing order.
+    ///
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    ///
+    /// Highlights the selected objects and then the hovered object on top of the current content of the given render target, which should contain the rendered scene including the depth.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(
+        &self,
+        context: &Context,
+        target: &RenderTarget,
+        camera: &Camera,
+        geometries: &[&dyn Geometry],
+    ) {
+        let selected: Vec<&dyn Geometry> = self
+            .selection
+            .iter()
+            .filter_map(|i| geometries.get(*i).copied())
+            .collect();
+        let hovered: Vec<&dyn Geometry> = self
+            .hovered
+            .and_then(|i| geometries.get(i).copied())
+            .into_iter()
+            .collect();
+        for (style, geometries) in [
+            (&self.selection_style, selected),
+            (&self.hover_style, hovered),
+        ] {
+            if geometries.is_empty() {
+                continue;
+            }
+            if let Some(material) = &style.material {
+                target.write(|| {
+                    for geometry in geometries.iter() {
+                        geometry.render_with_material(material, camera, &[]);
+                    }
+                });
+            }
+            if let Some(outline) = &style.outline {
+                let mask = outline.render_mask(context, camera, geometries.iter().copied());
+                target.write(|| outline.apply(context, camera, ColorTexture::Single(&mask)));
+            }
+        }
+    }
+}
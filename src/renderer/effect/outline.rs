@@ -0,0 +1,93 @@
+use crate::renderer::*;
+
+///
+/// An effect that draws a colored outline around a set of objects, for example to highlight selected or hovered objects.
+/// First, render the objects into a mask using [OutlineEffect::render_mask] and then apply the effect using that mask with [OutlineEffect::apply].
+///
+#[derive(Clone, Debug)]
+pub struct OutlineEffect {
+    /// The color of the outline.
+    pub color: Color,
+    /// The width of the outline in pixels.
+    pub width: u32,
+}
+
+impl Default for OutlineEffect {
+    fn default() -> Self {
+        Self {
+            color: Color::new_opaque(255, 165, 0),
+            width: 3,
+        }
+    }
+}
+
+impl OutlineEffect {
+    ///
+    /// Renders the given geometries into a mask texture with the same size as the viewport of the camera.
+    /// The mask is used as input to [OutlineEffect::apply].
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render_mask(
+        &self,
+        context: &Context,
+        camera: &Camera,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) -> Texture2D {
+        let viewport = Viewport::new_at_origin(camera.viewport().width, camera.viewport().height);
+        let mut mask_camera = camera.clone();
+        mask_camera.set_viewport(viewport);
+        let mut mask = Texture2D::new_empty::<u8>(
+            context,
+            viewport.width,
+            viewport.height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        mask.as_color_target(None)
+            .clear(ClearState::color(0.0, 0.0, 0.0, 0.0))
+            .render_with_material(
+                &ColorMaterial {
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+                &mask_camera,
+                geometries,
+                &[],
+            );
+        mask
+    }
+
+    ///
+    /// Draws the outline around the area defined by the given mask (see [OutlineEffect::render_mask]).
+    /// The outline is blended on top of the current content of the render target.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn apply(&self, context: &Context, camera: &Camera, mask: ColorTexture) {
+        apply_effect(
+            context,
+            &format!(
+                "{}\n{}",
+                mask.fragment_shader_source(),
+                include_str!("shaders/outline_effect.frag")
+            ),
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                blend: Blend::TRANSPARENCY,
+                cull: Cull::Back,
+                ..Default::default()
+            },
+            camera.viewport(),
+            |program| {
+                mask.use_uniforms(program);
+                let (w, h) = mask.resolution();
+                program.use_uniform("resolution", vec2(w as f32, h as f32));
+                program.use_uniform("outlineColor", self.color);
+                program.use_uniform("outlineWidth", self.width as i32);
+            },
+        )
+    }
+}
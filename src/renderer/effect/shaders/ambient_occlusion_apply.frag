@@ -0,0 +1,9 @@
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(vec3(sample_color(uvs).x), 1.0);
+}
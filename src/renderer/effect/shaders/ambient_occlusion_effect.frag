@@ -0,0 +1,169 @@
+
+uniform mat4 projection;
+uniform mat4 projectionInverse;
+uniform mat4 viewProjectionInverse;
+uniform mat4 previousViewProjection;
+uniform vec2 resolution;
+uniform float radius;
+uniform float intensity;
+uniform int samples;
+uniform int steps;
+uniform float frame;
+uniform float temporalBlend;
+uniform int useHistory;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+const float PI = 3.14159265359;
+const float HALF_PI = 1.57079632679;
+
+vec3 view_position(vec2 uv) {
+    return world_pos_from_depth(projectionInverse, sample_depth(uv), uv);
+}
+
+// Reconstructs the view space normal from the depth buffer, using the neighbour with the smallest depth difference on each axis to avoid artifacts at depth discontinuities
+vec3 view_normal(vec3 p, vec2 uv) {
+    vec2 texel = 1.0 / resolution;
+    vec3 left = p - view_position(uv - vec2(texel.x, 0.0));
+    vec3 right = view_position(uv + vec2(texel.x, 0.0)) - p;
+    vec3 down = p - view_position(uv - vec2(0.0, texel.y));
+    vec3 up = view_position(uv + vec2(0.0, texel.y)) - p;
+    vec3 dx = abs(left.z) < abs(right.z) ? left : right;
+    vec3 dy = abs(down.z) < abs(up.z) ? down : up;
+    return normalize(cross(dx, dy));
+}
+
+// Interleaved gradient noise, offset every frame so the temporal accumulation converges to the noise free result
+float noise(vec2 pixel) {
+    pixel += 5.588238 * mod(frame, 64.0);
+    return fract(52.9829189 * fract(0.06711056 * pixel.x + 0.00583715 * pixel.y));
+}
+
+float radius_in_pixels(vec3 p) {
+    vec4 clip = projection * vec4(p, 1.0);
+    return radius * projection[1][1] * 0.5 * resolution.y / clip.w;
+}
+
+float ssao(vec3 p, vec3 n, float rotation) {
+    // Build a tangent frame randomly rotated around the normal
+    vec3 helper = abs(n.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+    vec3 t = normalize(cross(helper, n));
+    vec3 b = cross(n, t);
+    float c = cos(2.0 * PI * rotation);
+    float s = sin(2.0 * PI * rotation);
+    vec3 tangent = c * t + s * b;
+    vec3 bitangent = -s * t + c * b;
+
+    float occlusion = 0.0;
+    for (int i = 0; i < samples; i++) {
+        // Points on a spiral in the hemisphere, denser close to the surface point
+        float f = (float(i) + 0.5) / float(samples);
+        float angle = 2.4 * float(i);
+        float r = sqrt(1.0 - f);
+        vec3 direction = tangent * r * cos(angle) + bitangent * r * sin(angle) + n * sqrt(f);
+        vec3 samplePosition = p + direction * radius * mix(0.1, 1.0, f * f);
+
+        vec4 clip = projection * vec4(samplePosition, 1.0);
+        vec2 sampleUv = 0.5 * clip.xy / clip.w + 0.5;
+        if (any(lessThan(sampleUv, vec2(0.0))) || any(greaterThan(sampleUv, vec2(1.0)))) {
+            continue;
+        }
+        float sceneDepth = view_position(sampleUv).z;
+        float rangeCheck = smoothstep(0.0, 1.0, radius / abs(p.z - sceneDepth));
+        occlusion += (sceneDepth >= samplePosition.z + 0.02 * radius ? 1.0 : 0.0) * rangeCheck;
+    }
+    return 1.0 - occlusion / float(samples);
+}
+
+// Ground truth ambient occlusion, see "Practical Realtime Strategies for Accurate Indirect Occlusion" by Jimenez et al.
+float gtao(vec3 p, vec3 n, float rotation, float jitter) {
+    vec3 viewVector = normalize(-p);
+    float pixelRadius = radius_in_pixels(p);
+    if (pixelRadius < 1.0) {
+        return 1.0;
+    }
+    float visibility = 0.0;
+    for (int slice = 0; slice < samples; slice++) {
+        float phi = PI * (float(slice) + rotation) / float(samples);
+        vec2 direction = vec2(cos(phi), sin(phi));
+
+        vec3 directionVector = vec3(direction, 0.0);
+        vec3 orthoDirection = directionVector - dot(directionVector, viewVector) * viewVector;
+        vec3 axis = normalize(cross(directionVector, viewVector));
+        vec3 projectedNormal = n - axis * dot(n, axis);
+        float projectedNormalLength = length(projectedNormal);
+        if (projectedNormalLength < 1e-4) {
+            continue;
+        }
+        float cosNormal = clamp(dot(projectedNormal, viewVector) / projectedNormalLength, -1.0, 1.0);
+        float normalAngle = sign(dot(orthoDirection, projectedNormal)) * acos(cosNormal);
+
+        // Find the horizons on both sides of the slice
+        float lowCos0 = cos(normalAngle + HALF_PI);
+        float lowCos1 = cos(normalAngle - HALF_PI);
+        float horizonCos0 = lowCos0;
+        float horizonCos1 = lowCos1;
+        for (int i = 0; i < steps; i++) {
+            float s = (float(i) + jitter) / float(steps);
+            vec2 offset = direction * max(s * s * pixelRadius, float(i) + 1.0) / resolution;
+
+            vec3 delta0 = view_position(uvs + offset) - p;
+            vec3 delta1 = view_position(uvs - offset) - p;
+            float distance0 = length(delta0);
+            float distance1 = length(delta1);
+            float falloff0 = clamp(1.0 - distance0 * distance0 / (radius * radius), 0.0, 1.0);
+            float falloff1 = clamp(1.0 - distance1 * distance1 / (radius * radius), 0.0, 1.0);
+            horizonCos0 = max(horizonCos0, mix(lowCos0, dot(delta0, viewVector) / distance0, falloff0));
+            horizonCos1 = max(horizonCos1, mix(lowCos1, dot(delta1, viewVector) / distance1, falloff1));
+        }
+
+        // Integrate the visible arc projected onto the normal
+        float h0 = normalAngle + clamp(-acos(horizonCos1) - normalAngle, -HALF_PI, HALF_PI);
+        float h1 = normalAngle + clamp(acos(horizonCos0) - normalAngle, -HALF_PI, HALF_PI);
+        float sinNormal = sin(normalAngle);
+        float arc0 = (cosNormal + 2.0 * h0 * sinNormal - cos(2.0 * h0 - normalAngle)) * 0.25;
+        float arc1 = (cosNormal + 2.0 * h1 * sinNormal - cos(2.0 * h1 - normalAngle)) * 0.25;
+        visibility += projectedNormalLength * (arc0 + arc1);
+    }
+    return clamp(visibility / float(samples), 0.0, 1.0);
+}
+
+void main()
+{
+    float depth = sample_depth(uvs);
+    if (depth > 0.99999) {
+        outColor = vec4(1.0, 0.0, 0.0, 1.0);
+        return;
+    }
+    vec3 p = view_position(uvs);
+    vec3 n = view_normal(p, uvs);
+    if (dot(n, p) > 0.0) {
+        n = -n;
+    }
+
+    vec2 pixel = gl_FragCoord.xy;
+    float rotation = noise(pixel);
+    float jitter = noise(pixel.yx + 17.0);
+#ifdef USE_GTAO
+    float visibility = gtao(p, n, rotation, jitter);
+#else
+    float visibility = ssao(p, n, rotation);
+#endif
+    visibility = pow(visibility, intensity);
+
+    // Blend with the reprojected result of the previous frames, unless the history is disoccluded
+    if (useHistory == 1) {
+        vec3 worldPosition = world_pos_from_depth(viewProjectionInverse, depth, uvs);
+        vec4 previousClip = previousViewProjection * vec4(worldPosition, 1.0);
+        vec2 previousUv = 0.5 * previousClip.xy / previousClip.w + 0.5;
+        if (all(greaterThanEqual(previousUv, vec2(0.0))) && all(lessThanEqual(previousUv, vec2(1.0)))) {
+            vec2 history = sample_color(previousUv).xy;
+            if (abs(history.y - previousClip.w) < 0.05 * previousClip.w) {
+                visibility = mix(visibility, history.x, temporalBlend);
+            }
+        }
+    }
+    outColor = vec4(visibility, (projection * vec4(p, 1.0)).w, 0.0, 1.0);
+}
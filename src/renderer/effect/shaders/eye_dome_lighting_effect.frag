@@ -0,0 +1,30 @@
+uniform vec2 resolution;
+uniform mat4 projectionInverse;
+uniform float strength;
+uniform float radius;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+// The logarithm of the distance from the camera along the view direction
+float log_depth(vec2 uv) {
+    vec4 p = projectionInverse * vec4(2.0 * uv - 1.0, 2.0 * sample_depth(uv) - 1.0, 1.0);
+    return log2(max(-p.z / p.w, 0.000001));
+}
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+    float center = log_depth(uvs);
+
+    // Sum how much closer the neighbouring pixels in eight directions are
+    float response = 0.0;
+    for (int i = 0; i < 8; i++) {
+        float angle = float(i) * 0.78539816;
+        vec2 offset = radius * vec2(cos(angle), sin(angle)) / resolution;
+        response += max(0.0, center - log_depth(uvs + offset));
+    }
+    float shade = exp(-300.0 * strength * response / 8.0);
+    outColor = vec4(shade * color.rgb, color.a);
+}
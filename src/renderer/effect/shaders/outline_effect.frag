@@ -0,0 +1,32 @@
+
+uniform vec2 resolution;
+uniform vec4 outlineColor;
+uniform int outlineWidth;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    if (sample_color(uvs).r > 0.5) {
+        discard;
+    }
+
+    // Dilate the mask by searching for the closest masked pixel within the outline width
+    float width = float(outlineWidth);
+    float min_distance = width + 1.0;
+    for (int y = -outlineWidth; y <= outlineWidth; y++) {
+        for (int x = -outlineWidth; x <= outlineWidth; x++) {
+            float d = length(vec2(x, y));
+            if (d < min_distance && sample_color(uvs + vec2(x, y) / resolution).r > 0.5) {
+                min_distance = d;
+            }
+        }
+    }
+    float alpha = clamp(width + 0.5 - min_distance, 0.0, 1.0);
+    if (alpha <= 0.0) {
+        discard;
+    }
+    color = vec4(outlineColor.rgb, outlineColor.a * alpha);
+}
@@ -0,0 +1,21 @@
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 color;
+
+vec3 encode_srgb(vec3 rgb) {
+    vec3 a = vec3(0.055, 0.055, 0.055);
+    vec3 ap1 = vec3(1.0, 1.0, 1.0) + a;
+    vec3 g = vec3(2.4, 2.4, 2.4);
+    vec3 ginv = 1.0 / g;
+    vec3 select = step(vec3(0.0031308, 0.0031308, 0.0031308), rgb);
+    vec3 lo = rgb * 12.92;
+    vec3 hi = ap1 * pow(rgb, ginv) - a;
+    return mix(lo, hi, select);
+}
+
+void main()
+{
+    color = sample_color(uvs);
+    color.rgb = encode_srgb(clamp(color.rgb, 0.0, 1.0));
+}
@@ -0,0 +1,36 @@
+use crate::renderer::*;
+
+///
+/// Converts the linear colors in a color texture to the sRGB color space, which is the final pass needed when rendering with linear output (see [Context::set_linear_output])
+/// to a texture which is not in the sRGB color space, for example a texture with a higher precision used for post-processing.
+/// Should not be applied when rendering to a texture in the sRGB color space, see [Texture2D::new_empty_srgb], since the graphics hardware already converts the colors.
+///
+#[derive(Clone, Default, Debug)]
+pub struct SrgbEncodingEffect {}
+
+impl SrgbEncodingEffect {
+    ///
+    /// Writes the colors in the given color texture converted to the sRGB color space.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn apply(&self, context: &Context, color_texture: ColorTexture) {
+        apply_effect(
+            context,
+            &format!(
+                "{}\n{}",
+                color_texture.fragment_shader_source(),
+                include_str!("shaders/srgb_encoding_effect.frag")
+            ),
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTest::Always,
+                cull: Cull::Back,
+                ..Default::default()
+            },
+            Viewport::new_at_origin(color_texture.width(), color_texture.height()),
+            |program| {
+                color_texture.use_uniforms(program);
+            },
+        )
+    }
+}
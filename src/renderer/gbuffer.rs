@@ -0,0 +1,331 @@
+//!
+//! The geometry buffer (G-buffer) used by the deferred renderer, see [DeferredPhysicalMaterial].
+//!
+
+use crate::renderer::*;
+
+///
+/// The layout of a [GBuffer], ie. the precision of the layers and which optional layers are stored.
+/// The first layer always contains the albedo and metallic values and the second layer the normal, occlusion and roughness values.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GBufferLayout {
+    /// The precision of all layers. A higher precision gives more accurate normals, for example to avoid banding in the specular highlights of smooth surfaces,
+    /// and emissive colors above 1, at the cost of more memory and bandwidth. Falls back to a lower precision if not supported, see [Context::select_render_precision].
+    pub precision: ColorPrecision,
+    /// Whether or not the emissive color is stored in a layer. If not, the emissive color of the materials is ignored.
+    pub emissive: bool,
+    /// Whether or not the ID of the visible object is stored in a layer, ie. the index of the object plus one encoded as in [IdMaterial::decode] and zero where no object is visible.
+    /// The IDs are read using [GBuffer::read_id_at].
+    pub object_ids: bool,
+}
+
+impl GBufferLayout {
+    ///
+    /// Returns the number of layers of a G-buffer with this layout.
+    ///
+    pub fn layer_count(&self) -> u32 {
+        2 + self.emissive as u32 + self.object_ids as u32
+    }
+
+    ///
+    /// Returns the layer containing the emissive color if the emissive color is stored.
+    ///
+    pub fn emissive_layer(&self) -> Option<u32> {
+        self.emissive.then_some(2)
+    }
+
+    ///
+    /// Returns the layer containing the object IDs if the object IDs are stored.
+    ///
+    pub fn object_id_layer(&self) -> Option<u32> {
+        self.object_ids.then_some(2 + self.emissive as u32)
+    }
+}
+
+impl Default for GBufferLayout {
+    fn default() -> Self {
+        Self {
+            precision: ColorPrecision::U8,
+            emissive: true,
+            object_ids: false,
+        }
+    }
+}
+
+///
+/// The textures containing the geometry information written by the geometry pass of the deferred renderer and read by the lighting pass.
+/// Use [RenderTarget::render_with_gbuffer] to render with a custom [GBufferLayout] and to reuse the textures between frames.
+/// Alternatively, call [GBuffer::geometry_pass] and [GBuffer::lighting_pass] directly to insert custom passes in between,
+/// for example a screen space effect which reads the normals from the [GBuffer::texture] or a deferred decal which modifies the albedo.
+///
+pub struct GBuffer {
+    context: Context,
+    layout: GBufferLayout,
+    precision: ColorPrecision,
+    texture: Texture2DArray,
+    depth_texture: DepthTexture2D,
+    layers: Vec<u32>,
+    viewport: Viewport,
+    visible_layers: RenderLayers,
+}
+
+impl GBuffer {
+    ///
+    /// Creates a new G-buffer with the given size and layout.
+    ///
+    pub fn new(context: &Context, width: u32, height: u32, layout: GBufferLayout) -> Self {
+        let layer_count = layout.layer_count();
+        let precision = context.select_render_precision("G-buffer", layout.precision, false);
+        let texture = match precision {
+            ColorPrecision::U8 => new_texture::<[u8; 4]>(context, width, height, layer_count),
+            ColorPrecision::F16 => new_texture::<[f16; 4]>(context, width, height, layer_count),
+            ColorPrecision::F32 => new_texture::<[f32; 4]>(context, width, height, layer_count),
+        };
+        Self {
+            context: context.clone(),
+            layout,
+            precision,
+            texture,
+            depth_texture: DepthTexture2D::new::<f32>(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            layers: (0..layer_count).collect(),
+            viewport: Viewport::new_at_origin(width, height),
+            visible_layers: RenderLayers::ALL,
+        }
+    }
+
+    ///
+    /// Returns the layout of this G-buffer.
+    ///
+    pub fn layout(&self) -> GBufferLayout {
+        self.layout
+    }
+
+    /// The width of this G-buffer.
+    pub fn width(&self) -> u32 {
+        self.texture.width()
+    }
+
+    /// The height of this G-buffer.
+    pub fn height(&self) -> u32 {
+        self.texture.height()
+    }
+
+    ///
+    /// Returns the texture array containing the layers of this G-buffer, see [GBufferLayout].
+    ///
+    pub fn texture(&self) -> &Texture2DArray {
+        &self.texture
+    }
+
+    ///
+    /// Returns the depth texture written by the geometry pass.
+    ///
+    pub fn depth_texture(&self) -> &DepthTexture2D {
+        &self.depth_texture
+    }
+
+    ///
+    /// Returns all layers of this G-buffer as a [ColorTexture], for example for use in a screen space effect.
+    ///
+    pub fn color_texture(&self) -> ColorTexture<'_> {
+        ColorTexture::Array {
+            texture: &self.texture,
+            layers: &self.layers,
+        }
+    }
+
+    ///
+    /// Sets the layers which are visible in [GBuffer::geometry_pass], see [RenderTarget::with_visible_layers]. All layers are visible by default.
+    ///
+    pub fn set_visible_layers(&mut self, visible_layers: RenderLayers) {
+        self.visible_layers = visible_layers;
+    }
+
+    ///
+    /// Returns the layers which are visible in [GBuffer::geometry_pass].
+    ///
+    pub fn visible_layers(&self) -> RenderLayers {
+        self.visible_layers
+    }
+
+    ///
+    /// The first stage of a deferred render call, which renders the geometry information of the objects with a [MaterialType::Deferred] material into this G-buffer.
+    /// Other objects and objects outside the camera frustum or the visible layers, see [GBuffer::set_visible_layers], are ignored. The G-buffer is resized to the size of the camera viewport if needed.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn geometry_pass(
+        &mut self,
+        camera: &Camera,
+        objects: impl IntoIterator<Item = impl Object>,
+        lights: &[&dyn Light],
+    ) {
+        let mut objects = objects
+            .into_iter()
+            .enumerate()
+            .filter(|(_, o)| {
+                o.material_type() == MaterialType::Deferred
+                    && self.visible_layers.intersects(o.layers())
+                    && camera.in_frustum(&o.aabb())
+            })
+            .collect::<Vec<_>>();
+        self.render_geometry(camera, &mut objects, lights);
+    }
+
+    ///
+    /// The second stage of a deferred render call, which applies lighting based on the geometry information in this G-buffer.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn lighting_pass(&self, camera: &Camera, lights: &[&dyn Light]) {
+        DeferredPhysicalMaterial::lighting_pass_with_layout(
+            &self.context,
+            camera,
+            self.color_texture(),
+            DepthTexture::Single(&self.depth_texture),
+            self.layout,
+            lights,
+        )
+    }
+
+    ///
+    /// Returns the ID of the object visible at the given pixel in the latest render with this G-buffer, ie. the index of the object
+    /// in the list of objects given to for example [RenderTarget::render_with_gbuffer], or `None` if no object is visible at the pixel.
+    /// Both the deferred and the forward objects are included when rendering using [RenderTarget::render_with_gbuffer], but only the deferred objects when using [GBuffer::geometry_pass].
+    /// The pixel is given in the same coordinates as the camera viewport.
+    /// Always returns `None` if the object IDs are not stored, see [GBufferLayout::object_ids].
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn read_id_at(&mut self, pixel: impl Into<PhysicalPoint>) -> Option<u32> {
+        let layer = self.layout.object_id_layer()?;
+        read_id_at(
+            &mut self.texture,
+            layer,
+            self.precision,
+            self.viewport,
+            pixel,
+        )
+    }
+
+    // Returns the texture array, layer, precision and viewport of the object IDs if they are stored, see Context::object_ids
+    pub(crate) fn into_object_ids(self) -> Option<(Texture2DArray, u32, ColorPrecision, Viewport)> {
+        let layer = self.layout.object_id_layer()?;
+        Some((self.texture, layer, self.precision, self.viewport))
+    }
+
+    // Sorts the given deferred objects, each given together with its index used as object ID, in render order and renders them
+    pub(crate) fn render_geometry<T: Object>(
+        &mut self,
+        camera: &Camera,
+        objects: &mut [(usize, T)],
+        lights: &[&dyn Light],
+    ) {
+        let viewport = Viewport::new_at_origin(camera.viewport().width, camera.viewport().height);
+        if self.width() != viewport.width || self.height() != viewport.height {
+            *self = Self::new(&self.context, viewport.width, viewport.height, self.layout);
+        }
+        self.viewport = camera.viewport();
+        let mut geometry_pass_camera = camera.clone();
+        geometry_pass_camera.set_viewport(viewport);
+        objects.sort_by(|a, b| cmp_render_order(&geometry_pass_camera, &a.1, &b.1));
+
+        let surface_layers = &self.layers[..2 + self.layout.emissive as usize];
+        RenderTarget::new(
+            self.texture.as_color_target(surface_layers, None),
+            self.depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
+        .write(|| {
+            for (_, object) in objects.iter() {
+                object.render(&geometry_pass_camera, lights);
+            }
+        });
+
+        if let Some(layer) = self.layout.object_id_layer() {
+            self.texture
+                .as_color_target(&[layer], None)
+                .clear(ClearState::color(0.0, 0.0, 0.0, 0.0));
+            self.render_object_ids(camera, objects);
+        }
+    }
+
+    // Renders the IDs of the given objects, each given together with its index used as object ID, into the object ID layer if the object IDs are stored.
+    // The objects are depth tested against and written to the depth texture, so for example forward objects are correctly ordered with the deferred objects.
+    pub(crate) fn render_object_ids<T: Object>(&mut self, camera: &Camera, objects: &[(usize, T)]) {
+        let Some(layer) = self.layout.object_id_layer() else {
+            return;
+        };
+        let mut geometry_pass_camera = camera.clone();
+        geometry_pass_camera.set_viewport(Viewport::new_at_origin(self.width(), self.height()));
+        RenderTarget::new(
+            self.texture.as_color_target(&[layer], None),
+            self.depth_texture.as_depth_target(),
+        )
+        .write(|| {
+            for (index, object) in objects.iter() {
+                object.render_with_material(
+                    &IdMaterial::new(*index as u32 + 1),
+                    &geometry_pass_camera,
+                    &[],
+                );
+            }
+        });
+    }
+}
+
+// Reads the object ID at the given pixel from the given layer of the texture, which contains the IDs of the objects rendered with the given viewport
+pub(crate) fn read_id_at(
+    texture: &mut Texture2DArray,
+    layer: u32,
+    precision: ColorPrecision,
+    viewport: Viewport,
+    pixel: impl Into<PhysicalPoint>,
+) -> Option<u32> {
+    let pixel = pixel.into();
+    let x = pixel.x as i32 - viewport.x;
+    let y = pixel.y as i32 - viewport.y;
+    if x < 0 || y < 0 || x >= viewport.width as i32 || y >= viewport.height as i32 {
+        return None;
+    }
+    let scissor_box = ScissorBox {
+        x,
+        y,
+        width: 1,
+        height: 1,
+    };
+    let layers = [layer];
+    let target = texture.as_color_target(&layers, None);
+    let pixel = match precision {
+        ColorPrecision::U8 => target.read_partially::<[u8; 4]>(scissor_box)[0],
+        _ => target.read_partially::<[f32; 4]>(scissor_box)[0]
+            .map(|value| (value * 255.0).round() as u8),
+    };
+    match IdMaterial::decode(pixel) {
+        0 => None,
+        id => Some(id - 1),
+    }
+}
+
+fn new_texture<T: TextureDataType>(
+    context: &Context,
+    width: u32,
+    height: u32,
+    layer_count: u32,
+) -> Texture2DArray {
+    Texture2DArray::new_empty::<T>(
+        context,
+        width,
+        height,
+        layer_count,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
@@ -32,6 +32,10 @@ mod line2d;
 #[doc(inline)]
 pub use line2d::*;
 
+mod line3d;
+#[doc(inline)]
+pub use line3d::*;
+
 mod outline;
 #[doc(inline)]
 pub use outline::*;
@@ -40,10 +44,54 @@ mod rectangle;
 #[doc(inline)]
 pub use rectangle::*;
 
+mod sprite;
+#[doc(inline)]
+pub use sprite::*;
+
+mod sprite_batch;
+#[doc(inline)]
+pub use sprite_batch::*;
+
 mod circle;
 #[doc(inline)]
 pub use circle::*;
 
+mod cloth;
+#[doc(inline)]
+pub use cloth::*;
+
+mod crowd;
+#[doc(inline)]
+pub use crowd::*;
+
+mod heightfield;
+#[doc(inline)]
+pub use heightfield::*;
+
+mod mesh_processing;
+#[doc(inline)]
+pub use mesh_processing::*;
+
+mod mesh_builder;
+#[doc(inline)]
+pub use mesh_builder::*;
+
+mod topology;
+#[doc(inline)]
+pub use topology::*;
+
+mod surface_plot;
+#[doc(inline)]
+pub use surface_plot::*;
+
+mod marching_cubes;
+#[doc(inline)]
+pub use marching_cubes::*;
+
+mod points;
+#[doc(inline)]
+pub use points::*;
+
 use crate::core::*;
 use crate::renderer::*;
 use crate::OrientedBoundingBox2D;
@@ -105,6 +153,24 @@ pub trait Geometry {
     /// The time parameter should be some continious time, for example the time since start.
     ///
     fn animate(&mut self, _time: f32) {}
+
+    ///
+    /// Returns the number of triangles drawn when rendering this geometry, including all instances.
+    /// Used for reporting the cost of rendering, see [SceneStatistics].
+    /// The default implementation returns 0, which is only correct for geometries that do not draw triangles, for example points,
+    /// so all other geometries should implement this method.
+    ///
+    fn triangle_count(&self) -> usize {
+        0
+    }
+
+    ///
+    /// Returns the layers this geometry belongs to.
+    /// It is only rendered in a render call of a render target if at least one of its layers is visible, see [RenderTarget::with_visible_layers].
+    ///
+    fn layers(&self) -> RenderLayers {
+        RenderLayers::ALL
+    }
 }
 
 impl<T: Geometry + ?Sized> Geometry for &T {
@@ -135,6 +201,14 @@ impl<T: Geometry + ?Sized> Geometry for &T {
     fn obb(&self) -> OrientedBoundingBox2D {
         (*self).obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        (*self).triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        (*self).layers()
+    }
 }
 
 impl<T: Geometry + ?Sized> Geometry for &mut T {
@@ -165,6 +239,14 @@ impl<T: Geometry + ?Sized> Geometry for &mut T {
     fn obb(&self) -> OrientedBoundingBox2D {
         (**self).obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        (**self).triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        (**self).layers()
+    }
 }
 
 impl<T: Geometry> Geometry for Box<T> {
@@ -201,6 +283,14 @@ impl<T: Geometry> Geometry for Box<T> {
     fn obb(&self) -> OrientedBoundingBox2D {
         self.as_ref().obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.as_ref().triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.as_ref().layers()
+    }
 }
 
 impl<T: Geometry> Geometry for std::rc::Rc<T> {
@@ -237,6 +327,14 @@ impl<T: Geometry> Geometry for std::rc::Rc<T> {
     fn obb(&self) -> OrientedBoundingBox2D {
         self.as_ref().obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.as_ref().triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.as_ref().layers()
+    }
 }
 
 impl<T: Geometry> Geometry for std::sync::Arc<T> {
@@ -273,6 +371,14 @@ impl<T: Geometry> Geometry for std::sync::Arc<T> {
     fn obb(&self) -> OrientedBoundingBox2D {
         self.as_ref().obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.as_ref().triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.as_ref().layers()
+    }
 }
 
 impl<T: Geometry> Geometry for std::cell::RefCell<T> {
@@ -309,6 +415,14 @@ impl<T: Geometry> Geometry for std::cell::RefCell<T> {
     fn obb(&self) -> OrientedBoundingBox2D {
         self.borrow().obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.borrow().triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.borrow().layers()
+    }
 }
 
 impl<T: Geometry> Geometry for std::sync::RwLock<T> {
@@ -347,6 +461,14 @@ impl<T: Geometry> Geometry for std::sync::RwLock<T> {
     fn obb(&self) -> OrientedBoundingBox2D {
         self.read().unwrap().obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.read().unwrap().triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.read().unwrap().layers()
+    }
 }
 
 struct BaseMesh {
@@ -356,6 +478,14 @@ struct BaseMesh {
     tangents: Option<VertexBuffer>,
     uvs: Option<VertexBuffer>,
     colors: Option<VertexBuffer>,
+    custom_attributes: Vec<CustomAttribute>,
+}
+
+struct CustomAttribute {
+    name: String,
+    buffer: VertexBuffer,
+    glsl_type: &'static str,
+    flat: bool,
 }
 
 impl BaseMesh {
@@ -392,9 +522,84 @@ impl BaseMesh {
                 .colors
                 .as_ref()
                 .map(|data| VertexBuffer::new_with_data(context, data)),
+            custom_attributes: Vec::new(),
+        }
+    }
+
+    pub fn set_custom_attribute<T: BufferDataType>(
+        &mut self,
+        context: &Context,
+        name: &str,
+        data: &[T],
+    ) {
+        let vertex_count = self.positions.vertex_count() as usize;
+        if data.len() != vertex_count {
+            panic!(
+                "the vertex attribute {} has {} values but the mesh has {} vertices",
+                name,
+                data.len(),
+                vertex_count
+            );
+        }
+        let components = T::size().clamp(1, 4) as usize - 1;
+        let (glsl_type, flat) = match T::data_type() {
+            crate::context::FLOAT | crate::context::HALF_FLOAT => {
+                (["float", "vec2", "vec3", "vec4"][components], false)
+            }
+            crate::context::UNSIGNED_BYTE
+            | crate::context::UNSIGNED_SHORT
+            | crate::context::UNSIGNED_INT => {
+                (["uint", "uvec2", "uvec3", "uvec4"][components], true)
+            }
+            _ => (["int", "ivec2", "ivec3", "ivec4"][components], true),
+        };
+        let attribute = CustomAttribute {
+            name: name.to_owned(),
+            buffer: VertexBuffer::new_with_data(context, data),
+            glsl_type,
+            flat,
+        };
+        if let Some(existing) = self.custom_attributes.iter_mut().find(|a| a.name == name) {
+            *existing = attribute;
+        } else {
+            self.custom_attributes.push(attribute);
         }
     }
 
+    pub fn remove_custom_attribute(&mut self, name: &str) -> bool {
+        let count = self.custom_attributes.len();
+        self.custom_attributes.retain(|a| a.name != name);
+        self.custom_attributes.len() != count
+    }
+
+    pub fn custom_attribute_names(&self) -> Vec<&str> {
+        self.custom_attributes
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect()
+    }
+
+    // The attribute is named `<name>_vertex` in the vertex shader and `<name>` in the fragment shader
+    pub fn custom_attributes_source(&self) -> String {
+        if self.custom_attributes.is_empty() {
+            return String::new();
+        }
+        let mut declarations = String::new();
+        let mut assignments = String::new();
+        for attribute in self.custom_attributes.iter() {
+            let flat = if attribute.flat { "flat " } else { "" };
+            declarations.push_str(&format!(
+                "in {1} {0}_vertex;\n{2}out {1} {0};\n",
+                attribute.name, attribute.glsl_type, flat
+            ));
+            assignments.push_str(&format!("    {0} = {0}_vertex;\n", attribute.name));
+        }
+        format!(
+            "#define USE_CUSTOM_ATTRIBUTES\n{}void assign_custom_attributes()\n{{\n{}}}\n",
+            declarations, assignments
+        )
+    }
+
     pub fn draw(
         &self,
         program: &Program,
@@ -414,6 +619,54 @@ impl BaseMesh {
         }
     }
 
+    pub fn draw_range(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        camera: &Camera,
+        attributes: FragmentAttributes,
+        triangles: std::ops::Range<u32>,
+    ) {
+        let triangles = triangles.start.min(self.triangle_count() as u32)
+            ..triangles.end.min(self.triangle_count() as u32);
+        if triangles.is_empty() {
+            return;
+        }
+        self.use_attributes(program, attributes);
+        let first = 3 * triangles.start;
+        let count = 3 * (triangles.end - triangles.start);
+        if let Some(index_buffer) = &self.indices {
+            program.draw_subset_of_elements(
+                render_states,
+                camera.viewport(),
+                index_buffer,
+                first,
+                count,
+            )
+        } else {
+            program.draw_subset_of_arrays(render_states, camera.viewport(), first, count)
+        }
+    }
+
+    pub fn draw_indirect(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        camera: &Camera,
+        attributes: FragmentAttributes,
+        commands: &DrawIndirectBuffer,
+    ) {
+        self.use_attributes(program, attributes);
+        program.multi_draw_elements_indirect(
+            render_states,
+            camera.viewport(),
+            self.indices
+                .as_ref()
+                .expect("indirect drawing requires a mesh with indices"),
+            commands,
+        )
+    }
+
     pub fn draw_instanced(
         &self,
         program: &Program,
@@ -441,6 +694,13 @@ impl BaseMesh {
         }
     }
 
+    pub fn triangle_count(&self) -> usize {
+        self.indices
+            .as_ref()
+            .map(|indices| indices.triangle_count())
+            .unwrap_or(self.positions.vertex_count() as usize / 3)
+    }
+
     fn use_attributes(&self, program: &Program, attributes: FragmentAttributes) {
         program.use_vertex_attribute("position", &self.positions);
 
@@ -480,6 +740,14 @@ impl BaseMesh {
         if let Some(colors) = &self.colors {
             program.use_vertex_attribute("color", colors);
         }
+
+        for attribute in self.custom_attributes.iter() {
+            let name = format!("{}_vertex", attribute.name);
+            // The attribute is removed by the shader compiler if the fragment shader does not use it
+            if program.requires_attribute(&name) {
+                program.use_vertex_attribute(&name, &attribute.buffer);
+            }
+        }
     }
 }
 
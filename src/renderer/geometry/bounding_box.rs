@@ -108,4 +108,8 @@ impl Geometry for BoundingBox {
         self.mesh
             .render_with_post_material(material, camera, lights, color_texture, depth_texture)
     }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
 }
@@ -85,6 +85,10 @@ impl Geometry for Circle {
             (center + vec2(self.radius, self.radius)).extend(0.0),
         ])
     }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
 }
 
 impl<'a> IntoIterator for &'a Circle {
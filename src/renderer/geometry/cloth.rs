@@ -0,0 +1,325 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A collider which the vertices of a [Cloth] cannot penetrate.
+///
+#[derive(Clone, Copy, Debug)]
+pub enum ClothCollider {
+    /// A sphere with the given center and radius.
+    Sphere {
+        /// The center of the sphere.
+        center: Vec3,
+        /// The radius of the sphere.
+        radius: f32,
+    },
+    /// An infinite plane through the given point, the cloth is kept on the side of the plane the normal points to.
+    Plane {
+        /// A point on the plane.
+        point: Vec3,
+        /// The normal of the plane.
+        normal: Vec3,
+    },
+}
+
+///
+/// A triangle mesh [Geometry] which is simulated as a piece of cloth, for example a flag, a curtain or a cape.
+/// The simulation uses position based dynamics where each edge in the mesh is a distance constraint.
+/// Call [Cloth::step] each frame to advance the simulation, which also updates the vertex positions and normals on the GPU.
+///
+/// The cloth provides positions, normals and, if given in the constructor, uv coordinates, but no tangents.
+///
+pub struct Cloth {
+    mesh: Mesh,
+    positions: Vec<Vec3>,
+    previous_positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    inverse_masses: Vec<f32>,
+    indices: Vec<u32>,
+    constraints: Vec<(usize, usize, f32)>,
+    aabb: AxisAlignedBoundingBox,
+    /// The gravity applied to the cloth.
+    pub gravity: Vec3,
+    /// The wind velocity, the wind pushes the cloth in the direction of the wind depending on the angle between the wind and the cloth.
+    pub wind: Vec3,
+    /// A value between 0 and 1 which defines how much of the velocity is removed each step.
+    pub damping: f32,
+    /// The number of times the constraints are solved each step, higher values gives a stiffer cloth.
+    pub iterations: u32,
+    /// The colliders that the cloth cannot penetrate.
+    pub colliders: Vec<ClothCollider>,
+}
+
+impl Cloth {
+    ///
+    /// Creates a new cloth from the given triangle mesh where the positions are given in world space.
+    /// Each edge of the mesh is used as a distance constraint with the rest length equal to the length of the edge in the given mesh.
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CpuMesh) -> Self {
+        let positions = cpu_mesh.positions.to_f32();
+        let indices: Vec<u32> = match &cpu_mesh.indices {
+            Indices::U8(ind) => ind.iter().map(|i| *i as u32).collect(),
+            Indices::U16(ind) => ind.iter().map(|i| *i as u32).collect(),
+            Indices::U32(ind) => ind.clone(),
+            Indices::None => (0..positions.len() as u32).collect(),
+        };
+
+        let mut edges = std::collections::HashSet::new();
+        for triangle in indices.chunks_exact(3) {
+            for (a, b) in [
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                edges.insert((a.min(b) as usize, a.max(b) as usize));
+            }
+        }
+        let constraints = edges
+            .into_iter()
+            .map(|(a, b)| (a, b, positions[a].distance(positions[b])))
+            .collect();
+
+        let normals = compute_normals(&positions, &indices);
+        let mesh = Mesh::new(
+            context,
+            &CpuMesh {
+                positions: Positions::F32(positions.clone()),
+                indices: Indices::U32(indices.clone()),
+                normals: Some(normals.clone()),
+                uvs: cpu_mesh.uvs.clone(),
+                colors: cpu_mesh.colors.clone(),
+                ..Default::default()
+            },
+        );
+        Self {
+            mesh,
+            aabb: AxisAlignedBoundingBox::new_with_positions(&positions),
+            previous_positions: positions.clone(),
+            inverse_masses: vec![1.0; positions.len()],
+            positions,
+            normals,
+            indices,
+            constraints,
+            gravity: vec3(0.0, -9.82, 0.0),
+            wind: vec3(0.0, 0.0, 0.0),
+            damping: 0.01,
+            iterations: 10,
+            colliders: Vec::new(),
+        }
+    }
+
+    ///
+    /// Creates a new rectangular cloth in the xy-plane with the given size and number of vertices in each direction, centered at the origin.
+    /// The cloth has uv coordinates from `(0, 0)` in the top left corner to `(1, 1)` in the bottom right corner.
+    ///
+    pub fn new_grid(context: &Context, width: f32, height: f32, columns: u32, rows: u32) -> Self {
+        let columns = columns.max(2);
+        let rows = rows.max(2);
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        for r in 0..rows {
+            for c in 0..columns {
+                let u = c as f32 / (columns - 1) as f32;
+                let v = r as f32 / (rows - 1) as f32;
+                positions.push(vec3((u - 0.5) * width, (0.5 - v) * height, 0.0));
+                uvs.push(vec2(u, v));
+            }
+        }
+        let mut indices = Vec::new();
+        for r in 0..rows - 1 {
+            for c in 0..columns - 1 {
+                let i = r * columns + c;
+                indices.extend_from_slice(&[
+                    i,
+                    i + columns,
+                    i + 1,
+                    i + 1,
+                    i + columns,
+                    i + columns + 1,
+                ]);
+            }
+        }
+        Self::new(
+            context,
+            &CpuMesh {
+                positions: Positions::F32(positions),
+                indices: Indices::U32(indices),
+                uvs: Some(uvs),
+                ..Default::default()
+            },
+        )
+    }
+
+    ///
+    /// Returns the current vertex positions.
+    ///
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    ///
+    /// Pins the vertex with the given index, ie. it is not affected by the simulation.
+    /// Use [Cloth::set_pinned_position] to move a pinned vertex.
+    ///
+    pub fn pin(&mut self, vertex: usize) {
+        self.inverse_masses[vertex] = 0.0;
+    }
+
+    ///
+    /// Unpins a vertex pinned with [Cloth::pin].
+    ///
+    pub fn unpin(&mut self, vertex: usize) {
+        self.inverse_masses[vertex] = 1.0;
+    }
+
+    ///
+    /// Returns whether or not the vertex with the given index is pinned.
+    ///
+    pub fn is_pinned(&self, vertex: usize) -> bool {
+        self.inverse_masses[vertex] == 0.0
+    }
+
+    ///
+    /// Pins the vertex with the given index and moves it to the given position, for example to attach the cloth to a moving object.
+    ///
+    pub fn set_pinned_position(&mut self, vertex: usize, position: Vec3) {
+        self.pin(vertex);
+        self.positions[vertex] = position;
+        self.previous_positions[vertex] = position;
+    }
+
+    ///
+    /// Advances the simulation by the given time step in seconds and updates the positions and normals on the GPU.
+    /// To get a stable simulation, the time step should be small, for example by calling this method several times each frame.
+    ///
+    pub fn step(&mut self, time_step: f32) {
+        let time_step2 = time_step * time_step;
+        for i in 0..self.positions.len() {
+            if self.inverse_masses[i] == 0.0 {
+                continue;
+            }
+            let velocity = (self.positions[i] - self.previous_positions[i]) * (1.0 - self.damping);
+            let normal = self.normals[i];
+            let wind = normal * normal.dot(self.wind - velocity / time_step.max(0.000001));
+            self.previous_positions[i] = self.positions[i];
+            self.positions[i] += velocity + (self.gravity + wind) * time_step2;
+        }
+
+        for _ in 0..self.iterations {
+            for &(a, b, rest_length) in self.constraints.iter() {
+                let w = self.inverse_masses[a] + self.inverse_masses[b];
+                if w == 0.0 {
+                    continue;
+                }
+                let delta = self.positions[b] - self.positions[a];
+                let length = delta.magnitude();
+                if length < 0.000001 {
+                    continue;
+                }
+                let correction = delta * ((length - rest_length) / (length * w));
+                self.positions[a] += correction * self.inverse_masses[a];
+                self.positions[b] -= correction * self.inverse_masses[b];
+            }
+            for collider in self.colliders.iter() {
+                for (i, position) in self.positions.iter_mut().enumerate() {
+                    if self.inverse_masses[i] > 0.0 {
+                        *position = collide(collider, *position);
+                    }
+                }
+            }
+        }
+
+        self.normals = compute_normals(&self.positions, &self.indices);
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(&self.positions);
+        let base_mesh = self.mesh.base_mesh_mut();
+        base_mesh.positions.fill(&self.positions);
+        if let Some(normals) = base_mesh.normals.as_mut() {
+            normals.fill(&self.normals);
+        }
+    }
+}
+
+fn collide(collider: &ClothCollider, position: Vec3) -> Vec3 {
+    match *collider {
+        ClothCollider::Sphere { center, radius } => {
+            let d = position - center;
+            let distance = d.magnitude();
+            if distance < radius && distance > 0.000001 {
+                center + d * (radius / distance)
+            } else {
+                position
+            }
+        }
+        ClothCollider::Plane { point, normal } => {
+            let normal = normal.normalize();
+            let distance = (position - point).dot(normal);
+            if distance < 0.0 {
+                position - normal * distance
+            } else {
+                position
+            }
+        }
+    }
+}
+
+fn compute_normals(positions: &[Vec3], indices: &[u32]) -> Vec<Vec3> {
+    let mut normals = vec![vec3(0.0, 0.0, 0.0); positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let (a, b, c) = (
+            triangle[0] as usize,
+            triangle[1] as usize,
+            triangle[2] as usize,
+        );
+        let normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        normals[a] += normal;
+        normals[b] += normal;
+        normals[c] += normal;
+    }
+    for normal in normals.iter_mut() {
+        let length = normal.magnitude();
+        if length > 0.000001 {
+            *normal /= length;
+        }
+    }
+    normals
+}
+
+impl<'a> IntoIterator for &'a Cloth {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Cloth {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+}
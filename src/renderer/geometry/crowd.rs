@@ -0,0 +1,688 @@
+use crate::core::*;
+use crate::renderer::*;
+
+use super::BaseMesh;
+
+///
+/// An animation clip defined by the vertex positions of a mesh at a number of equally spaced points in time.
+/// The clips are baked into a [VertexAnimationTexture] which is used for rendering a [Crowd].
+///
+#[derive(Clone, Debug, Default)]
+pub struct VertexAnimationClip {
+    /// The name of the clip.
+    pub name: Option<String>,
+    /// The number of frames per second.
+    pub frame_rate: f32,
+    /// The vertex positions in local space for each frame.
+    /// Each frame must contain the same number of positions as the mesh the clip is used with.
+    pub frames: Vec<Vec<Vec3>>,
+}
+
+impl VertexAnimationClip {
+    ///
+    /// Bakes a clip by sampling the given pose function at the given frame rate from time zero to the given duration in seconds.
+    /// The pose function returns the vertex positions at the given time,
+    /// for example the result of evaluating the skinning of a skeletal animation on the CPU.
+    ///
+    pub fn bake(
+        name: Option<String>,
+        duration: f32,
+        frame_rate: f32,
+        mut pose: impl FnMut(f32) -> Vec<Vec3>,
+    ) -> Self {
+        let frame_count = ((duration * frame_rate).ceil() as usize).max(1);
+        Self {
+            name,
+            frame_rate,
+            frames: (0..frame_count)
+                .map(|frame| pose(frame as f32 / frame_rate))
+                .collect(),
+        }
+    }
+
+    ///
+    /// Bakes a clip from the given [KeyFrameAnimation] applied to the positions of the given mesh.
+    /// The animation moves the whole mesh rigidly, use [VertexAnimationClip::from_skeletal_animation] to bake the animation of a skinned mesh.
+    ///
+    pub fn from_key_frame_animation(
+        cpu_mesh: &CpuMesh,
+        animation: &KeyFrameAnimation,
+        duration: f32,
+        frame_rate: f32,
+    ) -> Self {
+        let positions = cpu_mesh.positions.to_f32();
+        Self::bake(animation.name.clone(), duration, frame_rate, |time| {
+            let transformation = animation.transformation(time);
+            positions
+                .iter()
+                .map(|p| (transformation * p.extend(1.0)).truncate())
+                .collect()
+        })
+    }
+
+    ///
+    /// Bakes a clip from the animation with the given name of the joints of the given [Skin] applied to the positions of the given mesh.
+    /// The positions are skinned on the CPU for each frame, see [Skin::skin_positions].
+    ///
+    pub fn from_skeletal_animation(
+        cpu_mesh: &CpuMesh,
+        skin: &Skin,
+        animation_name: Option<&str>,
+        duration: f32,
+        frame_rate: f32,
+    ) -> Self {
+        let positions = cpu_mesh.positions.to_f32();
+        Self::bake(
+            animation_name.map(|name| name.to_owned()),
+            duration,
+            frame_rate,
+            |time| {
+                skin.skin_positions(
+                    &positions,
+                    &skin.joint_transformations(animation_name, time),
+                )
+            },
+        )
+    }
+
+    ///
+    /// Returns the number of frames in this clip.
+    ///
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    ///
+    /// Returns the duration of this clip in seconds.
+    ///
+    pub fn duration(&self) -> f32 {
+        self.frames.len() as f32 / self.frame_rate
+    }
+}
+
+///
+/// A joint in a [Skin], which is animated by key frame animations relative to its parent joint.
+///
+#[derive(Clone, Debug)]
+pub struct Joint {
+    /// The name of the joint.
+    pub name: Option<String>,
+    /// The index of the parent joint in [Skin::joints], if any.
+    pub parent: Option<usize>,
+    /// The transformation of the joint relative to its parent joint, or to the mesh if it has no parent.
+    pub transformation: Mat4,
+    /// The transformation from the space of the mesh to the space of the joint in the bind pose.
+    pub inverse_bind_matrix: Mat4,
+    /// The animations of the joint, which are applied on top of the transformation of the joint.
+    /// Animations belonging to the same skeletal animation have the same name.
+    pub animations: Vec<KeyFrameAnimation>,
+}
+
+///
+/// The joints of a skinned mesh and the weights with which each vertex of the mesh follows the joints, for example loaded from the skin of a glTF file.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Skin {
+    /// The joints.
+    pub joints: Vec<Joint>,
+    /// The indices into [Skin::joints] of up to four joints influencing each vertex.
+    pub vertex_joints: Vec<[u32; 4]>,
+    /// The weights of the joints in [Skin::vertex_joints] for each vertex.
+    pub vertex_weights: Vec<Vec4>,
+}
+
+impl Skin {
+    ///
+    /// Returns the transformation of each joint relative to the mesh at the given time of the animation with the given name.
+    /// Joints without an animation with the given name keep their transformation.
+    ///
+    pub fn joint_transformations(&self, animation_name: Option<&str>, time: f32) -> Vec<Mat4> {
+        fn resolve(
+            joints: &[Joint],
+            local: &[Mat4],
+            transformations: &mut [Option<Mat4>],
+            index: usize,
+            depth: usize,
+        ) -> Mat4 {
+            if let Some(transformation) = transformations[index] {
+                return transformation;
+            }
+            assert!(
+                depth <= joints.len(),
+                "the parents of the joints must not form a cycle"
+            );
+            let transformation = match joints[index].parent {
+                Some(parent) => {
+                    resolve(joints, local, transformations, parent, depth + 1) * local[index]
+                }
+                None => local[index],
+            };
+            transformations[index] = Some(transformation);
+            transformation
+        }
+
+        let local = self
+            .joints
+            .iter()
+            .map(|joint| {
+                joint
+                    .animations
+                    .iter()
+                    .find(|a| a.name.as_deref() == animation_name)
+                    .map(|a| joint.transformation * a.transformation(time))
+                    .unwrap_or(joint.transformation)
+            })
+            .collect::<Vec<_>>();
+        let mut transformations = vec![None; self.joints.len()];
+        (0..self.joints.len())
+            .map(|index| resolve(&self.joints, &local, &mut transformations, index, 0))
+            .collect()
+    }
+
+    ///
+    /// Returns the given rest positions of the mesh moved by the joints with the given transformations, see [Skin::joint_transformations].
+    /// Each position is the weighted sum of the position transformed by each of the joints influencing the vertex,
+    /// where the weights are normalized and vertices without weights keep their rest position.
+    ///
+    pub fn skin_positions(&self, positions: &[Vec3], joint_transformations: &[Mat4]) -> Vec<Vec3> {
+        assert_eq!(
+            self.vertex_joints.len(),
+            positions.len(),
+            "the skin must contain joint indices for each vertex in the mesh"
+        );
+        assert_eq!(
+            self.vertex_weights.len(),
+            positions.len(),
+            "the skin must contain joint weights for each vertex in the mesh"
+        );
+        let skinning = self
+            .joints
+            .iter()
+            .zip(joint_transformations.iter())
+            .map(|(joint, transformation)| transformation * joint.inverse_bind_matrix)
+            .collect::<Vec<_>>();
+        positions
+            .iter()
+            .zip(self.vertex_joints.iter().zip(self.vertex_weights.iter()))
+            .map(|(position, (joints, weights))| {
+                let total = weights.x + weights.y + weights.z + weights.w;
+                if total <= 0.0 {
+                    return *position;
+                }
+                let p = position.extend(1.0);
+                let mut skinned = Vec4::zero();
+                for (joint, weight) in joints
+                    .iter()
+                    .zip([weights.x, weights.y, weights.z, weights.w])
+                {
+                    if weight > 0.0 {
+                        skinned += skinning[*joint as usize] * p * (weight / total);
+                    }
+                }
+                skinned.truncate()
+            })
+            .collect()
+    }
+}
+
+struct BakedClip {
+    name: Option<String>,
+    first_frame: u32,
+    frame_count: u32,
+    frame_rate: f32,
+}
+
+///
+/// A set of [VertexAnimationClip]s baked into textures.
+/// Each texel contains the offset of a vertex at a frame from the rest position of that vertex, both for positions and normals.
+///
+pub struct VertexAnimationTexture {
+    positions: Texture2D,
+    normals: Texture2D,
+    vertex_count: u32,
+    clips: Vec<BakedClip>,
+    aabb: AxisAlignedBoundingBox,
+}
+
+impl VertexAnimationTexture {
+    ///
+    /// Bakes the given clips, which must animate the given mesh, into textures.
+    /// The normals for each frame are computed from the baked positions.
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CpuMesh, clips: &[VertexAnimationClip]) -> Self {
+        let rest_positions = cpu_mesh.positions.to_f32();
+        let rest_normals = {
+            let mut mesh = CpuMesh {
+                positions: cpu_mesh.positions.clone(),
+                indices: cpu_mesh.indices.clone(),
+                ..Default::default()
+            };
+            mesh.compute_normals();
+            mesh.normals.unwrap()
+        };
+        let vertex_count = rest_positions.len() as u32;
+        let frame_count: usize = clips.iter().map(|clip| clip.frames.len()).sum();
+
+        let mut baked_clips = Vec::new();
+        let mut position_offsets = Vec::with_capacity(frame_count * vertex_count as usize);
+        let mut normal_offsets = Vec::with_capacity(frame_count * vertex_count as usize);
+        let mut aabb = AxisAlignedBoundingBox::new_with_positions(&rest_positions);
+        for clip in clips {
+            baked_clips.push(BakedClip {
+                name: clip.name.clone(),
+                first_frame: (position_offsets.len() / vertex_count.max(1) as usize) as u32,
+                frame_count: clip.frames.len() as u32,
+                frame_rate: clip.frame_rate,
+            });
+            for frame in clip.frames.iter() {
+                assert_eq!(
+                    frame.len(),
+                    rest_positions.len(),
+                    "each frame must contain a position for each vertex in the mesh"
+                );
+                let mut mesh = CpuMesh {
+                    positions: Positions::F32(frame.clone()),
+                    indices: cpu_mesh.indices.clone(),
+                    ..Default::default()
+                };
+                mesh.compute_normals();
+                aabb.expand_with_aabb(&AxisAlignedBoundingBox::new_with_positions(frame));
+                for (p, rest) in frame.iter().zip(rest_positions.iter()) {
+                    let offset = p - rest;
+                    position_offsets.push([offset.x, offset.y, offset.z, 0.0]);
+                }
+                for (n, rest) in mesh.normals.unwrap().iter().zip(rest_normals.iter()) {
+                    let offset = n - rest;
+                    normal_offsets.push([offset.x, offset.y, offset.z, 0.0]);
+                }
+            }
+        }
+
+        let texel_count = position_offsets.len().max(1) as u32;
+        let width = texel_count.min(4096);
+        let height = (texel_count + width - 1) / width;
+        position_offsets.resize((width * height) as usize, [0.0; 4]);
+        normal_offsets.resize((width * height) as usize, [0.0; 4]);
+        let new_texture = |data: &[[f32; 4]]| {
+            let mut texture = Texture2D::new_empty::<[f32; 4]>(
+                context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            texture.fill(data);
+            texture
+        };
+        Self {
+            positions: new_texture(&position_offsets),
+            normals: new_texture(&normal_offsets),
+            vertex_count,
+            clips: baked_clips,
+            aabb,
+        }
+    }
+
+    ///
+    /// Returns the number of vertices in each frame.
+    ///
+    pub fn vertex_count(&self) -> u32 {
+        self.vertex_count
+    }
+
+    ///
+    /// Returns the number of baked clips.
+    ///
+    pub fn clip_count(&self) -> usize {
+        self.clips.len()
+    }
+
+    ///
+    /// Returns the index of the clip with the given name, if any.
+    ///
+    pub fn clip_index(&self, name: &str) -> Option<usize> {
+        self.clips
+            .iter()
+            .position(|clip| clip.name.as_deref() == Some(name))
+    }
+
+    ///
+    /// Returns the duration in seconds of the clip with the given index.
+    ///
+    pub fn clip_duration(&self, clip: usize) -> f32 {
+        self.clips[clip].frame_count as f32 / self.clips[clip].frame_rate
+    }
+
+    ///
+    /// Returns the bounding box containing the mesh in all of the frames of all of the clips in local space.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    ///
+    /// Returns the texture memory used by the baked clips in bytes.
+    ///
+    pub fn memory_size(&self) -> usize {
+        self.positions.memory_size() + self.normals.memory_size()
+    }
+}
+
+///
+/// Defines the attributes for one instance in a [Crowd].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct CrowdInstance {
+    /// The local to world transformation applied to the instance.
+    pub transformation: Mat4,
+    /// The index of the clip played by the instance, see [VertexAnimationTexture::clip_index].
+    pub clip: usize,
+    /// The time in seconds added to the animation time, use different offsets to avoid that all instances move in sync.
+    pub time_offset: f32,
+    /// The playback speed of the clip, where 1.0 is the speed the clip was baked with.
+    pub speed: f32,
+}
+
+impl Default for CrowdInstance {
+    fn default() -> Self {
+        Self {
+            transformation: Mat4::identity(),
+            clip: 0,
+            time_offset: 0.0,
+            speed: 1.0,
+        }
+    }
+}
+
+///
+/// Renders a large number of animated instances of the same mesh, for example a crowd of people or a herd of animals.
+/// The animations are baked into a [VertexAnimationTexture] and each instance samples its own clip at its own time in the vertex shader,
+/// so there is no skinning or other per-instance animation cost on the CPU.
+/// Call [Geometry::animate] each frame to advance the animation time.
+///
+pub struct Crowd {
+    context: Context,
+    base_mesh: BaseMesh,
+    animation_texture: VertexAnimationTexture,
+    instances: Vec<CrowdInstance>,
+    instance_buffers: [InstanceBuffer; 4],
+    aabb: AxisAlignedBoundingBox,
+    time: f32,
+}
+
+impl Crowd {
+    ///
+    /// Creates a new crowd of the given mesh where the given clips are baked into a [VertexAnimationTexture].
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        clips: &[VertexAnimationClip],
+        instances: &[CrowdInstance],
+    ) -> Self {
+        let mut crowd = Self {
+            context: context.clone(),
+            base_mesh: BaseMesh::new(context, cpu_mesh),
+            animation_texture: VertexAnimationTexture::new(context, cpu_mesh, clips),
+            instances: Vec::new(),
+            instance_buffers: [
+                InstanceBuffer::new(context),
+                InstanceBuffer::new(context),
+                InstanceBuffer::new(context),
+                InstanceBuffer::new(context),
+            ],
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            time: 0.0,
+        };
+        crowd.set_instances(instances);
+        crowd
+    }
+
+    ///
+    /// Returns the baked animations.
+    ///
+    pub fn animation_texture(&self) -> &VertexAnimationTexture {
+        &self.animation_texture
+    }
+
+    ///
+    /// Returns the instances.
+    ///
+    pub fn instances(&self) -> &[CrowdInstance] {
+        &self.instances
+    }
+
+    ///
+    /// Updates the instances.
+    ///
+    pub fn set_instances(&mut self, instances: &[CrowdInstance]) {
+        let mut row1 = Vec::new();
+        let mut row2 = Vec::new();
+        let mut row3 = Vec::new();
+        let mut animation = Vec::new();
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for instance in instances {
+            let clip = &self.animation_texture.clips[instance.clip];
+            row1.push(instance.transformation.row(0));
+            row2.push(instance.transformation.row(1));
+            row3.push(instance.transformation.row(2));
+            animation.push(vec4(
+                clip.first_frame as f32,
+                clip.frame_count as f32,
+                clip.frame_rate * instance.speed,
+                instance.time_offset * clip.frame_rate,
+            ));
+            let mut instance_aabb = self.animation_texture.aabb;
+            instance_aabb.transform(&instance.transformation);
+            aabb.expand_with_aabb(&instance_aabb);
+        }
+        self.instance_buffers[0].fill(&row1);
+        self.instance_buffers[1].fill(&row2);
+        self.instance_buffers[2].fill(&row3);
+        self.instance_buffers[3].fill(&animation);
+        self.instances = instances.to_vec();
+        self.aabb = aabb;
+    }
+
+    fn draw(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        camera: &Camera,
+        attributes: FragmentAttributes,
+    ) {
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform("modelMatrix", Mat4::identity());
+        program.use_uniform("animationTime", self.time);
+        program.use_uniform(
+            "animationVertexCount",
+            self.animation_texture.vertex_count as i32,
+        );
+        program.use_texture("animationPositions", &self.animation_texture.positions);
+        if attributes.normal {
+            program.use_texture("animationNormals", &self.animation_texture.normals);
+        }
+        for (name, buffer) in ["row1", "row2", "row3", "instance_animation"]
+            .iter()
+            .zip(self.instance_buffers.iter())
+        {
+            program.use_instance_attribute(name, buffer);
+        }
+        self.base_mesh.draw_instanced(
+            program,
+            render_states,
+            camera,
+            attributes,
+            self.instances.len() as u32,
+        );
+    }
+
+    fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
+        format!(
+            "#define USE_INSTANCE_TRANSFORMS\n#define USE_VERTEX_ANIMATION\n{}{}{}{}{}{}{}",
+            if required_attributes.normal {
+                "#define USE_NORMALS\n"
+            } else {
+                ""
+            },
+            if required_attributes.tangents {
+                "#define USE_TANGENTS\n"
+            } else {
+                ""
+            },
+            if required_attributes.uv {
+                "#define USE_UVS\n"
+            } else {
+                ""
+            },
+            if self.base_mesh.colors.is_some() {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            if required_attributes.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            include_str!("../../core/shared.frag"),
+            include_str!("shaders/mesh.vert"),
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a Crowd {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Crowd {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.base_mesh.triangle_count() * self.instances.len()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if self.instances.is_empty() {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights);
+        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
+        self.context
+            .program(vertex_shader_source, fragment_shader.source, |program| {
+                material.use_uniforms(program, camera, lights);
+                self.draw(
+                    program,
+                    material.render_states(),
+                    camera,
+                    fragment_shader.attributes,
+                );
+            })
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if self.instances.is_empty() {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
+        self.context
+            .program(vertex_shader_source, fragment_shader.source, |program| {
+                material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                self.draw(
+                    program,
+                    material.render_states(),
+                    camera,
+                    fragment_shader.attributes,
+                );
+            })
+            .expect("Failed compiling shader");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn joint(parent: Option<usize>, transformation: Mat4) -> Joint {
+        Joint {
+            name: None,
+            parent,
+            transformation,
+            inverse_bind_matrix: Mat4::identity(),
+            animations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn joint_transformations_are_relative_to_the_parent_joint() {
+        let skin = Skin {
+            joints: vec![
+                joint(Some(1), Mat4::from_translation(vec3(0.0, 1.0, 0.0))),
+                joint(None, Mat4::from_translation(vec3(2.0, 0.0, 0.0))),
+            ],
+            ..Default::default()
+        };
+        let transformations = skin.joint_transformations(None, 0.0);
+        assert_eq!(
+            transformations[0],
+            Mat4::from_translation(vec3(2.0, 1.0, 0.0))
+        );
+        assert_eq!(
+            transformations[1],
+            Mat4::from_translation(vec3(2.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn skinned_positions_blend_the_joint_transformations() {
+        let mut moved = joint(None, Mat4::from_translation(vec3(4.0, 0.0, 0.0)));
+        moved.inverse_bind_matrix = Mat4::from_translation(vec3(-2.0, 0.0, 0.0));
+        let skin = Skin {
+            joints: vec![joint(None, Mat4::identity()), moved],
+            vertex_joints: vec![[0, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 0]],
+            vertex_weights: vec![
+                vec4(1.0, 0.0, 0.0, 0.0),
+                vec4(1.0, 0.0, 0.0, 0.0),
+                vec4(1.0, 3.0, 0.0, 0.0),
+                Vec4::zero(),
+            ],
+        };
+        let positions = vec![vec3(1.0, 0.0, 0.0); 4];
+        let skinned = skin.skin_positions(&positions, &skin.joint_transformations(None, 0.0));
+        assert_eq!(skinned[0], vec3(1.0, 0.0, 0.0));
+        assert_eq!(skinned[1], vec3(3.0, 0.0, 0.0));
+        assert_eq!(skinned[2], vec3(2.5, 0.0, 0.0));
+        assert_eq!(skinned[3], vec3(1.0, 0.0, 0.0));
+    }
+}
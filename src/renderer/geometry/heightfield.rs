@@ -0,0 +1,371 @@
+use crate::core::*;
+use crate::renderer::*;
+
+const GRID_RESOLUTION: u32 = 32;
+
+///
+/// A heightfield [Geometry] where the vertices of a flat grid are displaced in the vertex shader by the heights in a height map texture.
+/// Since the heights are only stored in a texture, the heightfield can be edited dynamically by filling the texture with new data (see [Heightfield::set_heights])
+/// or by rendering into it (see [Heightfield::height_map_mut]) instead of rebuilding a [CpuMesh].
+///
+/// The heightfield is rendered as a quadtree of grid patches with a level of detail that depends on the distance to the camera.
+/// To avoid popping and cracks between patches of different levels of detail, the vertices are smoothly morphed towards the coarser level
+/// when approaching the end of the range of a level.
+///
+/// In local space, the heightfield is centered at the origin in the xz-plane and the heights are in the direction of the y-axis.
+/// The heightfield provides positions, normals, tangents and uv coordinates from `(0, 0)` to `(1, 1)` across the heightfield.
+///
+pub struct Heightfield {
+    context: Context,
+    height_map: Texture2D,
+    grid_positions: VertexBuffer,
+    index_buffer: ElementBuffer,
+    size: Vec2,
+    height_scale: f32,
+    height_range: (f32, f32),
+    transformation: Mat4,
+    drawn_patches: std::sync::atomic::AtomicUsize,
+    /// The number of levels of detail, where the finest level is used closest to the camera and each level halves the resolution of the previous level.
+    pub lod_levels: u32,
+    /// The distance from the camera where the finest level of detail ends, each of the following levels covers twice the distance of the previous level.
+    pub lod_distance: f32,
+    /// A value between 0 and 1 which defines at which fraction of the range of a level of detail the morphing towards the next level starts.
+    pub morph_start: f32,
+}
+
+impl Heightfield {
+    ///
+    /// Creates a new heightfield with the given size in the x and z directions.
+    /// The heights are given row by row, with `columns * rows` values between 0 and 1, which are multiplied by the height scale.
+    ///
+    pub fn new(
+        context: &Context,
+        size: Vec2,
+        height_scale: f32,
+        columns: u32,
+        rows: u32,
+        heights: &[f32],
+    ) -> Self {
+        let height_map = Texture2D::new_empty::<f32>(
+            context,
+            columns,
+            rows,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut grid_positions = Vec::new();
+        for z in 0..=GRID_RESOLUTION {
+            for x in 0..=GRID_RESOLUTION {
+                grid_positions.push(vec2(
+                    x as f32 / GRID_RESOLUTION as f32,
+                    z as f32 / GRID_RESOLUTION as f32,
+                ));
+            }
+        }
+        let mut indices = Vec::new();
+        let stride = GRID_RESOLUTION + 1;
+        for z in 0..GRID_RESOLUTION {
+            for x in 0..GRID_RESOLUTION {
+                let i = z * stride + x;
+                indices.extend_from_slice(&[
+                    i,
+                    i + stride,
+                    i + 1,
+                    i + 1,
+                    i + stride,
+                    i + stride + 1,
+                ]);
+            }
+        }
+        let mut heightfield = Self {
+            context: context.clone(),
+            height_map,
+            grid_positions: VertexBuffer::new_with_data(context, &grid_positions),
+            index_buffer: ElementBuffer::new_with_data(context, &indices),
+            size,
+            height_scale,
+            height_range: (0.0, height_scale),
+            transformation: Mat4::identity(),
+            drawn_patches: std::sync::atomic::AtomicUsize::new(0),
+            lod_levels: 6,
+            lod_distance: 0.05 * size.x.max(size.y),
+            morph_start: 0.7,
+        };
+        heightfield.set_heights(heights);
+        heightfield
+    }
+
+    ///
+    /// Replaces the heights, which are given row by row as values between 0 and 1.
+    /// The number of heights must be equal to the number of columns times the number of rows given at construction.
+    ///
+    pub fn set_heights(&mut self, heights: &[f32]) {
+        self.height_map.fill(heights);
+        let (min, max) = heights.iter().fold((f32::MAX, f32::MIN), |(min, max), h| {
+            (min.min(*h), max.max(*h))
+        });
+        if min <= max {
+            self.set_height_range(min * self.height_scale, max * self.height_scale);
+        }
+    }
+
+    ///
+    /// Returns the height map texture containing a height between 0 and 1 in the red channel.
+    ///
+    pub fn height_map(&self) -> &Texture2D {
+        &self.height_map
+    }
+
+    ///
+    /// Returns a mutable reference to the height map texture, for example to render new heights into it using [Texture2D::as_color_target].
+    /// Since the heights are not known on the CPU after editing the texture this way, call [Heightfield::set_height_range] to make sure the heightfield is not culled wrongly.
+    ///
+    pub fn height_map_mut(&mut self) -> &mut Texture2D {
+        &mut self.height_map
+    }
+
+    ///
+    /// Sets the minimum and maximum height in local space, used for culling and selecting the level of detail.
+    /// This is calculated automatically when calling [Heightfield::set_heights].
+    ///
+    pub fn set_height_range(&mut self, min: f32, max: f32) {
+        self.height_range = (min, max);
+    }
+
+    ///
+    /// Returns the size of the heightfield in the x and z directions.
+    ///
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    ///
+    /// Returns the value which the heights between 0 and 1 are multiplied by.
+    ///
+    pub fn height_scale(&self) -> f32 {
+        self.height_scale
+    }
+
+    ///
+    /// Sets the value which the heights between 0 and 1 are multiplied by.
+    ///
+    pub fn set_height_scale(&mut self, height_scale: f32) {
+        let factor = if self.height_scale.abs() > 0.000001 {
+            height_scale / self.height_scale
+        } else {
+            0.0
+        };
+        self.height_range = (self.height_range.0 * factor, self.height_range.1 * factor);
+        self.height_scale = height_scale;
+    }
+
+    ///
+    /// Returns the local to world transformation applied to this heightfield.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to this heightfield.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    fn patch_aabb(&self, offset: Vec2, patch_size: f32) -> AxisAlignedBoundingBox {
+        let min = offset - vec2(0.5, 0.5);
+        AxisAlignedBoundingBox::new_with_positions(&[
+            vec3(
+                min.x * self.size.x,
+                self.height_range.0,
+                min.y * self.size.y,
+            ),
+            vec3(
+                (min.x + patch_size) * self.size.x,
+                self.height_range.1,
+                (min.y + patch_size) * self.size.y,
+            ),
+        ])
+    }
+
+    fn lod_range(&self, level: u32) -> f32 {
+        self.lod_distance * 2.0f32.powi(level as i32)
+    }
+
+    ///
+    /// Selects the patches to render as (offset, size, level of detail) where the offset and size are in uv coordinates.
+    ///
+    fn select_patches(
+        &self,
+        local_eye: Vec3,
+        offset: Vec2,
+        patch_size: f32,
+        level: u32,
+        patches: &mut Vec<(Vec2, f32, u32)>,
+    ) {
+        let aabb = self.patch_aabb(offset, patch_size);
+        let closest = vec3(
+            local_eye.x.clamp(aabb.min().x, aabb.max().x),
+            local_eye.y.clamp(aabb.min().y, aabb.max().y),
+            local_eye.z.clamp(aabb.min().z, aabb.max().z),
+        );
+        if level == 0 || closest.distance(local_eye) > self.lod_range(level - 1) {
+            patches.push((offset, patch_size, level));
+        } else {
+            let half = 0.5 * patch_size;
+            for child in [
+                offset,
+                offset + vec2(half, 0.0),
+                offset + vec2(0.0, half),
+                offset + vec2(half, half),
+            ] {
+                self.select_patches(local_eye, child, half, level - 1, patches);
+            }
+        }
+    }
+
+    fn draw(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        camera: &Camera,
+        attributes: FragmentAttributes,
+    ) {
+        let inverse = if let Some(inverse) = self.transformation.invert() {
+            inverse
+        } else {
+            // determinant is float zero
+            return;
+        };
+        if attributes.normal {
+            program.use_uniform("normalMatrix", inverse.transpose());
+        }
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_texture("heightMap", &self.height_map);
+        program.use_uniform("heightScale", self.height_scale);
+        program.use_uniform("size", self.size);
+        program.use_uniform("gridResolution", GRID_RESOLUTION as f32);
+        let local_eye = (inverse * camera.position().extend(1.0)).truncate();
+        program.use_uniform("localEye", local_eye);
+        program.use_vertex_attribute("grid_position", &self.grid_positions);
+
+        let levels = self.lod_levels.max(1);
+        let mut patches = Vec::new();
+        self.select_patches(local_eye, vec2(0.0, 0.0), 1.0, levels - 1, &mut patches);
+        let mut drawn_patches = 0;
+        for (offset, patch_size, level) in patches {
+            let mut aabb = self.patch_aabb(offset, patch_size);
+            aabb.transform(&self.transformation);
+            if !camera.in_frustum(&aabb) {
+                continue;
+            }
+            let range = self.lod_range(level);
+            program.use_uniform("patchOffset", offset);
+            program.use_uniform("patchSize", patch_size);
+            program.use_uniform("morphRange", vec2(self.morph_start * range, range));
+            program.draw_elements(render_states, camera.viewport(), &self.index_buffer);
+            drawn_patches += 1;
+        }
+        self.drawn_patches
+            .store(drawn_patches, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
+        format!(
+            "{}{}{}{}",
+            if required_attributes.normal {
+                "#define USE_NORMALS\n"
+            } else {
+                ""
+            },
+            if required_attributes.tangents {
+                "#define USE_TANGENTS\n"
+            } else {
+                ""
+            },
+            if required_attributes.uv {
+                "#define USE_UVS\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/heightfield.vert"),
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a Heightfield {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Heightfield {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.patch_aabb(vec2(0.0, 0.0), 1.0);
+        aabb.transform(&self.transformation);
+        aabb
+    }
+
+    fn triangle_count(&self) -> usize {
+        // The number of patches depends on the camera, so the count is from the latest render call
+        self.index_buffer.triangle_count()
+            * self
+                .drawn_patches
+                .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        let fragment_shader = material.fragment_shader(lights);
+        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
+        self.context
+            .program(vertex_shader_source, fragment_shader.source, |program| {
+                material.use_uniforms(program, camera, lights);
+                self.draw(
+                    program,
+                    material.render_states(),
+                    camera,
+                    fragment_shader.attributes,
+                );
+            })
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
+        self.context
+            .program(vertex_shader_source, fragment_shader.source, |program| {
+                material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                self.draw(
+                    program,
+                    material.render_states(),
+                    camera,
+                    fragment_shader.attributes,
+                );
+            })
+            .expect("Failed compiling shader");
+    }
+}
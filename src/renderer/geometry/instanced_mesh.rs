@@ -5,13 +5,33 @@ use std::sync::RwLock;
 
 use super::BaseMesh;
 
+///
+/// Defines whether the instances of an [InstancedMesh] are culled before rendering, see [InstancedMesh::set_culling].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CullingMode {
+    /// All instances are rendered.
+    #[default]
+    None,
+    /// The instances which bounding box is outside the view frustum of the camera are removed on the CPU before rendering,
+    /// resulting in a compacted instance buffer which is only updated when the camera, the transformation or the instances change.
+    /// This is useful for a large number of instances spread out in the scene where only some of them are visible at a time.
+    ///
+    /// On desktop OpenGL with [Capabilities::multi_draw_indirect] support, instances of a mesh with indices rendered with an opaque material are instead culled
+    /// by drawing only the ranges of visible instances in the full instance buffers with one multi draw indirect call, see [Program::multi_draw_elements_indirect],
+    /// so only the small buffer of draw calls is updated when the camera moves.
+    Frustum,
+}
+
 ///
 /// Similar to [Mesh], except it is possible to render many instances of the same mesh efficiently.
 ///
 pub struct InstancedMesh {
     context: Context,
     base_mesh: BaseMesh,
-    instance_buffers: RwLock<(HashMap<String, InstanceBuffer>, Vec3)>,
+    instance_buffers: RwLock<(HashMap<String, InstanceBuffer>, Vec3, Option<(Mat4, u32)>)>,
+    // The draw calls of the ranges of visible instances and the culling key they were created with, see CullingMode::Frustum
+    indirect_commands: RwLock<(Option<(Mat4, u32)>, DrawIndirectBuffer)>,
     aabb: AxisAlignedBoundingBox,
     aabb_local: AxisAlignedBoundingBox,
     transformation: Mat4,
@@ -19,6 +39,7 @@ pub struct InstancedMesh {
     animation: Option<Box<dyn Fn(f32) -> Mat4 + Send + Sync>>,
     instance_count: u32,
     instances: Instances,
+    culling: CullingMode,
 }
 
 impl InstancedMesh {
@@ -32,7 +53,8 @@ impl InstancedMesh {
         let mut instanced_mesh = Self {
             context: context.clone(),
             base_mesh: BaseMesh::new(context, cpu_mesh),
-            instance_buffers: RwLock::new((Default::default(), vec3(0.0, 0.0, 0.0))),
+            instance_buffers: RwLock::new((Default::default(), vec3(0.0, 0.0, 0.0), None)),
+            indirect_commands: RwLock::new((None, DrawIndirectBuffer::new(context))),
             aabb,
             aabb_local: aabb,
             transformation: Mat4::identity(),
@@ -40,6 +62,7 @@ impl InstancedMesh {
             animation: None,
             instance_count: 0,
             instances: instances.clone(),
+            culling: CullingMode::None,
         };
         instanced_mesh.set_instances(instances);
         instanced_mesh
@@ -100,6 +123,95 @@ impl InstancedMesh {
                 .expect("failed acquiring write accesss");
             s.0.clear();
         }
+        self.indirect_commands
+            .write()
+            .expect("failed acquiring write accesss")
+            .0 = None;
+    }
+
+    ///
+    /// Returns the [CullingMode] used when rendering this instanced mesh.
+    ///
+    pub fn culling(&self) -> CullingMode {
+        self.culling
+    }
+
+    ///
+    /// Sets the [CullingMode] used when rendering this instanced mesh.
+    /// Culling is disabled by default, so all instances are rendered regardless of visibility.
+    ///
+    pub fn set_culling(&mut self, culling: CullingMode) {
+        self.culling = culling;
+        self.instance_buffers
+            .write()
+            .expect("failed acquiring write accesss")
+            .0
+            .clear();
+        self.indirect_commands
+            .write()
+            .expect("failed acquiring write accesss")
+            .0 = None;
+    }
+
+    ///
+    /// Returns whether the instances are culled using indirect draw calls instead of compacted instance buffers, see [CullingMode::Frustum].
+    /// The instances rendered with a transparent material are ordered by distance to the camera, which requires compacted instance buffers.
+    /// The base instance of an indirect draw call is not supported on OpenGL ES.
+    ///
+    fn uses_indirect_culling(&self, depth_ordering_pose: Option<Vec3>) -> bool {
+        self.culling == CullingMode::Frustum
+            && depth_ordering_pose.is_none()
+            && self.base_mesh.indices.is_some()
+            && self.context.capabilities().multi_draw_indirect
+            && !self.context.version().is_embedded
+    }
+
+    ///
+    /// Updates the indirect draw calls so that each draws a range of consecutive instances which are inside the view frustum of the camera.
+    ///
+    fn update_indirect_commands(&self, camera: &Camera) {
+        let culling_key = Some((
+            camera.projection() * camera.view() * self.current_transformation,
+            self.instance_count,
+        ));
+        if self
+            .indirect_commands
+            .read()
+            .expect("failed acquiring read accesss")
+            .0
+            == culling_key
+        {
+            return;
+        }
+        let count = 3 * self.base_mesh.triangle_count() as u32;
+        let mut commands: Vec<DrawElementsIndirectCommand> = Vec::new();
+        for i in 0..self.instance_count {
+            let mut aabb = self.aabb_local;
+            aabb.transform(
+                &(self.current_transformation * self.instances.transformations[i as usize]),
+            );
+            if !camera.in_frustum(&aabb) {
+                continue;
+            }
+            match commands.last_mut() {
+                Some(command) if command.base_instance + command.instance_count == i => {
+                    command.instance_count += 1
+                }
+                _ => commands.push(DrawElementsIndirectCommand {
+                    count,
+                    instance_count: 1,
+                    first_index: 0,
+                    base_vertex: 0,
+                    base_instance: i,
+                }),
+            }
+        }
+        let mut s = self
+            .indirect_commands
+            .write()
+            .expect("failed acquiring write accesss");
+        s.1.fill(&commands);
+        s.0 = culling_key;
     }
 
     fn update_aabb(&mut self) {
@@ -113,8 +225,21 @@ impl InstancedMesh {
     }
 
     /// Update the instance buffers, if depth_ordering_pose is populated depth ordering is performed
-    /// using this position.
-    fn update_instance_buffers(&self, depth_ordering_pose: Option<Vec3>) {
+    /// using this position and if culling is enabled, the instances outside the view frustum of the camera are removed.
+    fn update_instance_buffers(&self, depth_ordering_pose: Option<Vec3>, camera: &Camera) {
+        let indirect_culling = self.uses_indirect_culling(depth_ordering_pose);
+        if indirect_culling {
+            self.update_indirect_commands(camera);
+        }
+        // The instances only need to be culled again if the camera, the transformation or the instance count changed
+        let culling_key = match self.culling {
+            CullingMode::Frustum if !indirect_culling => Some((
+                camera.projection() * camera.view() * self.current_transformation,
+                self.instance_count,
+            )),
+            _ => None,
+        };
+
         let needs_update = {
             let s = self
                 .instance_buffers
@@ -137,7 +262,8 @@ impl InstancedMesh {
 
             // Update is always needed if the instance buffers is empty; Opaque materials only.
             // Or, for transparent materials, if the camera moved or if the instance count changed.
-            s.0.is_empty() || reorder_needed
+            // Or, if culling is enabled, if the culling camera changed.
+            s.0.is_empty() || reorder_needed || culling_key != s.2
         };
 
         if needs_update {
@@ -145,10 +271,14 @@ impl InstancedMesh {
                 .instance_buffers
                 .write()
                 .expect("failed acquiring mutable access");
-            s.0 = self.create_instance_buffers(depth_ordering_pose);
+            s.0 = self.create_instance_buffers(
+                depth_ordering_pose,
+                culling_key.is_some().then_some(camera),
+            );
             if let Some(ordering_pose) = depth_ordering_pose {
                 s.1 = ordering_pose;
             }
+            s.2 = culling_key;
         }
     }
 
@@ -166,12 +296,14 @@ impl InstancedMesh {
 
     ///
     /// This function creates the instance buffers, ordering them by distance to the camera
+    /// and removing the instances outside the view frustum of the culling camera, if given.
     ///
     fn create_instance_buffers(
         &self,
         depth_ordering: Option<Vec3>,
+        culling_camera: Option<&Camera>,
     ) -> HashMap<String, InstanceBuffer> {
-        let indices = if let Some(position) = depth_ordering {
+        let mut indices = if let Some(position) = depth_ordering {
             // Need to order by using the position.
             let distances = self
                 .instances
@@ -184,6 +316,13 @@ impl InstancedMesh {
             // No need to order, just return the indices as is.
             (0..self.instances.transformations.len()).collect::<Vec<usize>>()
         };
+        if let Some(camera) = culling_camera {
+            indices.retain(|i| {
+                let mut aabb = self.aabb_local;
+                aabb.transform(&(self.current_transformation * self.instances.transformations[*i]));
+                *i < self.instance_count as usize && camera.in_frustum(&aabb)
+            });
+        }
 
         // Next, we can compute the instance buffers with that ordering.
         let mut instance_buffers: HashMap<String, InstanceBuffer> = Default::default();
@@ -252,6 +391,14 @@ impl InstancedMesh {
                 InstanceBuffer::new_with_data(&self.context, &instance_tex_transform2),
             );
         }
+        // The original index of each instance, since the instances might be reordered
+        instance_buffers.insert(
+            "instance_index".to_string(),
+            InstanceBuffer::new_with_data(
+                &self.context,
+                &indices.iter().map(|i| *i as f32).collect::<Vec<_>>(),
+            ),
+        );
         if let Some(instance_colors) = &self.instances.colors {
             // Create the re-ordered color buffer by depth.
             let ordered_instance_colors = indices
@@ -273,6 +420,7 @@ impl InstancedMesh {
         camera: &Camera,
         attributes: FragmentAttributes,
         instance_buffers: &HashMap<String, InstanceBuffer>,
+        indirect_commands: Option<&DrawIndirectBuffer>,
     ) {
         if attributes.normal && instance_buffers.contains_key("instance_translation") {
             if let Some(inverse) = self.current_transformation.invert() {
@@ -282,7 +430,10 @@ impl InstancedMesh {
                 return;
             }
         }
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
         program.use_uniform("modelMatrix", self.current_transformation);
 
         for attribute_name in [
@@ -293,6 +444,7 @@ impl InstancedMesh {
             "tex_transform_row1",
             "tex_transform_row2",
             "instance_color",
+            "instance_index",
         ] {
             if program.requires_attribute(attribute_name) {
                 program.use_instance_attribute(
@@ -302,13 +454,22 @@ impl InstancedMesh {
                 );
             }
         }
-        self.base_mesh.draw_instanced(
-            program,
-            render_states,
-            camera,
-            attributes,
-            self.instance_count,
-        );
+        if let Some(commands) = indirect_commands {
+            self.base_mesh
+                .draw_indirect(program, render_states, camera, attributes, commands);
+            return;
+        }
+        // The instance buffers contain fewer instances than the instance count if some of them are culled
+        let instance_count = instance_buffers
+            .get("instance_index")
+            .map(|buffer| buffer.instance_count())
+            .unwrap_or(0)
+            .min(self.instance_count);
+        if instance_count == 0 {
+            return;
+        }
+        self.base_mesh
+            .draw_instanced(program, render_states, camera, attributes, instance_count);
     }
 
     fn vertex_shader_source(
@@ -317,7 +478,7 @@ impl InstancedMesh {
         instance_buffers: &HashMap<String, InstanceBuffer>,
     ) -> String {
         format!(
-            "{}{}{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}{}",
             if instance_buffers.contains_key("instance_translation") {
                 "#define USE_INSTANCE_TRANSLATIONS\n"
             } else {
@@ -352,6 +513,11 @@ impl InstancedMesh {
             } else {
                 ""
             },
+            if required_attributes.instance_id {
+                "#define USE_INSTANCE_ID\n#define USE_INSTANCE_INDICES\n"
+            } else {
+                ""
+            },
             include_str!("../../core/shared.frag"),
             include_str!("shaders/mesh.vert"),
         )
@@ -380,6 +546,10 @@ impl Geometry for InstancedMesh {
         }
     }
 
+    fn triangle_count(&self) -> usize {
+        self.base_mesh.triangle_count() * self.instance_count as usize
+    }
+
     fn render_with_material(
         &self,
         material: &dyn Material,
@@ -393,12 +563,17 @@ impl Geometry for InstancedMesh {
             None
         };
 
-        self.update_instance_buffers(update_pose);
+        self.update_instance_buffers(update_pose, camera);
         let instance_buffers = &self
             .instance_buffers
             .read()
             .expect("failed to acquire read access")
             .0;
+        let indirect_commands = self.uses_indirect_culling(update_pose).then(|| {
+            self.indirect_commands
+                .read()
+                .expect("failed to acquire read access")
+        });
 
         let fragment_shader = material.fragment_shader(lights);
         let vertex_shader_source =
@@ -412,6 +587,7 @@ impl Geometry for InstancedMesh {
                     camera,
                     fragment_shader.attributes,
                     instance_buffers,
+                    indirect_commands.as_ref().map(|commands| &commands.1),
                 );
             })
             .expect("Failed compiling shader");
@@ -432,12 +608,17 @@ impl Geometry for InstancedMesh {
             None
         };
 
-        self.update_instance_buffers(update_pose);
+        self.update_instance_buffers(update_pose, camera);
         let instance_buffers = &self
             .instance_buffers
             .read()
             .expect("failed to acquire read access")
             .0;
+        let indirect_commands = self.uses_indirect_culling(update_pose).then(|| {
+            self.indirect_commands
+                .read()
+                .expect("failed to acquire read access")
+        });
 
         let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
         let vertex_shader_source =
@@ -451,6 +632,7 @@ impl Geometry for InstancedMesh {
                     camera,
                     fragment_shader.attributes,
                     instance_buffers,
+                    indirect_commands.as_ref().map(|commands| &commands.1),
                 );
             })
             .expect("Failed compiling shader");
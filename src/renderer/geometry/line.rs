@@ -109,6 +109,10 @@ impl Geometry for Line {
         let pixel1: Vec2 = self.pixel1.into();
         AxisAlignedBoundingBox::new_with_positions(&[pixel0.extend(0.0), pixel1.extend(0.0)])
     }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
 }
 
 impl<'a> IntoIterator for &'a Line {
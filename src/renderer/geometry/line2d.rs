@@ -92,7 +92,10 @@ impl Line2D {
     fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
         let viewport = camera.viewport();
         program.use_uniform("model", self.transformation);
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
         program.use_uniform(
             "resolution",
             vec2(viewport.width as f32, viewport.height as f32),
@@ -193,6 +196,10 @@ impl Geometry for Line2D {
         let end: Vec2 = self.end.into();
         AxisAlignedBoundingBox::new_with_positions(&[start.extend(0.0), end.extend(0.0)])
     }
+
+    fn triangle_count(&self) -> usize {
+        self.positions.vertex_count() as usize / 3
+    }
 }
 
 impl<'a> IntoIterator for &'a Line2D {
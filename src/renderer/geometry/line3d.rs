@@ -0,0 +1,285 @@
+use crate::core::*;
+use crate::renderer::*;
+
+// The end point (x) and side (y) of each of the six vertices of the two triangles making up a segment
+const CORNERS: [Vec2; 6] = [
+    Vec2::new(0.0, -1.0),
+    Vec2::new(1.0, -1.0),
+    Vec2::new(1.0, 1.0),
+    Vec2::new(1.0, 1.0),
+    Vec2::new(0.0, 1.0),
+    Vec2::new(0.0, -1.0),
+];
+
+///
+/// A set of 3D line segments whose thickness is a constant number of pixels at any distance from the camera,
+/// for example for wireframes, trajectories or measurements in a 3D scene.
+/// In contrast to [Line2D], the segments can be placed anywhere in 3D and are occluded by other objects as usual.
+///
+/// Each segment is expanded to a flat band facing the camera in the vertex shader.
+/// The ends of the bands are extended by half the thickness, so connected segments, for example in a line strip (see [Line3D::new_strip]), do not have gaps at the joints.
+///
+pub struct Line3D {
+    context: Context,
+    starts: VertexBuffer,
+    ends: VertexBuffer,
+    corners: VertexBuffer,
+    colors: Option<VertexBuffer>,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+    segment_count: u32,
+    /// The thickness of the lines in pixels.
+    pub thickness: f32,
+}
+
+impl Line3D {
+    ///
+    /// Creates new white line segments, each given by its start and end point, with the given thickness in pixels.
+    ///
+    pub fn new(context: &Context, segments: &[(Vec3, Vec3)], thickness: f32) -> Self {
+        let mut line = Self {
+            context: context.clone(),
+            starts: VertexBuffer::new(context),
+            ends: VertexBuffer::new(context),
+            corners: VertexBuffer::new(context),
+            colors: None,
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            transformation: Mat4::identity(),
+            segment_count: 0,
+            thickness,
+        };
+        line.set_segments(segments, None);
+        line
+    }
+
+    ///
+    /// Creates a new white line strip, which connects each of the given points to the next, with the given thickness in pixels.
+    ///
+    pub fn new_strip(context: &Context, points: &[Vec3], thickness: f32) -> Self {
+        let mut line = Self::new(context, &[], thickness);
+        line.set_strip(points, None);
+        line
+    }
+
+    ///
+    /// Replaces the line segments, each given by its start and end point, and optionally the color at the start and end point of each segment.
+    /// The color is interpolated along the segment.
+    ///
+    /// # Panic
+    /// Will panic if the colors are specified and the number of colors does not match the number of segments.
+    ///
+    pub fn set_segments(&mut self, segments: &[(Vec3, Vec3)], colors: Option<&[(Color, Color)]>) {
+        let mut starts = Vec::with_capacity(segments.len() * 6);
+        let mut ends = Vec::with_capacity(segments.len() * 6);
+        let mut corners = Vec::with_capacity(segments.len() * 6);
+        for (start, end) in segments {
+            for corner in CORNERS {
+                starts.push(*start);
+                ends.push(*end);
+                corners.push(corner);
+            }
+        }
+        self.starts.fill(&starts);
+        self.ends.fill(&ends);
+        self.corners.fill(&corners);
+
+        self.colors = colors.map(|colors| {
+            if colors.len() != segments.len() {
+                panic!(
+                    "the number of colors ({}) does not match the number of segments ({})",
+                    colors.len(),
+                    segments.len()
+                );
+            }
+            let colors = colors
+                .iter()
+                .flat_map(|(start, end)| {
+                    CORNERS.map(|corner| if corner.x < 0.5 { *start } else { *end })
+                })
+                .collect::<Vec<_>>();
+            VertexBuffer::new_with_data(&self.context, &colors)
+        });
+
+        self.aabb = if segments.is_empty() {
+            AxisAlignedBoundingBox::EMPTY
+        } else {
+            AxisAlignedBoundingBox::new_with_positions(
+                &segments
+                    .iter()
+                    .flat_map(|(start, end)| [*start, *end])
+                    .collect::<Vec<_>>(),
+            )
+        };
+        self.segment_count = segments.len() as u32;
+    }
+
+    ///
+    /// Replaces the line segments with a line strip, which connects each of the given points to the next,
+    /// and optionally sets the color at each point, for example to fade out the old part of a trajectory.
+    ///
+    /// # Panic
+    /// Will panic if the colors are specified and the number of colors does not match the number of points.
+    ///
+    pub fn set_strip(&mut self, points: &[Vec3], colors: Option<&[Color]>) {
+        if let Some(colors) = colors {
+            if colors.len() != points.len() {
+                panic!(
+                    "the number of colors ({}) does not match the number of points ({})",
+                    colors.len(),
+                    points.len()
+                );
+            }
+        }
+        let segments = points.windows(2).map(|p| (p[0], p[1])).collect::<Vec<_>>();
+        let colors =
+            colors.map(|colors| colors.windows(2).map(|c| (c[0], c[1])).collect::<Vec<_>>());
+        self.set_segments(&segments, colors.as_deref());
+    }
+
+    ///
+    /// Returns the number of line segments.
+    ///
+    pub fn segment_count(&self) -> u32 {
+        self.segment_count
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all line segments.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all line segments.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
+        format!(
+            "{}{}{}{}{}{}",
+            if required_attributes.normal {
+                "#define USE_NORMALS\n"
+            } else {
+                ""
+            },
+            if required_attributes.tangents {
+                "#define USE_TANGENTS\n"
+            } else {
+                ""
+            },
+            if required_attributes.uv {
+                "#define USE_UVS\n"
+            } else {
+                ""
+            },
+            if self.colors.is_some() {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            if required_attributes.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/line3d.vert"),
+        )
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
+        let viewport = camera.viewport();
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform_if_required("cameraPosition", camera.position());
+        program.use_uniform(
+            "resolution",
+            vec2(viewport.width as f32, viewport.height as f32),
+        );
+        program.use_uniform("thickness", self.thickness);
+        program.use_vertex_attribute("start", &self.starts);
+        program.use_vertex_attribute("end", &self.ends);
+        program.use_vertex_attribute("corner", &self.corners);
+        if let Some(colors) = &self.colors {
+            program.use_vertex_attribute("color", colors);
+        }
+        // Both sides of the bands are visible, since the winding depends on the direction of the segment on the screen
+        let render_states = RenderStates {
+            cull: Cull::None,
+            ..render_states
+        };
+        program.draw_arrays(render_states, viewport, self.segment_count * 6);
+    }
+}
+
+impl<'a> IntoIterator for &'a Line3D {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Line3D {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.aabb;
+        aabb.transform(&self.transformation);
+        aabb
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if self.segment_count == 0 {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                self.vertex_shader_source(fragment_shader.attributes),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader")
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if self.segment_count == 0 {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                self.vertex_shader_source(fragment_shader.attributes),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader")
+    }
+
+    fn triangle_count(&self) -> usize {
+        2 * self.segment_count as usize
+    }
+}
@@ -0,0 +1,597 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::{HashMap, HashSet};
+
+///
+/// A regular 3D grid of scalar values, for example samples of a density or a signed distance function, which can be turned into a [CpuMesh] of an isosurface using [ScalarGrid::marching_cubes].
+/// The sample with index `(x, y, z)` is located at `origin + spacing * (x, y, z)` (componentwise multiplication).
+///
+#[derive(Clone, Debug)]
+pub struct ScalarGrid {
+    /// The position of the sample with index `(0, 0, 0)`.
+    pub origin: Vec3,
+    /// The distance between neighbouring samples in each direction.
+    pub spacing: Vec3,
+    resolution: (u32, u32, u32),
+    values: Vec<f32>,
+}
+
+impl ScalarGrid {
+    ///
+    /// Creates a grid with the given number of samples in each direction where all values are zero.
+    ///
+    pub fn new(resolution: (u32, u32, u32), origin: Vec3, spacing: Vec3) -> Self {
+        Self {
+            origin,
+            spacing,
+            resolution,
+            values: vec![0.0; (resolution.0 * resolution.1 * resolution.2) as usize],
+        }
+    }
+
+    ///
+    /// Creates a grid with the given number of samples in each direction by sampling the scalar field given as a closure inside the bounding box,
+    /// where the first and last samples in each direction are on the sides of the bounding box.
+    ///
+    pub fn from_fn(
+        resolution: (u32, u32, u32),
+        aabb: AxisAlignedBoundingBox,
+        field: impl Fn(Vec3) -> f32,
+    ) -> Self {
+        let size = aabb.size();
+        let spacing = vec3(
+            size.x / resolution.0.saturating_sub(1).max(1) as f32,
+            size.y / resolution.1.saturating_sub(1).max(1) as f32,
+            size.z / resolution.2.saturating_sub(1).max(1) as f32,
+        );
+        let mut grid = Self::new(resolution, aabb.min(), spacing);
+        grid.fill_region((0, 0, 0), resolution, field);
+        grid
+    }
+
+    ///
+    /// Creates a grid from the red channel of the given 3D texture, where the texture fills a box with the given size and center in origo, like in a [CpuVoxelGrid].
+    /// Values stored as `u8` are converted to the range `[0..1]`.
+    ///
+    /// A [Texture3D] cannot be read back from the GPU, so use the [CpuTexture3D] which was used to create it.
+    ///
+    pub fn from_cpu_texture_3d(cpu_texture: &CpuTexture3D, size: Vec3) -> Self {
+        let values: Vec<f32> = match &cpu_texture.data {
+            TextureData::RU8(data) => data.iter().map(|v| *v as f32 / 255.0).collect(),
+            TextureData::RgU8(data) => data.iter().map(|v| v[0] as f32 / 255.0).collect(),
+            TextureData::RgbU8(data) => data.iter().map(|v| v[0] as f32 / 255.0).collect(),
+            TextureData::RgbaU8(data) => data.iter().map(|v| v[0] as f32 / 255.0).collect(),
+            TextureData::RF16(data) => data.iter().map(|v| v.to_f32()).collect(),
+            TextureData::RgF16(data) => data.iter().map(|v| v[0].to_f32()).collect(),
+            TextureData::RgbF16(data) => data.iter().map(|v| v[0].to_f32()).collect(),
+            TextureData::RgbaF16(data) => data.iter().map(|v| v[0].to_f32()).collect(),
+            TextureData::RF32(data) => data.clone(),
+            TextureData::RgF32(data) => data.iter().map(|v| v[0]).collect(),
+            TextureData::RgbF32(data) => data.iter().map(|v| v[0]).collect(),
+            TextureData::RgbaF32(data) => data.iter().map(|v| v[0]).collect(),
+        };
+        let resolution = (cpu_texture.width, cpu_texture.height, cpu_texture.depth);
+        // The texels are sampled at their centers, so the first and last samples are half a texel from the sides of the box
+        let spacing = vec3(
+            size.x / resolution.0.max(1) as f32,
+            size.y / resolution.1.max(1) as f32,
+            size.z / resolution.2.max(1) as f32,
+        );
+        Self {
+            origin: 0.5 * spacing - 0.5 * size,
+            spacing,
+            resolution,
+            values,
+        }
+    }
+
+    ///
+    /// Creates a grid from the red channel of the voxels in the given voxel grid, see [ScalarGrid::from_cpu_texture_3d].
+    ///
+    pub fn from_cpu_voxel_grid(cpu_voxel_grid: &CpuVoxelGrid) -> Self {
+        Self::from_cpu_texture_3d(&cpu_voxel_grid.voxels, cpu_voxel_grid.size)
+    }
+
+    ///
+    /// Returns the number of samples in each direction.
+    ///
+    pub fn resolution(&self) -> (u32, u32, u32) {
+        self.resolution
+    }
+
+    ///
+    /// Returns the position of the sample with the given index.
+    ///
+    pub fn position(&self, x: u32, y: u32, z: u32) -> Vec3 {
+        self.origin
+            + vec3(
+                self.spacing.x * x as f32,
+                self.spacing.y * y as f32,
+                self.spacing.z * z as f32,
+            )
+    }
+
+    ///
+    /// Returns the value of the sample with the given index.
+    ///
+    pub fn value(&self, x: u32, y: u32, z: u32) -> f32 {
+        self.values[self.index(x, y, z)]
+    }
+
+    ///
+    /// Sets the value of the sample with the given index.
+    ///
+    pub fn set_value(&mut self, x: u32, y: u32, z: u32, value: f32) {
+        let index = self.index(x, y, z);
+        self.values[index] = value;
+    }
+
+    ///
+    /// Sets the values of the samples with indices from `min` (inclusive) to `max` (exclusive) by sampling the scalar field given as a closure at the position of each sample.
+    ///
+    pub fn fill_region(
+        &mut self,
+        min: (u32, u32, u32),
+        max: (u32, u32, u32),
+        field: impl Fn(Vec3) -> f32,
+    ) {
+        for z in min.2..max.2.min(self.resolution.2) {
+            for y in min.1..max.1.min(self.resolution.1) {
+                for x in min.0..max.0.min(self.resolution.0) {
+                    let value = field(self.position(x, y, z));
+                    self.set_value(x, y, z, value);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns the number of cells, ie. the cubes between eight neighbouring samples, in each direction.
+    ///
+    pub fn cell_count(&self) -> (u32, u32, u32) {
+        (
+            self.resolution.0.saturating_sub(1),
+            self.resolution.1.saturating_sub(1),
+            self.resolution.2.saturating_sub(1),
+        )
+    }
+
+    ///
+    /// Returns a mesh of the isosurface where the scalar field is equal to the given iso value, using the marching cubes algorithm.
+    /// The values above the iso value are considered inside the surface, so the normals point in the direction of decreasing values,
+    /// for example out of a dense region. Negate the values of a signed distance function, which is negative inside, to get outwards facing normals.
+    /// The normals are computed from the gradient of the scalar field which gives a smooth surface.
+    ///
+    pub fn marching_cubes(&self, iso_value: f32) -> CpuMesh {
+        self.marching_cubes_in_cells(iso_value, (0, 0, 0), self.cell_count())
+    }
+
+    ///
+    /// Same as [ScalarGrid::marching_cubes], except that only the cells with indices from `min` (inclusive) to `max` (exclusive) are included.
+    ///
+    pub fn marching_cubes_in_cells(
+        &self,
+        iso_value: f32,
+        min: (u32, u32, u32),
+        max: (u32, u32, u32),
+    ) -> CpuMesh {
+        let cells = self.cell_count();
+        let max = (max.0.min(cells.0), max.1.min(cells.1), max.2.min(cells.2));
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut indices = Vec::new();
+        let mut vertex_ids = HashMap::new();
+        for z in min.2..max.2 {
+            for y in min.1..max.1 {
+                for x in min.0..max.0 {
+                    self.polygonize_cell(
+                        iso_value,
+                        (x, y, z),
+                        &mut positions,
+                        &mut normals,
+                        &mut indices,
+                        &mut vertex_ids,
+                    );
+                }
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            normals: Some(normals),
+            indices: Indices::U32(indices),
+            ..Default::default()
+        }
+    }
+
+    fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.resolution.0 * (y + self.resolution.1 * z)) as usize
+    }
+
+    fn gradient(&self, x: u32, y: u32, z: u32) -> Vec3 {
+        let difference = |axis: usize| {
+            let mut before = [x, y, z];
+            let mut after = [x, y, z];
+            let resolution = [self.resolution.0, self.resolution.1, self.resolution.2];
+            before[axis] = before[axis].saturating_sub(1);
+            after[axis] = (after[axis] + 1).min(resolution[axis] - 1);
+            let distance = (after[axis] - before[axis]) as f32 * self.spacing[axis];
+            if distance > 0.0 {
+                (self.value(after[0], after[1], after[2])
+                    - self.value(before[0], before[1], before[2]))
+                    / distance
+            } else {
+                0.0
+            }
+        };
+        vec3(difference(0), difference(1), difference(2))
+    }
+
+    fn polygonize_cell(
+        &self,
+        iso_value: f32,
+        cell: (u32, u32, u32),
+        positions: &mut Vec<Vec3>,
+        normals: &mut Vec<Vec3>,
+        indices: &mut Vec<u32>,
+        vertex_ids: &mut HashMap<(u32, u32, u32, usize), u32>,
+    ) {
+        let corner = |i: usize| {
+            (
+                cell.0 + (i & 1) as u32,
+                cell.1 + ((i >> 1) & 1) as u32,
+                cell.2 + ((i >> 2) & 1) as u32,
+            )
+        };
+        let mut values = [0.0; 8];
+        let mut inside = [false; 8];
+        for i in 0..8 {
+            let (x, y, z) = corner(i);
+            values[i] = self.value(x, y, z);
+            inside[i] = values[i] > iso_value;
+        }
+        if inside.iter().all(|i| *i) || inside.iter().all(|i| !*i) {
+            return;
+        }
+
+        // Connect the crossed edges of each face with segments, which together form closed polygons
+        let mut neighbours: [Vec<usize>; 12] = Default::default();
+        for face in FACES {
+            let edges: [usize; 4] = [0, 1, 2, 3].map(|k| edge_between(face[k], face[(k + 1) % 4]));
+            let crossed: Vec<usize> = (0..4)
+                .filter(|k| inside[face[*k]] != inside[face[(*k + 1) % 4]])
+                .collect();
+            let mut connect = |a: usize, b: usize| {
+                neighbours[a].push(b);
+                neighbours[b].push(a);
+            };
+            if crossed.len() == 2 {
+                connect(edges[crossed[0]], edges[crossed[1]]);
+            } else if crossed.len() == 4 {
+                // The face is ambiguous, so separate the corners which are not connected through the center of the face,
+                // which gives the same result for the two cells sharing the face
+                let center_inside = face.iter().map(|c| values[*c]).sum::<f32>() / 4.0 > iso_value;
+                for k in 0..4 {
+                    if inside[face[k]] != center_inside {
+                        connect(edges[(k + 3) % 4], edges[k]);
+                    }
+                }
+            }
+        }
+
+        let mut vertex = |edge: usize| {
+            let (a, b) = EDGES[edge];
+            let (a, b) = (corner(a), corner(b));
+            let axis = if a.0 != b.0 {
+                0
+            } else if a.1 != b.1 {
+                1
+            } else {
+                2
+            };
+            *vertex_ids.entry((a.0, a.1, a.2, axis)).or_insert_with(|| {
+                let (value_a, value_b) = (self.value(a.0, a.1, a.2), self.value(b.0, b.1, b.2));
+                let t = if value_a != value_b {
+                    ((iso_value - value_a) / (value_b - value_a)).clamp(0.0, 1.0)
+                } else {
+                    0.5
+                };
+                let position_a = self.position(a.0, a.1, a.2);
+                let position_b = self.position(b.0, b.1, b.2);
+                positions.push(position_a + t * (position_b - position_a));
+                let gradient_a = self.gradient(a.0, a.1, a.2);
+                let gradient_b = self.gradient(b.0, b.1, b.2);
+                let gradient = gradient_a + t * (gradient_b - gradient_a);
+                normals.push(if gradient.magnitude2() > 0.0 {
+                    -gradient.normalize()
+                } else {
+                    vec3(0.0, 1.0, 0.0)
+                });
+                positions.len() as u32 - 1
+            })
+        };
+
+        let mut polygons: Vec<Vec<usize>> = Vec::new();
+        let mut visited = [false; 12];
+        for start in 0..12 {
+            if visited[start] || neighbours[start].len() != 2 {
+                continue;
+            }
+            let mut polygon = vec![start];
+            visited[start] = true;
+            let mut previous = start;
+            let mut current = neighbours[start][0];
+            while current != start && !visited[current] {
+                visited[current] = true;
+                polygon.push(current);
+                let next = if neighbours[current][0] != previous {
+                    neighbours[current][0]
+                } else {
+                    neighbours[current][1]
+                };
+                previous = current;
+                current = next;
+            }
+            polygons.push(polygon);
+        }
+        for polygon in polygons.iter_mut() {
+            // Orient the polygon so it faces away from the inside corners, computed in the local coordinates of the cell using the midpoints of the edges,
+            // which gives the same orientation as the neighbouring cells
+            let local =
+                |i: usize| vec3((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32);
+            let midpoint = |edge: usize| 0.5 * (local(EDGES[edge].0) + local(EDGES[edge].1));
+            let mut normal = vec3(0.0, 0.0, 0.0);
+            let mut outwards = vec3(0.0, 0.0, 0.0);
+            for k in 0..polygon.len() {
+                let (a, b) = (
+                    midpoint(polygon[k]),
+                    midpoint(polygon[(k + 1) % polygon.len()]),
+                );
+                normal += a.cross(b);
+                let (c0, c1) = EDGES[polygon[k]];
+                outwards += if inside[c0] {
+                    local(c1) - local(c0)
+                } else {
+                    local(c0) - local(c1)
+                };
+            }
+            if normal.dot(outwards) < 0.0 {
+                polygon.reverse();
+            }
+        }
+
+        for polygon in polygons {
+            let ids: Vec<u32> = polygon.into_iter().map(&mut vertex).collect();
+            for i in 1..ids.len().saturating_sub(1) {
+                indices.extend_from_slice(&[ids[0], ids[i], ids[i + 1]]);
+            }
+        }
+    }
+}
+
+// The corners of a cell with index i are offset by (i & 1, (i >> 1) & 1, (i >> 2) & 1)
+const EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+// The corners of each face of a cell in cyclic order
+const FACES: [[usize; 4]; 6] = [
+    [0, 2, 6, 4],
+    [1, 3, 7, 5],
+    [0, 1, 5, 4],
+    [2, 3, 7, 6],
+    [0, 1, 3, 2],
+    [4, 5, 7, 6],
+];
+
+fn edge_between(a: usize, b: usize) -> usize {
+    EDGES
+        .iter()
+        .position(|e| *e == (a, b) || *e == (b, a))
+        .unwrap()
+}
+
+///
+/// Returns a mesh of the isosurface where the scalar field given as a closure is equal to the given iso value,
+/// by sampling the field in a grid with the given number of samples in each direction inside the bounding box, see [ScalarGrid::marching_cubes].
+///
+pub fn marching_cubes(
+    resolution: (u32, u32, u32),
+    aabb: AxisAlignedBoundingBox,
+    iso_value: f32,
+    field: impl Fn(Vec3) -> f32,
+) -> CpuMesh {
+    ScalarGrid::from_fn(resolution, aabb, field).marching_cubes(iso_value)
+}
+
+///
+/// Extracts the isosurface of a [ScalarGrid] which changes over time, for example when sculpting or in a simulation,
+/// by dividing the grid into chunks and only extracting the chunks again where the values have changed, see [IncrementalMarchingCubes::update].
+/// Each chunk has its own mesh, which can be transferred to the GPU separately, or they can be combined using [IncrementalMarchingCubes::mesh].
+///
+pub struct IncrementalMarchingCubes {
+    grid: ScalarGrid,
+    iso_value: f32,
+    chunk_size: u32,
+    chunks: HashMap<(u32, u32, u32), CpuMesh>,
+    dirty: HashSet<(u32, u32, u32)>,
+}
+
+impl IncrementalMarchingCubes {
+    ///
+    /// Creates a new incremental extraction of the isosurface of the given grid at the given iso value, where each chunk contains `chunk_size` cells in each direction.
+    /// All chunks are extracted on the first call to [IncrementalMarchingCubes::update].
+    ///
+    pub fn new(grid: ScalarGrid, iso_value: f32, chunk_size: u32) -> Self {
+        let mut s = Self {
+            grid,
+            iso_value,
+            chunk_size: chunk_size.max(1),
+            chunks: HashMap::new(),
+            dirty: HashSet::new(),
+        };
+        s.mark_all_dirty();
+        s
+    }
+
+    ///
+    /// Returns the grid.
+    ///
+    pub fn grid(&self) -> &ScalarGrid {
+        &self.grid
+    }
+
+    ///
+    /// Returns the iso value.
+    ///
+    pub fn iso_value(&self) -> f32 {
+        self.iso_value
+    }
+
+    ///
+    /// Sets the iso value, which requires extracting all chunks again.
+    ///
+    pub fn set_iso_value(&mut self, iso_value: f32) {
+        self.iso_value = iso_value;
+        self.mark_all_dirty();
+    }
+
+    ///
+    /// Sets the value of the sample with the given index in the grid and marks the chunks which are affected by the change.
+    ///
+    pub fn set_value(&mut self, x: u32, y: u32, z: u32, value: f32) {
+        self.grid.set_value(x, y, z, value);
+        self.mark_dirty((x, y, z), (x + 1, y + 1, z + 1));
+    }
+
+    ///
+    /// Sets the values of the samples with indices from `min` (inclusive) to `max` (exclusive) by sampling the scalar field given as a closure, see [ScalarGrid::fill_region],
+    /// and marks the chunks which are affected by the change.
+    ///
+    pub fn fill_region(
+        &mut self,
+        min: (u32, u32, u32),
+        max: (u32, u32, u32),
+        field: impl Fn(Vec3) -> f32,
+    ) {
+        self.grid.fill_region(min, max, field);
+        self.mark_dirty(min, max);
+    }
+
+    ///
+    /// Extracts the isosurface again in the chunks which are affected by changes since the last update and returns the indices of the updated chunks.
+    /// An updated chunk without any triangles is removed, see [IncrementalMarchingCubes::chunk_mesh].
+    ///
+    pub fn update(&mut self) -> Vec<(u32, u32, u32)> {
+        let mut updated: Vec<_> = self.dirty.drain().collect();
+        updated.sort();
+        for chunk in updated.iter() {
+            let min = (
+                chunk.0 * self.chunk_size,
+                chunk.1 * self.chunk_size,
+                chunk.2 * self.chunk_size,
+            );
+            let max = (
+                min.0 + self.chunk_size,
+                min.1 + self.chunk_size,
+                min.2 + self.chunk_size,
+            );
+            let mesh = self.grid.marching_cubes_in_cells(self.iso_value, min, max);
+            if mesh.positions.len() > 0 {
+                self.chunks.insert(*chunk, mesh);
+            } else {
+                self.chunks.remove(chunk);
+            }
+        }
+        updated
+    }
+
+    ///
+    /// Returns the mesh of the chunk with the given index or `None` if the chunk does not contain any triangles.
+    ///
+    pub fn chunk_mesh(&self, chunk: (u32, u32, u32)) -> Option<&CpuMesh> {
+        self.chunks.get(&chunk)
+    }
+
+    ///
+    /// Returns the indices and meshes of all chunks which contain triangles.
+    ///
+    pub fn chunks(&self) -> impl Iterator<Item = (&(u32, u32, u32), &CpuMesh)> {
+        self.chunks.iter()
+    }
+
+    ///
+    /// Returns a single mesh containing the meshes of all chunks.
+    ///
+    pub fn mesh(&self) -> CpuMesh {
+        let mut keys: Vec<_> = self.chunks.keys().collect();
+        keys.sort();
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut indices = Vec::new();
+        for key in keys {
+            let chunk = &self.chunks[key];
+            let offset = positions.len() as u32;
+            positions.extend(chunk.positions.to_f32());
+            normals.extend(chunk.normals.iter().flatten());
+            if let Indices::U32(chunk_indices) = &chunk.indices {
+                indices.extend(chunk_indices.iter().map(|i| i + offset));
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            normals: Some(normals),
+            indices: Indices::U32(indices),
+            ..Default::default()
+        }
+    }
+
+    fn mark_all_dirty(&mut self) {
+        let cells = self.grid.cell_count();
+        let chunks = (
+            cells.0.div_ceil(self.chunk_size),
+            cells.1.div_ceil(self.chunk_size),
+            cells.2.div_ceil(self.chunk_size),
+        );
+        for z in 0..chunks.2 {
+            for y in 0..chunks.1 {
+                for x in 0..chunks.0 {
+                    self.dirty.insert((x, y, z));
+                }
+            }
+        }
+    }
+
+    fn mark_dirty(&mut self, min: (u32, u32, u32), max: (u32, u32, u32)) {
+        let cells = self.grid.cell_count();
+        if cells.0 == 0
+            || cells.1 == 0
+            || cells.2 == 0
+            || max.0 <= min.0
+            || max.1 <= min.1
+            || max.2 <= min.2
+        {
+            return;
+        }
+        // A sample affects the cells it is a corner of and, through the normals, the neighbouring cells
+        let first = |sample: u32| sample.saturating_sub(2) / self.chunk_size;
+        let last = |sample: u32, cells: u32| (sample + 1).min(cells - 1) / self.chunk_size;
+        for z in first(min.2)..=last(max.2 - 1, cells.2) {
+            for y in first(min.1)..=last(max.1 - 1, cells.1) {
+                for x in first(min.0)..=last(max.0 - 1, cells.0) {
+                    self.dirty.insert((x, y, z));
+                }
+            }
+        }
+    }
+}
@@ -13,6 +13,20 @@ pub struct Mesh {
     transformation: Mat4,
     current_transformation: Mat4,
     animation: Option<Box<dyn Fn(f32) -> Mat4 + Send + Sync>>,
+    morph_targets: Option<MorphTargets>,
+    morph_weights: Vec<f32>,
+}
+
+///
+/// The position and normal displacements of the morph targets of a [Mesh], stored in textures where the texel with index
+/// `target * vertex_count + vertex` contains the displacement of the vertex in the morph target.
+///
+struct MorphTargets {
+    positions: Texture2D,
+    normals: Option<Texture2D>,
+    count: usize,
+    vertex_count: u32,
+    extent: Vec3,
 }
 
 impl Mesh {
@@ -29,9 +43,186 @@ impl Mesh {
             transformation: Mat4::identity(),
             current_transformation: Mat4::identity(),
             animation: None,
+            morph_targets: None,
+            morph_weights: Vec::new(),
+        }
+    }
+
+    ///
+    /// Sets the morph targets (also called blend shapes) of this mesh, for example for facial animation.
+    /// The positions of each morph target are the displacements of the vertices of this mesh, as defined in glTF files, so each morph target must have the same number of vertices as this mesh.
+    /// If all morph targets contain normals, these are also used as displacements of the normals of this mesh.
+    /// The morph targets are blended in the vertex shader using the weights given by [Mesh::set_morph_weights].
+    ///
+    pub fn set_morph_targets(&mut self, morph_targets: &[CpuMesh]) {
+        self.morph_weights.resize(morph_targets.len(), 0.0);
+        if morph_targets.is_empty() {
+            self.morph_targets = None;
+            return;
+        }
+        let vertex_count = self.base_mesh.positions.vertex_count();
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut extent = vec3(0.0, 0.0, 0.0);
+        let use_normals = morph_targets.iter().all(|m| m.normals.is_some());
+        for morph_target in morph_targets {
+            let displacements = morph_target.positions.to_f32();
+            if displacements.len() != vertex_count as usize {
+                panic!(
+                    "the morph target has {} vertices but the mesh has {} vertices",
+                    displacements.len(),
+                    vertex_count
+                );
+            }
+            let mut max = vec3(0.0, 0.0, 0.0);
+            for d in displacements.iter() {
+                max = vec3(
+                    max.x.max(d.x.abs()),
+                    max.y.max(d.y.abs()),
+                    max.z.max(d.z.abs()),
+                );
+                positions.push([d.x, d.y, d.z, 0.0]);
+            }
+            extent += max;
+            if use_normals {
+                normals.extend(
+                    morph_target
+                        .normals
+                        .as_ref()
+                        .unwrap()
+                        .iter()
+                        .map(|n| [n.x, n.y, n.z, 0.0]),
+                );
+            }
+        }
+
+        let texel_count = positions.len() as u32;
+        let width = texel_count.min(4096);
+        let height = (texel_count + width - 1) / width;
+        let new_texture = |mut data: Vec<[f32; 4]>| {
+            data.resize((width * height) as usize, [0.0; 4]);
+            let mut texture = Texture2D::new_empty::<[f32; 4]>(
+                &self.context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            texture.fill(&data);
+            texture
+        };
+        self.morph_targets = Some(MorphTargets {
+            positions: new_texture(positions),
+            normals: if use_normals {
+                Some(new_texture(normals))
+            } else {
+                None
+            },
+            count: morph_targets.len(),
+            vertex_count,
+            extent,
+        });
+    }
+
+    ///
+    /// Returns the weights of the morph targets set by [Mesh::set_morph_targets].
+    ///
+    pub fn morph_weights(&self) -> &[f32] {
+        &self.morph_weights
+    }
+
+    ///
+    /// Sets the weights of the morph targets, ie. how much each morph target is applied, usually between 0 and 1.
+    /// If fewer weights than morph targets are given, the remaining weights are set to zero.
+    ///
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        for (i, weight) in self.morph_weights.iter_mut().enumerate() {
+            *weight = weights.get(i).copied().unwrap_or(0.0);
+        }
+    }
+
+    ///
+    /// Sets a custom per vertex attribute with the given name, for example a scalar value like the temperature at each vertex which can be visualized by a custom [Material].
+    /// The data must contain one value per vertex and the value type can be `f32`, [Vec2], [Vec3], [Vec4] or the corresponding integer types.
+    /// The attribute is available in the fragment shader of a material as for example `in float temperature;` for `f32` values or `flat in int temperature;` for `i32` values.
+    /// Setting an attribute with the same name as an existing attribute replaces the existing attribute.
+    ///
+    /// # Panic
+    /// Will panic if the number of values is not the same as the number of vertices.
+    ///
+    pub fn set_vertex_attribute<T: BufferDataType>(&mut self, name: &str, data: &[T]) {
+        self.base_mesh
+            .set_custom_attribute(&self.context, name, data);
+    }
+
+    ///
+    /// Removes the custom per vertex attribute with the given name, see [Mesh::set_vertex_attribute]. Returns false if there is no attribute with the given name.
+    ///
+    pub fn remove_vertex_attribute(&mut self, name: &str) -> bool {
+        self.base_mesh.remove_custom_attribute(name)
+    }
+
+    ///
+    /// Returns the names of the custom per vertex attributes, see [Mesh::set_vertex_attribute].
+    ///
+    pub fn vertex_attribute_names(&self) -> Vec<&str> {
+        self.base_mesh.custom_attribute_names()
+    }
+
+    ///
+    /// Replaces the positions of the vertices in the given range with the given positions by updating the GPU buffer in place,
+    /// which is much faster than creating a new mesh when only a few vertices change each frame, for example in a sculpting tool.
+    /// The bounding box is enlarged to contain the new positions, but it is never shrunk, so it might be larger than necessary after vertices are moved inwards.
+    ///
+    /// # Panic
+    /// Will panic if the number of positions is not the same as the number of vertices in the range or if the range is outside of the mesh.
+    ///
+    pub fn update_positions(&mut self, range: std::ops::Range<u32>, positions: &[Vec3]) {
+        check_range(&range, positions.len());
+        self.base_mesh.positions.fill_subset(range.start, positions);
+        if !positions.is_empty() {
+            self.aabb
+                .expand_with_aabb(&AxisAlignedBoundingBox::new_with_positions(positions));
         }
     }
 
+    ///
+    /// Replaces the normals of the vertices in the given range with the given normals by updating the GPU buffer in place, see [Mesh::update_positions].
+    ///
+    /// # Panic
+    /// Will panic if the mesh does not have normals, if the number of normals is not the same as the number of vertices in the range or if the range is outside of the mesh.
+    ///
+    pub fn update_normals(&mut self, range: std::ops::Range<u32>, normals: &[Vec3]) {
+        check_range(&range, normals.len());
+        self.base_mesh
+            .normals
+            .as_mut()
+            .expect("the mesh does not have normals")
+            .fill_subset(range.start, normals);
+    }
+
+    ///
+    /// Replaces the colors of the vertices in the given range with the given colors by updating the GPU buffer in place, for example in a vertex painting tool, see [Mesh::update_positions].
+    ///
+    /// # Panic
+    /// Will panic if the mesh does not have colors, if the number of colors is not the same as the number of vertices in the range or if the range is outside of the mesh.
+    ///
+    pub fn update_colors(&mut self, range: std::ops::Range<u32>, colors: &[Color]) {
+        check_range(&range, colors.len());
+        self.base_mesh
+            .colors
+            .as_mut()
+            .expect("the mesh does not have colors")
+            .fill_subset(range.start, colors);
+    }
+
+    pub(super) fn base_mesh_mut(&mut self) -> &mut BaseMesh {
+        &mut self.base_mesh
+    }
+
     pub(in crate::renderer) fn set_transformation_2d(&mut self, transformation: Mat3) {
         self.set_transformation(to_3d_transformation(transformation));
     }
@@ -61,12 +252,84 @@ impl Mesh {
         self.animation = Some(Box::new(animation));
     }
 
+    ///
+    /// Returns a [Geometry] which renders the given range of the triangles in this mesh, for example to render parts of the mesh with different materials
+    /// without duplicating the vertex data, see also [MultiMaterialMesh].
+    /// For a mesh with indices, the triangle with number `i` consists of the vertices given by the indices with number `3 * i`, `3 * i + 1` and `3 * i + 2`.
+    ///
+    pub fn sub_mesh(&self, triangles: std::ops::Range<u32>) -> SubMesh<'_> {
+        SubMesh {
+            mesh: self,
+            triangles,
+        }
+    }
+
+    // Renders the draw calls in the given buffer, each of which draws a range of the triangles in this mesh, which must have indices
+    pub(in crate::renderer) fn render_indirect_with_material(
+        &self,
+        commands: &DrawIndirectBuffer,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.render_range_with_material(DrawRange::Indirect(commands), material, camera, lights)
+    }
+
+    fn render_range_with_material(
+        &self,
+        range: DrawRange<'_>,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        let fragment_shader = material.fragment_shader(lights);
+        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
+        self.context
+            .program(vertex_shader_source, fragment_shader.source, |program| {
+                material.use_uniforms(program, camera, lights);
+                self.draw(
+                    program,
+                    material.render_states(),
+                    camera,
+                    fragment_shader.attributes,
+                    range,
+                );
+            })
+            .expect("Failed compiling shader");
+    }
+
+    fn render_range_with_post_material(
+        &self,
+        range: DrawRange<'_>,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
+        self.context
+            .program(vertex_shader_source, fragment_shader.source, |program| {
+                material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                self.draw(
+                    program,
+                    material.render_states(),
+                    camera,
+                    fragment_shader.attributes,
+                    range,
+                );
+            })
+            .expect("Failed compiling shader");
+    }
+
     fn draw(
         &self,
         program: &Program,
         render_states: RenderStates,
         camera: &Camera,
         attributes: FragmentAttributes,
+        range: DrawRange<'_>,
     ) {
         if attributes.normal {
             if let Some(inverse) = self.current_transformation.invert() {
@@ -77,16 +340,40 @@ impl Mesh {
             }
         }
 
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
         program.use_uniform("modelMatrix", self.current_transformation);
+        if let Some(morph_targets) = &self.morph_targets {
+            program.use_texture("morphPositions", &morph_targets.positions);
+            program.use_uniform("morphVertexCount", morph_targets.vertex_count as i32);
+            program.use_uniform_array("morphWeights", &self.morph_weights);
+            if attributes.normal {
+                if let Some(normals) = &morph_targets.normals {
+                    program.use_texture("morphNormals", normals);
+                }
+            }
+        }
 
-        self.base_mesh
-            .draw(program, render_states, camera, attributes);
+        match range {
+            DrawRange::All => self
+                .base_mesh
+                .draw(program, render_states, camera, attributes),
+            DrawRange::Triangles(triangles) => {
+                self.base_mesh
+                    .draw_range(program, render_states, camera, attributes, triangles)
+            }
+            DrawRange::Indirect(commands) => {
+                self.base_mesh
+                    .draw_indirect(program, render_states, camera, attributes, commands)
+            }
+        }
     }
 
     fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
         format!(
-            "{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}{}",
             if required_attributes.normal {
                 "#define USE_NORMALS\n"
             } else {
@@ -107,6 +394,25 @@ impl Mesh {
             } else {
                 ""
             },
+            if required_attributes.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            if let Some(morph_targets) = &self.morph_targets {
+                format!(
+                    "#define USE_MORPH_TARGETS\n#define MORPH_TARGET_COUNT {}\n{}",
+                    morph_targets.count,
+                    if required_attributes.normal && morph_targets.normals.is_some() {
+                        "#define USE_MORPH_NORMALS\n"
+                    } else {
+                        ""
+                    }
+                )
+            } else {
+                String::new()
+            },
+            self.base_mesh.custom_attributes_source(),
             include_str!("../../core/shared.frag"),
             include_str!("shaders/mesh.vert"),
         )
@@ -125,6 +431,15 @@ impl<'a> IntoIterator for &'a Mesh {
 impl Geometry for Mesh {
     fn aabb(&self) -> AxisAlignedBoundingBox {
         let mut aabb = self.aabb;
+        if let Some(morph_targets) = &self.morph_targets {
+            // Assumes weights between -1 and 1
+            if !aabb.is_empty() {
+                aabb = AxisAlignedBoundingBox::new_with_positions(&[
+                    aabb.min() - morph_targets.extent,
+                    aabb.max() + morph_targets.extent,
+                ]);
+            }
+        }
         aabb.transform(&self.current_transformation);
         aabb
     }
@@ -135,25 +450,17 @@ impl Geometry for Mesh {
         }
     }
 
+    fn triangle_count(&self) -> usize {
+        self.base_mesh.triangle_count()
+    }
+
     fn render_with_material(
         &self,
         material: &dyn Material,
         camera: &Camera,
         lights: &[&dyn Light],
     ) {
-        let fragment_shader = material.fragment_shader(lights);
-        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
-        self.context
-            .program(vertex_shader_source, fragment_shader.source, |program| {
-                material.use_uniforms(program, camera, lights);
-                self.draw(
-                    program,
-                    material.render_states(),
-                    camera,
-                    fragment_shader.attributes,
-                );
-            })
-            .expect("Failed compiling shader");
+        self.render_range_with_material(DrawRange::All, material, camera, lights)
     }
 
     fn render_with_post_material(
@@ -164,18 +471,101 @@ impl Geometry for Mesh {
         color_texture: Option<ColorTexture>,
         depth_texture: Option<DepthTexture>,
     ) {
-        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
-        let vertex_shader_source = self.vertex_shader_source(fragment_shader.attributes);
-        self.context
-            .program(vertex_shader_source, fragment_shader.source, |program| {
-                material.use_uniforms(program, camera, lights, color_texture, depth_texture);
-                self.draw(
-                    program,
-                    material.render_states(),
-                    camera,
-                    fragment_shader.attributes,
-                );
-            })
-            .expect("Failed compiling shader");
+        self.render_range_with_post_material(
+            DrawRange::All,
+            material,
+            camera,
+            lights,
+            color_texture,
+            depth_texture,
+        )
+    }
+}
+
+// The part of a mesh which is drawn
+enum DrawRange<'a> {
+    All,
+    Triangles(std::ops::Range<u32>),
+    Indirect(&'a DrawIndirectBuffer),
+}
+
+///
+/// A [Geometry] which renders a range of the triangles in a [Mesh], see [Mesh::sub_mesh].
+///
+#[derive(Clone)]
+pub struct SubMesh<'a> {
+    mesh: &'a Mesh,
+    triangles: std::ops::Range<u32>,
+}
+
+impl SubMesh<'_> {
+    ///
+    /// Returns the range of triangles in the mesh which are rendered.
+    ///
+    pub fn triangles(&self) -> std::ops::Range<u32> {
+        self.triangles.clone()
+    }
+}
+
+impl<'a> IntoIterator for &'a SubMesh<'_> {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for SubMesh<'_> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
+
+    fn triangle_count(&self) -> usize {
+        (self.triangles.end.min(self.mesh.triangle_count() as u32) as usize)
+            .saturating_sub(self.triangles.start as usize)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_range_with_material(
+            DrawRange::Triangles(self.triangles.clone()),
+            material,
+            camera,
+            lights,
+        )
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh.render_range_with_post_material(
+            DrawRange::Triangles(self.triangles.clone()),
+            material,
+            camera,
+            lights,
+            color_texture,
+            depth_texture,
+        )
+    }
+}
+
+fn check_range(range: &std::ops::Range<u32>, count: usize) {
+    if range.end < range.start || (range.end - range.start) as usize != count {
+        panic!(
+            "the range {:?} contains {} vertices but {} values are given",
+            range,
+            range.end.saturating_sub(range.start),
+            count
+        );
     }
 }
@@ -0,0 +1,642 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Options for [MeshBuilder::extrude].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ExtrudeOptions {
+    /// Whether or not the last point of the profile is connected to the first point.
+    pub closed_profile: bool,
+    /// Whether or not the ends of the extrusion are closed. Only applies to a closed profile.
+    pub caps: bool,
+    /// The total rotation of the profile around the path from the first to the last point of the path.
+    pub twist: Radians,
+    /// The scale of the profile at the first point of the path.
+    pub start_scale: f32,
+    /// The scale of the profile at the last point of the path. The scale is interpolated linearly along the path.
+    pub end_scale: f32,
+}
+
+impl Default for ExtrudeOptions {
+    fn default() -> Self {
+        Self {
+            closed_profile: true,
+            caps: true,
+            twist: radians(0.0),
+            start_scale: 1.0,
+            end_scale: 1.0,
+        }
+    }
+}
+
+///
+/// Functionality for building a [CpuMesh] from a 2D profile, for example pipes, railings or vases.
+///
+/// The returned meshes contain positions, smooth normals, uv coordinates and indices.
+/// Use [MeshProcessing::recompute_normals_smooth] to get sharp edges at the corners of the profile.
+///
+pub trait MeshBuilder {
+    ///
+    /// Creates a mesh by sweeping the given 2D profile along the given 3D path.
+    /// The x and y axes of the profile are perpendicular to the path and rotate with the path, so that the profile follows the bends of the path without twisting.
+    /// A closed profile should be given in counter-clockwise order, otherwise it is reversed so that the triangles face outwards.
+    /// The u coordinate follows the profile and the v coordinate follows the path.
+    ///
+    fn extrude(profile: &[Vec2], path: &[Vec3], options: ExtrudeOptions) -> Self;
+
+    ///
+    /// Creates a surface of revolution by rotating the given 2D profile around the y-axis with the given number of segments.
+    /// The x coordinate of the profile is the distance to the y-axis and the y coordinate is the height.
+    /// The triangles face outwards when the profile is given from the bottom to the top.
+    /// Use an angle less than a full turn to create an open surface.
+    /// The u coordinate follows the rotation and the v coordinate follows the profile.
+    ///
+    fn lathe(profile: &[Vec2], segments: u32, angle: impl Into<Radians>) -> Self;
+
+    ///
+    /// Creates a torus around the y-axis where the major radius is the distance from the center to the center of the tube and the minor radius is the radius of the tube.
+    /// The number of segments around the y-axis and around the tube defines the resolution.
+    ///
+    fn torus(
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: u32,
+        minor_segments: u32,
+    ) -> Self;
+
+    ///
+    /// Creates a capsule along the y-axis, ie. a cylinder with the given radius and length between the centers of two hemispheres, centered at origo.
+    /// The number of segments around the y-axis and the number of rings in each hemisphere defines the resolution.
+    ///
+    fn capsule(radius: f32, length: f32, segments: u32, rings: u32) -> Self;
+
+    ///
+    /// Creates a closed truncated cone along the y-axis from the bottom radius at `y = 0` to the top radius at `y = height`.
+    /// Use a top radius of zero to create a cone and equal radii to create a cylinder.
+    /// The number of segments around the y-axis and the number of stacks along the side defines the resolution.
+    ///
+    fn truncated_cone(
+        bottom_radius: f32,
+        top_radius: f32,
+        height: f32,
+        segments: u32,
+        stacks: u32,
+    ) -> Self;
+
+    ///
+    /// Creates a tube with the given radius along the given path, with or without caps at the ends.
+    /// The number of segments around the path defines the resolution.
+    ///
+    fn tube(path: &[Vec3], radius: f32, segments: u32, caps: bool) -> Self;
+
+    ///
+    /// Creates a closed arrow along the x-axis from origo to the given length, consisting of a cylindrical shaft and a conical head.
+    /// The number of segments around the x-axis defines the resolution.
+    ///
+    fn arrow_with_head(
+        length: f32,
+        shaft_radius: f32,
+        head_length: f32,
+        head_radius: f32,
+        segments: u32,
+    ) -> Self;
+
+    ///
+    /// Creates a sphere with radius 1 by subdividing an icosahedron the given number of times, which gives evenly distributed triangles contrary to [CpuMesh::sphere].
+    /// The uv coordinates are a spherical mapping where the u coordinate follows the longitude and the v coordinate follows the latitude.
+    ///
+    fn icosphere(subdivisions: u32) -> Self;
+}
+
+impl MeshBuilder for CpuMesh {
+    fn extrude(profile: &[Vec2], path: &[Vec3], options: ExtrudeOptions) -> Self {
+        if profile.len() < 2 || path.len() < 2 {
+            return CpuMesh::default();
+        }
+        let closed = options.closed_profile && profile.len() > 2;
+        let mut profile = profile.to_vec();
+        if closed && signed_area(&profile) < 0.0 {
+            profile.reverse();
+        }
+        let path_distances = distances(
+            path.iter()
+                .zip(path.iter().skip(1))
+                .map(|(a, b)| a.distance(*b)),
+        );
+        let mut ring = profile.clone();
+        if closed {
+            ring.push(profile[0]);
+        }
+        let ring_distances = distances(
+            ring.iter()
+                .zip(ring.iter().skip(1))
+                .map(|(a, b)| a.distance(*b)),
+        );
+
+        // The frames along the path, rotated from the previous frame to minimize twisting
+        let mut frames: Vec<(Vec3, Vec3, Vec3)> = Vec::with_capacity(path.len());
+        for i in 0..path.len() {
+            let tangent =
+                (path[(i + 1).min(path.len() - 1)] - path[i.saturating_sub(1)]).normalize();
+            let normal = match frames.last() {
+                Some((_, previous, _)) => *previous - tangent * previous.dot(tangent),
+                None => perpendicular(tangent),
+            };
+            let normal = if normal.magnitude2() > 0.000001 {
+                normal.normalize()
+            } else {
+                perpendicular(tangent)
+            };
+            frames.push((tangent, normal, tangent.cross(normal)));
+        }
+
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let transform = |i: usize, p: Vec2| {
+            let t = path_distances[i];
+            let scale = options.start_scale + (options.end_scale - options.start_scale) * t;
+            let (sin, cos) = (options.twist * t).0.sin_cos();
+            let p = scale * vec2(cos * p.x - sin * p.y, sin * p.x + cos * p.y);
+            let (_, normal, binormal) = frames[i];
+            path[i] + normal * p.x + binormal * p.y
+        };
+        for (i, v) in path_distances.iter().enumerate() {
+            for (p, u) in ring.iter().zip(ring_distances.iter()) {
+                positions.push(transform(i, *p));
+                uvs.push(vec2(*u, *v));
+            }
+        }
+        let stride = ring.len() as u32;
+        for i in 0..path.len().saturating_sub(1) as u32 {
+            for j in 0..stride.saturating_sub(1) {
+                let a = i * stride + j;
+                let c = a + stride;
+                indices.extend_from_slice(&[a, a + 1, c, c, a + 1, c + 1]);
+            }
+        }
+
+        let mut mesh = CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            uvs: Some(uvs),
+            ..Default::default()
+        };
+        mesh.compute_normals();
+
+        if closed && options.caps {
+            let triangles = triangulate(&profile);
+            let (min, max) = profile.iter().fold(
+                (vec2(f32::MAX, f32::MAX), vec2(f32::MIN, f32::MIN)),
+                |(min, max), p| {
+                    (
+                        vec2(min.x.min(p.x), min.y.min(p.y)),
+                        vec2(max.x.max(p.x), max.y.max(p.y)),
+                    )
+                },
+            );
+            let size = max - min;
+            for (i, reverse) in [(0, true), (path.len() - 1, false)] {
+                let mut cap_positions = Vec::with_capacity(profile.len());
+                let mut cap_uvs = Vec::with_capacity(profile.len());
+                for p in profile.iter() {
+                    cap_positions.push(transform(i, *p));
+                    cap_uvs.push(vec2(
+                        (p.x - min.x) / size.x.max(0.000001),
+                        (p.y - min.y) / size.y.max(0.000001),
+                    ));
+                }
+                let normal = if reverse { -frames[i].0 } else { frames[i].0 };
+                append(
+                    &mut mesh,
+                    cap_positions,
+                    vec![normal; profile.len()],
+                    cap_uvs,
+                    &triangles,
+                    reverse,
+                );
+            }
+        }
+        mesh
+    }
+
+    fn lathe(profile: &[Vec2], segments: u32, angle: impl Into<Radians>) -> Self {
+        if profile.len() < 2 {
+            return CpuMesh::default();
+        }
+        let angle = angle.into();
+        let segments = segments.max(1);
+        let profile_distances = distances(
+            profile
+                .iter()
+                .zip(profile.iter().skip(1))
+                .map(|(a, b)| a.distance(*b)),
+        );
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for s in 0..=segments {
+            let u = s as f32 / segments as f32;
+            let (sin, cos) = (angle * u).0.sin_cos();
+            for (p, v) in profile.iter().zip(profile_distances.iter()) {
+                positions.push(vec3(p.x * cos, p.y, p.x * sin));
+                uvs.push(vec2(u, *v));
+            }
+        }
+        let stride = profile.len() as u32;
+        for s in 0..segments {
+            for j in 0..stride.saturating_sub(1) {
+                let a = s * stride + j;
+                let c = a + stride;
+                indices.extend_from_slice(&[a, a + 1, c, c, a + 1, c + 1]);
+            }
+        }
+        let mut mesh = CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            uvs: Some(uvs),
+            ..Default::default()
+        };
+        mesh.compute_normals();
+        mesh
+    }
+
+    fn torus(
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: u32,
+        minor_segments: u32,
+    ) -> Self {
+        let minor_segments = minor_segments.max(3);
+        let profile = (0..=minor_segments)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / minor_segments as f32;
+                vec2(
+                    major_radius - minor_radius * angle.cos(),
+                    -minor_radius * angle.sin(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut mesh = Self::lathe(
+            &profile,
+            major_segments.max(3),
+            radians(std::f32::consts::TAU),
+        );
+        weld_normals(&mut mesh, major_segments.max(3), profile.len() as u32);
+        // Make the normals smooth across the seam of the profile
+        if let Some(normals) = &mut mesh.normals {
+            for ring in normals.chunks_mut(profile.len()) {
+                let normal = (ring[0] + ring[ring.len() - 1]).normalize();
+                ring[0] = normal;
+                ring[ring.len() - 1] = normal;
+            }
+        }
+        mesh
+    }
+
+    fn capsule(radius: f32, length: f32, segments: u32, rings: u32) -> Self {
+        let rings = rings.max(1);
+        let half = 0.5 * length;
+        let mut profile = Vec::with_capacity(2 * rings as usize + 2);
+        for i in 0..=rings {
+            let angle = std::f32::consts::FRAC_PI_2 * i as f32 / rings as f32;
+            profile.push(vec2(radius * angle.sin(), -half - radius * angle.cos()));
+        }
+        for i in 0..=rings {
+            let angle = std::f32::consts::FRAC_PI_2 * i as f32 / rings as f32;
+            profile.push(vec2(radius * angle.cos(), half + radius * angle.sin()));
+        }
+        let segments = segments.max(3);
+        let mut mesh = Self::lathe(&profile, segments, radians(std::f32::consts::TAU));
+        weld_normals(&mut mesh, segments, profile.len() as u32);
+        mesh
+    }
+
+    fn truncated_cone(
+        bottom_radius: f32,
+        top_radius: f32,
+        height: f32,
+        segments: u32,
+        stacks: u32,
+    ) -> Self {
+        let stacks = stacks.max(1);
+        // The corners are duplicated to get sharp edges between the caps and the side
+        let mut profile = vec![vec2(0.0, 0.0), vec2(bottom_radius, 0.0)];
+        for i in 0..=stacks {
+            let t = i as f32 / stacks as f32;
+            profile.push(vec2(
+                bottom_radius + (top_radius - bottom_radius) * t,
+                height * t,
+            ));
+        }
+        if top_radius > 0.0 {
+            profile.push(vec2(top_radius, height));
+            profile.push(vec2(0.0, height));
+        }
+        let segments = segments.max(3);
+        let mut mesh = Self::lathe(&profile, segments, radians(std::f32::consts::TAU));
+        weld_normals(&mut mesh, segments, profile.len() as u32);
+        mesh
+    }
+
+    fn tube(path: &[Vec3], radius: f32, segments: u32, caps: bool) -> Self {
+        let segments = segments.max(3);
+        let profile = (0..segments)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / segments as f32;
+                radius * vec2(angle.cos(), angle.sin())
+            })
+            .collect::<Vec<_>>();
+        let mut mesh = Self::extrude(
+            &profile,
+            path,
+            ExtrudeOptions {
+                caps,
+                ..Default::default()
+            },
+        );
+        // Make the normals smooth across the seam of the profile
+        if let Some(normals) = &mut mesh.normals {
+            let stride = segments as usize + 1;
+            for i in 0..path.len() {
+                let (first, last) = (i * stride, i * stride + segments as usize);
+                let normal = (normals[first] + normals[last]).normalize();
+                normals[first] = normal;
+                normals[last] = normal;
+            }
+        }
+        mesh
+    }
+
+    fn arrow_with_head(
+        length: f32,
+        shaft_radius: f32,
+        head_length: f32,
+        head_radius: f32,
+        segments: u32,
+    ) -> Self {
+        let shaft_length = (length - head_length).max(0.0);
+        // The corners are duplicated to get sharp edges
+        let profile = [
+            vec2(0.0, 0.0),
+            vec2(shaft_radius, 0.0),
+            vec2(shaft_radius, 0.0),
+            vec2(shaft_radius, shaft_length),
+            vec2(shaft_radius, shaft_length),
+            vec2(head_radius, shaft_length),
+            vec2(head_radius, shaft_length),
+            vec2(0.0, length),
+        ];
+        let segments = segments.max(3);
+        let mut mesh = Self::lathe(&profile, segments, radians(std::f32::consts::TAU));
+        weld_normals(&mut mesh, segments, profile.len() as u32);
+        // Rotate from the y-axis to the x-axis
+        if let Positions::F32(positions) = &mut mesh.positions {
+            positions.iter_mut().for_each(|p| *p = vec3(p.y, -p.x, p.z));
+        }
+        if let Some(normals) = &mut mesh.normals {
+            normals.iter_mut().for_each(|n| *n = vec3(n.y, -n.x, n.z));
+        }
+        mesh
+    }
+
+    fn icosphere(subdivisions: u32) -> Self {
+        let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+        let mut positions = [
+            vec3(-1.0, t, 0.0),
+            vec3(1.0, t, 0.0),
+            vec3(-1.0, -t, 0.0),
+            vec3(1.0, -t, 0.0),
+            vec3(0.0, -1.0, t),
+            vec3(0.0, 1.0, t),
+            vec3(0.0, -1.0, -t),
+            vec3(0.0, 1.0, -t),
+            vec3(t, 0.0, -1.0),
+            vec3(t, 0.0, 1.0),
+            vec3(-t, 0.0, -1.0),
+            vec3(-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|p| p.normalize())
+        .collect::<Vec<_>>();
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+        for _ in 0..subdivisions {
+            let mut midpoints = std::collections::HashMap::new();
+            let mut midpoint = |a: u32, b: u32, positions: &mut Vec<Vec3>| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    positions.push((positions[a as usize] + positions[b as usize]).normalize());
+                    positions.len() as u32 - 1
+                })
+            };
+            let mut new_triangles = Vec::with_capacity(4 * triangles.len());
+            for [a, b, c] in triangles {
+                let ab = midpoint(a, b, &mut positions);
+                let bc = midpoint(b, c, &mut positions);
+                let ca = midpoint(c, a, &mut positions);
+                new_triangles.extend_from_slice(&[
+                    [a, ab, ca],
+                    [b, bc, ab],
+                    [c, ca, bc],
+                    [ab, bc, ca],
+                ]);
+            }
+            triangles = new_triangles;
+        }
+
+        let mut uvs = positions
+            .iter()
+            .map(|p| {
+                vec2(
+                    0.5 + p.z.atan2(p.x) / std::f32::consts::TAU,
+                    0.5 + p.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI,
+                )
+            })
+            .collect::<Vec<_>>();
+        // Duplicate the vertices of the triangles crossing the seam and the vertices at the poles where the u coordinate is undefined
+        let mut seam_vertices = std::collections::HashMap::new();
+        for triangle in triangles.iter_mut() {
+            let us = triangle.map(|v| uvs[v as usize].x);
+            let crosses_seam = us.iter().fold(f32::MIN, |m, u| m.max(*u))
+                - us.iter().fold(f32::MAX, |m, u| m.min(*u))
+                > 0.5;
+            for i in 0..3 {
+                let v = triangle[i] as usize;
+                let pole = positions[v].y.abs() > 0.99999;
+                let mut u = us[i];
+                if pole {
+                    let (a, b) = (us[(i + 1) % 3], us[(i + 2) % 3]);
+                    let (a, b) = if (a - b).abs() > 0.5 {
+                        (
+                            if a < 0.5 { a + 1.0 } else { a },
+                            if b < 0.5 { b + 1.0 } else { b },
+                        )
+                    } else {
+                        (a, b)
+                    };
+                    u = 0.5 * (a + b);
+                } else if crosses_seam && u < 0.5 {
+                    u += 1.0;
+                }
+                if u != us[i] {
+                    triangle[i] = *seam_vertices.entry((v, u.to_bits())).or_insert_with(|| {
+                        positions.push(positions[v]);
+                        uvs.push(vec2(u, uvs[v].y));
+                        positions.len() as u32 - 1
+                    });
+                }
+            }
+        }
+        CpuMesh {
+            normals: Some(positions.clone()),
+            positions: Positions::F32(positions),
+            indices: Indices::U32(triangles.into_iter().flatten().collect()),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Averages the normals of the first and last segment of a full turn created by [MeshBuilder::lathe], which are separate vertices because of the uv seam.
+///
+fn weld_normals(mesh: &mut CpuMesh, segments: u32, profile_length: u32) {
+    if let Some(normals) = &mut mesh.normals {
+        for j in 0..profile_length as usize {
+            let (first, last) = (j, segments as usize * profile_length as usize + j);
+            let sum = normals[first] + normals[last];
+            if sum.magnitude2() > 0.000001 {
+                normals[first] = sum.normalize();
+                normals[last] = normals[first];
+            }
+        }
+    }
+}
+
+///
+/// Returns the accumulated distances normalized to be between 0 and 1, starting with 0.
+///
+fn distances(lengths: impl Iterator<Item = f32>) -> Vec<f32> {
+    let mut result = vec![0.0];
+    for length in lengths {
+        result.push(result.last().unwrap() + length);
+    }
+    let total = *result.last().unwrap();
+    if total > 0.0 {
+        result.iter_mut().for_each(|d| *d /= total);
+    }
+    result
+}
+
+fn perpendicular(v: Vec3) -> Vec3 {
+    let axis = if v.x.abs() < 0.9 {
+        vec3(1.0, 0.0, 0.0)
+    } else {
+        vec3(0.0, 1.0, 0.0)
+    };
+    v.cross(axis).normalize()
+}
+
+fn signed_area(polygon: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    0.5 * area
+}
+
+///
+/// Triangulates a simple counter-clockwise polygon using ear clipping.
+///
+fn triangulate(polygon: &[Vec2]) -> Vec<[u32; 3]> {
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2));
+    let cross = |o: Vec2, a: Vec2, b: Vec2| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|i| {
+            let (a, b, c) = (
+                polygon[remaining[(i + n - 1) % n]],
+                polygon[remaining[*i]],
+                polygon[remaining[(i + 1) % n]],
+            );
+            cross(a, b, c) > 0.0
+                && remaining.iter().all(|j| {
+                    let p = polygon[*j];
+                    p == a
+                        || p == b
+                        || p == c
+                        || cross(a, b, p) < 0.0
+                        || cross(b, c, p) < 0.0
+                        || cross(c, a, p) < 0.0
+                })
+        });
+        // Fall back to clipping any vertex if the polygon is not simple
+        let i = ear.unwrap_or(0);
+        triangles.push([
+            remaining[(i + n - 1) % n] as u32,
+            remaining[i] as u32,
+            remaining[(i + 1) % n] as u32,
+        ]);
+        remaining.remove(i);
+    }
+    if remaining.len() == 3 {
+        triangles.push([
+            remaining[0] as u32,
+            remaining[1] as u32,
+            remaining[2] as u32,
+        ]);
+    }
+    triangles
+}
+
+fn append(
+    mesh: &mut CpuMesh,
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<Vec2>,
+    triangles: &[[u32; 3]],
+    reverse: bool,
+) {
+    let offset = mesh.positions.len() as u32;
+    if let Indices::U32(indices) = &mut mesh.indices {
+        for [a, b, c] in triangles {
+            if reverse {
+                indices.extend_from_slice(&[offset + a, offset + c, offset + b]);
+            } else {
+                indices.extend_from_slice(&[offset + a, offset + b, offset + c]);
+            }
+        }
+    }
+    if let Positions::F32(p) = &mut mesh.positions {
+        p.extend(positions);
+    }
+    if let Some(n) = &mut mesh.normals {
+        n.extend(normals);
+    }
+    if let Some(u) = &mut mesh.uvs {
+        u.extend(uvs);
+    }
+}
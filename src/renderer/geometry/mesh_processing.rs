@@ -0,0 +1,694 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+///
+/// Geometry processing functionality for a [CpuMesh] which can be applied before the mesh is transferred to the GPU.
+///
+pub trait MeshProcessing {
+    ///
+    /// Returns a simplified version of this mesh with approximately the given ratio of the triangles left, for example 0.5 to halve the number of triangles.
+    /// The mesh is simplified by repeatedly collapsing the edge which changes the surface the least, measured using quadric error metrics.
+    /// Boundary edges, including the edges along uv seams where the vertices are split, are preserved as much as possible.
+    /// Collapses which would flip a triangle, create a degenerate triangle or make the mesh non-manifold are skipped, so fewer triangles than requested may be removed.
+    /// The remaining vertices keep their normals, tangents, uv coordinates and colors.
+    ///
+    fn simplify(&self, target_ratio: f32) -> CpuMesh;
+
+    ///
+    /// Returns a smoother version of this mesh where each triangle is split into four triangles using Loop subdivision.
+    /// The vertices are moved towards a smooth surface, except along the boundaries, including uv seams where the vertices are split, which are subdivided as curves.
+    /// The uv coordinates and colors are interpolated linearly, the normals are recomputed if this mesh has normals and the tangents are removed
+    /// since they are no longer valid, use [CpuMesh::compute_tangents] to compute them again.
+    ///
+    fn subdivide_loop(&self) -> CpuMesh;
+
+    ///
+    /// Recomputes the normals of this mesh so that the surface is smooth, except at the edges where the angle between the neighbouring triangles is larger than the given threshold.
+    /// The vertices along such sharp edges are split, so the returned mesh can contain more vertices than this mesh.
+    /// The tangents are removed since they are no longer valid, use [CpuMesh::compute_tangents] to compute them again.
+    ///
+    fn recompute_normals_smooth(&self, angle_threshold: impl Into<Radians>) -> CpuMesh;
+}
+
+impl MeshProcessing for CpuMesh {
+    fn simplify(&self, target_ratio: f32) -> CpuMesh {
+        let mut positions: Vec<Vec3> = self.positions.to_f32();
+        let mut triangles: Vec<[usize; 3]> = indices_u32(self)
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect();
+        let target = (triangles.len() as f32 * target_ratio.clamp(0.0, 1.0)).round() as usize;
+
+        let mut quadrics = vec![Quadric::default(); positions.len()];
+        let mut vertex_triangles = vec![Vec::new(); positions.len()];
+        let mut edge_count = std::collections::HashMap::new();
+        for (t, triangle) in triangles.iter().enumerate() {
+            let [a, b, c] = *triangle;
+            let normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+            let area = normal.magnitude();
+            if area > 0.0 {
+                let q = Quadric::from_plane(normal / area, positions[a], area as f64);
+                quadrics[a].add(&q);
+                quadrics[b].add(&q);
+                quadrics[c].add(&q);
+            }
+            for v in triangle {
+                vertex_triangles[*v].push(t);
+            }
+            for (v0, v1) in [(a, b), (b, c), (c, a)] {
+                *edge_count.entry((v0.min(v1), v0.max(v1))).or_insert(0) += 1;
+            }
+        }
+
+        // Add planes perpendicular to the boundary edges to preserve the boundaries
+        for [a, b, c] in triangles.iter().copied() {
+            let normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+            for (v0, v1) in [(a, b), (b, c), (c, a)] {
+                if edge_count[&(v0.min(v1), v0.max(v1))] == 1 {
+                    let edge = positions[v1] - positions[v0];
+                    let plane_normal = edge.cross(normal);
+                    if plane_normal.magnitude2() > 0.0 {
+                        let q = Quadric::from_plane(
+                            plane_normal.normalize(),
+                            positions[v0],
+                            BOUNDARY_WEIGHT * edge.magnitude2() as f64,
+                        );
+                        quadrics[v0].add(&q);
+                        quadrics[v1].add(&q);
+                    }
+                }
+            }
+        }
+
+        let mut heap = BinaryHeap::new();
+        let mut versions = vec![0u32; positions.len()];
+        for (v0, v1) in edge_count.keys() {
+            heap.push(collapse(&quadrics, &positions, &versions, *v0, *v1));
+        }
+
+        let mut removed = vec![false; triangles.len()];
+        let mut triangle_count = triangles.len();
+        while triangle_count > target {
+            let candidate = match heap.pop() {
+                Some(candidate) => candidate,
+                None => break,
+            };
+            let (v0, v1) = (candidate.v0, candidate.v1);
+            if versions[v0] != candidate.versions.0 || versions[v1] != candidate.versions.1 {
+                // The edge has changed since it was added to the heap
+                continue;
+            }
+            if !keeps_manifold(&triangles, &removed, &vertex_triangles, v0, v1) {
+                continue;
+            }
+            if flips_triangle(
+                &positions,
+                &triangles,
+                &removed,
+                &vertex_triangles,
+                v0,
+                v1,
+                candidate.position,
+            ) || flips_triangle(
+                &positions,
+                &triangles,
+                &removed,
+                &vertex_triangles,
+                v1,
+                v0,
+                candidate.position,
+            ) {
+                continue;
+            }
+
+            // Collapse v1 into v0
+            positions[v0] = candidate.position;
+            let q = quadrics[v1];
+            quadrics[v0].add(&q);
+            versions[v0] += 1;
+            versions[v1] += 1;
+            let moved = std::mem::take(&mut vertex_triangles[v1]);
+            for t in moved {
+                if removed[t] {
+                    continue;
+                }
+                if triangles[t].contains(&v0) {
+                    removed[t] = true;
+                    triangle_count -= 1;
+                } else {
+                    for v in triangles[t].iter_mut() {
+                        if *v == v1 {
+                            *v = v0;
+                        }
+                    }
+                    vertex_triangles[v0].push(t);
+                }
+            }
+            vertex_triangles[v0].retain(|t| !removed[*t]);
+
+            let neighbours: HashSet<usize> = vertex_triangles[v0]
+                .iter()
+                .flat_map(|t| triangles[*t])
+                .filter(|v| *v != v0)
+                .collect();
+            for v in neighbours {
+                heap.push(collapse(&quadrics, &positions, &versions, v0, v));
+            }
+        }
+
+        // Remove the unused vertices
+        let mut remap = vec![u32::MAX; positions.len()];
+        let mut used = Vec::new();
+        let mut indices = Vec::with_capacity(triangle_count * 3);
+        for (triangle, _) in triangles.iter().zip(removed.iter()).filter(|(_, r)| !**r) {
+            for v in triangle {
+                if remap[*v] == u32::MAX {
+                    remap[*v] = used.len() as u32;
+                    used.push(*v);
+                }
+                indices.push(remap[*v]);
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(select(&positions, &used)),
+            indices: Indices::U32(indices),
+            normals: self.normals.as_ref().map(|data| select(data, &used)),
+            tangents: self.tangents.as_ref().map(|data| select(data, &used)),
+            uvs: self.uvs.as_ref().map(|data| select(data, &used)),
+            colors: self.colors.as_ref().map(|data| select(data, &used)),
+            ..Default::default()
+        }
+    }
+
+    fn subdivide_loop(&self) -> CpuMesh {
+        let positions: Vec<Vec3> = self.positions.to_f32();
+        let indices = indices_u32(self);
+
+        // Find the vertices opposite to each edge and the new vertex created on each edge
+        let mut edges: HashMap<(u32, u32), (u32, Vec<u32>)> = HashMap::new();
+        let mut edge_vertices = Vec::new();
+        for triangle in indices.chunks_exact(3) {
+            for i in 0..3 {
+                let (a, b, opposite) = (triangle[i], triangle[(i + 1) % 3], triangle[(i + 2) % 3]);
+                let key = (a.min(b), a.max(b));
+                let edge = edges.entry(key).or_insert_with(|| {
+                    edge_vertices.push(key);
+                    (
+                        (positions.len() + edge_vertices.len() - 1) as u32,
+                        Vec::new(),
+                    )
+                });
+                edge.1.push(opposite);
+            }
+        }
+
+        let mut neighbours = vec![HashSet::new(); positions.len()];
+        let mut boundary_neighbours = vec![Vec::new(); positions.len()];
+        for (&(a, b), (_, opposite)) in edges.iter() {
+            neighbours[a as usize].insert(b);
+            neighbours[b as usize].insert(a);
+            if opposite.len() == 1 {
+                boundary_neighbours[a as usize].push(b);
+                boundary_neighbours[b as usize].push(a);
+            }
+        }
+
+        let mut new_positions = Vec::with_capacity(positions.len() + edge_vertices.len());
+        for (i, p) in positions.iter().enumerate() {
+            new_positions.push(if !boundary_neighbours[i].is_empty() {
+                if boundary_neighbours[i].len() == 2 {
+                    0.75 * *p
+                        + 0.125
+                            * (positions[boundary_neighbours[i][0] as usize]
+                                + positions[boundary_neighbours[i][1] as usize])
+                } else {
+                    // A corner or non-manifold vertex is kept in place
+                    *p
+                }
+            } else if neighbours[i].is_empty() {
+                *p
+            } else {
+                let n = neighbours[i].len() as f32;
+                let beta = if neighbours[i].len() == 3 {
+                    3.0 / 16.0
+                } else {
+                    3.0 / (8.0 * n)
+                };
+                let sum = neighbours[i]
+                    .iter()
+                    .fold(vec3(0.0, 0.0, 0.0), |sum, v| sum + positions[*v as usize]);
+                (1.0 - n * beta) * *p + beta * sum
+            });
+        }
+        for key in edge_vertices.iter() {
+            let (a, b) = (positions[key.0 as usize], positions[key.1 as usize]);
+            let opposite = &edges[key].1;
+            new_positions.push(if opposite.len() == 2 {
+                0.375 * (a + b)
+                    + 0.125 * (positions[opposite[0] as usize] + positions[opposite[1] as usize])
+            } else {
+                0.5 * (a + b)
+            });
+        }
+
+        let mut new_indices = Vec::with_capacity(indices.len() * 4);
+        for triangle in indices.chunks_exact(3) {
+            let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+            let edge_vertex = |v0: u32, v1: u32| edges[&(v0.min(v1), v0.max(v1))].0;
+            let (ab, bc, ca) = (edge_vertex(a, b), edge_vertex(b, c), edge_vertex(c, a));
+            new_indices.extend_from_slice(&[a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca]);
+        }
+
+        let mut mesh = CpuMesh {
+            positions: Positions::F32(new_positions),
+            indices: Indices::U32(new_indices),
+            uvs: self
+                .uvs
+                .as_ref()
+                .map(|data| interpolate(data, &edge_vertices, |a, b| 0.5 * (a + b))),
+            colors: self.colors.as_ref().map(|data| {
+                interpolate(data, &edge_vertices, |a, b| {
+                    Color::new(
+                        ((a.r as u16 + b.r as u16) / 2) as u8,
+                        ((a.g as u16 + b.g as u16) / 2) as u8,
+                        ((a.b as u16 + b.b as u16) / 2) as u8,
+                        ((a.a as u16 + b.a as u16) / 2) as u8,
+                    )
+                })
+            }),
+            ..Default::default()
+        };
+        if self.normals.is_some() {
+            mesh.compute_normals();
+        }
+        mesh
+    }
+
+    fn recompute_normals_smooth(&self, angle_threshold: impl Into<Radians>) -> CpuMesh {
+        let cos_threshold = angle_threshold.into().0.cos();
+        let positions: Vec<Vec3> = self.positions.to_f32();
+        let indices = indices_u32(self);
+        let face_normals: Vec<Vec3> = indices
+            .chunks_exact(3)
+            .map(|t| {
+                let (a, b, c) = (
+                    positions[t[0] as usize],
+                    positions[t[1] as usize],
+                    positions[t[2] as usize],
+                );
+                // Not normalized, so the normals are weighted by the area of the triangles
+                (b - a).cross(c - a)
+            })
+            .collect();
+        let mut vertex_faces = vec![Vec::new(); positions.len()];
+        for (f, triangle) in indices.chunks_exact(3).enumerate() {
+            for v in triangle {
+                vertex_faces[*v as usize].push(f);
+            }
+        }
+
+        // Each corner of a triangle is smoothed with the neighbouring triangles within the angle threshold,
+        // and corners of the same vertex which are smoothed with the same triangles share a vertex in the result
+        let mut vertices: HashMap<(u32, Vec<usize>), u32> = HashMap::new();
+        let mut used = Vec::new();
+        let mut normals = Vec::new();
+        let mut new_indices = Vec::with_capacity(indices.len());
+        for (f, triangle) in indices.chunks_exact(3).enumerate() {
+            let normal = face_normals[f].normalize();
+            for v in triangle {
+                let smooth_faces: Vec<usize> = vertex_faces[*v as usize]
+                    .iter()
+                    .copied()
+                    .filter(|g| {
+                        *g == f || face_normals[*g].normalize().dot(normal) >= cos_threshold
+                    })
+                    .collect();
+                let index = *vertices
+                    .entry((*v, smooth_faces))
+                    .or_insert_with_key(|(_, faces)| {
+                        let sum = faces
+                            .iter()
+                            .fold(vec3(0.0, 0.0, 0.0), |sum, g| sum + face_normals[*g]);
+                        normals.push(if sum.magnitude2() > 0.0 {
+                            sum.normalize()
+                        } else {
+                            normal
+                        });
+                        used.push(*v as usize);
+                        (used.len() - 1) as u32
+                    });
+                new_indices.push(index);
+            }
+        }
+
+        CpuMesh {
+            positions: Positions::F32(select(&positions, &used)),
+            indices: Indices::U32(new_indices),
+            normals: Some(normals),
+            uvs: self.uvs.as_ref().map(|data| select(data, &used)),
+            colors: self.colors.as_ref().map(|data| select(data, &used)),
+            ..Default::default()
+        }
+    }
+}
+
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+// The minimum ratio between twice the area and the sum of the squared edge lengths of a triangle after a collapse
+const MIN_TRIANGLE_QUALITY: f32 = 0.001;
+
+fn select<T: Copy>(data: &[T], vertices: &[usize]) -> Vec<T> {
+    vertices.iter().map(|v| data[*v]).collect()
+}
+
+///
+/// Returns the given vertex data followed by the data for each of the given edges interpolated using the given function.
+///
+fn interpolate<T: Copy>(data: &[T], edges: &[(u32, u32)], mix: impl Fn(T, T) -> T) -> Vec<T> {
+    let mut result = data.to_vec();
+    result.extend(
+        edges
+            .iter()
+            .map(|(a, b)| mix(data[*a as usize], data[*b as usize])),
+    );
+    result
+}
+
+fn indices_u32(cpu_mesh: &CpuMesh) -> Vec<u32> {
+    match &cpu_mesh.indices {
+        Indices::U8(ind) => ind.iter().map(|i| *i as u32).collect(),
+        Indices::U16(ind) => ind.iter().map(|i| *i as u32).collect(),
+        Indices::U32(ind) => ind.clone(),
+        Indices::None => (0..cpu_mesh.positions.len() as u32).collect(),
+    }
+}
+
+///
+/// A symmetric 4x4 matrix which measures the sum of squared distances from a point to a set of planes.
+///
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: Vec3, point: Vec3, weight: f64) -> Self {
+        let (a, b, c) = (normal.x as f64, normal.y as f64, normal.z as f64);
+        let d = -(a * point.x as f64 + b * point.y as f64 + c * point.z as f64);
+        Self(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|v| v * weight),
+        )
+    }
+
+    fn add(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += b;
+        }
+    }
+
+    fn error(&self, p: Vec3) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (p.x as f64, p.y as f64, p.z as f64);
+        q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9]
+    }
+}
+
+struct Collapse {
+    cost: f64,
+    v0: usize,
+    v1: usize,
+    versions: (u32, u32),
+    position: Vec3,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed, so the cheapest collapse is at the top of the heap
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+fn collapse(
+    quadrics: &[Quadric],
+    positions: &[Vec3],
+    versions: &[u32],
+    v0: usize,
+    v1: usize,
+) -> Collapse {
+    let mut q = quadrics[v0];
+    q.add(&quadrics[v1]);
+    let (cost, position) = [
+        positions[v0],
+        positions[v1],
+        0.5 * (positions[v0] + positions[v1]),
+    ]
+    .into_iter()
+    .map(|p| (q.error(p), p))
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .unwrap();
+    Collapse {
+        cost,
+        v0,
+        v1,
+        versions: (versions[v0], versions[v1]),
+        position,
+    }
+}
+
+///
+/// Returns whether moving the vertex to the given position flips any of its triangles that are not removed by collapsing the edge to the other vertex.
+///
+fn flips_triangle(
+    positions: &[Vec3],
+    triangles: &[[usize; 3]],
+    removed: &[bool],
+    vertex_triangles: &[Vec<usize>],
+    vertex: usize,
+    other: usize,
+    position: Vec3,
+) -> bool {
+    vertex_triangles[vertex].iter().any(|t| {
+        let triangle = triangles[*t];
+        if removed[*t] || triangle.contains(&other) {
+            return false;
+        }
+        let p = triangle.map(|v| positions[v]);
+        let moved = triangle.map(|v| if v == vertex { position } else { positions[v] });
+        let before = (p[1] - p[0]).cross(p[2] - p[0]);
+        let after = (moved[1] - moved[0]).cross(moved[2] - moved[0]);
+        // Also rejects triangles which are rotated close to 90 degrees or become slivers
+        let edges = (moved[1] - moved[0]).magnitude2()
+            + (moved[2] - moved[1]).magnitude2()
+            + (moved[0] - moved[2]).magnitude2();
+        before.dot(after) <= 0.25 * before.magnitude() * after.magnitude()
+            || after.magnitude() <= MIN_TRIANGLE_QUALITY * edges
+    })
+}
+
+///
+/// Returns whether collapsing the edge between the two vertices keeps the mesh manifold,
+/// which is the case if the only vertices connected to both are the ones opposite to the edge.
+///
+fn keeps_manifold(
+    triangles: &[[usize; 3]],
+    removed: &[bool],
+    vertex_triangles: &[Vec<usize>],
+    v0: usize,
+    v1: usize,
+) -> bool {
+    let neighbours = |v: usize| -> HashSet<usize> {
+        vertex_triangles[v]
+            .iter()
+            .filter(|t| !removed[**t])
+            .flat_map(|t| triangles[*t])
+            .filter(|n| *n != v)
+            .collect()
+    };
+    let edge_triangles = vertex_triangles[v0]
+        .iter()
+        .filter(|t| !removed[**t] && triangles[**t].contains(&v1))
+        .count();
+    neighbours(v0).intersection(&neighbours(v1)).count() == edge_triangles
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A subdivided unit square in the xy plane with two triangles in each of the n x n cells
+    fn plane(n: u32) -> CpuMesh {
+        let mut positions = Vec::new();
+        for y in 0..=n {
+            for x in 0..=n {
+                positions.push(vec3(x as f32 / n as f32, y as f32 / n as f32, 0.0));
+            }
+        }
+        let mut indices = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                let i = y * (n + 1) + x;
+                indices.extend_from_slice(&[i, i + 1, i + n + 2, i, i + n + 2, i + n + 1]);
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            ..Default::default()
+        }
+    }
+
+    // A closed cube from -1 to 1 where each side is subdivided into n x n cells and the vertices are shared between the sides
+    fn cube(n: u32) -> CpuMesh {
+        let mut positions: Vec<Vec3> = Vec::new();
+        let mut lookup = HashMap::new();
+        let mut indices = Vec::new();
+        for (u, v, w) in [
+            (Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()),
+            (Vec3::unit_y(), Vec3::unit_z(), Vec3::unit_x()),
+            (Vec3::unit_z(), Vec3::unit_x(), Vec3::unit_y()),
+        ] {
+            for sign in [-1.0, 1.0] {
+                let mut index = |x: u32, y: u32| {
+                    let p = (2.0 * x as f32 / n as f32 - 1.0) * u
+                        + (2.0 * y as f32 / n as f32 - 1.0) * v
+                        + sign * w;
+                    let key = (p * n as f32).map(|c| c.round() as i32);
+                    *lookup.entry((key.x, key.y, key.z)).or_insert_with(|| {
+                        positions.push(p);
+                        positions.len() as u32 - 1
+                    })
+                };
+                for y in 0..n {
+                    for x in 0..n {
+                        let q = [
+                            index(x, y),
+                            index(x + 1, y),
+                            index(x + 1, y + 1),
+                            index(x, y + 1),
+                        ];
+                        if sign > 0.0 {
+                            indices.extend_from_slice(&[q[0], q[1], q[2], q[0], q[2], q[3]]);
+                        } else {
+                            indices.extend_from_slice(&[q[0], q[2], q[1], q[0], q[3], q[2]]);
+                        }
+                    }
+                }
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            ..Default::default()
+        }
+    }
+
+    fn triangles(mesh: &CpuMesh) -> Vec<[Vec3; 3]> {
+        let positions = mesh.positions.to_f32();
+        indices_u32(mesh)
+            .chunks_exact(3)
+            .map(|t| [0, 1, 2].map(|i| positions[t[i] as usize]))
+            .collect()
+    }
+
+    fn assert_triangle_count(mesh: &CpuMesh, target: usize) {
+        let count = triangles(mesh).len();
+        assert!(
+            count <= target && count + 2 >= target,
+            "{} triangles, expected {}",
+            count,
+            target
+        );
+    }
+
+    fn assert_not_degenerate(mesh: &CpuMesh, min_area: f32) {
+        for [a, b, c] in triangles(mesh) {
+            let area = 0.5 * (b - a).cross(c - a).magnitude();
+            assert!(area > min_area, "degenerate triangle {:?}", [a, b, c]);
+        }
+    }
+
+    #[test]
+    fn test_simplify_plane() {
+        let mesh = plane(10);
+        for (ratio, target) in [(0.5, 100), (0.25, 50), (0.1, 20), (0.02, 4)] {
+            let simplified = mesh.simplify(ratio);
+            assert_triangle_count(&simplified, target);
+            assert_not_degenerate(&simplified, 0.001);
+
+            // The boundary is preserved if the corners are kept, all vertices are inside the square and the area is unchanged
+            let positions = simplified.positions.to_f32();
+            for corner in [
+                vec3(0.0, 0.0, 0.0),
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                vec3(1.0, 1.0, 0.0),
+            ] {
+                assert!(positions.contains(&corner), "missing corner {:?}", corner);
+            }
+            for p in positions.iter() {
+                assert!((0.0..=1.0).contains(&p.x) && (0.0..=1.0).contains(&p.y) && p.z == 0.0);
+            }
+            let area = triangles(&simplified)
+                .iter()
+                .map(|[a, b, c]| 0.5 * (b - a).cross(c - a).z)
+                .sum::<f32>();
+            assert!((area - 1.0).abs() < 0.0001, "area {}", area);
+        }
+    }
+
+    #[test]
+    fn test_simplify_cube() {
+        let mesh = cube(6);
+        assert_eq!(triangles(&mesh).len(), 432);
+        for (ratio, target) in [(0.5, 216), (0.25, 108), (0.1, 43), (0.05, 22)] {
+            let simplified = mesh.simplify(ratio);
+            assert_triangle_count(&simplified, target);
+            assert_not_degenerate(&simplified, 0.01);
+
+            // The mesh is still closed and all vertices are on the surface of the cube
+            let mut edges = HashMap::new();
+            for t in indices_u32(&simplified).chunks_exact(3) {
+                for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                    *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+                }
+            }
+            assert!(edges.values().all(|count| *count == 2));
+            for p in simplified.positions.to_f32() {
+                assert!((p.x.abs().max(p.y.abs()).max(p.z.abs()) - 1.0).abs() < 0.0001);
+            }
+        }
+    }
+}
@@ -164,6 +164,13 @@ impl Geometry for Outline {
             (center + 0.5 * vec2(self.width, self.height)).extend(0.0),
         ])
     }
+
+    fn triangle_count(&self) -> usize {
+        self.top.triangle_count()
+            + self.right.triangle_count()
+            + self.bottom.triangle_count()
+            + self.left.triangle_count()
+    }
 }
 
 impl<'a> IntoIterator for &'a Outline {
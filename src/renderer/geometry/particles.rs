@@ -185,7 +185,10 @@ impl ParticleSystem {
                 return;
             }
         }
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
         program.use_uniform("modelMatrix", self.transformation);
         program.use_uniform("acceleration", self.acceleration);
         program.use_uniform("time", self.time);
@@ -218,7 +221,7 @@ impl ParticleSystem {
 
     fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
         format!(
-            "#define PARTICLES\n{}{}{}{}{}{}{}",
+            "#define PARTICLES\n{}{}{}{}{}{}{}{}",
             if required_attributes.normal {
                 "#define USE_NORMALS\n"
             } else {
@@ -250,6 +253,11 @@ impl ParticleSystem {
             } else {
                 ""
             },
+            if required_attributes.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
             include_str!("../../core/shared.frag"),
             include_str!("shaders/mesh.vert"),
         )
@@ -317,4 +325,8 @@ impl Geometry for ParticleSystem {
     fn animate(&mut self, time: f32) {
         self.time = time;
     }
+
+    fn triangle_count(&self) -> usize {
+        self.base_mesh.triangle_count() * self.instance_count as usize
+    }
 }
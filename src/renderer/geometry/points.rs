@@ -0,0 +1,359 @@
+use crate::core::*;
+use crate::renderer::*;
+
+// The maximum number of points in a chunk which is culled as a whole
+const CHUNK_SIZE: usize = 16384;
+// The data of each point is the position (3 x f32), the color (4 x u8) and the size (f32)
+const POINT_STRIDE: u32 = 20;
+
+struct PointChunk {
+    first: u32,
+    count: u32,
+    aabb: AxisAlignedBoundingBox,
+}
+
+///
+/// A point cloud [Geometry] which renders each point as a point sprite with a color and a size, for example a LiDAR scan or the result of photogrammetry.
+/// It is able to render millions of points, since the data of all points is stored interleaved in a single buffer and each point is a single vertex.
+/// The points are sorted into spatially coherent chunks, which are culled against the view frustum of the camera, so only the visible part of a large point cloud is rendered.
+///
+/// The points are rendered as camera facing discs, so lit materials use a normal pointing towards the camera.
+/// For rendering each point as a mesh, for example a small sphere, use an [InstancedMesh] instead.
+///
+/// This geometry is named `Points` and not `PointCloud` to avoid a clash with the [PointCloud] from three-d-asset,
+/// which is re-exported by this crate and holds the CPU-side data the points are created from.
+///
+pub struct Points {
+    context: Context,
+    buffer: VertexBuffer,
+    chunks: Vec<PointChunk>,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+    point_count: u32,
+    has_colors: bool,
+    /// The size of the points, multiplied by the size of each point. The size is in pixels unless [Points::size_attenuation] is enabled.
+    pub point_size: f32,
+    /// Whether the size of the points is in world units, which makes points further away from the camera appear smaller, or in pixels.
+    pub size_attenuation: bool,
+    /// Whether the points are rendered as round discs or as squares, which is slightly faster.
+    pub round: bool,
+}
+
+impl Points {
+    ///
+    /// Creates new points from the positions and the optional colors of the given [PointCloud], where all points have the size 1.
+    ///
+    pub fn new(context: &Context, point_cloud: &PointCloud) -> Self {
+        Self::new_with_sizes(context, point_cloud, &[])
+    }
+
+    ///
+    /// Creates new points from the positions and the optional colors of the given [PointCloud] and the given size of each point.
+    /// If the sizes are empty, all points have the size 1.
+    ///
+    /// # Panic
+    /// Will panic if the sizes are not empty and the number of sizes does not match the number of points.
+    ///
+    pub fn new_with_sizes(context: &Context, point_cloud: &PointCloud, sizes: &[f32]) -> Self {
+        let positions = point_cloud.positions.to_f32();
+        if !sizes.is_empty() && sizes.len() != positions.len() {
+            panic!(
+                "the number of sizes ({}) does not match the number of points ({})",
+                sizes.len(),
+                positions.len()
+            );
+        }
+        if let Some(colors) = &point_cloud.colors {
+            if colors.len() != positions.len() {
+                panic!(
+                    "the number of colors ({}) does not match the number of points ({})",
+                    colors.len(),
+                    positions.len()
+                );
+            }
+        }
+
+        let mut order: Vec<u32> = (0..positions.len() as u32).collect();
+        let mut chunks = Vec::new();
+        split_into_chunks(&positions, &mut order, 0, &mut chunks);
+
+        let mut data = Vec::with_capacity(positions.len() * POINT_STRIDE as usize);
+        for i in order {
+            let i = i as usize;
+            let position = positions[i];
+            let color = point_cloud
+                .colors
+                .as_ref()
+                .map(|colors| colors[i])
+                .unwrap_or(Color::WHITE);
+            let size = sizes.get(i).copied().unwrap_or(1.0);
+            data.extend_from_slice(&position.x.to_ne_bytes());
+            data.extend_from_slice(&position.y.to_ne_bytes());
+            data.extend_from_slice(&position.z.to_ne_bytes());
+            data.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+            data.extend_from_slice(&size.to_ne_bytes());
+        }
+
+        Self {
+            context: context.clone(),
+            buffer: VertexBuffer::new_with_data(context, &data),
+            chunks,
+            aabb: AxisAlignedBoundingBox::new_with_positions(&positions),
+            transformation: Mat4::identity(),
+            point_count: positions.len() as u32,
+            has_colors: point_cloud.colors.is_some(),
+            point_size: 2.0,
+            size_attenuation: false,
+            round: true,
+        }
+    }
+
+    ///
+    /// Returns the number of points.
+    ///
+    pub fn point_count(&self) -> u32 {
+        self.point_count
+    }
+
+    ///
+    /// Returns the number of chunks the points are sorted into, each chunk is culled against the view frustum as a whole.
+    ///
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all points.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all points.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    fn vertex_shader_source(&self, required_attributes: FragmentAttributes) -> String {
+        format!(
+            "{}{}{}{}{}{}",
+            if required_attributes.normal {
+                "#define USE_NORMALS\n"
+            } else {
+                ""
+            },
+            if required_attributes.tangents {
+                "#define USE_TANGENTS\n"
+            } else {
+                ""
+            },
+            if required_attributes.uv {
+                "#define USE_UVS\n"
+            } else {
+                ""
+            },
+            if self.has_colors {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            if required_attributes.instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/points.vert"),
+        )
+    }
+
+    fn fragment_shader_source(&self, source: String) -> String {
+        if self.round {
+            // The main function of the material is renamed, so it can be called after discarding the fragments outside the disc
+            format!(
+                "#define main material_main\n{}\n#undef main\n{}",
+                source,
+                include_str!("shaders/points.frag")
+            )
+        } else {
+            source
+        }
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
+        let viewport = camera.viewport();
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform_if_required("cameraPosition", camera.position());
+        program.use_uniform("pointSize", self.point_size);
+        program.use_uniform(
+            "projectionScale",
+            if self.size_attenuation {
+                0.5 * viewport.height as f32 * camera.projection()[1][1]
+            } else {
+                0.0
+            },
+        );
+        let render_states = RenderStates {
+            draw_primitive: DrawPrimitive::Points,
+            ..render_states
+        };
+
+        // Draw consecutive visible chunks in one draw call
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        for chunk in self.chunks.iter() {
+            let mut aabb = chunk.aabb;
+            aabb.transform(&self.transformation);
+            if !camera.in_frustum(&aabb) {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some((first, count)) if *first + *count == chunk.first => *count += chunk.count,
+                _ => ranges.push((chunk.first, chunk.count)),
+            }
+        }
+        for (first, count) in ranges {
+            // The attributes are disabled after each draw call, so they are set again for each range
+            program.use_interleaved_vertex_attribute(
+                "position",
+                &self.buffer,
+                VertexAttributeLayout {
+                    component_type: AttributeComponentType::F32,
+                    components: 3,
+                    offset: 0,
+                    stride: POINT_STRIDE,
+                },
+            );
+            if self.has_colors {
+                program.use_interleaved_vertex_attribute(
+                    "color",
+                    &self.buffer,
+                    VertexAttributeLayout {
+                        component_type: AttributeComponentType::U8Normalized,
+                        components: 4,
+                        offset: 12,
+                        stride: POINT_STRIDE,
+                    },
+                );
+            }
+            program.use_interleaved_vertex_attribute(
+                "size",
+                &self.buffer,
+                VertexAttributeLayout {
+                    component_type: AttributeComponentType::F32,
+                    components: 1,
+                    offset: 16,
+                    stride: POINT_STRIDE,
+                },
+            );
+            program.draw_subset_of_arrays(render_states, viewport, first, count);
+        }
+    }
+}
+
+// Sorts the points into chunks of at most CHUNK_SIZE points by recursively splitting the points at the median along the longest axis of their bounding box
+fn split_into_chunks(
+    positions: &[Vec3],
+    order: &mut [u32],
+    first: u32,
+    chunks: &mut Vec<PointChunk>,
+) {
+    if order.is_empty() {
+        return;
+    }
+    let aabb = AxisAlignedBoundingBox::new_with_positions(
+        &order
+            .iter()
+            .map(|i| positions[*i as usize])
+            .collect::<Vec<_>>(),
+    );
+    if order.len() <= CHUNK_SIZE {
+        chunks.push(PointChunk {
+            first,
+            count: order.len() as u32,
+            aabb,
+        });
+        return;
+    }
+    let size = aabb.size();
+    let axis = if size.x >= size.y && size.x >= size.z {
+        0
+    } else if size.y >= size.z {
+        1
+    } else {
+        2
+    };
+    let middle = order.len() / 2;
+    order.select_nth_unstable_by(middle, |a, b| {
+        positions[*a as usize][axis].total_cmp(&positions[*b as usize][axis])
+    });
+    let (left, right) = order.split_at_mut(middle);
+    split_into_chunks(positions, left, first, chunks);
+    split_into_chunks(positions, right, first + middle as u32, chunks);
+}
+
+impl<'a> IntoIterator for &'a Points {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Points {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.aabb;
+        aabb.transform(&self.transformation);
+        aabb
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                self.vertex_shader_source(fragment_shader.attributes),
+                self.fragment_shader_source(fragment_shader.source),
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader")
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                self.vertex_shader_source(fragment_shader.attributes),
+                self.fragment_shader_source(fragment_shader.source),
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader")
+    }
+
+    fn triangle_count(&self) -> usize {
+        // The points are drawn as points and not as triangles
+        0
+    }
+}
@@ -114,6 +114,10 @@ impl Geometry for Rectangle {
     fn obb(&self) -> OrientedBoundingBox2D {
         OrientedBoundingBox2D::new(self.width, self.height, self.center, self.rotation)
     }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
 }
 
 impl<'a> IntoIterator for &'a Rectangle {
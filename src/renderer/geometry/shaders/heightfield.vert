@@ -0,0 +1,81 @@
+invariant gl_Position;
+
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+uniform sampler2D heightMap;
+uniform float heightScale;
+uniform vec2 size;
+uniform vec2 patchOffset;
+uniform float patchSize;
+uniform float gridResolution;
+uniform vec2 morphRange;
+uniform vec3 localEye;
+
+in vec2 grid_position;
+
+out vec3 pos;
+
+#ifdef USE_NORMALS
+uniform mat4 normalMatrix;
+out vec3 nor;
+#ifdef USE_TANGENTS
+out vec3 tang;
+out vec3 bitang;
+#endif
+#endif
+
+#ifdef USE_UVS
+out vec2 uvs;
+#endif
+
+out vec4 col;
+
+// Bilinear filtering is done manually since linear filtering of float textures is not supported everywhere.
+float height(vec2 uv) {
+    ivec2 texture_size = textureSize(heightMap, 0);
+    vec2 p = clamp(uv, 0.0, 1.0) * vec2(texture_size - 1);
+    ivec2 p0 = ivec2(floor(p));
+    ivec2 p1 = min(p0 + 1, texture_size - 1);
+    vec2 f = p - vec2(p0);
+    float h0 = mix(texelFetch(heightMap, p0, 0).r, texelFetch(heightMap, ivec2(p1.x, p0.y), 0).r, f.x);
+    float h1 = mix(texelFetch(heightMap, ivec2(p0.x, p1.y), 0).r, texelFetch(heightMap, p1, 0).r, f.x);
+    return heightScale * mix(h0, h1, f.y);
+}
+
+vec3 local_position(vec2 uv) {
+    return vec3((uv.x - 0.5) * size.x, height(uv), (uv.y - 0.5) * size.y);
+}
+
+void main()
+{
+    // Morph the odd grid vertices towards the coarser grid when approaching the end of the level of detail range
+    vec2 uv = patchOffset + grid_position * patchSize;
+    float morph = clamp((distance(localEye, local_position(uv)) - morphRange.x) / (morphRange.y - morphRange.x), 0.0, 1.0);
+    vec2 grid = grid_position * gridResolution;
+    grid -= fract(grid * 0.5) * 2.0 * morph;
+    uv = patchOffset + grid / gridResolution * patchSize;
+
+    vec4 worldPosition = modelMatrix * vec4(local_position(uv), 1.0);
+    worldPosition /= worldPosition.w;
+    pos = worldPosition.xyz;
+    gl_Position = viewProjection * worldPosition;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+
+#ifdef USE_NORMALS
+    vec2 texel = 1.0 / vec2(textureSize(heightMap, 0) - 1);
+    float dx = (height(uv + vec2(texel.x, 0.0)) - height(uv - vec2(texel.x, 0.0))) / (2.0 * texel.x * size.x);
+    float dz = (height(uv + vec2(0.0, texel.y)) - height(uv - vec2(0.0, texel.y))) / (2.0 * texel.y * size.y);
+    nor = normalize(mat3(normalMatrix) * vec3(-dx, 1.0, -dz));
+#ifdef USE_TANGENTS
+    tang = normalize(mat3(modelMatrix) * vec3(1.0, dx, 0.0));
+    bitang = normalize(cross(nor, tang));
+#endif
+#endif
+
+#ifdef USE_UVS
+    uvs = uv;
+#endif
+    col = vec4(1.0);
+}
@@ -1,3 +1,5 @@
+invariant gl_Position;
+
 in vec3 position;
 in vec3 prev;
 uniform mat4 model;
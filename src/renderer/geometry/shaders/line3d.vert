@@ -0,0 +1,94 @@
+invariant gl_Position;
+
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+uniform vec3 cameraPosition;
+uniform vec2 resolution;
+uniform float thickness;
+
+in vec3 start;
+in vec3 end;
+in vec2 corner;
+
+out vec3 pos;
+
+#ifdef USE_NORMALS
+out vec3 nor;
+#ifdef USE_TANGENTS
+out vec3 tang;
+out vec3 bitang;
+#endif
+#endif
+
+#ifdef USE_UVS
+out vec2 uvs;
+#endif
+
+#ifdef USE_VERTEX_COLORS
+in vec4 color;
+#endif
+
+#ifdef USE_INSTANCE_ID
+flat out int instanceId;
+#endif
+
+out vec4 col;
+
+void main()
+{
+    vec4 worldStart = modelMatrix * vec4(start, 1.0);
+    vec4 worldEnd = modelMatrix * vec4(end, 1.0);
+    vec4 clipStart = viewProjection * worldStart;
+    vec4 clipEnd = viewProjection * worldEnd;
+
+    // Clip the segment against the near plane, otherwise the direction of the segment on the screen is wrong when one end point is behind the camera
+    float distanceStart = clipStart.z + clipStart.w;
+    float distanceEnd = clipEnd.z + clipEnd.w;
+    if (distanceStart < 0.0 && distanceEnd >= 0.0) {
+        clipStart = mix(clipStart, clipEnd, distanceStart / (distanceStart - distanceEnd));
+    } else if (distanceEnd < 0.0 && distanceStart >= 0.0) {
+        clipEnd = mix(clipEnd, clipStart, distanceEnd / (distanceEnd - distanceStart));
+    }
+
+    // The direction of the segment and the normal to the segment in pixels
+    vec2 halfResolution = 0.5 * resolution;
+    vec2 screenStart = clipStart.xy / clipStart.w * halfResolution;
+    vec2 screenEnd = clipEnd.xy / clipEnd.w * halfResolution;
+    vec2 direction = screenEnd - screenStart;
+    direction = length(direction) > 0.0001 ? normalize(direction) : vec2(1.0, 0.0);
+    vec2 normal = vec2(-direction.y, direction.x);
+
+    // Shift the vertex half the thickness to the side and along the segment, so the ends of connected segments overlap
+    vec2 offset = 0.5 * thickness * (corner.y * normal + (2.0 * corner.x - 1.0) * direction);
+    vec4 clipPosition = corner.x < 0.5 ? clipStart : clipEnd;
+    clipPosition.xy += offset / halfResolution * clipPosition.w;
+    gl_Position = clipPosition;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+
+    pos = mix(worldStart.xyz, worldEnd.xyz, corner.x);
+
+#ifdef USE_NORMALS
+    // The line is rendered as a flat band facing the camera
+    nor = normalize(cameraPosition - pos);
+#ifdef USE_TANGENTS
+    tang = normalize(worldEnd.xyz - worldStart.xyz);
+    bitang = normalize(cross(nor, tang));
+#endif
+#endif
+
+#ifdef USE_UVS
+    uvs = vec2(corner.x, 0.5 + 0.5 * corner.y);
+#endif
+
+#ifdef USE_VERTEX_COLORS
+    col = color;
+#else
+    col = vec4(1.0);
+#endif
+
+#ifdef USE_INSTANCE_ID
+    instanceId = 0;
+#endif
+}
@@ -1,3 +1,4 @@
+invariant gl_Position;
 
 uniform mat4 viewProjection;
 uniform mat4 modelMatrix;
@@ -14,6 +15,31 @@ uniform float time;
 in vec3 instance_translation;
 #endif
 
+#ifdef USE_VERTEX_ANIMATION
+uniform sampler2D animationPositions;
+uniform int animationVertexCount;
+uniform float animationTime;
+in vec4 instance_animation;
+
+ivec2 animation_texel(int frame) {
+    int i = frame * animationVertexCount + gl_VertexID;
+    int width = textureSize(animationPositions, 0).x;
+    return ivec2(i % width, i / width);
+}
+#endif
+
+#ifdef USE_MORPH_TARGETS
+uniform sampler2D morphPositions;
+uniform int morphVertexCount;
+uniform float morphWeights[MORPH_TARGET_COUNT];
+
+ivec2 morph_texel(int target) {
+    int i = target * morphVertexCount + gl_VertexID;
+    int width = textureSize(morphPositions, 0).x;
+    return ivec2(i % width, i / width);
+}
+#endif
+
 #ifdef USE_INSTANCE_TRANSFORMS
 in vec4 row1;
 in vec4 row2;
@@ -26,6 +52,12 @@ out vec3 pos;
 uniform mat4 normalMatrix;
 in vec3 normal;
 out vec3 nor;
+#ifdef USE_VERTEX_ANIMATION
+uniform sampler2D animationNormals;
+#endif
+#ifdef USE_MORPH_NORMALS
+uniform sampler2D morphNormals;
+#endif
 
 #ifdef USE_TANGENTS 
 in vec4 tangent;
@@ -52,6 +84,13 @@ in vec4 color;
 in vec4 instance_color;
 #endif
 
+#ifdef USE_INSTANCE_ID
+#ifdef USE_INSTANCE_INDICES
+in float instance_index;
+#endif
+flat out int instanceId;
+#endif
+
 out vec4 col;
 
 void main()
@@ -68,7 +107,24 @@ void main()
     local2World *= transform;
 #endif
 
-    vec4 worldPosition = local2World * vec4(position, 1.);
+    vec3 localPosition = position;
+#ifdef USE_VERTEX_ANIMATION
+    // instance_animation is (first frame, frame count, frames per second, frame offset)
+    float frame = mod(animationTime * instance_animation.z + instance_animation.w, instance_animation.y);
+    int frame0 = int(floor(frame));
+    int frame1 = int(mod(float(frame0 + 1), instance_animation.y));
+    float frameBlend = fract(frame);
+    ivec2 texel0 = animation_texel(int(instance_animation.x) + frame0);
+    ivec2 texel1 = animation_texel(int(instance_animation.x) + frame1);
+    localPosition += mix(texelFetch(animationPositions, texel0, 0).xyz, texelFetch(animationPositions, texel1, 0).xyz, frameBlend);
+#endif
+#ifdef USE_MORPH_TARGETS
+    for (int i = 0; i < MORPH_TARGET_COUNT; i++) {
+        localPosition += morphWeights[i] * texelFetch(morphPositions, morph_texel(i), 0).xyz;
+    }
+#endif
+
+    vec4 worldPosition = local2World * vec4(localPosition, 1.);
     worldPosition /= worldPosition.w;
 #ifdef PARTICLES
     worldPosition.xyz += start_position + start_velocity * time + 0.5 * acceleration * time * time;
@@ -77,6 +133,9 @@ void main()
     worldPosition.xyz += instance_translation;
 #endif
     gl_Position = viewProjection * worldPosition;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
 
     pos = worldPosition.xyz;
 
@@ -87,7 +146,16 @@ void main()
 #else
     mat3 normalMat = mat3(normalMatrix);
 #endif
-    nor = normalize(normalMat * normal);
+    vec3 localNormal = normal;
+#ifdef USE_VERTEX_ANIMATION
+    localNormal += mix(texelFetch(animationNormals, texel0, 0).xyz, texelFetch(animationNormals, texel1, 0).xyz, frameBlend);
+#endif
+#ifdef USE_MORPH_NORMALS
+    for (int i = 0; i < MORPH_TARGET_COUNT; i++) {
+        localNormal += morphWeights[i] * texelFetch(morphNormals, morph_texel(i), 0).xyz;
+    }
+#endif
+    nor = normalize(normalMat * localNormal);
 
 #ifdef USE_TANGENTS 
     tang = normalize(normalMat * tangent.xyz);
@@ -116,5 +184,19 @@ void main()
 #endif
 #ifdef USE_INSTANCE_COLORS
     col *= instance_color;
+#endif
+
+    // *** CUSTOM ATTRIBUTES ***
+#ifdef USE_CUSTOM_ATTRIBUTES
+    assign_custom_attributes();
+#endif
+
+    // *** INSTANCE ID ***
+#ifdef USE_INSTANCE_ID
+#ifdef USE_INSTANCE_INDICES
+    instanceId = int(instance_index);
+#else
+    instanceId = gl_InstanceID;
+#endif
 #endif
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+
+void main()
+{
+    vec2 pointCoord = 2.0 * gl_PointCoord - 1.0;
+    if (dot(pointCoord, pointCoord) > 1.0) {
+        discard;
+    }
+    material_main();
+}
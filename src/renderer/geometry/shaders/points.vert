@@ -0,0 +1,75 @@
+invariant gl_Position;
+
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+uniform vec3 cameraPosition;
+uniform float pointSize;
+uniform float projectionScale;
+
+in vec3 position;
+in float size;
+
+out vec3 pos;
+
+#ifdef USE_NORMALS
+out vec3 nor;
+#ifdef USE_TANGENTS
+out vec3 tang;
+out vec3 bitang;
+#endif
+#endif
+
+#ifdef USE_UVS
+out vec2 uvs;
+#endif
+
+#ifdef USE_VERTEX_COLORS
+in vec4 color;
+#endif
+
+#ifdef USE_INSTANCE_ID
+flat out int instanceId;
+#endif
+
+out vec4 col;
+
+void main()
+{
+    vec4 worldPosition = modelMatrix * vec4(position, 1.0);
+    pos = worldPosition.xyz;
+    gl_Position = viewProjection * worldPosition;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+
+    // The size is either in pixels or, if the projection scale is positive, in world units which are projected to pixels
+    float pixels = pointSize * size;
+    if (projectionScale > 0.0) {
+        pixels *= projectionScale / gl_Position.w;
+    }
+    gl_PointSize = max(pixels, 1.0);
+
+#ifdef USE_NORMALS
+    // Points are rendered as discs facing the camera
+    nor = normalize(cameraPosition - pos);
+#ifdef USE_TANGENTS
+    vec3 up = abs(nor.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0);
+    tang = normalize(cross(up, nor));
+    bitang = cross(nor, tang);
+#endif
+#endif
+
+#ifdef USE_UVS
+    uvs = vec2(0.5, 0.5);
+#endif
+
+#ifdef USE_VERTEX_COLORS
+    col = color;
+#else
+    col = vec4(1.0);
+#endif
+
+#ifdef USE_INSTANCE_ID
+    instanceId = 0;
+#endif
+}
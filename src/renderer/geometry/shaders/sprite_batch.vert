@@ -0,0 +1,46 @@
+invariant gl_Position;
+
+uniform mat4 viewProjection;
+uniform mat4 transformation;
+uniform vec3 cameraRight;
+uniform vec3 cameraUp;
+uniform vec3 normal;
+uniform int billboard;
+
+in vec3 position;
+in vec3 instance_position;
+in vec3 instance_rotation_size;
+in vec4 instance_uv;
+in vec4 instance_color;
+
+out vec2 uvs;
+out vec4 col;
+out vec3 pos;
+out vec3 nor;
+
+void main()
+{
+    float c = cos(instance_rotation_size.x);
+    float s = sin(instance_rotation_size.x);
+    vec2 corner = position.xy * instance_rotation_size.yz;
+    corner = vec2(c * corner.x - s * corner.y, s * corner.x + c * corner.y);
+
+    vec4 world_pos;
+    if (billboard == 1) {
+        vec4 center = transformation * vec4(instance_position, 1.0);
+        world_pos = vec4(center.xyz / center.w + cameraRight * corner.x + cameraUp * corner.y, 1.0);
+    } else {
+        world_pos = transformation * vec4(instance_position + vec3(corner, 0.0), 1.0);
+    }
+    pos = world_pos.xyz / world_pos.w;
+    nor = normal;
+
+    // The uv rectangle is given in standard uv coordinates, which are flipped in v
+    vec2 t = position.xy + 0.5;
+    uvs = vec2(mix(instance_uv.x, instance_uv.z, t.x), 1.0 - mix(instance_uv.w, instance_uv.y, t.y));
+    col = instance_color;
+    gl_Position = viewProjection * world_pos;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+}
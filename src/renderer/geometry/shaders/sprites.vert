@@ -1,3 +1,5 @@
+invariant gl_Position;
+
 uniform mat4 viewProjection;
 uniform vec3 eye;
 uniform mat4 transformation;
@@ -38,4 +40,7 @@ void main()
     vec4 world_pos = instanced_transform * transformation * vec4(position, 1.);
     pos = world_pos.xyz / world_pos.w;
     gl_Position = viewProjection * world_pos;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
 }
@@ -0,0 +1,183 @@
+use crate::{renderer::*, OrientedBoundingBox2D};
+
+///
+/// A 2D quad showing a region of a [TextureAtlas], for example an icon, which can be rendered using the [camera2d] camera.
+/// Render the sprite with a material using the full atlas texture, for example a [ColorMaterial] with the [TextureAtlas::texture] as texture,
+/// so all sprites using the same atlas can share the same material.
+///
+pub struct Sprite {
+    context: Context,
+    mesh: Mesh,
+    region: AtlasRegion,
+    width: f32,
+    height: f32,
+    center: PhysicalPoint,
+    rotation: Radians,
+}
+
+impl Sprite {
+    ///
+    /// Constructs a new sprite showing the given region of a texture atlas with the given center, rotation and size in pixels.
+    ///
+    pub fn new(
+        context: &Context,
+        region: AtlasRegion,
+        center: impl Into<PhysicalPoint>,
+        rotation: impl Into<Radians>,
+        width: f32,
+        height: f32,
+    ) -> Self {
+        let mut sprite = Self {
+            context: context.clone(),
+            mesh: Self::mesh(context, &region),
+            region,
+            width,
+            height,
+            center: center.into(),
+            rotation: rotation.into(),
+        };
+        sprite.update();
+        sprite
+    }
+
+    ///
+    /// Constructs a new sprite showing the given region of a texture atlas with the given center and the size of the region in pixels.
+    ///
+    pub fn new_with_region_size(
+        context: &Context,
+        region: AtlasRegion,
+        center: impl Into<PhysicalPoint>,
+    ) -> Self {
+        Self::new(
+            context,
+            region,
+            center,
+            radians(0.0),
+            region.width as f32,
+            region.height as f32,
+        )
+    }
+
+    /// Set the region of the texture atlas shown by the sprite.
+    pub fn set_region(&mut self, region: AtlasRegion) {
+        self.region = region;
+        self.mesh = Self::mesh(&self.context, &region);
+        self.update();
+    }
+
+    /// Get the region of the texture atlas shown by the sprite.
+    pub fn region(&self) -> AtlasRegion {
+        self.region
+    }
+
+    /// Set the size of the sprite.
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        self.update();
+    }
+
+    /// Get the size of the sprite.
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    /// Set the center of the sprite.
+    pub fn set_center(&mut self, center: impl Into<PhysicalPoint>) {
+        self.center = center.into();
+        self.update();
+    }
+
+    /// Get the center of the sprite.
+    pub fn center(&self) -> PhysicalPoint {
+        self.center
+    }
+
+    /// Set the rotation of the sprite.
+    pub fn set_rotation(&mut self, rotation: impl Into<Radians>) {
+        self.rotation = rotation.into();
+        self.update();
+    }
+
+    /// Get the rotation of the sprite.
+    pub fn rotation(&self) -> Radians {
+        self.rotation
+    }
+
+    fn update(&mut self) {
+        self.mesh.set_transformation_2d(
+            Mat3::from_translation(self.center.into())
+                * Mat3::from_angle_z(self.rotation)
+                * Mat3::from_nonuniform_scale(self.width, self.height),
+        );
+    }
+
+    fn mesh(context: &Context, region: &AtlasRegion) -> Mesh {
+        Mesh::new(
+            context,
+            &CpuMesh {
+                positions: Positions::F32(vec![
+                    vec3(-0.5, -0.5, 0.0),
+                    vec3(0.5, -0.5, 0.0),
+                    vec3(0.5, 0.5, 0.0),
+                    vec3(-0.5, 0.5, 0.0),
+                ]),
+                indices: Indices::U8(vec![0, 1, 2, 2, 3, 0]),
+                uvs: Some(vec![
+                    region.uv(vec2(0.0, 1.0)),
+                    region.uv(vec2(1.0, 1.0)),
+                    region.uv(vec2(1.0, 0.0)),
+                    region.uv(vec2(0.0, 0.0)),
+                ]),
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Geometry for Sprite {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    ///
+    /// Returns the [AxisAlignedBoundingBox] for this geometry in the global coordinate system.
+    ///
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
+
+    fn obb(&self) -> OrientedBoundingBox2D {
+        OrientedBoundingBox2D::new(self.width, self.height, self.center, self.rotation)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+}
+
+impl<'a> IntoIterator for &'a Sprite {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
@@ -0,0 +1,300 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A sprite in a [SpriteBatch].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteInstance {
+    /// The position of the center of the sprite.
+    pub position: Vec3,
+    /// The rotation of the sprite around its center.
+    pub rotation: Radians,
+    /// The width and height of the sprite.
+    pub size: Vec2,
+    /// The uv coordinates of the top left corner of the part of the texture shown by the sprite, see [AtlasRegion::uv_min].
+    pub uv_min: Vec2,
+    /// The uv coordinates of the bottom right corner of the part of the texture shown by the sprite, see [AtlasRegion::uv_max].
+    pub uv_max: Vec2,
+    /// The color multiplied onto the color of the material.
+    pub color: Color,
+}
+
+impl Default for SpriteInstance {
+    fn default() -> Self {
+        Self {
+            position: vec3(0.0, 0.0, 0.0),
+            rotation: radians(0.0),
+            size: vec2(1.0, 1.0),
+            uv_min: vec2(0.0, 0.0),
+            uv_max: vec2(1.0, 1.0),
+            color: Color::WHITE,
+        }
+    }
+}
+
+impl SpriteInstance {
+    ///
+    /// Creates a new sprite at the given position with the given size showing the full texture.
+    ///
+    pub fn new(position: Vec3, size: Vec2) -> Self {
+        Self {
+            position,
+            size,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Creates a new sprite at the given position with the given size showing the given region of a [TextureAtlas].
+    ///
+    pub fn new_with_region(position: Vec3, size: Vec2, region: &AtlasRegion) -> Self {
+        Self {
+            position,
+            size,
+            uv_min: region.uv_min,
+            uv_max: region.uv_max,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Many textured quads, for example icons, labels, health bars or particles, rendered in a single draw call.
+/// Each sprite has its own position, rotation, size, part of the texture and color, see [SpriteInstance].
+/// Render the batch with a material using a texture atlas, for example a [ColorMaterial] with the [TextureAtlas::texture] as texture.
+///
+/// By default, the sprites lie in the xy plane, which can be rendered with the [camera2d] camera where the position and size is in pixels.
+/// In billboard mode, the sprites always face the camera, so they can be placed in a 3D scene, see [SpriteBatch::set_billboard].
+///
+pub struct SpriteBatch {
+    context: Context,
+    position_buffer: VertexBuffer,
+    instance_buffers: Option<SpriteInstanceBuffers>,
+    instance_count: u32,
+    transformation: Mat4,
+    billboard: bool,
+    aabb: AxisAlignedBoundingBox,
+}
+
+struct SpriteInstanceBuffers {
+    positions: InstanceBuffer,
+    rotation_sizes: InstanceBuffer,
+    uvs: InstanceBuffer,
+    colors: InstanceBuffer,
+}
+
+impl SpriteBatch {
+    ///
+    /// Creates a new batch with the given sprites lying in the xy plane.
+    ///
+    pub fn new(context: &Context, sprites: &[SpriteInstance]) -> Self {
+        let position_buffer = VertexBuffer::new_with_data(
+            context,
+            &[
+                vec3(-0.5, -0.5, 0.0),
+                vec3(0.5, -0.5, 0.0),
+                vec3(0.5, 0.5, 0.0),
+                vec3(0.5, 0.5, 0.0),
+                vec3(-0.5, 0.5, 0.0),
+                vec3(-0.5, -0.5, 0.0),
+            ],
+        );
+        let mut batch = Self {
+            context: context.clone(),
+            position_buffer,
+            instance_buffers: None,
+            instance_count: 0,
+            transformation: Mat4::identity(),
+            billboard: false,
+            aabb: AxisAlignedBoundingBox::EMPTY,
+        };
+        batch.set_sprites(sprites);
+        batch
+    }
+
+    ///
+    /// Creates a new batch with the given sprites in billboard mode, see [SpriteBatch::set_billboard].
+    ///
+    pub fn new_billboards(context: &Context, sprites: &[SpriteInstance]) -> Self {
+        let mut batch = Self::new(context, sprites);
+        batch.set_billboard(true);
+        batch
+    }
+
+    ///
+    /// Replaces the sprites in the batch. The sprites also determines the number of sprites.
+    ///
+    pub fn set_sprites(&mut self, sprites: &[SpriteInstance]) {
+        self.instance_count = sprites.len() as u32;
+        self.instance_buffers = if sprites.is_empty() {
+            None
+        } else {
+            Some(SpriteInstanceBuffers {
+                positions: InstanceBuffer::new_with_data(
+                    &self.context,
+                    &sprites.iter().map(|s| s.position).collect::<Vec<_>>(),
+                ),
+                rotation_sizes: InstanceBuffer::new_with_data(
+                    &self.context,
+                    &sprites
+                        .iter()
+                        .map(|s| vec3(s.rotation.0, s.size.x, s.size.y))
+                        .collect::<Vec<_>>(),
+                ),
+                uvs: InstanceBuffer::new_with_data(
+                    &self.context,
+                    &sprites
+                        .iter()
+                        .map(|s| vec4(s.uv_min.x, s.uv_min.y, s.uv_max.x, s.uv_max.y))
+                        .collect::<Vec<_>>(),
+                ),
+                colors: InstanceBuffer::new_with_data(
+                    &self.context,
+                    &sprites.iter().map(|s| s.color).collect::<Vec<_>>(),
+                ),
+            })
+        };
+        // The sprites can rotate, so use the half diagonal in all directions
+        let corners: Vec<Vec3> = sprites
+            .iter()
+            .flat_map(|sprite| {
+                let radius = 0.5 * sprite.size.magnitude();
+                let extent = vec3(radius, radius, radius);
+                [sprite.position - extent, sprite.position + extent]
+            })
+            .collect();
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(&corners);
+    }
+
+    ///
+    /// Returns the number of sprites.
+    ///
+    pub fn sprite_count(&self) -> u32 {
+        self.instance_count
+    }
+
+    ///
+    /// Sets whether or not the sprites are billboards, ie. they always face the camera and their rotation is around the view direction.
+    /// Otherwise the sprites lie in the xy plane.
+    ///
+    pub fn set_billboard(&mut self, billboard: bool) {
+        self.billboard = billboard;
+    }
+
+    ///
+    /// Returns whether or not the sprites are billboards.
+    ///
+    pub fn is_billboard(&self) -> bool {
+        self.billboard
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all sprites.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all sprites.
+    /// In billboard mode, the transformation is only applied to the positions of the sprites.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
+        let buffers = match &self.instance_buffers {
+            Some(buffers) => buffers,
+            None => return,
+        };
+        let right = camera.right_direction().normalize();
+        let up = right.cross(camera.view_direction()).normalize();
+        let normal = if self.billboard {
+            -camera.view_direction().normalize()
+        } else {
+            (self.transformation * vec4(0.0, 0.0, 1.0, 0.0))
+                .truncate()
+                .normalize()
+        };
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform("transformation", self.transformation);
+        program.use_uniform("cameraRight", right);
+        program.use_uniform("cameraUp", up);
+        program.use_uniform_if_required("normal", normal);
+        program.use_uniform("billboard", i32::from(self.billboard));
+        program.use_vertex_attribute("position", &self.position_buffer);
+        program.use_instance_attribute("instance_position", &buffers.positions);
+        program.use_instance_attribute("instance_rotation_size", &buffers.rotation_sizes);
+        program.use_instance_attribute("instance_uv", &buffers.uvs);
+        program.use_instance_attribute("instance_color", &buffers.colors);
+        program.draw_arrays_instanced(render_states, camera.viewport(), 6, self.instance_count)
+    }
+
+    fn program(&self, fragment_shader: FragmentShader, callback: impl FnOnce(&Program)) {
+        if fragment_shader.attributes.tangents {
+            panic!("a SpriteBatch does not have tangents");
+        }
+        self.context
+            .program(
+                include_str!("shaders/sprite_batch.vert").to_owned(),
+                fragment_shader.source,
+                callback,
+            )
+            .expect("Failed compiling shader")
+    }
+}
+
+impl<'a> IntoIterator for &'a SpriteBatch {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for SpriteBatch {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.program(material.fragment_shader(lights), |program| {
+            material.use_uniforms(program, camera, lights);
+            self.draw(program, material.render_states(), camera);
+        })
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.program(
+            material.fragment_shader(lights, color_texture, depth_texture),
+            |program| {
+                material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                self.draw(program, material.render_states(), camera);
+            },
+        )
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = self.aabb;
+        aabb.transform(&self.transformation);
+        aabb
+    }
+
+    fn triangle_count(&self) -> usize {
+        2 * self.instance_count as usize
+    }
+}
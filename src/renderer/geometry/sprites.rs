@@ -85,7 +85,10 @@ impl Sprites {
 
     fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
         program.use_uniform("eye", camera.position());
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
         program.use_uniform("transformation", self.transformation);
         program.use_vertex_attribute("position", &self.position_buffer);
         program.use_vertex_attribute("uv_coordinate", &self.uv_buffer);
@@ -165,4 +168,8 @@ impl Geometry for Sprites {
     fn aabb(&self) -> AxisAlignedBoundingBox {
         AxisAlignedBoundingBox::INFINITE
     }
+
+    fn triangle_count(&self) -> usize {
+        2 * self.center_buffer.instance_count() as usize
+    }
 }
@@ -0,0 +1,229 @@
+use crate::renderer::*;
+use crate::OrientedBoundingBox2D;
+use std::ops::Range;
+
+///
+/// A surface [Geometry] generated by sampling a function on a regular grid, for example for plotting mathematical functions or measurements.
+/// The surface is either a height function `y = f(x, z)` (see [SurfacePlot::new]) or a parametric surface `p = f(u, v)` (see [SurfacePlot::new_parametric]).
+/// The normals are computed automatically and the vertices can optionally be colored by a scalar value (see [SurfacePlot::set_scalar_coloring]).
+/// Triangles containing a sample that is not finite, for example where the function is undefined, are left out.
+///
+pub struct SurfacePlot {
+    context: Context,
+    mesh: Mesh,
+    cpu_mesh: CpuMesh,
+}
+
+impl SurfacePlot {
+    ///
+    /// Creates a surface plot of the height function `y = f(x, z)` sampled on a grid with the given resolution in the given x and z ranges.
+    ///
+    pub fn new(
+        context: &Context,
+        x_range: Range<f32>,
+        z_range: Range<f32>,
+        resolution: (u32, u32),
+        f: impl Fn(f32, f32) -> f32,
+    ) -> Self {
+        Self::new_parametric(context, x_range, z_range, resolution, |x, z| {
+            vec3(x, f(x, z), z)
+        })
+    }
+
+    ///
+    /// Creates a plot of the parametric surface `p = f(u, v)` sampled on a grid with the given resolution in the given u and v ranges.
+    /// The triangles face in the direction of the cross product of the derivative in v and the derivative in u.
+    ///
+    pub fn new_parametric(
+        context: &Context,
+        u_range: Range<f32>,
+        v_range: Range<f32>,
+        resolution: (u32, u32),
+        f: impl Fn(f32, f32) -> Vec3,
+    ) -> Self {
+        let (columns, rows) = (resolution.0.max(1), resolution.1.max(1));
+        let mut positions = Vec::with_capacity(((columns + 1) * (rows + 1)) as usize);
+        let mut uvs = Vec::with_capacity(positions.capacity());
+        for j in 0..=rows {
+            let v = j as f32 / rows as f32;
+            for i in 0..=columns {
+                let u = i as f32 / columns as f32;
+                positions.push(f(
+                    u_range.start + u * (u_range.end - u_range.start),
+                    v_range.start + v * (v_range.end - v_range.start),
+                ));
+                uvs.push(vec2(u, v));
+            }
+        }
+        let finite = |i: u32| {
+            let p = positions[i as usize];
+            p.x.is_finite() && p.y.is_finite() && p.z.is_finite()
+        };
+        let stride = columns + 1;
+        let mut indices = Vec::with_capacity((6 * columns * rows) as usize);
+        for j in 0..rows {
+            for i in 0..columns {
+                let a = j * stride + i;
+                for triangle in [[a, a + stride, a + 1], [a + 1, a + stride, a + stride + 1]] {
+                    if triangle.iter().all(|v| finite(*v)) {
+                        indices.extend_from_slice(&triangle);
+                    }
+                }
+            }
+        }
+        // Replace the samples that are not used by any triangle so they do not affect the bounding box
+        for p in positions.iter_mut() {
+            if !(p.x.is_finite() && p.y.is_finite() && p.z.is_finite()) {
+                *p = vec3(0.0, 0.0, 0.0);
+            }
+        }
+        let mut cpu_mesh = CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            uvs: Some(uvs),
+            ..Default::default()
+        };
+        cpu_mesh.compute_normals();
+        Self {
+            context: context.clone(),
+            mesh: Mesh::new(context, &cpu_mesh),
+            cpu_mesh,
+        }
+    }
+
+    ///
+    /// Colors the vertices by mapping the given scalar function of the position to the given color map.
+    /// The scalar values are normalized to the range between the minimum and maximum value, which is then mapped linearly to the colors of the color map,
+    /// from the first color at the minimum value to the last color at the maximum value.
+    /// The material must use the vertex colors for the coloring to be visible, for example [ColorMaterial] or [PhysicalMaterial].
+    ///
+    pub fn set_scalar_coloring(&mut self, scalar: impl Fn(Vec3) -> f32, color_map: &[Color]) {
+        if color_map.is_empty() {
+            self.clear_coloring();
+            return;
+        }
+        let positions = self.cpu_mesh.positions.to_f32();
+        let values = positions.iter().map(|p| scalar(*p)).collect::<Vec<_>>();
+        let (min, max) = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold((f32::MAX, f32::MIN), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            });
+        let range = (max - min).max(0.000001);
+        self.cpu_mesh.colors = Some(
+            values
+                .iter()
+                .map(|v| {
+                    let t = if v.is_finite() {
+                        ((v - min) / range).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    sample_color_map(color_map, t)
+                })
+                .collect(),
+        );
+        self.update_mesh();
+    }
+
+    ///
+    /// Colors the vertices by the height (the y coordinate) using the given color map, see [SurfacePlot::set_scalar_coloring].
+    ///
+    pub fn set_height_coloring(&mut self, color_map: &[Color]) {
+        self.set_scalar_coloring(|p| p.y, color_map);
+    }
+
+    ///
+    /// Removes the vertex colors set by [SurfacePlot::set_scalar_coloring].
+    ///
+    pub fn clear_coloring(&mut self) {
+        if self.cpu_mesh.colors.take().is_some() {
+            self.update_mesh();
+        }
+    }
+
+    ///
+    /// Returns the sampled surface.
+    ///
+    pub fn cpu_mesh(&self) -> &CpuMesh {
+        &self.cpu_mesh
+    }
+
+    fn update_mesh(&mut self) {
+        let transformation = self.mesh.transformation();
+        self.mesh = Mesh::new(&self.context, &self.cpu_mesh);
+        self.mesh.set_transformation(transformation);
+    }
+}
+
+fn sample_color_map(color_map: &[Color], t: f32) -> Color {
+    let x = t * (color_map.len() - 1) as f32;
+    let i = (x.floor() as usize).min(color_map.len() - 1);
+    let j = (i + 1).min(color_map.len() - 1);
+    let f = x - i as f32;
+    let (a, b) = (color_map[i], color_map[j]);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+    Color::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), mix(a.a, b.a))
+}
+
+impl std::ops::Deref for SurfacePlot {
+    type Target = Mesh;
+    fn deref(&self) -> &Self::Target {
+        &self.mesh
+    }
+}
+
+impl std::ops::DerefMut for SurfacePlot {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.mesh
+    }
+}
+
+impl<'a> IntoIterator for &'a SurfacePlot {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for SurfacePlot {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
+
+    fn obb(&self) -> OrientedBoundingBox2D {
+        self.mesh.obb()
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.mesh.animate(time)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+}
@@ -0,0 +1,317 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::HashMap;
+
+///
+/// An edge in a [MeshTopology].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MeshEdge {
+    /// The indices of the two vertices of the edge, where the first index is the smallest.
+    pub vertices: [u32; 2],
+    /// The number of triangles which contains the edge.
+    pub triangle_count: u32,
+}
+
+impl MeshEdge {
+    ///
+    /// Returns whether or not this edge is on the boundary of the mesh, ie. it is only part of one triangle.
+    ///
+    pub fn is_boundary(&self) -> bool {
+        self.triangle_count == 1
+    }
+}
+
+///
+/// Adjacency information for a triangle mesh, ie. which triangles contain each vertex, the edges of the mesh and the boundary loops.
+/// The topology is built once from the indices of the mesh and can then be used by for example selection tools, smoothing brushes or to render the edges as a wireframe.
+/// The topology only depends on the indices, so it can be reused when the positions of the vertices change, for example when using [Mesh::update_positions].
+///
+#[derive(Clone, Debug)]
+pub struct MeshTopology {
+    triangles: Vec<[u32; 3]>,
+    vertex_triangle_offsets: Vec<u32>,
+    vertex_triangles: Vec<u32>,
+    edges: Vec<MeshEdge>,
+    edge_indices: HashMap<(u32, u32), usize>,
+    boundary_vertices: Vec<bool>,
+    boundary_loops: Vec<Vec<u32>>,
+}
+
+impl MeshTopology {
+    ///
+    /// Builds the topology of the given mesh.
+    ///
+    pub fn new(cpu_mesh: &CpuMesh) -> Self {
+        let vertex_count = cpu_mesh.positions.len();
+        let indices = cpu_mesh
+            .indices
+            .to_u32()
+            .unwrap_or_else(|| (0..vertex_count as u32).collect());
+        Self::from_indices(vertex_count, &indices)
+    }
+
+    ///
+    /// Builds the topology of a mesh with the given number of vertices and the given indices, where each consecutive three indices define a triangle.
+    ///
+    /// # Panic
+    /// Will panic if an index is not smaller than the number of vertices.
+    ///
+    pub fn from_indices(vertex_count: usize, indices: &[u32]) -> Self {
+        let triangles: Vec<[u32; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        if let Some(index) = indices.iter().find(|i| **i as usize >= vertex_count) {
+            panic!(
+                "the index {} refers to a vertex which is not in the mesh with {} vertices",
+                index, vertex_count
+            );
+        }
+
+        // Store the triangles of each vertex consecutively
+        let mut vertex_triangle_offsets = vec![0u32; vertex_count + 1];
+        for triangle in triangles.iter() {
+            for vertex in triangle {
+                vertex_triangle_offsets[*vertex as usize + 1] += 1;
+            }
+        }
+        for i in 0..vertex_count {
+            vertex_triangle_offsets[i + 1] += vertex_triangle_offsets[i];
+        }
+        let mut vertex_triangles = vec![0u32; vertex_triangle_offsets[vertex_count] as usize];
+        let mut next = vertex_triangle_offsets.clone();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for vertex in triangle {
+                vertex_triangles[next[*vertex as usize] as usize] = t as u32;
+                next[*vertex as usize] += 1;
+            }
+        }
+
+        let mut edges = Vec::new();
+        let mut edge_indices = HashMap::new();
+        for triangle in triangles.iter() {
+            for (a, b) in triangle_edges(triangle) {
+                if a == b {
+                    continue;
+                }
+                let index = *edge_indices.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    edges.push(MeshEdge {
+                        vertices: [a.min(b), a.max(b)],
+                        triangle_count: 0,
+                    });
+                    edges.len() - 1
+                });
+                edges[index].triangle_count += 1;
+            }
+        }
+
+        // The boundary edges are oriented as in their triangle, so each boundary loop can be followed from one vertex to the next
+        let mut boundary_vertices = vec![false; vertex_count];
+        let mut outgoing: HashMap<u32, Vec<u32>> = HashMap::new();
+        for triangle in triangles.iter() {
+            for (a, b) in triangle_edges(triangle) {
+                if a != b && edges[edge_indices[&(a.min(b), a.max(b))]].is_boundary() {
+                    outgoing.entry(a).or_default().push(b);
+                    boundary_vertices[a as usize] = true;
+                    boundary_vertices[b as usize] = true;
+                }
+            }
+        }
+        let mut starts: Vec<u32> = outgoing.keys().copied().collect();
+        starts.sort_unstable();
+        let mut boundary_loops = Vec::new();
+        for start in starts {
+            while let Some(mut vertex) = outgoing.get_mut(&start).and_then(|o| o.pop()) {
+                let mut boundary_loop = vec![start];
+                while vertex != start {
+                    boundary_loop.push(vertex);
+                    match outgoing.get_mut(&vertex).and_then(|o| o.pop()) {
+                        Some(v) => vertex = v,
+                        None => break,
+                    }
+                }
+                boundary_loops.push(boundary_loop);
+            }
+        }
+
+        Self {
+            triangles,
+            vertex_triangle_offsets,
+            vertex_triangles,
+            edges,
+            edge_indices,
+            boundary_vertices,
+            boundary_loops,
+        }
+    }
+
+    ///
+    /// Returns the number of vertices.
+    ///
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_triangle_offsets.len() - 1
+    }
+
+    ///
+    /// Returns the vertex indices of each triangle.
+    ///
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    ///
+    /// Returns the indices of the triangles which contain the given vertex.
+    ///
+    pub fn vertex_triangles(&self, vertex: u32) -> &[u32] {
+        let start = self.vertex_triangle_offsets[vertex as usize] as usize;
+        let end = self.vertex_triangle_offsets[vertex as usize + 1] as usize;
+        &self.vertex_triangles[start..end]
+    }
+
+    ///
+    /// Returns the indices of the vertices which are connected to the given vertex by an edge, sorted by index.
+    ///
+    pub fn vertex_neighbours(&self, vertex: u32) -> Vec<u32> {
+        let mut neighbours: Vec<u32> = self
+            .vertex_triangles(vertex)
+            .iter()
+            .flat_map(|t| self.triangles[*t as usize])
+            .filter(|v| *v != vertex)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    ///
+    /// Returns the indices of the triangles which share an edge with the given triangle, sorted by index.
+    ///
+    pub fn triangle_neighbours(&self, triangle: u32) -> Vec<u32> {
+        let mut neighbours = Vec::new();
+        for (a, b) in triangle_edges(&self.triangles[triangle as usize]) {
+            neighbours.extend(
+                self.edge_triangles(a, b)
+                    .into_iter()
+                    .filter(|t| *t != triangle),
+            );
+        }
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    ///
+    /// Returns the unique edges of the mesh.
+    ///
+    pub fn edges(&self) -> &[MeshEdge] {
+        &self.edges
+    }
+
+    ///
+    /// Returns the edge between the two given vertices or `None` if the vertices are not connected by an edge.
+    ///
+    pub fn edge(&self, a: u32, b: u32) -> Option<&MeshEdge> {
+        self.edge_indices
+            .get(&(a.min(b), a.max(b)))
+            .map(|i| &self.edges[*i])
+    }
+
+    ///
+    /// Returns the indices of the triangles which contain the edge between the two given vertices.
+    ///
+    pub fn edge_triangles(&self, a: u32, b: u32) -> Vec<u32> {
+        self.vertex_triangles(a)
+            .iter()
+            .copied()
+            .filter(|t| self.triangles[*t as usize].contains(&b))
+            .collect()
+    }
+
+    ///
+    /// Returns the edges on the boundary of the mesh, ie. the edges which are only part of one triangle.
+    ///
+    pub fn boundary_edges(&self) -> impl Iterator<Item = &MeshEdge> {
+        self.edges.iter().filter(|e| e.is_boundary())
+    }
+
+    ///
+    /// Returns whether or not the given vertex is on the boundary of the mesh.
+    ///
+    pub fn is_boundary_vertex(&self, vertex: u32) -> bool {
+        self.boundary_vertices[vertex as usize]
+    }
+
+    ///
+    /// Returns the boundary loops of the mesh, for example the rim of a hole, where each loop is the vertex indices along the loop in the same orientation as the triangles.
+    /// A closed mesh has no boundary loops.
+    ///
+    pub fn boundary_loops(&self) -> &[Vec<u32>] {
+        &self.boundary_loops
+    }
+
+    ///
+    /// Returns the given vertices together with all vertices which are connected to one of them by an edge, sorted by index.
+    /// Useful for growing a selection of vertices.
+    ///
+    pub fn grow_vertex_selection(&self, vertices: &[u32]) -> Vec<u32> {
+        let mut selection = vertices.to_vec();
+        for vertex in vertices {
+            selection.extend(self.vertex_neighbours(*vertex));
+        }
+        selection.sort_unstable();
+        selection.dedup();
+        selection
+    }
+
+    ///
+    /// Returns the given triangles together with all triangles which share a vertex with one of them, sorted by index.
+    /// Useful for growing a selection of triangles.
+    ///
+    pub fn grow_triangle_selection(&self, triangles: &[u32]) -> Vec<u32> {
+        let mut selection = triangles.to_vec();
+        for triangle in triangles {
+            for vertex in self.triangles[*triangle as usize] {
+                selection.extend_from_slice(self.vertex_triangles(vertex));
+            }
+        }
+        selection.sort_unstable();
+        selection.dedup();
+        selection
+    }
+
+    ///
+    /// Moves each of the given vertices towards the average position of its neighbours, where a factor of 0 does not move the vertices and a factor of 1 moves them to the average position.
+    /// Vertices on the boundary are not moved, so the outline of the mesh is preserved.
+    /// Useful for a smoothing brush, where the changed positions can be sent to the GPU using [Mesh::update_positions].
+    ///
+    pub fn smooth(&self, positions: &mut [Vec3], vertices: &[u32], factor: f32) {
+        let new_positions: Vec<(u32, Vec3)> = vertices
+            .iter()
+            .filter(|v| !self.is_boundary_vertex(**v))
+            .filter_map(|v| {
+                let neighbours = self.vertex_neighbours(*v);
+                if neighbours.is_empty() {
+                    return None;
+                }
+                let average = neighbours
+                    .iter()
+                    .fold(vec3(0.0, 0.0, 0.0), |sum, n| sum + positions[*n as usize])
+                    / neighbours.len() as f32;
+                let position = positions[*v as usize];
+                Some((*v, position + (average - position) * factor))
+            })
+            .collect();
+        for (vertex, position) in new_positions {
+            positions[vertex as usize] = position;
+        }
+    }
+}
+
+fn triangle_edges(triangle: &[u32; 3]) -> [(u32, u32); 3] {
+    [
+        (triangle[0], triangle[1]),
+        (triangle[1], triangle[2]),
+        (triangle[2], triangle[0]),
+    ]
+}
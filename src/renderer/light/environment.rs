@@ -39,19 +39,13 @@ impl Environment {
         environment_map: &TextureCubeMap,
         lighting_model: LightingModel,
     ) -> Self {
+        // Fall back to a lower precision on devices which cannot render into floating point textures
+        let precision =
+            context.select_render_precision("environment lighting", ColorPrecision::F16, true);
+
         // Diffuse
         let irradiance_size = 32;
-        let mut irradiance_map = TextureCubeMap::new_empty::<[f16; 4]>(
-            context,
-            irradiance_size,
-            irradiance_size,
-            Interpolation::Linear,
-            Interpolation::Linear,
-            Some(Interpolation::Linear),
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
+        let mut irradiance_map = new_cube_map(context, irradiance_size, precision);
         {
             let fragment_shader_source = format!(
                 "{}{}",
@@ -80,17 +74,7 @@ impl Environment {
 
         // Prefilter
         let prefilter_size = 128;
-        let mut prefilter_map = TextureCubeMap::new_empty::<[f16; 4]>(
-            context,
-            prefilter_size,
-            prefilter_size,
-            Interpolation::Linear,
-            Interpolation::Linear,
-            Some(Interpolation::Linear),
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
+        let mut prefilter_map = new_cube_map(context, prefilter_size, precision);
         {
             let fragment_shader_source = format!(
                 "{}{}{}{}",
@@ -128,16 +112,13 @@ impl Environment {
         }
 
         // BRDF
-        let mut brdf_map = Texture2D::new_empty::<[f32; 2]>(
-            context,
-            512,
-            512,
-            Interpolation::Linear,
-            Interpolation::Linear,
-            None,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
+        let brdf_precision =
+            context.select_render_precision("environment BRDF map", ColorPrecision::F32, true);
+        let mut brdf_map = match brdf_precision {
+            ColorPrecision::F32 => new_brdf_map::<[f32; 2]>(context),
+            ColorPrecision::F16 => new_brdf_map::<[f16; 2]>(context),
+            ColorPrecision::U8 => new_brdf_map::<[u8; 2]>(context),
+        };
         let viewport = Viewport::new_at_origin(brdf_map.width(), brdf_map.height());
         brdf_map
             .as_color_target(None)
@@ -165,3 +146,37 @@ impl Environment {
         }
     }
 }
+
+fn new_cube_map(context: &Context, size: u32, precision: ColorPrecision) -> TextureCubeMap {
+    fn new<T: TextureDataType>(context: &Context, size: u32) -> TextureCubeMap {
+        TextureCubeMap::new_empty::<T>(
+            context,
+            size,
+            size,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            Some(Interpolation::Linear),
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    }
+    match precision {
+        ColorPrecision::F32 => new::<[f32; 4]>(context, size),
+        ColorPrecision::F16 => new::<[f16; 4]>(context, size),
+        ColorPrecision::U8 => new::<[u8; 4]>(context, size),
+    }
+}
+
+fn new_brdf_map<T: TextureDataType>(context: &Context) -> Texture2D {
+    Texture2D::new_empty::<T>(
+        context,
+        512,
+        512,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
@@ -154,6 +154,9 @@ float is_visible(sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
         return 1.0;
     }
     float shadow_cast_distance = texture(shadowMap, uv).x;
+#ifdef REVERSE_Z
+    shadow_cast_distance = 1.0 - shadow_cast_distance;
+#endif
     if(shadow_cast_distance > 0.999) {
         return 1.0;
     }
@@ -161,8 +164,14 @@ float is_visible(sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
     return shadow_cast_distance > true_distance ? 1.0 : 0.0;
 }
 
+// Set to true to calculate the lighting without shadows, for example to find out how much a point is shadowed
+bool ignoreShadows = false;
+
 float calculate_shadow(sampler2D shadowMap, mat4 shadowMVP, vec3 position)
 {
+    if (ignoreShadows) {
+        return 1.0;
+    }
     vec4 shadow_coord = shadowMVP * vec4(position, 1.);
     float visibility = 0.0;
     vec2 poissonDisk[4] = vec2[](
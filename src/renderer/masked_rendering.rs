@@ -0,0 +1,100 @@
+//!
+//! Stencil based masking, which restricts rendering to the region of the screen covered by some geometries, for example for portals, mirrors and clipping of user interfaces.
+//!
+
+use crate::renderer::*;
+
+///
+/// Restricts rendering to a region of the render target which is marked in the stencil buffer by rendering the shape of some geometries, see [Stencil].
+/// The render target must have a stencil buffer, for example a [DepthTexture2D] with the [f24s8] data type or a window with a stencil buffer,
+/// and the stencil buffer should be cleared before writing the mask, for example using [ClearState::stencil].
+///
+/// The mask is written with [MaskedRendering::write_mask], for example the shape of a mirror or a portal, which is hidden behind other objects as usual,
+/// and then everything rendered inside the closure given to [MaskedRendering::render_inside] is only visible inside the mask,
+/// for example the scene seen through the mirror or portal, regardless of the materials used.
+/// Several masks can be used at the same time by using a different reference value for each mask.
+///
+pub struct MaskedRendering {
+    context: Context,
+    /// The value written to the stencil buffer where the mask is and compared with the stencil buffer when rendering inside or outside the mask.
+    pub reference: u8,
+}
+
+impl MaskedRendering {
+    ///
+    /// Creates a new masked rendering helper using the given reference value, which should be different from the value the stencil buffer is cleared to.
+    ///
+    pub fn new(context: &Context, reference: u8) -> Self {
+        Self {
+            context: context.clone(),
+            reference,
+        }
+    }
+
+    ///
+    /// Adds the region covered by the given geometries to the mask by writing the reference value to the stencil buffer.
+    /// The geometries are not visible since neither the color nor the depth is written, but they are tested against the depth buffer, so the mask is hidden behind other objects.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn write_mask(&self, camera: &Camera, geometries: impl IntoIterator<Item = impl Geometry>) {
+        let material = MaskMaterial {
+            reference: self.reference,
+        };
+        // The mask is written even if called inside the closure given to render_inside or render_outside
+        let previous = self.context.stencil_override();
+        self.context.set_stencil_override(None);
+        for geometry in geometries {
+            geometry.render_with_material(&material, camera, &[]);
+        }
+        self.context.set_stencil_override(previous);
+    }
+
+    ///
+    /// Everything rendered in the `render` closure is only visible inside the mask.
+    ///
+    pub fn render_inside(&self, render: impl FnOnce()) {
+        self.render_with_stencil(Stencil::equal(self.reference), render)
+    }
+
+    ///
+    /// Everything rendered in the `render` closure is only visible outside the mask.
+    ///
+    pub fn render_outside(&self, render: impl FnOnce()) {
+        self.render_with_stencil(Stencil::not_equal(self.reference), render)
+    }
+
+    fn render_with_stencil(&self, stencil: Stencil, render: impl FnOnce()) {
+        let previous = self.context.stencil_override();
+        self.context.set_stencil_override(Some(stencil));
+        render();
+        self.context.set_stencil_override(previous);
+    }
+}
+
+struct MaskMaterial {
+    reference: u8,
+}
+
+impl Material for MaskMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: "layout (location = 0) out vec4 outColor;\nvoid main() {\n    outColor = vec4(0.0);\n}\n"
+                .to_owned(),
+            attributes: FragmentAttributes::NONE,
+        }
+    }
+
+    fn use_uniforms(&self, _program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {}
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::NONE,
+            stencil: Stencil::write(self.reference),
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
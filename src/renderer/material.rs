@@ -55,6 +55,38 @@ mod isosurface_material;
 #[doc(inline)]
 pub use isosurface_material::*;
 
+mod sdf_material;
+#[doc(inline)]
+pub use sdf_material::*;
+
+mod shadow_catcher_material;
+#[doc(inline)]
+pub use shadow_catcher_material::*;
+
+mod decal_material;
+#[doc(inline)]
+pub use decal_material::*;
+
+mod id_material;
+#[doc(inline)]
+pub use id_material::*;
+
+mod layered_material;
+#[doc(inline)]
+pub use layered_material::*;
+
+mod highlight_material;
+#[doc(inline)]
+pub use highlight_material::*;
+
+mod texture_atlas;
+#[doc(inline)]
+pub use texture_atlas::*;
+
+mod colormap_material;
+#[doc(inline)]
+pub use colormap_material::*;
+
 use std::sync::Arc;
 
 ///
@@ -115,6 +147,9 @@ pub struct FragmentAttributes {
     pub uv: bool,
     /// Color: `in vec4 col;`
     pub color: bool,
+    /// The index of the instance for instanced geometries and 0 otherwise: `flat in int instanceId;`
+    /// Only supported by [Mesh], [InstancedMesh], [ParticleSystem] and [Crowd].
+    pub instance_id: bool,
 }
 
 impl FragmentAttributes {
@@ -125,6 +160,7 @@ impl FragmentAttributes {
         tangents: true,
         uv: true,
         color: true,
+        instance_id: true,
     };
     /// No attributes
     pub const NONE: Self = Self {
@@ -133,6 +169,7 @@ impl FragmentAttributes {
         tangents: false,
         uv: false,
         color: false,
+        instance_id: false,
     };
 }
 
@@ -171,6 +208,30 @@ pub trait Material {
     /// Returns the type of material.
     ///
     fn material_type(&self) -> MaterialType;
+
+    ///
+    /// Returns the number of bytes of GPU memory used by the textures of this material.
+    ///
+    fn texture_memory(&self) -> usize {
+        0
+    }
+
+    ///
+    /// Returns the base color of this material, not including any textures.
+    /// Used as the albedo output when rendering auxiliary buffers, see [AovTargets].
+    ///
+    fn albedo(&self) -> Color {
+        Color::WHITE
+    }
+
+    ///
+    /// Returns the number of color outputs written by the fragment shader of this material, ie. the outputs declared with `layout (location = 0)` up to `layout (location = n - 1)`.
+    /// A material which writes to several color attachments at the same time, for example the attachments of a render target constructed with [RenderTarget::new_mrt]
+    /// as part of a custom deferred pipeline, should return the number of outputs. The content of the attachments which are not written by the fragment shader is undefined.
+    ///
+    fn color_outputs(&self) -> u32 {
+        1
+    }
 }
 
 ///
@@ -206,6 +267,15 @@ impl<T: Material + ?Sized> Material for &T {
     fn material_type(&self) -> MaterialType {
         (*self).material_type()
     }
+    fn texture_memory(&self) -> usize {
+        (*self).texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        (*self).albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        (*self).color_outputs()
+    }
 }
 
 impl<T: Material + ?Sized> Material for &mut T {
@@ -221,6 +291,15 @@ impl<T: Material + ?Sized> Material for &mut T {
     fn material_type(&self) -> MaterialType {
         (**self).material_type()
     }
+    fn texture_memory(&self) -> usize {
+        (**self).texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        (**self).albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        (**self).color_outputs()
+    }
 }
 
 impl<T: Material> Material for Box<T> {
@@ -236,6 +315,15 @@ impl<T: Material> Material for Box<T> {
     fn material_type(&self) -> MaterialType {
         self.as_ref().material_type()
     }
+    fn texture_memory(&self) -> usize {
+        self.as_ref().texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        self.as_ref().albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        self.as_ref().color_outputs()
+    }
 }
 
 impl<T: Material> Material for std::rc::Rc<T> {
@@ -251,6 +339,15 @@ impl<T: Material> Material for std::rc::Rc<T> {
     fn material_type(&self) -> MaterialType {
         self.as_ref().material_type()
     }
+    fn texture_memory(&self) -> usize {
+        self.as_ref().texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        self.as_ref().albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        self.as_ref().color_outputs()
+    }
 }
 
 impl<T: Material> Material for std::sync::Arc<T> {
@@ -266,6 +363,15 @@ impl<T: Material> Material for std::sync::Arc<T> {
     fn material_type(&self) -> MaterialType {
         self.as_ref().material_type()
     }
+    fn texture_memory(&self) -> usize {
+        self.as_ref().texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        self.as_ref().albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        self.as_ref().color_outputs()
+    }
 }
 
 impl<T: Material> Material for std::cell::RefCell<T> {
@@ -281,6 +387,15 @@ impl<T: Material> Material for std::cell::RefCell<T> {
     fn material_type(&self) -> MaterialType {
         self.borrow().material_type()
     }
+    fn texture_memory(&self) -> usize {
+        self.borrow().texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        self.borrow().albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        self.borrow().color_outputs()
+    }
 }
 
 impl<T: Material> Material for std::sync::RwLock<T> {
@@ -296,6 +411,15 @@ impl<T: Material> Material for std::sync::RwLock<T> {
     fn material_type(&self) -> MaterialType {
         self.read().unwrap().material_type()
     }
+    fn texture_memory(&self) -> usize {
+        self.read().unwrap().texture_memory()
+    }
+    fn albedo(&self) -> Color {
+        self.read().unwrap().albedo()
+    }
+    fn color_outputs(&self) -> u32 {
+        self.read().unwrap().color_outputs()
+    }
 }
 
 fn is_transparent(cpu_material: &CpuMaterial) -> bool {
@@ -382,7 +506,11 @@ impl DepthTexture<'_> {
                 uniform sampler2D depthMap;
                 float sample_depth(vec2 uv)
                 {
+                #ifdef REVERSE_Z
+                    return 1.0 - texture(depthMap, uv).x;
+                #else
                     return texture(depthMap, uv).x;
+                #endif
                 }"
             .to_owned(),
             Self::Array { .. } => "
@@ -390,7 +518,11 @@ impl DepthTexture<'_> {
                 uniform int depthLayer;
                 float sample_depth(vec2 uv)
                 {
+                #ifdef REVERSE_Z
+                    return 1.0 - texture(depthMap, vec3(uv, depthLayer)).x;
+                #else
                     return texture(depthMap, vec3(uv, depthLayer)).x;
+                #endif
                 }"
             .to_owned(),
             Self::CubeMap { .. } => {
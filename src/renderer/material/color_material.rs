@@ -14,6 +14,9 @@ pub struct ColorMaterial {
     pub texture: Option<Texture2DRef>,
     /// Render states.
     pub render_states: RenderStates,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    /// This field is new since version 0.16, so struct literals which do not use `..Default::default()` must set it, `0.0` gives the previous result.
+    pub lod_bias: f32,
     /// Whether this material should be treated as a transparent material (An object needs to be rendered differently depending on whether it is transparent or opaque).
     pub is_transparent: bool,
 }
@@ -42,6 +45,7 @@ impl ColorMaterial {
             color: cpu_material.albedo,
             texture,
             is_transparent: false,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
         }
     }
@@ -56,6 +60,7 @@ impl ColorMaterial {
             color: cpu_material.albedo,
             texture,
             is_transparent: true,
+            lod_bias: 0.0,
             render_states: RenderStates {
                 write_mask: WriteMask::COLOR,
                 blend: Blend::TRANSPARENCY,
@@ -69,6 +74,7 @@ impl ColorMaterial {
         Self {
             color: physical_material.albedo,
             texture: physical_material.albedo_texture.clone(),
+            lod_bias: physical_material.lod_bias,
             render_states: physical_material.render_states,
             is_transparent: physical_material.is_transparent,
         }
@@ -88,9 +94,12 @@ impl Material for ColorMaterial {
             ..FragmentAttributes::NONE
         };
         let mut shader = String::new();
-        if self.texture.is_some() {
+        if let Some(texture) = &self.texture {
             attributes.uv = true;
             shader.push_str("#define USE_TEXTURE\nin vec2 uvs;\n");
+            if texture.is_srgb() {
+                shader.push_str("#define TEXTURE_SRGB\n");
+            }
         }
         shader.push_str(include_str!("../../core/shared.frag"));
         shader.push_str(include_str!("shaders/color_material.frag"));
@@ -101,6 +110,10 @@ impl Material for ColorMaterial {
     }
 
     fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
         program.use_uniform("surfaceColor", self.color);
         if let Some(ref tex) = self.texture {
             program.use_uniform("textureTransformation", tex.transformation);
@@ -117,4 +130,10 @@ impl Material for ColorMaterial {
             MaterialType::Opaque
         }
     }
+    fn texture_memory(&self) -> usize {
+        self.texture.as_ref().map(|t| t.memory_size()).unwrap_or(0)
+    }
+    fn albedo(&self) -> Color {
+        self.color
+    }
 }
@@ -0,0 +1,285 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::sync::Arc;
+
+// The number of colors in the lookup table of a colormap
+const COLORMAP_SIZE: u32 = 256;
+
+///
+/// A colormap which maps a scalar value in the range `[0..1]` to a color, see [ColormapMaterial].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Colormap {
+    /// The perceptually uniform colormap from dark blue through green to yellow.
+    Viridis,
+    /// The perceptually uniform colormap from dark blue through magenta to yellow.
+    Plasma,
+    /// An improved rainbow colormap from dark blue through green and yellow to dark red.
+    Turbo,
+    /// A diverging colormap from blue through light gray to red, for values above and below a center value.
+    Coolwarm,
+    /// Equally spaced colors from the color at the lowest value to the color at the highest value, which are linearly interpolated.
+    Custom(Vec<Color>),
+}
+
+impl Colormap {
+    ///
+    /// Returns the equally spaced colors which are interpolated to get the color of a value.
+    ///
+    pub fn colors(&self) -> Vec<Color> {
+        let colors: &[(u8, u8, u8)] = match self {
+            Self::Viridis => &[
+                (68, 1, 84),
+                (71, 45, 123),
+                (59, 82, 139),
+                (44, 114, 142),
+                (33, 145, 140),
+                (40, 174, 128),
+                (94, 201, 98),
+                (173, 220, 48),
+                (253, 231, 37),
+            ],
+            Self::Plasma => &[
+                (13, 8, 135),
+                (76, 2, 161),
+                (126, 3, 168),
+                (169, 35, 149),
+                (204, 71, 120),
+                (229, 107, 93),
+                (248, 149, 64),
+                (253, 195, 40),
+                (240, 249, 33),
+            ],
+            Self::Turbo => &[
+                (48, 18, 59),
+                (70, 107, 227),
+                (40, 187, 236),
+                (49, 242, 153),
+                (162, 252, 60),
+                (237, 208, 58),
+                (251, 128, 34),
+                (208, 47, 5),
+                (122, 4, 3),
+            ],
+            Self::Coolwarm => &[
+                (59, 76, 192),
+                (98, 130, 234),
+                (141, 176, 254),
+                (184, 208, 249),
+                (221, 221, 221),
+                (245, 196, 173),
+                (244, 154, 123),
+                (222, 96, 77),
+                (180, 4, 38),
+            ],
+            Self::Custom(colors) => {
+                return if colors.is_empty() {
+                    vec![Color::WHITE]
+                } else {
+                    colors.clone()
+                }
+            }
+        };
+        colors
+            .iter()
+            .map(|(r, g, b)| Color::new_opaque(*r, *g, *b))
+            .collect()
+    }
+
+    ///
+    /// Returns the color of the given value in the range `[0..1]`, where values outside the range are clamped.
+    ///
+    pub fn sample(&self, t: f32) -> Color {
+        let colors = self.colors();
+        let x = t.clamp(0.0, 1.0) * (colors.len() - 1) as f32;
+        let i = (x.floor() as usize).min(colors.len() - 1);
+        let j = (i + 1).min(colors.len() - 1);
+        let f = x - i as f32;
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+        Color::new(
+            mix(colors[i].r, colors[j].r),
+            mix(colors[i].g, colors[j].g),
+            mix(colors[i].b, colors[j].b),
+            mix(colors[i].a, colors[j].a),
+        )
+    }
+}
+
+///
+/// The scalar value which is mapped to a color by a [ColormapMaterial].
+///
+#[derive(Clone)]
+pub enum ScalarSource {
+    /// The u coordinate of the uv coordinates of the geometry, which is the easiest way to specify a scalar value per vertex in full precision,
+    /// for example by setting the uv coordinates of a [CpuMesh] to `vec2(value, 0.0)`.
+    UvU,
+    /// The red channel of the vertex colors of the geometry, which is in the range `[0..1]`.
+    VertexColor,
+    /// The given channel (0 to 3) of the texture, which is sampled using the uv coordinates of the geometry.
+    Texture {
+        /// The texture containing the scalar values.
+        texture: Texture2DRef,
+        /// The channel of the texture containing the scalar values.
+        channel: usize,
+    },
+}
+
+///
+/// A material which visualizes a scalar field, for example the result of a simulation, by mapping a scalar value to a color using a [Colormap].
+/// The scalar value is given by the [ColormapMaterial::source] and is mapped linearly from the [ColormapMaterial::value_range] to the colormap.
+/// The colormap can be divided into discrete bands and contour lines can be drawn at regular intervals of the value.
+/// This material is not affected by lights.
+///
+#[derive(Clone)]
+pub struct ColormapMaterial {
+    context: Context,
+    colormap: Colormap,
+    lookup_table: Arc<Texture2D>,
+    /// The source of the scalar value.
+    pub source: ScalarSource,
+    /// The values mapped to the first and last color of the colormap. Values outside the range are clamped.
+    pub value_range: (f32, f32),
+    /// The number of discrete bands of equal color which the colormap is divided into, or 0 for a continuous colormap.
+    pub bands: u32,
+    /// The difference in value between two contour lines, for example 0.1 to draw a line at each multiple of 0.1, or 0 to disable the contour lines.
+    pub contour_spacing: f32,
+    /// The width of the contour lines in pixels.
+    pub contour_width: f32,
+    /// The color of the contour lines. Assumed to be in linear color space.
+    pub contour_color: Color,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl ColormapMaterial {
+    ///
+    /// Creates a new material which maps the given source to the given colormap, with the value range `[0..1]`, a continuous colormap and no contour lines.
+    ///
+    pub fn new(context: &Context, colormap: Colormap, source: ScalarSource) -> Self {
+        Self {
+            context: context.clone(),
+            lookup_table: Arc::new(lookup_table(context, &colormap)),
+            colormap,
+            source,
+            value_range: (0.0, 1.0),
+            bands: 0,
+            contour_spacing: 0.0,
+            contour_width: 1.0,
+            contour_color: Color::BLACK,
+            render_states: RenderStates::default(),
+        }
+    }
+
+    ///
+    /// Returns the colormap.
+    ///
+    pub fn colormap(&self) -> &Colormap {
+        &self.colormap
+    }
+
+    ///
+    /// Sets the colormap.
+    ///
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.lookup_table = Arc::new(lookup_table(&self.context, &colormap));
+        self.colormap = colormap;
+    }
+
+    ///
+    /// Sets the value range to the minimum and maximum of the given values, for example the scalar values of all vertices.
+    ///
+    pub fn fit_value_range(&mut self, values: &[f32]) {
+        let (min, max) = values
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            });
+        if min <= max {
+            self.value_range = (min, if max > min { max } else { min + 1.0 });
+        }
+    }
+}
+
+fn lookup_table(context: &Context, colormap: &Colormap) -> Texture2D {
+    Texture2D::new(
+        context,
+        &CpuTexture {
+            data: TextureData::RgbaU8(
+                (0..COLORMAP_SIZE)
+                    .map(|i| {
+                        let c = colormap.sample(i as f32 / (COLORMAP_SIZE - 1) as f32);
+                        [c.r, c.g, c.b, c.a]
+                    })
+                    .collect(),
+            ),
+            width: COLORMAP_SIZE,
+            height: 1,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mip_map_filter: None,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            ..Default::default()
+        },
+    )
+}
+
+impl Material for ColormapMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        let mut attributes = FragmentAttributes::NONE;
+        let mut shader = String::new();
+        match self.source {
+            ScalarSource::UvU => {
+                attributes.uv = true;
+                shader.push_str("#define SOURCE_UV\nin vec2 uvs;\n");
+            }
+            ScalarSource::VertexColor => {
+                attributes.color = true;
+                shader.push_str("#define SOURCE_COLOR\n");
+            }
+            ScalarSource::Texture { .. } => {
+                attributes.uv = true;
+                shader.push_str("#define SOURCE_TEXTURE\nin vec2 uvs;\n");
+            }
+        }
+        shader.push_str(include_str!("../../core/shared.frag"));
+        shader.push_str(include_str!("shaders/colormap_material.frag"));
+        FragmentShader {
+            source: shader,
+            attributes,
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_texture("colormap", &self.lookup_table);
+        program.use_uniform("valueRange", vec2(self.value_range.0, self.value_range.1));
+        program.use_uniform("bands", self.bands as f32);
+        program.use_uniform("contourSpacing", self.contour_spacing.max(0.0));
+        program.use_uniform_if_required("contourWidth", self.contour_width);
+        program.use_uniform_if_required("contourColor", self.contour_color);
+        if let ScalarSource::Texture {
+            ref texture,
+            channel,
+        } = self.source
+        {
+            program.use_uniform("textureTransformation", texture.transformation);
+            program.use_texture("tex", texture);
+            program.use_uniform("channel", channel.min(3) as i32);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+
+    fn texture_memory(&self) -> usize {
+        match &self.source {
+            ScalarSource::Texture { texture, .. } => texture.memory_size(),
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A material that projects a texture onto the underlying geometry, for example bullet holes, stickers or labels.
+/// The decal is projected along the negative z-axis of the unit cube (from -0.5 to 0.5 in all axes) transformed by [DecalMaterial::transformation]
+/// and the texture is mapped to the xy-plane of that cube.
+///
+/// The material can be used in two ways:
+/// - As a [PostMaterial] (deferred decal) where the surface position is reconstructed from a depth texture. This is usually done using a [Decal] object.
+/// - As a [Material] (forward decal) by rendering the geometries that should receive the decal a second time with this material.
+///
+/// This material is affected by lights if any are given, otherwise the color of the decal is used directly.
+///
+#[derive(Clone)]
+pub struct DecalMaterial {
+    /// The transformation from the unit cube to the box in which the decal is projected.
+    /// Nothing is rendered if the transformation is not invertible, for example if it scales an axis to zero, since the box is then empty.
+    pub transformation: Mat4,
+    /// Albedo base color, also called diffuse color. Assumed to be in linear color space.
+    pub albedo: Color,
+    /// Texture with albedo base colors, also called diffuse color. Assumed to be in sRGB with or without an alpha channel.
+    pub albedo_texture: Option<Texture2DRef>,
+    /// A value in the range `[0..1]` specifying how metallic the surface is.
+    pub metallic: f32,
+    /// A value in the range `[0..1]` specifying how rough the surface is.
+    pub roughness: f32,
+    /// Texture containing the metallic and roughness parameters which are multiplied with the [Self::metallic] and [Self::roughness] values in the shader.
+    /// The metallic values are sampled from the blue channel and the roughness from the green channel.
+    pub metallic_roughness_texture: Option<Texture2DRef>,
+    /// A scalar multiplier applied to each normal vector of the [Self::normal_texture].
+    pub normal_scale: f32,
+    /// A tangent space normal map, where the tangent space is defined by the x- and y-axis of the decal box.
+    pub normal_texture: Option<Texture2DRef>,
+    /// The lighting model used when rendering this material
+    pub lighting_model: LightingModel,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    pub lod_bias: f32,
+}
+
+impl DecalMaterial {
+    ///
+    /// Creates a new decal material that projects the given texture into the box defined by the transformation.
+    ///
+    pub fn new(albedo_texture: Texture2DRef, transformation: Mat4) -> Self {
+        Self {
+            transformation,
+            albedo_texture: Some(albedo_texture),
+            ..Default::default()
+        }
+    }
+
+    fn shader(&self, lights: &[&dyn Light], depth_texture: Option<DepthTexture>) -> FragmentShader {
+        let mut output = lights_shader_source(lights, self.lighting_model);
+        if !lights.is_empty() {
+            output.push_str("#define USE_LIGHTING\n");
+            if self.metallic_roughness_texture.is_some() {
+                output.push_str("#define USE_METALLIC_ROUGHNESS_TEXTURE\n");
+            }
+            if self.normal_texture.is_some() {
+                output.push_str("#define USE_NORMAL_TEXTURE\n");
+            }
+        }
+        if let Some(texture) = &self.albedo_texture {
+            output.push_str("#define USE_ALBEDO_TEXTURE\n");
+            if texture.is_srgb() {
+                output.push_str("#define ALBEDO_TEXTURE_SRGB\n");
+            }
+        }
+        if let Some(depth_texture) = depth_texture {
+            output.push_str("#define USE_DEPTH_TEXTURE\n");
+            output.push_str(&depth_texture.fragment_shader_source());
+        }
+        output.push_str(include_str!("shaders/decal_material.frag"));
+        FragmentShader {
+            source: output,
+            attributes: FragmentAttributes {
+                position: depth_texture.is_none(),
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        depth_texture: Option<DepthTexture>,
+    ) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
+        if let Some(depth_texture) = depth_texture {
+            depth_texture.use_uniforms(program);
+            program.use_uniform(
+                "viewProjectionInverse",
+                (camera.projection() * camera.view()).invert().unwrap(),
+            );
+            let viewport = camera.viewport();
+            program.use_uniform(
+                "viewport",
+                vec4(
+                    viewport.x as f32,
+                    viewport.y as f32,
+                    viewport.width as f32,
+                    viewport.height as f32,
+                ),
+            );
+        }
+        // A transformation which is not invertible defines an empty box, so the inverse is replaced by a transformation which moves all positions outside the box, where they are discarded
+        program.use_uniform(
+            "decalTransformationInverse",
+            self.transformation.invert().unwrap_or_else(|| {
+                Mat4::from_translation(vec3(1.0, 1.0, 1.0)) * Mat4::from_scale(0.0)
+            }),
+        );
+        program.use_uniform(
+            "decalDirection",
+            -self.transformation.z.truncate().normalize(),
+        );
+        program.use_uniform("cameraPosition", camera.position());
+        program.use_uniform("albedo", self.albedo);
+        if let Some(ref texture) = self.albedo_texture {
+            program.use_uniform("albedoTexTransform", texture.transformation);
+            program.use_texture("albedoTexture", texture);
+        }
+        if !lights.is_empty() {
+            for (i, light) in lights.iter().enumerate() {
+                light.use_uniforms(program, i as u32);
+            }
+            program.use_uniform("metallic", self.metallic);
+            program.use_uniform("roughness", self.roughness);
+            if let Some(ref texture) = self.metallic_roughness_texture {
+                program.use_uniform("metallicRoughnessTexTransform", texture.transformation);
+                program.use_texture("metallicRoughnessTexture", texture);
+            }
+            if let Some(ref texture) = self.normal_texture {
+                program.use_uniform("decalTangent", self.transformation.x.truncate().normalize());
+                program.use_uniform(
+                    "decalBitangent",
+                    self.transformation.y.truncate().normalize(),
+                );
+                program.use_uniform("normalTexTransform", texture.transformation);
+                program.use_uniform("normalScale", self.normal_scale);
+                program.use_texture("normalTexture", texture);
+            }
+        }
+    }
+}
+
+impl Material for DecalMaterial {
+    fn fragment_shader(&self, lights: &[&dyn Light]) -> FragmentShader {
+        self.shader(lights, None)
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
+        self.uniforms(program, camera, lights, None)
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::LessOrEqual,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+
+    fn texture_memory(&self) -> usize {
+        [
+            &self.albedo_texture,
+            &self.metallic_roughness_texture,
+            &self.normal_texture,
+        ]
+        .iter()
+        .filter_map(|t| t.as_ref().map(|t| t.memory_size()))
+        .sum()
+    }
+}
+
+impl PostMaterial for DecalMaterial {
+    fn fragment_shader(
+        &self,
+        lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> FragmentShader {
+        self.shader(
+            lights,
+            Some(depth_texture.expect("Must supply a depth texture to render a decal")),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.uniforms(
+            program,
+            camera,
+            lights,
+            Some(depth_texture.expect("Must supply a depth texture to render a decal")),
+        )
+    }
+
+    fn render_states(&self) -> RenderStates {
+        // Render the back faces of the decal box without depth test, so the decal is also visible when the camera is inside the box
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::Front,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+impl Default for DecalMaterial {
+    fn default() -> Self {
+        Self {
+            transformation: Mat4::identity(),
+            albedo: Color::WHITE,
+            albedo_texture: None,
+            metallic: 0.0,
+            roughness: 1.0,
+            metallic_roughness_texture: None,
+            normal_scale: 1.0,
+            normal_texture: None,
+            lighting_model: LightingModel::Blinn,
+            lod_bias: 0.0,
+        }
+    }
+}
@@ -11,6 +11,7 @@ use std::sync::Arc;
 /// However, it is not possible to use the [Object::render] method to render a [Geometry] with this material directly to the screen.
 /// Instead render the object into a [RenderTarget] consisting of a [Texture2DArray] with three RGBA u8 layers as color target and a [DepthTexture2D] as depth target.
 /// Then call the [DeferredPhysicalMaterial::lighting_pass] method with these textures to render to the screen.
+/// To configure the layout of the textures or to insert custom passes between the two stages, use a [GBuffer].
 ///
 #[derive(Clone)]
 pub struct DeferredPhysicalMaterial {
@@ -38,6 +39,9 @@ pub struct DeferredPhysicalMaterial {
     pub normal_texture: Option<Texture2DRef>,
     /// Render states
     pub render_states: RenderStates,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    /// This field is new since version 0.16, so struct literals which do not use `..Default::default()` must set it, `0.0` gives the previous result.
+    pub lod_bias: f32,
     /// Color of light shining from an object.
     pub emissive: Color,
     /// Texture with color of light shining from an object.
@@ -95,6 +99,7 @@ impl DeferredPhysicalMaterial {
             normal_scale: cpu_material.normal_scale,
             occlusion_texture,
             occlusion_strength: cpu_material.occlusion_strength,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
             alpha_cutout: cpu_material.alpha_cutout,
             emissive: cpu_material.emissive,
@@ -117,6 +122,7 @@ impl DeferredPhysicalMaterial {
             normal_scale: physical_material.normal_scale,
             occlusion_texture: physical_material.occlusion_texture.clone(),
             occlusion_strength: physical_material.occlusion_strength,
+            lod_bias: physical_material.lod_bias,
             render_states: RenderStates {
                 write_mask: WriteMask::default(),
                 blend: Blend::Disabled,
@@ -143,13 +149,37 @@ impl DeferredPhysicalMaterial {
         geometry_pass_depth_texture: DepthTexture,
         lights: &[&dyn Light],
     ) {
-        let mut fragment_shader = lights_shader_source(
+        Self::lighting_pass_with_layout(
+            context,
+            camera,
+            geometry_pass_color_texture,
+            geometry_pass_depth_texture,
+            GBufferLayout::default(),
+            lights,
+        )
+    }
+
+    // The lighting pass for a G-buffer with the given layout, see GBuffer::lighting_pass
+    pub(crate) fn lighting_pass_with_layout(
+        context: &Context,
+        camera: &Camera,
+        geometry_pass_color_texture: ColorTexture,
+        geometry_pass_depth_texture: DepthTexture,
+        layout: GBufferLayout,
+        lights: &[&dyn Light],
+    ) {
+        let mut fragment_shader = if layout.emissive {
+            String::new()
+        } else {
+            "#define NO_EMISSIVE_LAYER\n".to_owned()
+        };
+        fragment_shader.push_str(&lights_shader_source(
             lights,
             LightingModel::Cook(
                 NormalDistributionFunction::TrowbridgeReitzGGX,
                 GeometryFunction::SmithSchlickGGX,
             ),
-        );
+        ));
         fragment_shader.push_str(&geometry_pass_color_texture.fragment_shader_source());
         fragment_shader.push_str(&geometry_pass_depth_texture.fragment_shader_source());
         fragment_shader.push_str(include_str!("shaders/deferred_lighting.frag"));
@@ -199,8 +229,11 @@ impl Material for DeferredPhysicalMaterial {
         {
             attributes.uv = true;
             output.push_str("in vec2 uvs;\n");
-            if self.albedo_texture.is_some() {
+            if let Some(texture) = &self.albedo_texture {
                 output.push_str("#define USE_ALBEDO_TEXTURE;\n");
+                if texture.is_srgb() {
+                    output.push_str("#define ALBEDO_TEXTURE_SRGB;\n");
+                }
             }
             if self.metallic_roughness_texture.is_some() {
                 output.push_str("#define USE_METALLIC_ROUGHNESS_TEXTURE;\n");
@@ -212,8 +245,11 @@ impl Material for DeferredPhysicalMaterial {
                 attributes.tangents = true;
                 output.push_str("#define USE_NORMAL_TEXTURE;\nin vec3 tang;\nin vec3 bitang;\n");
             }
-            if self.emissive_texture.is_some() {
+            if let Some(texture) = &self.emissive_texture {
                 output.push_str("#define USE_EMISSIVE_TEXTURE;\n");
+                if texture.is_srgb() {
+                    output.push_str("#define EMISSIVE_TEXTURE_SRGB;\n");
+                }
             }
             if self.alpha_cutout.is_some() {
                 output.push_str(
@@ -233,6 +269,10 @@ impl Material for DeferredPhysicalMaterial {
     }
 
     fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
         program.use_uniform("metallic", self.metallic);
         program.use_uniform("roughness", self.roughness);
         program.use_uniform("albedo", self.albedo);
@@ -270,6 +310,23 @@ impl Material for DeferredPhysicalMaterial {
     fn material_type(&self) -> MaterialType {
         MaterialType::Deferred
     }
+
+    fn texture_memory(&self) -> usize {
+        [
+            &self.albedo_texture,
+            &self.metallic_roughness_texture,
+            &self.occlusion_texture,
+            &self.normal_texture,
+            &self.emissive_texture,
+        ]
+        .iter()
+        .filter_map(|t| t.as_ref().map(|t| t.memory_size()))
+        .sum()
+    }
+
+    fn albedo(&self) -> Color {
+        self.albedo
+    }
 }
 
 impl Default for DeferredPhysicalMaterial {
@@ -285,6 +342,7 @@ impl Default for DeferredPhysicalMaterial {
             normal_scale: 1.0,
             occlusion_texture: None,
             occlusion_strength: 1.0,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
             alpha_cutout: None,
             emissive: Color::BLACK,
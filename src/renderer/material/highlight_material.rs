@@ -0,0 +1,59 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A material which is rendered on top of an already rendered object to highlight it, for example when the object is hovered or selected in an editor.
+/// The object is tinted with a transparent color and optionally covered with diagonal hatch lines.
+/// The depth test passes where the object is visible, so render the highlighted objects with this material after the scene has been rendered, see also [Highlighter].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct HighlightMaterial {
+    /// The color blended on top of the object. Use an alpha value of zero to disable the tint.
+    pub tint: Color,
+    /// The color of the hatch lines.
+    pub hatch_color: Color,
+    /// The distance in pixels between the hatch lines. A value of zero disables the hatch lines.
+    pub hatch_spacing: f32,
+    /// The width in pixels of the hatch lines.
+    pub hatch_width: f32,
+}
+
+impl Default for HighlightMaterial {
+    fn default() -> Self {
+        Self {
+            tint: Color::new(255, 165, 0, 80),
+            hatch_color: Color::new(255, 165, 0, 160),
+            hatch_spacing: 0.0,
+            hatch_width: 2.0,
+        }
+    }
+}
+
+impl Material for HighlightMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: include_str!("shaders/highlight_material.frag").to_string(),
+            attributes: FragmentAttributes::NONE,
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform("tintColor", self.tint);
+        program.use_uniform("hatchColor", self.hatch_color);
+        program.use_uniform("hatchSpacing", self.hatch_spacing);
+        program.use_uniform("hatchWidth", self.hatch_width);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::LessOrEqual,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
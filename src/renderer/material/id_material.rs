@@ -0,0 +1,73 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Renders a [Geometry] with a 32-bit ID encoded into the four 8-bit channels of the color (the least significant byte in the red channel).
+/// Used for identifying which object is visible at a pixel, see for example [GBuffer::read_id_at].
+/// This material is not affected by lights.
+///
+#[derive(Clone, Debug)]
+pub struct IdMaterial {
+    /// The ID written to each pixel covered by the geometry.
+    pub id: u32,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl IdMaterial {
+    ///
+    /// Creates a new ID material with the given ID.
+    ///
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+            render_states: RenderStates {
+                depth_test: DepthTest::LessOrEqual,
+                ..Default::default()
+            },
+        }
+    }
+
+    ///
+    /// Decodes an ID from a pixel value read from a texture rendered with an [IdMaterial].
+    ///
+    pub fn decode(pixel: [u8; 4]) -> u32 {
+        u32::from_le_bytes(pixel)
+    }
+}
+
+impl Material for IdMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: include_str!("shaders/id_material.frag").to_string(),
+            attributes: FragmentAttributes::NONE,
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        let bytes = self.id.to_le_bytes();
+        program.use_uniform(
+            "id",
+            vec4(
+                bytes[0] as f32,
+                bytes[1] as f32,
+                bytes[2] as f32,
+                bytes[3] as f32,
+            ) / 255.0,
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+impl Default for IdMaterial {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
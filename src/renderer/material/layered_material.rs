@@ -0,0 +1,297 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Defines where a [MaterialLayer] in a [LayeredMaterial] covers the layers below it.
+/// Each mask results in a weight between 0 and 1 where 0 means that the layers below are visible and 1 means that the layer is fully visible.
+///
+#[derive(Clone)]
+pub enum LayerMask {
+    /// The weight is sampled from the red channel of the texture using the uv coordinates of the geometry.
+    Texture(Texture2DRef),
+    /// The weight is given by the vertex color channel with the given index, ie. 0 for red, 1 for green, 2 for blue and 3 for alpha.
+    VertexColor(usize),
+    /// The weight is given by the angle in radians between the surface normal and the y-axis,
+    /// it goes smoothly from 0 at the start angle to 1 at the end angle.
+    /// Use a start angle larger than the end angle to cover flat surfaces, for example with snow.
+    Slope {
+        /// The angle where the weight is 0.
+        start: f32,
+        /// The angle where the weight is 1.
+        end: f32,
+    },
+    /// The weight is given by the height, ie. the y-coordinate in world space,
+    /// it goes smoothly from 0 at the start height to 1 at the end height.
+    Height {
+        /// The height where the weight is 0.
+        start: f32,
+        /// The height where the weight is 1.
+        end: f32,
+    },
+}
+
+///
+/// A layer in a [LayeredMaterial].
+///
+#[derive(Clone)]
+pub struct MaterialLayer {
+    /// The material of this layer.
+    pub material: PhysicalMaterial,
+    /// Defines where this layer covers the layers below.
+    pub mask: LayerMask,
+}
+
+///
+/// A material that blends a number of [PhysicalMaterial]s on top of each other, for example moss on rock or snow on a terrain.
+/// Each layer is blended on top of the layers below using the weight given by its [LayerMask].
+/// All layers are evaluated in one shader pass, so the geometry is only rendered once.
+/// The vertex colors are not multiplied onto the albedo, since they can be used as masks.
+/// This material is affected by lights.
+///
+#[derive(Clone)]
+pub struct LayeredMaterial {
+    /// The bottom layer which is visible where none of the other layers are.
+    pub base: PhysicalMaterial,
+    /// The layers blended on top of the base material, from the bottom to the top.
+    pub layers: Vec<MaterialLayer>,
+    /// The lighting model used when rendering this material
+    pub lighting_model: LightingModel,
+    /// Render states.
+    pub render_states: RenderStates,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    pub lod_bias: f32,
+}
+
+impl LayeredMaterial {
+    ///
+    /// Creates a new layered material with the given base material and layers.
+    ///
+    pub fn new(base: PhysicalMaterial, layers: Vec<MaterialLayer>) -> Self {
+        Self {
+            lighting_model: base.lighting_model,
+            base,
+            layers,
+            render_states: RenderStates::default(),
+            lod_bias: 0.0,
+        }
+    }
+
+    fn materials(&self) -> impl Iterator<Item = &PhysicalMaterial> {
+        std::iter::once(&self.base).chain(self.layers.iter().map(|layer| &layer.material))
+    }
+}
+
+impl Material for LayeredMaterial {
+    fn fragment_shader(&self, lights: &[&dyn Light]) -> FragmentShader {
+        let mut attributes = FragmentAttributes {
+            position: true,
+            normal: true,
+            color: true,
+            ..FragmentAttributes::NONE
+        };
+        let mut output = lights_shader_source(lights, self.lighting_model);
+        if self.materials().any(|m| {
+            m.albedo_texture.is_some()
+                || m.metallic_roughness_texture.is_some()
+                || m.normal_texture.is_some()
+                || m.occlusion_texture.is_some()
+                || m.emissive_texture.is_some()
+        }) || self
+            .layers
+            .iter()
+            .any(|layer| matches!(layer.mask, LayerMask::Texture(_)))
+        {
+            attributes.uv = true;
+            output.push_str("in vec2 uvs;\n");
+        }
+        if self.materials().any(|m| m.normal_texture.is_some()) {
+            attributes.tangents = true;
+            output.push_str("in vec3 tang;\nin vec3 bitang;\n");
+        }
+        output.push_str(include_str!("shaders/layered_material.frag"));
+
+        for (i, material) in self.materials().enumerate() {
+            output.push_str(&layer_source(i, material));
+        }
+        for (i, layer) in self.layers.iter().enumerate() {
+            output.push_str(&mask_source(i + 1, &layer.mask));
+        }
+        output.push_str(
+            "Surface blend_layers(vec3 normal) {\n    Surface s = sample_layer0(normal);\n",
+        );
+        for i in 1..=self.layers.len() {
+            output.push_str(&format!(
+                "    s = mix_surface(s, sample_layer{i}(normal), layer{i}_weight(normal));\n"
+            ));
+        }
+        output.push_str("    return s;\n}\n");
+        FragmentShader {
+            source: output,
+            attributes,
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
+        program.use_uniform_if_required("cameraPosition", camera.position());
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(program, i as u32);
+        }
+        for (i, material) in self.materials().enumerate() {
+            let name = |n: &str| format!("layer{}{}", i, n);
+            program.use_uniform_if_required(&name("Albedo"), material.albedo);
+            program.use_uniform_if_required(&name("Metallic"), material.metallic);
+            program.use_uniform_if_required(&name("Roughness"), material.roughness);
+            program.use_uniform_if_required(&name("Emissive"), material.emissive);
+            program
+                .use_uniform_if_required(&name("OcclusionStrength"), material.occlusion_strength);
+            program.use_uniform_if_required(&name("NormalScale"), material.normal_scale);
+            for (texture_name, texture) in [
+                ("AlbedoTexture", &material.albedo_texture),
+                (
+                    "MetallicRoughnessTexture",
+                    &material.metallic_roughness_texture,
+                ),
+                ("OcclusionTexture", &material.occlusion_texture),
+                ("NormalTexture", &material.normal_texture),
+                ("EmissiveTexture", &material.emissive_texture),
+            ] {
+                if let Some(texture) = texture {
+                    if program.requires_uniform(&name(texture_name)) {
+                        program.use_uniform(
+                            &name(&format!("{}Transform", texture_name)),
+                            texture.transformation,
+                        );
+                        program.use_texture(&name(texture_name), texture);
+                    }
+                }
+            }
+        }
+        for (i, layer) in self.layers.iter().enumerate() {
+            let name = |n: &str| format!("layer{}{}", i + 1, n);
+            match &layer.mask {
+                LayerMask::Texture(texture) => {
+                    if program.requires_uniform(&name("Mask")) {
+                        program.use_uniform(&name("MaskTransform"), texture.transformation);
+                        program.use_texture(&name("Mask"), texture);
+                    }
+                }
+                LayerMask::Slope { start, end } | LayerMask::Height { start, end } => {
+                    program.use_uniform_if_required(&name("MaskRange"), vec2(*start, *end));
+                }
+                LayerMask::VertexColor(_) => {}
+            }
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+
+    fn texture_memory(&self) -> usize {
+        self.materials().map(|m| m.texture_memory()).sum::<usize>()
+            + self
+                .layers
+                .iter()
+                .filter_map(|layer| match &layer.mask {
+                    LayerMask::Texture(texture) => Some(texture.memory_size()),
+                    _ => None,
+                })
+                .sum::<usize>()
+    }
+}
+
+fn layer_source(i: usize, material: &PhysicalMaterial) -> String {
+    let mut uniforms = format!(
+        "uniform vec4 layer{i}Albedo;\nuniform float layer{i}Metallic;\nuniform float layer{i}Roughness;\nuniform vec4 layer{i}Emissive;\n"
+    );
+    let mut body = format!(
+        "    Surface s;\n    s.albedo = layer{i}Albedo;\n    s.metallic = layer{i}Metallic;\n    s.roughness = layer{i}Roughness;\n    s.occlusion = 1.0;\n    s.normal = normal;\n    s.emissive = layer{i}Emissive.rgb;\n"
+    );
+    let sample = |name: &str| {
+        format!("texture(layer{i}{name}, (layer{i}{name}Transform * vec3(uvs, 1.0)).xy, lodBias)")
+    };
+    for (name, texture) in [
+        ("AlbedoTexture", &material.albedo_texture),
+        (
+            "MetallicRoughnessTexture",
+            &material.metallic_roughness_texture,
+        ),
+        ("OcclusionTexture", &material.occlusion_texture),
+        ("NormalTexture", &material.normal_texture),
+        ("EmissiveTexture", &material.emissive_texture),
+    ] {
+        if texture.is_some() {
+            uniforms.push_str(&format!(
+                "uniform sampler2D layer{i}{name};\nuniform mat3 layer{i}{name}Transform;\n"
+            ));
+        }
+    }
+    if let Some(texture) = &material.albedo_texture {
+        if texture.is_srgb() {
+            uniforms.push_str(&format!("#define LAYER{i}_ALBEDO_TEXTURE_SRGB\n"));
+        }
+        body.push_str(&format!(
+            "    vec4 c = {};\n#ifndef LAYER{i}_ALBEDO_TEXTURE_SRGB\n    c.rgb = rgb_from_srgb(c.rgb);\n#endif\n    s.albedo *= c;\n",
+            sample("AlbedoTexture")
+        ));
+    }
+    if material.metallic_roughness_texture.is_some() {
+        body.push_str(&format!(
+            "    vec2 t = {}.gb;\n    s.roughness *= t.x;\n    s.metallic *= t.y;\n",
+            sample("MetallicRoughnessTexture")
+        ));
+    }
+    if material.occlusion_texture.is_some() {
+        uniforms.push_str(&format!("uniform float layer{i}OcclusionStrength;\n"));
+        body.push_str(&format!(
+            "    s.occlusion = mix(1.0, {}.r, layer{i}OcclusionStrength);\n",
+            sample("OcclusionTexture")
+        ));
+    }
+    if material.normal_texture.is_some() {
+        uniforms.push_str(&format!("uniform float layer{i}NormalScale;\n"));
+        body.push_str(&format!(
+            "    vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
+    vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
+    mat3 tbn = mat3(tangent, bitangent, normal);
+    s.normal = normalize(tbn * ((2.0 * {}.xyz - 1.0) * vec3(layer{i}NormalScale, layer{i}NormalScale, 1.0)));\n",
+            sample("NormalTexture")
+        ));
+    }
+    if let Some(texture) = &material.emissive_texture {
+        if texture.is_srgb() {
+            uniforms.push_str(&format!("#define LAYER{i}_EMISSIVE_TEXTURE_SRGB\n"));
+        }
+        body.push_str(&format!(
+            "    vec3 e = {}.rgb;\n#ifndef LAYER{i}_EMISSIVE_TEXTURE_SRGB\n    e = rgb_from_srgb(e);\n#endif\n    s.emissive *= e;\n",
+            sample("EmissiveTexture")
+        ));
+    }
+    format!("{uniforms}Surface sample_layer{i}(vec3 normal) {{\n{body}    return s;\n}}\n")
+}
+
+fn mask_source(i: usize, mask: &LayerMask) -> String {
+    match mask {
+        LayerMask::Texture(_) => format!(
+            "uniform sampler2D layer{i}Mask;\nuniform mat3 layer{i}MaskTransform;\nfloat layer{i}_weight(vec3 normal) {{\n    return texture(layer{i}Mask, (layer{i}MaskTransform * vec3(uvs, 1.0)).xy, lodBias).r;\n}}\n"
+        ),
+        LayerMask::VertexColor(channel) => format!(
+            "float layer{i}_weight(vec3 normal) {{\n    return col[{}];\n}}\n",
+            (*channel).min(3)
+        ),
+        LayerMask::Slope { .. } => format!(
+            "uniform vec2 layer{i}MaskRange;\nfloat layer{i}_weight(vec3 normal) {{\n    return layer_ramp(acos(clamp(normal.y, -1.0, 1.0)), layer{i}MaskRange.x, layer{i}MaskRange.y);\n}}\n"
+        ),
+        LayerMask::Height { .. } => format!(
+            "uniform vec2 layer{i}MaskRange;\nfloat layer{i}_weight(vec3 normal) {{\n    return layer_ramp(pos.y, layer{i}MaskRange.x, layer{i}MaskRange.y);\n}}\n"
+        ),
+    }
+}
@@ -15,6 +15,9 @@ pub struct NormalMaterial {
     pub normal_texture: Option<Texture2DRef>,
     /// Render states.
     pub render_states: RenderStates,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    /// This field is new since version 0.16, so struct literals which do not use `..Default::default()` must set it, `0.0` gives the previous result.
+    pub lod_bias: f32,
 }
 
 impl NormalMaterial {
@@ -27,6 +30,7 @@ impl NormalMaterial {
         Self {
             normal_scale: cpu_material.normal_scale,
             normal_texture,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
         }
     }
@@ -36,6 +40,7 @@ impl NormalMaterial {
         Self {
             normal_scale: physical_material.normal_scale,
             normal_texture: physical_material.normal_texture.clone(),
+            lod_bias: physical_material.lod_bias,
             render_states: RenderStates {
                 write_mask: WriteMask::default(),
                 blend: Blend::Disabled,
@@ -68,6 +73,10 @@ impl Material for NormalMaterial {
     }
 
     fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
         if let Some(ref tex) = self.normal_texture {
             program.use_uniform("normalScale", self.normal_scale);
             program.use_uniform("textureTransformation", tex.transformation);
@@ -87,6 +96,7 @@ impl Default for NormalMaterial {
         Self {
             normal_texture: None,
             normal_scale: 1.0,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
         }
     }
@@ -22,6 +22,9 @@ pub struct ORMMaterial {
     pub occlusion_texture: Option<Texture2DRef>,
     /// Render states.
     pub render_states: RenderStates,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    /// This field is new since version 0.16, so struct literals which do not use `..Default::default()` must set it, `0.0` gives the previous result.
+    pub lod_bias: f32,
 }
 
 impl ORMMaterial {
@@ -50,6 +53,7 @@ impl ORMMaterial {
             metallic_roughness_texture,
             occlusion_texture,
             occlusion_strength: cpu_material.occlusion_strength,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
         }
     }
@@ -62,6 +66,7 @@ impl ORMMaterial {
             metallic_roughness_texture: physical_material.metallic_roughness_texture.clone(),
             occlusion_strength: physical_material.occlusion_strength,
             occlusion_texture: physical_material.occlusion_texture.clone(),
+            lod_bias: physical_material.lod_bias,
             render_states: RenderStates {
                 write_mask: WriteMask::default(),
                 blend: Blend::Disabled,
@@ -96,6 +101,10 @@ impl Material for ORMMaterial {
     }
 
     fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
         program.use_uniform("metallic", self.metallic);
         program.use_uniform("roughness", self.roughness);
         if let Some(ref texture) = self.metallic_roughness_texture {
@@ -126,6 +135,7 @@ impl Default for ORMMaterial {
             metallic_roughness_texture: None,
             occlusion_texture: None,
             occlusion_strength: 1.0,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
         }
     }
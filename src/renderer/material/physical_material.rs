@@ -6,6 +6,11 @@ use std::sync::Arc;
 /// A physically-based material that renders a [Geometry] in an approximate correct physical manner based on Physically Based Rendering (PBR).
 /// This material is affected by lights.
 ///
+/// All lighting is computed with linear colors. The albedo and emissive colors are assumed to be linear,
+/// while the albedo and emissive textures are assumed to be in the sRGB color space and are converted to linear colors when sampled,
+/// either in the shader or by the graphics hardware if the texture is an sRGB texture, see [Texture2D::new_srgb].
+/// The result is converted back to the sRGB color space as the last step, unless linear output is enabled, see [Context::set_linear_output].
+///
 #[derive(Clone)]
 pub struct PhysicalMaterial {
     /// Name.
@@ -13,6 +18,7 @@ pub struct PhysicalMaterial {
     /// Albedo base color, also called diffuse color. Assumed to be in linear color space.
     pub albedo: Color,
     /// Texture with albedo base colors, also called diffuse color. Assumed to be in sRGB with or without an alpha channel.
+    /// Use an sRGB texture, see [Texture2D::new_srgb], to let the graphics hardware convert the colors before they are filtered.
     pub albedo_texture: Option<Texture2DRef>,
     /// A value in the range `[0..1]` specifying how metallic the surface is.
     pub metallic: f32,
@@ -32,6 +38,9 @@ pub struct PhysicalMaterial {
     pub normal_texture: Option<Texture2DRef>,
     /// Render states.
     pub render_states: RenderStates,
+    /// The bias added to the level of detail used when sampling the textures of this material, in addition to the global bias (see [Context::set_texture_lod_bias]).
+    /// This field is new since version 0.16, so struct literals which do not use `..Default::default()` must set it, `0.0` gives the previous result.
+    pub lod_bias: f32,
     /// Whether this material should be treated as a transparent material (An object needs to be rendered differently depending on whether it is transparent or opaque).
     pub is_transparent: bool,
     /// Color of light shining from an object.
@@ -40,6 +49,42 @@ pub struct PhysicalMaterial {
     pub emissive_texture: Option<Texture2DRef>,
     /// The lighting model used when rendering this material
     pub lighting_model: LightingModel,
+    /// Detail textures layered on top of the albedo and normal textures with an independent tiling.
+    pub detail: Option<DetailTextures>,
+    /// Whether or not to sample the albedo, metallic roughness, occlusion, normal and emissive textures using stochastic texture bombing,
+    /// where each tile is sampled with a random offset and blended with its neighbours.
+    /// This hides the repetition of tiled textures on large surfaces like terrain and walls at the cost of four texture samples instead of one.
+    pub stochastic_sampling: bool,
+}
+
+///
+/// Detail textures which add small scale variation to a [PhysicalMaterial].
+/// The detail textures are tiled [DetailTextures::scale] times more than the textures of the material.
+///
+#[derive(Clone)]
+pub struct DetailTextures {
+    /// A detail albedo texture which is multiplied onto the albedo of the material, where a value of 0.5 in all channels leaves the albedo unchanged.
+    pub albedo_texture: Option<Texture2DRef>,
+    /// A value between 0 and 1 specifying how much the detail albedo texture affects the albedo.
+    pub albedo_strength: f32,
+    /// A tangent space detail normal map which is blended with the normal texture of the material.
+    pub normal_texture: Option<Texture2DRef>,
+    /// A scalar multiplier applied to each normal vector of the [DetailTextures::normal_texture].
+    pub normal_scale: f32,
+    /// The number of times the detail textures repeat for each repetition of the textures of the material.
+    pub scale: f32,
+}
+
+impl Default for DetailTextures {
+    fn default() -> Self {
+        Self {
+            albedo_texture: None,
+            albedo_strength: 1.0,
+            normal_texture: None,
+            normal_scale: 1.0,
+            scale: 10.0,
+        }
+    }
 }
 
 impl PhysicalMaterial {
@@ -109,6 +154,7 @@ impl PhysicalMaterial {
             normal_scale: cpu_material.normal_scale,
             occlusion_texture,
             occlusion_strength: cpu_material.occlusion_strength,
+            lod_bias: 0.0,
             render_states: if is_transparent {
                 RenderStates {
                     write_mask: WriteMask::COLOR,
@@ -122,6 +168,8 @@ impl PhysicalMaterial {
             emissive: cpu_material.emissive,
             emissive_texture,
             lighting_model: cpu_material.lighting_model,
+            detail: None,
+            stochastic_sampling: false,
         }
     }
 }
@@ -141,16 +189,34 @@ impl Material for PhysicalMaterial {
             ..FragmentAttributes::NONE
         };
         let mut output = lights_shader_source(lights, self.lighting_model);
+        let detail_albedo = self
+            .detail
+            .as_ref()
+            .map(|d| d.albedo_texture.is_some())
+            .unwrap_or(false);
+        let detail_normal = self
+            .detail
+            .as_ref()
+            .map(|d| d.normal_texture.is_some())
+            .unwrap_or(false);
         if self.albedo_texture.is_some()
             || self.metallic_roughness_texture.is_some()
             || self.normal_texture.is_some()
             || self.occlusion_texture.is_some()
             || self.emissive_texture.is_some()
+            || detail_albedo
+            || detail_normal
         {
             attributes.uv = true;
             output.push_str("in vec2 uvs;\n");
-            if self.albedo_texture.is_some() {
+            if self.stochastic_sampling {
+                output.push_str("#define USE_STOCHASTIC_SAMPLING;\n");
+            }
+            if let Some(texture) = &self.albedo_texture {
                 output.push_str("#define USE_ALBEDO_TEXTURE;\n");
+                if texture.is_srgb() {
+                    output.push_str("#define ALBEDO_TEXTURE_SRGB;\n");
+                }
             }
             if self.metallic_roughness_texture.is_some() {
                 output.push_str("#define USE_METALLIC_ROUGHNESS_TEXTURE;\n");
@@ -159,11 +225,23 @@ impl Material for PhysicalMaterial {
                 output.push_str("#define USE_OCCLUSION_TEXTURE;\n");
             }
             if self.normal_texture.is_some() {
+                output.push_str("#define USE_NORMAL_TEXTURE;\n");
+            }
+            if detail_albedo {
+                output.push_str("#define USE_DETAIL_ALBEDO_TEXTURE;\n");
+            }
+            if detail_normal {
+                output.push_str("#define USE_DETAIL_NORMAL_TEXTURE;\n");
+            }
+            if self.normal_texture.is_some() || detail_normal {
                 attributes.tangents = true;
-                output.push_str("#define USE_NORMAL_TEXTURE;\nin vec3 tang;\nin vec3 bitang;\n");
+                output.push_str("in vec3 tang;\nin vec3 bitang;\n");
             }
-            if self.emissive_texture.is_some() {
+            if let Some(texture) = &self.emissive_texture {
                 output.push_str("#define USE_EMISSIVE_TEXTURE;\n");
+                if texture.is_srgb() {
+                    output.push_str("#define EMISSIVE_TEXTURE_SRGB;\n");
+                }
             }
         }
         output.push_str(include_str!("shaders/physical_material.frag"));
@@ -174,6 +252,10 @@ impl Material for PhysicalMaterial {
     }
 
     fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
+        program.use_uniform_if_required(
+            "lodBias",
+            self.lod_bias + program.context().texture_lod_bias(),
+        );
         if !lights.is_empty() {
             program.use_uniform_if_required("cameraPosition", camera.position());
             for (i, light) in lights.iter().enumerate() {
@@ -208,6 +290,23 @@ impl Material for PhysicalMaterial {
                 }
             }
         }
+        if let Some(detail) = &self.detail {
+            program.use_uniform_if_required("detailScale", detail.scale);
+            if program.requires_uniform("detailAlbedoTexture") {
+                if let Some(ref texture) = detail.albedo_texture {
+                    program.use_uniform("detailAlbedoTexTransform", texture.transformation);
+                    program.use_uniform("detailAlbedoStrength", detail.albedo_strength);
+                    program.use_texture("detailAlbedoTexture", texture);
+                }
+            }
+            if program.requires_uniform("detailNormalTexture") {
+                if let Some(ref texture) = detail.normal_texture {
+                    program.use_uniform("detailNormalTexTransform", texture.transformation);
+                    program.use_uniform("detailNormalScale", detail.normal_scale);
+                    program.use_texture("detailNormalTexture", texture);
+                }
+            }
+        }
         program.use_uniform("albedo", self.albedo);
         program.use_uniform("emissive", self.emissive);
         if program.requires_uniform("emissiveTexture") {
@@ -228,6 +327,23 @@ impl Material for PhysicalMaterial {
             MaterialType::Opaque
         }
     }
+    fn texture_memory(&self) -> usize {
+        [
+            &self.albedo_texture,
+            &self.metallic_roughness_texture,
+            &self.occlusion_texture,
+            &self.normal_texture,
+            &self.emissive_texture,
+            &self.detail.as_ref().and_then(|d| d.albedo_texture.clone()),
+            &self.detail.as_ref().and_then(|d| d.normal_texture.clone()),
+        ]
+        .iter()
+        .filter_map(|t| t.as_ref().map(|t| t.memory_size()))
+        .sum()
+    }
+    fn albedo(&self) -> Color {
+        self.albedo
+    }
 }
 
 impl Default for PhysicalMaterial {
@@ -243,11 +359,14 @@ impl Default for PhysicalMaterial {
             normal_scale: 1.0,
             occlusion_texture: None,
             occlusion_strength: 1.0,
+            lod_bias: 0.0,
             render_states: RenderStates::default(),
             is_transparent: false,
             emissive: Color::BLACK,
             emissive_texture: None,
             lighting_model: LightingModel::Blinn,
+            detail: None,
+            stochastic_sampling: false,
         }
     }
 }
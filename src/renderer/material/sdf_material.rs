@@ -0,0 +1,91 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A material that renders an implicit surface defined by a signed distance function (SDF) using ray marching.
+/// The signed distance function is given as a GLSL snippet defining the function `float sdf(vec3 p)`, which returns the signed distance from the point `p` to the surface,
+/// negative inside the surface and positive outside.
+/// The depth of the surface is written to the depth buffer, so the surface is depth tested correctly against the other objects in the scene.
+///
+/// This material should be applied to a cube with center in origo and the given [SdfMaterial::size], for example [CpuMesh::cube] scaled by half the size.
+/// The surface is only rendered inside this bounding box and the points given to the signed distance function are in the same space as the cube.
+///
+#[derive(Clone)]
+pub struct SdfMaterial {
+    /// GLSL source code defining the signed distance function `float sdf(vec3 p)`.
+    pub sdf_source: String,
+    /// The size of the cube that bounds the surface.
+    pub size: Vec3,
+    /// Base surface color. Assumed to be in linear color space.
+    pub color: Color,
+    /// A value in the range `[0..1]` specifying how metallic the surface is.
+    pub metallic: f32,
+    /// A value in the range `[0..1]` specifying how rough the surface is.
+    pub roughness: f32,
+    /// The lighting model used when rendering this material
+    pub lighting_model: LightingModel,
+    /// The maximum number of ray marching steps, at most 1024. Pixels where the surface is not found within this number of steps are discarded.
+    pub max_steps: u32,
+    /// The distance to the surface where a ray is considered to have hit the surface.
+    pub surface_distance: f32,
+}
+
+impl SdfMaterial {
+    ///
+    /// Creates a new material rendering the surface defined by the given signed distance function within a bounding box of the given size.
+    ///
+    pub fn new(sdf_source: impl Into<String>, size: Vec3) -> Self {
+        Self {
+            sdf_source: sdf_source.into(),
+            size,
+            color: Color::WHITE,
+            metallic: 0.0,
+            roughness: 1.0,
+            lighting_model: LightingModel::Blinn,
+            max_steps: 256,
+            surface_distance: 0.001,
+        }
+    }
+}
+
+impl Material for SdfMaterial {
+    fn fragment_shader(&self, lights: &[&dyn Light]) -> FragmentShader {
+        let mut source = lights_shader_source(lights, self.lighting_model);
+        source.push_str(&self.sdf_source);
+        source.push('\n');
+        source.push_str(include_str!("shaders/sdf_material.frag"));
+        FragmentShader {
+            source,
+            attributes: FragmentAttributes {
+                position: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(program, i as u32);
+        }
+        program.use_uniform("cameraPosition", camera.position());
+        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform("surfaceColor", self.color);
+        program.use_uniform_if_required("metallic", self.metallic);
+        program.use_uniform_if_required("roughness", self.roughness);
+        program.use_uniform("size", self.size);
+        program.use_uniform("maxSteps", self.max_steps.min(1024) as i32);
+        program.use_uniform("surfaceDistance", self.surface_distance);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            // Render the back faces so the surface is also visible when the camera is inside the bounding box
+            cull: Cull::Front,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
@@ -1,3 +1,4 @@
+uniform float lodBias;
 uniform vec4 surfaceColor;
 
 #ifdef USE_TEXTURE
@@ -14,8 +15,11 @@ void main()
     outColor = surfaceColor * col;
     
     #ifdef USE_TEXTURE
-    vec4 tex_color = texture(tex, (textureTransformation * vec3(uvs, 1.0)).xy);
-    outColor *= vec4(rgb_from_srgb(tex_color.rgb), tex_color.a);
+    vec4 tex_color = texture(tex, (textureTransformation * vec3(uvs, 1.0)).xy, lodBias);
+    #ifndef TEXTURE_SRGB
+    tex_color.rgb = rgb_from_srgb(tex_color.rgb);
+    #endif
+    outColor *= tex_color;
     #endif
 
     outColor.rgb = srgb_from_rgb(outColor.rgb);
@@ -0,0 +1,52 @@
+uniform sampler2D colormap;
+uniform vec2 valueRange;
+uniform float bands;
+uniform float contourSpacing;
+uniform float contourWidth;
+uniform vec4 contourColor;
+
+#ifdef SOURCE_COLOR
+in vec4 col;
+#endif
+
+#ifdef SOURCE_TEXTURE
+uniform sampler2D tex;
+uniform mat3 textureTransformation;
+uniform int channel;
+#endif
+
+layout (location = 0) out vec4 outColor;
+
+float scalar_value()
+{
+#ifdef SOURCE_UV
+    return uvs.x;
+#endif
+#ifdef SOURCE_COLOR
+    return col.r;
+#endif
+#ifdef SOURCE_TEXTURE
+    return texture(tex, (textureTransformation * vec3(uvs, 1.0)).xy)[channel];
+#endif
+}
+
+void main()
+{
+    float value = scalar_value();
+    float t = clamp((value - valueRange.x) / (valueRange.y - valueRange.x), 0.0, 1.0);
+    if (bands > 0.0) {
+        t = (min(floor(t * bands), bands - 1.0) + 0.5) / bands;
+    }
+    outColor = texture(colormap, vec2(t, 0.5));
+    outColor.rgb = rgb_from_srgb(outColor.rgb);
+
+    if (contourSpacing > 0.0) {
+        // The distance in pixels to the closest contour line, using the screen space derivative of the value
+        float f = value / contourSpacing;
+        float distance = abs(fract(f - 0.5) - 0.5) / max(fwidth(f), 0.000001);
+        float coverage = clamp(0.5 * contourWidth - distance + 0.5, 0.0, 1.0);
+        outColor.rgb = mix(outColor.rgb, contourColor.rgb, coverage * contourColor.a);
+    }
+
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+}
@@ -0,0 +1,94 @@
+
+uniform float lodBias;
+uniform mat4 decalTransformationInverse;
+uniform vec3 decalTangent;
+uniform vec3 decalBitangent;
+uniform vec3 decalDirection;
+uniform vec3 cameraPosition;
+
+uniform vec4 albedo;
+#ifdef USE_ALBEDO_TEXTURE
+uniform sampler2D albedoTexture;
+uniform mat3 albedoTexTransform;
+#endif
+
+uniform float metallic;
+uniform float roughness;
+#ifdef USE_METALLIC_ROUGHNESS_TEXTURE
+uniform sampler2D metallicRoughnessTexture;
+uniform mat3 metallicRoughnessTexTransform;
+#endif
+
+#ifdef USE_NORMAL_TEXTURE
+uniform sampler2D normalTexture;
+uniform mat3 normalTexTransform;
+uniform float normalScale;
+#endif
+
+#ifdef USE_DEPTH_TEXTURE
+uniform mat4 viewProjectionInverse;
+uniform vec4 viewport;
+#else
+in vec3 pos;
+#endif
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+#ifdef USE_DEPTH_TEXTURE
+    vec2 screen_uv = (gl_FragCoord.xy - viewport.xy) / viewport.zw;
+    float depth = sample_depth(screen_uv);
+    if (depth >= 1.0) discard;
+    vec3 position = world_pos_from_depth(viewProjectionInverse, depth, screen_uv);
+#else
+    vec3 position = pos;
+#endif
+
+    // Project the position into the unit box of the decal
+    vec3 p = (decalTransformationInverse * vec4(position, 1.0)).xyz;
+    if (any(greaterThan(abs(p), vec3(0.5)))) discard;
+    vec2 uv = p.xy + 0.5;
+
+    vec3 normal = normalize(cross(dFdx(position), dFdy(position)));
+    if (dot(normal, cameraPosition - position) < 0.0) normal = -normal;
+
+    // Fade out on surfaces which are almost parallel to the projection direction
+    float fade = smoothstep(0.05, 0.3, dot(normal, -decalDirection));
+    if (fade <= 0.0) discard;
+
+    vec4 surface_color = albedo;
+#ifdef USE_ALBEDO_TEXTURE
+    vec4 c = texture(albedoTexture, (albedoTexTransform * vec3(uv, 1.0)).xy, lodBias);
+#ifndef ALBEDO_TEXTURE_SRGB
+    c.rgb = rgb_from_srgb(c.rgb);
+#endif
+    surface_color *= c;
+#endif
+    surface_color.a *= fade;
+
+#ifdef USE_LIGHTING
+    float metallic_factor = metallic;
+    float roughness_factor = roughness;
+#ifdef USE_METALLIC_ROUGHNESS_TEXTURE
+    vec2 t = texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uv, 1.0)).xy, lodBias).gb;
+    roughness_factor *= t.x;
+    metallic_factor *= t.y;
+#endif
+
+#ifdef USE_NORMAL_TEXTURE
+    vec3 tangent = normalize(decalTangent - normal * dot(normal, decalTangent));
+    vec3 bitangent = cross(normal, tangent);
+    if (dot(bitangent, decalBitangent) < 0.0) bitangent = -bitangent;
+    mat3 tbn = mat3(tangent, bitangent, normal);
+    normal = normalize(tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(uv, 1.0)).xy, lodBias).xyz - 1.0) * vec3(normalScale, normalScale, 1.0)));
+#endif
+
+    outColor.rgb = calculate_lighting(cameraPosition, surface_color.rgb, position, normal, metallic_factor, roughness_factor, 1.0);
+    outColor.rgb = reinhard_tone_mapping(outColor.rgb);
+#else
+    outColor.rgb = surface_color.rgb;
+#endif
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+    outColor.a = surface_color.a;
+}
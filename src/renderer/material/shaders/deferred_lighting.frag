@@ -12,11 +12,15 @@ layout (location = 0) out vec4 outColor;
 void main()
 {
     float depth = sample_depth(uvs);
+#ifdef REVERSE_Z
+    gl_FragDepth = 1.0 - depth;
+#else
+    gl_FragDepth = depth;
+#endif
     if(depth > 0.99999)
     {
         discard;
     }
-    gl_FragDepth = depth;
 
     vec3 position = world_pos_from_depth(viewProjectionInverse, depth, uvs);
    	
@@ -33,7 +37,10 @@ void main()
     vec3 normal = normalize(vec3(n2.x, n2.y, (int(floor(n.z * 255.0)) & 128) == 128 ? z: -z));
     float roughness_factor = n.w;
     float occlusion = float(int(floor(n.z * 255.0)) & 127) / 127.0;
-    vec3 total_emissive = sample_layer(uvs, 2).rgb;
+    vec3 total_emissive = vec3(0.0);
+#ifndef NO_EMISSIVE_LAYER
+    total_emissive = sample_layer(uvs, 2).rgb;
+#endif
 
     if(debug_type == 0) // Position
     {
@@ -1,4 +1,5 @@
 
+uniform float lodBias;
 uniform float metallic;
 uniform float roughness;
 
@@ -43,24 +44,27 @@ void main()
 {
     vec4 surface_color = albedo * col;
 #ifdef USE_ALBEDO_TEXTURE
-    vec4 c = texture(albedoTexture, (albedoTexTransform * vec3(uvs, 1.0)).xy);
+    vec4 c = texture(albedoTexture, (albedoTexTransform * vec3(uvs, 1.0)).xy, lodBias);
     #ifdef ALPHACUT
         if (c.a < acut) discard;
     #endif
-    surface_color *= vec4(rgb_from_srgb(c.rgb), c.a);
+    #ifndef ALBEDO_TEXTURE_SRGB
+        c.rgb = rgb_from_srgb(c.rgb);
+    #endif
+    surface_color *= c;
 #endif
 
     float metallic_factor = metallic;
     float roughness_factor = roughness;
 #ifdef USE_METALLIC_ROUGHNESS_TEXTURE
-    vec2 t = texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uvs, 1.0)).xy).gb;
+    vec2 t = texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uvs, 1.0)).xy, lodBias).gb;
     roughness_factor *= t.x;
     metallic_factor *= t.y;
 #endif
 
     float occlusion = 1.0;
 #ifdef USE_OCCLUSION_TEXTURE
-    occlusion = mix(1.0, texture(occlusionTexture, (occlusionTexTransform * vec3(uvs, 1.0)).xy).r, occlusionStrength);
+    occlusion = mix(1.0, texture(occlusionTexture, (occlusionTexTransform * vec3(uvs, 1.0)).xy, lodBias).r, occlusionStrength);
 #endif
 
     vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
@@ -68,13 +72,16 @@ void main()
     vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
     vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
     mat3 tbn = mat3(tangent, bitangent, normal);
-    normal = tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+    normal = tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(uvs, 1.0)).xy, lodBias).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
 #endif
 
     vec3 total_emissive = emissive.rgb;
 #ifdef USE_EMISSIVE_TEXTURE
-    vec4 e = texture(emissiveTexture, (emissiveTexTransform * vec3(uvs, 1.0)).xy);
-    total_emissive *= rgb_from_srgb(e.rgb);
+    vec4 e = texture(emissiveTexture, (emissiveTexTransform * vec3(uvs, 1.0)).xy, lodBias);
+    #ifndef EMISSIVE_TEXTURE_SRGB
+        e.rgb = rgb_from_srgb(e.rgb);
+    #endif
+    total_emissive *= e.rgb;
 #endif
 
     outColor = vec4(surface_color.rgb, metallic_factor);
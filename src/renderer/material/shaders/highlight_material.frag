@@ -0,0 +1,20 @@
+uniform vec4 tintColor;
+uniform vec4 hatchColor;
+uniform float hatchSpacing;
+uniform float hatchWidth;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 color = tintColor;
+    if (hatchSpacing > 0.0 && mod(gl_FragCoord.x + gl_FragCoord.y, hatchSpacing) < hatchWidth) {
+        // Blend the hatch lines over the tint
+        float alpha = hatchColor.a + color.a * (1.0 - hatchColor.a);
+        color = vec4(mix(color.rgb * color.a, hatchColor.rgb, hatchColor.a) / max(alpha, 0.0001), alpha);
+    }
+    if (color.a <= 0.0) {
+        discard;
+    }
+    outColor = color;
+}
@@ -0,0 +1,9 @@
+
+uniform vec4 id;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = id;
+}
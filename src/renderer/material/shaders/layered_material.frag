@@ -0,0 +1,53 @@
+
+uniform vec3 cameraPosition;
+uniform float lodBias;
+
+in vec3 pos;
+in vec3 nor;
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+struct Surface {
+    vec4 albedo;
+    float metallic;
+    float roughness;
+    float occlusion;
+    vec3 normal;
+    vec3 emissive;
+};
+
+Surface mix_surface(Surface a, Surface b, float weight) {
+    Surface s;
+    s.albedo = mix(a.albedo, b.albedo, weight);
+    s.metallic = mix(a.metallic, b.metallic, weight);
+    s.roughness = mix(a.roughness, b.roughness, weight);
+    s.occlusion = mix(a.occlusion, b.occlusion, weight);
+    s.normal = normalize(mix(a.normal, b.normal, weight));
+    s.emissive = mix(a.emissive, b.emissive, weight);
+    return s;
+}
+
+float layer_ramp(float value, float start, float end) {
+    // The start can be larger than the end, so the range keeps its sign and is a step if it is empty
+    float range = end - start;
+    if (abs(range) < 0.0001) {
+        return step(start, value);
+    }
+    float t = clamp((value - start) / range, 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t);
+}
+
+Surface blend_layers(vec3 normal);
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    Surface s = blend_layers(normal);
+
+    outColor.rgb = s.emissive + calculate_lighting(cameraPosition, s.albedo.rgb, pos, s.normal, s.metallic, s.roughness, s.occlusion);
+    outColor.rgb = reinhard_tone_mapping(outColor.rgb);
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+    outColor.a = s.albedo.a;
+}
+
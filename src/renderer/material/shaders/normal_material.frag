@@ -1,4 +1,5 @@
 
+uniform float lodBias;
 in vec3 nor;
 
 #ifdef USE_TEXTURE
@@ -17,7 +18,7 @@ void main()
     vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
     vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
     mat3 tbn = mat3(tangent, bitangent, normal);
-    normal = tbn * ((2.0 * texture(normalTexture, (textureTransformation * vec3(uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+    normal = tbn * ((2.0 * texture(normalTexture, (textureTransformation * vec3(uvs, 1.0)).xy, lodBias).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
 #endif
     outColor = vec4(0.5 + 0.5 * normal, 1.0);
 }
\ No newline at end of file
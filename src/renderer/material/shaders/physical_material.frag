@@ -1,4 +1,5 @@
 
+uniform float lodBias;
 uniform float metallic;
 uniform float roughness;
 uniform vec3 cameraPosition;
@@ -32,6 +33,43 @@ uniform mat3 normalTexTransform;
 uniform float normalScale;
 #endif
 
+uniform float detailScale;
+#ifdef USE_DETAIL_ALBEDO_TEXTURE
+uniform sampler2D detailAlbedoTexture;
+uniform mat3 detailAlbedoTexTransform;
+uniform float detailAlbedoStrength;
+#endif
+
+#ifdef USE_DETAIL_NORMAL_TEXTURE
+uniform sampler2D detailNormalTexture;
+uniform mat3 detailNormalTexTransform;
+uniform float detailNormalScale;
+#endif
+
+#ifdef USE_STOCHASTIC_SAMPLING
+vec2 tile_offset(vec2 tile) {
+    return fract(sin(vec2(dot(tile, vec2(127.1, 311.7)), dot(tile, vec2(269.5, 183.3)))) * 43758.5453);
+}
+
+// Texture bombing: each tile is sampled with a random offset and blended with the neighbouring tiles
+vec4 sample_texture(sampler2D tex, vec2 uv) {
+    vec2 tile = floor(uv);
+    vec2 f = smoothstep(0.25, 0.75, fract(uv));
+    float scale = exp2(lodBias);
+    vec2 ddx = dFdx(uv) * scale;
+    vec2 ddy = dFdy(uv) * scale;
+    vec4 a = textureGrad(tex, uv + tile_offset(tile), ddx, ddy);
+    vec4 b = textureGrad(tex, uv + tile_offset(tile + vec2(1.0, 0.0)), ddx, ddy);
+    vec4 c = textureGrad(tex, uv + tile_offset(tile + vec2(0.0, 1.0)), ddx, ddy);
+    vec4 d = textureGrad(tex, uv + tile_offset(tile + vec2(1.0, 1.0)), ddx, ddy);
+    return mix(mix(a, b, f.x), mix(c, d, f.x), f.y);
+}
+#else
+vec4 sample_texture(sampler2D tex, vec2 uv) {
+    return texture(tex, uv, lodBias);
+}
+#endif
+
 in vec3 pos;
 in vec3 nor;
 in vec4 col;
@@ -42,38 +80,56 @@ void main()
 {
     vec4 surface_color = albedo * col;
 #ifdef USE_ALBEDO_TEXTURE
-    vec4 c = texture(albedoTexture, (albedoTexTransform * vec3(uvs, 1.0)).xy);
+    vec4 c = sample_texture(albedoTexture, (albedoTexTransform * vec3(uvs, 1.0)).xy);
     #ifdef ALPHACUT
         if (c.a < acut) discard;
     #endif
-    surface_color *= vec4(rgb_from_srgb(c.rgb), c.a);
+    #ifndef ALBEDO_TEXTURE_SRGB
+        c.rgb = rgb_from_srgb(c.rgb);
+    #endif
+    surface_color *= c;
+#endif
+#ifdef USE_DETAIL_ALBEDO_TEXTURE
+    vec3 detail_albedo = sample_texture(detailAlbedoTexture, (detailAlbedoTexTransform * vec3(uvs * detailScale, 1.0)).xy).rgb;
+    surface_color.rgb *= mix(vec3(1.0), 2.0 * detail_albedo, detailAlbedoStrength);
 #endif
 
     float metallic_factor = metallic;
     float roughness_factor = roughness;
 #ifdef USE_METALLIC_ROUGHNESS_TEXTURE
-    vec2 t = texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uvs, 1.0)).xy).gb;
+    vec2 t = sample_texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uvs, 1.0)).xy).gb;
     roughness_factor *= t.x;
     metallic_factor *= t.y;
 #endif
 
     float occlusion = 1.0;
 #ifdef USE_OCCLUSION_TEXTURE
-    occlusion = mix(1.0, texture(occlusionTexture, (occlusionTexTransform * vec3(uvs, 1.0)).xy).r, occlusionStrength);
+    occlusion = mix(1.0, sample_texture(occlusionTexture, (occlusionTexTransform * vec3(uvs, 1.0)).xy).r, occlusionStrength);
 #endif
 
     vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
-#ifdef USE_NORMAL_TEXTURE
+#if defined(USE_NORMAL_TEXTURE) || defined(USE_DETAIL_NORMAL_TEXTURE)
     vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
     vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
     mat3 tbn = mat3(tangent, bitangent, normal);
-    normal = tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+    vec3 tangent_normal = vec3(0.0, 0.0, 1.0);
+#ifdef USE_NORMAL_TEXTURE
+    tangent_normal = (2.0 * sample_texture(normalTexture, (normalTexTransform * vec3(uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0);
+#endif
+#ifdef USE_DETAIL_NORMAL_TEXTURE
+    vec3 detail_normal = (2.0 * sample_texture(detailNormalTexture, (detailNormalTexTransform * vec3(uvs * detailScale, 1.0)).xy).xyz - 1.0) * vec3(detailNormalScale, detailNormalScale, 1.0);
+    tangent_normal = vec3(tangent_normal.xy + detail_normal.xy, tangent_normal.z * detail_normal.z);
+#endif
+    normal = tbn * tangent_normal;
 #endif
 
     vec3 total_emissive = emissive.rgb;
 #ifdef USE_EMISSIVE_TEXTURE
-    vec4 e = texture(emissiveTexture, (emissiveTexTransform * vec3(uvs, 1.0)).xy);
-    total_emissive *= rgb_from_srgb(e.rgb);
+    vec4 e = sample_texture(emissiveTexture, (emissiveTexTransform * vec3(uvs, 1.0)).xy);
+    #ifndef EMISSIVE_TEXTURE_SRGB
+        e.rgb = rgb_from_srgb(e.rgb);
+    #endif
+    total_emissive *= e.rgb;
 #endif
 
     outColor.rgb = total_emissive + calculate_lighting(cameraPosition, surface_color.rgb, pos, normal, metallic_factor, roughness_factor, occlusion);
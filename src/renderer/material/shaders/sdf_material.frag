@@ -0,0 +1,53 @@
+uniform vec3 cameraPosition;
+uniform mat4 viewProjection;
+uniform vec4 surfaceColor;
+uniform float metallic;
+uniform float roughness;
+uniform vec3 size;
+uniform int maxSteps;
+uniform float surfaceDistance;
+
+in vec3 pos;
+
+layout (location = 0) out vec4 outColor;
+
+vec3 estimate_normal(vec3 p) {
+    const vec2 k = vec2(1.0, -1.0);
+    float h = surfaceDistance;
+    return normalize(k.xyy * sdf(p + k.xyy * h) + k.yyx * sdf(p + k.yyx * h) + k.yxy * sdf(p + k.yxy * h) + k.xxx * sdf(p + k.xxx * h));
+}
+
+void main() {
+    vec3 rayDir = normalize(pos - cameraPosition);
+
+    // Intersect the ray with the bounding box to find where to start and stop marching
+    vec3 invDir = 1.0 / rayDir;
+    vec3 t0 = (-0.5 * size - cameraPosition) * invDir;
+    vec3 t1 = (0.5 * size - cameraPosition) * invDir;
+    vec3 tMin = min(t0, t1);
+    vec3 tMax = max(t0, t1);
+    float t = max(max(max(tMin.x, tMin.y), tMin.z), 0.0);
+    float tEnd = min(min(tMax.x, tMax.y), tMax.z);
+
+    for (int i = 0; i < 1024; i++) {
+        if (i >= maxSteps || t > tEnd) {
+            discard;
+        }
+        vec3 p = cameraPosition + t * rayDir;
+        float d = sdf(p);
+        if (d < surfaceDistance) {
+            vec3 normal = estimate_normal(p);
+            outColor.rgb = calculate_lighting(cameraPosition, surfaceColor.rgb, p, normal, metallic, roughness, 1.0);
+            outColor.rgb = reinhard_tone_mapping(outColor.rgb);
+            outColor.rgb = srgb_from_rgb(outColor.rgb);
+            outColor.a = surfaceColor.a;
+
+            // Write the depth of the hit point so the surface is depth tested correctly against other geometry
+            vec4 clipPosition = viewProjection * vec4(p, 1.0);
+            gl_FragDepth = depth_from_clip_position(clipPosition);
+            return;
+        }
+        t += d;
+    }
+    discard;
+}
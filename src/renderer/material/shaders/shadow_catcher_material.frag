@@ -0,0 +1,26 @@
+uniform vec3 cameraPosition;
+uniform vec4 shadowColor;
+uniform float opacity;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+float luminance(vec3 color) {
+    return dot(color, vec3(0.299, 0.587, 0.114));
+}
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+
+    // The shadow is the fraction of the light that is blocked by other objects
+    ignoreShadows = true;
+    float unshadowed = luminance(calculate_lighting(cameraPosition, vec3(1.0), pos, normal, 0.0, 1.0, 1.0));
+    ignoreShadows = false;
+    float shadowed = luminance(calculate_lighting(cameraPosition, vec3(1.0), pos, normal, 0.0, 1.0, 1.0));
+    float shadow = unshadowed > 0.0001 ? clamp(1.0 - shadowed / unshadowed, 0.0, 1.0) : 0.0;
+
+    outColor = vec4(srgb_from_rgb(shadowColor.rgb), shadow * opacity * shadowColor.a);
+}
@@ -27,6 +27,15 @@ vec2 uv_at(vec3 world_pos) {
     return 0.5 + 0.5 * p_s.xy / p_s.w;
 }
 
+// The scene color is only in the sRGB color space if linear output is disabled
+vec3 rgb_from_scene(vec3 color) {
+#ifdef LINEAR_OUTPUT
+    return color;
+#else
+    return rgb_from_srgb(color);
+#endif
+}
+
 vec3 reflect_color(vec3 incidentDir, vec3 normal)
 {
 #ifdef USE_BACKGROUND_TEXTURE
@@ -37,10 +46,10 @@ vec3 reflect_color(vec3 incidentDir, vec3 normal)
     {
         p_ray += stepDir;
         vec2 uv = uv_at(p_ray);
-        vec3 p = world_pos_from_depth(viewProjectionInverse, texture(depthMap, uv).x, uv);
+        vec3 p = world_pos_from_depth(viewProjectionInverse, sample_depth(uv), uv);
         if(distance(cameraPosition, p) < distance(cameraPosition, p_ray))
         {
-            return inverse_reinhard_tone_mapping(rgb_from_srgb(texture(colorMap, uv).xyz));
+            return inverse_reinhard_tone_mapping(rgb_from_scene(texture(colorMap, uv).xyz));
         }
     }
     return texture(environmentMap, reflectDir).xyz;
@@ -70,7 +79,7 @@ void main()
     screen_uv -= 0.05 * normal.xz; // Shift the water bottom/sky.
     float depth = sample_depth(screen_uv);
     vec3 backgroundPos = world_pos_from_depth(viewProjectionInverse, depth, screen_uv);
-    outColor.rgb = inverse_reinhard_tone_mapping(rgb_from_srgb(sample_color(screen_uv).rgb));
+    outColor.rgb = inverse_reinhard_tone_mapping(rgb_from_scene(sample_color(screen_uv).rgb));
     
     // Compute cosine to the incident angle
     float cosAngle = dot(normal, -incidentDir);
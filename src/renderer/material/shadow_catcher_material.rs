@@ -0,0 +1,63 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A material which is invisible except where the surface receives shadows from the other objects in the scene.
+/// Apply it to a ground plane below the objects to get grounded contact shadows, for example when compositing the rendered objects over a photograph or a [Backdrop].
+/// Only lights which have generated a shadow map cast shadows on the surface, see for example [DirectionalLight::generate_shadow_map].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowCatcherMaterial {
+    /// The color of the shadows. Assumed to be in linear color space.
+    pub shadow_color: Color,
+    /// The opacity of a fully shadowed point in the range `[0..1]`.
+    pub opacity: f32,
+    /// The lighting model used for calculating how much of the light is blocked.
+    pub lighting_model: LightingModel,
+}
+
+impl Default for ShadowCatcherMaterial {
+    fn default() -> Self {
+        Self {
+            shadow_color: Color::BLACK,
+            opacity: 0.6,
+            lighting_model: LightingModel::Blinn,
+        }
+    }
+}
+
+impl Material for ShadowCatcherMaterial {
+    fn fragment_shader(&self, lights: &[&dyn Light]) -> FragmentShader {
+        let mut source = lights_shader_source(lights, self.lighting_model);
+        source.push_str(include_str!("shaders/shadow_catcher_material.frag"));
+        FragmentShader {
+            source,
+            attributes: FragmentAttributes {
+                position: true,
+                normal: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, lights: &[&dyn Light]) {
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(program, i as u32);
+        }
+        program.use_uniform_if_required("cameraPosition", camera.position());
+        program.use_uniform("shadowColor", self.shadow_color);
+        program.use_uniform("opacity", self.opacity);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
@@ -0,0 +1,292 @@
+use crate::renderer::*;
+use std::sync::Arc;
+
+///
+/// The part of a [TextureAtlas] which contains one of the packed textures.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasRegion {
+    /// The distance in pixels from the left edge of the atlas to the left edge of the region.
+    pub x: u32,
+    /// The distance in pixels from the top edge of the atlas to the top edge of the region.
+    pub y: u32,
+    /// The width of the region in pixels.
+    pub width: u32,
+    /// The height of the region in pixels.
+    pub height: u32,
+    /// The uv coordinates of the top left corner of the region, where `(0, 0)` is the top left corner of the atlas.
+    pub uv_min: Vec2,
+    /// The uv coordinates of the bottom right corner of the region, where `(1, 1)` is the bottom right corner of the atlas.
+    pub uv_max: Vec2,
+}
+
+impl AtlasRegion {
+    ///
+    /// Returns the uv coordinates of the given uv coordinates inside the region, for example to set the uv coordinates of a [CpuMesh] which should show the region.
+    ///
+    pub fn uv(&self, uv: Vec2) -> Vec2 {
+        vec2(
+            self.uv_min.x + uv.x * (self.uv_max.x - self.uv_min.x),
+            self.uv_min.y + uv.y * (self.uv_max.y - self.uv_min.y),
+        )
+    }
+
+    ///
+    /// Returns the texture transformation which maps the uv coordinates of a geometry to the region, see [Texture2DRef::transformation].
+    ///
+    pub fn uv_transformation(&self) -> Mat3 {
+        // The uv coordinates are flipped in v in the shaders
+        Mat3::from_translation(vec2(self.uv_min.x, 1.0 - self.uv_max.y))
+            * Mat3::from_nonuniform_scale(
+                self.uv_max.x - self.uv_min.x,
+                self.uv_max.y - self.uv_min.y,
+            )
+    }
+}
+
+///
+/// Many small textures, for example the icons in a 2D scene, packed into one RGBA texture, so the objects using them can share the same texture and material.
+/// The textures are packed using the skyline bottom-left algorithm and each texture is padded by repeating its edge pixels to avoid bleeding between neighbouring textures.
+///
+/// Use [TextureAtlas::texture_ref] as the texture of a material to show one of the textures on a geometry with the standard uv coordinates,
+/// or use the full [TextureAtlas::texture] and the uv coordinates of the region in the geometry, for example with a [Sprite].
+///
+pub struct TextureAtlas {
+    texture: Arc<Texture2D>,
+    regions: Vec<AtlasRegion>,
+}
+
+impl TextureAtlas {
+    ///
+    /// Packs the given textures into a new texture atlas with a padding of one pixel around each texture.
+    /// The regions of the textures are in the same order as the given textures, see [TextureAtlas::region].
+    ///
+    /// # Panic
+    /// Will panic if the textures do not fit into the maximum texture size supported by the graphics card.
+    ///
+    pub fn new(context: &Context, cpu_textures: &[CpuTexture]) -> Self {
+        Self::new_with_padding(context, cpu_textures, 1)
+    }
+
+    ///
+    /// Packs the given textures into a new texture atlas with the given padding in pixels around each texture, see [TextureAtlas::new].
+    ///
+    pub fn new_with_padding(context: &Context, cpu_textures: &[CpuTexture], padding: u32) -> Self {
+        let max_size = context.capabilities().max_texture_size;
+        let sizes: Vec<(u32, u32)> = cpu_textures
+            .iter()
+            .map(|t| (t.width + 2 * padding, t.height + 2 * padding))
+            .collect();
+        let area: u32 = sizes.iter().map(|(w, h)| w * h).sum();
+        let mut width = ((area as f32).sqrt().ceil() as u32)
+            .max(sizes.iter().map(|(w, _)| *w).max().unwrap_or(1))
+            .next_power_of_two();
+        let mut height = width;
+        let positions = loop {
+            if let Some(positions) = pack(&sizes, width, height) {
+                break positions;
+            }
+            if width > height {
+                height *= 2;
+            } else {
+                width *= 2;
+            }
+            if width > max_size || height > max_size {
+                panic!(
+                    "the textures do not fit into a texture atlas with the maximum size {}x{}",
+                    max_size, max_size
+                );
+            }
+        };
+
+        let mut data = vec![[0u8; 4]; (width * height) as usize];
+        let mut regions = Vec::with_capacity(cpu_textures.len());
+        for (cpu_texture, (x, y)) in cpu_textures.iter().zip(positions) {
+            let pixels = rgba_u8(cpu_texture);
+            let (w, h) = (cpu_texture.width, cpu_texture.height);
+            // The padding repeats the edge pixels of the texture
+            for row in 0..h + 2 * padding {
+                let source_row = row.saturating_sub(padding).min(h.saturating_sub(1));
+                for column in 0..w + 2 * padding {
+                    let source_column = column.saturating_sub(padding).min(w.saturating_sub(1));
+                    data[((y + row) * width + x + column) as usize] =
+                        pixels[(source_row * w + source_column) as usize];
+                }
+            }
+            let (x, y) = (x + padding, y + padding);
+            regions.push(AtlasRegion {
+                x,
+                y,
+                width: w,
+                height: h,
+                uv_min: vec2(x as f32 / width as f32, y as f32 / height as f32),
+                uv_max: vec2(
+                    (x + w) as f32 / width as f32,
+                    (y + h) as f32 / height as f32,
+                ),
+            });
+        }
+
+        Self {
+            texture: Arc::new(Texture2D::new(
+                context,
+                &CpuTexture {
+                    data: TextureData::RgbaU8(data),
+                    width,
+                    height,
+                    mip_map_filter: None,
+                    wrap_s: Wrapping::ClampToEdge,
+                    wrap_t: Wrapping::ClampToEdge,
+                    ..Default::default()
+                },
+            )),
+            regions,
+        }
+    }
+
+    ///
+    /// Returns the texture containing all of the packed textures.
+    ///
+    pub fn texture(&self) -> &Arc<Texture2D> {
+        &self.texture
+    }
+
+    ///
+    /// Returns the region of the texture with the given index in the textures given at construction.
+    ///
+    pub fn region(&self, index: usize) -> AtlasRegion {
+        self.regions[index]
+    }
+
+    ///
+    /// Returns the regions of all the packed textures in the same order as the textures given at construction.
+    ///
+    pub fn regions(&self) -> &[AtlasRegion] {
+        &self.regions
+    }
+
+    ///
+    /// Returns a reference to the atlas texture with a texture transformation which shows the texture with the given index, see [TextureAtlas::region].
+    ///
+    pub fn texture_ref(&self, index: usize) -> Texture2DRef {
+        Texture2DRef {
+            texture: self.texture.clone(),
+            transformation: self.regions[index].uv_transformation(),
+        }
+    }
+
+    /// The width of the atlas texture in pixels.
+    pub fn width(&self) -> u32 {
+        self.texture.width()
+    }
+
+    /// The height of the atlas texture in pixels.
+    pub fn height(&self) -> u32 {
+        self.texture.height()
+    }
+}
+
+///
+/// Packs rectangles with the given sizes into a rectangle with the given size using the skyline bottom-left algorithm,
+/// where the skyline grows downwards from the top edge. Returns the top left corner of each rectangle or `None` if they do not fit.
+///
+fn pack(sizes: &[(u32, u32)], width: u32, height: u32) -> Option<Vec<(u32, u32)>> {
+    // Each segment of the skyline is the x position, the height of the skyline and the width
+    let mut skyline: Vec<(u32, u32, u32)> = vec![(0, 0, width)];
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse((sizes[*i].1, sizes[*i].0)));
+    let mut positions = vec![(0, 0); sizes.len()];
+    for index in order {
+        let (w, h) = sizes[index];
+        let mut best: Option<(u32, u32, usize)> = None;
+        for start in 0..skyline.len() {
+            let x = skyline[start].0;
+            if x + w > width {
+                break;
+            }
+            let mut y = 0;
+            let mut end = start;
+            while end < skyline.len() && skyline[end].0 < x + w {
+                y = y.max(skyline[end].1);
+                end += 1;
+            }
+            if y + h <= height && best.map(|(bx, by, _)| (y, x) < (by, bx)).unwrap_or(true) {
+                best = Some((x, y, start));
+            }
+        }
+        let (x, y, start) = best?;
+        positions[index] = (x, y);
+
+        // Replace the covered part of the skyline with the new rectangle
+        let mut new_skyline = skyline[..start].to_vec();
+        new_skyline.push((x, y + h, w));
+        for &(sx, sy, sw) in skyline[start..].iter() {
+            if sx + sw > x + w {
+                let cut = (x + w).max(sx);
+                new_skyline.push((cut, sy, sx + sw - cut));
+            }
+        }
+        new_skyline.dedup_by(|b, a| {
+            if a.1 == b.1 {
+                a.2 += b.2;
+                true
+            } else {
+                false
+            }
+        });
+        skyline = new_skyline;
+    }
+    Some(positions)
+}
+
+fn rgba_u8(cpu_texture: &CpuTexture) -> Vec<[u8; 4]> {
+    let from_f32 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match &cpu_texture.data {
+        TextureData::RU8(data) => data.iter().map(|r| [*r, *r, *r, 255]).collect(),
+        TextureData::RgU8(data) => data.iter().map(|c| [c[0], c[0], c[0], c[1]]).collect(),
+        TextureData::RgbU8(data) => data.iter().map(|c| [c[0], c[1], c[2], 255]).collect(),
+        TextureData::RgbaU8(data) => data.clone(),
+        TextureData::RF16(data) => data
+            .iter()
+            .map(|r| {
+                let r = from_f32(r.to_f32());
+                [r, r, r, 255]
+            })
+            .collect(),
+        TextureData::RgF16(data) => data
+            .iter()
+            .map(|c| {
+                let r = from_f32(c[0].to_f32());
+                [r, r, r, from_f32(c[1].to_f32())]
+            })
+            .collect(),
+        TextureData::RgbF16(data) => data
+            .iter()
+            .map(|c| c.map(|v| from_f32(v.to_f32())))
+            .map(|c| [c[0], c[1], c[2], 255])
+            .collect(),
+        TextureData::RgbaF16(data) => data
+            .iter()
+            .map(|c| c.map(|v| from_f32(v.to_f32())))
+            .collect(),
+        TextureData::RF32(data) => data
+            .iter()
+            .map(|r| {
+                let r = from_f32(*r);
+                [r, r, r, 255]
+            })
+            .collect(),
+        TextureData::RgF32(data) => data
+            .iter()
+            .map(|c| {
+                let r = from_f32(c[0]);
+                [r, r, r, from_f32(c[1])]
+            })
+            .collect(),
+        TextureData::RgbF32(data) => data
+            .iter()
+            .map(|c| [from_f32(c[0]), from_f32(c[1]), from_f32(c[2]), 255])
+            .collect(),
+        TextureData::RgbaF32(data) => data.iter().map(|c| c.map(from_f32)).collect(),
+    }
+}
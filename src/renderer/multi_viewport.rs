@@ -0,0 +1,342 @@
+//!
+//! Rendering the same scene to several views inside one viewport, for example the top, front, side and perspective views of a CAD application.
+//!
+
+use crate::renderer::*;
+
+///
+/// The region of a [MultiViewport] covered by a view, given as fractions of the size of the viewport, where (0, 0) is the bottom left corner and (1, 1) is the top right corner.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewRegion {
+    /// The distance from the left edge of the viewport as a fraction of the viewport width.
+    pub x: f32,
+    /// The distance from the bottom edge of the viewport as a fraction of the viewport height.
+    pub y: f32,
+    /// The width as a fraction of the viewport width.
+    pub width: f32,
+    /// The height as a fraction of the viewport height.
+    pub height: f32,
+}
+
+impl ViewRegion {
+    ///
+    /// Returns the region covering the entire viewport.
+    ///
+    pub fn full() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+
+    ///
+    /// Returns the part of the given viewport covered by this region.
+    /// Neighbouring regions are rounded to the same pixel, so there are no gaps or overlaps between them.
+    ///
+    pub fn viewport(&self, viewport: Viewport) -> Viewport {
+        let x0 = (self.x * viewport.width as f32).round() as i32;
+        let x1 = ((self.x + self.width) * viewport.width as f32).round() as i32;
+        let y0 = (self.y * viewport.height as f32).round() as i32;
+        let y1 = ((self.y + self.height) * viewport.height as f32).round() as i32;
+        Viewport {
+            x: viewport.x + x0,
+            y: viewport.y + y0,
+            width: (x1 - x0).max(0) as u32,
+            height: (y1 - y0).max(0) as u32,
+        }
+    }
+}
+
+///
+/// Renders the same scene to several views, each with its own camera and region of the viewport, and routes the input events to the view they belong to.
+///
+/// Pointer events are routed to the view under the pointer, except while a mouse button is pressed or the screen is touched,
+/// in which case they are routed to the view where the press started, so dragging out of a view does not hand the drag over to another view.
+/// Keyboard events are routed to the view which was last under the pointer.
+///
+/// The views are laid out relative to the viewport, so call [MultiViewport::set_viewport] when the viewport changes, for example when the window is resized,
+/// and the viewports of the cameras are updated accordingly.
+///
+pub struct MultiViewport {
+    viewport: Viewport,
+    regions: Vec<ViewRegion>,
+    cameras: Vec<Camera>,
+    captured_view: Option<usize>,
+    active_view: Option<usize>,
+    pressed_count: usize,
+}
+
+impl MultiViewport {
+    ///
+    /// Creates a new multi viewport with the given views, each given as the region of the viewport it covers and the camera to render it with.
+    /// The viewports of the cameras are replaced with the part of the viewport covered by the region.
+    ///
+    pub fn new(viewport: Viewport, views: impl IntoIterator<Item = (ViewRegion, Camera)>) -> Self {
+        let (regions, cameras) = views.into_iter().unzip();
+        let mut multi_viewport = Self {
+            viewport,
+            regions,
+            cameras,
+            captured_view: None,
+            active_view: None,
+            pressed_count: 0,
+        };
+        multi_viewport.set_viewport(viewport);
+        multi_viewport
+    }
+
+    ///
+    /// Creates the four views commonly used in CAD applications, all looking at the given target:
+    /// An orthographic top view in the top left corner, an orthographic front view in the top right corner,
+    /// an orthographic side view in the bottom left corner and a perspective view in the bottom right corner.
+    /// The orthographic views show the given height in world units and the cameras are placed the given distance from the target.
+    /// The up direction is the positive y-axis and the front view is looking in the negative z direction.
+    ///
+    pub fn new_quad(viewport: Viewport, target: Vec3, distance: f32, height: f32) -> Self {
+        let z_far = 10.0 * distance;
+        let orthographic = |direction: Vec3, up: Vec3| {
+            Camera::new_orthographic(
+                viewport,
+                target - direction * distance,
+                target,
+                up,
+                height,
+                0.0,
+                z_far,
+            )
+        };
+        let half = |x: f32, y: f32| ViewRegion {
+            x,
+            y,
+            width: 0.5,
+            height: 0.5,
+        };
+        Self::new(
+            viewport,
+            [
+                (
+                    half(0.0, 0.5),
+                    orthographic(vec3(0.0, -1.0, 0.0), vec3(0.0, 0.0, -1.0)),
+                ),
+                (
+                    half(0.5, 0.5),
+                    orthographic(vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+                ),
+                (
+                    half(0.0, 0.0),
+                    orthographic(vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+                ),
+                (
+                    half(0.5, 0.0),
+                    Camera::new_perspective(
+                        viewport,
+                        target + vec3(1.0, 1.0, 1.0).normalize() * distance,
+                        target,
+                        vec3(0.0, 1.0, 0.0),
+                        degrees(45.0),
+                        0.01 * distance,
+                        z_far,
+                    ),
+                ),
+            ],
+        )
+    }
+
+    ///
+    /// Returns the viewport containing all of the views.
+    ///
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    ///
+    /// Sets the viewport containing all of the views and updates the viewports of the cameras.
+    ///
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+        for (region, camera) in self.regions.iter().zip(self.cameras.iter_mut()) {
+            camera.set_viewport(region.viewport(viewport));
+        }
+    }
+
+    ///
+    /// Returns the number of views.
+    ///
+    pub fn view_count(&self) -> usize {
+        self.cameras.len()
+    }
+
+    ///
+    /// Returns the region covered by the view with the given index.
+    ///
+    pub fn region(&self, index: usize) -> ViewRegion {
+        self.regions[index]
+    }
+
+    ///
+    /// Sets the region covered by the view with the given index and updates the viewport of its camera.
+    ///
+    pub fn set_region(&mut self, index: usize, region: ViewRegion) {
+        self.regions[index] = region;
+        self.cameras[index].set_viewport(region.viewport(self.viewport));
+    }
+
+    ///
+    /// Returns the cameras of all views.
+    ///
+    pub fn cameras(&self) -> &[Camera] {
+        &self.cameras
+    }
+
+    ///
+    /// Returns the camera of the view with the given index.
+    ///
+    pub fn camera(&self, index: usize) -> &Camera {
+        &self.cameras[index]
+    }
+
+    ///
+    /// Returns a mutable reference to the camera of the view with the given index.
+    /// The viewport of the camera is overwritten the next time the viewport or the region of the view is set.
+    ///
+    pub fn camera_mut(&mut self, index: usize) -> &mut Camera {
+        &mut self.cameras[index]
+    }
+
+    ///
+    /// Returns the scissor box of the view with the given index, ie. the part of the render target it is rendered to.
+    ///
+    pub fn scissor_box(&self, index: usize) -> ScissorBox {
+        self.cameras[index].viewport().into()
+    }
+
+    ///
+    /// Returns the index of the view at the given position, or `None` if the position is outside all views.
+    /// If the views overlap, the last of them is returned.
+    ///
+    pub fn view_at(&self, position: impl Into<PhysicalPoint>) -> Option<usize> {
+        let position = position.into();
+        self.cameras.iter().rposition(|camera| {
+            let viewport = camera.viewport();
+            position.x >= viewport.x as f32
+                && position.y >= viewport.y as f32
+                && position.x < (viewport.x + viewport.width as i32) as f32
+                && position.y < (viewport.y + viewport.height as i32) as f32
+        })
+    }
+
+    ///
+    /// Returns the index of the view which currently receives the keyboard events, ie. the view which was last under the pointer.
+    ///
+    pub fn active_view(&self) -> Option<usize> {
+        self.active_view
+    }
+
+    ///
+    /// Routes the events to the views they belong to, see [MultiViewport].
+    /// The `handle_events` closure is called once for each view which receives any events with the index of the view, its camera and the events routed to it,
+    /// which is usually handed on to the control of that view, for example [OrbitControl::handle_events].
+    /// Whether or not the events are handled is written back to the given events.
+    /// Returns whether or not any of the calls to `handle_events` returned true, ie. whether a camera changed.
+    ///
+    pub fn handle_events(
+        &mut self,
+        events: &mut [Event],
+        mut handle_events: impl FnMut(usize, &mut Camera, &mut [Event]) -> bool,
+    ) -> bool {
+        let mut routed = vec![Vec::new(); self.cameras.len()];
+        for (event_index, event) in events.iter().enumerate() {
+            if let Some(view) = self.route(event) {
+                routed[view].push(event_index);
+            }
+        }
+
+        let mut change = false;
+        for (view, event_indices) in routed.into_iter().enumerate() {
+            if event_indices.is_empty() {
+                continue;
+            }
+            let mut view_events = event_indices
+                .iter()
+                .map(|i| events[*i].clone())
+                .collect::<Vec<_>>();
+            change |= handle_events(view, &mut self.cameras[view], &mut view_events);
+            for (i, event) in event_indices.into_iter().zip(view_events) {
+                events[i] = event;
+            }
+        }
+        change
+    }
+
+    ///
+    /// Renders the given objects with the given lights into each view of the given render target using the camera of that view.
+    /// The render target is not cleared.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(
+        &self,
+        target: &RenderTarget,
+        objects: impl IntoIterator<Item = impl Object> + Clone,
+        lights: &[&dyn Light],
+    ) {
+        self.render_with(|_, camera, scissor_box| {
+            target.render_partially(scissor_box, camera, objects.clone(), lights);
+        })
+    }
+
+    ///
+    /// Calls the given closure once for each view with the index of the view, the camera of the view and the scissor box of the view.
+    /// Use this for rendering with other means than [MultiViewport::render], for example to render different objects or with different lights in each view.
+    ///
+    pub fn render_with(&self, mut render: impl FnMut(usize, &Camera, ScissorBox)) {
+        for (index, camera) in self.cameras.iter().enumerate() {
+            render(index, camera, self.scissor_box(index));
+        }
+    }
+
+    // Returns the view the event should be routed to and keeps track of pressed buttons and touches
+    fn route(&mut self, event: &Event) -> Option<usize> {
+        match event {
+            Event::MousePress { position, .. }
+            | Event::Touch {
+                phase: TouchPhase::Started,
+                position,
+                ..
+            } => {
+                if self.pressed_count == 0 {
+                    self.captured_view = self.view_at(position);
+                }
+                self.pressed_count += 1;
+                self.active_view = self.captured_view.or(self.active_view);
+                self.captured_view
+            }
+            Event::MouseRelease { .. }
+            | Event::Touch {
+                phase: TouchPhase::Ended | TouchPhase::Cancelled,
+                ..
+            } => {
+                let view = self.captured_view;
+                self.pressed_count = self.pressed_count.saturating_sub(1);
+                if self.pressed_count == 0 {
+                    self.captured_view = None;
+                }
+                view
+            }
+            Event::MouseMotion { position, .. }
+            | Event::MouseWheel { position, .. }
+            | Event::Touch { position, .. } => {
+                if self.pressed_count > 0 {
+                    self.captured_view
+                } else {
+                    let view = self.view_at(position);
+                    self.active_view = view.or(self.active_view);
+                    view
+                }
+            }
+            _ => self.active_view,
+        }
+    }
+}
@@ -9,10 +9,30 @@ mod gm;
 #[doc(inline)]
 pub use gm::*;
 
+mod with_layers;
+#[doc(inline)]
+pub use with_layers::*;
+
+mod with_render_queue;
+#[doc(inline)]
+pub use with_render_queue::*;
+
 mod model;
 #[doc(inline)]
 pub use model::*;
 
+mod multi_material_mesh;
+#[doc(inline)]
+pub use multi_material_mesh::*;
+
+mod static_batch;
+#[doc(inline)]
+pub use static_batch::*;
+
+mod scene;
+#[doc(inline)]
+pub use scene::*;
+
 mod instanced_model;
 #[doc(inline)]
 pub use instanced_model::*;
@@ -21,10 +41,18 @@ mod voxel_grid;
 #[doc(inline)]
 pub use voxel_grid::*;
 
+mod voxel_object;
+#[doc(inline)]
+pub use voxel_object::*;
+
 mod skybox;
 #[doc(inline)]
 pub use skybox::*;
 
+mod backdrop;
+#[doc(inline)]
+pub use backdrop::*;
+
 mod imposters;
 #[doc(inline)]
 pub use imposters::*;
@@ -41,6 +69,42 @@ mod axes;
 #[doc(inline)]
 pub use axes::*;
 
+mod axes_helper;
+#[doc(inline)]
+pub use axes_helper::*;
+
+mod grid_helper;
+#[doc(inline)]
+pub use grid_helper::*;
+
+mod decal;
+#[doc(inline)]
+pub use decal::*;
+
+mod lod_group;
+#[doc(inline)]
+pub use lod_group::*;
+
+mod worldspace_bar;
+#[doc(inline)]
+pub use worldspace_bar::*;
+
+mod trail;
+#[doc(inline)]
+pub use trail::*;
+
+mod route;
+#[doc(inline)]
+pub use route::*;
+
+mod volume_raycaster;
+#[doc(inline)]
+pub use volume_raycaster::*;
+
+mod measurement;
+#[doc(inline)]
+pub use measurement::*;
+
 use crate::core::*;
 use crate::renderer::*;
 
@@ -59,6 +123,71 @@ pub trait Object: Geometry {
     /// Returns the type of material applied to this object.
     ///
     fn material_type(&self) -> MaterialType;
+
+    ///
+    /// Returns the number of bytes of GPU memory used by the textures applied to this object.
+    /// Used for reporting the cost of rendering, see [SceneStatistics].
+    ///
+    fn texture_memory(&self) -> usize {
+        0
+    }
+
+    ///
+    /// Returns the base color of the material applied to this object, see [Material::albedo].
+    ///
+    fn albedo(&self) -> Color {
+        Color::WHITE
+    }
+
+    ///
+    /// Returns the material applied to this object, if the object has a single material which is stored in the object.
+    /// Used for counting the materials and shader programs needed for rendering, see [SceneStatistics].
+    ///
+    fn material(&self) -> Option<&dyn Material> {
+        None
+    }
+
+    ///
+    /// Returns the render queue of this object, which decides the order of the objects in a render call before anything else, see [cmp_render_order].
+    ///
+    fn render_queue(&self) -> RenderQueue {
+        RenderQueue::DEFAULT
+    }
+}
+
+///
+/// The render queue of an [Object], see [Object::render_queue]. Use [WithRenderQueue] to place an object, for example a [Gm], in a render queue.
+/// Objects in a lower render queue are rendered before objects in a higher render queue, regardless of their material type and distance to the camera,
+/// for example a background before everything else or an overlay after everything else.
+/// Objects in the same render queue are rendered in the order given by [cmp_render_order].
+/// Any value can be used, for example `RenderQueue(RenderQueue::DEFAULT.0 + 1)` to render after the default queue but before the overlay queue.
+///
+/// The render queue only applies to objects rendered in the forward pass, since the objects with a deferred material are rendered before the forward objects.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RenderQueue(pub i32);
+
+impl RenderQueue {
+    ///
+    /// The render queue for objects rendered before everything else, for example a background.
+    ///
+    pub const BACKGROUND: Self = Self(-1000);
+
+    ///
+    /// The render queue of all objects by default.
+    ///
+    pub const DEFAULT: Self = Self(0);
+
+    ///
+    /// The render queue for objects rendered after everything else, for example an overlay.
+    ///
+    pub const OVERLAY: Self = Self(1000);
+}
+
+impl Default for RenderQueue {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 impl<T: Object + ?Sized> Object for &T {
@@ -69,6 +198,22 @@ impl<T: Object + ?Sized> Object for &T {
     fn material_type(&self) -> MaterialType {
         (*self).material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        (*self).texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        (*self).albedo()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        (*self).material()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        (*self).render_queue()
+    }
 }
 
 impl<T: Object + ?Sized> Object for &mut T {
@@ -79,6 +224,22 @@ impl<T: Object + ?Sized> Object for &mut T {
     fn material_type(&self) -> MaterialType {
         (**self).material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        (**self).texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        (**self).albedo()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        (**self).material()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        (**self).render_queue()
+    }
 }
 
 impl<T: Object> Object for Box<T> {
@@ -89,6 +250,22 @@ impl<T: Object> Object for Box<T> {
     fn material_type(&self) -> MaterialType {
         self.as_ref().material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.as_ref().texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.as_ref().albedo()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.as_ref().material()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.as_ref().render_queue()
+    }
 }
 
 impl<T: Object> Object for std::rc::Rc<T> {
@@ -99,6 +276,22 @@ impl<T: Object> Object for std::rc::Rc<T> {
     fn material_type(&self) -> MaterialType {
         self.as_ref().material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.as_ref().texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.as_ref().albedo()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.as_ref().material()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.as_ref().render_queue()
+    }
 }
 
 impl<T: Object> Object for std::sync::Arc<T> {
@@ -109,6 +302,22 @@ impl<T: Object> Object for std::sync::Arc<T> {
     fn material_type(&self) -> MaterialType {
         self.as_ref().material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.as_ref().texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.as_ref().albedo()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.as_ref().material()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.as_ref().render_queue()
+    }
 }
 
 impl<T: Object> Object for std::cell::RefCell<T> {
@@ -119,6 +328,18 @@ impl<T: Object> Object for std::cell::RefCell<T> {
     fn material_type(&self) -> MaterialType {
         self.borrow().material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.borrow().texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.borrow().albedo()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.borrow().render_queue()
+    }
 }
 
 impl<T: Object> Object for std::sync::RwLock<T> {
@@ -129,4 +350,16 @@ impl<T: Object> Object for std::sync::RwLock<T> {
     fn material_type(&self) -> MaterialType {
         self.read().unwrap().material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.read().unwrap().texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.read().unwrap().albedo()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.read().unwrap().render_queue()
+    }
 }
@@ -142,6 +142,10 @@ impl Geometry for Axes {
             depth_texture,
         );
     }
+
+    fn triangle_count(&self) -> usize {
+        3 * self.model.read().unwrap().triangle_count()
+    }
 }
 
 impl Object for Axes {
@@ -0,0 +1,144 @@
+use crate::renderer::*;
+
+///
+/// Three thin lines from origo along the x-axis (red), the y-axis (green) and the z-axis (blue), optionally continued along the negative axes with darker colors.
+/// A lightweight alternative to [Axes], typically combined with a [GridHelper] to show the orientation of the world or of a local coordinate system.
+///
+pub struct AxesHelper {
+    mesh: InstancedMesh,
+    aabb_local: AxisAlignedBoundingBox,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+}
+
+impl AxesHelper {
+    ///
+    /// Creates new axes with the given length, only showing the positive part of the axes.
+    ///
+    pub fn new(context: &Context, length: f32) -> Self {
+        Self::new_with_options(context, length, 0.005 * length, false)
+    }
+
+    ///
+    /// Creates new axes with the given length and line thickness.
+    /// If `negative` is true, the axes are also shown from origo to minus the length with darker colors.
+    ///
+    pub fn new_with_options(
+        context: &Context,
+        length: f32,
+        thickness: f32,
+        negative: bool,
+    ) -> Self {
+        let rotations = [
+            Mat4::identity(),
+            Mat4::from_angle_z(degrees(90.0)),
+            Mat4::from_angle_y(degrees(-90.0)),
+        ];
+        let colors = [Color::RED, Color::GREEN, Color::BLUE];
+        let mut transformations = Vec::new();
+        let mut instance_colors = Vec::new();
+        for (rotation, color) in rotations.iter().zip(colors) {
+            transformations
+                .push(*rotation * Mat4::from_nonuniform_scale(length, thickness, thickness));
+            instance_colors.push(color);
+            if negative {
+                transformations.push(
+                    *rotation
+                        * Mat4::from_angle_z(degrees(180.0))
+                        * Mat4::from_nonuniform_scale(length, thickness, thickness),
+                );
+                instance_colors.push(Color::new_opaque(color.r / 3, color.g / 3, color.b / 3));
+            }
+        }
+        let mesh = InstancedMesh::new(
+            context,
+            &Instances {
+                transformations,
+                colors: Some(instance_colors),
+                ..Default::default()
+            },
+            &CpuMesh::cylinder(8),
+        );
+        let min = if negative { -length } else { -thickness };
+        let aabb = AxisAlignedBoundingBox::new_with_positions(&[
+            vec3(min, min, min),
+            vec3(length, length, length),
+        ]);
+        Self {
+            mesh,
+            aabb_local: aabb,
+            aabb,
+            transformation: Mat4::identity(),
+        }
+    }
+
+    ///
+    /// Returns the local to world transformation applied to the axes.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to the axes.
+    /// Can be used to visualize a local coordinate system.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.mesh.set_transformation(transformation);
+        let mut aabb = self.aabb_local;
+        aabb.transform(&self.transformation);
+        self.aabb = aabb;
+    }
+}
+
+impl<'a> IntoIterator for &'a AxesHelper {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for AxesHelper {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+}
+
+impl Object for AxesHelper {
+    fn render(&self, camera: &Camera, _lights: &[&dyn Light]) {
+        self.mesh
+            .render_with_material(&ColorMaterial::default(), camera, &[])
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
@@ -0,0 +1,217 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The type of background rendered by a [Backdrop]. The colors are assumed to be in linear color space.
+///
+#[derive(Clone)]
+pub enum BackdropMode {
+    /// A single color.
+    Solid(Color),
+    /// A vertical gradient on the screen from the bottom color to the top color.
+    VerticalGradient {
+        /// The color at the bottom of the screen.
+        bottom: Color,
+        /// The color at the top of the screen.
+        top: Color,
+    },
+    /// A texture stretched to fill the screen.
+    Texture(Texture2DRef),
+    /// A studio style radial gradient from a bright center slightly below the middle of the screen to darker edges.
+    /// Combine with a ground plane using for example a shadow catching material to get the look of a product photo studio.
+    Studio {
+        /// The color at the center.
+        center: Color,
+        /// The color at the edges.
+        edge: Color,
+    },
+}
+
+///
+/// An [Object] which fills the background behind all other objects with a color, a gradient or a texture, see [BackdropMode].
+/// It is a simple alternative to a [Skybox], for example for product viewers where an environment map is not needed.
+/// The backdrop is placed at the far plane and does not write to the depth buffer, so it can be rendered together with the other objects in any order.
+///
+pub struct Backdrop {
+    context: Context,
+    positions: VertexBuffer,
+    /// The type of background.
+    pub mode: BackdropMode,
+    /// The color of an optional line along the horizon, ie. where the view direction is horizontal. The alpha value is used for blending the line with the background.
+    pub horizon_color: Color,
+    /// The width of the horizon line as an angle. A width of zero disables the horizon line.
+    pub horizon_width: Radians,
+}
+
+impl Backdrop {
+    ///
+    /// Creates a new backdrop of the given type without a horizon line.
+    ///
+    pub fn new(context: &Context, mode: BackdropMode) -> Self {
+        Self {
+            context: context.clone(),
+            positions: VertexBuffer::new_with_data(
+                context,
+                &[
+                    vec3(-3.0, -1.0, 0.0),
+                    vec3(3.0, -1.0, 0.0),
+                    vec3(0.0, 2.0, 0.0),
+                ],
+            ),
+            mode,
+            horizon_color: Color::WHITE,
+            horizon_width: radians(0.0),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Backdrop {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Backdrop {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                include_str!("shaders/backdrop.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    program.use_vertex_attribute("position", &self.positions);
+                    program.draw_arrays(material.render_states(), camera.viewport(), 3);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                include_str!("shaders/backdrop.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    program.use_vertex_attribute("position", &self.positions);
+                    program.draw_arrays(material.render_states(), camera.viewport(), 3);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn triangle_count(&self) -> usize {
+        1
+    }
+}
+
+impl Object for Backdrop {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.render_with_material(
+            &BackdropMaterial {
+                mode: &self.mode,
+                horizon_color: self.horizon_color,
+                horizon_width: self.horizon_width,
+            },
+            camera,
+            lights,
+        )
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+struct BackdropMaterial<'a> {
+    mode: &'a BackdropMode,
+    horizon_color: Color,
+    horizon_width: Radians,
+}
+
+impl Material for BackdropMaterial<'_> {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}{}{}",
+                match self.mode {
+                    BackdropMode::Texture(texture) if texture.is_srgb() =>
+                        "#define USE_TEXTURE\n#define TEXTURE_SRGB\n",
+                    BackdropMode::Texture(_) => "#define USE_TEXTURE\n",
+                    _ => "",
+                },
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/backdrop.frag")
+            ),
+            attributes: FragmentAttributes {
+                uv: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, _lights: &[&dyn Light]) {
+        let (mode, color0, color1) = match self.mode {
+            BackdropMode::Solid(color) => (0, *color, *color),
+            BackdropMode::VerticalGradient { bottom, top } => (1, *bottom, *top),
+            BackdropMode::Studio { center, edge } => (2, *center, *edge),
+            BackdropMode::Texture(texture) => {
+                program.use_texture("backdropTexture", texture);
+                program.use_uniform("textureTransformation", texture.transformation);
+                (0, Color::WHITE, Color::WHITE)
+            }
+        };
+        program.use_uniform_if_required("mode", mode as i32);
+        program.use_uniform_if_required("color0", color0);
+        program.use_uniform_if_required("color1", color1);
+        program.use_uniform_if_required("center", vec2(0.5, 0.4));
+        let viewport = camera.viewport();
+        program.use_uniform_if_required(
+            "aspect",
+            viewport.width as f32 / viewport.height.max(1) as f32,
+        );
+        program.use_uniform("horizonWidth", self.horizon_width.0);
+        if self.horizon_width.0 > 0.0 {
+            program.use_uniform("horizonColor", self.horizon_color);
+            program.use_uniform(
+                "inverseViewProjection",
+                (camera.projection() * camera.view())
+                    .invert()
+                    .unwrap_or(Mat4::identity()),
+            );
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::LessOrEqual,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
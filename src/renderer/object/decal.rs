@@ -0,0 +1,111 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A decal, ie. a texture projected onto the underlying geometry, for example a bullet hole, a sticker or a label.
+/// The decal is rendered as a box using a [DecalMaterial] which reconstructs the position of the underlying surface from a depth texture
+/// and projects the texture onto that surface.
+///
+/// Since the depth of the scene is needed, the scene must first be rendered into a [DepthTexture2D] which is then given to [Decal::render].
+/// The decal cannot be rendered into a render target that uses the same depth texture.
+/// Alternatively, use [Decal::render_on] to render the decal directly onto a set of geometries without the need for a depth texture.
+///
+pub struct Decal {
+    mesh: Mesh,
+    material: DecalMaterial,
+}
+
+impl Decal {
+    ///
+    /// Creates a new decal rendered with the given material.
+    /// The decal is projected into the box defined by the [DecalMaterial::transformation].
+    ///
+    pub fn new(context: &Context, material: DecalMaterial) -> Self {
+        let mut mesh = Mesh::new(context, &CpuMesh::cube());
+        mesh.set_transformation(material.transformation * Mat4::from_scale(0.5));
+        Self { mesh, material }
+    }
+
+    ///
+    /// Returns the material used to render the decal.
+    ///
+    pub fn material(&self) -> &DecalMaterial {
+        &self.material
+    }
+
+    ///
+    /// Sets the material used to render the decal, which also moves the decal to the box defined by the [DecalMaterial::transformation].
+    ///
+    pub fn set_material(&mut self, material: DecalMaterial) {
+        self.mesh
+            .set_transformation(material.transformation * Mat4::from_scale(0.5));
+        self.material = material;
+    }
+
+    ///
+    /// Returns the transformation from the unit cube to the box in which the decal is projected.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.material.transformation
+    }
+
+    ///
+    /// Set the transformation from the unit cube (from -0.5 to 0.5 in all axes) to the box in which the decal is projected.
+    /// The decal is projected along the negative z-axis of the box.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.material.transformation = transformation;
+        self.mesh
+            .set_transformation(transformation * Mat4::from_scale(0.5));
+    }
+
+    ///
+    /// Render the decal onto the surface defined by the depth texture (deferred decal).
+    /// Use an empty array for the `lights` argument, if the decal should not be affected by lights.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(&self, camera: &Camera, lights: &[&dyn Light], depth_texture: DepthTexture) {
+        if self.is_empty() {
+            return;
+        }
+        self.mesh.render_with_post_material(
+            &self.material,
+            camera,
+            lights,
+            None,
+            Some(depth_texture),
+        );
+    }
+
+    ///
+    /// Render the decal directly onto the given geometries (forward decal), ie. the geometries are rendered again with the [DecalMaterial].
+    /// The geometries must already be rendered into the depth buffer of the render target.
+    /// Use an empty array for the `lights` argument, if the decal should not be affected by lights.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render_on(
+        &self,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+        for geometry in geometries {
+            geometry.render_with_material(&self.material, camera, lights);
+        }
+    }
+
+    ///
+    /// Returns the [AxisAlignedBoundingBox] of the box in which the decal is projected.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
+
+    // The box is empty if the transformation is not invertible, in which case there is nothing to render
+    fn is_empty(&self) -> bool {
+        self.material.transformation.invert().is_none()
+    }
+}
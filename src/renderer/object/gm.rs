@@ -68,6 +68,14 @@ impl<G: Geometry, M: Material> Geometry for Gm<G, M> {
     fn obb(&self) -> OrientedBoundingBox2D {
         self.geometry.obb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.geometry.triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.geometry.layers()
+    }
 }
 
 impl<G: Geometry, M: Material> Object for Gm<G, M> {
@@ -78,6 +86,18 @@ impl<G: Geometry, M: Material> Object for Gm<G, M> {
     fn material_type(&self) -> MaterialType {
         self.material.material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.material.texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.material.albedo()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(&self.material)
+    }
 }
 
 impl<G: Geometry + Clone, M: Material + Clone> Clone for Gm<G, M> {
@@ -0,0 +1,213 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A ground grid in the horizontal plane with minor and major lines, as seen in most 3D editors.
+/// The grid is computed per pixel by intersecting the view rays with the plane, so it can be infinite and the lines stay crisp and anti-aliased at any distance.
+/// The lines are faded out with the distance to the camera, and where they get too dense to be visible, to avoid moiré patterns.
+/// The grid is depth tested per pixel, so it is correctly occluded by the other objects in the scene, but it does not write to the depth buffer.
+///
+pub struct GridHelper {
+    context: Context,
+    positions: VertexBuffer,
+    /// The height of the horizontal plane containing the grid.
+    pub height: f32,
+    /// The size of the grid in the x and z directions with center in `(0, height, 0)`, or `None` for an infinite grid.
+    pub size: Option<Vec2>,
+    /// The distance between two minor lines.
+    pub cell_size: f32,
+    /// The number of minor cells between two major lines.
+    pub major_line_every: u32,
+    /// The width of the lines in pixels.
+    pub line_width: f32,
+    /// The color of the minor lines. The alpha value is used for blending the lines with the objects behind the grid.
+    pub minor_color: Color,
+    /// The color of the major lines. The alpha value is used for blending the lines with the objects behind the grid.
+    pub major_color: Color,
+    /// The color of the line along the x axis. Use a color with zero alpha value to hide it.
+    pub x_axis_color: Color,
+    /// The color of the line along the z axis. Use a color with zero alpha value to hide it.
+    pub z_axis_color: Color,
+    /// The distance from the camera at which the grid has faded out completely, or zero to disable fading.
+    pub fade_distance: f32,
+}
+
+impl GridHelper {
+    ///
+    /// Creates a new infinite grid in the `y = 0` plane with a minor line for every unit and a major line for every 10 units.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            positions: VertexBuffer::new_with_data(
+                context,
+                &[
+                    vec3(-3.0, -1.0, 0.0),
+                    vec3(3.0, -1.0, 0.0),
+                    vec3(0.0, 2.0, 0.0),
+                ],
+            ),
+            height: 0.0,
+            size: None,
+            cell_size: 1.0,
+            major_line_every: 10,
+            line_width: 1.0,
+            minor_color: Color::new(128, 128, 128, 100),
+            major_color: Color::new(160, 160, 160, 200),
+            x_axis_color: Color::new(220, 50, 50, 255),
+            z_axis_color: Color::new(50, 80, 220, 255),
+            fade_distance: 100.0,
+        }
+    }
+
+    ///
+    /// Creates a new grid in the `y = 0` plane with the given size and with a minor line for every `cell_size` units.
+    /// The grid does not fade with distance.
+    ///
+    pub fn new_sized(context: &Context, size: Vec2, cell_size: f32) -> Self {
+        Self {
+            size: Some(size),
+            cell_size,
+            fade_distance: 0.0,
+            ..Self::new(context)
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a GridHelper {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for GridHelper {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        if let Some(size) = self.size {
+            AxisAlignedBoundingBox::new_with_positions(&[
+                vec3(-0.5 * size.x, self.height, -0.5 * size.y),
+                vec3(0.5 * size.x, self.height, 0.5 * size.y),
+            ])
+        } else {
+            AxisAlignedBoundingBox::INFINITE
+        }
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                include_str!("shaders/grid_helper.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    program.use_vertex_attribute("position", &self.positions);
+                    program.draw_arrays(material.render_states(), camera.viewport(), 3);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                include_str!("shaders/grid_helper.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    program.use_vertex_attribute("position", &self.positions);
+                    program.draw_arrays(material.render_states(), camera.viewport(), 3);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn triangle_count(&self) -> usize {
+        1
+    }
+}
+
+impl Object for GridHelper {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.render_with_material(&GridHelperMaterial { grid: self }, camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+struct GridHelperMaterial<'a> {
+    grid: &'a GridHelper,
+}
+
+impl Material for GridHelperMaterial<'_> {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/grid_helper.frag")
+            ),
+            attributes: FragmentAttributes {
+                uv: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, _lights: &[&dyn Light]) {
+        let grid = self.grid;
+        let view_projection = camera.projection() * camera.view();
+        program.use_uniform("viewProjection", view_projection);
+        program.use_uniform(
+            "inverseViewProjection",
+            view_projection.invert().unwrap_or(Mat4::identity()),
+        );
+        program.use_uniform_if_required("cameraPosition", camera.position());
+        program.use_uniform("height", grid.height);
+        program.use_uniform("cellSize", grid.cell_size);
+        program.use_uniform(
+            "majorCellSize",
+            grid.cell_size * grid.major_line_every.max(1) as f32,
+        );
+        program.use_uniform("lineWidth", grid.line_width.max(0.1));
+        program.use_uniform("minorColor", grid.minor_color);
+        program.use_uniform("majorColor", grid.major_color);
+        program.use_uniform("xAxisColor", grid.x_axis_color);
+        program.use_uniform("zAxisColor", grid.z_axis_color);
+        program.use_uniform("fadeDistance", grid.fade_distance);
+        program.use_uniform(
+            "halfSize",
+            grid.size.map(|s| 0.5 * s).unwrap_or(vec2(0.0, 0.0)),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
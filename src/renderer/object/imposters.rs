@@ -119,6 +119,10 @@ impl Geometry for Imposters {
     fn aabb(&self) -> AxisAlignedBoundingBox {
         self.sprites.aabb()
     }
+
+    fn triangle_count(&self) -> usize {
+        self.sprites.triangle_count()
+    }
 }
 
 impl Object for Imposters {
@@ -129,11 +133,17 @@ impl Object for Imposters {
     fn material_type(&self) -> MaterialType {
         self.material.material_type()
     }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(&self.material)
+    }
 }
 
 struct ImpostersMaterial {
     context: Context,
     texture: Texture2DArray,
+    // Whether or not the views in the texture were rendered with linear output, in which case they are not in the sRGB color space
+    linear: bool,
 }
 
 impl ImpostersMaterial {
@@ -157,6 +167,7 @@ impl ImpostersMaterial {
                 Wrapping::ClampToEdge,
                 Wrapping::ClampToEdge,
             ),
+            linear: false,
         };
         m.update(aabb, objects, lights, max_texture_size);
         m
@@ -203,6 +214,7 @@ impl ImpostersMaterial {
                 Wrapping::ClampToEdge,
                 Wrapping::ClampToEdge,
             );
+            self.linear = self.context.linear_output();
             for i in 0..NO_VIEW_ANGLES {
                 let layers = [i];
                 let angle = i as f32 * 2.0 * PI / NO_VIEW_ANGLES as f32;
@@ -226,7 +238,12 @@ impl Material for ImpostersMaterial {
     fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
         FragmentShader {
             source: format!(
-                "{}{}",
+                "{}{}{}",
+                if self.linear {
+                    "#define TEXTURE_LINEAR\n"
+                } else {
+                    ""
+                },
                 include_str!("../../core/shared.frag"),
                 include_str!("shaders/imposter.frag")
             ),
@@ -6,6 +6,8 @@ use crate::renderer::*;
 pub struct InstancedModelPart<M: Material> {
     gm: Gm<InstancedMesh, M>,
     animations: Vec<KeyFrameAnimation>,
+    layers: RenderLayers,
+    render_queue: RenderQueue,
 }
 
 impl<M: Material> InstancedModelPart<M> {
@@ -32,6 +34,20 @@ impl<M: Material> InstancedModelPart<M> {
             self.set_animation(move |time| animation.transformation(time));
         }
     }
+
+    ///
+    /// Sets the layers this model part belongs to, see [Geometry::layers].
+    ///
+    pub fn set_layers(&mut self, layers: RenderLayers) {
+        self.layers = layers;
+    }
+
+    ///
+    /// Sets the render queue of this model part, see [Object::render_queue].
+    ///
+    pub fn set_render_queue(&mut self, render_queue: RenderQueue) {
+        self.render_queue = render_queue;
+    }
 }
 
 impl<M: Material> std::ops::Deref for InstancedModelPart<M> {
@@ -75,6 +91,14 @@ impl<M: Material> Geometry for InstancedModelPart<M> {
     fn animate(&mut self, time: f32) {
         self.gm.animate(time)
     }
+
+    fn triangle_count(&self) -> usize {
+        self.gm.triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.layers
+    }
 }
 
 impl<M: Material> Object for InstancedModelPart<M> {
@@ -85,6 +109,18 @@ impl<M: Material> Object for InstancedModelPart<M> {
     fn material_type(&self) -> MaterialType {
         self.gm.material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.gm.texture_memory()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.render_queue
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.gm.material()
+    }
 }
 
 impl<'a, M: Material> IntoIterator for &'a InstancedModelPart<M> {
@@ -92,7 +128,7 @@ impl<'a, M: Material> IntoIterator for &'a InstancedModelPart<M> {
     type IntoIter = std::iter::Once<&'a dyn Object>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.gm.into_iter()
+        std::iter::once(self)
     }
 }
 
@@ -152,6 +188,8 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
                 gms.push(InstancedModelPart {
                     gm,
                     animations: primitive.animations.clone(),
+                    layers: RenderLayers::ALL,
+                    render_queue: RenderQueue::DEFAULT,
                 });
             }
         }
@@ -188,6 +226,21 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
     pub fn animate(&mut self, time: f32) {
         self.iter_mut().for_each(|m| m.animate(time));
     }
+
+    ///
+    /// Sets the layers of all parts of this model, see [Geometry::layers].
+    ///
+    pub fn set_layers(&mut self, layers: RenderLayers) {
+        self.iter_mut().for_each(|m| m.set_layers(layers));
+    }
+
+    ///
+    /// Sets the render queue of all parts of this model, see [Object::render_queue].
+    ///
+    pub fn set_render_queue(&mut self, render_queue: RenderQueue) {
+        self.iter_mut()
+            .for_each(|m| m.set_render_queue(render_queue));
+    }
 }
 
 impl<M: Material> std::ops::Deref for InstancedModel<M> {
@@ -0,0 +1,212 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Defines how the thresholds of the levels in a [LodGroup] are interpreted.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LodMetric {
+    /// The threshold of a level is the maximum distance from the camera to the center of the bounding box of the group where the level is used.
+    Distance,
+    /// The threshold of a level is the minimum height on the screen, as a fraction of the viewport height, of the bounding sphere of the group where the level is used.
+    /// This takes the field of view and the size of the viewport into account, contrary to [LodMetric::Distance].
+    ScreenSize,
+}
+
+///
+/// A level of detail in a [LodGroup].
+///
+pub struct LodLevel<T: Object> {
+    /// The object rendered at this level of detail.
+    pub object: T,
+    /// The threshold for using this level, see [LodMetric].
+    pub threshold: f32,
+}
+
+///
+/// An [Object] which contains several versions of the same object with decreasing level of detail and for each frame selects which one to render,
+/// based on either the distance to the camera or the size on the screen (see [LodMetric]).
+/// The levels are ordered from the most detailed to the least detailed, and the first level that fulfils its threshold is rendered.
+/// If none of the levels fulfil their threshold, nothing is rendered.
+///
+pub struct LodGroup<T: Object> {
+    /// The levels of detail ordered from the most detailed to the least detailed.
+    pub levels: Vec<LodLevel<T>>,
+    /// Defines how the thresholds of the levels are interpreted.
+    pub metric: LodMetric,
+}
+
+impl<T: Object> LodGroup<T> {
+    ///
+    /// Creates a new level of detail group from the given levels, ordered from the most detailed to the least detailed.
+    ///
+    pub fn new(levels: Vec<LodLevel<T>>, metric: LodMetric) -> Self {
+        Self { levels, metric }
+    }
+
+    ///
+    /// Returns the index of the level that is rendered when using the given camera or `None` if none of the levels fulfil their threshold.
+    ///
+    pub fn select(&self, camera: &Camera) -> Option<usize> {
+        let aabb = self.aabb();
+        if aabb.is_empty() {
+            return None;
+        }
+        let center = aabb.center();
+        match self.metric {
+            LodMetric::Distance => {
+                let distance = center.distance(*camera.position());
+                self.levels
+                    .iter()
+                    .position(|level| distance <= level.threshold)
+            }
+            LodMetric::ScreenSize => {
+                let radius = 0.5 * aabb.size().magnitude();
+                let w = (camera.projection() * camera.view() * center.extend(1.0)).w;
+                let size = radius * camera.projection()[1][1] / w.max(0.000001);
+                self.levels.iter().position(|level| size >= level.threshold)
+            }
+        }
+    }
+
+    fn selected(&self, camera: &Camera) -> Option<&T> {
+        self.select(camera).map(|i| &self.levels[i].object)
+    }
+}
+
+impl<M: Material + Clone> LodGroup<Gm<Mesh, M>> {
+    ///
+    /// Creates a new level of detail group by simplifying the given mesh, see [MeshProcessing::simplify].
+    /// Each level is given as the ratio of triangles left after simplification and the threshold for using that level.
+    ///
+    pub fn new_simplified(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        material: M,
+        levels: &[(f32, f32)],
+        metric: LodMetric,
+    ) -> Self {
+        Self::new(
+            levels
+                .iter()
+                .map(|(ratio, threshold)| {
+                    let mesh = if *ratio >= 1.0 {
+                        Mesh::new(context, cpu_mesh)
+                    } else {
+                        Mesh::new(context, &cpu_mesh.simplify(*ratio))
+                    };
+                    LodLevel {
+                        object: Gm::new(mesh, material.clone()),
+                        threshold: *threshold,
+                    }
+                })
+                .collect(),
+            metric,
+        )
+    }
+}
+
+impl<'a, T: Object> IntoIterator for &'a LodGroup<T> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<T: Object> Geometry for LodGroup<T> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for level in self.levels.iter() {
+            aabb.expand_with_aabb(&level.object.aabb());
+        }
+        aabb
+    }
+
+    fn animate(&mut self, time: f32) {
+        for level in self.levels.iter_mut() {
+            level.object.animate(time);
+        }
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.levels
+            .first()
+            .map(|level| level.object.triangle_count())
+            .unwrap_or(0)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if let Some(object) = self.selected(camera) {
+            object.render_with_material(material, camera, lights);
+        }
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(object) = self.selected(camera) {
+            object.render_with_post_material(
+                material,
+                camera,
+                lights,
+                color_texture,
+                depth_texture,
+            );
+        }
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.levels
+            .iter()
+            .fold(RenderLayers::NONE, |layers, level| {
+                layers | level.object.layers()
+            })
+    }
+}
+
+impl<T: Object> Object for LodGroup<T> {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        if let Some(object) = self.selected(camera) {
+            object.render(camera, lights);
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.levels
+            .first()
+            .map(|level| level.object.material_type())
+            .unwrap_or(MaterialType::Opaque)
+    }
+
+    fn texture_memory(&self) -> usize {
+        self.levels
+            .iter()
+            .map(|level| level.object.texture_memory())
+            .sum()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.levels
+            .first()
+            .map(|level| level.object.render_queue())
+            .unwrap_or_default()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.levels
+            .first()
+            .and_then(|level| level.object.material())
+    }
+}
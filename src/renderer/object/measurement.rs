@@ -0,0 +1,474 @@
+use crate::core::*;
+use crate::renderer::*;
+use crate::LocationPicker;
+
+// The number of line segments used to draw the arc of an angle measurement
+const ARC_SEGMENTS: usize = 16;
+
+///
+/// A text label of a [Measurement] which is anchored to a position in the scene, see [Measurement::label].
+/// The label is not rendered by the measurement, instead it should be drawn at the pixel using a GUI, for example an `egui::Painter`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeasurementLabel {
+    /// The formatted value of the measurement, for example `1.25 m`.
+    pub text: String,
+    /// The position in world space which the label is anchored to.
+    pub position: Vec3,
+    /// The pixel on the screen where the position is shown for the camera given to [Measurement::label], in physical pixels with the origin in the bottom left corner of the viewport.
+    pub pixel: PhysicalPoint,
+}
+
+///
+/// A measurement in the 3D scene, for example a [DistanceMeasurement], [AngleMeasurement] or [AreaMeasurement], which is defined by anchor points
+/// that are typically placed on the surface of the geometries in the scene using [Measurement::pick_point].
+/// The measurement is rendered as lines with a constant thickness in pixels and the value is shown by a [MeasurementLabel],
+/// which is recomputed for the current camera each time [Measurement::label] is called, so it follows the measurement when the camera moves.
+///
+pub trait Measurement: Object {
+    ///
+    /// Returns the anchor points of the measurement.
+    ///
+    fn points(&self) -> &[Vec3];
+
+    ///
+    /// Adds an anchor point to the measurement. If the measurement is already complete, a new measurement is started at the point.
+    ///
+    fn add_point(&mut self, point: Vec3);
+
+    ///
+    /// Removes all anchor points.
+    ///
+    fn clear(&mut self);
+
+    ///
+    /// Returns the measured value or `None` if there are not enough anchor points to measure anything.
+    ///
+    fn value(&self) -> Option<f32>;
+
+    ///
+    /// Returns the label showing the measured value, placed at the pixel where it is shown for the given camera,
+    /// or `None` if nothing is measured yet or the label is behind the camera.
+    ///
+    fn label(&self, camera: &Camera) -> Option<MeasurementLabel>;
+
+    ///
+    /// Picks the location on the given geometries at the given pixel using the [LocationPicker] and adds it as an anchor point.
+    /// Returns the added point or `None` if no geometry is visible at the pixel.
+    ///
+    fn pick_point(
+        &mut self,
+        picker: &LocationPicker,
+        camera: &Camera,
+        pixel: impl Into<PhysicalPoint> + Copy,
+        geometries: &[&dyn Geometry],
+    ) -> Option<Vec3>
+    where
+        Self: Sized,
+    {
+        let pick = picker.pick_location(camera, pixel, geometries)?;
+        if pick.clipped_by.is_some() {
+            return None;
+        }
+        self.add_point(pick.position);
+        Some(pick.position)
+    }
+}
+
+///
+/// A [Measurement] of the distance between two anchor points, which is drawn as a line between the points and labeled at the middle of the line.
+///
+pub struct DistanceMeasurement {
+    line: Line3D,
+    points: Vec<Vec3>,
+    /// The color of the lines.
+    pub color: Color,
+    /// Whether the lines are rendered on top of the scene or hidden behind other objects.
+    pub always_on_top: bool,
+    /// The number of decimals in the label.
+    pub precision: usize,
+    /// The unit appended to the value in the label, for example `m`.
+    pub unit: String,
+}
+
+impl DistanceMeasurement {
+    ///
+    /// Creates a new distance measurement without any anchor points.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            line: Line3D::new(context, &[], 2.0),
+            points: Vec::new(),
+            color: Color::new_opaque(255, 220, 0),
+            always_on_top: true,
+            precision: 2,
+            unit: String::new(),
+        }
+    }
+
+    fn update(&mut self) {
+        self.line.set_strip(&self.points, None);
+    }
+}
+
+impl Measurement for DistanceMeasurement {
+    fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    fn add_point(&mut self, point: Vec3) {
+        if self.points.len() == 2 {
+            self.points.clear();
+        }
+        self.points.push(point);
+        self.update();
+    }
+
+    fn clear(&mut self) {
+        self.points.clear();
+        self.update();
+    }
+
+    fn value(&self) -> Option<f32> {
+        if self.points.len() == 2 {
+            Some(self.points[0].distance(self.points[1]))
+        } else {
+            None
+        }
+    }
+
+    fn label(&self, camera: &Camera) -> Option<MeasurementLabel> {
+        let value = self.value()?;
+        label(
+            camera,
+            format_value(value, self.precision, &self.unit),
+            0.5 * (self.points[0] + self.points[1]),
+        )
+    }
+}
+
+///
+/// A [Measurement] of the angle at the second of three anchor points, between the lines to the first and third point.
+/// The angle is drawn as the two lines and an arc between them, and the value is in radians while the label shows the angle in degrees.
+///
+pub struct AngleMeasurement {
+    line: Line3D,
+    points: Vec<Vec3>,
+    /// The color of the lines.
+    pub color: Color,
+    /// Whether the lines are rendered on top of the scene or hidden behind other objects.
+    pub always_on_top: bool,
+    /// The number of decimals in the label.
+    pub precision: usize,
+}
+
+impl AngleMeasurement {
+    ///
+    /// Creates a new angle measurement without any anchor points.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            line: Line3D::new(context, &[], 2.0),
+            points: Vec::new(),
+            color: Color::new_opaque(255, 220, 0),
+            always_on_top: true,
+            precision: 1,
+        }
+    }
+
+    // Returns the unit vectors along the two legs and the radius of the arc
+    fn legs(&self) -> Option<(Vec3, Vec3, f32)> {
+        if self.points.len() < 3 {
+            return None;
+        }
+        let a = self.points[0] - self.points[1];
+        let b = self.points[2] - self.points[1];
+        let radius = 0.3 * a.magnitude().min(b.magnitude());
+        if radius <= 0.0 {
+            return None;
+        }
+        Some((a.normalize(), b.normalize(), radius))
+    }
+
+    fn update(&mut self) {
+        self.line.set_strip(&self.points, None);
+        if let Some((a, b, radius)) = self.legs() {
+            // The arc is in the plane of the two legs, from the first to the second leg
+            let angle = a.dot(b).clamp(-1.0, 1.0).acos();
+            let perpendicular = b - a * a.dot(b);
+            if perpendicular.magnitude2() > 0.0 {
+                let perpendicular = perpendicular.normalize();
+                let arc = (0..=ARC_SEGMENTS)
+                    .map(|i| {
+                        let t = angle * i as f32 / ARC_SEGMENTS as f32;
+                        self.points[1] + radius * (t.cos() * a + t.sin() * perpendicular)
+                    })
+                    .collect::<Vec<_>>();
+                let segments = self
+                    .points
+                    .windows(2)
+                    .chain(arc.windows(2))
+                    .map(|p| (p[0], p[1]))
+                    .collect::<Vec<_>>();
+                self.line.set_segments(&segments, None);
+            }
+        }
+    }
+}
+
+impl Measurement for AngleMeasurement {
+    fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    fn add_point(&mut self, point: Vec3) {
+        if self.points.len() == 3 {
+            self.points.clear();
+        }
+        self.points.push(point);
+        self.update();
+    }
+
+    fn clear(&mut self) {
+        self.points.clear();
+        self.update();
+    }
+
+    fn value(&self) -> Option<f32> {
+        let (a, b, _) = self.legs()?;
+        Some(a.dot(b).clamp(-1.0, 1.0).acos())
+    }
+
+    fn label(&self, camera: &Camera) -> Option<MeasurementLabel> {
+        let value = self.value()?;
+        let (a, b, radius) = self.legs()?;
+        // Place the label just outside the arc on the bisector of the angle
+        let bisector = a + b;
+        let direction = if bisector.magnitude2() > 0.0 {
+            bisector.normalize()
+        } else {
+            a
+        };
+        label(
+            camera,
+            format_value(value.to_degrees(), self.precision, "°"),
+            self.points[1] + 1.3 * radius * direction,
+        )
+    }
+}
+
+///
+/// A [Measurement] of the area of the polygon with three or more anchor points as corners, which is drawn as the closed outline of the polygon
+/// and labeled at the center of the corners.
+/// The area is the area of the polygon projected onto the plane which it is most aligned with, which is the exact area for a planar polygon.
+///
+pub struct AreaMeasurement {
+    line: Line3D,
+    points: Vec<Vec3>,
+    /// The color of the lines.
+    pub color: Color,
+    /// Whether the lines are rendered on top of the scene or hidden behind other objects.
+    pub always_on_top: bool,
+    /// The number of decimals in the label.
+    pub precision: usize,
+    /// The unit of length, for example `m`, which is appended to the value in the label as a square unit, for example `m²`.
+    pub unit: String,
+}
+
+impl AreaMeasurement {
+    ///
+    /// Creates a new area measurement without any anchor points.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            line: Line3D::new(context, &[], 2.0),
+            points: Vec::new(),
+            color: Color::new_opaque(255, 220, 0),
+            always_on_top: true,
+            precision: 2,
+            unit: String::new(),
+        }
+    }
+
+    ///
+    /// Removes the last anchor point, for example to undo a misplaced corner.
+    ///
+    pub fn remove_last_point(&mut self) {
+        self.points.pop();
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let mut points = self.points.clone();
+        if points.len() > 2 {
+            points.push(points[0]);
+        }
+        self.line.set_strip(&points, None);
+    }
+}
+
+impl Measurement for AreaMeasurement {
+    fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    fn add_point(&mut self, point: Vec3) {
+        self.points.push(point);
+        self.update();
+    }
+
+    fn clear(&mut self) {
+        self.points.clear();
+        self.update();
+    }
+
+    fn value(&self) -> Option<f32> {
+        if self.points.len() < 3 {
+            return None;
+        }
+        let mut area = vec3(0.0, 0.0, 0.0);
+        for i in 0..self.points.len() {
+            area += self.points[i].cross(self.points[(i + 1) % self.points.len()]);
+        }
+        Some(0.5 * area.magnitude())
+    }
+
+    fn label(&self, camera: &Camera) -> Option<MeasurementLabel> {
+        let value = self.value()?;
+        let center = self
+            .points
+            .iter()
+            .fold(vec3(0.0, 0.0, 0.0), |sum, p| sum + *p)
+            / self.points.len() as f32;
+        let unit = if self.unit.is_empty() {
+            String::new()
+        } else {
+            format!("{}²", self.unit)
+        };
+        label(camera, format_value(value, self.precision, &unit), center)
+    }
+}
+
+fn format_value(value: f32, precision: usize, unit: &str) -> String {
+    if unit.is_empty() || unit == "°" {
+        format!("{:.*}{}", precision, value, unit)
+    } else {
+        format!("{:.*} {}", precision, value, unit)
+    }
+}
+
+// Projects the position to a pixel on the screen, which is None if the position is behind the camera
+fn label(camera: &Camera, text: String, position: Vec3) -> Option<MeasurementLabel> {
+    let clip = camera.projection() * camera.view() * position.extend(1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let viewport = camera.viewport();
+    Some(MeasurementLabel {
+        text,
+        position,
+        pixel: PhysicalPoint {
+            x: viewport.x as f32 + (0.5 + 0.5 * clip.x / clip.w) * viewport.width as f32,
+            y: viewport.y as f32 + (0.5 + 0.5 * clip.y / clip.w) * viewport.height as f32,
+        },
+    })
+}
+
+fn render_states(always_on_top: bool) -> RenderStates {
+    if always_on_top {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    } else {
+        RenderStates::default()
+    }
+}
+
+macro_rules! impl_measurement_object {
+    ($measurement:ty) => {
+        impl $measurement {
+            ///
+            /// Returns the thickness of the lines in pixels.
+            ///
+            pub fn thickness(&self) -> f32 {
+                self.line.thickness
+            }
+
+            ///
+            /// Sets the thickness of the lines in pixels.
+            ///
+            pub fn set_thickness(&mut self, thickness: f32) {
+                self.line.thickness = thickness;
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $measurement {
+            type Item = &'a dyn Object;
+            type IntoIter = std::iter::Once<&'a dyn Object>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                std::iter::once(self)
+            }
+        }
+
+        impl Geometry for $measurement {
+            fn aabb(&self) -> AxisAlignedBoundingBox {
+                self.line.aabb()
+            }
+
+            fn render_with_material(
+                &self,
+                material: &dyn Material,
+                camera: &Camera,
+                lights: &[&dyn Light],
+            ) {
+                self.line.render_with_material(material, camera, lights)
+            }
+
+            fn render_with_post_material(
+                &self,
+                material: &dyn PostMaterial,
+                camera: &Camera,
+                lights: &[&dyn Light],
+                color_texture: Option<ColorTexture>,
+                depth_texture: Option<DepthTexture>,
+            ) {
+                self.line.render_with_post_material(
+                    material,
+                    camera,
+                    lights,
+                    color_texture,
+                    depth_texture,
+                )
+            }
+
+            fn triangle_count(&self) -> usize {
+                self.line.triangle_count()
+            }
+        }
+
+        impl Object for $measurement {
+            fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+                self.render_with_material(
+                    &ColorMaterial {
+                        color: self.color,
+                        render_states: render_states(self.always_on_top),
+                        ..Default::default()
+                    },
+                    camera,
+                    lights,
+                )
+            }
+
+            fn material_type(&self) -> MaterialType {
+                MaterialType::Opaque
+            }
+        }
+    };
+}
+
+impl_measurement_object!(DistanceMeasurement);
+impl_measurement_object!(AngleMeasurement);
+impl_measurement_object!(AreaMeasurement);
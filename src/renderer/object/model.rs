@@ -7,6 +7,8 @@ pub use three_d_asset::Model as CpuModel;
 pub struct ModelPart<M: Material> {
     gm: Gm<Mesh, M>,
     animations: Vec<KeyFrameAnimation>,
+    layers: RenderLayers,
+    render_queue: RenderQueue,
 }
 
 impl<M: Material> ModelPart<M> {
@@ -33,6 +35,20 @@ impl<M: Material> ModelPart<M> {
             self.set_animation(move |time| animation.transformation(time));
         }
     }
+
+    ///
+    /// Sets the layers this model part belongs to, see [Geometry::layers].
+    ///
+    pub fn set_layers(&mut self, layers: RenderLayers) {
+        self.layers = layers;
+    }
+
+    ///
+    /// Sets the render queue of this model part, see [Object::render_queue].
+    ///
+    pub fn set_render_queue(&mut self, render_queue: RenderQueue) {
+        self.render_queue = render_queue;
+    }
 }
 
 impl<M: Material> std::ops::Deref for ModelPart<M> {
@@ -78,9 +94,17 @@ impl<M: Material> Geometry for ModelPart<M> {
         self.gm.animate(time)
     }
 
+    fn triangle_count(&self) -> usize {
+        self.gm.triangle_count()
+    }
+
     fn obb(&self) -> OrientedBoundingBox2D {
         self.gm.obb()
     }
+
+    fn layers(&self) -> RenderLayers {
+        self.layers
+    }
 }
 impl<M: Material> Object for ModelPart<M> {
     fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
@@ -90,6 +114,18 @@ impl<M: Material> Object for ModelPart<M> {
     fn material_type(&self) -> MaterialType {
         self.gm.material_type()
     }
+
+    fn texture_memory(&self) -> usize {
+        self.gm.texture_memory()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.render_queue
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.gm.material()
+    }
 }
 
 impl<'a, M: Material> IntoIterator for &'a ModelPart<M> {
@@ -97,7 +133,7 @@ impl<'a, M: Material> IntoIterator for &'a ModelPart<M> {
     type IntoIter = std::iter::Once<&'a dyn Object>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.gm.into_iter()
+        std::iter::once(self)
     }
 }
 
@@ -153,6 +189,8 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
                 gms.push(ModelPart {
                     gm,
                     animations: primitive.animations.clone(),
+                    layers: RenderLayers::ALL,
+                    render_queue: RenderQueue::DEFAULT,
                 });
             }
         }
@@ -189,6 +227,21 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     pub fn animate(&mut self, time: f32) {
         self.iter_mut().for_each(|m| m.animate(time));
     }
+
+    ///
+    /// Sets the layers of all parts of this model, see [Geometry::layers].
+    ///
+    pub fn set_layers(&mut self, layers: RenderLayers) {
+        self.iter_mut().for_each(|m| m.set_layers(layers));
+    }
+
+    ///
+    /// Sets the render queue of all parts of this model, see [Object::render_queue].
+    ///
+    pub fn set_render_queue(&mut self, render_queue: RenderQueue) {
+        self.iter_mut()
+            .for_each(|m| m.set_render_queue(render_queue));
+    }
 }
 
 impl<M: Material> std::ops::Deref for Model<M> {
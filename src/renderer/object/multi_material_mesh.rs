@@ -0,0 +1,163 @@
+use crate::renderer::*;
+use std::ops::Range;
+
+///
+/// A range of triangles in a [MultiMaterialMesh] which is rendered with the same material.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialGroup {
+    /// The range of triangles in the mesh, see [Mesh::sub_mesh].
+    pub triangles: Range<u32>,
+    /// The index of the material in [MultiMaterialMesh::materials] which is used for the triangles.
+    pub material_index: usize,
+}
+
+///
+/// A [Mesh] where ranges of the triangles, called material groups, are rendered with different materials.
+/// All of the groups share the same vertex buffers, so the mesh does not need to be split into a mesh per material.
+///
+/// Iterating over a reference to this mesh returns an object for each material group, so it can be used in a render call, for example [RenderTarget::render],
+/// where the groups are sorted according to the type of material like any other object.
+/// As a [Geometry], for example when rendering shadows, the whole mesh is rendered.
+///
+pub struct MultiMaterialMesh<M: Material> {
+    mesh: Mesh,
+    materials: Vec<M>,
+    groups: Vec<MaterialGroup>,
+}
+
+impl<M: Material> MultiMaterialMesh<M> {
+    ///
+    /// Creates a new mesh from the given [CpuMesh] where each of the groups is rendered with the material with the given index in the list of materials.
+    ///
+    /// # Panic
+    /// Will panic if a group refers to a material which is not in the list of materials.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        materials: Vec<M>,
+        groups: Vec<MaterialGroup>,
+    ) -> Self {
+        let mut mesh = Self {
+            mesh: Mesh::new(context, cpu_mesh),
+            materials,
+            groups: Vec::new(),
+        };
+        mesh.set_groups(groups);
+        mesh
+    }
+
+    ///
+    /// Returns the mesh containing all of the triangles.
+    ///
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    ///
+    /// Returns a mutable reference to the mesh containing all of the triangles, for example to set the transformation.
+    ///
+    pub fn mesh_mut(&mut self) -> &mut Mesh {
+        &mut self.mesh
+    }
+
+    ///
+    /// Returns the materials.
+    ///
+    pub fn materials(&self) -> &[M] {
+        &self.materials
+    }
+
+    ///
+    /// Returns mutable references to the materials.
+    ///
+    pub fn materials_mut(&mut self) -> &mut [M] {
+        &mut self.materials
+    }
+
+    ///
+    /// Returns the material groups.
+    ///
+    pub fn groups(&self) -> &[MaterialGroup] {
+        &self.groups
+    }
+
+    ///
+    /// Sets the material groups.
+    ///
+    /// # Panic
+    /// Will panic if a group refers to a material which is not in the list of materials.
+    ///
+    pub fn set_groups(&mut self, groups: Vec<MaterialGroup>) {
+        for group in groups.iter() {
+            if group.material_index >= self.materials.len() {
+                panic!(
+                    "the material group refers to the material with index {} but there are only {} materials",
+                    group.material_index,
+                    self.materials.len()
+                );
+            }
+        }
+        self.groups = groups;
+    }
+
+    ///
+    /// Returns an object for each material group, which renders the triangles in the group with the material of the group.
+    ///
+    pub fn objects(&self) -> Vec<Gm<SubMesh<'_>, &M>> {
+        self.groups
+            .iter()
+            .map(|group| {
+                Gm::new(
+                    self.mesh.sub_mesh(group.triangles.clone()),
+                    &self.materials[group.material_index],
+                )
+            })
+            .collect()
+    }
+}
+
+impl<'a, M: Material> IntoIterator for &'a MultiMaterialMesh<M> {
+    type Item = Gm<SubMesh<'a>, &'a M>;
+    type IntoIter = std::vec::IntoIter<Gm<SubMesh<'a>, &'a M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.objects().into_iter()
+    }
+}
+
+impl<M: Material> Geometry for MultiMaterialMesh<M> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.mesh.animate(time)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+}
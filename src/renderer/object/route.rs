@@ -0,0 +1,291 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The pattern moving along a [Route] to indicate the direction of the flow.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FlowPattern {
+    /// Dashes moving in the direction of the flow.
+    #[default]
+    Dashes,
+    /// Arrows pointing and moving in the direction of the flow.
+    Arrows,
+}
+
+///
+/// An [Object] which renders a polyline, for example a route or a connection in a network, with a pattern moving along the line
+/// from the first to the last point to indicate the direction and speed of the flow.
+/// The line has a constant width in pixels regardless of the distance to the camera, both with a perspective and an orthographic camera.
+/// Call [Geometry::animate] each frame with the current time to move the pattern.
+///
+pub struct Route {
+    context: Context,
+    points: Vec<Vec3>,
+    length: f32,
+    time: f32,
+    positions: VertexBuffer,
+    directions: VertexBuffer,
+    sides_and_distances: VertexBuffer,
+    index_buffer: ElementBuffer,
+    aabb: AxisAlignedBoundingBox,
+    /// The width of the line in physical pixels.
+    pub width: f32,
+    /// The color of the line between the dashes or arrows.
+    pub color: Color,
+    /// The color of the dashes or arrows.
+    pub flow_color: Color,
+    /// The pattern moving along the line.
+    pub pattern: FlowPattern,
+    /// The length in world space of one dash or arrow and the following gap.
+    pub pattern_length: f32,
+    /// The speed of the pattern along the line in world space units per time unit given to [Geometry::animate].
+    /// A negative speed moves the pattern from the last point to the first point.
+    pub speed: f32,
+}
+
+impl Route {
+    ///
+    /// Creates a new route through the given points.
+    ///
+    pub fn new(context: &Context, points: &[Vec3]) -> Self {
+        let mut route = Self {
+            context: context.clone(),
+            points: Vec::new(),
+            length: 0.0,
+            time: 0.0,
+            positions: VertexBuffer::new(context),
+            directions: VertexBuffer::new(context),
+            sides_and_distances: VertexBuffer::new(context),
+            index_buffer: ElementBuffer::new(context),
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            width: 4.0,
+            color: Color::new(40, 40, 40, 255),
+            flow_color: Color::WHITE,
+            pattern: FlowPattern::default(),
+            pattern_length: 1.0,
+            speed: 1.0,
+        };
+        route.set_points(points);
+        route
+    }
+
+    ///
+    /// Returns the points of the route.
+    ///
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    ///
+    /// Replaces the points of the route.
+    ///
+    pub fn set_points(&mut self, points: &[Vec3]) {
+        let segment_count = points.len().saturating_sub(1);
+        let mut positions = Vec::with_capacity(4 * segment_count);
+        let mut directions = Vec::with_capacity(4 * segment_count);
+        let mut sides_and_distances = Vec::with_capacity(4 * segment_count);
+        let mut indices = Vec::with_capacity(6 * segment_count);
+        let mut distance = 0.0;
+        for segment in points.windows(2) {
+            let direction = segment[1] - segment[0];
+            let next_distance = distance + direction.magnitude();
+            let i = positions.len() as u32;
+            for (position, d) in [(segment[0], distance), (segment[1], next_distance)] {
+                for side in [-1.0, 1.0] {
+                    positions.push(position);
+                    directions.push(direction);
+                    sides_and_distances.push(vec2(side, d));
+                }
+            }
+            indices.extend_from_slice(&[i, i + 1, i + 2, i + 2, i + 1, i + 3]);
+            distance = next_distance;
+        }
+        self.positions.fill(&positions);
+        self.directions.fill(&directions);
+        self.sides_and_distances.fill(&sides_and_distances);
+        self.index_buffer.fill(&indices);
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(points);
+        self.length = distance;
+        self.points = points.to_vec();
+    }
+
+    ///
+    /// Returns the length of the route in world space.
+    ///
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
+        let viewport = camera.viewport();
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform(
+            "viewportSize",
+            vec2(viewport.width as f32, viewport.height as f32),
+        );
+        program.use_uniform("width", self.width);
+        program.use_vertex_attribute("position", &self.positions);
+        program.use_vertex_attribute("direction", &self.directions);
+        program.use_vertex_attribute("side_distance", &self.sides_and_distances);
+        program.draw_elements(render_states, viewport, &self.index_buffer);
+    }
+}
+
+impl<'a> IntoIterator for &'a Route {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Route {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    ///
+    /// Updates the current time used for moving the pattern along the route.
+    ///
+    fn animate(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    fn triangle_count(&self) -> usize {
+        2 * self.points.len().saturating_sub(1)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if self.points.len() < 2 {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                include_str!("shaders/route.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if self.points.len() < 2 {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                include_str!("shaders/route.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+}
+
+impl Object for Route {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        let material = RouteMaterial {
+            color: self.color,
+            flow_color: self.flow_color,
+            pattern: self.pattern,
+            pattern_length: self.pattern_length,
+            flow_offset: self.time * self.speed,
+        };
+        self.render_with_material(&material, camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        if self.color.a == 255 && self.flow_color.a == 255 {
+            MaterialType::Opaque
+        } else {
+            MaterialType::Transparent
+        }
+    }
+}
+
+struct RouteMaterial {
+    color: Color,
+    flow_color: Color,
+    pattern: FlowPattern,
+    pattern_length: f32,
+    flow_offset: f32,
+}
+
+impl Material for RouteMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/route.frag")
+            ),
+            attributes: FragmentAttributes {
+                uv: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform("color", self.color);
+        program.use_uniform("flowColor", self.flow_color);
+        program.use_uniform("patternLength", self.pattern_length.max(0.000001));
+        // Wrap the offset to keep the precision when the time gets large
+        program.use_uniform(
+            "flowOffset",
+            self.flow_offset % self.pattern_length.max(0.000001),
+        );
+        program.use_uniform(
+            "pattern",
+            match self.pattern {
+                FlowPattern::Dashes => 0i32,
+                FlowPattern::Arrows => 1i32,
+            },
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            blend: if self.material_type() == MaterialType::Transparent {
+                Blend::TRANSPARENCY
+            } else {
+                Blend::Disabled
+            },
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        if self.color.a == 255 && self.flow_color.a == 255 {
+            MaterialType::Opaque
+        } else {
+            MaterialType::Transparent
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::renderer::*;
+
+///
+/// A node in a [Scene] with a transformation relative to its parent node, a set of objects and a set of child nodes.
+///
+pub struct SceneNode<M: Material> {
+    /// The name of the node, for example the name of the primitive in the loaded file.
+    pub name: Option<String>,
+    /// The objects attached to this node, which are placed using the world transformation of this node.
+    pub objects: Vec<Gm<Mesh, M>>,
+    /// The child nodes which are placed relative to this node.
+    pub children: Vec<SceneNode<M>>,
+    transformation: Mat4,
+    animations: Vec<KeyFrameAnimation>,
+    current_animation: Option<KeyFrameAnimation>,
+    world_transformation: Mat4,
+}
+
+impl<M: Material> SceneNode<M> {
+    ///
+    /// Creates a new node without objects and children.
+    ///
+    pub fn new(name: Option<String>, transformation: Mat4) -> Self {
+        Self {
+            name,
+            objects: Vec::new(),
+            children: Vec::new(),
+            transformation,
+            animations: Vec::new(),
+            current_animation: None,
+            world_transformation: transformation,
+        }
+    }
+
+    ///
+    /// Returns the transformation of this node relative to its parent node.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Sets the transformation of this node relative to its parent node.
+    /// The objects of this node and all its descendants are updated when calling [Scene::update_transformations] or [Scene::animate].
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Returns the local to world transformation of this node, including the animation, at the time of the latest update.
+    ///
+    pub fn world_transformation(&self) -> Mat4 {
+        self.world_transformation
+    }
+
+    ///
+    /// Returns the animations of this node.
+    ///
+    pub fn animations(&self) -> &[KeyFrameAnimation] {
+        &self.animations
+    }
+
+    ///
+    /// Adds an animation to this node, which is applied on top of the transformation of the node when it is chosen using [Scene::choose_animation].
+    ///
+    pub fn add_animation(&mut self, animation: KeyFrameAnimation) {
+        self.animations.push(animation);
+    }
+
+    ///
+    /// Returns the first node with the given name in the subtree starting at this node.
+    ///
+    pub fn find(&self, name: &str) -> Option<&SceneNode<M>> {
+        if self.name.as_deref() == Some(name) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    ///
+    /// Returns the first node with the given name in the subtree starting at this node.
+    ///
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut SceneNode<M>> {
+        if self.name.as_deref() == Some(name) {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_mut(name))
+    }
+
+    fn choose_animation(&mut self, animation_name: Option<&str>) {
+        self.current_animation = self
+            .animations
+            .iter()
+            .find(|a| animation_name == a.name.as_deref())
+            .cloned();
+        for child in self.children.iter_mut() {
+            child.choose_animation(animation_name);
+        }
+    }
+
+    fn collect_animation_names(&self, names: &mut std::collections::HashSet<Option<String>>) {
+        names.extend(self.animations.iter().map(|a| a.name.clone()));
+        for child in self.children.iter() {
+            child.collect_animation_names(names);
+        }
+    }
+
+    fn update(&mut self, parent_transformation: Mat4, time: Option<f32>) {
+        let animation = match (&self.current_animation, time) {
+            (Some(animation), Some(time)) => animation.transformation(time),
+            _ => Mat4::identity(),
+        };
+        self.world_transformation = parent_transformation * self.transformation * animation;
+        for object in self.objects.iter_mut() {
+            object.set_transformation(self.world_transformation);
+        }
+        for child in self.children.iter_mut() {
+            child.update(self.world_transformation, time);
+        }
+    }
+
+    fn collect_objects<'a>(&'a self, objects: &mut Vec<&'a dyn Object>) {
+        objects.extend(self.objects.iter().map(|o| o as &dyn Object));
+        for child in self.children.iter() {
+            child.collect_objects(objects);
+        }
+    }
+}
+
+///
+/// A hierarchy of [SceneNode]s where the transformation of each node is relative to its parent node,
+/// so moving a node moves all of its descendants, and where the nodes can be animated by key frame animations.
+///
+/// This is not a full glTF scene import: a [CpuModel] only contains the geometries, materials and key frame animations of the loaded file,
+/// so a scene constructed with [Scene::new] is flat and does not contain the cameras, lights or skins of the file.
+/// A hierarchy can instead be built by hand from [SceneNode]s and passed to [Scene::from_root].
+///
+pub struct Scene<M: Material> {
+    root: SceneNode<M>,
+    time: Option<f32>,
+}
+
+impl<M: Material + FromCpuMaterial + Clone + Default> Scene<M> {
+    ///
+    /// Constructs a [Scene] from a [CpuModel], for example loaded from a glTF file, including the materials and the key frame animations.
+    /// Each primitive in the model becomes a child of the root node, with the transformation and the animations of the primitive.
+    /// The first animation is chosen by default, use [Scene::choose_animation] to choose another one.
+    ///
+    /// The node hierarchy of a glTF file is not reconstructed, since it is not kept in the [CpuModel], so the transformation of each primitive is its world transformation in the file.
+    /// Cameras, lights and skins in the file are not imported, so cameras and lights have to be created separately, for example with [Camera::new_perspective] and [DirectionalLight::new],
+    /// and skinned primitives are rendered without their joint animations.
+    /// Primitives which are not triangle meshes, for example point clouds, are skipped.
+    ///
+    pub fn new(context: &Context, cpu_model: &CpuModel) -> Result<Self, RendererError> {
+        let materials = cpu_model
+            .materials
+            .iter()
+            .map(|m| M::from_cpu_material(context, m))
+            .collect::<Vec<_>>();
+        let mut root = SceneNode::new(None, Mat4::identity());
+        for primitive in cpu_model.geometries.iter() {
+            if let CpuGeometry::Triangles(geometry) = &primitive.geometry {
+                let material = if let Some(material_index) = primitive.material_index {
+                    materials
+                        .get(material_index)
+                        .ok_or_else(|| {
+                            RendererError::MissingMaterial(
+                                material_index.to_string(),
+                                primitive.name.clone(),
+                            )
+                        })?
+                        .clone()
+                } else {
+                    M::default()
+                };
+                let mut node =
+                    SceneNode::new(Some(primitive.name.clone()), primitive.transformation);
+                node.objects
+                    .push(Gm::new(Mesh::new(context, geometry), material));
+                node.animations = primitive.animations.clone();
+                root.children.push(node);
+            }
+        }
+        let mut scene = Self::from_root(root);
+        if let Some(animation_name) = scene.animations().first().cloned() {
+            scene.choose_animation(animation_name.as_deref());
+        }
+        Ok(scene)
+    }
+}
+
+impl<M: Material> Scene<M> {
+    ///
+    /// Constructs a [Scene] from the given root node.
+    ///
+    pub fn from_root(root: SceneNode<M>) -> Self {
+        let mut scene = Self { root, time: None };
+        scene.update_transformations();
+        scene
+    }
+
+    ///
+    /// Returns the root node.
+    ///
+    pub fn root(&self) -> &SceneNode<M> {
+        &self.root
+    }
+
+    ///
+    /// Returns the root node. Call [Scene::update_transformations] after changing the transformations of the nodes.
+    ///
+    pub fn root_mut(&mut self) -> &mut SceneNode<M> {
+        &mut self.root
+    }
+
+    ///
+    /// Returns the first node with the given name.
+    ///
+    pub fn find(&self, name: &str) -> Option<&SceneNode<M>> {
+        self.root.find(name)
+    }
+
+    ///
+    /// Returns the first node with the given name. Call [Scene::update_transformations] after changing the transformation of the node.
+    ///
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut SceneNode<M>> {
+        self.root.find_mut(name)
+    }
+
+    ///
+    /// Returns a list of unique names for the animations in this scene. Use these names as input to [Self::choose_animation].
+    ///
+    pub fn animations(&self) -> Vec<Option<String>> {
+        let mut set = std::collections::HashSet::new();
+        self.root.collect_animation_names(&mut set);
+        set.into_iter().collect()
+    }
+
+    ///
+    /// Specifies the animation to use when [Scene::animate] is called. Use the [Self::animations] method to get a list of possible animations.
+    /// Nodes without an animation with the given name are not animated.
+    ///
+    pub fn choose_animation(&mut self, animation_name: Option<&str>) {
+        self.root.choose_animation(animation_name);
+        self.update_transformations();
+    }
+
+    ///
+    /// For updating the animation. The time parameter should be some continious time, for example the time since start.
+    ///
+    pub fn animate(&mut self, time: f32) {
+        self.time = Some(time);
+        self.update_transformations();
+    }
+
+    ///
+    /// Updates the world transformations of all nodes and the objects attached to them, which is needed after changing the transformation of a node.
+    ///
+    pub fn update_transformations(&mut self) {
+        self.root.update(Mat4::identity(), self.time);
+    }
+
+    ///
+    /// Returns all objects in this scene.
+    ///
+    pub fn objects(&self) -> Vec<&dyn Object> {
+        let mut objects = Vec::new();
+        self.root.collect_objects(&mut objects);
+        objects
+    }
+
+    ///
+    /// Analyzes the objects in this scene together with the given lights and returns a report of the cost of rendering them, see [SceneStatistics::analyze].
+    ///
+    pub fn analyze(&self, context: &Context, lights: &[&dyn Light]) -> SceneStatistics {
+        SceneStatistics::analyze(context, self.objects(), lights)
+    }
+}
+
+impl<'a, M: Material> IntoIterator for &'a Scene<M> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::vec::IntoIter<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.objects().into_iter()
+    }
+}
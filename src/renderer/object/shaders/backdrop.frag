@@ -0,0 +1,49 @@
+uniform int mode;
+uniform vec4 color0;
+uniform vec4 color1;
+uniform vec2 center;
+uniform float aspect;
+uniform vec4 horizonColor;
+uniform float horizonWidth;
+uniform mat4 inverseViewProjection;
+
+#ifdef USE_TEXTURE
+uniform sampler2D backdropTexture;
+uniform mat3 textureTransformation;
+#endif
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+#ifdef USE_TEXTURE
+    vec4 textureColor = texture(backdropTexture, (textureTransformation * vec3(uvs, 1.0)).xy);
+    outColor = textureColor;
+#ifndef TEXTURE_SRGB
+    outColor.rgb = rgb_from_srgb(outColor.rgb);
+#endif
+#else
+    if (mode == 0) {
+        outColor = color0;
+    } else if (mode == 1) {
+        outColor = mix(color0, color1, uvs.y);
+    } else {
+        float distanceToCenter = length((uvs - center) * vec2(aspect, 1.0));
+        outColor = mix(color0, color1, smoothstep(0.0, 1.0, distanceToCenter));
+    }
+#endif
+
+    if (horizonWidth > 0.0) {
+        // The elevation of the view direction through this pixel
+        vec2 ndc = 2.0 * uvs - 1.0;
+        vec4 near = inverseViewProjection * vec4(ndc, -1.0, 1.0);
+        vec4 far = inverseViewProjection * vec4(ndc, 1.0, 1.0);
+        vec3 direction = normalize(far.xyz / far.w - near.xyz / near.w);
+        float elevation = asin(clamp(direction.y, -1.0, 1.0));
+        float line = 1.0 - smoothstep(0.5 * horizonWidth, horizonWidth, abs(elevation));
+        outColor = mix(outColor, horizonColor, line * horizonColor.a);
+    }
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+}
@@ -0,0 +1,14 @@
+in vec3 position;
+
+out vec2 uvs;
+
+void main()
+{
+    uvs = 0.5 * position.xy + 0.5;
+    // Place the backdrop at the far plane so it is behind everything else
+    gl_Position = vec4(position.xy, 1.0, 1.0);
+#ifdef CLIP_DEPTH_ZERO_TO_ONE
+    // The far plane is at a clip space depth of zero when the depth is reversed, see Context::set_reverse_z
+    gl_Position.z = 0.0;
+#endif
+}
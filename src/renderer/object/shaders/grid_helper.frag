@@ -0,0 +1,77 @@
+
+uniform mat4 viewProjection;
+uniform mat4 inverseViewProjection;
+uniform vec3 cameraPosition;
+uniform float height;
+uniform float cellSize;
+uniform float majorCellSize;
+uniform float lineWidth;
+uniform vec4 minorColor;
+uniform vec4 majorColor;
+uniform vec4 xAxisColor;
+uniform vec4 zAxisColor;
+uniform float fadeDistance;
+uniform vec2 halfSize;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+// Returns the coverage of the grid lines with the given spacing in the range [0..1]
+float grid(vec2 p, float spacing) {
+    vec2 coord = p / spacing;
+    vec2 derivative = fwidth(coord);
+    vec2 g = abs(fract(coord - 0.5) - 0.5) / derivative;
+    float line = min(g.x, g.y);
+    // Fade out lines that are thinner than a pixel apart to avoid moire patterns
+    float density = max(derivative.x, derivative.y);
+    return (1.0 - min(line / lineWidth, 1.0)) * (1.0 - smoothstep(0.2, 0.5, density));
+}
+
+// Returns the coverage of the line where the given coordinate is zero
+float axis(float coord) {
+    return 1.0 - min(abs(coord) / (lineWidth * fwidth(coord)), 1.0);
+}
+
+void main()
+{
+    vec2 ndc = 2.0 * uvs - 1.0;
+    vec4 near = inverseViewProjection * vec4(ndc, -1.0, 1.0);
+    vec4 far = inverseViewProjection * vec4(ndc, 1.0, 1.0);
+    vec3 origin = near.xyz / near.w;
+    vec3 direction = far.xyz / far.w - origin;
+    if(abs(direction.y) < 1e-6) {
+        discard;
+    }
+    float t = (height - origin.y) / direction.y;
+    if(t < 0.0 || t > 1.0) {
+        discard;
+    }
+    vec3 p = origin + t * direction;
+    if(halfSize.x > 0.0 && (abs(p.x) > halfSize.x || abs(p.z) > halfSize.y)) {
+        discard;
+    }
+
+    float minor = grid(p.xz, cellSize) * minorColor.a;
+    float major = grid(p.xz, majorCellSize) * majorColor.a;
+    vec4 color = major >= minor ? vec4(majorColor.rgb, major) : vec4(minorColor.rgb, minor);
+    float x = axis(p.z) * xAxisColor.a;
+    if(x > 0.0) {
+        color = vec4(mix(color.rgb, xAxisColor.rgb, x), max(color.a, x));
+    }
+    float z = axis(p.x) * zAxisColor.a;
+    if(z > 0.0) {
+        color = vec4(mix(color.rgb, zAxisColor.rgb, z), max(color.a, z));
+    }
+
+    if(fadeDistance > 0.0) {
+        color.a *= 1.0 - smoothstep(0.5 * fadeDistance, fadeDistance, distance(p, cameraPosition));
+    }
+    if(color.a < 0.001) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(color.rgb), color.a);
+
+    vec4 clipPosition = viewProjection * vec4(p, 1.0);
+    gl_FragDepth = depth_from_clip_position(clipPosition);
+}
@@ -0,0 +1,10 @@
+
+in vec3 position;
+
+out vec2 uvs;
+
+void main()
+{
+    uvs = 0.5 * position.xy + 0.5;
+    gl_Position = vec4(position.xy, 0.0, 1.0);
+}
@@ -19,9 +19,11 @@ void main()
     float frac = layer - index0;
 
     vec4 color0 = texture(tex, vec3(uvs.x, uvs.y, index0));
-    color0.rgb = rgb_from_srgb(color0.rgb);
     vec4 color1 = texture(tex, vec3(uvs.x, uvs.y, index1));
+#ifndef TEXTURE_LINEAR
+    color0.rgb = rgb_from_srgb(color0.rgb);
     color1.rgb = rgb_from_srgb(color1.rgb);
+#endif
     out_color = mix(color0, color1, frac);
     out_color = vec4(srgb_from_rgb(out_color.rgb), out_color.a);
     if(out_color.a < 0.5) {
@@ -0,0 +1,20 @@
+uniform vec4 color;
+uniform vec4 flowColor;
+uniform float patternLength;
+uniform float flowOffset;
+uniform int pattern;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float t = (uvs.x - flowOffset) / patternLength;
+    if (pattern == 1) {
+        // Arrows pointing in the direction of the flow
+        t += abs(uvs.y - 0.5);
+    }
+    outColor = fract(t) < 0.5 ? flowColor : color;
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+}
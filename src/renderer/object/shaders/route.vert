@@ -0,0 +1,30 @@
+uniform mat4 viewProjection;
+uniform vec2 viewportSize;
+uniform float width;
+
+in vec3 position;
+in vec3 direction;
+in vec2 side_distance;
+
+out vec3 pos;
+out vec2 uvs;
+
+void main()
+{
+    vec4 clipPosition = viewProjection * vec4(position, 1.0);
+    vec4 clipNext = viewProjection * vec4(position + direction, 1.0);
+
+    // Expand the line sideways in screen space and multiply by w to cancel out the perspective division, so the line has a constant width on the screen
+    vec2 screenDirection = (clipNext.xy / clipNext.w - clipPosition.xy / clipPosition.w) * viewportSize;
+    float directionLength = length(screenDirection);
+    screenDirection = directionLength > 0.000001 ? screenDirection / directionLength : vec2(1.0, 0.0);
+    vec2 normal = vec2(-screenDirection.y, screenDirection.x);
+    clipPosition.xy += side_distance.x * normal * width / viewportSize * clipPosition.w;
+    gl_Position = clipPosition;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+
+    pos = position;
+    uvs = vec2(side_distance.y, 0.5 + 0.5 * side_distance.x);
+}
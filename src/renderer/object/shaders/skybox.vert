@@ -10,4 +10,8 @@ void main()
 {
     coords = position;
     gl_Position = (projection * mat4(mat3(view)) * vec4(position, 1.)).xyww;
+#ifdef CLIP_DEPTH_ZERO_TO_ONE
+    // The far plane is at a clip space depth of zero when the depth is reversed, see Context::set_reverse_z
+    gl_Position.z = 0.0;
+#endif
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+invariant gl_Position;
+
 uniform mat4 viewProjectionMatrix;
 
 in vec3 position;
@@ -27,4 +29,7 @@ void main()
     bitang = cross(nor, tang);
 #endif
     gl_Position = viewProjectionMatrix * worldPos;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
 }
@@ -0,0 +1,10 @@
+
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = col;
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+}
@@ -0,0 +1,35 @@
+uniform mat4 viewProjection;
+uniform vec3 eyePosition;
+uniform float startWidth;
+uniform float endWidth;
+uniform float currentTime;
+uniform float lifetime;
+uniform vec4 startColor;
+uniform vec4 endColor;
+
+in vec3 position;
+in vec3 direction;
+in vec2 side_time;
+
+out vec3 pos;
+out vec2 uvs;
+out vec4 col;
+
+void main()
+{
+    float age = clamp((currentTime - side_time.y) / lifetime, 0.0, 1.0);
+
+    // Expand the line sideways, perpendicular to both the line and the direction to the camera, so the ribbon faces the camera
+    vec3 side = cross(direction, eyePosition - position);
+    float sideLength = length(side);
+    side = sideLength > 0.000001 ? side / sideLength : vec3(0.0);
+    vec3 worldPosition = position + side * side_time.x * 0.5 * mix(startWidth, endWidth, age);
+
+    pos = worldPosition;
+    uvs = vec2(age, 0.5 + 0.5 * side_time.x);
+    col = mix(startColor, endColor, age);
+    gl_Position = viewProjection * vec4(worldPosition, 1.0);
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+}
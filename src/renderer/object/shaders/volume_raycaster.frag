@@ -0,0 +1,48 @@
+uniform vec3 cameraPosition;
+uniform mat4 textureFromWorld;
+uniform sampler3D voxels;
+uniform sampler2D transferFunction;
+uniform vec2 valueRange;
+uniform float opacity;
+uniform float stepSize;
+uniform float opacityCorrection;
+uniform int maxSteps;
+uniform float terminationThreshold;
+
+in vec3 pos;
+
+layout (location = 0) out vec4 outColor;
+
+void main() {
+    // March the ray in texture coordinates, where the volume is the unit cube
+    vec3 rayOrigin = (textureFromWorld * vec4(cameraPosition, 1.0)).xyz;
+    vec3 rayDir = normalize((textureFromWorld * vec4(pos, 1.0)).xyz - rayOrigin);
+
+    // Intersect the ray with the volume to find where to start and stop marching
+    vec3 invDir = 1.0 / rayDir;
+    vec3 t0 = -rayOrigin * invDir;
+    vec3 t1 = (vec3(1.0) - rayOrigin) * invDir;
+    vec3 tMin = min(t0, t1);
+    vec3 tMax = max(t0, t1);
+    float t = max(max(max(tMin.x, tMin.y), tMin.z), 0.0);
+    float tEnd = min(min(tMax.x, tMax.y), tMax.z);
+
+    // Composite the samples front to back
+    vec4 color = vec4(0.0);
+    for (int i = 0; i < 4096; i++) {
+        if (i >= maxSteps || t > tEnd || color.a >= terminationThreshold) {
+            break;
+        }
+        float value = texture(voxels, rayOrigin + t * rayDir).r;
+        float x = clamp((value - valueRange.x) / max(valueRange.y - valueRange.x, 0.00001), 0.0, 1.0);
+        vec4 sampleColor = texture(transferFunction, vec2(x, 0.5));
+        float alpha = 1.0 - pow(1.0 - clamp(opacity * sampleColor.a, 0.0, 0.9999), opacityCorrection);
+        color.rgb += (1.0 - color.a) * alpha * sampleColor.rgb;
+        color.a += (1.0 - color.a) * alpha;
+        t += stepSize;
+    }
+    if (color.a <= 0.0) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(color.rgb / color.a), color.a);
+}
@@ -1,3 +1,5 @@
+invariant gl_Position;
+
 uniform vec3 offset;
 uniform mat4 viewProjection;
 uniform float time;
@@ -52,6 +54,9 @@ void main()
     }
     
     gl_Position = viewProjection * vec4(pos, 1.);
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
     uvs = pos.xz;
     col = vec4(1.0);
 }
@@ -0,0 +1,23 @@
+
+uniform vec2 barSize;
+uniform float borderWidth;
+uniform vec4 backgroundColor;
+uniform vec4 borderColor;
+
+in vec2 uvs;
+in vec4 col;
+flat in float barValue;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 pixel = uvs * barSize;
+    if (pixel.x < borderWidth || pixel.y < borderWidth || pixel.x > barSize.x - borderWidth || pixel.y > barSize.y - borderWidth) {
+        outColor = borderColor;
+    } else {
+        float fill = (pixel.x - borderWidth) / max(barSize.x - 2.0 * borderWidth, 1.0);
+        outColor = fill <= barValue ? col : backgroundColor;
+    }
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+}
@@ -0,0 +1,31 @@
+uniform mat4 viewProjection;
+uniform vec2 viewportSize;
+uniform vec2 barSize;
+uniform vec2 barOffset;
+
+in vec2 corner;
+in vec3 instance_position;
+in float instance_value;
+in vec4 instance_color;
+
+out vec3 pos;
+out vec2 uvs;
+out vec4 col;
+flat out float barValue;
+
+void main()
+{
+    vec4 clipPosition = viewProjection * vec4(instance_position, 1.0);
+    // Offset in pixels, multiplied by w to cancel out the perspective division so the bar has a constant size on the screen
+    vec2 pixelOffset = barOffset + (corner - 0.5) * barSize;
+    clipPosition.xy += 2.0 * pixelOffset / viewportSize * clipPosition.w;
+    gl_Position = clipPosition;
+#ifdef LOGARITHMIC_DEPTH
+    gl_Position = logarithmic_depth(gl_Position);
+#endif
+
+    pos = instance_position;
+    uvs = corner;
+    col = instance_color;
+    barValue = instance_value;
+}
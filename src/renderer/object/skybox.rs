@@ -146,7 +146,7 @@ impl Geometry for Skybox {
                 |program| {
                     material.use_uniforms(program, camera, lights);
                     program.use_uniform("view", camera.view());
-                    program.use_uniform("projection", camera.projection());
+                    program.use_uniform("projection", camera.render_projection(program.context()));
                     program.use_vertex_attribute("position", &self.vertex_buffer);
                     program.draw_arrays(material.render_states(), camera.viewport(), 36);
                 },
@@ -170,13 +170,17 @@ impl Geometry for Skybox {
                 |program| {
                     material.use_uniforms(program, camera, lights, color_texture, depth_texture);
                     program.use_uniform("view", camera.view());
-                    program.use_uniform("projection", camera.projection());
+                    program.use_uniform("projection", camera.render_projection(program.context()));
                     program.use_vertex_attribute("position", &self.vertex_buffer);
                     program.draw_arrays(material.render_states(), camera.viewport(), 36);
                 },
             )
             .expect("Failed compiling shader");
     }
+
+    fn triangle_count(&self) -> usize {
+        12
+    }
 }
 
 impl Object for Skybox {
@@ -187,4 +191,8 @@ impl Object for Skybox {
     fn material_type(&self) -> MaterialType {
         MaterialType::Opaque
     }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(&self.material)
+    }
 }
@@ -0,0 +1,371 @@
+use crate::{renderer::*, OrientedBoundingBox2D};
+use std::ops::Range;
+
+///
+/// A set of static meshes which share the same material merged into one [Mesh], so they are rendered in one draw call instead of one draw call per mesh.
+/// Each source mesh is transformed by its transformation when the batch is built, so the source meshes cannot be moved individually afterwards.
+///
+/// The triangles of each source mesh are stored in a contiguous range, see [StaticBatch::triangles], which maps the triangles back to the source meshes,
+/// for example to find the source mesh of a picked position using [StaticBatch::source_at] or to render a single source mesh highlighted using [StaticBatch::source_mesh].
+///
+/// When only some of the source meshes are inside the view frustum, only those are drawn, using one indirect draw call if [Capabilities::multi_draw_indirect] is supported,
+/// see [Program::multi_draw_elements_indirect].
+///
+/// The normals, tangents, uv coordinates and colors are only kept if all of the source meshes have them,
+/// except that missing normals are computed and missing colors are white if any of the source meshes have normals or colors.
+///
+pub struct StaticBatch<M: Material> {
+    gm: Gm<Mesh, M>,
+    triangles: Vec<Range<u32>>,
+    aabbs: Vec<AxisAlignedBoundingBox>,
+    positions: Vec<Vec3>,
+    indices: Vec<u32>,
+    commands: std::sync::RwLock<DrawIndirectBuffer>,
+}
+
+impl<M: Material> StaticBatch<M> {
+    ///
+    /// Builds a batch from the given source meshes, each given together with its transformation, which are rendered with the given material.
+    ///
+    pub fn new<'a>(
+        context: &Context,
+        sources: impl IntoIterator<Item = (&'a CpuMesh, Mat4)>,
+        material: M,
+    ) -> Self {
+        let sources = sources.into_iter().collect::<Vec<_>>();
+        let any_normals = sources.iter().any(|(m, _)| m.normals.is_some());
+        let all_tangents = sources.iter().all(|(m, _)| m.tangents.is_some());
+        let all_uvs = sources.iter().all(|(m, _)| m.uvs.is_some());
+        let any_colors = sources.iter().any(|(m, _)| m.colors.is_some());
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut tangents = Vec::new();
+        let mut uvs = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+        let mut triangles = Vec::new();
+        let mut aabbs = Vec::new();
+        for (cpu_mesh, transformation) in sources {
+            let normal_transformation = transformation
+                .invert()
+                .unwrap_or(Mat4::identity())
+                .transpose();
+            let offset = positions.len() as u32;
+            let first_triangle = (indices.len() / 3) as u32;
+            positions.extend(
+                cpu_mesh
+                    .positions
+                    .to_f32()
+                    .into_iter()
+                    .map(|p| (transformation * p.extend(1.0)).truncate()),
+            );
+            indices.extend(source_indices(cpu_mesh).into_iter().map(|i| offset + i));
+            triangles.push(first_triangle..(indices.len() / 3) as u32);
+            aabbs.push(AxisAlignedBoundingBox::new_with_positions(
+                &positions[offset as usize..],
+            ));
+
+            if any_normals {
+                let computed;
+                let source_normals = match &cpu_mesh.normals {
+                    Some(normals) => normals,
+                    None => {
+                        let mut mesh = cpu_mesh.clone();
+                        mesh.compute_normals();
+                        computed = mesh.normals.unwrap();
+                        &computed
+                    }
+                };
+                normals.extend(source_normals.iter().map(|n| {
+                    (normal_transformation * n.extend(0.0))
+                        .truncate()
+                        .normalize()
+                }));
+            }
+            if all_tangents {
+                tangents.extend(cpu_mesh.tangents.as_ref().unwrap().iter().map(|t| {
+                    (transformation * t.truncate().extend(0.0))
+                        .truncate()
+                        .normalize()
+                        .extend(t.w)
+                }));
+            }
+            if all_uvs {
+                uvs.extend_from_slice(cpu_mesh.uvs.as_ref().unwrap());
+            }
+            if any_colors {
+                match &cpu_mesh.colors {
+                    Some(source_colors) => colors.extend_from_slice(source_colors),
+                    None => colors
+                        .extend(std::iter::repeat(Color::WHITE).take(cpu_mesh.positions.len())),
+                }
+            }
+        }
+
+        let cpu_mesh = CpuMesh {
+            positions: Positions::F32(positions.clone()),
+            indices: Indices::U32(indices.clone()),
+            normals: any_normals.then_some(normals),
+            tangents: all_tangents.then_some(tangents),
+            uvs: all_uvs.then_some(uvs),
+            colors: any_colors.then_some(colors),
+        };
+        Self {
+            gm: Gm::new(Mesh::new(context, &cpu_mesh), material),
+            triangles,
+            aabbs,
+            positions,
+            indices,
+            commands: std::sync::RwLock::new(DrawIndirectBuffer::new(context)),
+        }
+    }
+
+    ///
+    /// Returns the number of source meshes in this batch.
+    ///
+    pub fn source_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    ///
+    /// Returns the range of triangles in the merged mesh which belongs to the source mesh with the given index.
+    ///
+    pub fn triangles(&self, source: usize) -> Range<u32> {
+        self.triangles[source].clone()
+    }
+
+    ///
+    /// Returns the index of the source mesh which the triangle with the given number in the merged mesh belongs to.
+    ///
+    pub fn source_of_triangle(&self, triangle: u32) -> Option<usize> {
+        let index = self.triangles.partition_point(|r| r.end <= triangle);
+        (index < self.triangles.len() && self.triangles[index].contains(&triangle)).then_some(index)
+    }
+
+    ///
+    /// Returns the index of the source mesh closest to the given position in world space, for example a position picked using [LocationPicker::pick_location].
+    /// Returns `None` if the batch is empty.
+    ///
+    pub fn source_at(&self, position: Vec3) -> Option<usize> {
+        let position = self
+            .gm
+            .geometry
+            .transformation()
+            .invert()
+            .map(|t| (t * position.extend(1.0)).truncate())
+            .unwrap_or(position);
+        self.indices
+            .chunks(3)
+            .enumerate()
+            .map(|(triangle, t)| {
+                let closest = closest_point_on_triangle(
+                    position,
+                    self.positions[t[0] as usize],
+                    self.positions[t[1] as usize],
+                    self.positions[t[2] as usize],
+                );
+                (triangle, closest.distance2(position))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .and_then(|(triangle, _)| self.source_of_triangle(triangle as u32))
+    }
+
+    ///
+    /// Returns the bounding box of the source mesh with the given index in world space.
+    ///
+    pub fn source_aabb(&self, source: usize) -> AxisAlignedBoundingBox {
+        let mut aabb = self.aabbs[source];
+        aabb.transform(&self.gm.geometry.transformation());
+        aabb
+    }
+
+    ///
+    /// Returns a geometry which renders only the source mesh with the given index, for example to highlight it using another material.
+    ///
+    pub fn source_mesh(&self, source: usize) -> SubMesh<'_> {
+        self.gm.geometry.sub_mesh(self.triangles(source))
+    }
+}
+
+impl<M: Material> StaticBatch<M> {
+    // Returns the draw calls for the source meshes inside the view frustum, merging neighbouring source meshes into one draw call,
+    // or None if all of the source meshes are inside the view frustum
+    fn visible_commands(&self, camera: &Camera) -> Option<Vec<DrawElementsIndirectCommand>> {
+        let visible = (0..self.source_count())
+            .map(|source| camera.in_frustum(&self.source_aabb(source)))
+            .collect::<Vec<_>>();
+        if visible.iter().all(|v| *v) {
+            return None;
+        }
+        let mut commands: Vec<DrawElementsIndirectCommand> = Vec::new();
+        for (triangles, _) in self
+            .triangles
+            .iter()
+            .zip(visible)
+            .filter(|(t, visible)| *visible && !t.is_empty())
+        {
+            match commands.last_mut() {
+                Some(last) if last.first_index + last.count == 3 * triangles.start => {
+                    last.count += 3 * triangles.len() as u32;
+                }
+                _ => commands.push(DrawElementsIndirectCommand {
+                    count: 3 * triangles.len() as u32,
+                    instance_count: 1,
+                    first_index: 3 * triangles.start,
+                    base_vertex: 0,
+                    base_instance: 0,
+                }),
+            }
+        }
+        Some(commands)
+    }
+}
+
+impl<M: Material> std::ops::Deref for StaticBatch<M> {
+    type Target = Gm<Mesh, M>;
+    fn deref(&self) -> &Self::Target {
+        &self.gm
+    }
+}
+
+impl<M: Material> std::ops::DerefMut for StaticBatch<M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.gm
+    }
+}
+
+impl<'a, M: Material> IntoIterator for &'a StaticBatch<M> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<M: Material> Geometry for StaticBatch<M> {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if let Some(commands) = self.visible_commands(camera) {
+            if !commands.is_empty() {
+                let mut buffer = self.commands.write().unwrap();
+                buffer.fill(&commands);
+                self.gm
+                    .geometry
+                    .render_indirect_with_material(&buffer, material, camera, lights);
+            }
+        } else {
+            self.gm.render_with_material(material, camera, lights)
+        }
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.gm
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.gm.aabb()
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.gm.animate(time)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.gm.triangle_count()
+    }
+
+    fn obb(&self) -> OrientedBoundingBox2D {
+        self.gm.obb()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.gm.layers()
+    }
+}
+
+impl<M: Material> Object for StaticBatch<M> {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.render_with_material(&self.gm.material, camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.gm.material_type()
+    }
+
+    fn texture_memory(&self) -> usize {
+        self.gm.texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.gm.albedo()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.gm.render_queue()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.gm.material()
+    }
+}
+
+fn source_indices(cpu_mesh: &CpuMesh) -> Vec<u32> {
+    match &cpu_mesh.indices {
+        Indices::U8(ind) => ind.iter().map(|i| *i as u32).collect(),
+        Indices::U16(ind) => ind.iter().map(|i| *i as u32).collect(),
+        Indices::U32(ind) => ind.clone(),
+        Indices::None => (0..cpu_mesh.positions.len() as u32).collect(),
+    }
+}
+
+// Returns the point on the triangle abc closest to the point p, see Real-Time Collision Detection by Christer Ericson
+fn closest_point_on_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
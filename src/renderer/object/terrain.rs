@@ -353,7 +353,10 @@ impl TerrainPatch {
         render_states: RenderStates,
         camera: &Camera,
     ) {
-        program.use_uniform("viewProjectionMatrix", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjectionMatrix",
+            camera.render_view_projection(program.context()),
+        );
         program.use_vertex_attribute("position", &self.positions_buffer);
         if required_attributes.normal || required_attributes.tangents {
             program.use_vertex_attribute("normal", &self.normals_buffer);
@@ -410,4 +413,8 @@ impl Geometry for TerrainPatch {
     fn aabb(&self) -> AxisAlignedBoundingBox {
         self.aabb
     }
+
+    fn triangle_count(&self) -> usize {
+        self.index_buffer.triangle_count()
+    }
 }
@@ -0,0 +1,265 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::VecDeque;
+
+///
+/// An [Object] which records the positions of a moving object over time and renders them as a ribbon facing the camera,
+/// which fades out as the recorded positions get older, for example to visualize the trajectory of a vehicle or a physics body.
+/// Call [Trail::push] each frame with the current position of the followed object.
+///
+pub struct Trail {
+    context: Context,
+    points: VecDeque<(Vec3, f32)>,
+    time: f32,
+    positions: VertexBuffer,
+    directions: VertexBuffer,
+    sides_and_times: VertexBuffer,
+    index_buffer: ElementBuffer,
+    /// The time a recorded position is visible, in the same unit as the time given to [Trail::push].
+    pub lifetime: f32,
+    /// The width of the ribbon at the newest position.
+    pub start_width: f32,
+    /// The width of the ribbon at the oldest position.
+    pub end_width: f32,
+    /// The color of the ribbon at the newest position.
+    pub start_color: Color,
+    /// The color of the ribbon at the oldest position, the alpha value is used to fade out the ribbon.
+    pub end_color: Color,
+    /// The minimum distance between two recorded positions, if the followed object has moved less than this since the last recorded position, that position is moved instead.
+    pub min_distance: f32,
+}
+
+impl Trail {
+    ///
+    /// Creates a new empty trail where the recorded positions are visible for the given lifetime and the ribbon has the given width.
+    ///
+    pub fn new(context: &Context, lifetime: f32, width: f32) -> Self {
+        Self {
+            context: context.clone(),
+            points: VecDeque::new(),
+            time: 0.0,
+            positions: VertexBuffer::new(context),
+            directions: VertexBuffer::new(context),
+            sides_and_times: VertexBuffer::new(context),
+            index_buffer: ElementBuffer::new(context),
+            lifetime,
+            start_width: width,
+            end_width: 0.0,
+            start_color: Color::WHITE,
+            end_color: Color::new(255, 255, 255, 0),
+            min_distance: 0.01,
+        }
+    }
+
+    ///
+    /// Records the position of the followed object at the given time and removes the recorded positions that are older than the lifetime.
+    ///
+    pub fn push(&mut self, position: Vec3, time: f32) {
+        let replace_last = self.points.len() > 1
+            && self.points[self.points.len() - 2].0.distance(position) < self.min_distance;
+        if replace_last {
+            *self.points.back_mut().unwrap() = (position, time);
+        } else {
+            self.points.push_back((position, time));
+        }
+        self.time = time;
+        self.remove_old_points();
+        self.update_buffers();
+    }
+
+    ///
+    /// Removes all recorded positions.
+    ///
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.update_buffers();
+    }
+
+    ///
+    /// Returns the recorded positions and the time they were recorded, from the oldest to the newest.
+    ///
+    pub fn points(&self) -> impl Iterator<Item = &(Vec3, f32)> {
+        self.points.iter()
+    }
+
+    fn remove_old_points(&mut self) {
+        while self.points.len() > 1 && self.time - self.points[0].1 > self.lifetime {
+            self.points.pop_front();
+        }
+    }
+
+    fn update_buffers(&mut self) {
+        let mut positions = Vec::with_capacity(2 * self.points.len());
+        let mut directions = Vec::with_capacity(2 * self.points.len());
+        let mut sides_and_times = Vec::with_capacity(2 * self.points.len());
+        let count = self.points.len();
+        for (i, (position, time)) in self.points.iter().enumerate() {
+            let previous = self.points[i.saturating_sub(1)].0;
+            let next = self.points[(i + 1).min(count - 1)].0;
+            for side in [-1.0, 1.0] {
+                positions.push(*position);
+                directions.push(next - previous);
+                sides_and_times.push(vec2(side, *time));
+            }
+        }
+        let mut indices = Vec::with_capacity(6 * count.saturating_sub(1));
+        for i in 0..count.saturating_sub(1) as u32 {
+            let j = 2 * i;
+            indices.extend_from_slice(&[j, j + 1, j + 2, j + 2, j + 1, j + 3]);
+        }
+        self.positions.fill(&positions);
+        self.directions.fill(&directions);
+        self.sides_and_times.fill(&sides_and_times);
+        self.index_buffer.fill(&indices);
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform("eyePosition", *camera.position());
+        program.use_uniform("startWidth", self.start_width);
+        program.use_uniform("endWidth", self.end_width);
+        program.use_uniform("currentTime", self.time);
+        program.use_uniform("lifetime", self.lifetime.max(0.000001));
+        program.use_uniform_if_required("startColor", self.start_color);
+        program.use_uniform_if_required("endColor", self.end_color);
+        program.use_vertex_attribute("position", &self.positions);
+        program.use_vertex_attribute("direction", &self.directions);
+        program.use_vertex_attribute("side_time", &self.sides_and_times);
+        program.draw_elements(render_states, camera.viewport(), &self.index_buffer);
+    }
+}
+
+impl<'a> IntoIterator for &'a Trail {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Trail {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let aabb = AxisAlignedBoundingBox::new_with_positions(
+            &self.points.iter().map(|(p, _)| *p).collect::<Vec<_>>(),
+        );
+        if aabb.is_empty() {
+            return aabb;
+        }
+        // Expand with the width of the ribbon
+        let r = 0.5 * self.start_width.max(self.end_width);
+        AxisAlignedBoundingBox::new_with_positions(&[
+            aabb.min() - vec3(r, r, r),
+            aabb.max() + vec3(r, r, r),
+        ])
+    }
+
+    ///
+    /// Updates the current time used for fading out the ribbon, which is useful if the followed object has stopped and no new positions are pushed.
+    /// The time must be in the same unit as the time given to [Trail::push].
+    ///
+    fn animate(&mut self, time: f32) {
+        self.time = time;
+        let count = self.points.len();
+        self.remove_old_points();
+        if count != self.points.len() {
+            self.update_buffers();
+        }
+    }
+
+    fn triangle_count(&self) -> usize {
+        2 * self.points.len().saturating_sub(1)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if self.points.len() < 2 {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                include_str!("shaders/trail.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if self.points.len() < 2 {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                include_str!("shaders/trail.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+}
+
+impl Object for Trail {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.render_with_material(&TrailMaterial, camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+struct TrailMaterial;
+
+impl Material for TrailMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/trail.frag")
+            ),
+            attributes: FragmentAttributes {
+                color: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, _program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {}
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
@@ -0,0 +1,264 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::sync::Arc;
+
+///
+/// An [Object] which visualizes the scalar values in the red channel of a [Texture3D] by ray marching through the volume,
+/// for example for medical or scientific volume visualization of CT or MRI scans or simulation results.
+///
+/// Each sample along a ray is mapped to a color and opacity by the transfer function, a 1D lookup table where the first color is used for the lowest value in the [VolumeRaycaster::value_range]
+/// and the last color for the highest value. The samples are composited front to back and the ray is terminated early when the accumulated opacity reaches the [VolumeRaycaster::termination_threshold].
+/// The volume fills a box with the given size and center in origo, before the transformation is applied.
+///
+pub struct VolumeRaycaster {
+    context: Context,
+    mesh: Mesh,
+    transformation: Mat4,
+    size: Vec3,
+    /// The 3D texture containing the values in the red channel.
+    pub voxels: Arc<Texture3D>,
+    /// The transfer function which maps a value to a color and opacity, see [VolumeRaycaster::set_transfer_function].
+    pub transfer_function: Arc<Texture2D>,
+    /// The values mapped to the first and last color of the transfer function. Values outside the range are clamped.
+    pub value_range: (f32, f32),
+    /// A factor multiplied onto the opacity of each sample, where a lower value makes the volume more transparent.
+    pub opacity: f32,
+    /// The number of samples along the ray per voxel, where a higher value gives a more accurate image but is slower to render.
+    /// The opacity of each sample is corrected for the step size, so the overall opacity of the volume does not change with the number of samples.
+    pub samples_per_voxel: f32,
+    /// The maximum number of samples along each ray, which is limited to 4096.
+    pub max_steps: u32,
+    /// The accumulated opacity, in the range `[0..1]`, at which a ray is terminated since the samples behind are not visible.
+    pub termination_threshold: f32,
+}
+
+impl VolumeRaycaster {
+    ///
+    /// Creates a new volume ray caster which renders the given voxels in a box with the given size and center in origo.
+    /// The transfer function is given as equally spaced colors from the lowest to the highest value, where the alpha value of the colors is the opacity.
+    ///
+    pub fn new(
+        context: &Context,
+        voxels: Arc<Texture3D>,
+        size: Vec3,
+        transfer_function: &[Color],
+    ) -> Self {
+        let mut raycaster = Self {
+            context: context.clone(),
+            mesh: Mesh::new(context, &CpuMesh::cube()),
+            transformation: Mat4::identity(),
+            size,
+            voxels,
+            transfer_function: Arc::new(Self::transfer_function_texture(
+                context,
+                transfer_function,
+            )),
+            value_range: (0.0, 1.0),
+            opacity: 1.0,
+            samples_per_voxel: 1.0,
+            max_steps: 1024,
+            termination_threshold: 0.99,
+        };
+        raycaster.update_transformation();
+        raycaster
+    }
+
+    ///
+    /// Creates a new volume ray caster from the voxel data and size in the given [CpuVoxelGrid], see [VolumeRaycaster::new].
+    ///
+    pub fn from_cpu_voxel_grid(
+        context: &Context,
+        cpu_voxel_grid: &CpuVoxelGrid,
+        transfer_function: &[Color],
+    ) -> Self {
+        Self::new(
+            context,
+            Arc::new(Texture3D::new(context, &cpu_voxel_grid.voxels)),
+            cpu_voxel_grid.size,
+            transfer_function,
+        )
+    }
+
+    ///
+    /// Sets the transfer function given as equally spaced colors from the lowest to the highest value in the [VolumeRaycaster::value_range].
+    /// The alpha value of the colors is the opacity and the colors are linearly interpolated between the values.
+    ///
+    pub fn set_transfer_function(&mut self, colors: &[Color]) {
+        self.transfer_function = Arc::new(Self::transfer_function_texture(&self.context, colors));
+    }
+
+    ///
+    /// Returns the size of the box which the volume fills.
+    ///
+    pub fn size(&self) -> Vec3 {
+        self.size
+    }
+
+    ///
+    /// Sets the size of the box which the volume fills.
+    ///
+    pub fn set_size(&mut self, size: Vec3) {
+        self.size = size;
+        self.update_transformation();
+    }
+
+    ///
+    /// Returns the local to world transformation applied to the volume.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to the volume.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.update_transformation();
+    }
+
+    fn update_transformation(&mut self) {
+        self.mesh.set_transformation(
+            self.transformation
+                * Mat4::from_nonuniform_scale(
+                    0.5 * self.size.x,
+                    0.5 * self.size.y,
+                    0.5 * self.size.z,
+                ),
+        );
+    }
+
+    fn transfer_function_texture(context: &Context, colors: &[Color]) -> Texture2D {
+        let colors = if colors.is_empty() {
+            vec![Color::WHITE]
+        } else {
+            colors.to_vec()
+        };
+        Texture2D::new(
+            context,
+            &CpuTexture {
+                data: TextureData::RgbaU8(colors.iter().map(|c| [c.r, c.g, c.b, c.a]).collect()),
+                width: colors.len() as u32,
+                height: 1,
+                min_filter: Interpolation::Linear,
+                mag_filter: Interpolation::Linear,
+                mip_map_filter: None,
+                wrap_s: Wrapping::ClampToEdge,
+                wrap_t: Wrapping::ClampToEdge,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a VolumeRaycaster {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for VolumeRaycaster {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.mesh.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.mesh.triangle_count()
+    }
+}
+
+impl Object for VolumeRaycaster {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.render_with_material(&VolumeRaycasterMaterial { raycaster: self }, camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+struct VolumeRaycasterMaterial<'a> {
+    raycaster: &'a VolumeRaycaster,
+}
+
+impl Material for VolumeRaycasterMaterial<'_> {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/volume_raycaster.frag")
+            ),
+            attributes: FragmentAttributes {
+                position: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, camera: &Camera, _lights: &[&dyn Light]) {
+        let raycaster = self.raycaster;
+        let voxels = &raycaster.voxels;
+        // Maps from world space to texture coordinates in the range [0..1]
+        let texture_from_world = Mat4::from_translation(vec3(0.5, 0.5, 0.5))
+            * Mat4::from_scale(0.5)
+            * raycaster
+                .mesh
+                .transformation()
+                .invert()
+                .unwrap_or(Mat4::identity());
+        let voxels_per_side = voxels.width().max(voxels.height()).max(voxels.depth()) as f32;
+        let samples_per_voxel = raycaster.samples_per_voxel.max(0.01);
+        program.use_uniform("cameraPosition", camera.position());
+        program.use_uniform("textureFromWorld", texture_from_world);
+        program.use_texture_3d("voxels", voxels);
+        program.use_texture("transferFunction", &raycaster.transfer_function);
+        program.use_uniform(
+            "valueRange",
+            vec2(raycaster.value_range.0, raycaster.value_range.1),
+        );
+        program.use_uniform("opacity", raycaster.opacity);
+        program.use_uniform("stepSize", 1.0 / (voxels_per_side * samples_per_voxel));
+        program.use_uniform("opacityCorrection", 1.0 / samples_per_voxel);
+        program.use_uniform("maxSteps", raycaster.max_steps as i32);
+        program.use_uniform("terminationThreshold", raycaster.termination_threshold);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        // Renders the back faces of the box, so the volume is also visible when the camera is inside the box
+        RenderStates {
+            blend: Blend::TRANSPARENCY,
+            write_mask: WriteMask::COLOR,
+            cull: Cull::Front,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
@@ -75,6 +75,10 @@ impl<M: Material> Geometry for VoxelGrid<M> {
         self.0
             .render_with_post_material(material, camera, lights, color_texture, depth_texture)
     }
+
+    fn triangle_count(&self) -> usize {
+        self.0.triangle_count()
+    }
 }
 
 impl<M: Material> Object for VoxelGrid<M> {
@@ -85,4 +89,8 @@ impl<M: Material> Object for VoxelGrid<M> {
     fn material_type(&self) -> MaterialType {
         self.0.material_type()
     }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.0.material()
+    }
 }
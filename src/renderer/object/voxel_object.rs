@@ -0,0 +1,453 @@
+use crate::renderer::*;
+use std::collections::VecDeque;
+
+///
+/// A regular grid of voxels where each voxel is either empty or filled with a color, for example created by voxelizing a mesh using [OccupancyGrid::from_mesh].
+/// The voxel with index `(x, y, z)` covers the cube from `origin + voxel_size * (x, y, z)` to `origin + voxel_size * (x + 1, y + 1, z + 1)`.
+///
+#[derive(Clone, Debug)]
+pub struct OccupancyGrid {
+    /// The minimum corner of the grid.
+    pub origin: Vec3,
+    /// The size of the edges of each voxel.
+    pub voxel_size: f32,
+    resolution: (u32, u32, u32),
+    voxels: Vec<Option<Color>>,
+}
+
+impl OccupancyGrid {
+    ///
+    /// Creates an empty grid with the given number of voxels in each direction.
+    ///
+    pub fn new(resolution: (u32, u32, u32), origin: Vec3, voxel_size: f32) -> Self {
+        Self {
+            origin,
+            voxel_size,
+            resolution,
+            voxels: vec![None; (resolution.0 * resolution.1 * resolution.2) as usize],
+        }
+    }
+
+    ///
+    /// Voxelizes the surface of the given mesh, ie. fills all voxels that intersect a triangle of the mesh, using voxels of the given size.
+    /// The color of a voxel is the vertex color of the intersecting triangle or white if the mesh has no colors.
+    /// Use [OccupancyGrid::fill_interior] to also fill the voxels inside a closed mesh.
+    ///
+    pub fn from_mesh(cpu_mesh: &CpuMesh, voxel_size: f32) -> Self {
+        let positions = cpu_mesh.positions.to_f32();
+        let aabb = AxisAlignedBoundingBox::new_with_positions(&positions);
+        if aabb.is_empty() {
+            return Self::new((0, 0, 0), vec3(0.0, 0.0, 0.0), voxel_size);
+        }
+        let size = aabb.size() / voxel_size;
+        let resolution = (
+            (size.x.ceil() as u32).max(1),
+            (size.y.ceil() as u32).max(1),
+            (size.z.ceil() as u32).max(1),
+        );
+        // Center the mesh in the grid
+        let origin = aabb.center()
+            - 0.5
+                * voxel_size
+                * vec3(
+                    resolution.0 as f32,
+                    resolution.1 as f32,
+                    resolution.2 as f32,
+                );
+        let mut grid = Self::new(resolution, origin, voxel_size);
+
+        let indices: Vec<u32> = match &cpu_mesh.indices {
+            Indices::U8(ind) => ind.iter().map(|i| *i as u32).collect(),
+            Indices::U16(ind) => ind.iter().map(|i| *i as u32).collect(),
+            Indices::U32(ind) => ind.clone(),
+            Indices::None => (0..positions.len() as u32).collect(),
+        };
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| i as usize);
+            let vertices = [positions[a], positions[b], positions[c]];
+            let color = cpu_mesh
+                .colors
+                .as_ref()
+                .map(|colors| {
+                    let mix = |f: fn(&Color) -> u8| {
+                        ((f(&colors[a]) as u32 + f(&colors[b]) as u32 + f(&colors[c]) as u32) / 3)
+                            as u8
+                    };
+                    Color::new(mix(|c| c.r), mix(|c| c.g), mix(|c| c.b), mix(|c| c.a))
+                })
+                .unwrap_or(Color::WHITE);
+            let min = grid.voxel_index(AxisAlignedBoundingBox::new_with_positions(&vertices).min());
+            let max = grid.voxel_index(AxisAlignedBoundingBox::new_with_positions(&vertices).max());
+            for z in min.2..=max.2 {
+                for y in min.1..=max.1 {
+                    for x in min.0..=max.0 {
+                        let center = grid.origin
+                            + voxel_size * vec3(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
+                        if triangle_intersects_box(&vertices, center, 0.5 * voxel_size) {
+                            grid.set(x, y, z, Some(color));
+                        }
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    ///
+    /// Returns the number of voxels in each direction.
+    ///
+    pub fn resolution(&self) -> (u32, u32, u32) {
+        self.resolution
+    }
+
+    ///
+    /// Returns the color of the voxel with the given index or `None` if the voxel is empty or outside the grid.
+    ///
+    pub fn get(&self, x: u32, y: u32, z: u32) -> Option<Color> {
+        if x < self.resolution.0 && y < self.resolution.1 && z < self.resolution.2 {
+            self.voxels[self.index(x, y, z)]
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Fills the voxel with the given index with the given color or empties it if the color is `None`.
+    ///
+    pub fn set(&mut self, x: u32, y: u32, z: u32, color: Option<Color>) {
+        let index = self.index(x, y, z);
+        self.voxels[index] = color;
+    }
+
+    ///
+    /// Returns the number of filled voxels.
+    ///
+    pub fn filled_count(&self) -> usize {
+        self.voxels.iter().filter(|v| v.is_some()).count()
+    }
+
+    ///
+    /// Fills all empty voxels that are enclosed by filled voxels with the given color, for example to get a solid voxelization of a closed mesh.
+    ///
+    pub fn fill_interior(&mut self, color: Color) {
+        let (w, h, d) = self.resolution;
+        let mut outside = vec![false; self.voxels.len()];
+        let mut queue = VecDeque::new();
+        for z in 0..d {
+            for y in 0..h {
+                for x in 0..w {
+                    let boundary =
+                        x == 0 || y == 0 || z == 0 || x == w - 1 || y == h - 1 || z == d - 1;
+                    let index = self.index(x, y, z);
+                    if boundary && self.voxels[index].is_none() {
+                        outside[index] = true;
+                        queue.push_back((x, y, z));
+                    }
+                }
+            }
+        }
+        while let Some((x, y, z)) = queue.pop_front() {
+            for (dx, dy, dz) in [
+                (-1, 0, 0),
+                (1, 0, 0),
+                (0, -1, 0),
+                (0, 1, 0),
+                (0, 0, -1),
+                (0, 0, 1),
+            ] {
+                let (nx, ny, nz) = (x as i32 + dx, y as i32 + dy, z as i32 + dz);
+                if nx < 0 || ny < 0 || nz < 0 || nx >= w as i32 || ny >= h as i32 || nz >= d as i32
+                {
+                    continue;
+                }
+                let index = self.index(nx as u32, ny as u32, nz as u32);
+                if !outside[index] && self.voxels[index].is_none() {
+                    outside[index] = true;
+                    queue.push_back((nx as u32, ny as u32, nz as u32));
+                }
+            }
+        }
+        for (voxel, outside) in self.voxels.iter_mut().zip(outside) {
+            if voxel.is_none() && !outside {
+                *voxel = Some(color);
+            }
+        }
+    }
+
+    ///
+    /// Creates a mesh of the surface of the filled voxels with vertex colors, where neighbouring faces with the same color are merged into larger rectangles (greedy meshing).
+    ///
+    pub fn to_cpu_mesh(&self) -> CpuMesh {
+        let res = [self.resolution.0, self.resolution.1, self.resolution.2];
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+        for d in 0..3 {
+            let (u, v) = ((d + 1) % 3, (d + 2) % 3);
+            for positive in [false, true] {
+                let mut mask = vec![None; (res[u] * res[v]) as usize];
+                for slice in 0..res[d] {
+                    // Find the visible faces in this slice
+                    for j in 0..res[v] {
+                        for i in 0..res[u] {
+                            let mut p = [0; 3];
+                            p[d] = slice;
+                            p[u] = i;
+                            p[v] = j;
+                            let color = self.get(p[0], p[1], p[2]);
+                            let neighbour = if positive {
+                                p[d] + 1 < res[d]
+                            } else {
+                                p[d] > 0
+                            }
+                            .then(|| {
+                                let mut n = p;
+                                n[d] = if positive { n[d] + 1 } else { n[d] - 1 };
+                                self.get(n[0], n[1], n[2])
+                            })
+                            .flatten();
+                            mask[(j * res[u] + i) as usize] =
+                                if neighbour.is_none() { color } else { None };
+                        }
+                    }
+                    // Merge the faces with the same color into rectangles
+                    for j in 0..res[v] {
+                        let mut i = 0;
+                        while i < res[u] {
+                            let color = match mask[(j * res[u] + i) as usize] {
+                                Some(color) => color,
+                                None => {
+                                    i += 1;
+                                    continue;
+                                }
+                            };
+                            let mut width = 1;
+                            while i + width < res[u]
+                                && mask[(j * res[u] + i + width) as usize] == Some(color)
+                            {
+                                width += 1;
+                            }
+                            let mut height = 1;
+                            while j + height < res[v]
+                                && (0..width).all(|k| {
+                                    mask[((j + height) * res[u] + i + k) as usize] == Some(color)
+                                })
+                            {
+                                height += 1;
+                            }
+                            for l in 0..height {
+                                for k in 0..width {
+                                    mask[((j + l) * res[u] + i + k) as usize] = None;
+                                }
+                            }
+
+                            let mut corner = [0.0; 3];
+                            corner[d] = (slice + positive as u32) as f32;
+                            corner[u] = i as f32;
+                            corner[v] = j as f32;
+                            let corner = self.origin
+                                + self.voxel_size * vec3(corner[0], corner[1], corner[2]);
+                            let mut du = vec3(0.0, 0.0, 0.0);
+                            du[u] = width as f32 * self.voxel_size;
+                            let mut dv = vec3(0.0, 0.0, 0.0);
+                            dv[v] = height as f32 * self.voxel_size;
+                            let mut normal = vec3(0.0, 0.0, 0.0);
+                            normal[d] = if positive { 1.0 } else { -1.0 };
+
+                            let start = positions.len() as u32;
+                            positions.extend_from_slice(&[
+                                corner,
+                                corner + du,
+                                corner + du + dv,
+                                corner + dv,
+                            ]);
+                            normals.extend_from_slice(&[normal; 4]);
+                            colors.extend_from_slice(&[color; 4]);
+                            if positive {
+                                indices.extend_from_slice(&[
+                                    start,
+                                    start + 1,
+                                    start + 2,
+                                    start,
+                                    start + 2,
+                                    start + 3,
+                                ]);
+                            } else {
+                                indices.extend_from_slice(&[
+                                    start,
+                                    start + 2,
+                                    start + 1,
+                                    start,
+                                    start + 3,
+                                    start + 2,
+                                ]);
+                            }
+                            i += width;
+                        }
+                    }
+                }
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            colors: Some(colors),
+            ..Default::default()
+        }
+    }
+
+    fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.resolution.0 * (y + self.resolution.1 * z)) as usize
+    }
+
+    fn voxel_index(&self, position: Vec3) -> (u32, u32, u32) {
+        let p = (position - self.origin) / self.voxel_size;
+        let clamp = |v: f32, max: u32| (v.floor().max(0.0) as u32).min(max.saturating_sub(1));
+        (
+            clamp(p.x, self.resolution.0),
+            clamp(p.y, self.resolution.1),
+            clamp(p.z, self.resolution.2),
+        )
+    }
+}
+
+///
+/// Tests whether a triangle intersects an axis aligned cube with the given center and half size using the separating axis theorem.
+///
+fn triangle_intersects_box(triangle: &[Vec3; 3], center: Vec3, half_size: f32) -> bool {
+    let v = triangle.map(|p| p - center);
+    let edges = [v[1] - v[0], v[2] - v[1], v[0] - v[2]];
+    let box_axes = [
+        vec3(1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+    ];
+    let separated = |axis: Vec3| {
+        if axis.magnitude2() < 1.0e-12 {
+            return false;
+        }
+        let p = v.map(|v| v.dot(axis));
+        let r = half_size * (axis.x.abs() + axis.y.abs() + axis.z.abs());
+        p[0].min(p[1]).min(p[2]) > r || p[0].max(p[1]).max(p[2]) < -r
+    };
+    if box_axes.iter().any(|a| separated(*a)) || separated(edges[0].cross(edges[1])) {
+        return false;
+    }
+    !box_axes
+        .iter()
+        .any(|a| edges.iter().any(|e| separated(a.cross(*e))))
+}
+
+///
+/// An [Object] rendering the filled voxels of an [OccupancyGrid] as cubes with the voxel colors, for example for Minecraft-style rendering of voxelized models.
+/// The cubes are rendered efficiently as a single mesh where the hidden faces are removed and neighbouring faces with the same color are merged, see [OccupancyGrid::to_cpu_mesh].
+/// The material should use the vertex colors, for example [ColorMaterial] or [PhysicalMaterial].
+///
+pub struct VoxelObject<M: Material> {
+    context: Context,
+    gm: Gm<Mesh, M>,
+    grid: OccupancyGrid,
+}
+
+impl<M: Material> VoxelObject<M> {
+    ///
+    /// Creates a new voxel object rendering the given grid with the given material.
+    ///
+    pub fn new(context: &Context, grid: OccupancyGrid, material: M) -> Self {
+        Self {
+            context: context.clone(),
+            gm: Gm::new(Mesh::new(context, &grid.to_cpu_mesh()), material),
+            grid,
+        }
+    }
+
+    ///
+    /// Returns the rendered grid.
+    ///
+    pub fn grid(&self) -> &OccupancyGrid {
+        &self.grid
+    }
+
+    ///
+    /// Replaces the rendered grid, for example after editing the voxels.
+    ///
+    pub fn set_grid(&mut self, grid: OccupancyGrid) {
+        let transformation = self.gm.transformation();
+        self.gm.geometry = Mesh::new(&self.context, &grid.to_cpu_mesh());
+        self.gm.set_transformation(transformation);
+        self.grid = grid;
+    }
+}
+
+impl<'a, M: Material> IntoIterator for &'a VoxelObject<M> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<M: Material> std::ops::Deref for VoxelObject<M> {
+    type Target = Gm<Mesh, M>;
+    fn deref(&self) -> &Self::Target {
+        &self.gm
+    }
+}
+
+impl<M: Material> std::ops::DerefMut for VoxelObject<M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.gm
+    }
+}
+
+impl<M: Material> Geometry for VoxelObject<M> {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.gm.aabb()
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.gm.triangle_count()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.gm.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.gm
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+}
+
+impl<M: Material> Object for VoxelObject<M> {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.gm.render(camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.gm.material_type()
+    }
+
+    fn texture_memory(&self) -> usize {
+        self.gm.texture_memory()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.gm.material()
+    }
+}
@@ -215,7 +215,10 @@ impl WaterPatch {
             "offset",
             self.center + vec3(self.offset.x, 0.0, self.offset.y),
         );
-        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
         program.use_uniform("time", self.time * 0.001);
         program.use_uniform_array(
             "waveParameters",
@@ -302,4 +305,8 @@ impl Geometry for WaterPatch {
     fn animate(&mut self, time: f32) {
         self.time = time;
     }
+
+    fn triangle_count(&self) -> usize {
+        self.index_buffer.triangle_count()
+    }
 }
@@ -0,0 +1,113 @@
+use crate::{renderer::*, OrientedBoundingBox2D};
+
+///
+/// Assigns a geometry or an object, for example a [Gm], to the given layers, see [Geometry::layers].
+/// The wrapped geometry or object is used as before, except that it is only rendered in the render calls of render targets
+/// where at least one of the layers is visible, see [RenderTarget::with_visible_layers].
+///
+pub struct WithLayers<T: Geometry> {
+    /// The wrapped geometry or object
+    pub inner: T,
+    /// The layers the wrapped geometry or object belongs to
+    pub layers: RenderLayers,
+}
+
+impl<T: Geometry> WithLayers<T> {
+    ///
+    /// Assigns the given geometry or object to the given layers.
+    ///
+    pub fn new(inner: T, layers: RenderLayers) -> Self {
+        Self { inner, layers }
+    }
+}
+
+impl<'a, T: Object> IntoIterator for &'a WithLayers<T> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<T: Geometry> Geometry for WithLayers<T> {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.inner.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.inner
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.inner.aabb()
+    }
+
+    fn obb(&self) -> OrientedBoundingBox2D {
+        self.inner.obb()
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.inner.animate(time)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.inner.triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.layers
+    }
+}
+
+impl<T: Object> Object for WithLayers<T> {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.inner.render(camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.inner.material_type()
+    }
+
+    fn texture_memory(&self) -> usize {
+        self.inner.texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.inner.albedo()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.inner.render_queue()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.inner.material()
+    }
+}
+
+impl<T: Geometry> std::ops::Deref for WithLayers<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: Geometry> std::ops::DerefMut for WithLayers<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{renderer::*, OrientedBoundingBox2D};
+
+///
+/// Places an object, for example a [Gm], in the given render queue, see [Object::render_queue].
+/// The wrapped object is used as before, except that it is sorted by the given render queue in a render call.
+///
+pub struct WithRenderQueue<T: Object> {
+    /// The wrapped object
+    pub inner: T,
+    /// The render queue of the wrapped object
+    pub render_queue: RenderQueue,
+}
+
+impl<T: Object> WithRenderQueue<T> {
+    ///
+    /// Places the given object in the given render queue.
+    ///
+    pub fn new(inner: T, render_queue: RenderQueue) -> Self {
+        Self {
+            inner,
+            render_queue,
+        }
+    }
+}
+
+impl<'a, T: Object> IntoIterator for &'a WithRenderQueue<T> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<T: Object> Geometry for WithRenderQueue<T> {
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        self.inner.render_with_material(material, camera, lights)
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.inner
+            .render_with_post_material(material, camera, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.inner.aabb()
+    }
+
+    fn obb(&self) -> OrientedBoundingBox2D {
+        self.inner.obb()
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.inner.animate(time)
+    }
+
+    fn triangle_count(&self) -> usize {
+        self.inner.triangle_count()
+    }
+
+    fn layers(&self) -> RenderLayers {
+        self.inner.layers()
+    }
+}
+
+impl<T: Object> Object for WithRenderQueue<T> {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        self.inner.render(camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.inner.material_type()
+    }
+
+    fn texture_memory(&self) -> usize {
+        self.inner.texture_memory()
+    }
+
+    fn albedo(&self) -> Color {
+        self.inner.albedo()
+    }
+
+    fn render_queue(&self) -> RenderQueue {
+        self.render_queue
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.inner.material()
+    }
+}
+
+impl<T: Object> std::ops::Deref for WithRenderQueue<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: Object> std::ops::DerefMut for WithRenderQueue<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
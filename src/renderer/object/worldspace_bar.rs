@@ -0,0 +1,271 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A single bar rendered by a [WorldspaceBar].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct BarInstance {
+    /// The position in world space which the bar is anchored to, for example the top of the object that the bar displays the status of.
+    pub position: Vec3,
+    /// The fill level of the bar between 0 (empty) and 1 (full).
+    pub value: f32,
+    /// The color of the filled part of the bar.
+    pub color: Color,
+}
+
+impl Default for BarInstance {
+    fn default() -> Self {
+        Self {
+            position: vec3(0.0, 0.0, 0.0),
+            value: 1.0,
+            color: Color::GREEN,
+        }
+    }
+}
+
+///
+/// An overlay [Object] which renders filled bars anchored to positions in world space, for example health bars or progress bars above objects in a scene.
+/// The bars are always facing the camera and have a constant size in pixels regardless of the distance to the camera.
+/// All bars are rendered in a single draw call.
+///
+pub struct WorldspaceBar {
+    context: Context,
+    corners: VertexBuffer,
+    position_buffer: InstanceBuffer,
+    value_buffer: InstanceBuffer,
+    color_buffer: InstanceBuffer,
+    bars: Vec<BarInstance>,
+    aabb: AxisAlignedBoundingBox,
+    /// The size of the bars in physical pixels.
+    pub size: Vec2,
+    /// The offset in physical pixels from the projected anchor position to the center of the bars, for example to place the bars above the anchor.
+    pub offset: Vec2,
+    /// The width of the border in physical pixels.
+    pub border_width: f32,
+    /// The color of the unfilled part of the bars.
+    pub background_color: Color,
+    /// The color of the border of the bars.
+    pub border_color: Color,
+    /// Whether or not the bars are hidden behind other objects. If false, the bars are always visible.
+    pub depth_test: bool,
+}
+
+impl WorldspaceBar {
+    ///
+    /// Creates a new worldspace bar object rendering the given bars.
+    ///
+    pub fn new(context: &Context, bars: &[BarInstance]) -> Self {
+        let mut worldspace_bar = Self {
+            context: context.clone(),
+            corners: VertexBuffer::new_with_data(
+                context,
+                &[
+                    vec2(0.0, 0.0),
+                    vec2(1.0, 0.0),
+                    vec2(1.0, 1.0),
+                    vec2(1.0, 1.0),
+                    vec2(0.0, 1.0),
+                    vec2(0.0, 0.0),
+                ],
+            ),
+            position_buffer: InstanceBuffer::new(context),
+            value_buffer: InstanceBuffer::new(context),
+            color_buffer: InstanceBuffer::new(context),
+            bars: Vec::new(),
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            size: vec2(60.0, 8.0),
+            offset: vec2(0.0, 20.0),
+            border_width: 1.0,
+            background_color: Color::new(40, 40, 40, 200),
+            border_color: Color::BLACK,
+            depth_test: true,
+        };
+        worldspace_bar.set_bars(bars);
+        worldspace_bar
+    }
+
+    ///
+    /// Returns the bars rendered by this object.
+    ///
+    pub fn bars(&self) -> &[BarInstance] {
+        &self.bars
+    }
+
+    ///
+    /// Replaces the bars rendered by this object, for example to update the fill level or to follow moving objects.
+    ///
+    pub fn set_bars(&mut self, bars: &[BarInstance]) {
+        let positions = bars.iter().map(|b| b.position).collect::<Vec<_>>();
+        self.position_buffer.fill(&positions);
+        self.value_buffer.fill(
+            &bars
+                .iter()
+                .map(|b| b.value.clamp(0.0, 1.0))
+                .collect::<Vec<_>>(),
+        );
+        self.color_buffer
+            .fill(&bars.iter().map(|b| b.color).collect::<Vec<_>>());
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(&positions);
+        self.bars = bars.to_vec();
+    }
+}
+
+impl<'a> IntoIterator for &'a WorldspaceBar {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for WorldspaceBar {
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+
+    fn triangle_count(&self) -> usize {
+        2 * self.bars.len()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        camera: &Camera,
+        lights: &[&dyn Light],
+    ) {
+        if self.bars.is_empty() {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights);
+        self.context
+            .program(
+                include_str!("shaders/worldspace_bar.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+
+    fn render_with_post_material(
+        &self,
+        material: &dyn PostMaterial,
+        camera: &Camera,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if self.bars.is_empty() {
+            return;
+        }
+        let fragment_shader = material.fragment_shader(lights, color_texture, depth_texture);
+        self.context
+            .program(
+                include_str!("shaders/worldspace_bar.vert").to_owned(),
+                fragment_shader.source,
+                |program| {
+                    material.use_uniforms(program, camera, lights, color_texture, depth_texture);
+                    self.draw(program, material.render_states(), camera);
+                },
+            )
+            .expect("Failed compiling shader");
+    }
+}
+
+impl WorldspaceBar {
+    fn draw(&self, program: &Program, render_states: RenderStates, camera: &Camera) {
+        let viewport = camera.viewport();
+        program.use_uniform(
+            "viewProjection",
+            camera.render_view_projection(program.context()),
+        );
+        program.use_uniform(
+            "viewportSize",
+            vec2(viewport.width as f32, viewport.height as f32),
+        );
+        program.use_uniform("barSize", self.size);
+        program.use_uniform("barOffset", self.offset);
+        program.use_vertex_attribute("corner", &self.corners);
+        program.use_instance_attribute("instance_position", &self.position_buffer);
+        // The value and color are optimized out if the material does not use them
+        for (name, buffer) in [
+            ("instance_value", &self.value_buffer),
+            ("instance_color", &self.color_buffer),
+        ] {
+            if program.requires_attribute(name) {
+                program.use_instance_attribute(name, buffer);
+            }
+        }
+        program.draw_arrays_instanced(render_states, viewport, 6, self.bars.len() as u32);
+    }
+}
+
+impl Object for WorldspaceBar {
+    fn render(&self, camera: &Camera, lights: &[&dyn Light]) {
+        let material = BarMaterial {
+            size: self.size,
+            border_width: self.border_width,
+            background_color: self.background_color,
+            border_color: self.border_color,
+            depth_test: self.depth_test,
+        };
+        self.render_with_material(&material, camera, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+struct BarMaterial {
+    size: Vec2,
+    border_width: f32,
+    background_color: Color,
+    border_color: Color,
+    depth_test: bool,
+}
+
+impl Material for BarMaterial {
+    fn fragment_shader(&self, _lights: &[&dyn Light]) -> FragmentShader {
+        FragmentShader {
+            source: format!(
+                "{}{}",
+                include_str!("../../core/shared.frag"),
+                include_str!("shaders/worldspace_bar.frag")
+            ),
+            attributes: FragmentAttributes {
+                uv: true,
+                color: true,
+                ..FragmentAttributes::NONE
+            },
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _camera: &Camera, _lights: &[&dyn Light]) {
+        program.use_uniform("barSize", self.size);
+        program.use_uniform("borderWidth", self.border_width);
+        program.use_uniform("backgroundColor", self.background_color);
+        program.use_uniform("borderColor", self.border_color);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: if self.depth_test {
+                DepthTest::LessOrEqual
+            } else {
+                DepthTest::Always
+            },
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
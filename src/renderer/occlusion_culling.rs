@@ -0,0 +1,152 @@
+//!
+//! Occlusion culling, ie. skipping objects which are hidden behind other objects, using occlusion queries on the bounding boxes of the objects.
+//!
+
+use crate::renderer::*;
+
+struct ObjectOcclusion {
+    query: OcclusionQuery,
+    pending: bool,
+    visible: bool,
+}
+
+///
+/// Keeps track of which objects are hidden behind other objects between frames, see [RenderTarget::render_with_occlusion_culling].
+///
+/// After the visible objects are rendered, the bounding box of each object inside the view frustum is tested against the depth of the rendered scene using an [OcclusionQuery].
+/// The result is used to decide whether to render the object in one of the next frames, so an object which becomes visible may appear one or a few frames late.
+/// This is worth it when the scene contains heavy objects, for example detailed models inside buildings, which are often hidden behind simple occluders, for example walls.
+///
+/// The objects are identified by their index in the list of objects, so the same objects must be given in the same order each frame.
+///
+pub struct OcclusionCulling {
+    context: Context,
+    proxy: Mesh,
+    objects: Vec<Option<ObjectOcclusion>>,
+}
+
+impl OcclusionCulling {
+    ///
+    /// Creates a new occlusion culling state where all objects are visible.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            proxy: Mesh::new(context, &CpuMesh::cube()),
+            objects: Vec::new(),
+        }
+    }
+
+    ///
+    /// Returns whether or not the object with the given index was visible in the latest result of its occlusion query.
+    /// Objects without any result are visible.
+    ///
+    pub fn is_visible(&self, index: usize) -> bool {
+        self.objects
+            .get(index)
+            .and_then(|o| o.as_ref())
+            .map(|o| o.visible)
+            .unwrap_or(true)
+    }
+
+    ///
+    /// Returns the number of objects which are hidden behind other objects and therefore not rendered.
+    ///
+    pub fn hidden_count(&self) -> usize {
+        self.objects
+            .iter()
+            .filter(|o| o.as_ref().map(|o| !o.visible).unwrap_or(false))
+            .count()
+    }
+
+    ///
+    /// Forgets all results, so all objects are visible until new results are available, for example when the objects are replaced.
+    ///
+    pub fn reset(&mut self) {
+        self.objects.clear();
+    }
+
+    // Updates the visibility with the results which are available and returns whether each object should be rendered
+    pub(crate) fn update(
+        &mut self,
+        camera: &Camera,
+        aabbs: &[AxisAlignedBoundingBox],
+    ) -> Vec<bool> {
+        self.objects.resize_with(aabbs.len(), || None);
+        aabbs
+            .iter()
+            .zip(self.objects.iter_mut())
+            .map(|(aabb, object)| {
+                if !camera.in_frustum(aabb) {
+                    return false;
+                }
+                if let Some(object) = object {
+                    if object.pending {
+                        if let Some(visible) = object.query.result() {
+                            object.visible = visible;
+                            object.pending = false;
+                        }
+                    }
+                    // The bounding box is clipped by the near plane when the camera is inside it, so the query result cannot be trusted
+                    object.visible || contains(aabb, camera.position())
+                } else {
+                    true
+                }
+            })
+            .collect()
+    }
+
+    // Renders the bounding boxes of the objects inside the view frustum which do not have a pending query, testing them against the current depth.
+    // Must be called in the callback given as input to a render target write method after rendering the visible objects.
+    pub(crate) fn test(&mut self, camera: &Camera, aabbs: &[AxisAlignedBoundingBox]) {
+        let material = ColorMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::NONE,
+                cull: Cull::None,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for (aabb, object) in aabbs.iter().zip(self.objects.iter_mut()) {
+            let size = aabb.size();
+            // Objects with an infinite bounding box, for example a skybox, are always rendered
+            if aabb.is_empty()
+                || !camera.in_frustum(aabb)
+                || !(size.x.is_finite() && size.y.is_finite() && size.z.is_finite())
+            {
+                continue;
+            }
+            let object = object.get_or_insert_with(|| ObjectOcclusion {
+                query: OcclusionQuery::new(&self.context),
+                pending: false,
+                visible: true,
+            });
+            if object.pending {
+                continue;
+            }
+            self.proxy.set_transformation(
+                Mat4::from_translation(aabb.center())
+                    * Mat4::from_nonuniform_scale(
+                        0.5 * size.x.max(0.0001),
+                        0.5 * size.y.max(0.0001),
+                        0.5 * size.z.max(0.0001),
+                    ),
+            );
+            object.query.begin();
+            self.proxy.render_with_material(&material, camera, &[]);
+            object.query.end();
+            object.pending = true;
+        }
+    }
+}
+
+fn contains(aabb: &AxisAlignedBoundingBox, point: Vec3) -> bool {
+    let min = aabb.min();
+    let max = aabb.max();
+    point.x >= min.x
+        && point.y >= min.y
+        && point.z >= min.z
+        && point.x <= max.x
+        && point.y <= max.y
+        && point.z <= max.z
+}
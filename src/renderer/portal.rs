@@ -0,0 +1,359 @@
+//!
+//! Portal based visibility, ie. rooms connected by portals, for culling objects that cannot be seen in interior scenes.
+//!
+
+use crate::renderer::*;
+
+///
+/// A plane defined by a unit normal and a distance, the inside of the plane is where `normal.dot(p) + distance >= 0`.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    /// The unit normal of the plane, pointing to the inside.
+    pub normal: Vec3,
+    /// The signed distance from the origin to the plane along the normal.
+    pub distance: f32,
+}
+
+impl Plane {
+    ///
+    /// Creates a plane with the given normal going through the given point.
+    ///
+    pub fn from_point_and_normal(point: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            distance: -normal.dot(point),
+        }
+    }
+
+    ///
+    /// Returns the signed distance from the plane to the point, positive values means that the point is inside.
+    ///
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+
+    fn from_vec4(v: Vec4) -> Self {
+        let length = v.truncate().magnitude();
+        Self {
+            normal: v.truncate() / length,
+            distance: v.w / length,
+        }
+    }
+
+    fn is_aabb_outside(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        let min = aabb.min();
+        let max = aabb.max();
+        let p = vec3(
+            if self.normal.x >= 0.0 { max.x } else { min.x },
+            if self.normal.y >= 0.0 { max.y } else { min.y },
+            if self.normal.z >= 0.0 { max.z } else { min.z },
+        );
+        self.signed_distance(p) < 0.0
+    }
+}
+
+///
+/// Returns the six planes of the view frustum of the camera.
+///
+pub fn frustum_planes(camera: &Camera) -> [Plane; 6] {
+    let m = camera.projection() * camera.view();
+    let row = |i: usize| vec4(m.x[i], m.y[i], m.z[i], m.w[i]);
+    [
+        Plane::from_vec4(row(3) + row(0)),
+        Plane::from_vec4(row(3) - row(0)),
+        Plane::from_vec4(row(3) + row(1)),
+        Plane::from_vec4(row(3) - row(1)),
+        Plane::from_vec4(row(3) + row(2)),
+        Plane::from_vec4(row(3) - row(2)),
+    ]
+}
+
+///
+/// A portal connecting two rooms in a [PortalGraph].
+///
+#[derive(Clone, Debug)]
+pub struct Portal {
+    /// The indices of the two rooms connected by this portal.
+    pub rooms: (usize, usize),
+    /// The corners of the convex and planar polygon defining the opening of the portal.
+    pub polygon: Vec<Vec3>,
+}
+
+///
+/// A room visible from the camera, see [PortalGraph::visible_rooms].
+///
+#[derive(Clone, Debug)]
+pub struct VisibleRoom {
+    /// The index of the room.
+    pub room: usize,
+    /// The planes which defines the part of the room that is visible through the chain of portals from the camera.
+    pub planes: Vec<Plane>,
+    /// The part of the viewport in which the room is visible.
+    /// This can be used as the scissor box in for example [RenderTarget::render_partially] to render the view through a portal.
+    pub scissor_box: ScissorBox,
+    /// The index of the portal through which the room is seen or `None` if the camera is inside the room.
+    pub portal: Option<usize>,
+    /// The number of portals between the camera and the room.
+    pub depth: usize,
+}
+
+impl VisibleRoom {
+    ///
+    /// Returns whether or not the given bounding box is inside the visible part of this room.
+    ///
+    pub fn is_visible(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        !self.planes.iter().any(|plane| plane.is_aabb_outside(aabb))
+    }
+}
+
+///
+/// A set of convex rooms, defined by axis aligned bounding boxes, connected by portals.
+/// Use [PortalGraph::visible_rooms] to find the rooms visible from a camera, by traversing the portals visible from the camera,
+/// and [PortalGraph::cull] to remove the objects that are not visible.
+///
+#[derive(Clone, Debug)]
+pub struct PortalGraph {
+    rooms: Vec<AxisAlignedBoundingBox>,
+    portals: Vec<Portal>,
+    /// The maximum number of portals traversed from the room containing the camera.
+    pub max_depth: usize,
+}
+
+impl Default for PortalGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PortalGraph {
+    ///
+    /// Creates a new empty portal graph.
+    ///
+    pub fn new() -> Self {
+        Self {
+            rooms: Vec::new(),
+            portals: Vec::new(),
+            max_depth: 8,
+        }
+    }
+
+    ///
+    /// Adds a room with the given volume and returns the index of the room.
+    ///
+    pub fn add_room(&mut self, volume: AxisAlignedBoundingBox) -> usize {
+        self.rooms.push(volume);
+        self.rooms.len() - 1
+    }
+
+    ///
+    /// Adds a portal between the two rooms with the given indices and returns the index of the portal.
+    /// The polygon must be convex and planar.
+    ///
+    pub fn add_portal(&mut self, room0: usize, room1: usize, polygon: Vec<Vec3>) -> usize {
+        self.portals.push(Portal {
+            rooms: (room0, room1),
+            polygon,
+        });
+        self.portals.len() - 1
+    }
+
+    ///
+    /// Returns the rooms.
+    ///
+    pub fn rooms(&self) -> &[AxisAlignedBoundingBox] {
+        &self.rooms
+    }
+
+    ///
+    /// Returns the portals.
+    ///
+    pub fn portals(&self) -> &[Portal] {
+        &self.portals
+    }
+
+    ///
+    /// Returns the index of the room containing the given position, if any.
+    ///
+    pub fn room_at(&self, position: Vec3) -> Option<usize> {
+        self.rooms.iter().position(|room| {
+            let min = room.min();
+            let max = room.max();
+            position.x >= min.x
+                && position.x <= max.x
+                && position.y >= min.y
+                && position.y <= max.y
+                && position.z >= min.z
+                && position.z <= max.z
+        })
+    }
+
+    ///
+    /// Returns the rooms visible from the camera, starting with the room containing the camera and then the rooms seen through portals.
+    /// A room can be visible several times through different portals.
+    /// Returns `None` if the camera is not inside any of the rooms.
+    ///
+    pub fn visible_rooms(&self, camera: &Camera) -> Option<Vec<VisibleRoom>> {
+        let room = self.room_at(*camera.position())?;
+        let mut visible_rooms = vec![VisibleRoom {
+            room,
+            planes: frustum_planes(camera).to_vec(),
+            scissor_box: camera.viewport().into(),
+            portal: None,
+            depth: 0,
+        }];
+        let mut i = 0;
+        while i < visible_rooms.len() {
+            let current = visible_rooms[i].clone();
+            i += 1;
+            if current.depth >= self.max_depth {
+                continue;
+            }
+            for (index, portal) in self.portals.iter().enumerate() {
+                if current.portal == Some(index) {
+                    continue;
+                }
+                let next_room = if portal.rooms.0 == current.room {
+                    portal.rooms.1
+                } else if portal.rooms.1 == current.room {
+                    portal.rooms.0
+                } else {
+                    continue;
+                };
+                if let Some(next) = self.look_through(camera, &current, index, next_room) {
+                    visible_rooms.push(next);
+                }
+            }
+        }
+        Some(visible_rooms)
+    }
+
+    ///
+    /// Returns the objects which are visible from the camera through the portals.
+    /// If the camera is not inside any of the rooms, the objects inside the camera frustum are returned.
+    ///
+    pub fn cull<T: Geometry>(
+        &self,
+        camera: &Camera,
+        objects: impl IntoIterator<Item = T>,
+    ) -> Vec<T> {
+        let visible_rooms = self.visible_rooms(camera);
+        objects
+            .into_iter()
+            .filter(|object| {
+                let aabb = object.aabb();
+                if let Some(visible_rooms) = &visible_rooms {
+                    visible_rooms.iter().any(|visible_room| {
+                        overlaps(&self.rooms[visible_room.room], &aabb)
+                            && visible_room.is_visible(&aabb)
+                    })
+                } else {
+                    camera.in_frustum(&aabb)
+                }
+            })
+            .collect()
+    }
+
+    fn look_through(
+        &self,
+        camera: &Camera,
+        from: &VisibleRoom,
+        portal: usize,
+        room: usize,
+    ) -> Option<VisibleRoom> {
+        let eye = *camera.position();
+        let mut polygon = self.portals[portal].polygon.clone();
+        for plane in from.planes.iter() {
+            polygon = clip_polygon(&polygon, plane);
+            if polygon.len() < 3 {
+                return None;
+            }
+        }
+        let center = polygon.iter().fold(Vec3::zero(), |acc, p| acc + *p) / polygon.len() as f32;
+
+        // The portal plane, oriented away from the camera, so everything between the camera and the portal is removed
+        let normal = (polygon[1] - polygon[0]).cross(polygon[2] - polygon[0]);
+        let mut portal_plane = Plane::from_point_and_normal(center, normal);
+        if portal_plane.signed_distance(eye) > 0.0 {
+            portal_plane = Plane::from_point_and_normal(center, -normal);
+        }
+
+        let mut planes = vec![portal_plane];
+        for j in 0..polygon.len() {
+            let a = polygon[j];
+            let b = polygon[(j + 1) % polygon.len()];
+            let normal = (a - eye).cross(b - eye);
+            if normal.magnitude2() < 0.000001 {
+                continue;
+            }
+            let mut plane = Plane::from_point_and_normal(eye, normal);
+            if plane.signed_distance(center) < 0.0 {
+                plane = Plane::from_point_and_normal(eye, -normal);
+            }
+            planes.push(plane);
+        }
+        // Keep the far plane of the camera frustum
+        planes.push(from.planes[from.planes.len() - 1]);
+
+        Some(VisibleRoom {
+            room,
+            planes,
+            scissor_box: scissor_box(camera, &polygon).intersection(from.scissor_box),
+            portal: Some(portal),
+            depth: from.depth + 1,
+        })
+    }
+}
+
+fn overlaps(a: &AxisAlignedBoundingBox, b: &AxisAlignedBoundingBox) -> bool {
+    let (a_min, a_max, b_min, b_max) = (a.min(), a.max(), b.min(), b.max());
+    a_min.x <= b_max.x
+        && a_max.x >= b_min.x
+        && a_min.y <= b_max.y
+        && a_max.y >= b_min.y
+        && a_min.z <= b_max.z
+        && a_max.z >= b_min.z
+}
+
+fn clip_polygon(polygon: &[Vec3], plane: &Plane) -> Vec<Vec3> {
+    let mut result = Vec::new();
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let da = plane.signed_distance(a);
+        let db = plane.signed_distance(b);
+        if da >= 0.0 {
+            result.push(a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            result.push(a + (b - a) * (da / (da - db)));
+        }
+    }
+    result
+}
+
+fn scissor_box(camera: &Camera, polygon: &[Vec3]) -> ScissorBox {
+    let viewport = camera.viewport();
+    let view_projection = camera.projection() * camera.view();
+    let mut min = vec2(f32::MAX, f32::MAX);
+    let mut max = vec2(f32::MIN, f32::MIN);
+    for p in polygon {
+        let clip = view_projection * p.extend(1.0);
+        let ndc = vec2(clip.x, clip.y) / clip.w.max(0.000001);
+        let pixel = vec2(
+            viewport.x as f32 + (0.5 * ndc.x + 0.5) * viewport.width as f32,
+            viewport.y as f32 + (0.5 * ndc.y + 0.5) * viewport.height as f32,
+        );
+        min = vec2(min.x.min(pixel.x), min.y.min(pixel.y));
+        max = vec2(max.x.max(pixel.x), max.y.max(pixel.y));
+    }
+    let x = min.x.floor() as i32;
+    let y = min.y.floor() as i32;
+    ScissorBox {
+        x,
+        y,
+        width: (max.x.ceil() as i32 - x).max(0) as u32,
+        height: (max.y.ceil() as i32 - y).max(0) as u32,
+    }
+}
@@ -0,0 +1,370 @@
+//!
+//! Progressive refinement of still images, where the scene is rendered with low quality while the camera moves and then rendered again and again with increasing quality
+//! and accumulated when the camera is still, for example to get crisp images in an editor without slowing down the interaction.
+//!
+
+use crate::renderer::*;
+use three_d_asset::ProjectionType;
+
+///
+/// The quality settings used when rendering a [RefinementPass].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RefinementQuality {
+    /// The number of samples used for the ambient occlusion, see [AmbientOcclusionEffect::samples].
+    pub ambient_occlusion_samples: u32,
+    /// The size of the shadow maps, see for example [DirectionalLight::generate_shadow_map].
+    pub shadow_texture_size: u32,
+}
+
+///
+/// A render pass requested by [ProgressiveRefinement::next_pass].
+/// Render the scene with the given camera and quality using [ProgressiveRefinement::render].
+///
+#[derive(Clone, Debug)]
+pub struct RefinementPass {
+    /// The index of the sample, which is zero for the first pass after the camera has changed.
+    pub sample: u32,
+    /// The quality settings which should be used for this pass.
+    pub quality: RefinementQuality,
+    /// Whether or not the quality settings are different from the previous pass, for example when the shadow maps need to be generated again.
+    pub quality_changed: bool,
+    /// The camera to render with, which is slightly offset by less than a pixel for all but the first sample to anti-alias the accumulated image.
+    pub camera: Camera,
+}
+
+///
+/// Renders a still image with increasing quality.
+///
+/// Each frame, call [ProgressiveRefinement::next_pass] with the current camera.
+/// When the camera has changed, a pass with [ProgressiveRefinement::interactive_quality] is requested immediately.
+/// When the camera has been still for [ProgressiveRefinement::idle_delay] milliseconds, passes with [ProgressiveRefinement::refined_quality] and a sub-pixel offset are requested
+/// at most every [ProgressiveRefinement::min_interval] milliseconds until [ProgressiveRefinement::max_samples] are accumulated.
+/// Render each requested pass with [ProgressiveRefinement::render] and show the accumulated image with [ProgressiveRefinement::apply].
+/// Changes to the scene which are not caused by the camera are not detected, so call [ProgressiveRefinement::reset] in that case.
+///
+pub struct ProgressiveRefinement {
+    /// The time in milliseconds that the camera must be still before the refinement starts.
+    pub idle_delay: f64,
+    /// The minimum time in milliseconds between two refinement passes, which limits the time spent on refinement each frame.
+    pub min_interval: f64,
+    /// The maximum number of samples that are accumulated.
+    pub max_samples: u32,
+    /// The quality settings used while the camera moves.
+    pub interactive_quality: RefinementQuality,
+    /// The quality settings used for the refinement passes.
+    pub refined_quality: RefinementQuality,
+    color_texture: Option<Texture2D>,
+    depth_texture: Option<DepthTexture2D>,
+    accumulation: Vec<Texture2D>,
+    current: usize,
+    samples: u32,
+    idle_time: f64,
+    time_since_pass: f64,
+    previous_camera: Option<(Mat4, Mat4, Viewport)>,
+    previous_quality: Option<RefinementQuality>,
+}
+
+impl Default for ProgressiveRefinement {
+    fn default() -> Self {
+        Self {
+            idle_delay: 200.0,
+            min_interval: 0.0,
+            max_samples: 64,
+            interactive_quality: RefinementQuality {
+                ambient_occlusion_samples: 8,
+                shadow_texture_size: 1024,
+            },
+            refined_quality: RefinementQuality {
+                ambient_occlusion_samples: 32,
+                shadow_texture_size: 4096,
+            },
+            color_texture: None,
+            depth_texture: None,
+            accumulation: Vec::new(),
+            current: 0,
+            samples: 0,
+            idle_time: 0.0,
+            time_since_pass: 0.0,
+            previous_camera: None,
+            previous_quality: None,
+        }
+    }
+}
+
+impl ProgressiveRefinement {
+    ///
+    /// Creates a new progressive refinement with default settings.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Discards the accumulated samples, so the next call to [ProgressiveRefinement::next_pass] requests a new first pass.
+    /// Call this when the scene has changed.
+    ///
+    pub fn reset(&mut self) {
+        self.previous_camera = None;
+    }
+
+    ///
+    /// Returns the number of samples accumulated since the camera or scene last changed.
+    ///
+    pub fn sample_count(&self) -> u32 {
+        self.samples
+    }
+
+    ///
+    /// Returns whether or not more refinement passes will be requested if the camera and scene stays the same,
+    /// in which case [ProgressiveRefinement::next_pass] must be called continuously, ie. the application should not wait for the next event before rendering the next frame.
+    ///
+    pub fn is_refining(&self) -> bool {
+        self.samples < self.max_samples.max(1)
+    }
+
+    ///
+    /// Returns the pass that should be rendered this frame, if any, given the current camera and the time in milliseconds since the last frame,
+    /// for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time).
+    ///
+    pub fn next_pass(&mut self, camera: &Camera, elapsed_time: f64) -> Option<RefinementPass> {
+        let current_camera = (camera.view(), camera.projection(), camera.viewport());
+        if self.previous_camera != Some(current_camera) {
+            self.previous_camera = Some(current_camera);
+            self.samples = 0;
+            self.idle_time = 0.0;
+            self.time_since_pass = 0.0;
+            return Some(self.pass(camera, self.interactive_quality));
+        }
+        self.idle_time += elapsed_time;
+        self.time_since_pass += elapsed_time;
+        if !self.is_refining()
+            || self.idle_time < self.idle_delay
+            || self.time_since_pass < self.min_interval
+        {
+            return None;
+        }
+        self.time_since_pass = 0.0;
+        Some(self.pass(camera, self.refined_quality))
+    }
+
+    ///
+    /// Renders the given pass and accumulates the result.
+    /// The `render` closure should clear the given render target and render the scene with the camera in the pass.
+    /// If an ambient occlusion effect is given, it is computed from the rendered depth with the number of samples in the pass quality and applied to the rendered image.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(
+        &mut self,
+        context: &Context,
+        pass: &RefinementPass,
+        ambient_occlusion: Option<&mut AmbientOcclusionEffect>,
+        render: impl FnOnce(&RenderTarget),
+    ) {
+        let viewport = pass.camera.viewport();
+        self.resize(context, viewport.width, viewport.height);
+        let color_texture = self.color_texture.as_mut().unwrap();
+        let depth_texture = self.depth_texture.as_mut().unwrap();
+        render(&RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        ));
+        if let Some(ambient_occlusion) = ambient_occlusion {
+            let samples = ambient_occlusion.samples;
+            ambient_occlusion.samples = pass.quality.ambient_occlusion_samples;
+            ambient_occlusion.compute(
+                context,
+                &pass.camera,
+                DepthTexture::Single(self.depth_texture.as_ref().unwrap()),
+            );
+            ambient_occlusion.samples = samples;
+            self.color_texture
+                .as_mut()
+                .unwrap()
+                .as_color_target(None)
+                .write(|| ambient_occlusion.apply(context, &pass.camera));
+        }
+
+        let (first, second) = self.accumulation.split_at_mut(1);
+        let (target, history) = if self.current == 0 {
+            (&mut second[0], &first[0])
+        } else {
+            (&mut first[0], &second[0])
+        };
+        let color_texture = self.color_texture.as_ref().unwrap();
+        let weight = 1.0 / (pass.sample as f32 + 1.0);
+        target.as_color_target(None).write(|| {
+            apply_effect(
+                context,
+                include_str!("shaders/progressive_accumulate.frag"),
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTest::Always,
+                    cull: Cull::Back,
+                    ..Default::default()
+                },
+                viewport,
+                |program| {
+                    program.use_texture("sampleTexture", color_texture);
+                    program.use_texture("historyTexture", history);
+                    program.use_uniform("weight", weight);
+                },
+            );
+        });
+        self.current = 1 - self.current;
+        self.samples = pass.sample + 1;
+    }
+
+    ///
+    /// Copies the accumulated image to the given viewport of the render target.
+    /// Does nothing if no pass has been rendered.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn apply(&self, context: &Context, viewport: Viewport) {
+        if let Some(texture) = self.texture() {
+            let texture = ColorTexture::Single(texture);
+            apply_effect(
+                context,
+                &format!(
+                    "{}\n{}",
+                    texture.fragment_shader_source(),
+                    include_str!("shaders/progressive_apply.frag")
+                ),
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTest::Always,
+                    cull: Cull::Back,
+                    ..Default::default()
+                },
+                viewport,
+                |program| {
+                    texture.use_uniforms(program);
+                },
+            )
+        }
+    }
+
+    ///
+    /// Returns the texture containing the accumulated image, if any pass has been rendered.
+    ///
+    pub fn texture(&self) -> Option<&Texture2D> {
+        if self.samples > 0 {
+            self.accumulation.get(self.current)
+        } else {
+            None
+        }
+    }
+
+    fn pass(&mut self, camera: &Camera, quality: RefinementQuality) -> RefinementPass {
+        let sample = self.samples;
+        let quality_changed = self.previous_quality != Some(quality);
+        self.previous_quality = Some(quality);
+        let viewport = camera.viewport();
+        let mut camera = camera.clone();
+        camera.set_viewport(Viewport::new_at_origin(viewport.width, viewport.height));
+        if sample > 0 {
+            let jitter = vec2(halton(sample, 2) - 0.5, halton(sample, 3) - 0.5);
+            jitter_camera(&mut camera, jitter);
+        }
+        RefinementPass {
+            sample,
+            quality,
+            quality_changed,
+            camera,
+        }
+    }
+
+    fn resize(&mut self, context: &Context, width: u32, height: u32) {
+        if self
+            .color_texture
+            .as_ref()
+            .map(|t| t.width() == width && t.height() == height)
+            .unwrap_or(false)
+        {
+            return;
+        }
+        self.color_texture = Some(Texture2D::new_empty::<[u8; 4]>(
+            context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        ));
+        self.depth_texture = Some(DepthTexture2D::new::<f32>(
+            context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        ));
+        let precision =
+            context.select_render_precision("progressive accumulation", ColorPrecision::F32, false);
+        self.accumulation = (0..2)
+            .map(|_| match precision {
+                ColorPrecision::F32 => new_accumulation_texture::<[f32; 4]>(context, width, height),
+                ColorPrecision::F16 => new_accumulation_texture::<[f16; 4]>(context, width, height),
+                ColorPrecision::U8 => new_accumulation_texture::<[u8; 4]>(context, width, height),
+            })
+            .collect();
+    }
+}
+
+///
+/// Offsets the image seen by the camera by the given number of pixels.
+/// A perspective camera is rotated, which offsets all points by approximately the same number of pixels regardless of their distance to the camera.
+///
+fn jitter_camera(camera: &mut Camera, jitter: Vec2) {
+    let viewport = camera.viewport();
+    let position = *camera.position();
+    let target = *camera.target();
+    let up = *camera.up();
+    let right = camera.right_direction();
+    let camera_up = right.cross(camera.view_direction());
+    let pixel_size = match camera.projection_type() {
+        ProjectionType::Perspective { field_of_view_y } => {
+            2.0 * (0.5 * field_of_view_y.0).tan() * target.distance(position)
+                / viewport.height as f32
+        }
+        ProjectionType::Orthographic { height } => *height / viewport.height as f32,
+        #[allow(unreachable_patterns)]
+        _ => return,
+    };
+    let offset = (right * jitter.x + camera_up * jitter.y) * pixel_size;
+    if let ProjectionType::Orthographic { .. } = camera.projection_type() {
+        camera.set_view(position + offset, target + offset, up);
+    } else {
+        camera.set_view(position, target + offset, up);
+    }
+}
+
+fn halton(index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut f = 1.0;
+    let mut i = index;
+    while i > 0 {
+        f /= base as f32;
+        result += f * (i % base) as f32;
+        i /= base;
+    }
+    result
+}
+
+fn new_accumulation_texture<T: TextureDataType>(
+    context: &Context,
+    width: u32,
+    height: u32,
+) -> Texture2D {
+    Texture2D::new_empty::<T>(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
@@ -0,0 +1,185 @@
+//!
+//! Recording of the rendered frames, for example to turn a demo into a video.
+//!
+
+use crate::renderer::*;
+use std::collections::VecDeque;
+
+///
+/// A frame captured by a [FrameRecorder].
+///
+#[derive(Clone, Debug)]
+pub struct RecordedFrame {
+    /// The number of the frame, starting at zero for the first captured frame.
+    pub index: u32,
+    /// The width of the frame in pixels.
+    pub width: u32,
+    /// The height of the frame in pixels.
+    pub height: u32,
+    /// The RGBA colors of the pixels from the top row to the bottom row.
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl From<RecordedFrame> for CpuTexture {
+    fn from(frame: RecordedFrame) -> Self {
+        CpuTexture {
+            data: TextureData::RgbaU8(frame.pixels),
+            width: frame.width,
+            height: frame.height,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Captures the content of the screen or an offscreen render target each frame and delivers the frames to a callback, for example to turn a demo into a video.
+/// Call [FrameRecorder::capture] after the frame has been rendered and before the buffers are swapped.
+///
+/// By default, the pixels are read immediately, which stalls the pipeline until the frame is rendered.
+/// With [FrameRecorder::new_async], the pixels are copied into pixel buffers on the GPU and delivered when the copy is finished, usually one or two frames later,
+/// which avoids the stall. Call [FrameRecorder::flush] when the recording is done to deliver the remaining frames.
+///
+pub struct FrameRecorder {
+    context: Context,
+    callback: Box<dyn FnMut(RecordedFrame)>,
+    asynchronous: bool,
+    pending: VecDeque<(u32, PixelReadback<[u8; 4]>)>,
+    available: Vec<crate::context::Buffer>,
+    frame_count: u32,
+}
+
+// The number of frames which can be in flight before the recorder waits for the oldest frame
+const MAX_PENDING_FRAMES: usize = 3;
+
+impl FrameRecorder {
+    ///
+    /// Creates a new recorder which reads the pixels immediately when a frame is captured and calls the callback with the frame.
+    ///
+    pub fn new(context: &Context, callback: impl FnMut(RecordedFrame) + 'static) -> Self {
+        Self {
+            context: context.clone(),
+            callback: Box::new(callback),
+            asynchronous: false,
+            pending: VecDeque::new(),
+            available: Vec::new(),
+            frame_count: 0,
+        }
+    }
+
+    ///
+    /// Creates a new recorder which reads the pixels asynchronously using pixel buffers and calls the callback with each frame when it is available,
+    /// in the same order as the frames are captured.
+    ///
+    pub fn new_async(context: &Context, callback: impl FnMut(RecordedFrame) + 'static) -> Self {
+        Self {
+            asynchronous: true,
+            ..Self::new(context, callback)
+        }
+    }
+
+    ///
+    /// Creates a new recorder which saves each frame as a PNG file named `frame-00000.png`, `frame-00001.png` etc. in the given directory.
+    /// The image sequence can be turned into a video with for example `ffmpeg -framerate 60 -i frame-%05d.png video.mp4`.
+    ///
+    /// # Panic
+    /// Will panic if a frame cannot be saved, for example if the directory does not exist.
+    ///
+    #[cfg(all(feature = "image", not(target_arch = "wasm32")))]
+    pub fn png_sequence(
+        context: &Context,
+        directory: impl AsRef<std::path::Path>,
+        asynchronous: bool,
+    ) -> Self {
+        let directory = directory.as_ref().to_path_buf();
+        let callback = move |frame: RecordedFrame| {
+            let path = directory.join(format!("frame-{:05}.png", frame.index));
+            let bytes: Vec<u8> = frame.pixels.iter().flatten().copied().collect();
+            image::save_buffer(
+                &path,
+                &bytes,
+                frame.width,
+                frame.height,
+                image::ColorType::Rgba8,
+            )
+            .unwrap_or_else(|e| panic!("failed saving the frame to {}: {}", path.display(), e));
+        };
+        if asynchronous {
+            Self::new_async(context, callback)
+        } else {
+            Self::new(context, callback)
+        }
+    }
+
+    ///
+    /// Captures the color of the given render target, for example the screen, see [RenderTarget::screen].
+    /// When recording asynchronously, the frames which have finished copying since the last call are delivered to the callback.
+    ///
+    pub fn capture(&mut self, target: &RenderTarget) {
+        let index = self.frame_count;
+        self.frame_count += 1;
+        if !self.asynchronous {
+            (self.callback)(RecordedFrame {
+                index,
+                width: target.width(),
+                height: target.height(),
+                pixels: target.read_color(),
+            });
+            return;
+        }
+
+        // The pixel buffers of the delivered frames are reused
+        let readback =
+            target.read_color_async_with_buffer(target.scissor_box(), self.available.pop());
+        self.pending.push_back((index, readback));
+        while self
+            .pending
+            .front()
+            .map(|(_, readback)| readback.is_ready())
+            .unwrap_or(false)
+            || self.pending.len() > MAX_PENDING_FRAMES
+        {
+            self.deliver_oldest();
+        }
+    }
+
+    ///
+    /// Delivers all frames which are still being copied to the callback, waiting for the copies to finish.
+    ///
+    pub fn flush(&mut self) {
+        while !self.pending.is_empty() {
+            self.deliver_oldest();
+        }
+    }
+
+    ///
+    /// Returns the number of captured frames.
+    ///
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    fn deliver_oldest(&mut self) {
+        if let Some((index, readback)) = self.pending.pop_front() {
+            let (width, height) = (readback.width(), readback.height());
+            let (pixels, buffer) = readback.read_and_take_buffer();
+            self.available.push(buffer);
+            (self.callback)(RecordedFrame {
+                index,
+                width,
+                height,
+                pixels,
+            });
+        }
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        self.flush();
+        for buffer in self.available.drain(..) {
+            unsafe {
+                self.context.delete_buffer(buffer);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+
+uniform float objectId;
+uniform vec4 albedo;
+uniform mat4 view;
+uniform mat4 viewProjection;
+uniform mat4 previousViewProjection;
+uniform mat4 motion;
+uniform vec2 resolution;
+
+#ifdef USE_POSITION
+in vec3 pos;
+#endif
+#ifdef USE_NORMAL
+in vec3 nor;
+#endif
+
+#ifdef USE_OBJECT_ID
+layout (location = OBJECT_ID_LOCATION) out vec4 outObjectId;
+#endif
+#ifdef USE_NORMAL
+layout (location = NORMAL_LOCATION) out vec4 outNormal;
+#endif
+#ifdef USE_DEPTH
+layout (location = DEPTH_LOCATION) out vec4 outDepth;
+#endif
+#ifdef USE_MOTION_VECTOR
+layout (location = MOTION_VECTOR_LOCATION) out vec4 outMotionVector;
+#endif
+#ifdef USE_ALBEDO
+layout (location = ALBEDO_LOCATION) out vec4 outAlbedo;
+#endif
+
+void main()
+{
+#ifdef USE_OBJECT_ID
+    outObjectId = vec4(objectId, 0.0, 0.0, 1.0);
+#endif
+#ifdef USE_NORMAL
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    outNormal = vec4(normal, 1.0);
+#endif
+#ifdef USE_DEPTH
+    outDepth = vec4(-(view * vec4(pos, 1.0)).z, gl_FragCoord.z, 0.0, 1.0);
+#endif
+#ifdef USE_MOTION_VECTOR
+    vec4 current = viewProjection * vec4(pos, 1.0);
+    vec4 previous = previousViewProjection * motion * vec4(pos, 1.0);
+    vec2 velocity = 0.5 * (current.xy / current.w - previous.xy / previous.w) * resolution;
+    outMotionVector = vec4(velocity, 0.0, 1.0);
+#endif
+#ifdef USE_ALBEDO
+    outAlbedo = albedo;
+#endif
+}
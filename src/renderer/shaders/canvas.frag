@@ -0,0 +1,23 @@
+
+in vec4 col;
+
+#ifdef USE_TEXTURE
+uniform sampler2D tex;
+in vec2 uvs;
+#endif
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The colors are given in the sRGB color space, so they are shown unchanged unless linear output is enabled
+    outColor = vec4(rgb_from_srgb(col.rgb), col.a);
+#ifdef USE_TEXTURE
+    vec4 tex_color = texture(tex, uvs);
+#ifndef TEXTURE_SRGB
+    tex_color.rgb = rgb_from_srgb(tex_color.rgb);
+#endif
+    outColor *= tex_color;
+#endif
+    outColor.rgb = srgb_from_rgb(outColor.rgb);
+}
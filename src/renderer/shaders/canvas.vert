@@ -0,0 +1,19 @@
+uniform mat4 viewProjection;
+
+in vec2 position;
+in vec4 color;
+out vec4 col;
+
+#ifdef USE_TEXTURE
+in vec2 uv;
+out vec2 uvs;
+#endif
+
+void main()
+{
+    col = color;
+#ifdef USE_TEXTURE
+    uvs = uv;
+#endif
+    gl_Position = viewProjection * vec4(position, 0.0, 1.0);
+}
@@ -0,0 +1,40 @@
+
+#if defined(VIEW_NORMALS)
+in vec3 nor;
+#elif defined(VIEW_TANGENTS)
+in vec3 tang;
+#elif defined(VIEW_UVS)
+in vec2 uvs;
+#elif defined(VIEW_CHECKERBOARD)
+in vec2 uvs;
+uniform float squares;
+uniform vec4 color0;
+uniform vec4 color1;
+#else
+in vec4 col;
+#endif
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+#if defined(VIEW_NORMALS)
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    outColor = vec4(0.5 + 0.5 * normal, 1.0);
+#elif defined(VIEW_TANGENTS)
+    vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
+    outColor = vec4(0.5 + 0.5 * tangent, 1.0);
+#elif defined(VIEW_UVS)
+    outColor = vec4(fract(uvs), 0.0, 1.0);
+#elif defined(VIEW_CHECKERBOARD)
+    vec2 cell = floor(uvs * squares);
+    vec4 color = mod(cell.x + cell.y, 2.0) < 0.5 ? color0 : color1;
+    // Tint the uv coordinates outside [0..1] so wrapping and flipped coordinates are easy to spot
+    if(any(lessThan(uvs, vec2(0.0))) || any(greaterThan(uvs, vec2(1.0)))) {
+        color.rgb = mix(color.rgb, vec3(1.0, 0.0, 1.0), 0.5);
+    }
+    outColor = vec4(srgb_from_rgb(color.rgb), 1.0);
+#else
+    outColor = vec4(srgb_from_rgb(col.rgb), 1.0);
+#endif
+}
@@ -0,0 +1,14 @@
+
+uniform sampler2D sampleTexture;
+uniform sampler2D historyTexture;
+uniform float weight;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 color = texture(sampleTexture, uvs);
+    outColor = weight >= 1.0 ? color : mix(texture(historyTexture, uvs), color, weight);
+}
@@ -0,0 +1,9 @@
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = sample_color(uvs);
+}
@@ -0,0 +1,14 @@
+
+uniform sampler2D leftTexture;
+uniform sampler2D rightTexture;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 left = texture(leftTexture, uvs);
+    vec4 right = texture(rightTexture, uvs);
+    outColor = vec4(left.r, right.g, right.b, max(left.a, right.a));
+}
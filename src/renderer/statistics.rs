@@ -0,0 +1,119 @@
+//!
+//! Functionality for reporting the complexity of a scene, for example to show users why their content is slow or to enforce budgets.
+//!
+
+use crate::renderer::*;
+
+///
+/// The rendering cost of a single object, see [SceneStatistics::objects].
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ObjectStatistics {
+    /// The index of the object in the list of objects given to [SceneStatistics::analyze].
+    pub index: usize,
+    /// The number of triangles drawn when rendering the object, including all instances.
+    pub triangle_count: usize,
+    /// The number of bytes of GPU memory used by the textures applied to the object.
+    pub texture_memory: usize,
+    /// The type of material applied to the object.
+    pub material_type: MaterialType,
+}
+
+///
+/// A report of the complexity of a set of objects and lights, see [SceneStatistics::analyze].
+///
+#[derive(Clone, Debug, Default)]
+pub struct SceneStatistics {
+    /// The total number of triangles drawn when rendering all objects.
+    pub triangle_count: usize,
+    /// The total number of bytes of GPU memory used by the textures applied to the objects.
+    /// A texture shared between several objects is counted once for each object.
+    pub texture_memory: usize,
+    /// The number of objects with an opaque forward material.
+    pub opaque_count: usize,
+    /// The number of objects with a transparent forward material.
+    pub transparent_count: usize,
+    /// The number of objects with a deferred material.
+    pub deferred_count: usize,
+    /// The number of different materials applied to the objects, see [Object::material].
+    /// Materials are identified by their address in memory and each object which does not expose its material is counted as having a different material.
+    pub material_count: usize,
+    /// The number of different shader programs needed for rendering the objects, ie. the number of different fragment shaders of the materials given the lights.
+    /// Each object which does not expose its material is counted as needing a different program.
+    pub program_count: usize,
+    /// The number of shader programs compiled by the context so far, including programs which are not used by the objects.
+    pub compiled_program_count: usize,
+    /// The number of lights.
+    pub light_count: usize,
+    /// The cost of each object, sorted from the most to the least expensive to render.
+    pub objects: Vec<ObjectStatistics>,
+}
+
+impl SceneStatistics {
+    ///
+    /// Analyzes the given objects and lights and returns a report of the triangle count, texture memory, the number of materials and shader programs,
+    /// the number of objects of each material type, the light count and the cost of each of the objects.
+    /// Use [Scene::analyze] to analyze a [Scene].
+    /// The objects are sorted by triangle count and then by texture memory, so the largest objects are found using [SceneStatistics::largest_objects].
+    ///
+    pub fn analyze(
+        context: &Context,
+        objects: impl IntoIterator<Item = impl Object>,
+        lights: &[&dyn Light],
+    ) -> Self {
+        let mut statistics = Self {
+            compiled_program_count: context.program_count(),
+            light_count: lights.len(),
+            ..Default::default()
+        };
+        let mut materials = std::collections::HashSet::new();
+        let mut fragment_shaders = std::collections::HashSet::new();
+        for (index, object) in objects.into_iter().enumerate() {
+            if let Some(material) = object.material() {
+                if materials.insert(material as *const dyn Material as *const ()) {
+                    statistics.material_count += 1;
+                }
+                if fragment_shaders.insert(material.fragment_shader(lights).source) {
+                    statistics.program_count += 1;
+                }
+            } else {
+                statistics.material_count += 1;
+                statistics.program_count += 1;
+            }
+            let object_statistics = ObjectStatistics {
+                index,
+                triangle_count: object.triangle_count(),
+                texture_memory: object.texture_memory(),
+                material_type: object.material_type(),
+            };
+            statistics.triangle_count += object_statistics.triangle_count;
+            statistics.texture_memory += object_statistics.texture_memory;
+            match object_statistics.material_type {
+                MaterialType::Opaque => statistics.opaque_count += 1,
+                MaterialType::Transparent => statistics.transparent_count += 1,
+                MaterialType::Deferred => statistics.deferred_count += 1,
+            }
+            statistics.objects.push(object_statistics);
+        }
+        statistics.objects.sort_by(|a, b| {
+            b.triangle_count
+                .cmp(&a.triangle_count)
+                .then(b.texture_memory.cmp(&a.texture_memory))
+        });
+        statistics
+    }
+
+    ///
+    /// Returns the total number of objects.
+    ///
+    pub fn object_count(&self) -> usize {
+        self.objects.len()
+    }
+
+    ///
+    /// Returns the (at most) `count` most expensive objects to render.
+    ///
+    pub fn largest_objects(&self, count: usize) -> &[ObjectStatistics] {
+        &self.objects[..count.min(self.objects.len())]
+    }
+}
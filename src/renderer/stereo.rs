@@ -0,0 +1,265 @@
+//!
+//! Stereoscopic rendering, where the scene is rendered once for each eye with slightly different cameras,
+//! for example for side-by-side VR headsets, red-cyan anaglyph glasses or as input to an XR runtime.
+//!
+
+use crate::renderer::*;
+use three_d_asset::ProjectionType;
+
+///
+/// One of the two eyes in stereoscopic rendering.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Eye {
+    /// The left eye.
+    Left,
+    /// The right eye.
+    Right,
+}
+
+impl Eye {
+    ///
+    /// Returns the left and right eye.
+    ///
+    pub fn both() -> [Eye; 2] {
+        [Eye::Left, Eye::Right]
+    }
+
+    fn sign(&self) -> f32 {
+        match self {
+            Eye::Left => -1.0,
+            Eye::Right => 1.0,
+        }
+    }
+}
+
+///
+/// Defines how the images for the two eyes are combined by [StereoRenderer::render].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StereoLayout {
+    /// The left eye is rendered to the left half of the viewport and the right eye to the right half, for example for a VR headset or a 3D TV.
+    SideBySide,
+    /// The red channel of the left eye is combined with the green and blue channels of the right eye, to be viewed with red-cyan glasses.
+    Anaglyph,
+}
+
+///
+/// Renders a scene for both eyes.
+///
+/// The eye cameras are the given camera moved half the [StereoRenderer::interpupillary_distance] to each side, looking in the same direction.
+/// The projection of each eye is offset horizontally so that objects at the [StereoRenderer::convergence_distance] appear at the same position for both eyes, ie. they appear to be at the screen,
+/// while objects further away appear behind the screen and objects closer to the camera appear in front of the screen.
+///
+pub struct StereoRenderer {
+    /// The distance between the eyes in world units. The default is 0.064 which is the average distance in meters.
+    pub interpupillary_distance: f32,
+    /// The distance from the camera to the plane which appears at the screen. If `None`, the distance from the camera position to the camera target is used.
+    pub convergence_distance: Option<f32>,
+    /// How the images for the two eyes are combined.
+    pub layout: StereoLayout,
+    eye_textures: Vec<Texture2D>,
+    depth_texture: Option<DepthTexture2D>,
+}
+
+impl Default for StereoRenderer {
+    fn default() -> Self {
+        Self::new(StereoLayout::SideBySide)
+    }
+}
+
+impl StereoRenderer {
+    ///
+    /// Creates a new stereo renderer with the given layout and the default interpupillary distance.
+    ///
+    pub fn new(layout: StereoLayout) -> Self {
+        Self {
+            interpupillary_distance: 0.064,
+            convergence_distance: None,
+            layout,
+            eye_textures: Vec::new(),
+            depth_texture: None,
+        }
+    }
+
+    ///
+    /// Returns the camera for the given eye when rendering to the viewport of the given camera and the scissor box which the eye should be rendered into.
+    /// The viewport of the eye camera is larger than the viewport of the given camera and offset horizontally, which offsets the projection,
+    /// so make sure to only render inside the returned scissor box, for example using [RenderTarget::render_partially].
+    /// Use this to render the eyes into separate render targets, for example the layers of a [Texture2DArray] used by an XR runtime, see also [StereoRenderer::render_to_layers].
+    ///
+    pub fn eye_camera(&self, camera: &Camera, eye: Eye) -> (Camera, ScissorBox) {
+        let viewport = camera.viewport();
+        let mut eye_camera = camera.clone();
+        let offset = camera.right_direction() * 0.5 * self.interpupillary_distance * eye.sign();
+        eye_camera.translate(&offset);
+
+        let shift = self.projection_shift(camera).round() as i32;
+        eye_camera.set_viewport(Viewport {
+            x: viewport.x - shift.abs() + shift * eye.sign() as i32,
+            y: viewport.y,
+            width: viewport.width + 2 * shift.unsigned_abs(),
+            height: viewport.height,
+        });
+        (eye_camera, viewport.into())
+    }
+
+    ///
+    /// Renders both eyes into the given render target, where the [StereoLayout::SideBySide] layout uses the viewport of the given camera for both eyes
+    /// and the [StereoLayout::Anaglyph] layout combines the eyes in the viewport of the given camera.
+    /// The `render` closure is called once for each eye and should render the scene into the given render target with the given camera inside the given scissor box,
+    /// for example using [RenderTarget::render_partially]. The render target is not cleared.
+    /// Must not be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
+    ///
+    pub fn render(
+        &mut self,
+        context: &Context,
+        target: &RenderTarget,
+        camera: &Camera,
+        mut render: impl FnMut(Eye, &RenderTarget, &Camera, ScissorBox),
+    ) {
+        let viewport = camera.viewport();
+        match self.layout {
+            StereoLayout::SideBySide => {
+                let width = viewport.width / 2;
+                for eye in Eye::both() {
+                    let mut half_camera = camera.clone();
+                    half_camera.set_viewport(Viewport {
+                        x: viewport.x + if eye == Eye::Left { 0 } else { width as i32 },
+                        y: viewport.y,
+                        width,
+                        height: viewport.height,
+                    });
+                    let (eye_camera, scissor_box) = self.eye_camera(&half_camera, eye);
+                    render(eye, target, &eye_camera, scissor_box);
+                }
+            }
+            StereoLayout::Anaglyph => {
+                self.resize(context, viewport.width, viewport.height);
+                let mut origin_camera = camera.clone();
+                origin_camera
+                    .set_viewport(Viewport::new_at_origin(viewport.width, viewport.height));
+                for (i, eye) in Eye::both().into_iter().enumerate() {
+                    let (eye_camera, scissor_box) = self.eye_camera(&origin_camera, eye);
+                    let eye_target = RenderTarget::new(
+                        self.eye_textures[i].as_color_target(None),
+                        self.depth_texture.as_mut().unwrap().as_depth_target(),
+                    );
+                    eye_target.clear(ClearState::default());
+                    render(eye, &eye_target, &eye_camera, scissor_box);
+                }
+                let (left, right) = (&self.eye_textures[0], &self.eye_textures[1]);
+                target.write(|| {
+                    apply_effect(
+                        context,
+                        include_str!("shaders/stereo_anaglyph.frag"),
+                        RenderStates {
+                            write_mask: WriteMask::COLOR,
+                            depth_test: DepthTest::Always,
+                            cull: Cull::Back,
+                            ..Default::default()
+                        },
+                        viewport,
+                        |program| {
+                            program.use_texture("leftTexture", left);
+                            program.use_texture("rightTexture", right);
+                        },
+                    );
+                });
+            }
+        }
+    }
+
+    ///
+    /// Renders each eye into a separate layer of the given textures, the left eye into layer 0 and the right eye into layer 1, for example to submit the images to an XR runtime.
+    /// The layers are not cleared and the textures must have at least two layers.
+    /// The `render` closure is called once for each eye, see [StereoRenderer::render].
+    ///
+    pub fn render_to_layers(
+        &self,
+        color_texture: &mut Texture2DArray,
+        depth_texture: &mut DepthTexture2DArray,
+        camera: &Camera,
+        mut render: impl FnMut(Eye, &RenderTarget, &Camera, ScissorBox),
+    ) {
+        let mut layer_camera = camera.clone();
+        layer_camera.set_viewport(Viewport::new_at_origin(
+            color_texture.width(),
+            color_texture.height(),
+        ));
+        for (layer, eye) in Eye::both().into_iter().enumerate() {
+            let (eye_camera, scissor_box) = self.eye_camera(&layer_camera, eye);
+            let layers = [layer as u32];
+            let target = RenderTarget::new(
+                color_texture.as_color_target(&layers, None),
+                depth_texture.as_depth_target(layer as u32),
+            );
+            render(eye, &target, &eye_camera, scissor_box);
+        }
+    }
+
+    ///
+    /// The horizontal shift in pixels of the projection of each eye, which makes objects at the convergence distance appear at the same position for both eyes.
+    ///
+    fn projection_shift(&self, camera: &Camera) -> f32 {
+        let half_distance = 0.5 * self.interpupillary_distance;
+        let height = camera.viewport().height as f32;
+        match camera.projection_type() {
+            ProjectionType::Perspective { field_of_view_y } => {
+                let distance = self
+                    .convergence_distance
+                    .unwrap_or_else(|| camera.position().distance(*camera.target()));
+                let visible_height = 2.0 * distance * (0.5 * field_of_view_y.0).tan();
+                if visible_height > 0.0 {
+                    half_distance * height / visible_height
+                } else {
+                    0.0
+                }
+            }
+            ProjectionType::Orthographic {
+                height: visible_height,
+            } => {
+                if *visible_height > 0.0 {
+                    half_distance * height / visible_height
+                } else {
+                    0.0
+                }
+            }
+            #[allow(unreachable_patterns)]
+            _ => 0.0,
+        }
+    }
+
+    fn resize(&mut self, context: &Context, width: u32, height: u32) {
+        if self
+            .depth_texture
+            .as_ref()
+            .map(|t| t.width() == width && t.height() == height)
+            .unwrap_or(false)
+        {
+            return;
+        }
+        self.eye_textures = Eye::both()
+            .iter()
+            .map(|_| {
+                Texture2D::new_empty::<[u8; 4]>(
+                    context,
+                    width,
+                    height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                )
+            })
+            .collect();
+        self.depth_texture = Some(DepthTexture2D::new::<f32>(
+            context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        ));
+    }
+}
@@ -18,3 +18,9 @@ pub use winit_window::*;
 mod headless;
 #[cfg(all(feature = "headless", not(target_arch = "wasm32")))]
 pub use headless::*;
+
+#[cfg(all(feature = "headless", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(feature = "headless"))]
+mod snapshot;
+#[cfg(all(feature = "headless", not(target_arch = "wasm32")))]
+pub use snapshot::*;
@@ -1,4 +1,7 @@
-use crate::{Context, CoreError};
+use crate::{
+    Context, CoreError, CpuTexture, DepthTexture2D, Interpolation, RenderTarget, Texture2D,
+    TextureData, Wrapping,
+};
 use glutin_029::{
     dpi::PhysicalSize, event_loop::EventLoop, ContextBuilder, ContextCurrentState, CreationError,
     NotCurrent, PossiblyCurrent,
@@ -18,6 +21,10 @@ pub enum HeadlessError {
     GlutinContextError(#[from] glutin_029::ContextError),
     #[error("error in three-d")]
     ThreeDError(#[from] CoreError),
+    #[error("error when rendering")]
+    RendererError(#[from] crate::renderer::RendererError),
+    #[error("error when loading assets")]
+    AssetError(#[from] three_d_asset::Error),
 }
 
 ///
@@ -25,10 +32,20 @@ pub enum HeadlessError {
 /// For a graphics context associated with a window, see [WindowedContext](crate::WindowedContext).
 /// Can only be created on native, not on web.
 ///
+/// On Linux, the context is preferably created on an EGL device without any display server, which makes it possible to render in for example continuous integration or on a server.
+/// Render to a texture, for example using [HeadlessContext::render_to_cpu_texture], since there is no screen to render to.
+///
 #[derive(Clone)]
 pub struct HeadlessContext {
     context: Context,
-    _glutin_context: Rc<glutin_029::Context<PossiblyCurrent>>,
+    _glutin_context: Rc<GlutinContext>,
+}
+
+#[allow(dead_code)]
+enum GlutinContext {
+    Glutin029(glutin_029::Context<PossiblyCurrent>),
+    #[cfg(target_os = "linux")]
+    EglDevice(glutin::api::egl::context::PossiblyCurrentContext),
 }
 
 impl HeadlessContext {
@@ -37,19 +54,115 @@ impl HeadlessContext {
     ///
     #[allow(unsafe_code)]
     pub fn new() -> Result<Self, HeadlessError> {
+        #[cfg(target_os = "linux")]
+        if let Some(context) = Self::new_egl_device() {
+            return Ok(context);
+        }
+
         let cb = ContextBuilder::new();
         let (glutin_context, _el) = build_context(cb)?;
         let glutin_context = unsafe { glutin_context.make_current().map_err(|(_, e)| e)? };
+        let loader_function =
+            |s: &str| glutin_context.get_proc_address(s) as *const std::os::raw::c_void;
         let context = Context::from_gl_context(std::sync::Arc::new(unsafe {
-            crate::context::Context::from_loader_function(|s| {
-                glutin_context.get_proc_address(s) as *const _
-            })
+            crate::context::Context::from_loader_function(loader_function)
         }))?;
+        unsafe { context.load_clip_control(loader_function) };
         Ok(Self {
             context,
-            _glutin_context: Rc::new(glutin_context),
+            _glutin_context: Rc::new(GlutinContext::Glutin029(glutin_context)),
         })
     }
+
+    ///
+    /// Creates a surfaceless context on the first EGL device that supports it, which does not require a display server.
+    ///
+    #[cfg(target_os = "linux")]
+    #[allow(unsafe_code)]
+    fn new_egl_device() -> Option<Self> {
+        use glutin::api::egl::{device::Device, display::Display};
+        use glutin::prelude::*;
+        for device in Device::query_devices().ok()? {
+            let display = match unsafe { Display::with_device(&device, None) } {
+                Ok(display) => display,
+                Err(_) => continue,
+            };
+            let config_template = glutin::config::ConfigTemplateBuilder::new()
+                .with_surface_type(glutin::config::ConfigSurfaceTypes::empty())
+                .build();
+            let config = match unsafe { display.find_configs(config_template) }
+                .ok()
+                .and_then(|mut configs| configs.next())
+            {
+                Some(config) => config,
+                None => continue,
+            };
+            let context_attributes = glutin::context::ContextAttributesBuilder::new().build(None);
+            let glutin_context =
+                match unsafe { display.create_context(&config, &context_attributes) }
+                    .and_then(|context| context.make_current_surfaceless())
+                {
+                    Ok(context) => context,
+                    Err(_) => continue,
+                };
+            let loader_function = |s: &str| {
+                let s = std::ffi::CString::new(s)
+                    .expect("failed to construct C string from string for gl proc address");
+                display.get_proc_address(&s)
+            };
+            let context = Context::from_gl_context(std::sync::Arc::new(unsafe {
+                crate::context::Context::from_loader_function(loader_function)
+            }))
+            .ok()?;
+            unsafe { context.load_clip_control(loader_function) };
+            return Some(Self {
+                context,
+                _glutin_context: Rc::new(GlutinContext::EglDevice(glutin_context)),
+            });
+        }
+        None
+    }
+
+    ///
+    /// Renders into a color texture and a depth texture with the given size and returns the color of the pixels,
+    /// for example to save the image to a file or compare it to a reference image in a test.
+    /// The `render` closure should clear the given render target and render the scene.
+    ///
+    pub fn render_to_cpu_texture(
+        &self,
+        width: u32,
+        height: u32,
+        render: impl FnOnce(&RenderTarget),
+    ) -> CpuTexture {
+        let mut texture = Texture2D::new_empty::<[u8; 4]>(
+            &self.context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth_texture = DepthTexture2D::new::<f32>(
+            &self.context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let target = RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        );
+        render(&target);
+        CpuTexture {
+            data: TextureData::RgbaU8(target.read_color()),
+            width,
+            height,
+            ..Default::default()
+        }
+    }
 }
 
 impl std::ops::Deref for HeadlessContext {
@@ -59,6 +172,15 @@ impl std::ops::Deref for HeadlessContext {
     }
 }
 
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        // The low-level context is destroyed together with the last clone
+        if Rc::strong_count(&self._glutin_context) == 1 {
+            self.context.clear_current();
+        }
+    }
+}
+
 /*#[cfg(target_os = "linux")]
 fn build_context_surfaceless<T1: ContextCurrentState>(
     cb: ContextBuilder<T1>,
@@ -0,0 +1,173 @@
+use crate::*;
+use std::path::PathBuf;
+
+///
+/// A description of a scene rendered by [render_single_frame].
+///
+#[derive(Clone)]
+pub struct SceneDescription {
+    /// Paths to model files, for example glTF or obj files, which are loaded and added to the scene.
+    /// Loading a file format requires that the corresponding feature of the `three-d-asset` crate is enabled.
+    pub model_paths: Vec<PathBuf>,
+    /// Models which are already loaded and are added to the scene.
+    pub models: Vec<CpuModel>,
+    /// The background color.
+    pub background: Color,
+    /// The intensity of the default ambient light.
+    pub ambient_intensity: f32,
+    /// The intensity of the default directional light, which shines from above and slightly behind the default camera.
+    pub directional_intensity: f32,
+}
+
+impl Default for SceneDescription {
+    fn default() -> Self {
+        Self {
+            model_paths: Vec::new(),
+            models: Vec::new(),
+            background: Color::new_opaque(204, 204, 204),
+            ambient_intensity: 0.4,
+            directional_intensity: 2.0,
+        }
+    }
+}
+
+impl SceneDescription {
+    ///
+    /// Creates a scene description containing the models in the given files with default lighting and background.
+    ///
+    pub fn from_paths(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        Self {
+            model_paths: paths.into_iter().map(|p| p.into()).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Loads the assets in the scene description, renders the scene once with default lighting and returns the image.
+/// If no camera is given, a perspective camera viewing the whole scene from the front, slightly from above and to the right, is used.
+/// The viewport of the camera is set to the given size.
+///
+/// Creates a new [HeadlessContext] for each call, which is not possible on all platforms,
+/// so when rendering more than one frame, for example in a batch rendering script, use [render_single_frame_with_context] instead.
+///
+pub fn render_single_frame(
+    scene: &SceneDescription,
+    camera: Option<Camera>,
+    width: u32,
+    height: u32,
+) -> Result<CpuTexture, HeadlessError> {
+    let context = HeadlessContext::new()?;
+    render_single_frame_with_context(&context, scene, camera, width, height)
+}
+
+///
+/// Same as [render_single_frame], except that the given context is used for rendering.
+///
+pub fn render_single_frame_with_context(
+    context: &Context,
+    scene: &SceneDescription,
+    camera: Option<Camera>,
+    width: u32,
+    height: u32,
+) -> Result<CpuTexture, HeadlessError> {
+    let mut cpu_models = scene.models.clone();
+    if !scene.model_paths.is_empty() {
+        let mut loaded = three_d_asset::io::load(&scene.model_paths)?;
+        for path in scene.model_paths.iter() {
+            cpu_models.push(loaded.deserialize(path)?);
+        }
+    }
+    let models = cpu_models
+        .iter()
+        .map(|cpu_model| Model::<PhysicalMaterial>::new(context, cpu_model))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut aabb = AxisAlignedBoundingBox::EMPTY;
+    for model in models.iter() {
+        for object in model.into_iter() {
+            aabb.expand_with_aabb(&object.aabb());
+        }
+    }
+    let viewport = Viewport::new_at_origin(width, height);
+    let camera = if let Some(mut camera) = camera {
+        camera.set_viewport(viewport);
+        camera
+    } else {
+        default_camera(viewport, &aabb)
+    };
+
+    let ambient = AmbientLight::new(context, scene.ambient_intensity, Color::WHITE);
+    let directional = DirectionalLight::new(
+        context,
+        scene.directional_intensity,
+        Color::WHITE,
+        &vec3(-0.5, -1.0, -0.8),
+    );
+
+    let mut texture = Texture2D::new_empty::<[u8; 4]>(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        context,
+        width,
+        height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let background = scene.background;
+    let pixels = RenderTarget::new(
+        texture.as_color_target(None),
+        depth_texture.as_depth_target(),
+    )
+    .clear(ClearState::color_and_depth(
+        background.r as f32 / 255.0,
+        background.g as f32 / 255.0,
+        background.b as f32 / 255.0,
+        background.a as f32 / 255.0,
+        1.0,
+    ))
+    .render(
+        &camera,
+        models.iter().flat_map(|model| model.into_iter()),
+        &[&ambient, &directional],
+    )
+    .read_color();
+    Ok(CpuTexture {
+        data: TextureData::RgbaU8(pixels),
+        width,
+        height,
+        ..Default::default()
+    })
+}
+
+fn default_camera(viewport: Viewport, aabb: &AxisAlignedBoundingBox) -> Camera {
+    let (center, radius) = if aabb.is_empty() {
+        (vec3(0.0, 0.0, 0.0), 1.0)
+    } else {
+        (
+            0.5 * (aabb.min() + aabb.max()),
+            0.5 * aabb.size().magnitude().max(0.0001),
+        )
+    };
+    let mut camera = Camera::new_perspective(
+        viewport,
+        center + vec3(1.0, 0.6, 2.0).normalize() * radius,
+        center,
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.01 * radius,
+        10.0 * radius,
+    );
+    if !aabb.is_empty() {
+        Viewpoint::focus_on(&camera, aabb).apply(&mut camera);
+    }
+    camera
+}
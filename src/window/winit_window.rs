@@ -17,7 +17,21 @@ pub use frame_input_generator::*;
 mod windowed_context;
 pub use windowed_context::*;
 
+mod clipboard;
+pub use clipboard::*;
+
+mod cursor;
+pub use cursor::*;
+
+mod monitor;
+pub use monitor::*;
+
 use thiserror::Error;
+
+// The name, contents and drop position in logical pixels of files dropped onto the canvas which have been read but not yet added to the events
+#[cfg(target_arch = "wasm32")]
+type DroppedFiles = std::rc::Rc<std::cell::RefCell<Vec<(String, Vec<u8>, (f32, f32))>>>;
+
 ///
 /// Error associated with a window.
 ///
@@ -74,9 +88,16 @@ pub struct Window {
     event_loop: EventLoop<()>,
     #[cfg(target_arch = "wasm32")]
     closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    drag_over_closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    drop_closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    dropped_files: DroppedFiles,
     gl: WindowedContext,
     #[allow(dead_code)]
     maximized: bool,
+    cursor_locked: bool,
 }
 
 impl Window {
@@ -157,6 +178,7 @@ impl Window {
         };
 
         let winit_window = window_builder.build(&event_loop)?;
+        monitor::set_fullscreen(&winit_window, &window_settings.fullscreen);
         Self::from_winit_window(
             winit_window,
             event_loop,
@@ -197,13 +219,78 @@ impl Window {
             closure
         };
 
+        // Files dropped onto the canvas are not reported by winit on web, so they are read here and added to the events of the next frame
+        #[cfg(target_arch = "wasm32")]
+        let (drag_over_closure, drop_closure, dropped_files) = {
+            use wasm_bindgen::JsCast;
+            use winit::platform::web::WindowExtWebSys;
+            let drag_over_closure =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    // Required for the browser to allow dropping onto the canvas
+                    event.prevent_default();
+                }) as Box<dyn FnMut(_)>);
+            let dropped_files = DroppedFiles::default();
+            let drop_closure = {
+                let dropped_files = dropped_files.clone();
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    event.prevent_default();
+                    let event = event.unchecked_into::<web_sys::DragEvent>();
+                    let position = (event.offset_x() as f32, event.offset_y() as f32);
+                    let files = match event.data_transfer().and_then(|d| d.files()) {
+                        Some(files) => files,
+                        None => return,
+                    };
+                    for file in (0..files.length()).filter_map(|i| files.get(i)) {
+                        let reader = match web_sys::FileReader::new() {
+                            Ok(reader) => reader,
+                            Err(_) => continue,
+                        };
+                        let onload = {
+                            let reader = reader.clone();
+                            let dropped_files = dropped_files.clone();
+                            let name = file.name();
+                            wasm_bindgen::closure::Closure::once_into_js(move || {
+                                if let Ok(result) = reader.result() {
+                                    dropped_files.borrow_mut().push((
+                                        name,
+                                        js_sys::Uint8Array::new(&result).to_vec(),
+                                        position,
+                                    ));
+                                }
+                            })
+                        };
+                        reader.set_onload(Some(onload.unchecked_ref()));
+                        reader.read_as_array_buffer(&file).ok();
+                    }
+                }) as Box<dyn FnMut(_)>)
+            };
+            let canvas = winit_window.canvas();
+            canvas
+                .add_event_listener_with_callback(
+                    "dragover",
+                    drag_over_closure.as_ref().unchecked_ref(),
+                )
+                .expect("failed to listen to canvas drag over");
+            canvas
+                .add_event_listener_with_callback("drop", drop_closure.as_ref().unchecked_ref())
+                .expect("failed to listen to canvas drop");
+            (drag_over_closure, drop_closure, dropped_files)
+        };
+
         Ok(Self {
             window: winit_window,
             event_loop,
             gl: gl?,
             #[cfg(target_arch = "wasm32")]
             closure,
+            #[cfg(target_arch = "wasm32")]
+            drag_over_closure,
+            #[cfg(target_arch = "wasm32")]
+            drop_closure,
+            #[cfg(target_arch = "wasm32")]
+            dropped_files,
             maximized,
+            cursor_locked: false,
         })
     }
 
@@ -212,6 +299,8 @@ impl Window {
     ///
     pub fn render_loop<F: 'static + FnMut(FrameInput) -> FrameOutput>(self, mut callback: F) {
         let mut frame_input_generator = FrameInputGenerator::from_winit_window(&self.window);
+        frame_input_generator.set_cursor_locked(self.cursor_locked);
+        let mut clipboard = Clipboard::new();
         self.event_loop
             .run(move |event, _, control_flow| match event {
                 Event::LoopDestroyed => {
@@ -219,13 +308,25 @@ impl Window {
                     {
                         use wasm_bindgen::JsCast;
                         use winit::platform::web::WindowExtWebSys;
-                        self.window
-                            .canvas()
+                        let canvas = self.window.canvas();
+                        canvas
                             .remove_event_listener_with_callback(
                                 "contextmenu",
                                 self.closure.as_ref().unchecked_ref(),
                             )
                             .unwrap();
+                        canvas
+                            .remove_event_listener_with_callback(
+                                "dragover",
+                                self.drag_over_closure.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
+                        canvas
+                            .remove_event_listener_with_callback(
+                                "drop",
+                                self.drop_closure.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
                     }
                 }
                 Event::MainEventsCleared => {
@@ -249,8 +350,26 @@ impl Window {
                         });
                     }
 
+                    #[cfg(target_arch = "wasm32")]
+                    for (name, bytes, position) in self.dropped_files.borrow_mut().drain(..) {
+                        frame_input_generator.handle_dropped_file(name, bytes, position);
+                    }
+
                     let frame_input = frame_input_generator.generate(&self.gl);
                     let frame_output = callback(frame_input);
+                    if let Some(text) = &frame_output.copied_text {
+                        clipboard.set_text(text);
+                    }
+                    if let Some(cursor) = &frame_output.cursor {
+                        cursor::set_cursor(&self.window, cursor);
+                    }
+                    if let Some(fullscreen) = &frame_output.fullscreen {
+                        monitor::set_fullscreen(&self.window, fullscreen);
+                    }
+                    if let Some(locked) = frame_output.cursor_locked {
+                        let locked = locked && cursor::set_cursor_locked(&self.window, locked);
+                        frame_input_generator.set_cursor_locked(locked);
+                    }
                     if frame_output.exit {
                         *control_flow = ControlFlow::Exit;
                     } else {
@@ -266,6 +385,9 @@ impl Window {
                         }
                     }
                 }
+                Event::DeviceEvent { ref event, .. } => {
+                    frame_input_generator.handle_winit_device_event(event);
+                }
                 Event::WindowEvent { ref event, .. } => {
                     frame_input_generator.handle_winit_window_event(event);
                     match event {
@@ -308,6 +430,55 @@ impl Window {
         self.window.scale_factor() as f32
     }
 
+    ///
+    /// Returns information about the monitors connected to the system.
+    /// The index of a monitor in the list is used to specify the monitor in [Fullscreen].
+    ///
+    /// On web, the monitors are not available and the list is empty.
+    ///
+    pub fn monitors(&self) -> Vec<Monitor> {
+        monitor::monitors(&self.window)
+    }
+
+    ///
+    /// Switches to or from fullscreen, without recreating the graphics context.
+    /// The window is resized accordingly, which is reflected in the [FrameInput] of the following frames.
+    /// Use [FrameOutput::fullscreen] to change it while the render loop is running.
+    ///
+    /// On web, the browser only allows fullscreen shortly after user input, for example in the frame after a mouse press, and it leaves fullscreen when the user presses escape.
+    ///
+    pub fn set_fullscreen(&self, fullscreen: &Fullscreen) {
+        monitor::set_fullscreen(&self.window, fullscreen);
+    }
+
+    ///
+    /// Returns whether or not the window is fullscreen.
+    ///
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.fullscreen().is_some()
+    }
+
+    ///
+    /// Sets the appearance of the cursor when it is over the window.
+    /// Use [FrameOutput::cursor] to change it while the render loop is running.
+    ///
+    pub fn set_cursor(&self, cursor: &Cursor) {
+        cursor::set_cursor(&self.window, cursor);
+    }
+
+    ///
+    /// Locks the cursor to the window and hides it, or releases it again, for example for first person controls where the mouse movement should rotate the camera without the cursor leaving the window.
+    /// While the cursor is locked, [Event::MouseMotion](crate::Event::MouseMotion) events contain the mouse movement but the position does not change.
+    /// Use [FrameOutput::cursor_locked] to lock or release it while the render loop is running.
+    ///
+    /// On native, the cursor is confined to the window instead if the platform does not support locking it.
+    /// On web, the [Pointer Lock API](https://developer.mozilla.org/en-US/docs/Web/API/Pointer_Lock_API) is used, which only allows locking shortly after user input, for example in the frame after a mouse press,
+    /// and the browser releases the lock when the user presses escape.
+    ///
+    pub fn set_cursor_locked(&mut self, locked: bool) {
+        self.cursor_locked = locked && cursor::set_cursor_locked(&self.window, locked);
+    }
+
     ///
     /// Returns the graphics context for this window.
     ///
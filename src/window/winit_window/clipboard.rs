@@ -0,0 +1,104 @@
+///
+/// Access to the system clipboard.
+/// On native, the text is read and written using [arboard](https://crates.io/crates/arboard).
+/// On web, the text is written using the asynchronous clipboard API and it cannot be read directly,
+/// instead pasted text is received as [Event::Paste](crate::Event::Paste) events.
+///
+/// The default [Window](crate::window::Window) writes the [FrameOutput::copied_text](crate::window::FrameOutput::copied_text) to the clipboard
+/// and the [FrameInputGenerator](crate::window::FrameInputGenerator) sends [Event::Copy](crate::Event::Copy), [Event::Cut](crate::Event::Cut) and [Event::Paste](crate::Event::Paste) events,
+/// so it is only necessary to use the clipboard directly for example when reading it outside of a paste event.
+///
+pub struct Clipboard {
+    #[cfg(not(target_arch = "wasm32"))]
+    clipboard: Option<arboard::Clipboard>,
+    #[cfg(target_arch = "wasm32")]
+    requested_text: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+}
+
+impl Clipboard {
+    ///
+    /// Creates a new clipboard. If the system clipboard is not available, reading returns `None` and writing does nothing.
+    ///
+    pub fn new() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            clipboard: arboard::Clipboard::new().ok(),
+            #[cfg(target_arch = "wasm32")]
+            requested_text: Default::default(),
+        }
+    }
+
+    ///
+    /// Returns the text in the clipboard, if any.
+    /// Always returns `None` on web, since the clipboard can only be read asynchronously, use the [Event::Paste](crate::Event::Paste) events instead.
+    ///
+    pub fn text(&mut self) -> Option<String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.clipboard.as_mut()?.get_text().ok()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            None
+        }
+    }
+
+    ///
+    /// Writes the given text to the clipboard.
+    /// On web, the text is written asynchronously and the browser only allows it in response to user input, for example in the frame after a key press.
+    ///
+    pub fn set_text(&mut self, text: &str) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(clipboard) = self.clipboard.as_mut() {
+            clipboard.set_text(text).ok();
+        }
+        #[cfg(target_arch = "wasm32")]
+        if let Some((clipboard, function)) = web_clipboard_function("writeText") {
+            function.call1(&clipboard, &text.into()).ok();
+        }
+    }
+
+    // Starts reading the clipboard asynchronously, the text is returned from `take_requested_text` when it is available
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn request_text(&self) {
+        use wasm_bindgen::JsCast;
+        if let Some(promise) = web_clipboard_function("readText")
+            .and_then(|(clipboard, function)| function.call0(&clipboard).ok())
+            .and_then(|promise| promise.dyn_into::<js_sys::Promise>().ok())
+        {
+            let requested_text = self.requested_text.clone();
+            let closure =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |text: wasm_bindgen::JsValue| {
+                    if let Some(text) = text.as_string() {
+                        requested_text.borrow_mut().push(text);
+                    }
+                }) as Box<dyn FnMut(_)>);
+            let _ = promise.then(&closure);
+            closure.forget();
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn take_requested_text(&self) -> Vec<String> {
+        self.requested_text.borrow_mut().drain(..).collect()
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The clipboard API is unstable in web-sys, so the functions on `navigator.clipboard` are looked up dynamically
+#[cfg(target_arch = "wasm32")]
+fn web_clipboard_function(name: &str) -> Option<(wasm_bindgen::JsValue, js_sys::Function)> {
+    use wasm_bindgen::JsCast;
+    let navigator = web_sys::window()?.navigator();
+    let clipboard = js_sys::Reflect::get(&navigator, &"clipboard".into()).ok()?;
+    let function = js_sys::Reflect::get(&clipboard, &name.into())
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    Some((clipboard, function))
+}
@@ -0,0 +1,212 @@
+///
+/// One of the standard cursor icons provided by the operating system or browser, see [Cursor].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum CursorIcon {
+    /// The default cursor, usually an arrow.
+    #[default]
+    Default,
+    /// A pointing hand, usually used for links and buttons.
+    Pointer,
+    /// A crosshair, usually used for precise selection.
+    Crosshair,
+    /// A text cursor.
+    Text,
+    /// Indicates that something can be moved.
+    Move,
+    /// Indicates that something can be grabbed.
+    Grab,
+    /// Indicates that something is being grabbed.
+    Grabbing,
+    /// Indicates that the application is busy and cannot be interacted with.
+    Wait,
+    /// Indicates that the application is busy but can still be interacted with.
+    Progress,
+    /// Indicates that help is available.
+    Help,
+    /// Indicates that the requested action is not allowed.
+    NotAllowed,
+    /// Indicates that something can be zoomed in.
+    ZoomIn,
+    /// Indicates that something can be zoomed out.
+    ZoomOut,
+    /// Indicates that something can be resized horizontally.
+    ResizeHorizontal,
+    /// Indicates that something can be resized vertically.
+    ResizeVertical,
+    /// Indicates that something can be resized diagonally from the bottom left to the top right corner.
+    ResizeNeSw,
+    /// Indicates that something can be resized diagonally from the top left to the bottom right corner.
+    ResizeNwSe,
+}
+
+impl CursorIcon {
+    fn to_winit(self) -> winit::window::CursorIcon {
+        use winit::window::CursorIcon as Icon;
+        match self {
+            Self::Default => Icon::Default,
+            Self::Pointer => Icon::Hand,
+            Self::Crosshair => Icon::Crosshair,
+            Self::Text => Icon::Text,
+            Self::Move => Icon::Move,
+            Self::Grab => Icon::Grab,
+            Self::Grabbing => Icon::Grabbing,
+            Self::Wait => Icon::Wait,
+            Self::Progress => Icon::Progress,
+            Self::Help => Icon::Help,
+            Self::NotAllowed => Icon::NotAllowed,
+            Self::ZoomIn => Icon::ZoomIn,
+            Self::ZoomOut => Icon::ZoomOut,
+            Self::ResizeHorizontal => Icon::EwResize,
+            Self::ResizeVertical => Icon::NsResize,
+            Self::ResizeNeSw => Icon::NeswResize,
+            Self::ResizeNwSe => Icon::NwseResize,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn css_name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Pointer => "pointer",
+            Self::Crosshair => "crosshair",
+            Self::Text => "text",
+            Self::Move => "move",
+            Self::Grab => "grab",
+            Self::Grabbing => "grabbing",
+            Self::Wait => "wait",
+            Self::Progress => "progress",
+            Self::Help => "help",
+            Self::NotAllowed => "not-allowed",
+            Self::ZoomIn => "zoom-in",
+            Self::ZoomOut => "zoom-out",
+            Self::ResizeHorizontal => "ew-resize",
+            Self::ResizeVertical => "ns-resize",
+            Self::ResizeNeSw => "nesw-resize",
+            Self::ResizeNwSe => "nwse-resize",
+        }
+    }
+}
+
+///
+/// A cursor defined by an image, see [Cursor::Image].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CursorImage {
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The pixels of the image as RGBA bytes, row by row starting from the top left corner, so the length must be `4 * width * height`.
+    pub rgba: Vec<u8>,
+    /// The pixel in the image, measured from the top left corner, which is at the pointer position.
+    pub hotspot: (u32, u32),
+    /// The standard icon which is used if image cursors are not supported.
+    pub fallback: CursorIcon,
+}
+
+///
+/// The appearance of the cursor when it is over the window, see [Window::set_cursor](crate::window::Window::set_cursor) and [FrameOutput::cursor](crate::window::FrameOutput::cursor).
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cursor {
+    /// One of the standard cursor icons.
+    Icon(CursorIcon),
+    /// A cursor defined by an image.
+    /// Image cursors are only supported on web where the image is used as a CSS cursor, on native the fallback icon is used instead.
+    Image(CursorImage),
+    /// The cursor is hidden.
+    Hidden,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self::Icon(CursorIcon::Default)
+    }
+}
+
+impl From<CursorIcon> for Cursor {
+    fn from(icon: CursorIcon) -> Self {
+        Self::Icon(icon)
+    }
+}
+
+pub(super) fn set_cursor(window: &winit::window::Window, cursor: &Cursor) {
+    match cursor {
+        Cursor::Icon(icon) => {
+            window.set_cursor_visible(true);
+            window.set_cursor_icon(icon.to_winit());
+        }
+        Cursor::Image(image) => {
+            window.set_cursor_visible(true);
+            window.set_cursor_icon(image.fallback.to_winit());
+            #[cfg(target_arch = "wasm32")]
+            if let Some(css) = image_css(image) {
+                use winit::platform::web::WindowExtWebSys;
+                window.canvas().style().set_property("cursor", &css).ok();
+            }
+        }
+        Cursor::Hidden => window.set_cursor_visible(false),
+    }
+}
+
+// Locks the cursor to the window and hides it, or releases it, and returns whether or not it succeeded
+pub(super) fn set_cursor_locked(window: &winit::window::Window, locked: bool) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use winit::window::CursorGrabMode;
+        let result = if locked {
+            // Locking is not supported on all platforms, in which case the cursor is confined to the window instead
+            window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined))
+        } else {
+            window.set_cursor_grab(CursorGrabMode::None)
+        };
+        window.set_cursor_visible(!locked);
+        result.is_ok()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        use winit::platform::web::WindowExtWebSys;
+        if locked {
+            window.canvas().request_pointer_lock();
+        } else if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            document.exit_pointer_lock();
+        }
+        true
+    }
+}
+
+// Returns the CSS cursor value which uses the image with the fallback icon
+#[cfg(target_arch = "wasm32")]
+fn image_css(image: &CursorImage) -> Option<String> {
+    use wasm_bindgen::JsCast;
+    let canvas = web_sys::window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width(image.width);
+    canvas.set_height(image.height);
+    let context = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .ok()?;
+    let data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        wasm_bindgen::Clamped(&image.rgba),
+        image.width,
+        image.height,
+    )
+    .ok()?;
+    context.put_image_data(&data, 0.0, 0.0).ok()?;
+    Some(format!(
+        "url({}) {} {}, {}",
+        canvas.to_data_url().ok()?,
+        image.hotspot.0,
+        image.hotspot.1,
+        image.fallback.css_name()
+    ))
+}
@@ -1,4 +1,4 @@
-use super::FrameInput;
+use super::{Clipboard, FrameInput};
 use crate::control::*;
 use crate::core::*;
 #[cfg(target_arch = "wasm32")]
@@ -6,6 +6,7 @@ use instant::Instant;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 use winit::dpi::PhysicalSize;
+use winit::event::DeviceEvent;
 use winit::event::TouchPhase;
 use winit::event::WindowEvent;
 
@@ -28,6 +29,8 @@ pub struct FrameInputGenerator {
     secondary_finger_id: Option<u64>,
     modifiers: Modifiers,
     mouse_pressed: Option<MouseButton>,
+    clipboard: Clipboard,
+    cursor_locked: bool,
 }
 
 impl FrameInputGenerator {
@@ -52,6 +55,8 @@ impl FrameInputGenerator {
             secondary_finger_id: None,
             modifiers: Modifiers::default(),
             mouse_pressed: None,
+            clipboard: Clipboard::new(),
+            cursor_locked: false,
         }
     }
 
@@ -73,6 +78,11 @@ impl FrameInputGenerator {
         self.accumulated_time += elapsed_time;
         self.last_time = now;
 
+        #[cfg(target_arch = "wasm32")]
+        for text in self.clipboard.take_requested_text() {
+            self.events.push(crate::Event::Paste(text));
+        }
+
         let frame_input = FrameInput {
             events: self.events.drain(..).collect(),
             elapsed_time,
@@ -114,6 +124,74 @@ impl FrameInputGenerator {
         frame_input
     }
 
+    ///
+    /// Tells the generator whether or not the cursor is locked to the window, see [Window::set_cursor_locked](crate::window::Window::set_cursor_locked).
+    /// This is done automatically by the default [Window](crate::window::Window), but must be called when locking the cursor of a custom [winit](https://crates.io/crates/winit) window on native.
+    /// On web, the pointer lock state is read from the document so this is not necessary.
+    ///
+    pub fn set_cursor_locked(&mut self, locked: bool) {
+        self.cursor_locked = locked;
+    }
+
+    ///
+    /// Handle the [DeviceEvent] generated by a [winit](https://crates.io/crates/winit) event loop.
+    /// This generates [Event::MouseMotion] events while the cursor is locked, since the cursor position does not change in that case, otherwise device events are ignored.
+    ///
+    pub fn handle_winit_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event {
+            if self.is_cursor_locked() {
+                if let Some(position) = self.cursor_pos {
+                    self.events.push(crate::Event::MouseMotion {
+                        button: self.mouse_pressed,
+                        delta: (delta.0 as f32, delta.1 as f32),
+                        position,
+                        modifiers: self.modifiers,
+                        handled: false,
+                    });
+                }
+            }
+        }
+    }
+
+    fn is_cursor_locked(&self) -> bool {
+        #[cfg(target_arch = "wasm32")]
+        {
+            web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.pointer_lock_element())
+                .is_some()
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.cursor_locked
+        }
+    }
+
+    ///
+    /// Adds an [Event::FileDropped] event with the contents of a file dropped onto the canvas at the given position in logical pixels.
+    /// On web, the dropped files are not reported by [winit](https://crates.io/crates/winit) so they are read by the default [Window](crate::window::Window) and added using this method.
+    ///
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn handle_dropped_file(
+        &mut self,
+        name: String,
+        bytes: Vec<u8>,
+        position: (f32, f32),
+    ) {
+        self.events.push(crate::Event::FileDropped {
+            name,
+            path: None,
+            bytes: Some(bytes),
+            position: Some(LogicalPoint {
+                x: position.0,
+                y: position.1,
+                device_pixel_ratio: self.device_pixel_ratio as f32,
+                height: self.viewport.height as f32,
+            }),
+            handled: false,
+        });
+    }
+
     ///
     /// Handle the [WindowEvent] generated by a [winit](https://crates.io/crates/winit) event loop.
     ///
@@ -135,12 +213,18 @@ impl FrameInputGenerator {
                 let logical_size = new_inner_size.to_logical(self.device_pixel_ratio);
                 self.window_width = logical_size.width;
                 self.window_height = logical_size.height;
+                self.events.push(crate::Event::DevicePixelRatioChange {
+                    device_pixel_ratio: *scale_factor as f32,
+                });
             }
             WindowEvent::KeyboardInput { input, .. } => {
                 if let Some(keycode) = input.virtual_keycode {
                     use winit::event::VirtualKeyCode;
                     let state = input.state == winit::event::ElementState::Pressed;
                     if let Some(kind) = translate_virtual_key_code(keycode) {
+                        if state && self.modifiers.command {
+                            self.handle_clipboard_shortcut(kind);
+                        }
                         self.events.push(if state {
                             crate::Event::KeyPress {
                                 kind,
@@ -239,6 +323,9 @@ impl FrameInputGenerator {
                     }
                 }
             }
+            // The motion is generated from the device events while the cursor is locked on native
+            WindowEvent::CursorMoved { .. }
+                if self.cursor_locked && !cfg!(target_arch = "wasm32") => {}
             WindowEvent::CursorMoved { position, .. } => {
                 let p = position.to_logical(self.device_pixel_ratio);
                 let delta = if let Some(last_pos) = self.cursor_pos {
@@ -266,6 +353,18 @@ impl FrameInputGenerator {
                     self.events.push(crate::Event::Text(ch.to_string()));
                 }
             }
+            WindowEvent::DroppedFile(path) => {
+                self.events.push(crate::Event::FileDropped {
+                    name: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    path: Some(path.clone()),
+                    bytes: None,
+                    position: self.cursor_pos,
+                    handled: false,
+                });
+            }
             WindowEvent::CursorEntered { .. } => {
                 self.events.push(crate::Event::MouseEnter);
             }
@@ -281,6 +380,18 @@ impl FrameInputGenerator {
                     device_pixel_ratio: self.device_pixel_ratio as f32,
                     height: self.viewport.height as f32,
                 };
+                self.events.push(crate::Event::Touch {
+                    id: touch.id,
+                    phase: match touch.phase {
+                        TouchPhase::Started => crate::TouchPhase::Started,
+                        TouchPhase::Moved => crate::TouchPhase::Moved,
+                        TouchPhase::Ended => crate::TouchPhase::Ended,
+                        TouchPhase::Cancelled => crate::TouchPhase::Cancelled,
+                    },
+                    position,
+                    modifiers: self.modifiers,
+                    handled: false,
+                });
                 match touch.phase {
                     TouchPhase::Started => {
                         if self.finger_id.is_none() {
@@ -364,6 +475,24 @@ impl FrameInputGenerator {
             _ => (),
         }
     }
+
+    // Sends the clipboard events for the copy, cut and paste shortcuts
+    fn handle_clipboard_shortcut(&mut self, kind: Key) {
+        match kind {
+            Key::C => self.events.push(crate::Event::Copy),
+            Key::X => self.events.push(crate::Event::Cut),
+            Key::V => {
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(text) = self.clipboard.text() {
+                    self.events.push(crate::Event::Paste(text));
+                }
+                // The clipboard can only be read asynchronously on web, so the paste event is sent in a later frame
+                #[cfg(target_arch = "wasm32")]
+                self.clipboard.request_text();
+            }
+            _ => {}
+        }
+    }
 }
 
 fn is_printable_char(chr: char) -> bool {
@@ -1,3 +1,4 @@
+use super::{Cursor, Fullscreen};
 use crate::control::Event;
 use crate::core::{Context, RenderTarget, Viewport};
 
@@ -79,6 +80,26 @@ pub struct FrameOutput {
     /// Whether to stop the render loop until next event.
     ///
     pub wait_next_event: bool,
+
+    ///
+    /// Text which is written to the clipboard at the end of the frame, for example in response to an [Event::Copy] or [Event::Cut] event.
+    ///
+    pub copied_text: Option<String>,
+
+    ///
+    /// Changes the appearance of the cursor if this is not `None`, see [Window::set_cursor](crate::window::Window::set_cursor).
+    ///
+    pub cursor: Option<Cursor>,
+
+    ///
+    /// Locks or releases the cursor if this is not `None`, see [Window::set_cursor_locked](crate::window::Window::set_cursor_locked).
+    ///
+    pub cursor_locked: Option<bool>,
+
+    ///
+    /// Changes the fullscreen mode if this is not `None`, see [Window::set_fullscreen](crate::window::Window::set_fullscreen).
+    ///
+    pub fullscreen: Option<Fullscreen>,
 }
 
 impl Default for FrameOutput {
@@ -87,6 +108,10 @@ impl Default for FrameOutput {
             exit: false,
             swap_buffers: true,
             wait_next_event: false,
+            copied_text: None,
+            cursor: None,
+            cursor_locked: None,
+            fullscreen: None,
         }
     }
 }
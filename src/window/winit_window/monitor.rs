@@ -0,0 +1,96 @@
+use super::Fullscreen;
+
+///
+/// A video mode supported by a [Monitor], which can be used for exclusive fullscreen, see [Fullscreen::Exclusive].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+    /// The resolution `(width, height)` in physical pixels.
+    pub size: (u32, u32),
+    /// The number of bits per pixel.
+    pub bit_depth: u16,
+    /// The refresh rate in millihertz, for example 60000 for 60 Hz.
+    pub refresh_rate_millihertz: u32,
+}
+
+impl From<&winit::monitor::VideoMode> for VideoMode {
+    fn from(video_mode: &winit::monitor::VideoMode) -> Self {
+        Self {
+            size: video_mode.size().into(),
+            bit_depth: video_mode.bit_depth(),
+            refresh_rate_millihertz: video_mode.refresh_rate_millihertz(),
+        }
+    }
+}
+
+///
+/// Information about a monitor connected to the system, see [Window::monitors](crate::window::Window::monitors).
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Monitor {
+    /// The name of the monitor, if available.
+    pub name: Option<String>,
+    /// The position `(x, y)` of the top left corner of the monitor on the desktop in physical pixels.
+    pub position: (i32, i32),
+    /// The current resolution `(width, height)` of the monitor in physical pixels.
+    pub size: (u32, u32),
+    /// The number of physical pixels for each logical pixel on the monitor.
+    pub device_pixel_ratio: f32,
+    /// The current refresh rate in millihertz, if available.
+    pub refresh_rate_millihertz: Option<u32>,
+    /// Whether or not this is the primary monitor.
+    pub primary: bool,
+    /// The video modes supported by the monitor.
+    pub video_modes: Vec<VideoMode>,
+}
+
+pub(super) fn monitors(window: &winit::window::Window) -> Vec<Monitor> {
+    let primary = window.primary_monitor();
+    window
+        .available_monitors()
+        .map(|monitor| Monitor {
+            name: monitor.name(),
+            position: monitor.position().into(),
+            size: monitor.size().into(),
+            device_pixel_ratio: monitor.scale_factor() as f32,
+            refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+            primary: primary.as_ref() == Some(&monitor),
+            video_modes: monitor
+                .video_modes()
+                .map(|video_mode| VideoMode::from(&video_mode))
+                .collect(),
+        })
+        .collect()
+}
+
+pub(super) fn set_fullscreen(window: &winit::window::Window, fullscreen: &Fullscreen) {
+    // Falls back to the monitor which contains the window if no monitor or an invalid monitor is specified
+    let monitor = |index: Option<usize>| {
+        index
+            .and_then(|index| window.available_monitors().nth(index))
+            .or_else(|| window.current_monitor())
+    };
+    window.set_fullscreen(match fullscreen {
+        Fullscreen::Off => None,
+        Fullscreen::Borderless { monitor: index } => {
+            Some(winit::window::Fullscreen::Borderless(monitor(*index)))
+        }
+        Fullscreen::Exclusive {
+            monitor: index,
+            video_mode,
+        } => {
+            let monitor = monitor(*index);
+            let winit_video_mode = monitor.as_ref().and_then(|monitor| {
+                monitor
+                    .video_modes()
+                    .find(|mode| VideoMode::from(mode) == *video_mode)
+            });
+            Some(match winit_video_mode {
+                Some(mode) if !cfg!(target_arch = "wasm32") => {
+                    winit::window::Fullscreen::Exclusive(mode)
+                }
+                _ => winit::window::Fullscreen::Borderless(monitor),
+            })
+        }
+    });
+}
@@ -54,6 +54,33 @@ impl Default for SurfaceSettings {
     }
 }
 
+///
+/// Whether or not the default [Window](super::Window) is fullscreen and how, see [WindowSettings::fullscreen] and [Window::set_fullscreen](super::Window::set_fullscreen).
+/// The monitors are specified by their index in the list returned from [Window::monitors](super::Window::monitors),
+/// where `None` or an invalid index means the monitor which currently contains the window.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Fullscreen {
+    /// The window is not fullscreen.
+    #[default]
+    Off,
+    /// The window covers the entire monitor without changing the video mode, which is fast to switch to and from.
+    Borderless {
+        /// The index of the monitor.
+        monitor: Option<usize>,
+    },
+    /// The window has exclusive access to the monitor which is changed to the given video mode.
+    /// If the monitor does not support the video mode, borderless fullscreen is used instead.
+    ///
+    /// On web, this is the same as [Fullscreen::Borderless].
+    Exclusive {
+        /// The index of the monitor.
+        monitor: Option<usize>,
+        /// One of the video modes of the monitor, see [Monitor::video_modes](super::Monitor::video_modes).
+        video_mode: super::VideoMode,
+    },
+}
+
 ///
 /// Settings for the default [Window](super::Window).
 ///
@@ -78,6 +105,11 @@ pub struct WindowSettings {
     ///
     /// On web this has no effect.
     pub borderless: bool,
+    /// Whether or not the window is fullscreen when created.
+    /// The default is [Fullscreen::Off].
+    ///
+    /// On web, browsers only allow fullscreen in response to user input, so use [FrameOutput::fullscreen](super::FrameOutput::fullscreen) after for example a click instead.
+    pub fullscreen: Fullscreen,
     /// An optional [canvas element][web_sys::HtmlCanvasElement] for using as winit window.
     /// If this is `None`, the DOM (`index.html`) must contain a canvas element
     #[cfg(target_arch = "wasm32")]
@@ -93,6 +125,7 @@ impl Default for WindowSettings {
             min_size: (2, 2),
             max_size: None,
             borderless: false,
+            fullscreen: Fullscreen::Off,
             #[cfg(target_arch = "wasm32")]
             canvas: None,
             surface_settings: SurfaceSettings::default(),
@@ -78,6 +78,7 @@ mod inner {
 
         /// Make this context current. Needed when using multiple windows (contexts) on native.
         pub fn make_current(&self) -> Result<(), WindowError> {
+            self.context.set_current();
             Ok(())
         }
 
@@ -192,15 +193,18 @@ mod inner {
             let gl_context = gl_context.make_current(&gl_surface)?;
             gl_surface.set_swap_interval(&gl_context, swap_interval)?;
 
-            Ok(Self {
-                context: Context::from_gl_context(Arc::new(unsafe {
-                    crate::context::Context::from_loader_function(|s| {
-                        let s = std::ffi::CString::new(s)
-                            .expect("failed to construct C string from string for gl proc address");
+            let loader_function = |s: &str| {
+                let s = std::ffi::CString::new(s)
+                    .expect("failed to construct C string from string for gl proc address");
 
-                        gl_display.get_proc_address(&s)
-                    })
-                }))?,
+                gl_display.get_proc_address(&s)
+            };
+            let context = Context::from_gl_context(Arc::new(unsafe {
+                crate::context::Context::from_loader_function(loader_function)
+            }))?;
+            unsafe { context.load_clip_control(loader_function) };
+            Ok(Self {
+                context,
                 glutin_context: gl_context,
                 surface: gl_surface,
             })
@@ -215,7 +219,9 @@ mod inner {
 
         /// Make this context current. Needed when using multiple windows (contexts) on native.
         pub fn make_current(&self) -> Result<(), WindowError> {
-            Ok(self.glutin_context.make_current(&self.surface)?)
+            self.glutin_context.make_current(&self.surface)?;
+            self.context.set_current();
+            Ok(())
         }
 
         /// Swap buffers - should always be called after rendering.
@@ -234,3 +240,9 @@ impl std::ops::Deref for WindowedContext {
         &self.context
     }
 }
+
+impl Drop for WindowedContext {
+    fn drop(&mut self) {
+        self.context.clear_current();
+    }
+}